@@ -1,8 +1,20 @@
 use anyhow::Result;
-use code_guardian_core::{CustomDetectorManager, DistributedCoordinator, WorkerConfig};
-use std::path::PathBuf;
-
-use crate::cli_definitions::{CustomDetectorAction, DistributedAction, IncrementalAction};
+use code_guardian_core::config::load_config;
+use code_guardian_core::{
+    negotiate_workers, validate_webhook_url, CiRun, CustomDetectorManager, DistributedCoordinator,
+    Icon, ScanCompletedPayload, ScanSummary, ThresholdStatus, WorkerConfig, WorkerDiscovery,
+    WorkerTls,
+};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::cli_definitions::{
+    CliError, CustomDetectorAction, DistributedAction, IncrementalAction,
+};
+use crate::scan_handlers::notify_webhooks;
+
+/// How long each worker gets to answer the capability handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub fn handle_custom_detectors(action: CustomDetectorAction) -> Result<()> {
     match action {
@@ -15,9 +27,14 @@ pub fn handle_custom_detectors(action: CustomDetectorAction) -> Result<()> {
                 return Ok(());
             }
 
-            println!("📋 Custom Detectors:");
+            println!("{} Custom Detectors:", Icon::List);
             for detector in detectors {
-                println!("  🔍 {} ({})", detector.name, detector.description);
+                println!(
+                    "  {} {} ({})",
+                    Icon::Search,
+                    detector.name,
+                    detector.description
+                );
                 println!("     Pattern: {}", detector.pattern);
                 println!("     Severity: {:?}", detector.severity);
                 println!("     Enabled: {}", detector.enabled);
@@ -33,7 +50,8 @@ pub fn handle_custom_detectors(action: CustomDetectorAction) -> Result<()> {
             manager.create_examples()?;
             manager.save_to_file(&output)?;
             println!(
-                "✅ Created example custom detectors in {}",
+                "{} Created example custom detectors in {}",
+                Icon::Success,
                 output.display()
             );
         }
@@ -44,7 +62,8 @@ pub fn handle_custom_detectors(action: CustomDetectorAction) -> Result<()> {
 
             let detectors = manager.list_detectors();
             println!(
-                "✅ Loaded {} custom detectors from {}",
+                "{} Loaded {} custom detectors from {}",
+                Icon::Success,
                 detectors.len(),
                 file.display()
             );
@@ -72,7 +91,11 @@ pub fn handle_custom_detectors(action: CustomDetectorAction) -> Result<()> {
             let content = std::fs::read_to_string(&test_file)?;
             let detector_instances = manager.get_detectors();
 
-            println!("🧪 Testing custom detectors on {}", test_file.display());
+            println!(
+                "{} Testing custom detectors on {}",
+                Icon::Test,
+                test_file.display()
+            );
 
             let mut total_matches = 0;
             for detector in detector_instances {
@@ -87,9 +110,9 @@ pub fn handle_custom_detectors(action: CustomDetectorAction) -> Result<()> {
             }
 
             if total_matches == 0 {
-                println!("  ✅ No matches found");
+                println!("  {} No matches found", Icon::Success);
             } else {
-                println!("  📊 Total matches: {}", total_matches);
+                println!("  {} Total matches: {}", Icon::Stats, total_matches);
             }
         }
     }
@@ -103,13 +126,13 @@ pub fn handle_incremental(action: IncrementalAction) -> Result<()> {
     match action {
         IncrementalAction::Status => {
             if !state_file.exists() {
-                println!("❌ No incremental scan state found.");
+                println!("{} No incremental scan state found.", Icon::Failure);
                 println!("   Run a scan with --incremental to create state.");
                 return Ok(());
             }
 
             // Load state and show status
-            println!("📊 Incremental Scan Status:");
+            println!("{} Incremental Scan Status:", Icon::Stats);
             println!("   State file: {}", state_file.display());
             println!(
                 "   State file size: {} bytes",
@@ -137,23 +160,23 @@ pub fn handle_incremental(action: IncrementalAction) -> Result<()> {
         IncrementalAction::Reset => {
             if state_file.exists() {
                 std::fs::remove_file(&state_file)?;
-                println!("✅ Incremental scan state reset.");
+                println!("{} Incremental scan state reset.", Icon::Success);
                 println!("   Next scan will be a full scan.");
             } else {
-                println!("❌ No incremental state to reset.");
+                println!("{} No incremental state to reset.", Icon::Failure);
             }
         }
 
         IncrementalAction::Stats => {
             if !state_file.exists() {
-                println!("❌ No incremental scan state found.");
+                println!("{} No incremental scan state found.", Icon::Failure);
                 return Ok(());
             }
 
             let content = std::fs::read_to_string(&state_file)?;
             let state: code_guardian_core::IncrementalState = serde_json::from_str(&content)?;
 
-            println!("📈 Incremental Scan Statistics:");
+            println!("{} Incremental Scan Statistics:", Icon::Trend);
             println!("   Total tracked files: {}", state.file_metadata.len());
             println!("   Scan history entries: {}", state.scan_history.len());
 
@@ -200,7 +223,8 @@ pub async fn handle_distributed(action: DistributedAction) -> Result<()> {
     match action {
         DistributedAction::Setup { workers } => {
             println!(
-                "🚀 Setting up distributed scanning with {} workers",
+                "{} Setting up distributed scanning with {} workers",
+                Icon::Launch,
                 workers
             );
 
@@ -224,7 +248,7 @@ pub async fn handle_distributed(action: DistributedAction) -> Result<()> {
                 coordinator.register_worker(worker_config);
             }
 
-            println!("✅ Distributed setup complete!");
+            println!("{} Distributed setup complete!", Icon::Success);
             println!("   Workers: {}", workers);
             println!(
                 "   Total capacity: {} cores, {}MB memory",
@@ -232,7 +256,7 @@ pub async fn handle_distributed(action: DistributedAction) -> Result<()> {
                 workers * 2048
             );
 
-            println!("\n💡 To run a distributed scan:");
+            println!("\n{} To run a distributed scan:", Icon::Tip);
             println!(
                 "   code-guardian distributed scan <path> --workers {}",
                 workers
@@ -242,26 +266,41 @@ pub async fn handle_distributed(action: DistributedAction) -> Result<()> {
         DistributedAction::Scan {
             path,
             workers,
+            worker_endpoints,
+            discover_srv,
             batch_size,
+            webhooks,
+            tls_cert,
+            tls_key,
+            tls_ca,
         } => {
-            println!("🌐 Running distributed scan on {}", path.display());
-            println!("   Workers: {}, Batch size: {}", workers, batch_size);
+            let worker_tls = WorkerTls {
+                cert: tls_cert,
+                key: tls_key,
+                ca: tls_ca,
+            };
+            let mut config = load_config(None::<&Path>)?;
+            config.webhooks.extend(webhooks);
+            for url in &config.webhooks {
+                validate_webhook_url(url).map_err(|e| CliError::Usage(e.to_string()))?;
+            }
+            // Command-line discovery replaces the configured one
+            let discovery = if let Some(name) = discover_srv {
+                Some(WorkerDiscovery::DnsSrv(name))
+            } else if !worker_endpoints.is_empty() {
+                Some(WorkerDiscovery::Static(worker_endpoints))
+            } else if let Some(name) = config.worker_srv_record.take() {
+                Some(WorkerDiscovery::DnsSrv(name))
+            } else if !config.worker_endpoints.is_empty() {
+                Some(WorkerDiscovery::Static(std::mem::take(
+                    &mut config.worker_endpoints,
+                )))
+            } else {
+                None
+            };
 
             let mut coordinator = DistributedCoordinator::new();
 
-            // Register workers
-            for i in 0..workers {
-                let worker_config = WorkerConfig {
-                    worker_id: format!("worker_{}", i),
-                    max_concurrent_units: 2,
-                    supported_detectors: vec!["TODO".to_string(), "FIXME".to_string()],
-                    cpu_cores: 2,
-                    memory_limit_mb: 1024,
-                    endpoint: None,
-                };
-                coordinator.register_worker(worker_config);
-            }
-
             // Register basic detectors
             coordinator.register_detector(
                 "TODO".to_string(),
@@ -272,6 +311,42 @@ pub async fn handle_distributed(action: DistributedAction) -> Result<()> {
                 Box::new(code_guardian_core::FixmeDetector),
             );
 
+            // Register workers, refusing to scan if any discovered worker is
+            // unreachable or can't run the same detectors at a compatible version
+            let worker_configs = match discovery {
+                Some(discovery) => negotiate_workers(
+                    &discovery,
+                    &coordinator.detector_names(),
+                    HANDSHAKE_TIMEOUT,
+                    &worker_tls,
+                )
+                .map_err(|e| CliError::Usage(e.to_string()))?,
+                None => (0..workers)
+                    .map(|i| WorkerConfig {
+                        worker_id: format!("worker_{}", i),
+                        max_concurrent_units: 2,
+                        supported_detectors: vec!["TODO".to_string(), "FIXME".to_string()],
+                        cpu_cores: 2,
+                        memory_limit_mb: 1024,
+                        endpoint: None,
+                    })
+                    .collect(),
+            };
+
+            println!(
+                "{} Running distributed scan on {}",
+                Icon::Network,
+                path.display()
+            );
+            println!(
+                "   Workers: {}, Batch size: {}",
+                worker_configs.len(),
+                batch_size
+            );
+            for worker_config in worker_configs {
+                coordinator.register_worker(worker_config);
+            }
+
             // Collect files
             let files: Vec<PathBuf> = ignore::WalkBuilder::new(&path)
                 .build()
@@ -287,19 +362,41 @@ pub async fn handle_distributed(action: DistributedAction) -> Result<()> {
                 .collect();
 
             coordinator.create_work_units(files, batch_size)?;
-            let matches = coordinator.execute_distributed_scan().await?;
+            let aggregated = coordinator.execute_distributed_scan().await?;
+            let matches = aggregated.matches;
 
             let stats = coordinator.get_statistics();
+            let summary = ScanSummary::from_matches(&matches, stats.total_files_processed);
+            notify_webhooks(
+                &config,
+                &ScanCompletedPayload::new(
+                    None,
+                    path.to_string_lossy(),
+                    chrono::Utc::now().timestamp(),
+                    summary,
+                    ThresholdStatus::NotConfigured,
+                )
+                .with_ci(CiRun::detect()),
+            );
 
-            println!("✅ Distributed scan complete!");
+            println!("{} Distributed scan complete!", Icon::Success);
             println!("   Total matches: {}", matches.len());
             println!("   Files processed: {}", stats.total_files_processed);
             println!("   Work units: {}", stats.total_work_units);
             println!("   Processing time: {}ms", stats.total_processing_time_ms);
+            for worker in &aggregated.workers {
+                println!(
+                    "   {}: {} units, {} files, {} matches",
+                    worker.worker_id,
+                    worker.units_completed,
+                    worker.files_processed,
+                    worker.matches_found
+                );
+            }
 
             // Show top matches
             if !matches.is_empty() {
-                println!("\n🔍 Sample matches:");
+                println!("\n{} Sample matches:", Icon::Search);
                 for (i, mat) in matches.iter().take(5).enumerate() {
                     println!(
                         "   {}. {}:{} - {}",
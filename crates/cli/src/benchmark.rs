@@ -1,62 +1,83 @@
 use anyhow::Result;
 use code_guardian_core::{
     performance_optimized_scanner::PerformanceOptimizedScanner, DetectorFactory, DetectorProfile,
-    OptimizedScanner, Scanner, StreamingScanner,
+    DetectorTiming, Icon, OptimizedScanner, PatternDetector, Scanner, StreamingScanner,
 };
 use std::path::Path;
 use std::time::Instant;
 
 /// Run performance benchmarks on different scanner types
 pub fn run_benchmark(path: &Path) -> Result<()> {
-    println!("🚀 Code-Guardian Performance Benchmark");
+    println!("{} Code-Guardian Performance Benchmark", Icon::Launch);
     println!("=====================================\n");
 
-    println!("📁 Scanning path: {}", path.display());
-    println!("🔍 Testing different scanner configurations...\n");
+    println!("{} Scanning path: {}", Icon::Folder, path.display());
+    println!(
+        "{} Testing different scanner configurations...\n",
+        Icon::Search
+    );
 
     // Test basic scanner
-    println!("1️⃣ Basic Scanner (TODO + FIXME only)");
+    println!("{} Basic Scanner (TODO + FIXME only)", Icon::Step(1));
     let start = Instant::now();
     let basic_scanner = Scanner::new(DetectorFactory::create_default_detectors());
     let basic_matches = basic_scanner.scan(path)?;
     let basic_duration = start.elapsed();
-    println!("   ⏱️  Duration: {:?}", basic_duration);
-    println!("   📊 Matches found: {}", basic_matches.len());
+    println!("   {} Duration: {:?}", Icon::Timer, basic_duration);
+    println!("   {} Matches found: {}", Icon::Stats, basic_matches.len());
     println!();
 
     // Test comprehensive scanner
-    println!("2️⃣ Comprehensive Scanner (All detectors)");
+    println!("{} Comprehensive Scanner (All detectors)", Icon::Step(2));
     let start = Instant::now();
     let comprehensive_scanner = Scanner::new(DetectorProfile::Comprehensive.get_detectors());
     let comprehensive_matches = comprehensive_scanner.scan(path)?;
     let comprehensive_duration = start.elapsed();
-    println!("   ⏱️  Duration: {:?}", comprehensive_duration);
-    println!("   📊 Matches found: {}", comprehensive_matches.len());
+    println!("   {} Duration: {:?}", Icon::Timer, comprehensive_duration);
+    println!(
+        "   {} Matches found: {}",
+        Icon::Stats,
+        comprehensive_matches.len()
+    );
     println!();
 
     // Test optimized scanner
-    println!("3️⃣ Optimized Scanner (With caching)");
+    println!("{} Optimized Scanner (With caching)", Icon::Step(3));
     let start = Instant::now();
     let optimized_scanner = OptimizedScanner::new(DetectorProfile::Comprehensive.get_detectors())
         .with_cache_size(10000);
     let (optimized_matches, optimized_metrics) = optimized_scanner.scan_optimized(path)?;
     let optimized_duration = start.elapsed();
-    println!("   ⏱️  Duration: {:?}", optimized_duration);
-    println!("   📊 Matches found: {}", optimized_matches.len());
+    println!("   {} Duration: {:?}", Icon::Timer, optimized_duration);
     println!(
-        "   📈 Files scanned: {}",
+        "   {} Matches found: {}",
+        Icon::Stats,
+        optimized_matches.len()
+    );
+    println!(
+        "   {} Files scanned: {}",
+        Icon::Trend,
         optimized_metrics.total_files_scanned
     );
     println!(
-        "   📈 Lines processed: {}",
+        "   {} Lines processed: {}",
+        Icon::Trend,
         optimized_metrics.total_lines_processed
     );
-    println!("   🎯 Cache hits: {}", optimized_metrics.cache_hits);
-    println!("   🎯 Cache misses: {}", optimized_metrics.cache_misses);
+    println!(
+        "   {} Cache hits: {}",
+        Icon::Target,
+        optimized_metrics.cache_hits
+    );
+    println!(
+        "   {} Cache misses: {}",
+        Icon::Target,
+        optimized_metrics.cache_misses
+    );
     println!();
 
     // Test streaming scanner
-    println!("4️⃣ Streaming Scanner (Memory efficient)");
+    println!("{} Streaming Scanner (Memory efficient)", Icon::Step(4));
     let start = Instant::now();
     let streaming_scanner = StreamingScanner::new(DetectorProfile::Comprehensive.get_detectors());
     let mut streaming_matches = Vec::new();
@@ -65,44 +86,75 @@ pub fn run_benchmark(path: &Path) -> Result<()> {
         Ok(())
     })?;
     let streaming_duration = start.elapsed();
-    println!("   ⏱️  Duration: {:?}", streaming_duration);
-    println!("   📊 Matches found: {}", streaming_matches.len());
+    println!("   {} Duration: {:?}", Icon::Timer, streaming_duration);
     println!(
-        "   📈 Files scanned: {}",
+        "   {} Matches found: {}",
+        Icon::Stats,
+        streaming_matches.len()
+    );
+    println!(
+        "   {} Files scanned: {}",
+        Icon::Trend,
         streaming_metrics.total_files_scanned
     );
     println!(
-        "   📈 Lines processed: {}",
+        "   {} Lines processed: {}",
+        Icon::Trend,
         streaming_metrics.total_lines_processed
     );
     println!();
 
     // Test performance optimized scanner
-    println!("5️⃣ Performance Optimized Scanner (SIMD + Advanced Caching)");
+    println!(
+        "{} Performance Optimized Scanner (SIMD + Advanced Caching)",
+        Icon::Step(5)
+    );
     let start = Instant::now();
     let perf_scanner =
         PerformanceOptimizedScanner::new(DetectorProfile::Comprehensive.get_detectors());
     let (perf_matches, perf_metrics) = perf_scanner.scan_ultra_fast(path)?;
     let perf_duration = start.elapsed();
-    println!("   ⏱️  Duration: {:?}", perf_duration);
-    println!("   📊 Matches found: {}", perf_matches.len());
-    println!("   📈 Files scanned: {}", perf_metrics.total_files_scanned);
+    println!("   {} Duration: {:?}", Icon::Timer, perf_duration);
+    println!("   {} Matches found: {}", Icon::Stats, perf_matches.len());
     println!(
-        "   📈 Lines processed: {}",
+        "   {} Files scanned: {}",
+        Icon::Trend,
+        perf_metrics.total_files_scanned
+    );
+    println!(
+        "   {} Lines processed: {}",
+        Icon::Trend,
         perf_metrics.total_lines_processed
     );
-    println!("   🎯 Cache hits: {}", perf_metrics.cache_hits);
-    println!("   ⚡ SIMD matches: {}", perf_metrics.simd_matches);
-    println!("   🔍 Regex matches: {}", perf_metrics.regex_matches);
-    println!("   📖 File read time: {}ms", perf_metrics.file_read_time_ms);
     println!(
-        "   🔎 Pattern search time: {}ms",
+        "   {} Cache hits: {}",
+        Icon::Target,
+        perf_metrics.cache_hits
+    );
+    println!(
+        "   {} SIMD matches: {}",
+        Icon::Fast,
+        perf_metrics.simd_matches
+    );
+    println!(
+        "   {} Regex matches: {}",
+        Icon::Search,
+        perf_metrics.regex_matches
+    );
+    println!(
+        "   {} File read time: {}ms",
+        Icon::Docs,
+        perf_metrics.file_read_time_ms
+    );
+    println!(
+        "   {} Pattern search time: {}ms",
+        Icon::Inspect,
         perf_metrics.pattern_search_time_ms
     );
     println!();
 
     // Performance comparison
-    println!("📊 Performance Comparison");
+    println!("{} Performance Comparison", Icon::Stats);
     println!("========================");
 
     let basic_files_per_sec =
@@ -115,7 +167,7 @@ pub fn run_benchmark(path: &Path) -> Result<()> {
         streaming_metrics.total_files_scanned as f64 / streaming_duration.as_secs_f64();
     let perf_files_per_sec = perf_metrics.total_files_scanned as f64 / perf_duration.as_secs_f64();
 
-    println!("📈 Files per second:");
+    println!("{} Files per second:", Icon::Trend);
     println!("   Basic:          {:.1}", basic_files_per_sec);
     println!("   Comprehensive:  {:.1}", comprehensive_files_per_sec);
     println!("   Optimized:      {:.1}", optimized_files_per_sec);
@@ -123,7 +175,7 @@ pub fn run_benchmark(path: &Path) -> Result<()> {
     println!("   Performance:    {:.1}", perf_files_per_sec);
     println!();
 
-    println!("🎯 Speed improvements:");
+    println!("{} Speed improvements:", Icon::Target);
     let optimized_speedup = optimized_files_per_sec / comprehensive_files_per_sec;
     let streaming_speedup = streaming_files_per_sec / comprehensive_files_per_sec;
     let perf_speedup = perf_files_per_sec / comprehensive_files_per_sec;
@@ -142,40 +194,49 @@ pub fn run_benchmark(path: &Path) -> Result<()> {
     );
     println!();
 
-    println!("💡 Recommendations:");
+    println!("{} Recommendations:", Icon::Tip);
     if perf_speedup > 2.0 {
         println!(
-            "   🚀 Use --ultra-fast flag for maximum performance ({}x faster)",
+            "   {} Use --ultra-fast flag for maximum performance ({}x faster)",
+            Icon::Launch,
             perf_speedup
         );
     } else if optimized_speedup > 1.2 {
-        println!("   ✅ Use --optimize flag for better performance");
+        println!(
+            "   {} Use --optimize flag for better performance",
+            Icon::Success
+        );
     }
     if streaming_speedup > 1.1 {
-        println!("   ✅ Use --streaming flag for large codebases");
+        println!(
+            "   {} Use --streaming flag for large codebases",
+            Icon::Success
+        );
     }
     if perf_metrics.cache_hits > 0 {
         println!(
-            "   ✅ Caching is highly effective ({}% hit rate)",
+            "   {} Caching is highly effective ({}% hit rate)",
+            Icon::Success,
             perf_metrics.cache_hits * 100 / (perf_metrics.cache_hits + perf_metrics.cache_misses)
         );
     }
     if perf_metrics.simd_matches > 0 {
         println!(
-            "   ⚡ SIMD optimization found {} patterns ultra-fast",
+            "   {} SIMD optimization found {} patterns ultra-fast",
+            Icon::Fast,
             perf_metrics.simd_matches
         );
     }
 
     println!();
-    println!("🏁 Benchmark completed!");
+    println!("{} Benchmark completed!", Icon::Finish);
 
     Ok(())
 }
 
 /// Quick performance test
 pub fn quick_performance_test(path: &Path) -> Result<()> {
-    println!("⚡ Quick Performance Test");
+    println!("{} Quick Performance Test", Icon::Fast);
     println!("========================\n");
 
     let start = Instant::now();
@@ -183,7 +244,7 @@ pub fn quick_performance_test(path: &Path) -> Result<()> {
     let (matches, metrics) = scanner.scan_optimized(path)?;
     let duration = start.elapsed();
 
-    println!("📊 Results:");
+    println!("{} Results:", Icon::Stats);
     println!("   Duration: {:?}", duration);
     println!("   Files scanned: {}", metrics.total_files_scanned);
     println!("   Lines processed: {}", metrics.total_lines_processed);
@@ -205,3 +266,128 @@ pub fn quick_performance_test(path: &Path) -> Result<()> {
 
     Ok(())
 }
+
+/// Detectors slower than this multiple of the average detector time are flagged as hot rules
+const HOT_DETECTOR_FACTOR: f64 = 3.0;
+
+/// Per-detector benchmark: time spent, matches and files for each detector
+pub fn per_detector_benchmark(path: &Path, detectors: Vec<Box<dyn PatternDetector>>) -> Result<()> {
+    println!("{} Per-Detector Benchmark", Icon::Timer);
+    println!("=========================\n");
+    println!("{} Scanning path: {}", Icon::Folder, path.display());
+    println!(
+        "{} Profiling {} detectors...\n",
+        Icon::Search,
+        detectors.len()
+    );
+
+    let scanner = OptimizedScanner::new(detectors).with_detector_profiling(true);
+    let (_, metrics) = scanner.scan_optimized(path)?;
+
+    println!(
+        "{} Files scanned: {}",
+        Icon::Trend,
+        metrics.total_files_scanned
+    );
+    println!(
+        "{} Scan duration: {}ms\n",
+        Icon::Timer,
+        metrics.scan_duration_ms
+    );
+    print!("{}", format_detector_timings(&metrics.detector_timings));
+    Ok(())
+}
+
+/// Formats detector timings as a table, slowest first, flagging hot rules
+pub fn format_detector_timings(timings: &[DetectorTiming]) -> String {
+    let total_us: u64 = timings.iter().map(|t| t.duration_us).sum();
+    let hot_threshold_us = HOT_DETECTOR_FACTOR * total_us as f64 / timings.len().max(1) as f64;
+    let name_width = timings
+        .iter()
+        .map(|t| t.detector.len())
+        .max()
+        .unwrap_or(0)
+        .max("Detector".len());
+
+    let mut output = format!(
+        "{:<name_width$}  {:>10}  {:>6}  {:>8}  {:>8}  {:>10}\n",
+        "Detector", "Time (ms)", "Share", "Matches", "Files", "With match"
+    );
+    let mut hot = Vec::new();
+    for timing in timings {
+        let share = if total_us > 0 {
+            timing.duration_us as f64 / total_us as f64
+        } else {
+            0.0
+        };
+        output.push_str(&format!(
+            "{:<name_width$}  {:>10.2}  {:>5.1}%  {:>8}  {:>8}  {:>10}\n",
+            timing.detector,
+            timing.duration_us as f64 / 1000.0,
+            share * 100.0,
+            timing.matches_found,
+            timing.files_scanned,
+            timing.files_with_matches
+        ));
+        if total_us > 0 && timing.duration_us as f64 > hot_threshold_us {
+            hot.push(timing.detector.as_str());
+        }
+    }
+
+    if !hot.is_empty() {
+        output.push_str(&format!(
+            "\n{} Hot rules (over {}x the average detector time): {}\n",
+            Icon::Slow,
+            HOT_DETECTOR_FACTOR,
+            hot.join(", ")
+        ));
+        output.push_str(&format!(
+            "{} Consider anchoring or simplifying these patterns, or restricting them with file_extensions\n",
+            Icon::Tip
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(detector: &str, duration_us: u64, matches_found: usize) -> DetectorTiming {
+        DetectorTiming {
+            detector: detector.to_string(),
+            duration_us,
+            matches_found,
+            files_scanned: 10,
+            files_with_matches: matches_found.min(10),
+        }
+    }
+
+    #[test]
+    fn test_format_detector_timings_flags_hot_rules() {
+        let timings = vec![
+            timing("SLOW_REGEX", 80_000, 3),
+            timing("TodoDetector", 5_000, 12),
+            timing("FixmeDetector", 5_000, 0),
+            timing("HackDetector", 5_000, 1),
+            timing("BugDetector", 5_000, 0),
+        ];
+        let report = format_detector_timings(&timings);
+
+        let rows: Vec<&str> = report.lines().collect();
+        assert!(rows[0].starts_with("Detector"));
+        assert!(rows[1].starts_with("SLOW_REGEX"));
+        assert!(rows[1].contains("80.00"));
+        assert!(rows[1].contains("80.0%"));
+        assert!(report.contains("Hot rules (over 3x the average detector time): SLOW_REGEX\n"));
+    }
+
+    #[test]
+    fn test_format_detector_timings_even_spread_not_hot() {
+        let report = format_detector_timings(&[
+            timing("TodoDetector", 1_000, 1),
+            timing("FixmeDetector", 2_000, 1),
+        ]);
+        assert!(!report.contains("Hot rules"));
+    }
+}
@@ -1,6 +1,12 @@
-use clap::{Parser, Subcommand};
-use clap_complete::Shell;
-use std::path::PathBuf;
+use clap::{ArgAction, Parser, Subcommand};
+use clap_complete::{ArgValueCandidates, Shell};
+use code_guardian_core::{
+    CacheKey, FindingState, ScanBudget, Severity, TokenScope, DEFAULT_BITBUCKET_API_URL,
+    DEFAULT_GITLAB_API_URL,
+};
+use std::path::{Path, PathBuf};
+
+use crate::completions::{profile_candidates, scan_id_candidates, target_candidates};
 
 #[derive(Parser)]
 #[command(
@@ -10,24 +16,121 @@ use std::path::PathBuf;
     version
 )]
 pub struct Cli {
+    /// Always exit with code 0 when findings exceed thresholds (report-only pipelines)
+    #[arg(long, global = true)]
+    pub exit_zero: bool,
+    /// Print ASCII instead of emoji, for CI log viewers and terminals that cannot show them
+    #[arg(long, global = true, visible_alias = "ascii")]
+    pub no_emoji: bool,
+    /// End scanning commands with a statistics line on stderr, e.g.
+    /// `files=1234 skipped=12 matches=345 critical=2 duration_ms=4521`
+    #[arg(long, global = true)]
+    pub summary: bool,
+    /// Keep the secret values secrets detectors found in findings and reports
+    /// instead of masking them; refused in CI
+    #[arg(long, global = true)]
+    pub no_redact: bool,
+    /// Append a JSON line recording this command, its user, configuration
+    /// hash and results to this file; overrides `audit_log` in the config
+    #[arg(long, global = true, value_name = "PATH")]
+    pub audit_log: Option<PathBuf>,
     #[command(subcommand)]
     pub command: Commands,
 }
 
+/// Process exit codes shared by all commands.
+///
+/// | Code | Meaning                                   |
+/// |------|-------------------------------------------|
+/// | 0    | Success, no findings above threshold      |
+/// | 1    | Findings exceeded the configured threshold |
+/// | 2    | Usage error (bad arguments, paths, formats) |
+/// | 3    | Internal error                            |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    Success = 0,
+    FindingsAboveThreshold = 1,
+    UsageError = 2,
+    InternalError = 3,
+}
+
+impl ExitCode {
+    /// Map a command error to its exit code
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        match error.downcast_ref::<CliError>() {
+            Some(CliError::ThresholdExceeded(_)) => ExitCode::FindingsAboveThreshold,
+            Some(CliError::Usage(_)) => ExitCode::UsageError,
+            None => ExitCode::InternalError,
+        }
+    }
+}
+
+impl From<ExitCode> for std::process::ExitCode {
+    fn from(code: ExitCode) -> Self {
+        std::process::ExitCode::from(code as u8)
+    }
+}
+
+/// Errors that carry a specific exit code
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    /// Findings exceeded the configured failure threshold
+    #[error("{0}")]
+    ThresholdExceeded(String),
+    /// Invalid arguments or inputs supplied by the user
+    #[error("{0}")]
+    Usage(String),
+}
+
+// Parsed once per process, so the size of the `Scan` variant doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum Commands {
     /// Scan a directory for patterns and save results
     Scan {
         /// Path to the directory to scan
-        path: PathBuf,
+        #[arg(required_unless_present_any = ["stdin", "target", "all_targets"])]
+        path: Option<PathBuf>,
+        /// Scan content read from stdin and print the findings as JSON without saving them
+        #[arg(
+            long,
+            conflicts_with_all = ["path", "incremental", "distributed", "streaming", "optimize", "list_files", "blame", "group_by", "target", "all_targets"]
+        )]
+        stdin: bool,
+        /// Scan the `[targets.<NAME>]` directory of the config as its own scan (repeatable)
+        #[arg(
+            long,
+            value_name = "NAME",
+            conflicts_with = "path",
+            requires = "config",
+            add = ArgValueCandidates::new(target_candidates)
+        )]
+        target: Vec<String>,
+        /// Scan every target of the config, each stored as a separate scan
+        #[arg(
+            long,
+            conflicts_with_all = ["path", "target"],
+            requires = "config"
+        )]
+        all_targets: bool,
+        /// Scan the targets of --target or --all-targets in parallel instead of one after another
+        #[arg(long)]
+        parallel: bool,
+        /// Virtual path reported for stdin content; its extension selects language-specific rules
+        #[arg(long, value_name = "PATH", requires = "stdin")]
+        filename: Option<PathBuf>,
         /// Database file path (optional, defaults to data/code-guardian.db)
         #[arg(short, long)]
         db: Option<PathBuf>,
+        /// Save the scan under this project, so one database can hold the scans of many
+        /// repositories: lowercase letters, digits, '-', '_' and '.'
+        #[arg(long, value_name = "NAME", conflicts_with = "stdin")]
+        project: Option<String>,
         /// Config file path (optional)
         #[arg(short, long)]
         config: Option<PathBuf>,
-        /// Detector profile: basic, comprehensive, security, performance, rust
-        #[arg(long, default_value = "basic")]
+        /// Detector profile: basic, comprehensive, security, performance, rust, rust-panics, go, c, python, jvm, iac, quality, accessibility, migrations
+        #[arg(long, default_value = "basic", add = ArgValueCandidates::new(profile_candidates))]
         profile: String,
         /// Show progress bar
         #[arg(long)]
@@ -41,18 +144,64 @@ pub enum Commands {
         /// Show performance metrics
         #[arg(long)]
         metrics: bool,
+        /// Write scan metrics as JSON to this file, e.g. for CI dashboards
+        #[arg(long, value_name = "PATH", conflicts_with = "stdin")]
+        metrics_out: Option<PathBuf>,
+        /// Upload the JSON report and metrics to object storage under content-addressed
+        /// names: s3://bucket/prefix, or gs:// and az:// in builds with the gcs and azure features
+        #[arg(long, value_name = "URL", conflicts_with = "stdin")]
+        upload: Option<String>,
+        /// POST a signed JSON summary to this URL when the scan completes (repeatable)
+        #[arg(long = "webhook", value_name = "URL", conflicts_with = "stdin")]
+        webhooks: Vec<String>,
+        /// Ask the configured `llm_triage_endpoint` whether low-confidence LLM findings are real
+        #[arg(long, conflicts_with = "stdin")]
+        llm_triage: bool,
         /// Use incremental scanning (only scan changed files)
         #[arg(long)]
         incremental: bool,
+        /// With --incremental, read only directories modified since the last scan, reusing the
+        /// recorded entries of the others
+        #[arg(long, requires = "incremental")]
+        prune_dirs: bool,
         /// Use distributed scanning across multiple workers
         #[arg(long)]
         distributed: bool,
+        /// Skip detectors that found nothing in 100 or more files of an extension in earlier
+        /// scans of the database, running them again every 10th fast scan
+        #[arg(
+            long,
+            conflicts_with_all = ["stdin", "incremental", "distributed", "streaming", "optimize"]
+        )]
+        fast: bool,
+        /// Stop scanning files after this long, e.g. 30s, 500ms or 2m, scanning files with
+        /// tracked findings and recently modified files first and reporting the files covered
+        #[arg(
+            long,
+            value_name = "DURATION",
+            conflicts_with_all = ["stdin", "incremental", "distributed", "streaming", "optimize"]
+        )]
+        budget: Option<ScanBudget>,
         /// Path to custom detectors configuration file
         #[arg(long)]
         custom_detectors: Option<PathBuf>,
+        /// Reject custom detectors with slow or overly broad patterns instead of warning
+        #[arg(long, requires = "custom_detectors")]
+        strict_rules: bool,
+        /// Time after which a custom detector stops reporting matches in a
+        /// single file, checked as each match is found (default: 1000)
+        #[arg(long, value_name = "MS", requires = "custom_detectors")]
+        rule_timeout_ms: Option<u64>,
         /// Cache size for optimized scanning
         #[arg(long)]
         cache_size: Option<usize>,
+        /// Key the scan cache on file `content` hashes, persisted under .code-guardian/cache, or on `mtime` [default: mtime]
+        #[arg(long, value_name = "KEY", conflicts_with = "stdin")]
+        cache_key: Option<CacheKey>,
+        /// Directory of the content-keyed scan cache, shared by checkouts and worktrees
+        /// that point at it [default: .code-guardian/cache in the scan root]
+        #[arg(long, value_name = "DIR", conflicts_with = "stdin")]
+        cache_dir: Option<PathBuf>,
         /// Batch size for distributed scanning
         #[arg(long)]
         batch_size: Option<usize>,
@@ -62,6 +211,55 @@ pub enum Commands {
         /// Maximum number of threads
         #[arg(long)]
         max_threads: Option<usize>,
+        /// Keep at most N findings per file; the rest are summarized by a TRUNCATED marker
+        #[arg(long, value_name = "N")]
+        max_matches_per_file: Option<usize>,
+        /// Keep at most N findings per pattern within a file
+        #[arg(long, value_name = "N")]
+        max_matches_per_pattern: Option<usize>,
+        /// When there are more than N findings, keep all Critical/High findings but sample Medium/Low ones per file
+        #[arg(long, value_name = "N")]
+        sample_above: Option<usize>,
+        /// Medium/Low findings kept per file when sampling [default: 10]
+        #[arg(long, value_name = "N")]
+        sample_per_file: Option<usize>,
+        /// Also scan generated and vendored files (minified bundles, protobuf output, vendor/, @generated headers)
+        #[arg(long)]
+        include_generated: bool,
+        /// Follow symlinked files and directories; each directory is walked once, so cycles are safe
+        #[arg(long)]
+        follow_symlinks: bool,
+        /// Do not descend more than N directories below the scan path
+        #[arg(long, value_name = "N")]
+        max_depth: Option<usize>,
+        /// List each file that could not be read (permission denied, invalid UTF-8, too large) with the reason
+        #[arg(long)]
+        report_skipped: bool,
+        /// Print only the files containing findings, one per line
+        #[arg(long)]
+        list_files: bool,
+        /// Separate listed files with NUL instead of newline (for `xargs -0`)
+        #[arg(short = '0', long = "null", requires = "list_files")]
+        null: bool,
+        /// Flag TODO/FIXME markers that lack an issue reference (ORPHAN_TODO)
+        #[arg(long)]
+        require_issue_ref: bool,
+        /// Regex accepted as an issue reference (default: #123, PROJ-42 or an issue URL)
+        #[arg(long, requires = "require_issue_ref")]
+        issue_pattern: Option<String>,
+        /// Fail if TODO/FIXME-style comments were last touched more than DAYS ago (uses git blame)
+        #[arg(long, value_name = "DAYS")]
+        fail_on_stale_todos: Option<u64>,
+        /// Attach the last-touch author from git blame to each match, stored with the scan
+        #[arg(long)]
+        blame: bool,
+        /// Group blamed matches in the output: author
+        #[arg(long, requires = "blame")]
+        group_by: Option<String>,
+        /// Print the detectors, the files to scan, the effective settings and
+        /// an estimated duration, then stop without reading files or saving a scan
+        #[arg(long, conflicts_with = "stdin")]
+        dry_run: bool,
     },
     /// List all scan history from the database
     History {
@@ -72,12 +270,17 @@ pub enum Commands {
             help = "Specify the database file path. If not provided, uses 'data/code-guardian.db'"
         )]
         db: Option<PathBuf>,
+        /// Only scans of this project
+        #[arg(long, value_name = "NAME")]
+        project: Option<String>,
     },
     /// Generate a report for a specific scan in various formats
     Report {
         /// Scan ID to generate report for
+        #[arg(add = ArgValueCandidates::new(scan_id_candidates))]
         id: i64,
-        /// Output format: text, json, csv, markdown, html (default: text)
+        /// Output format: text, json, csv, markdown, html, sarif, defectdojo,
+        /// sonarqube (default: text)
         #[arg(
             short,
             long,
@@ -92,14 +295,53 @@ pub enum Commands {
             help = "Specify the database file path. If not provided, uses 'data/code-guardian.db'"
         )]
         db: Option<PathBuf>,
+        /// Skip this many matches, for paging through large scans
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+        /// Print at most this many matches, loading only those from the database
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Only matches of this pattern, e.g. TODO
+        #[arg(long)]
+        pattern: Option<String>,
+        /// Only matches whose file path contains this text
+        #[arg(long)]
+        file: Option<String>,
+        /// Group findings of a rule in a file on consecutive lines or with
+        /// the same message into one issue with an occurrence count
+        #[arg(long, conflicts_with_all = ["offset", "limit"])]
+        group: bool,
+        /// Summarize the findings per CWE, OWASP Top 10 and SOC 2 control
+        /// instead of listing them; text or json
+        #[arg(long, conflicts_with_all = ["offset", "limit", "pattern", "file", "group"])]
+        by_compliance: bool,
+        /// List the rules with the most findings and suggest config quieting
+        /// them: path exclusions and severity downgrades; text or json
+        #[arg(
+            long,
+            conflicts_with_all = ["offset", "limit", "pattern", "file", "group", "by_compliance"]
+        )]
+        tune: bool,
+        /// Rules listed by --tune
+        #[arg(long, default_value_t = 10, requires = "tune")]
+        top: usize,
+        /// Config file whose `compliance` section maps rules to controls
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Fail unless the scan belongs to this project
+        #[arg(long, value_name = "NAME")]
+        project: Option<String>,
     },
     /// Compare two scans and show differences
     Compare {
         /// First scan ID
+        #[arg(add = ArgValueCandidates::new(scan_id_candidates))]
         id1: i64,
         /// Second scan ID
+        #[arg(add = ArgValueCandidates::new(scan_id_candidates))]
         id2: i64,
-        /// Output format: text, json, csv, markdown, html (default: text)
+        /// Output format: text, json, csv, markdown, html, sarif, defectdojo,
+        /// sonarqube (default: text)
         #[arg(
             short,
             long,
@@ -115,10 +357,138 @@ pub enum Commands {
         )]
         db: Option<PathBuf>,
     },
-    /// Generate shell completion scripts
+    /// Report the languages, files, lines and detector coverage of a codebase, stored with its scan
+    Inventory {
+        /// Path to the directory to scan
+        #[arg(required_unless_present = "scan")]
+        path: Option<PathBuf>,
+        /// Show the inventory stored with this scan instead of scanning
+        #[arg(long, value_name = "ID", conflicts_with_all = ["path", "profile", "custom_detectors"])]
+        scan: Option<i64>,
+        /// Detector profile whose coverage is reported: basic, comprehensive, security, performance, rust, rust-panics, go, c, python, jvm, iac, quality, accessibility, migrations
+        #[arg(long, default_value = "basic", add = ArgValueCandidates::new(profile_candidates))]
+        profile: String,
+        /// Path to custom detectors configuration file
+        #[arg(long)]
+        custom_detectors: Option<PathBuf>,
+        /// Output format: text, json (default: text)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+    /// Show which detectors found nothing, which files were skipped and why, and which file types no detector looks at
+    Coverage {
+        /// Path to the directory to scan
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Detector profile to check: basic, comprehensive, security, performance, rust, rust-panics, go, c, python, jvm, iac, quality, accessibility, migrations
+        #[arg(long, default_value = "basic", add = ArgValueCandidates::new(profile_candidates))]
+        profile: String,
+        /// Path to custom detectors configuration file
+        #[arg(long)]
+        custom_detectors: Option<PathBuf>,
+        /// Output format: text, json (default: text)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+    /// Report the remaining usages of the deprecated APIs declared in a custom detector file
+    Migration {
+        /// Path to the directory to scan
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Custom detector file whose rules with a `deprecation` block are checked
+        #[arg(long)]
+        custom_detectors: PathBuf,
+        /// Output format: text, json (default: text)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+    /// Track the state of findings across scans: open, acknowledged, fixed or wontfix
+    Findings {
+        #[command(subcommand)]
+        action: FindingsAction,
+    },
+    /// Back up, restore and check the findings database
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+    /// Inspect the findings database
+    Storage {
+        #[command(subcommand)]
+        action: StorageAction,
+    },
+    /// Manage the bearer tokens `serve` accepts
+    Token {
+        #[command(subcommand)]
+        action: TokenAction,
+    },
+    /// Generate shell completion scripts or man pages
     Completion {
         /// Shell to generate completion for (bash, zsh, fish, etc.)
-        shell: Shell,
+        #[arg(required_unless_present = "man")]
+        shell: Option<Shell>,
+        /// Print a script that asks code-guardian for values while completing,
+        /// such as scan IDs from the database and targets from code-guardian.toml
+        #[arg(long, requires = "shell")]
+        dynamic: bool,
+        /// Write man pages of code-guardian and each subcommand to this directory
+        #[arg(long, value_name = "DIR", conflicts_with = "shell")]
+        man: Option<PathBuf>,
+    },
+    /// Inspect and validate configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Serve scans to LLM assistants over the Model Context Protocol (stdio)
+    Mcp {
+        /// Directory the assistant may scan; paths outside it are rejected
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+        /// Detector profile used when a scan doesn't name one
+        #[arg(long, default_value = "basic", add = ArgValueCandidates::new(profile_candidates))]
+        profile: String,
+    },
+    /// Serve the health endpoints and an Atom feed of recent scans at /feed.atom over HTTP
+    Serve {
+        /// Port to listen on, on all interfaces
+        #[arg(long, default_value = "8080")]
+        port: u16,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+        /// Number of most recent scans in the feed
+        #[arg(long, default_value = "20")]
+        feed_scans: usize,
+        /// Directory `POST /scans` may scan; paths outside it are rejected
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+        /// Detector profile used when a requested scan doesn't name one
+        #[arg(long, default_value = "basic", add = ArgValueCandidates::new(profile_candidates))]
+        profile: String,
+        /// Serve https with this PEM certificate chain, leaf first
+        #[arg(long, value_name = "PEM", requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+        /// PEM private key of --tls-cert
+        #[arg(long, value_name = "PEM", requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+    },
+    /// Print the version, enabled features, detectors, languages and storage backend of this build
+    Info {
+        /// Print the report as JSON, for tools adapting to the installed binary
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the JSON Schema for a report or configuration format
+    Schema {
+        /// Schema to print: report, custom-detectors, config
+        kind: String,
     },
     /// Run performance benchmark
     Benchmark {
@@ -127,6 +497,20 @@ pub enum Commands {
         /// Run quick test only
         #[arg(long)]
         quick: bool,
+        /// Report time spent, matches and files per detector
+        #[arg(long, conflicts_with = "quick")]
+        per_detector: bool,
+        /// Detector profile to profile with --per-detector
+        #[arg(
+            long,
+            default_value = "comprehensive",
+            requires = "per_detector",
+            add = ArgValueCandidates::new(profile_candidates)
+        )]
+        profile: String,
+        /// Custom detectors to include with --per-detector
+        #[arg(long, requires = "per_detector")]
+        custom_detectors: Option<PathBuf>,
     },
     /// Manage custom detectors
     CustomDetectors {
@@ -163,6 +547,13 @@ pub enum Commands {
         /// Output file path (optional)
         #[arg(short, long)]
         output: Option<PathBuf>,
+        /// Also run the custom detectors in this file, reported at their configured severity
+        #[arg(long)]
+        custom_detectors: Option<PathBuf>,
+        /// Report commented-out console.log and debugger statements, which don't
+        /// count as CONSOLE_LOG or DEBUGGER, as low severity COMMENTED_OUT_CODE
+        #[arg(long)]
+        report_commented_out: bool,
     },
     /// Pre-commit hook for checking code quality
     PreCommit {
@@ -175,6 +566,14 @@ pub enum Commands {
         /// Fast mode - only critical and high severity issues
         #[arg(long)]
         fast: bool,
+        /// Database whose tracked findings put staged files with earlier findings first
+        /// (optional, defaults to data/code-guardian.db when it exists)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+        /// Stop scanning staged files after this long, e.g. 5s or 500ms, and report the
+        /// files covered
+        #[arg(long, value_name = "DURATION")]
+        budget: Option<ScanBudget>,
     },
     /// CI/CD gate with proper exit codes
     CiGate {
@@ -193,6 +592,12 @@ pub enum Commands {
         /// Maximum allowed high severity issues (default: 5)
         #[arg(long, default_value = "5")]
         max_high: u32,
+        /// Also run the custom detectors in this file; their configured severities count toward the limits
+        #[arg(long)]
+        custom_detectors: Option<PathBuf>,
+        /// Upload the JSON report to object storage under a content-addressed name, e.g. s3://bucket/prefix
+        #[arg(long, value_name = "URL")]
+        upload: Option<String>,
     },
     /// Language-specific scanning presets
     Lang {
@@ -205,8 +610,9 @@ pub enum Commands {
         /// Output format: text, json, summary (default: text)
         #[arg(short, long, default_value = "text")]
         format: String,
-        /// Include production readiness checks
-        #[arg(long)]
+        /// Include production readiness checks; `--production=false` runs the
+        /// comprehensive detectors, test hygiene checks included
+        #[arg(long, num_args = 0..=1, default_missing_value = "true", action = ArgAction::Set, default_value_t = false)]
         production: bool,
     },
     /// Technology stack presets
@@ -234,6 +640,59 @@ pub enum Commands {
         #[command(subcommand)]
         action: GitAction,
     },
+    /// Bitbucket Code Insights reports
+    Bitbucket {
+        #[command(subcommand)]
+        action: BitbucketAction,
+    },
+    /// GitLab merge request decoration
+    Gitlab {
+        #[command(subcommand)]
+        action: GitLabAction,
+    },
+    /// Jira issues for findings
+    Jira {
+        #[command(subcommand)]
+        action: JiraAction,
+    },
+    /// Apply automated fixes to source files
+    Fix {
+        /// Path to the directory to fix
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Insert a tracking placeholder into TODO/FIXME markers lacking an issue reference
+        #[arg(
+            long,
+            value_name = "PLACEHOLDER",
+            num_args = 0..=1,
+            default_missing_value = "#TBD"
+        )]
+        add_issue_placeholder: Option<String>,
+        /// Regex accepted as an issue reference (default: #123, PROJ-42 or an issue URL)
+        #[arg(long)]
+        issue_pattern: Option<String>,
+        /// Show what would change without writing files
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+impl Commands {
+    /// The config file passed to the command, if it takes one
+    pub fn config_path(&self) -> Option<&Path> {
+        match self {
+            Commands::Scan { config, .. }
+            | Commands::CiGate { config, .. }
+            | Commands::Report { config, .. } => config.as_deref(),
+            Commands::Config {
+                action: ConfigAction::Validate { config, .. },
+            }
+            | Commands::Jira {
+                action: JiraAction::Create { config, .. },
+            } => config.as_deref(),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -260,6 +719,163 @@ pub enum CustomDetectorAction {
     },
 }
 
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Validate the merged configuration and print effective values with their source
+    Validate {
+        /// Config file path (optional)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+        /// Path to custom detectors configuration file
+        #[arg(long)]
+        custom_detectors: Option<PathBuf>,
+        /// Database file path override
+        #[arg(long)]
+        db: Option<PathBuf>,
+        /// Cache size override
+        #[arg(long)]
+        cache_size: Option<usize>,
+        /// Batch size override
+        #[arg(long)]
+        batch_size: Option<usize>,
+        /// Maximum file size override (in bytes)
+        #[arg(long)]
+        max_file_size: Option<usize>,
+        /// Maximum number of threads override
+        #[arg(long)]
+        max_threads: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FindingsAction {
+    /// List tracked findings with their fingerprints and states
+    List {
+        /// Only findings of scans of this root path, as passed to `scan`
+        #[arg(long)]
+        root: Option<String>,
+        /// Only findings in this state: open, acknowledged, fixed, wontfix
+        #[arg(long)]
+        state: Option<FindingState>,
+        /// Output format: text or json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+    /// Acknowledge a finding: it is known and accepted for now
+    Ack {
+        /// Fingerprint shown by `findings list`
+        fingerprint: String,
+        /// Why, e.g. the issue tracking it
+        #[arg(long)]
+        note: Option<String>,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+    /// Mark a finding as deliberately left in place
+    Wontfix {
+        /// Fingerprint shown by `findings list`
+        fingerprint: String,
+        /// Why it stays
+        #[arg(long)]
+        note: Option<String>,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+    /// Reopen an acknowledged or wontfix finding
+    Reopen {
+        /// Fingerprint shown by `findings list`
+        fingerprint: String,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DbAction {
+    /// Copy the database to a new file, safely while other processes use it
+    Backup {
+        /// Path of the backup; must not exist yet
+        path: PathBuf,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+    /// Replace the contents of the database with a backup
+    Restore {
+        /// Path of a backup made by `db backup`
+        path: PathBuf,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+    /// Check the database for corruption with SQLite's integrity check
+    Check {
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+    /// List the schema migrations applied to the database and the version this build expects
+    Migrations {
+        /// Output format: text or json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StorageAction {
+    /// Report the size of the stored scans and what message compression saves
+    Stats {
+        /// Output format: text or json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TokenAction {
+    /// Create a token and print it; it can't be shown again
+    Create {
+        /// Name of the token, e.g. the dashboard or CI job using it
+        name: String,
+        /// read: the feed and metrics; scan: also trigger scans
+        #[arg(long, default_value = "read")]
+        scope: TokenScope,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+    /// List the tokens, without the tokens themselves
+    List {
+        /// Output format: text or json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+    /// Revoke a token; servers refuse it from the next request
+    Revoke {
+        /// Name of the token
+        name: String,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum IncrementalAction {
     /// Show incremental scan status
@@ -285,15 +901,33 @@ pub enum DistributedAction {
         /// Number of workers
         #[arg(short, long, default_value = "4")]
         workers: usize,
+        /// Hand work to the worker at this URL instead of simulating workers (repeatable)
+        #[arg(long = "worker-endpoint", value_name = "URL")]
+        worker_endpoints: Vec<String>,
+        /// Discover workers from this DNS SRV record (e.g. _code-guardian._tcp.example.com)
+        #[arg(long, value_name = "NAME", conflicts_with = "worker_endpoints")]
+        discover_srv: Option<String>,
         /// Batch size per worker
         #[arg(short, long, default_value = "50")]
         batch_size: usize,
+        /// POST a signed JSON summary to this URL when the scan completes (repeatable)
+        #[arg(long = "webhook", value_name = "URL")]
+        webhooks: Vec<String>,
+        /// Present this PEM client certificate to workers requiring mutual TLS
+        #[arg(long, value_name = "PEM", requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+        /// PEM private key of --tls-cert
+        #[arg(long, value_name = "PEM", requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+        /// Trust workers' certificates signed by these PEM CAs instead of the system's
+        #[arg(long, value_name = "PEM")]
+        tls_ca: Option<PathBuf>,
     },
 }
 
 #[derive(Subcommand)]
 pub enum StackPreset {
-    /// Web frontend (JavaScript, TypeScript, React, Vue, etc.)
+    /// Web frontend (JavaScript, TypeScript, React, Vue, etc.), with accessibility checks
     Web {
         /// Path to scan
         #[arg(default_value = ".")]
@@ -361,3 +995,84 @@ pub enum GitAction {
         path: PathBuf,
     },
 }
+
+#[derive(Subcommand)]
+pub enum GitLabAction {
+    /// Start a discussion on a merge request listing the findings its scan
+    /// added over the scan of the target branch; needs GITLAB_TOKEN
+    Decorate {
+        /// Merge request IID, the number shown as !<iid>
+        #[arg(long, env = "CI_MERGE_REQUEST_IID")]
+        mr: u64,
+        /// Scan of the merge request (default: the latest scan)
+        #[arg(long)]
+        scan: Option<i64>,
+        /// Scan of the target branch (default: the previous scan of the same path)
+        #[arg(long)]
+        base: Option<i64>,
+        /// Project ID or path, e.g. group/project
+        #[arg(long, env = "CI_PROJECT_ID")]
+        project: String,
+        /// GitLab API URL
+        #[arg(long, env = "CI_API_V4_URL", default_value = DEFAULT_GITLAB_API_URL)]
+        api_url: String,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BitbucketAction {
+    /// Publish a scan as the Code Insights report of a commit, annotating
+    /// each finding; needs BITBUCKET_TOKEN
+    Report {
+        /// Scan to publish (default: the latest scan)
+        #[arg(long)]
+        scan: Option<i64>,
+        /// Commit the report belongs to
+        #[arg(long, env = "BITBUCKET_COMMIT")]
+        commit: String,
+        /// Workspace of the repository
+        #[arg(long, env = "BITBUCKET_WORKSPACE")]
+        workspace: String,
+        /// Repository slug
+        #[arg(long, env = "BITBUCKET_REPO_SLUG")]
+        repo: String,
+        /// Bitbucket API URL
+        #[arg(long, default_value = DEFAULT_BITBUCKET_API_URL)]
+        api_url: String,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum JiraAction {
+    /// Create a Jira issue for each finding of a scan at or above a severity,
+    /// or update the issue filed for it earlier; needs JIRA_API_TOKEN
+    Create {
+        /// Lowest severity to file issues for: info, low, medium, high, critical
+        #[arg(long, default_value = "critical")]
+        severity: Severity,
+        /// Scan whose findings to file (default: the latest scan)
+        #[arg(long)]
+        scan: Option<i64>,
+        /// Jira site URL, overriding `jira.url` of the config
+        #[arg(long)]
+        url: Option<String>,
+        /// Project key, overriding `jira.project` of the config
+        #[arg(long)]
+        project: Option<String>,
+        /// Label of the issues, overriding `jira.labels` of the config (repeatable)
+        #[arg(long = "label", value_name = "LABEL")]
+        labels: Vec<String>,
+        /// Config file with the `jira` section
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+        /// Database file path (optional, defaults to data/code-guardian.db)
+        #[arg(short, long)]
+        db: Option<PathBuf>,
+    },
+}
@@ -1,24 +1,51 @@
 use anyhow::Result;
 use clap::CommandFactory;
+use clap_complete::env::Shells;
 use clap_complete::generate;
 use clap_complete::Shell;
-use code_guardian_storage::ScanRepository;
+use code_guardian_core::config_validation::{validate_config, CliOverride, DiagnosticLevel};
+use code_guardian_core::health_server::{
+    start_server, DatabaseIntegrity, HealthState, TokenVerifier,
+};
+use code_guardian_core::schema::SchemaKind;
+use code_guardian_core::{
+    critical_findings, known_languages, new_findings, server_tls_config, CustomDetectorManager,
+    FeedScan, Icon, ScanFeed, ScanResult, TokenScope,
+};
+use code_guardian_storage::{
+    expected_schema_version, sqlite_version, ScanRepository, SchemaStatus, SqliteScanRepository,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use crate::benchmark;
-use crate::cli_definitions::{Cli, GitAction};
+use crate::cli_definitions::{
+    Cli, CliError, ConfigAction, DbAction, GitAction, StorageAction, TokenAction,
+};
 use crate::git_integration::GitIntegration;
+use crate::mcp_server::McpServer;
+use crate::report_handlers::REPORT_FORMATS;
 use crate::utils;
 
-/// Handle history command - show all scan history from database
-pub fn handle_history(db: Option<PathBuf>) -> Result<()> {
+/// Handle history command - show all scan history from database, or the
+/// scans of `project`
+pub fn handle_history(db: Option<PathBuf>, project: Option<String>) -> Result<()> {
     let db_path = utils::get_db_path(db);
     let repo = code_guardian_storage::SqliteScanRepository::new(&db_path)?;
-    let scans = repo.get_all_scans()?;
+    let scans = match &project {
+        Some(project) => repo.get_project_scans(project)?,
+        None => repo.get_all_scans()?,
+    };
 
     if scans.is_empty() {
-        println!("No scans found.");
+        match &project {
+            Some(project) => println!("No scans found for project '{}'.", project),
+            None => println!("No scans found."),
+        }
         return Ok(());
     }
 
@@ -27,26 +54,620 @@ pub fn handle_history(db: Option<PathBuf>) -> Result<()> {
         let id = scan.id.ok_or_else(|| anyhow::anyhow!("Scan missing ID"))?;
         let timestamp = chrono::DateTime::from_timestamp(scan.timestamp, 0)
             .ok_or_else(|| anyhow::anyhow!("Invalid timestamp: {}", scan.timestamp))?;
+        let statistics = repo
+            .get_statistics(id)?
+            .map(|statistics| match statistics.cache_hit_rate() {
+                Some(hit_rate) => {
+                    format!(", {} cache_hit_rate={:.1}%", statistics, hit_rate * 100.0)
+                }
+                None => format!(", {}", statistics),
+            })
+            .unwrap_or_default();
+        let ci_run = repo
+            .get_ci_run(id)?
+            .map(|ci_run| format!(", CI: {}", ci_run))
+            .unwrap_or_default();
+        let quarantine = repo
+            .get_quarantine(id)?
+            .map(|reason| format!(", Quarantined: {}", reason))
+            .unwrap_or_default();
+        let project = scan
+            .project
+            .as_ref()
+            .map(|project| format!(", Project: {}", project))
+            .unwrap_or_default();
         println!(
-            "ID: {}, Timestamp: {}, Path: {}",
+            "ID: {}, Timestamp: {}, Path: {}{}{}{}{}",
             id,
             timestamp.format("%Y-%m-%d %H:%M:%S"),
-            scan.root_path
+            scan.root_path,
+            project,
+            statistics,
+            ci_run,
+            quarantine
         );
     }
     Ok(())
 }
 
+/// Handle db commands - back up, restore and check the findings database
+pub fn handle_db(action: DbAction) -> Result<()> {
+    let key = code_guardian_storage::database_key()?;
+    match action {
+        DbAction::Backup { path, db } => {
+            let db_path = utils::get_db_path(db);
+            code_guardian_storage::backup_database(&db_path, &path, key.as_deref())?;
+            println!(
+                "{} Backed up {} to {}",
+                Icon::Success,
+                db_path.display(),
+                path.display()
+            );
+        }
+        DbAction::Restore { path, db } => {
+            let db_path = utils::get_db_path(db);
+            code_guardian_storage::restore_database(&path, &db_path, key.as_deref())?;
+            // Brings a backup of an older version up to the current schema
+            let repo = SqliteScanRepository::with_key(&db_path, key.as_deref())?;
+            println!(
+                "{} Restored {} from {} ({} scans)",
+                Icon::Success,
+                db_path.display(),
+                path.display(),
+                repo.get_all_scans()?.len()
+            );
+        }
+        DbAction::Check { db } => {
+            let db_path = utils::get_db_path(db);
+            let problems =
+                SqliteScanRepository::with_key(&db_path, key.as_deref())?.integrity_check()?;
+            if !problems.is_empty() {
+                for problem in &problems {
+                    eprintln!("{}", problem);
+                }
+                return Err(anyhow::anyhow!(
+                    "{}: integrity check found {} problem(s); restore a backup with `code-guardian db restore`",
+                    db_path.display(),
+                    problems.len()
+                ));
+            }
+            println!(
+                "{} {}: integrity check passed",
+                Icon::Success,
+                db_path.display()
+            );
+        }
+        DbAction::Migrations { format, db } => {
+            let status =
+                SqliteScanRepository::schema_status(utils::get_db_path(db), key.as_deref())?;
+            match format.as_str() {
+                "json" => println!("{}", serde_json::to_string_pretty(&status)?),
+                "text" => print_schema_status(&status),
+                _ => {
+                    return Err(CliError::Usage(format!(
+                        "Unsupported format: {}. Supported: text, json",
+                        format
+                    ))
+                    .into())
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_schema_status(status: &SchemaStatus) {
+    println!(
+        "Schema version: {} (this build expects {})",
+        status
+            .applied_version
+            .map_or("none".to_string(), |version| version.to_string()),
+        status.expected_version
+    );
+    for migration in &status.migrations {
+        let state = match (&migration.applied_on, migration.known) {
+            (Some(applied_on), true) => format!("applied {}", applied_on),
+            (Some(applied_on), false) => {
+                format!("applied {}, unknown to this build", applied_on)
+            }
+            (None, _) => "pending".to_string(),
+        };
+        println!(
+            "  V{:<3} {:<28} {}",
+            migration.version, migration.name, state
+        );
+    }
+    if status.is_newer() {
+        println!(
+            "{} A newer code-guardian migrated this database; this build will not open it",
+            Icon::Warning
+        );
+    } else if status.pending() > 0 {
+        println!(
+            "{} migration(s) will be applied the next time the database is opened",
+            status.pending()
+        );
+    }
+}
+
+/// Handle storage commands - report database size and compression savings
+pub fn handle_storage(action: StorageAction) -> Result<()> {
+    match action {
+        StorageAction::Stats { format, db } => {
+            let repo = SqliteScanRepository::new(utils::get_db_path(db))?;
+            let stats = repo.storage_stats()?;
+            match format.as_str() {
+                "json" => println!("{}", serde_json::to_string_pretty(&stats)?),
+                "text" => {
+                    println!("Scans: {}", stats.scans);
+                    println!("Matches: {}", stats.matches);
+                    println!(
+                        "Compressed scans: {} (at least {} matches)",
+                        stats.compressed_scans,
+                        code_guardian_storage::COMPRESS_MIN_MATCHES
+                    );
+                    println!(
+                        "Messages: {} as text, {} stored ({:.1}x, {} saved)",
+                        utils::format_bytes(stats.message_bytes),
+                        utils::format_bytes(stats.stored_message_bytes),
+                        stats.compression_ratio(),
+                        utils::format_bytes(stats.saved_bytes())
+                    );
+                    println!("Database: {}", utils::format_bytes(stats.database_bytes));
+                }
+                _ => {
+                    return Err(CliError::Usage(format!(
+                        "Unsupported format: {}. Supported: text, json",
+                        format
+                    ))
+                    .into())
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
 /// Handle shell completion generation
-pub fn handle_completion(shell: Shell) -> Result<()> {
+/// Handle `completion`: a static completion script for `shell`, one calling
+/// back into this binary for dynamic values, or man pages written to `man`
+pub fn handle_completion(shell: Option<Shell>, dynamic: bool, man: Option<&Path>) -> Result<()> {
     let mut cmd = Cli::command();
     let bin_name = cmd.get_name().to_string();
+    if let Some(dir) = man {
+        std::fs::create_dir_all(dir)?;
+        clap_mangen::generate_to(cmd, dir)?;
+        println!("{} Wrote man pages to {}", Icon::Success, dir.display());
+        return Ok(());
+    }
+    let Some(shell) = shell else {
+        return Err(CliError::Usage("a shell or --man is required".to_string()).into());
+    };
+    if dynamic {
+        // The script runs the binary as it was invoked, with COMPLETE set
+        let bin = std::env::args().next().unwrap_or_else(|| bin_name.clone());
+        let shells = Shells::builtins();
+        let completer = shells
+            .completer(&shell.to_string())
+            .ok_or_else(|| CliError::Usage(format!("no dynamic completion for {}", shell)))?;
+        completer.write_registration("COMPLETE", &bin_name, &bin, &bin, &mut io::stdout())?;
+        return Ok(());
+    }
     generate(shell, &mut cmd, bin_name, &mut io::stdout());
     Ok(())
 }
 
+/// Handle config management commands
+pub fn handle_config(action: ConfigAction) -> Result<()> {
+    match action {
+        ConfigAction::Validate {
+            config,
+            custom_detectors,
+            db,
+            cache_size,
+            batch_size,
+            max_file_size,
+            max_threads,
+        } => {
+            let mut overrides = Vec::new();
+            if let Some(db) = db {
+                overrides.push(CliOverride {
+                    key: "database_path",
+                    flag: "--db",
+                    value: db.to_string_lossy().into(),
+                });
+            }
+            for (key, flag, value) in [
+                ("cache_size", "--cache-size", cache_size),
+                ("batch_size", "--batch-size", batch_size),
+                ("max_file_size", "--max-file-size", max_file_size),
+                ("max_threads", "--max-threads", max_threads),
+            ] {
+                if let Some(value) = value {
+                    overrides.push(CliOverride {
+                        key,
+                        flag,
+                        value: value.into(),
+                    });
+                }
+            }
+
+            let env = std::env::vars().collect();
+            let validation = validate_config(
+                config.as_deref(),
+                &env,
+                &overrides,
+                custom_detectors.as_deref(),
+            );
+
+            println!("Effective configuration:");
+            for (key, value, source) in validation.effective_values() {
+                println!("   {} = {}  ({})", key, value, source);
+            }
+
+            let mut errors = 0;
+            for diagnostic in &validation.diagnostics {
+                match diagnostic.level {
+                    DiagnosticLevel::Error => {
+                        errors += 1;
+                        println!("{} error: {}", Icon::Failure, diagnostic.message);
+                    }
+                    DiagnosticLevel::Warning => {
+                        println!("{} warning: {}", Icon::Warning, diagnostic.message)
+                    }
+                }
+            }
+
+            if errors > 0 {
+                return Err(
+                    CliError::Usage(format!("Configuration has {} error(s)", errors)).into(),
+                );
+            }
+            println!(
+                "{} Configuration is valid ({} warning(s))",
+                Icon::Success,
+                validation.diagnostics.len()
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Handle `mcp`: serve MCP requests on stdin/stdout until stdin closes
+pub fn handle_mcp(root: &Path, db: Option<PathBuf>, profile: String) -> Result<()> {
+    let server = McpServer::new(root, utils::get_db_path(db), profile)
+        .map_err(|e| CliError::Usage(format!("{:#}", e)))?;
+    server.run(io::stdin().lock(), io::stdout().lock())
+}
+
+/// Handle `serve`: the health endpoints, the Atom feed of the database's
+/// scans and scans of `root` requested with a token, until the process is
+/// stopped. Given a certificate and key, serves https.
+pub async fn handle_serve(
+    port: u16,
+    db: Option<PathBuf>,
+    feed_scans: usize,
+    root: &Path,
+    profile: String,
+    tls: Option<(PathBuf, PathBuf)>,
+) -> Result<()> {
+    let tls = match tls {
+        Some((cert, key)) => Some(Arc::new(
+            server_tls_config(&cert, &key).map_err(|e| CliError::Usage(e.to_string()))?,
+        )),
+        None => None,
+    };
+    let db_path = utils::get_db_path(db);
+    let scans = McpServer::new(root, db_path.clone(), profile)
+        .map_err(|e| CliError::Usage(format!("{:#}", e)))?;
+    let feed = Arc::new(DatabaseFeed {
+        db_path,
+        limit: feed_scans,
+        integrity: Mutex::new(None),
+    });
+    if !feed.has_tokens()? {
+        println!(
+            "{} No API tokens: /metrics and /feed.atom are open and POST /scans is refused; create one with `code-guardian token create`",
+            Icon::Warning
+        );
+    }
+    println!(
+        "{} Serving /health, /ready, /live, /metrics, /feed.atom and POST /scans over {} on port {}",
+        Icon::Network,
+        if tls.is_some() { "https" } else { "http" },
+        port
+    );
+    let state = HealthState {
+        feed: Some(feed.clone()),
+        database: Some(feed.clone()),
+        tokens: Some(feed),
+        scans: Some(Arc::new(scans)),
+        tls,
+        ..HealthState::default()
+    };
+    start_server(port, state)
+        .await
+        .map_err(|e| anyhow::anyhow!("{}", e))
+}
+
+/// Handle token commands - create, list and revoke the tokens of `serve`
+pub fn handle_token(action: TokenAction) -> Result<()> {
+    match action {
+        TokenAction::Create { name, scope, db } => {
+            let repo = SqliteScanRepository::new(utils::get_db_path(db))?;
+            let token = repo.create_api_token(&name, scope)?;
+            println!("{} Created {} token '{}':", Icon::Success, scope, name);
+            println!("{}", token);
+            eprintln!("Store it now; it can't be shown again.");
+        }
+        TokenAction::List { format, db } => {
+            let tokens = SqliteScanRepository::new(utils::get_db_path(db))?.api_tokens()?;
+            match format.as_str() {
+                "json" => println!("{}", serde_json::to_string_pretty(&tokens)?),
+                "text" if tokens.is_empty() => println!("No tokens."),
+                "text" => {
+                    for token in &tokens {
+                        let created = chrono::DateTime::from_timestamp(token.created_at, 0)
+                            .map(|created| created.format("%Y-%m-%d %H:%M:%S").to_string())
+                            .unwrap_or_default();
+                        println!("{}  {}  created {}", token.name, token.scope, created);
+                    }
+                }
+                _ => {
+                    return Err(CliError::Usage(format!(
+                        "Unsupported format: {}. Supported: text, json",
+                        format
+                    ))
+                    .into())
+                }
+            }
+        }
+        TokenAction::Revoke { name, db } => {
+            if !SqliteScanRepository::new(utils::get_db_path(db))?.revoke_api_token(&name)? {
+                return Err(CliError::Usage(format!("no token called '{}'", name)).into());
+            }
+            println!("{} Revoked token '{}'", Icon::Success, name);
+        }
+    }
+    Ok(())
+}
+
+/// How long the result of an integrity check of the served database is
+/// reused; a full check reads the whole file
+const INTEGRITY_CHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+/// The `limit` most recent scans of a database, each with the Critical
+/// findings the previous scan of its root did not have
+struct DatabaseFeed {
+    db_path: PathBuf,
+    limit: usize,
+    /// Time and result of the last integrity check
+    integrity: Mutex<Option<(Instant, Vec<String>)>>,
+}
+
+impl DatabaseIntegrity for DatabaseFeed {
+    fn integrity_problems(&self) -> ScanResult<Vec<String>> {
+        let mut last = self.integrity.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((checked, problems)) = last.as_ref() {
+            if checked.elapsed() < INTEGRITY_CHECK_INTERVAL {
+                return Ok(problems.clone());
+            }
+        }
+        let problems = SqliteScanRepository::new(&self.db_path)?.integrity_check()?;
+        *last = Some((Instant::now(), problems.clone()));
+        Ok(problems)
+    }
+}
+
+impl TokenVerifier for DatabaseFeed {
+    fn has_tokens(&self) -> ScanResult<bool> {
+        Ok(!SqliteScanRepository::new(&self.db_path)?
+            .api_tokens()?
+            .is_empty())
+    }
+
+    fn verify(&self, token: &str) -> ScanResult<Option<TokenScope>> {
+        SqliteScanRepository::new(&self.db_path)?.verify_api_token(token)
+    }
+}
+
+impl ScanFeed for DatabaseFeed {
+    fn recent_scans(&self) -> ScanResult<Vec<FeedScan>> {
+        let repo = SqliteScanRepository::new(&self.db_path)?;
+        // Newest first, without matches
+        let mut scans = repo.get_all_scans()?;
+        scans.sort_by_key(|scan| std::cmp::Reverse((scan.timestamp, scan.id)));
+        let mut feed = Vec::new();
+        for (i, summary) in scans.iter().take(self.limit).enumerate() {
+            let Some(scan) = repo.get_scan(summary.id.unwrap_or_default())? else {
+                continue;
+            };
+            let previous = scans[i + 1..]
+                .iter()
+                .find(|previous| {
+                    previous.root_path == scan.root_path && previous.project == scan.project
+                })
+                .and_then(|previous| previous.id);
+            let added = match previous {
+                Some(id) => match repo.get_scan(id)? {
+                    Some(base) => new_findings(
+                        &scan.matches,
+                        &scan.root_path,
+                        &base.matches,
+                        &base.root_path,
+                    ),
+                    None => scan.matches.clone(),
+                },
+                None => scan.matches.clone(),
+            };
+            feed.push(FeedScan {
+                id: summary.id.unwrap_or_default(),
+                timestamp: scan.timestamp,
+                findings: scan.matches.len(),
+                new_critical: critical_findings(&added),
+                root_path: scan.root_path,
+            });
+        }
+        Ok(feed)
+    }
+}
+
+/// What `info` reports about this build
+#[derive(Debug, Serialize)]
+struct BuildInfo {
+    version: &'static str,
+    /// Cargo features this binary was built with
+    features: Vec<&'static str>,
+    /// Every built-in detector, by name
+    detectors: Vec<String>,
+    /// Detectors of each `--profile`
+    profiles: BTreeMap<&'static str, Vec<String>>,
+    /// File name globs of each language the inventory and coverage recognize
+    languages: BTreeMap<&'static str, Vec<String>>,
+    report_formats: &'static [&'static str],
+    storage: StorageInfo,
+}
+
+#[derive(Debug, Serialize)]
+struct StorageInfo {
+    backend: &'static str,
+    sqlite_version: &'static str,
+    /// Schema version databases are migrated to
+    schema_version: u32,
+    encryption: bool,
+}
+
+/// Cargo features of this binary
+fn enabled_features() -> Vec<&'static str> {
+    [
+        ("git", cfg!(feature = "git")),
+        ("gcs", cfg!(feature = "gcs")),
+        ("azure", cfg!(feature = "azure")),
+        ("encryption", cfg!(feature = "encryption")),
+        ("keychain", cfg!(feature = "keychain")),
+    ]
+    .into_iter()
+    .filter_map(|(feature, enabled)| enabled.then_some(feature))
+    .collect()
+}
+
+fn build_info() -> BuildInfo {
+    let profiles: BTreeMap<&'static str, Vec<String>> = utils::DETECTOR_PROFILES
+        .iter()
+        .map(|profile| {
+            let mut names: Vec<String> = utils::get_detectors_from_profile(profile)
+                .iter()
+                .map(|detector| detector.name().to_string())
+                .collect();
+            names.sort();
+            names.dedup();
+            (*profile, names)
+        })
+        .collect();
+    let detectors = profiles
+        .values()
+        .flatten()
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        features: enabled_features(),
+        detectors,
+        profiles,
+        languages: known_languages(),
+        report_formats: REPORT_FORMATS,
+        storage: StorageInfo {
+            backend: "sqlite",
+            sqlite_version: sqlite_version(),
+            schema_version: expected_schema_version(),
+            encryption: cfg!(feature = "encryption"),
+        },
+    }
+}
+
+/// Handle `info`: the capabilities of this build, for people and for tools
+/// adapting to the installed binary
+pub fn handle_info(json: bool) -> Result<()> {
+    let info = build_info();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(());
+    }
+    println!("code-guardian {}", info.version);
+    println!(
+        "Features: {}",
+        if info.features.is_empty() {
+            "none".to_string()
+        } else {
+            info.features.join(", ")
+        }
+    );
+    println!(
+        "Storage: SQLite {}, schema version {}{}",
+        info.storage.sqlite_version,
+        info.storage.schema_version,
+        if info.storage.encryption {
+            ", encryption available"
+        } else {
+            ""
+        }
+    );
+    println!("Report formats: {}", info.report_formats.join(", "));
+    println!(
+        "Languages ({}): {}",
+        info.languages.len(),
+        info.languages
+            .keys()
+            .copied()
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    println!("Detectors: {}, per profile:", info.detectors.len());
+    for (profile, detectors) in &info.profiles {
+        println!("  {:<24} {}", profile, detectors.len());
+    }
+    Ok(())
+}
+
+/// Handle JSON Schema printing
+pub fn handle_schema(kind: &str) -> Result<()> {
+    let kind = SchemaKind::from_name(kind).map_err(|e| CliError::Usage(e.to_string()))?;
+    println!("{}", kind.schema_json());
+    Ok(())
+}
+
 /// Handle benchmark command
 pub fn handle_benchmark(path: Option<PathBuf>, quick: bool) -> Result<()> {
+    let benchmark_path = resolve_benchmark_path(path)?;
+
+    if quick {
+        benchmark::quick_performance_test(&benchmark_path)
+    } else {
+        benchmark::run_benchmark(&benchmark_path)
+    }
+}
+
+/// Handle `benchmark --per-detector`
+pub fn handle_detector_benchmark(
+    path: Option<PathBuf>,
+    profile: &str,
+    custom_detectors: Option<PathBuf>,
+) -> Result<()> {
+    let benchmark_path = resolve_benchmark_path(path)?;
+
+    let mut detectors = utils::get_detectors_from_profile(profile);
+    if let Some(custom_path) = custom_detectors {
+        let mut manager = CustomDetectorManager::new();
+        manager.load_from_file(&custom_path)?;
+        detectors.extend(manager.get_detectors());
+    }
+
+    benchmark::per_detector_benchmark(&benchmark_path, detectors)
+}
+
+fn resolve_benchmark_path(path: Option<PathBuf>) -> Result<PathBuf> {
     let benchmark_path = match path {
         Some(p) => p,
         None => std::env::current_dir()
@@ -60,12 +681,7 @@ pub fn handle_benchmark(path: Option<PathBuf>, quick: bool) -> Result<()> {
             benchmark_path.display()
         ));
     }
-
-    if quick {
-        benchmark::quick_performance_test(&benchmark_path)
-    } else {
-        benchmark::run_benchmark(&benchmark_path)
-    }
+    Ok(benchmark_path)
 }
 
 // These functions are re-exported from advanced_handlers
@@ -77,11 +693,12 @@ pub use crate::advanced_handlers::{
 pub fn handle_git(action: GitAction) -> Result<()> {
     match action {
         GitAction::InstallHook { path } => {
-            println!("🔧 Installing Code-Guardian pre-commit hook...");
+            println!("{} Installing Code-Guardian pre-commit hook...", Icon::Tool);
 
             if !GitIntegration::is_git_repo(&path) {
                 return Err(anyhow::anyhow!(
-                    "❌ Error: {} is not a git repository",
+                    "{} Error: {} is not a git repository",
+                    Icon::Failure,
                     path.display()
                 ));
             }
@@ -89,16 +706,26 @@ pub fn handle_git(action: GitAction) -> Result<()> {
             let repo_root = GitIntegration::get_repo_root(&path)?;
             GitIntegration::install_pre_commit_hook(&repo_root)?;
 
-            println!("💡 Usage: The hook will automatically run on 'git commit'");
-            println!("💡 Manual run: code-guardian pre-commit --staged-only --fast");
+            println!(
+                "{} Usage: The hook will automatically run on 'git commit'",
+                Icon::Tip
+            );
+            println!(
+                "{} Manual run: code-guardian pre-commit --staged-only --fast",
+                Icon::Tip
+            );
             Ok(())
         }
         GitAction::UninstallHook { path } => {
-            println!("🗑️  Uninstalling Code-Guardian pre-commit hook...");
+            println!(
+                "{} Uninstalling Code-Guardian pre-commit hook...",
+                Icon::Delete
+            );
 
             if !GitIntegration::is_git_repo(&path) {
                 return Err(anyhow::anyhow!(
-                    "❌ Error: {} is not a git repository",
+                    "{} Error: {} is not a git repository",
+                    Icon::Failure,
                     path.display()
                 ));
             }
@@ -108,11 +735,12 @@ pub fn handle_git(action: GitAction) -> Result<()> {
             Ok(())
         }
         GitAction::Staged { path } => {
-            println!("📋 Listing staged files...");
+            println!("{} Listing staged files...", Icon::List);
 
             if !GitIntegration::is_git_repo(&path) {
                 return Err(anyhow::anyhow!(
-                    "❌ Error: {} is not a git repository",
+                    "{} Error: {} is not a git repository",
+                    Icon::Failure,
                     path.display()
                 ));
             }
@@ -121,9 +749,13 @@ pub fn handle_git(action: GitAction) -> Result<()> {
             let staged_files = GitIntegration::get_staged_files(&repo_root)?;
 
             if staged_files.is_empty() {
-                println!("ℹ️  No staged files found.");
+                println!("{} No staged files found.", Icon::Info);
             } else {
-                println!("🔍 Found {} staged file(s):", staged_files.len());
+                println!(
+                    "{} Found {} staged file(s):",
+                    Icon::Search,
+                    staged_files.len()
+                );
                 for (i, file) in staged_files.iter().enumerate() {
                     println!("  {}. {}", i + 1, file.display());
                 }
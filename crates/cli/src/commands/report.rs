@@ -2,6 +2,7 @@
 
 use anyhow::Result;
 use std::path::PathBuf;
+use code_guardian_core::Icon;
 use code_guardian_storage::SqliteStorage;
 use code_guardian_output::formatters::{JsonFormatter, TextFormatter, HtmlFormatter, MarkdownFormatter};
 use crate::utils::get_db_path;
@@ -70,7 +71,7 @@ impl ReportCommand {
 
         if let Some(output_path) = &self.output {
             std::fs::write(output_path, formatted_output)?;
-            println!("📄 Report written to {}", output_path.display());
+            println!("{} Report written to {}", Icon::File, output_path.display());
         } else {
             println!("{}", formatted_output);
         }
@@ -103,7 +104,7 @@ impl HistoryCommand {
             return Ok(());
         }
 
-        println!("📊 Scan History:");
+        println!("{} Scan History:", Icon::Stats);
         println!("{:<5} {:<20} {:<15} {:<10}", "ID", "Timestamp", "Path", "Issues");
         println!("{}", "-".repeat(60));
 
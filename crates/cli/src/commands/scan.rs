@@ -2,7 +2,7 @@
 
 use anyhow::Result;
 use std::path::PathBuf;
-use code_guardian_core::{Scanner, ScanOptions as CoreScanOptions};
+use code_guardian_core::{Icon, Scanner, ScanOptions as CoreScanOptions};
 use crate::utils::{get_db_path, get_detectors_from_profile};
 
 #[derive(Debug, Clone)]
@@ -75,7 +75,7 @@ impl ScanCommand {
         let results = scanner.scan_path(&self.path).await?;
 
         if self.show_metrics {
-            println!("📊 Scan Metrics:");
+            println!("{} Scan Metrics:", Icon::Stats);
             println!("  Files scanned: {}", results.metrics.files_scanned);
             println!("  Issues found: {}", results.issues.len());
             println!("  Scan duration: {:?}", results.metrics.scan_duration);
@@ -85,7 +85,7 @@ impl ScanCommand {
         let storage = code_guardian_storage::SqliteStorage::new(&db_path)?;
         storage.store_scan_results(&results).await?;
 
-        println!("✅ Scan completed successfully");
+        println!("{} Scan completed successfully", Icon::Success);
         Ok(())
     }
 }
@@ -1,6 +1,6 @@
 use anyhow::Result;
 use code_guardian_core::Match;
-use code_guardian_storage::{Scan, ScanRepository, SqliteScanRepository};
+use code_guardian_storage::{Scan, ScanRepository, SqliteScanRepository, StateTransition};
 use std::path::PathBuf;
 
 use crate::report_handlers::get_formatter;
@@ -16,12 +16,48 @@ pub fn handle_compare(id1: i64, id2: i64, format: String, db: Option<PathBuf>) -
         (Some(s1), Some(s2)) => {
             let diff = compare_scans(&s1, &s2);
             println!("{}", formatter.format(&diff));
+            let (earlier, later) = if s1.id <= s2.id {
+                (&s1, &s2)
+            } else {
+                (&s2, &s1)
+            };
+            let transitions = repo.get_transitions(earlier, later)?;
+            // On stderr so the formatted diff can still be piped
+            if !transitions.is_empty() {
+                eprintln!(
+                    "State changes from scan {} to scan {}:",
+                    earlier.id.unwrap_or_default(),
+                    later.id.unwrap_or_default()
+                );
+                for transition in &transitions {
+                    eprintln!("  {}", format_transition(transition));
+                }
+            }
         }
         _ => println!("One or both scans not found."),
     }
     Ok(())
 }
 
+/// One line describing a state change, e.g.
+/// `3f2a… TODO src/main.rs: open -> fixed (scan 4)`
+pub fn format_transition(transition: &StateTransition) -> String {
+    let cause = match (transition.scan_id, &transition.note) {
+        (Some(scan_id), _) => format!("scan {}", scan_id),
+        (None, Some(note)) => format!("set by hand: {}", note),
+        (None, None) => "set by hand".to_string(),
+    };
+    format!(
+        "{} {} {}: {} -> {} ({})",
+        transition.fingerprint,
+        transition.pattern,
+        transition.file_path,
+        transition.from.map_or("new", |state| state.as_str()),
+        transition.to,
+        cause
+    )
+}
+
 pub fn compare_scans(scan1: &Scan, scan2: &Scan) -> Vec<Match> {
     // Simple diff: matches in scan2 not in scan1
     // For simplicity, assume matches are unique by file_path, line_number, pattern
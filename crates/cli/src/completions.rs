@@ -0,0 +1,67 @@
+//! Values shells complete by calling back into the binary: profile names,
+//! the targets of `code-guardian.toml` and scan IDs from the database.
+//! See [`handle_completion`](crate::command_handlers::handle_completion).
+
+use clap_complete::CompletionCandidate;
+use code_guardian_core::config::load_config;
+use code_guardian_storage::{ScanRepository, SqliteScanRepository};
+
+use crate::utils;
+
+/// Scans offered for scan ID arguments, most recent first
+const MAX_SCAN_CANDIDATES: usize = 50;
+
+/// Config file whose targets `--target` completes
+const DEFAULT_CONFIG_FILE: &str = "code-guardian.toml";
+
+/// The detector profiles of `--profile`
+pub fn profile_candidates() -> Vec<CompletionCandidate> {
+    utils::DETECTOR_PROFILES
+        .iter()
+        .map(|profile| CompletionCandidate::new(*profile))
+        .collect()
+}
+
+/// The `[targets.<NAME>]` of `code-guardian.toml` in the current directory
+pub fn target_candidates() -> Vec<CompletionCandidate> {
+    load_config(Some(DEFAULT_CONFIG_FILE))
+        .map(|config| {
+            config
+                .targets
+                .into_iter()
+                .map(|(name, target)| {
+                    CompletionCandidate::new(name)
+                        .help(Some(target.path.display().to_string().into()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The most recent scans of the default database, with their time and root
+/// as help. Nothing is offered when there is no database, so completing
+/// never creates one.
+pub fn scan_id_candidates() -> Vec<CompletionCandidate> {
+    let db_path = utils::get_db_path(None);
+    if !db_path.is_file() {
+        return Vec::new();
+    }
+    let Ok(repo) = SqliteScanRepository::new(&db_path) else {
+        return Vec::new();
+    };
+    repo.get_all_scans()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|scan| Some((scan.id?, scan)))
+        .take(MAX_SCAN_CANDIDATES)
+        .enumerate()
+        .map(|(order, (id, scan))| {
+            let time = chrono::DateTime::from_timestamp(scan.timestamp, 0)
+                .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            CompletionCandidate::new(id.to_string())
+                .help(Some(format!("{} {}", time, scan.root_path).into()))
+                .display_order(Some(order))
+        })
+        .collect()
+}
@@ -0,0 +1,84 @@
+use anyhow::Result;
+use code_guardian_core::FindingState;
+use code_guardian_storage::{ScanRepository, SqliteScanRepository};
+use std::path::PathBuf;
+
+use crate::cli_definitions::{CliError, FindingsAction};
+use crate::utils::get_db_path;
+
+pub fn handle_findings(action: FindingsAction) -> Result<()> {
+    match action {
+        FindingsAction::List {
+            root,
+            state,
+            format,
+            db,
+        } => {
+            let repo = SqliteScanRepository::new(get_db_path(db))?;
+            let findings = repo.get_findings(root.as_deref(), state)?;
+            match format.as_str() {
+                "json" => println!("{}", serde_json::to_string_pretty(&findings)?),
+                "text" => {
+                    if findings.is_empty() {
+                        println!("No tracked findings.");
+                    }
+                    for finding in &findings {
+                        println!(
+                            "{} {:<12} {} {}:{} {}{}",
+                            finding.fingerprint,
+                            finding.state.as_str(),
+                            finding.pattern,
+                            finding.file_path,
+                            finding.line_number,
+                            finding.message,
+                            finding
+                                .issue_key
+                                .as_ref()
+                                .map(|key| format!(" [{}]", key))
+                                .unwrap_or_default()
+                        );
+                    }
+                }
+                _ => {
+                    return Err(CliError::Usage(format!(
+                        "Unsupported format: {}. Supported: text, json",
+                        format
+                    ))
+                    .into())
+                }
+            }
+            Ok(())
+        }
+        FindingsAction::Ack {
+            fingerprint,
+            note,
+            db,
+        } => set_state(db, &fingerprint, FindingState::Acknowledged, note),
+        FindingsAction::Wontfix {
+            fingerprint,
+            note,
+            db,
+        } => set_state(db, &fingerprint, FindingState::WontFix, note),
+        FindingsAction::Reopen { fingerprint, db } => {
+            set_state(db, &fingerprint, FindingState::Open, None)
+        }
+    }
+}
+
+fn set_state(
+    db: Option<PathBuf>,
+    fingerprint: &str,
+    state: FindingState,
+    note: Option<String>,
+) -> Result<()> {
+    let mut repo = SqliteScanRepository::new(get_db_path(db))?;
+    if repo.set_finding_state(fingerprint, state, note.as_deref())? == 0 {
+        return Err(CliError::Usage(format!(
+            "No tracked finding with fingerprint '{}'; see `code-guardian findings list`",
+            fingerprint
+        ))
+        .into());
+    }
+    println!("Finding {} is now {}.", fingerprint, state);
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+use code_guardian_core::{scan_walker, OrphanTodoDetector, DEFAULT_ISSUE_REFERENCE_PATTERN};
+use colored::*;
+use std::path::PathBuf;
+
+use crate::cli_definitions::CliError;
+
+/// Handle the fix command
+pub fn handle_fix(
+    path: PathBuf,
+    add_issue_placeholder: Option<String>,
+    issue_pattern: Option<String>,
+    dry_run: bool,
+) -> Result<()> {
+    let Some(placeholder) = add_issue_placeholder else {
+        return Err(
+            CliError::Usage("No fix selected; pass --add-issue-placeholder".to_string()).into(),
+        );
+    };
+    if !path.exists() {
+        return Err(CliError::Usage(format!("Path '{}' does not exist", path.display())).into());
+    }
+
+    let pattern = issue_pattern
+        .as_deref()
+        .unwrap_or(DEFAULT_ISSUE_REFERENCE_PATTERN);
+    let detector = OrphanTodoDetector::new(pattern)
+        .map_err(|e| CliError::Usage(format!("Invalid issue pattern '{}': {}", pattern, e)))?;
+
+    let mut files_changed = 0;
+    let mut markers_changed = 0;
+    for entry in scan_walker(&path).build().flatten() {
+        if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+            continue;
+        }
+        // Binary and non-UTF-8 files are left untouched
+        let Ok(content) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let (fixed, count) = detector.add_issue_placeholder(&content, entry.path(), &placeholder);
+        if count == 0 {
+            continue;
+        }
+        if !dry_run {
+            std::fs::write(entry.path(), fixed)?;
+        }
+        println!("{}: {} marker(s)", entry.path().display(), count);
+        files_changed += 1;
+        markers_changed += count;
+    }
+
+    let verb = if dry_run { "Would add" } else { "Added" };
+    println!(
+        "{} {} placeholder to {} TODO/FIXME marker(s) in {} file(s)",
+        verb.green(),
+        placeholder,
+        markers_changed,
+        files_changed
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_handle_fix_adds_placeholder() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("lib.rs");
+        std::fs::write(&file, "// TODO: one\n// TODO(#2): two\n").unwrap();
+
+        handle_fix(
+            temp_dir.path().to_path_buf(),
+            Some("#TBD".to_string()),
+            None,
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "// TODO: one\n// TODO(#2): two\n"
+        );
+
+        handle_fix(
+            temp_dir.path().to_path_buf(),
+            Some("#TBD".to_string()),
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "// TODO(#TBD): one\n// TODO(#2): two\n"
+        );
+    }
+
+    #[test]
+    fn test_handle_fix_requires_a_fix() {
+        let temp_dir = TempDir::new().unwrap();
+        let err = handle_fix(temp_dir.path().to_path_buf(), None, None, false).unwrap_err();
+        assert!(err.downcast_ref::<CliError>().is_some());
+    }
+}
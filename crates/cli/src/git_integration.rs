@@ -1,4 +1,6 @@
 use anyhow::{anyhow, Result};
+use code_guardian_core::{Author, Icon, Match};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -107,8 +109,12 @@ exec code-guardian pre-commit --staged-only --fast
             std::fs::set_permissions(&hook_path, perms)?;
         }
 
-        println!("✅ Pre-commit hook installed at: {}", hook_path.display());
-        println!("🔧 The hook will run 'code-guardian pre-commit --staged-only --fast' before each commit");
+        println!(
+            "{} Pre-commit hook installed at: {}",
+            Icon::Success,
+            hook_path.display()
+        );
+        println!("{} The hook will run 'code-guardian pre-commit --staged-only --fast' before each commit", Icon::Tool);
 
         Ok(())
     }
@@ -122,19 +128,126 @@ exec code-guardian pre-commit --staged-only --fast
             let content = std::fs::read_to_string(&hook_path)?;
             if content.contains("Code-Guardian pre-commit hook") {
                 std::fs::remove_file(&hook_path)?;
-                println!("✅ Code-Guardian pre-commit hook removed");
+                println!("{} Code-Guardian pre-commit hook removed", Icon::Success);
             } else {
                 println!(
-                    "⚠️  Pre-commit hook exists but doesn't appear to be Code-Guardian's hook"
+                    "{} Pre-commit hook exists but doesn't appear to be Code-Guardian's hook",
+                    Icon::Warning
                 );
                 println!("   Manual removal required: {}", hook_path.display());
             }
         } else {
-            println!("ℹ️  No pre-commit hook found");
+            println!("{} No pre-commit hook found", Icon::Info);
         }
 
         Ok(())
     }
+
+    /// Blame a file, returning last-touch information keyed by 1-based line number
+    pub fn blame_file(file_path: &Path) -> Result<HashMap<usize, BlameInfo>> {
+        let file_name = file_path
+            .file_name()
+            .ok_or_else(|| anyhow!("Not a file: {}", file_path.display()))?;
+        let dir = match file_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+
+        let output = Command::new("git")
+            .arg("blame")
+            .arg("--line-porcelain")
+            .arg("--")
+            .arg(file_name)
+            .current_dir(dir)
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Git blame failed: {}", stderr));
+        }
+
+        Ok(parse_blame_porcelain(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+
+    /// Blame every match, running `git blame` once per file.
+    /// Files that are untracked or outside a repository yield `None`.
+    pub fn blame_matches(matches: &[Match]) -> Vec<Option<BlameInfo>> {
+        let mut blames: HashMap<&str, Option<HashMap<usize, BlameInfo>>> = HashMap::new();
+        matches
+            .iter()
+            .map(|m| {
+                blames
+                    .entry(m.file_path.as_str())
+                    .or_insert_with(|| Self::blame_file(Path::new(&m.file_path)).ok())
+                    .as_ref()
+                    .and_then(|lines| lines.get(&m.line_number).cloned())
+            })
+            .collect()
+    }
+}
+
+/// Last-touch information for a single line, from `git blame`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameInfo {
+    pub commit: String,
+    pub author: String,
+    pub author_email: String,
+    /// Author timestamp in seconds since the Unix epoch
+    pub author_time: i64,
+}
+
+impl BlameInfo {
+    /// Whole days between the author timestamp and `now`
+    pub fn age_days(&self, now: i64) -> i64 {
+        (now - self.author_time).max(0) / 86_400
+    }
+
+    /// The author of the line, as carried by findings
+    pub fn to_author(&self) -> Author {
+        Author {
+            name: self.author.clone(),
+            email: self.author_email.clone(),
+        }
+    }
+}
+
+/// Parse `git blame --line-porcelain` output into per-line blame information
+fn parse_blame_porcelain(output: &str) -> HashMap<usize, BlameInfo> {
+    let mut lines = HashMap::new();
+    let mut line_number = 0;
+    let mut current = BlameInfo {
+        commit: String::new(),
+        author: String::new(),
+        author_email: String::new(),
+        author_time: 0,
+    };
+
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            lines.insert(line_number, current.clone());
+        } else if let Some(author) = line.strip_prefix("author ") {
+            current.author = author.to_string();
+        } else if let Some(email) = line.strip_prefix("author-mail ") {
+            current.author_email = email.trim_matches(|c| c == '<' || c == '>').to_string();
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            current.author_time = time.parse().unwrap_or(0);
+        } else {
+            // Header line: <sha> <original line> <final line> [<group size>]
+            let mut parts = line.split(' ');
+            if let (Some(sha), Some(_), Some(final_line)) =
+                (parts.next(), parts.next(), parts.next())
+            {
+                if sha.len() == 40 && sha.chars().all(|c| c.is_ascii_hexdigit()) {
+                    current.commit = sha.to_string();
+                    line_number = final_line.parse().unwrap_or(0);
+                }
+            }
+        }
+    }
+
+    lines
 }
 
 /// Represents a staged change in git
@@ -250,6 +363,33 @@ mod tests {
         assert_eq!(change.removed_lines.len(), 0);
     }
 
+    #[test]
+    fn test_parse_blame_porcelain() {
+        let output = "\
+1111111111111111111111111111111111111111 1 1 1
+author Alice
+author-mail <alice@example.com>
+author-time 1600000000
+author-tz +0000
+summary Add TODO
+filename src/lib.rs
+\t// TODO: refactor
+2222222222222222222222222222222222222222 5 2 1
+author Bob
+author-mail <bob@example.com>
+author-time 1700000000
+filename src/lib.rs
+\tfn main() {}
+";
+        let blame = parse_blame_porcelain(output);
+        assert_eq!(blame.len(), 2);
+        assert_eq!(blame[&1].author, "Alice");
+        assert_eq!(blame[&1].author_email, "alice@example.com");
+        assert_eq!(blame[&2].commit, "2".repeat(40));
+        assert_eq!(blame[&2].author_time, 1_700_000_000);
+        assert_eq!(blame[&1].age_days(1_600_000_000 + 3 * 86_400 + 5), 3);
+    }
+
     #[test]
     fn test_is_git_repo() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,237 @@
+use anyhow::Result;
+use code_guardian_core::config::load_config;
+use code_guardian_core::{
+    finding_fingerprints, issue_fields, merge_request_note, new_findings, BitbucketClient,
+    EnhancedScanConfig, FindingState, GitLabClient, Icon, JiraClient, Match, Severity,
+    BITBUCKET_TOKEN_ENV, GITLAB_TOKEN_ENV, JIRA_EMAIL_ENV, JIRA_TOKEN_ENV,
+};
+use code_guardian_storage::{Scan, ScanRepository, SqliteScanRepository, TrackedFinding};
+use serde_json::json;
+use std::collections::HashMap;
+
+use crate::cli_definitions::{BitbucketAction, CliError, GitLabAction, JiraAction};
+use crate::utils::get_db_path;
+
+pub fn handle_gitlab(action: GitLabAction) -> Result<()> {
+    match action {
+        GitLabAction::Decorate {
+            mr,
+            scan,
+            base,
+            project,
+            api_url,
+            db,
+        } => {
+            let token = access_token(GITLAB_TOKEN_ENV, "a GitLab access token with the api scope")?;
+            let repo = SqliteScanRepository::new(get_db_path(db))?;
+            let (head, base) = branch_scans(&repo, scan, base)?;
+            let added = added_findings(&head, base.as_ref());
+            let head_id = head.id.unwrap_or_default();
+            if added.is_empty() {
+                println!(
+                    "{} No new findings in scan {}; nothing posted",
+                    Icon::Success,
+                    head_id
+                );
+                return Ok(());
+            }
+            let note = merge_request_note(&added, head_id, base.and_then(|base| base.id));
+            let discussion =
+                GitLabClient::new(api_url, project, token).post_discussion(mr, &note)?;
+            println!(
+                "{} Posted {} new finding{} to merge request !{} (discussion {})",
+                Icon::Network,
+                added.len(),
+                if added.len() == 1 { "" } else { "s" },
+                mr,
+                discussion
+            );
+            Ok(())
+        }
+    }
+}
+
+pub fn handle_bitbucket(action: BitbucketAction) -> Result<()> {
+    match action {
+        BitbucketAction::Report {
+            scan,
+            commit,
+            workspace,
+            repo,
+            api_url,
+            db,
+        } => {
+            let token = access_token(
+                BITBUCKET_TOKEN_ENV,
+                "a Bitbucket access token with the repository:write scope",
+            )?;
+            let repository = SqliteScanRepository::new(get_db_path(db))?;
+            let scan = scan_or_latest(&repository, scan)?;
+            let annotated = BitbucketClient::new(api_url, workspace, repo, token)
+                .publish_report(&commit, &scan.matches)?;
+            println!(
+                "{} Published scan {} as the Code Insights report of {} with {} annotations",
+                Icon::Network,
+                scan.id.unwrap_or_default(),
+                commit,
+                annotated
+            );
+            Ok(())
+        }
+    }
+}
+
+pub fn handle_jira(action: JiraAction) -> Result<()> {
+    match action {
+        JiraAction::Create {
+            severity,
+            scan,
+            url,
+            project,
+            labels,
+            config,
+            db,
+        } => {
+            let mut jira = load_config(config.as_ref())?.jira;
+            let url = url.or(jira.url).ok_or_else(|| {
+                CliError::Usage("set jira.url in the config or pass --url".to_string())
+            })?;
+            let project = project.or(jira.project).ok_or_else(|| {
+                CliError::Usage("set jira.project in the config or pass --project".to_string())
+            })?;
+            if !labels.is_empty() {
+                jira.labels = labels;
+            }
+            let token = access_token(JIRA_TOKEN_ENV, "a Jira API token")?;
+            let email = std::env::var(JIRA_EMAIL_ENV)
+                .ok()
+                .filter(|email| !email.is_empty());
+            let client = JiraClient::new(url, email.as_deref(), &token);
+
+            let mut repo = SqliteScanRepository::new(get_db_path(db))?;
+            let scan = scan_or_latest(&repo, scan)?;
+            let tracked: HashMap<String, TrackedFinding> = repo
+                .get_findings(Some(&scan.root_path), None)?
+                .into_iter()
+                .filter(|finding| finding.project == scan.project)
+                .map(|finding| (finding.fingerprint.clone(), finding))
+                .collect();
+            let defaults = EnhancedScanConfig::default();
+            let (mut created, mut updated) = (0, 0);
+            for (m, fingerprint) in scan
+                .matches
+                .iter()
+                .zip(finding_fingerprints(&scan.matches, &scan.root_path))
+            {
+                let finding_severity = m
+                    .severity
+                    .clone()
+                    .or_else(|| defaults.severity_levels.get(m.pattern.as_str()).cloned())
+                    .unwrap_or(Severity::Medium);
+                // Limit markers are not tracked, and fixed and wontfix findings need no issue
+                let Some(finding) = tracked.get(&fingerprint) else {
+                    continue;
+                };
+                if (finding_severity.clone() as u8) < (severity.clone() as u8)
+                    || matches!(finding.state, FindingState::Fixed | FindingState::WontFix)
+                {
+                    continue;
+                }
+                let mut fields = issue_fields(m, &finding_severity, &fingerprint, &jira.labels);
+                let location = format!("{} {}:{}", m.pattern, m.file_path, m.line_number);
+                match &finding.issue_key {
+                    Some(key) => {
+                        client.update_issue(key, &fields)?;
+                        println!("{} Updated {} for {}", Icon::Network, key, location);
+                        updated += 1;
+                    }
+                    None => {
+                        fields["project"] = json!({ "key": project });
+                        fields["issuetype"] = json!({ "name": jira.issue_type });
+                        let key = client.create_issue(&fields)?;
+                        repo.set_issue_key(
+                            scan.project.as_deref(),
+                            &scan.root_path,
+                            &fingerprint,
+                            &key,
+                        )?;
+                        println!("{} Created {} for {}", Icon::Network, key, location);
+                        created += 1;
+                    }
+                }
+            }
+            println!(
+                "{} Jira issues for scan {}: {} created, {} updated",
+                Icon::Success,
+                scan.id.unwrap_or_default(),
+                created,
+                updated
+            );
+            Ok(())
+        }
+    }
+}
+
+/// The value of the token variable `env`, which should hold `description`
+fn access_token(env: &str, description: &str) -> Result<String> {
+    std::env::var(env)
+        .ok()
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| CliError::Usage(format!("set {} to {}", env, description)).into())
+}
+
+/// Scan `id`, or the latest scan
+fn scan_or_latest(repo: &SqliteScanRepository, id: Option<i64>) -> Result<Scan> {
+    let id = match id {
+        Some(id) => id,
+        None => repo
+            .get_all_scans()?
+            .iter()
+            .filter_map(|scan| scan.id)
+            .max()
+            .ok_or_else(|| CliError::Usage("the database has no scans".to_string()))?,
+    };
+    Ok(repo
+        .get_scan(id)?
+        .ok_or_else(|| CliError::Usage(format!("scan {} not found", id)))?)
+}
+
+/// The scan of a branch and the scan of its target branch to compare it
+/// with: `scan` or the latest scan, and `base` or the previous scan of the
+/// same root path and project, if there is one
+fn branch_scans(
+    repo: &SqliteScanRepository,
+    scan: Option<i64>,
+    base: Option<i64>,
+) -> Result<(Scan, Option<Scan>)> {
+    let head = scan_or_latest(repo, scan)?;
+    let head_id = head.id.unwrap_or_default();
+    let base_id = match base {
+        Some(id) => Some(id),
+        None => repo
+            .get_all_scans()?
+            .iter()
+            .filter(|scan| scan.root_path == head.root_path && scan.project == head.project)
+            .filter_map(|scan| scan.id)
+            .filter(|id| *id < head_id)
+            .max(),
+    };
+    let base = match base_id {
+        Some(id) => Some(scan_or_latest(repo, Some(id))?),
+        None => None,
+    };
+    Ok((head, base))
+}
+
+/// Findings of `head` that `base` does not have; all of them without a base
+fn added_findings(head: &Scan, base: Option<&Scan>) -> Vec<Match> {
+    match base {
+        Some(base) => new_findings(
+            &head.matches,
+            &head.root_path,
+            &base.matches,
+            &base.root_path,
+        ),
+        None => head.matches.clone(),
+    }
+}
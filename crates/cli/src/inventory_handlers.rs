@@ -0,0 +1,272 @@
+use anyhow::Result;
+use code_guardian_core::{
+    parse_custom_detector_file, CiRun, CoverageReport, CustomDetector, CustomDetectorManager,
+    Inventory, MigrationReport, OptimizedScanner, PatternDetector, ScanStatistics, Scanner,
+};
+use code_guardian_storage::{Scan, ScanRepository, SqliteScanRepository};
+use std::path::{Path, PathBuf};
+
+use crate::cli_definitions::CliError;
+use crate::utils::{get_db_path, get_detectors_from_profile, print_summary};
+
+/// Scans `path` and prints its inventory, saving both, or prints the
+/// inventory stored with scan `scan_id`
+pub fn handle_inventory(
+    path: Option<PathBuf>,
+    scan_id: Option<i64>,
+    profile: &str,
+    custom_detectors: Option<PathBuf>,
+    format: &str,
+    db: Option<PathBuf>,
+) -> Result<()> {
+    check_format(format)?;
+    let mut repo = SqliteScanRepository::new(get_db_path(db))?;
+    let mut statistics = None;
+    let inventory = match (scan_id, path) {
+        (Some(id), _) => repo.get_inventory(id)?.ok_or_else(|| {
+            CliError::Usage(format!(
+                "No inventory stored with scan {}; run `code-guardian inventory <PATH>`",
+                id
+            ))
+        })?,
+        (None, Some(path)) => {
+            let (id, inventory, scan_statistics) =
+                take_inventory(&mut repo, &path, profile, custom_detectors)?;
+            eprintln!("Inventory saved with scan ID: {}", id);
+            statistics = Some(scan_statistics);
+            inventory
+        }
+        (None, None) => unreachable!("clap requires a path or --scan"),
+    };
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&inventory)?),
+        _ => print!("{}", format_inventory(&inventory)),
+    }
+    if let Some(statistics) = &statistics {
+        print_summary(statistics);
+    }
+    Ok(())
+}
+
+/// Scans `path` with the detectors of `profile` and saves the scan together
+/// with the inventory of its files and its statistics
+fn take_inventory(
+    repo: &mut SqliteScanRepository,
+    path: &Path,
+    profile: &str,
+    custom_detectors: Option<PathBuf>,
+) -> Result<(i64, Inventory, ScanStatistics)> {
+    let scanner = Scanner::new(load_detectors(path, profile, custom_detectors)?);
+    let (matches, metrics) = scanner.scan_with_metrics(path)?;
+    let inventory = scanner.inventory(path, &matches);
+    let statistics = ScanStatistics::new(&matches, &metrics);
+    let scan = Scan {
+        id: None,
+        timestamp: chrono::Utc::now().timestamp(),
+        root_path: path.to_string_lossy().to_string(),
+        project: None,
+        matches,
+    };
+    let id = repo.save_scan(&scan)?;
+    repo.save_inventory(id, &inventory)?;
+    repo.save_statistics(id, &statistics)?;
+    if let Some(ci_run) = CiRun::detect() {
+        repo.save_ci_run(id, &ci_run)?;
+    }
+    Ok((id, inventory, statistics))
+}
+
+/// Scans `path` with detector profiling and prints which detectors found
+/// nothing, which files were skipped and which file types no detector covers
+pub fn handle_coverage(
+    path: &Path,
+    profile: &str,
+    custom_detectors: Option<PathBuf>,
+    format: &str,
+) -> Result<()> {
+    check_format(format)?;
+    let scanner = OptimizedScanner::new(load_detectors(path, profile, custom_detectors)?)
+        .with_detector_profiling(true);
+    let (_, metrics) = scanner.scan_optimized(path)?;
+    let report = scanner.coverage_report(path, &metrics);
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+        _ => print!("{}", format_coverage(&report)),
+    }
+    Ok(())
+}
+
+/// Scans `path` with the deprecated API rules of a custom detector file and
+/// prints the usages left of each API
+pub fn handle_migration(path: &Path, custom_detectors: &Path, format: &str) -> Result<()> {
+    check_format(format)?;
+    if !path.exists() {
+        return Err(CliError::Usage(format!("Path '{}' does not exist", path.display())).into());
+    }
+    let rules: Vec<_> = parse_custom_detector_file(custom_detectors)?
+        .into_iter()
+        .filter(|rule| rule.enabled && rule.deprecation.is_some())
+        .collect();
+    if rules.is_empty() {
+        return Err(CliError::Usage(format!(
+            "{} has no enabled rules with a `deprecation` block",
+            custom_detectors.display()
+        ))
+        .into());
+    }
+    let mut detectors: Vec<Box<dyn PatternDetector>> = Vec::new();
+    for rule in &rules {
+        detectors.push(Box::new(CustomDetector::new(rule.clone())?));
+    }
+    let matches = Scanner::new(detectors).scan(path)?;
+    let report = MigrationReport::new(&rules, &matches);
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+        _ => print!("{}", format_migration(&report)),
+    }
+    Ok(())
+}
+
+fn check_format(format: &str) -> Result<()> {
+    if !matches!(format, "text" | "json") {
+        return Err(CliError::Usage(format!(
+            "Unsupported format: {}. Supported: text, json",
+            format
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// The detectors of `profile` plus any custom detectors, for scanning `path`
+fn load_detectors(
+    path: &Path,
+    profile: &str,
+    custom_detectors: Option<PathBuf>,
+) -> Result<Vec<Box<dyn PatternDetector>>> {
+    if !path.exists() {
+        return Err(CliError::Usage(format!("Path '{}' does not exist", path.display())).into());
+    }
+    let mut detectors = get_detectors_from_profile(profile);
+    if let Some(custom_path) = custom_detectors {
+        let mut manager = CustomDetectorManager::new();
+        manager.load_from_file(&custom_path)?;
+        detectors.extend(manager.get_detectors());
+    }
+    Ok(detectors)
+}
+
+fn format_coverage(report: &CoverageReport) -> String {
+    let mut output = format!(
+        "{} files scanned with {} detectors\n",
+        report.files_scanned, report.detectors
+    );
+    if report.is_complete() {
+        output.push_str(
+            "Every detector matched, every file was read and every file type is covered.\n",
+        );
+    }
+    if !report.silent_detectors.is_empty() {
+        output.push_str(&format!(
+            "\nDetectors with no matches ({}):\n",
+            report.silent_detectors.len()
+        ));
+        for detector in &report.silent_detectors {
+            output.push_str(&format!("  {}\n", detector));
+        }
+    }
+    if !report.skipped_files.is_empty() {
+        output.push_str(&format!(
+            "\nSkipped files ({}):\n",
+            report.skipped_files.len()
+        ));
+        for skipped in &report.skipped_files {
+            output.push_str(&format!("  {}: {}\n", skipped.path, skipped.reason));
+        }
+    }
+    if report.generated_files_skipped > 0 {
+        output.push_str(&format!(
+            "\nGenerated or vendored files skipped: {}\n",
+            report.generated_files_skipped
+        ));
+    }
+    if !report.uncovered_extensions.is_empty() {
+        output.push_str(&format!(
+            "\nFile types no detector looks at: {}\n",
+            format_extensions(&report.uncovered_extensions)
+        ));
+    }
+    output
+}
+
+fn format_migration(report: &MigrationReport) -> String {
+    let mut output = format!(
+        "{} deprecated APIs, {} fully migrated, {} usages left\n\n",
+        report.apis.len(),
+        report.migrated(),
+        report.remaining_usages()
+    );
+    for api in &report.apis {
+        let status = match api.usages {
+            0 => "migrated".to_string(),
+            usages => format!("{} usages in {} files", usages, api.files.len()),
+        };
+        output.push_str(&format!(
+            "{}: {} (deprecated since {}, use {})\n",
+            api.rule, status, api.since, api.replacement
+        ));
+        for (file, usages) in &api.files {
+            output.push_str(&format!("  {}: {}\n", file, usages));
+        }
+    }
+    output
+}
+
+fn format_inventory(inventory: &Inventory) -> String {
+    let mut output = format!(
+        "{} files, {} lines, {} detectors\n\n",
+        inventory.files, inventory.lines, inventory.total_detectors
+    );
+    output.push_str(&format!(
+        "{:<14} {:>7} {:>9} {:>8} {:>10}\n",
+        "Language", "Files", "Lines", "Matches", "Detectors"
+    ));
+    for language in &inventory.languages {
+        output.push_str(&format!(
+            "{:<14} {:>7} {:>9} {:>8} {:>10}\n",
+            language.language,
+            language.files,
+            language.lines,
+            language.matches,
+            format!("{}/{}", language.detectors.len(), inventory.total_detectors)
+        ));
+    }
+    if !inventory.unrecognized.is_empty() {
+        output.push_str(&format!(
+            "\nUnrecognized file types: {}\n",
+            format_extensions(&inventory.unrecognized)
+        ));
+    }
+    if !inventory.unscanned.is_empty() {
+        output.push_str(&format!(
+            "Binary files, not scanned: {}\n",
+            format_extensions(&inventory.unscanned)
+        ));
+    }
+    output
+}
+
+/// `.ext (N)` entries, with `(no extension)` for files without one
+fn format_extensions(counts: &std::collections::BTreeMap<String, usize>) -> String {
+    counts
+        .iter()
+        .map(|(ext, count)| {
+            if ext.is_empty() {
+                format!("(no extension) ({})", count)
+            } else {
+                format!(".{} ({})", ext, count)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
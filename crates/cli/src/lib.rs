@@ -3,7 +3,13 @@ pub mod benchmark;
 pub mod cli_definitions;
 pub mod command_handlers;
 pub mod comparison_handlers;
+pub mod completions;
+pub mod finding_handlers;
+pub mod fix_handlers;
 pub mod git_integration;
+pub mod integration_handlers;
+pub mod inventory_handlers;
+pub mod mcp_server;
 pub mod production_handlers;
 pub mod report_handlers;
 pub mod scan_handlers;
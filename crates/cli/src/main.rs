@@ -1,5 +1,14 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
+use clap_complete::CompleteEnv;
+use code_guardian_core::config::load_config;
+use code_guardian_core::{
+    append_audit_record, config_hash, current_user, redact_args, set_compliance_mappings,
+    set_output_theme, set_redaction, AuditRecord, CiRun, ThemeConfig,
+};
+use code_guardian_storage::MatchFilter;
+use std::path::PathBuf;
+use std::time::Instant;
 
 // Module declarations
 mod advanced_handlers;
@@ -7,7 +16,13 @@ mod benchmark;
 mod cli_definitions;
 mod command_handlers;
 mod comparison_handlers;
+mod completions;
+mod finding_handlers;
+mod fix_handlers;
 mod git_integration;
+mod integration_handlers;
+mod inventory_handlers;
+mod mcp_server;
 mod production_handlers;
 mod report_handlers;
 mod scan_handlers;
@@ -15,70 +30,331 @@ mod stack_presets;
 mod utils;
 
 // Import the CLI definitions and command handlers
-use cli_definitions::{Cli, Commands};
+use cli_definitions::{Cli, CliError, Commands, ExitCode};
 use command_handlers::*;
 use comparison_handlers::*;
+use finding_handlers::*;
+use fix_handlers::*;
+use integration_handlers::*;
+use inventory_handlers::*;
 use production_handlers::*;
 use report_handlers::*;
 use scan_handlers::*;
 use stack_presets::*;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> std::process::ExitCode {
+    // Answers shells completing a command line, see `completion --dynamic`
+    CompleteEnv::with_factory(Cli::command).complete();
+
     // Initialize tracing
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let command = subcommand_path(&matches);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let exit_zero = cli.exit_zero;
+    // A config that fails to load is reported by the command itself
+    let config = load_config(cli.command.config_path()).unwrap_or_default();
+    let audit_log = cli
+        .audit_log
+        .clone()
+        .or_else(|| config.audit_log.as_ref().map(PathBuf::from));
+    let config_hash = config_hash(&config);
+    set_output_theme(output_theme(&cli, config.theme));
+    set_compliance_mappings(config.compliance);
+    utils::set_print_summary(cli.summary);
+
+    let started = Instant::now();
+    let result = run(cli).await;
+    let mut code = match &result {
+        Ok(()) => ExitCode::Success,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitCode::from_error(e)
+        }
+    };
+
+    // Report-only pipelines still fail on usage and internal errors
+    if exit_zero && code == ExitCode::FindingsAboveThreshold {
+        code = ExitCode::Success;
+    }
+
+    if let Some(path) = audit_log {
+        let record = AuditRecord {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            user: current_user(),
+            ci: CiRun::detect(),
+            command,
+            args: redact_args(
+                std::env::args_os()
+                    .skip(1)
+                    .map(|arg| arg.to_string_lossy().into_owned()),
+            ),
+            config_hash,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            exit_code: code as u8,
+            error: result.err().map(|e| e.to_string()),
+            duration_ms: started.elapsed().as_millis() as u64,
+            scans: utils::take_scan_statistics(),
+        };
+        // A command that can't be evidenced fails, even if it succeeded
+        if let Err(e) = append_audit_record(&path, &record) {
+            eprintln!("Error: could not write the audit log: {}", e);
+            if code == ExitCode::Success {
+                code = ExitCode::InternalError;
+            }
+        }
+    }
+    code.into()
+}
+
+/// Names of the subcommands given, e.g. `token create`
+fn subcommand_path(matches: &ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub_matches)) = current.subcommand() {
+        names.push(name);
+        current = sub_matches;
+    }
+    names.join(" ")
+}
+
+/// Turns off redaction of secrets for `--no-redact`, which only local runs may
+/// use: CI logs, artifacts and notifications outlive the run
+fn allow_unredacted() -> Result<()> {
+    if let Some(ci_run) = CiRun::detect() {
+        return Err(
+            CliError::Usage(format!("--no-redact is not allowed in {}", ci_run.provider)).into(),
+        );
+    }
+    if std::env::var_os("CI").is_some() {
+        return Err(CliError::Usage("--no-redact is not allowed in CI".to_string()).into());
+    }
+    set_redaction(false);
+    Ok(())
+}
+
+/// The `theme` of the config the command reads, with `--no-emoji` applied
+fn output_theme(cli: &Cli, mut theme: ThemeConfig) -> ThemeConfig {
+    if cli.no_emoji {
+        theme.emoji = false;
+    }
+    theme
+}
 
+async fn run(cli: Cli) -> Result<()> {
+    if cli.no_redact {
+        allow_unredacted()?;
+    }
     match cli.command {
         Commands::Scan {
             path,
+            stdin,
+            filename,
+            target,
+            all_targets,
+            parallel,
             db,
+            project,
             config,
             profile,
             progress,
             optimize,
             streaming,
             metrics,
+            metrics_out,
+            upload,
+            webhooks,
+            llm_triage,
             incremental,
+            prune_dirs,
             distributed,
+            fast,
+            budget,
             custom_detectors,
+            strict_rules,
+            rule_timeout_ms,
             cache_size,
+            cache_key,
+            cache_dir,
             batch_size,
             max_file_size,
             max_threads,
+            max_matches_per_file,
+            max_matches_per_pattern,
+            sample_above,
+            sample_per_file,
+            include_generated,
+            follow_symlinks,
+            max_depth,
+            report_skipped,
+            list_files,
+            null,
+            require_issue_ref,
+            issue_pattern,
+            fail_on_stale_todos,
+            blame,
+            group_by,
+            dry_run,
         } => {
             let options = ScanOptions {
-                path,
+                path: path.unwrap_or_default(),
+                stdin,
+                stdin_filename: filename,
                 db,
+                project,
                 config_path: config,
                 profile,
                 show_progress: progress,
                 optimize,
                 streaming,
                 show_metrics: metrics,
+                metrics_out,
+                upload,
+                webhooks,
+                llm_triage,
                 incremental,
+                prune_dirs,
                 distributed,
+                fast,
+                budget,
                 custom_detectors,
+                strict_rules,
+                rule_timeout_ms,
                 cache_size,
+                cache_key,
+                cache_dir,
                 batch_size,
                 max_file_size,
                 max_threads,
+                max_matches_per_file,
+                max_matches_per_pattern,
+                sample_above,
+                sample_per_file,
+                include_generated,
+                follow_symlinks,
+                max_depth,
+                report_skipped,
+                list_files,
+                null_delimited: null,
+                require_issue_ref,
+                issue_pattern,
+                fail_on_stale_todos,
+                blame,
+                group_by,
+                dry_run,
             };
-            handle_scan(options).await
+            if all_targets || !target.is_empty() {
+                handle_scan_targets(options, target, parallel).await
+            } else {
+                handle_scan(options).await
+            }
         }
-        Commands::History { db } => handle_history(db),
-        Commands::Report { id, format, db } => handle_report(id, format, db),
+        Commands::History { db, project } => handle_history(db, project),
+        Commands::Report {
+            id,
+            format,
+            db,
+            by_compliance: true,
+            project,
+            ..
+        } => handle_compliance_report(id, &format, db, project.as_deref()),
+        Commands::Report {
+            id,
+            format,
+            db,
+            tune: true,
+            top,
+            project,
+            ..
+        } => handle_tuning_report(id, &format, db, top, project.as_deref()),
+        Commands::Report {
+            id,
+            format,
+            db,
+            offset,
+            limit,
+            pattern,
+            file,
+            group,
+            by_compliance: false,
+            tune: false,
+            top: _,
+            config: _,
+            project,
+        } => handle_report(
+            id,
+            format,
+            db,
+            ReportPage {
+                offset,
+                limit,
+                filter: MatchFilter { pattern, file },
+            },
+            group,
+            project.as_deref(),
+        ),
         Commands::Compare {
             id1,
             id2,
             format,
             db,
         } => handle_compare(id1, id2, format, db),
-        Commands::Completion { shell } => handle_completion(shell),
-        Commands::Benchmark { path, quick } => handle_benchmark(path, quick),
+        Commands::Inventory {
+            path,
+            scan,
+            profile,
+            custom_detectors,
+            format,
+            db,
+        } => handle_inventory(path, scan, &profile, custom_detectors, &format, db),
+        Commands::Coverage {
+            path,
+            profile,
+            custom_detectors,
+            format,
+        } => handle_coverage(&path, &profile, custom_detectors, &format),
+        Commands::Migration {
+            path,
+            custom_detectors,
+            format,
+        } => handle_migration(&path, &custom_detectors, &format),
+        Commands::Findings { action } => handle_findings(action),
+        Commands::Db { action } => handle_db(action),
+        Commands::Storage { action } => handle_storage(action),
+        Commands::Token { action } => handle_token(action),
+        Commands::Completion {
+            shell,
+            dynamic,
+            man,
+        } => handle_completion(shell, dynamic, man.as_deref()),
+        Commands::Config { action } => handle_config(action),
+        Commands::Mcp { root, db, profile } => handle_mcp(&root, db, profile),
+        Commands::Serve {
+            port,
+            db,
+            feed_scans,
+            root,
+            profile,
+            tls_cert,
+            tls_key,
+        } => {
+            let tls = tls_cert.zip(tls_key);
+            handle_serve(port, db, feed_scans, &root, profile, tls).await
+        }
+        Commands::Info { json } => handle_info(json),
+        Commands::Schema { kind } => handle_schema(&kind),
+        Commands::Benchmark {
+            path,
+            per_detector: true,
+            profile,
+            custom_detectors,
+            ..
+        } => handle_detector_benchmark(path, &profile, custom_detectors),
+        Commands::Benchmark { path, quick, .. } => handle_benchmark(path, quick),
         Commands::CustomDetectors { action } => handle_custom_detectors(action),
         Commands::Incremental { action } => handle_incremental(action),
         Commands::Distributed { action } => handle_distributed(action).await,
@@ -89,26 +365,42 @@ async fn main() -> Result<()> {
             fail_on_high,
             severity,
             output,
-        } => handle_production_check(
+            custom_detectors,
+            report_commented_out,
+        } => handle_production_check(ProductionCheckOptions {
             path,
             format,
             fail_on_critical,
             fail_on_high,
-            severity,
+            severity_filter: severity,
             output,
-        ),
+            custom_detectors,
+            report_commented_out,
+        }),
         Commands::PreCommit {
             path,
             staged_only,
             fast,
-        } => handle_pre_commit(path, staged_only, fast),
+            db,
+            budget,
+        } => handle_pre_commit(path, staged_only, fast, db, budget),
         Commands::CiGate {
             path,
             config,
             output,
             max_critical,
             max_high,
-        } => handle_ci_gate(path, config, output, max_critical, max_high),
+            custom_detectors,
+            upload,
+        } => handle_ci_gate(
+            path,
+            config,
+            output,
+            max_critical,
+            max_high,
+            custom_detectors,
+            upload,
+        ),
         Commands::Lang {
             languages,
             path,
@@ -123,5 +415,14 @@ async fn main() -> Result<()> {
             delay,
         } => handle_watch(path, include, exclude, delay),
         Commands::Git { action } => handle_git(action),
+        Commands::Bitbucket { action } => handle_bitbucket(action),
+        Commands::Gitlab { action } => handle_gitlab(action),
+        Commands::Jira { action } => handle_jira(action),
+        Commands::Fix {
+            path,
+            add_issue_placeholder,
+            issue_pattern,
+            dry_run,
+        } => handle_fix(path, add_issue_placeholder, issue_pattern, dry_run),
     }
 }
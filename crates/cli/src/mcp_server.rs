@@ -0,0 +1,500 @@
+//! `code-guardian mcp`: a Model Context Protocol server on stdin/stdout.
+//!
+//! Speaks newline-delimited JSON-RPC 2.0 and exposes scanning as MCP tools.
+//! Scans and file reads are confined to the server's root directory and
+//! results are capped, so assistants can use it without broader access.
+
+use anyhow::{Context, Result};
+use code_guardian_core::health_server::{ScanRequest, ScanTrigger};
+use code_guardian_core::{windows_paths, ScanStatistics, ScanSummary, Scanner};
+use code_guardian_storage::{
+    validate_project, MatchFilter, Scan, ScanRepository, SqliteScanRepository,
+};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::utils::{get_detectors_from_profile, DETECTOR_PROFILES};
+
+/// MCP protocol revision implemented by the server
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Findings returned by `get_findings` when no limit is given, and the maximum
+const DEFAULT_FINDINGS_LIMIT: usize = 100;
+const MAX_FINDINGS_LIMIT: usize = 1000;
+
+/// Lines of source shown before and after a finding by `explain_finding`
+const CONTEXT_LINES: usize = 3;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+pub struct McpServer {
+    root: PathBuf,
+    db_path: PathBuf,
+    profile: String,
+}
+
+impl McpServer {
+    /// Serves scans below `root`, storing them in `db_path`. `profile` is the
+    /// detector profile used when `scan_path` doesn't name one.
+    pub fn new(root: &Path, db_path: PathBuf, profile: String) -> Result<Self> {
+        let root = windows_paths::canonicalize(root)
+            .with_context(|| format!("Cannot resolve root {}", root.display()))?;
+        if !DETECTOR_PROFILES.contains(&profile.as_str()) {
+            anyhow::bail!(
+                "Unknown profile '{}', expected one of: {}",
+                profile,
+                DETECTOR_PROFILES.join(", ")
+            );
+        }
+        Ok(Self {
+            root,
+            db_path,
+            profile,
+        })
+    }
+
+    /// Answers requests from `input` until it is closed
+    pub fn run(&self, input: impl BufRead, mut output: impl Write) -> Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_message(&line) {
+                writeln!(output, "{}", response)?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles one JSON-RPC message. Notifications get no response.
+    pub fn handle_message(&self, line: &str) -> Option<Value> {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, &e.to_string())),
+        };
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "Missing method",
+            ));
+        };
+        // Notifications such as `notifications/initialized` carry no id
+        let id = id?;
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": {
+                    "name": "code-guardian",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(&params),
+            _ => Err((METHOD_NOT_FOUND, format!("Unknown method '{}'", method))),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, &message),
+        })
+    }
+
+    fn call_tool(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+        let args = params.get("arguments").cloned().unwrap_or(json!({}));
+
+        let outcome = match name {
+            "scan_path" => self.scan_path(&args),
+            "get_findings" => self.get_findings(&args),
+            "explain_finding" => self.explain_finding(&args),
+            _ => return Err((INVALID_PARAMS, format!("Unknown tool '{}'", name))),
+        };
+
+        // Tool failures are reported to the model rather than as protocol errors
+        Ok(match outcome {
+            Ok(value) => json!({
+                "content": [{
+                    "type": "text",
+                    "text": serde_json::to_string_pretty(&value).unwrap_or_default(),
+                }],
+            }),
+            Err(message) => json!({
+                "content": [{ "type": "text", "text": message }],
+                "isError": true,
+            }),
+        })
+    }
+
+    fn scan_path(&self, args: &Value) -> Result<Value, String> {
+        let path = self.resolve_path(args.get("path").and_then(Value::as_str).unwrap_or("."))?;
+        let profile = args
+            .get("profile")
+            .and_then(Value::as_str)
+            .unwrap_or(&self.profile);
+        if !DETECTOR_PROFILES.contains(&profile) {
+            return Err(format!(
+                "Unknown profile '{}', expected one of: {}",
+                profile,
+                DETECTOR_PROFILES.join(", ")
+            ));
+        }
+        let project = args.get("project").and_then(Value::as_str);
+        if let Some(project) = project {
+            validate_project(project)?;
+        }
+
+        let scanner = Scanner::new(get_detectors_from_profile(profile));
+        let (matches, metrics) = scanner
+            .scan_with_metrics(&path)
+            .map_err(|e| e.to_string())?;
+        let scan = Scan {
+            id: None,
+            timestamp: chrono::Utc::now().timestamp(),
+            root_path: path.to_string_lossy().to_string(),
+            project: project.map(String::from),
+            matches,
+        };
+        let mut repo = self.repository()?;
+        let scan_id = repo.save_scan(&scan).map_err(|e| e.to_string())?;
+        repo.save_statistics(scan_id, &ScanStatistics::new(&scan.matches, &metrics))
+            .map_err(|e| e.to_string())?;
+
+        Ok(json!({
+            "scan_id": scan_id,
+            "root_path": scan.root_path,
+            "project": scan.project,
+            "profile": profile,
+            "summary": ScanSummary::from_matches(&scan.matches, metrics.total_files_scanned),
+            "unreadable_files": metrics.skipped_files.len(),
+        }))
+    }
+
+    fn get_findings(&self, args: &Value) -> Result<Value, String> {
+        let repo = self.repository()?;
+        let scan = find_scan(&repo, args)?;
+        let filter = MatchFilter {
+            pattern: args
+                .get("pattern")
+                .and_then(Value::as_str)
+                .map(String::from),
+            file: args.get("file").and_then(Value::as_str).map(String::from),
+        };
+        let offset = args.get("offset").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let limit = args
+            .get("limit")
+            .and_then(Value::as_u64)
+            .map_or(DEFAULT_FINDINGS_LIMIT, |limit| limit as usize)
+            .min(MAX_FINDINGS_LIMIT);
+
+        // Only the requested page is loaded, so large scans stay cheap to browse
+        let id = scan.id.unwrap_or_default();
+        let page = repo
+            .get_matches(id, offset, limit, &filter)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Scan {} not found", id))?;
+
+        Ok(json!({
+            "scan_id": scan.id,
+            "root_path": scan.root_path,
+            "total": page.total,
+            "offset": offset,
+            "truncated": page.has_more(),
+            "findings": page.matches,
+        }))
+    }
+
+    fn explain_finding(&self, args: &Value) -> Result<Value, String> {
+        let scan = self.load_scan(args)?;
+        let file_path = args
+            .get("file_path")
+            .and_then(Value::as_str)
+            .ok_or("Missing 'file_path'")?;
+        let line_number = args
+            .get("line_number")
+            .and_then(Value::as_u64)
+            .ok_or("Missing 'line_number'")? as usize;
+        let pattern = args.get("pattern").and_then(Value::as_str);
+
+        let finding = scan
+            .matches
+            .iter()
+            .find(|m| {
+                m.file_path == file_path
+                    && m.line_number == line_number
+                    && pattern.map_or(true, |p| m.pattern.eq_ignore_ascii_case(p))
+            })
+            .ok_or_else(|| {
+                format!(
+                    "No finding at {}:{} in scan {}",
+                    file_path,
+                    line_number,
+                    scan.id.unwrap_or_default()
+                )
+            })?;
+
+        let severity = crate::production_handlers::severity_of(finding);
+        // The file may have changed or moved since the scan; context is best effort
+        let context = self
+            .resolve_path(&finding.file_path)
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|content| source_context(&content, finding.line_number))
+            .unwrap_or_default();
+
+        Ok(json!({
+            "finding": finding,
+            "severity": severity,
+            "explanation": format!(
+                "{} finding ({} severity) at {}:{}:{}: {}",
+                finding.pattern,
+                severity,
+                finding.file_path,
+                finding.line_number,
+                finding.column,
+                finding.message
+            ),
+            "context": context,
+        }))
+    }
+
+    /// Loads the scan named by `scan_id`, or the most recent one
+    fn load_scan(&self, args: &Value) -> Result<Scan, String> {
+        let repo = self.repository()?;
+        let id = find_scan(&repo, args)?.id.unwrap_or_default();
+        repo.get_scan(id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Scan {} not found", id))
+    }
+
+    fn repository(&self) -> Result<SqliteScanRepository, String> {
+        if let Some(parent) = self.db_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        SqliteScanRepository::new(&self.db_path).map_err(|e| e.to_string())
+    }
+
+    /// Resolves `path` against the root, rejecting anything outside it
+    fn resolve_path(&self, path: &str) -> Result<PathBuf, String> {
+        let joined = self.root.join(path);
+        let resolved = windows_paths::canonicalize(&joined)
+            .map_err(|e| format!("Cannot access '{}': {}", path, e))?;
+        if resolved.starts_with(&self.root) {
+            Ok(resolved)
+        } else {
+            Err(format!(
+                "'{}' is outside the server root {}",
+                path,
+                self.root.display()
+            ))
+        }
+    }
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// Lines around `line_number` (1-based) as `{ "line", "text" }` objects
+fn source_context(content: &str, line_number: usize) -> Vec<Value> {
+    let first = line_number.saturating_sub(CONTEXT_LINES).max(1);
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, text)| (i + 1, text))
+        .skip(first - 1)
+        .take_while(|(line, _)| *line <= line_number + CONTEXT_LINES)
+        .map(|(line, text)| json!({ "line": line, "text": text }))
+        .collect()
+}
+
+/// The scan named by `scan_id`, or the most recent one, without its matches
+fn find_scan(repo: &SqliteScanRepository, args: &Value) -> Result<Scan, String> {
+    let scans = repo.get_all_scans().map_err(|e| e.to_string())?;
+    match args.get("scan_id").and_then(Value::as_i64) {
+        Some(id) => scans
+            .into_iter()
+            .find(|scan| scan.id == Some(id))
+            .ok_or_else(|| format!("Scan {} not found", id)),
+        None => scans
+            .into_iter()
+            .max_by_key(|scan| scan.id)
+            .ok_or_else(|| "No scans yet; run scan_path first".to_string()),
+    }
+}
+
+/// Scans of `code-guardian serve`, confined to the root like `scan_path`
+impl ScanTrigger for McpServer {
+    fn trigger_scan(&self, request: &ScanRequest) -> Result<Value, String> {
+        self.scan_path(&serde_json::to_value(request).map_err(|e| e.to_string())?)
+    }
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "scan_path",
+            "description": "Scan a file or directory below the server root for TODOs, security issues and other patterns. Saves the scan and returns its ID with a summary.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Path relative to the server root (default: the root)" },
+                    "profile": { "type": "string", "enum": DETECTOR_PROFILES, "description": "Detector profile" },
+                    "project": { "type": "string", "description": "Project to save the scan under" },
+                },
+            },
+        },
+        {
+            "name": "get_findings",
+            "description": "List findings from a saved scan, optionally filtered by pattern or file path.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "scan_id": { "type": "integer", "description": "Scan to read (default: the latest)" },
+                    "pattern": { "type": "string", "description": "Only findings of this pattern, e.g. TODO" },
+                    "file": { "type": "string", "description": "Only findings whose path contains this text" },
+                    "offset": { "type": "integer", "description": "Findings to skip, for paging through large scans (default 0)" },
+                    "limit": { "type": "integer", "description": "Maximum findings returned (default 100, at most 1000)" },
+                },
+            },
+        },
+        {
+            "name": "explain_finding",
+            "description": "Explain one finding: its severity, message and the surrounding source lines.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "scan_id": { "type": "integer", "description": "Scan containing the finding (default: the latest)" },
+                    "file_path": { "type": "string", "description": "file_path of the finding as returned by get_findings" },
+                    "line_number": { "type": "integer" },
+                    "pattern": { "type": "string", "description": "Disambiguates several findings on the same line" },
+                },
+                "required": ["file_path", "line_number"],
+            },
+        },
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn call(server: &McpServer, id: i64, method: &str, params: Value) -> Value {
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        server.handle_message(&request.to_string()).unwrap()
+    }
+
+    fn tool_json(response: &Value) -> Value {
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_mcp_tools_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("project");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(
+            root.join("main.rs"),
+            "fn main() {\n    // TODO: wire up\n}\n",
+        )
+        .unwrap();
+        let server =
+            McpServer::new(&root, temp_dir.path().join("scans.db"), "basic".to_string()).unwrap();
+
+        let init = call(&server, 1, "initialize", json!({}));
+        assert_eq!(init["result"]["protocolVersion"], PROTOCOL_VERSION);
+        let tools = call(&server, 2, "tools/list", json!({}));
+        assert_eq!(tools["result"]["tools"].as_array().unwrap().len(), 3);
+
+        let scan = tool_json(&call(
+            &server,
+            3,
+            "tools/call",
+            json!({ "name": "scan_path" }),
+        ));
+        assert_eq!(scan["scan_id"], 1);
+        assert_eq!(scan["summary"]["total_matches"], 1);
+
+        let findings = tool_json(&call(
+            &server,
+            4,
+            "tools/call",
+            json!({ "name": "get_findings", "arguments": { "pattern": "todo" } }),
+        ));
+        assert_eq!(findings["total"], 1);
+        let finding = &findings["findings"][0];
+        let past_end = tool_json(&call(
+            &server,
+            6,
+            "tools/call",
+            json!({ "name": "get_findings", "arguments": { "pattern": "todo", "offset": 1 } }),
+        ));
+        assert_eq!(past_end["total"], 1);
+        assert!(past_end["findings"].as_array().unwrap().is_empty());
+
+        let explained = tool_json(&call(
+            &server,
+            5,
+            "tools/call",
+            json!({
+                "name": "explain_finding",
+                "arguments": { "file_path": finding["file_path"], "line_number": 2 },
+            }),
+        ));
+        assert_eq!(explained["severity"], "Low");
+        assert_eq!(explained["context"].as_array().unwrap().len(), 3);
+        assert_eq!(explained["context"][1]["text"], "    // TODO: wire up");
+    }
+
+    #[test]
+    fn test_mcp_rejects_paths_outside_root_and_bad_requests() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("project");
+        std::fs::create_dir(&root).unwrap();
+        let server =
+            McpServer::new(&root, temp_dir.path().join("scans.db"), "basic".to_string()).unwrap();
+
+        let response = call(
+            &server,
+            1,
+            "tools/call",
+            json!({ "name": "scan_path", "arguments": { "path": ".." } }),
+        );
+        assert_eq!(response["result"]["isError"], true);
+        assert!(response["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("outside the server root"));
+
+        assert_eq!(
+            call(&server, 2, "resources/list", json!({}))["error"]["code"],
+            METHOD_NOT_FOUND
+        );
+        assert_eq!(
+            server.handle_message("{not json").unwrap()["error"]["code"],
+            PARSE_ERROR
+        );
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(server.handle_message(&notification.to_string()).is_none());
+    }
+}
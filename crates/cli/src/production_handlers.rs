@@ -1,33 +1,64 @@
+use crate::cli_definitions::CliError;
 use crate::git_integration::GitIntegration;
+use crate::scan_handlers::upload_artifacts;
+use crate::utils::{get_db_path, print_summary, report_budget_coverage, tracked_density};
 use anyhow::Result;
-use code_guardian_core::{AlertDetector, ConsoleLogDetector, DebuggerDetector};
-use code_guardian_core::{DetectorFactory, Match, PatternDetector, Scanner};
+use code_guardian_core::{
+    paint_severity, prioritize_files, ArtifactStore, BudgetCoverage, CustomDetectorManager,
+    DetectorFactory, FindingDensity, Icon, Match, PatternDetector, ScanBudget, ScanMetrics,
+    ScanStatistics, Scanner, Severity,
+};
+use code_guardian_core::{
+    AlertDetector, CommentedOutCodeDetector, ConsoleLogDetector, DebuggerDetector,
+};
+use code_guardian_storage::SqliteScanRepository;
 use colored::*;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default)]
+pub struct ProductionCheckOptions {
+    pub path: PathBuf,
+    pub format: String,
+    pub fail_on_critical: bool,
+    pub fail_on_high: bool,
+    pub severity_filter: Vec<String>,
+    pub output: Option<PathBuf>,
+    pub custom_detectors: Option<PathBuf>,
+    /// Report commented-out `console.log` and debugger statements, which the
+    /// production detectors skip, as `COMMENTED_OUT_CODE`
+    pub report_commented_out: bool,
+}
 
 /// Handle production readiness check command
-pub fn handle_production_check(
-    path: PathBuf,
-    format: String,
-    fail_on_critical: bool,
-    fail_on_high: bool,
-    severity_filter: Vec<String>,
-    output: Option<PathBuf>,
-) -> Result<()> {
+pub fn handle_production_check(options: ProductionCheckOptions) -> Result<()> {
+    let ProductionCheckOptions {
+        path,
+        format,
+        fail_on_critical,
+        fail_on_high,
+        severity_filter,
+        output,
+        custom_detectors,
+        report_commented_out,
+    } = options;
     println!(
-        "🔍 {} Production Readiness Check",
+        "{} {} Production Readiness Check",
+        Icon::Search,
         "Code-Guardian".bold().blue()
     );
-    println!("📁 Scanning: {}", path.display());
+    println!("{} Scanning: {}", Icon::Folder, path.display());
 
     // Create production-ready detectors
-    let detectors = DetectorFactory::create_production_ready_detectors();
+    let mut detectors = production_detectors(custom_detectors.as_deref())?;
+    if report_commented_out {
+        detectors.push(Box::new(CommentedOutCodeDetector));
+    }
     let scanner = Scanner::new(detectors);
 
     // Perform the scan
-    let matches = scanner.scan(&path)?;
+    let (matches, metrics) = scanner.scan_with_metrics(&path)?;
 
     // Filter by severity if specified
     let filtered_matches = if severity_filter.is_empty() {
@@ -38,6 +69,7 @@ pub fn handle_production_check(
 
     // Count issues by severity
     let severity_counts = count_by_severity(&filtered_matches);
+    let statistics = production_statistics(&filtered_matches, &metrics);
 
     // Generate output based on format
     let output_content = match format.as_str() {
@@ -49,7 +81,7 @@ pub fn handle_production_check(
     // Write to file if specified, otherwise print to stdout
     if let Some(output_path) = output {
         fs::write(&output_path, &output_content)?;
-        println!("📄 Report saved to: {}", output_path.display());
+        println!("{} Report saved to: {}", Icon::File, output_path.display());
     } else {
         println!("{}", output_content);
     }
@@ -58,35 +90,58 @@ pub fn handle_production_check(
     let critical_count = severity_counts.get("Critical").unwrap_or(&0);
     let high_count = severity_counts.get("High").unwrap_or(&0);
 
+    print_summary(&statistics);
+
     if fail_on_critical && *critical_count > 0 {
-        return Err(anyhow::anyhow!(
-            "❌ Production check FAILED: {} critical issues found",
+        return Err(CliError::ThresholdExceeded(format!(
+            "{} Production check FAILED: {} critical issues found",
+            Icon::Failure,
             critical_count
-        ));
+        ))
+        .into());
     }
 
     if fail_on_high && *high_count > 0 {
-        return Err(anyhow::anyhow!(
-            "⚠️  Production check FAILED: {} high severity issues found",
+        return Err(CliError::ThresholdExceeded(format!(
+            "{} Production check FAILED: {} high severity issues found",
+            Icon::Warning,
             high_count
-        ));
+        ))
+        .into());
     }
 
     if *critical_count > 0 || *high_count > 0 {
         println!(
-            "⚠️  Production readiness: {} - Address critical and high severity issues",
+            "{} Production readiness: {} - Address critical and high severity issues",
+            Icon::Warning,
             "NEEDS ATTENTION".yellow()
         );
     } else {
-        println!("✅ Production readiness: {}", "PASSED".green());
+        println!(
+            "{} Production readiness: {}",
+            Icon::Success,
+            "PASSED".green()
+        );
     }
 
     Ok(())
 }
 
-/// Handle pre-commit hook command
-pub fn handle_pre_commit(path: PathBuf, staged_only: bool, fast: bool) -> Result<()> {
-    println!("🔧 {} Pre-commit Check", "Code-Guardian".bold().cyan());
+/// Handle pre-commit hook command. Staged files are scanned one at a time,
+/// those with the most tracked findings in `db` and then the most recently
+/// modified first, and their findings are printed as soon as they are found.
+pub fn handle_pre_commit(
+    path: PathBuf,
+    staged_only: bool,
+    fast: bool,
+    db: Option<PathBuf>,
+    budget: Option<ScanBudget>,
+) -> Result<()> {
+    println!(
+        "{} {} Pre-commit Check",
+        Icon::Tool,
+        "Code-Guardian".bold().cyan()
+    );
 
     let detectors: Vec<Box<dyn PatternDetector>> = if fast {
         // Fast mode: only critical issues
@@ -99,82 +154,132 @@ pub fn handle_pre_commit(path: PathBuf, staged_only: bool, fast: bool) -> Result
         DetectorFactory::create_production_ready_detectors()
     };
 
-    let scanner = Scanner::new(detectors);
+    let start = std::time::Instant::now();
+    let density = finding_density(db);
+    let mut scanner = Scanner::new(detectors);
+    if let Some(budget) = &budget {
+        scanner = scanner.with_budget(budget.clone().with_density(density.clone()));
+    }
+    // Findings of staged files are printed as they are found, not at the end
+    let mut printed = false;
 
-    let matches = if staged_only {
+    let (matches, files, skipped) = if staged_only {
         // Check if we're in a git repository
         if !GitIntegration::is_git_repo(&path) {
-            println!("⚠️  Not in a git repository. Scanning entire directory instead.");
-            scanner.scan(&path)?
+            println!(
+                "{} Not in a git repository. Scanning entire directory instead.",
+                Icon::Warning
+            );
+            scan_counted(&scanner, &path)?
         } else {
             // Get repo root and staged files
             let repo_root = GitIntegration::get_repo_root(&path)?;
-            let staged_files = GitIntegration::get_staged_files(&repo_root)?;
+            let mut staged_files = GitIntegration::get_staged_files(&repo_root)?;
 
             if staged_files.is_empty() {
-                println!("ℹ️  No staged files found. Nothing to scan.");
+                println!("{} No staged files found. Nothing to scan.", Icon::Info);
+                print_summary(&ScanStatistics::default());
                 return Ok(());
             }
 
-            println!("🔍 Scanning {} staged file(s)...", staged_files.len());
+            println!(
+                "{} Scanning {} staged file(s)...",
+                Icon::Search,
+                staged_files.len()
+            );
+            prioritize_files(&mut staged_files, &density);
             if !fast {
                 for file in &staged_files {
-                    println!("  📄 {}", file.display());
+                    println!("  {} {}", Icon::File, file.display());
                 }
             }
 
-            // Scan only staged files
+            // Scan only staged files, printing each file's findings right away
             let mut all_matches = Vec::new();
+            let (mut files, mut skipped) = (0, 0);
+            let total = staged_files.len();
+            let mut covered = 0;
             for file_path in staged_files {
-                if file_path.is_file() {
-                    // For now, use the directory scanner on each file's parent
-                    // This is a workaround until we implement file-specific scanning
-                    if let Some(parent) = file_path.parent() {
-                        let file_matches = scanner.scan(parent)?;
-                        // Filter matches to only include the specific file
-                        let filtered_matches: Vec<_> = file_matches
-                            .into_iter()
-                            .filter(|m| m.file_path == file_path.to_string_lossy())
-                            .collect();
-                        all_matches.extend(filtered_matches);
-                    }
+                if budget
+                    .as_ref()
+                    .is_some_and(|budget| start.elapsed() >= budget.limit)
+                {
+                    break;
+                }
+                covered += 1;
+                if !file_path.is_file() {
+                    continue;
                 }
+                let Ok(content) = fs::read_to_string(&file_path) else {
+                    // Binary and non-UTF-8 files have nothing to report
+                    skipped += 1;
+                    continue;
+                };
+                files += 1;
+                let mut file_matches = scanner.scan_content(&content, &file_path);
+                file_matches.sort_by_key(|m| m.line_number);
+                for m in &file_matches {
+                    print_finding(m);
+                }
+                all_matches.extend(file_matches);
+            }
+            printed = true;
+            if let Some(budget) = &budget {
+                report_budget_coverage(budget, BudgetCoverage { covered, total });
             }
-            all_matches
+            (all_matches, files, skipped)
         }
     } else {
-        scanner.scan(&path)?
+        scan_counted(&scanner, &path)?
     };
+    if let (Some(budget), Some(coverage)) = (&budget, scanner.budget_coverage()) {
+        report_budget_coverage(budget, coverage);
+    }
 
     let severity_counts = count_by_severity(&matches);
     let critical_count = severity_counts.get("Critical").unwrap_or(&0);
     let high_count = severity_counts.get("High").unwrap_or(&0);
+    print_summary(&ScanStatistics {
+        files,
+        skipped,
+        matches: matches.len(),
+        critical: *critical_count,
+        duration_ms: start.elapsed().as_millis() as u64,
+        ..ScanStatistics::default()
+    });
 
     if *critical_count > 0 {
         eprintln!(
-            "❌ Pre-commit check FAILED: {} critical issues",
+            "{} Pre-commit check FAILED: {} critical issues",
+            Icon::Failure,
             critical_count
         );
-        for m in matches.iter().filter(|m| is_critical_severity(&m.pattern)) {
-            eprintln!("  {} [{}] {}", m.file_path, m.pattern.red(), m.message);
+        if !printed {
+            for m in matches.iter().filter(|m| severity_of(m) == "Critical") {
+                eprintln!("  {} [{}] {}", m.file_path, m.pattern.red(), m.message);
+            }
         }
-        return Err(anyhow::anyhow!(
+        return Err(CliError::ThresholdExceeded(format!(
             "Pre-commit check failed: {} critical issues found",
             critical_count
-        ));
+        ))
+        .into());
     }
 
     if *high_count > 0 {
         println!(
-            "⚠️  {} high severity issues found (warnings only)",
+            "{} {} high severity issues found (warnings only)",
+            Icon::Warning,
             high_count
         );
-        for m in matches.iter().filter(|m| is_high_severity(&m.pattern)) {
-            println!("  {} [{}] {}", m.file_path, m.pattern.yellow(), m.message);
+        if !printed {
+            for m in matches.iter().filter(|m| severity_of(m) == "High") {
+                println!("  {} [{}] {}", m.file_path, m.pattern.yellow(), m.message);
+            }
         }
     }
 
-    println!("✅ Pre-commit check passed");
+    println!("{} Pre-commit check passed", Icon::Success);
     Ok(())
 }
 
@@ -185,12 +290,23 @@ pub fn handle_ci_gate(
     output: Option<PathBuf>,
     max_critical: u32,
     max_high: u32,
+    custom_detectors: Option<PathBuf>,
+    upload: Option<String>,
 ) -> Result<()> {
-    println!("🚦 {} CI/CD Gate", "Code-Guardian".bold().green());
+    let artifact_store = upload
+        .as_deref()
+        .map(ArtifactStore::parse)
+        .transpose()
+        .map_err(|e| CliError::Usage(e.to_string()))?;
+    println!(
+        "{} {} CI/CD Gate",
+        Icon::Gate,
+        "Code-Guardian".bold().green()
+    );
 
-    let detectors = DetectorFactory::create_production_ready_detectors();
+    let detectors = production_detectors(custom_detectors.as_deref())?;
     let scanner = Scanner::new(detectors);
-    let matches = scanner.scan(&path)?;
+    let (matches, metrics) = scanner.scan_with_metrics(&path)?;
 
     let severity_counts = count_by_severity(&matches);
     let critical_count = *severity_counts.get("Critical").unwrap_or(&0) as u32;
@@ -214,7 +330,8 @@ pub fn handle_ci_gate(
             "column": m.column,
             "pattern": m.pattern,
             "message": m.message,
-            "severity": get_severity_for_pattern(&m.pattern)
+            "severity": severity_of(m),
+            "category": m.category
         })).collect::<Vec<_>>()
     });
 
@@ -222,31 +339,45 @@ pub fn handle_ci_gate(
 
     if let Some(output_path) = output {
         fs::write(&output_path, &json_output)?;
-        println!("📄 CI report saved to: {}", output_path.display());
+        println!(
+            "{} CI report saved to: {}",
+            Icon::File,
+            output_path.display()
+        );
+    }
+    if let Some(store) = &artifact_store {
+        for (name, url) in upload_artifacts(store, &[("ci-report.json", json_output)]) {
+            println!("{} Uploaded {}: {}", Icon::Package, name, url);
+        }
     }
 
     // Print summary
-    println!("📊 Results:");
+    println!("{} Results:", Icon::Stats);
     println!("  Critical: {}/{}", critical_count, max_critical);
     println!("  High: {}/{}", high_count, max_high);
+    print_summary(&production_statistics(&matches, &metrics));
 
     if critical_count > max_critical {
-        return Err(anyhow::anyhow!(
-            "❌ CI Gate FAILED: Too many critical issues ({} > {})",
+        return Err(CliError::ThresholdExceeded(format!(
+            "{} CI Gate FAILED: Too many critical issues ({} > {})",
+            Icon::Failure,
             critical_count,
             max_critical
-        ));
+        ))
+        .into());
     }
 
     if high_count > max_high {
-        return Err(anyhow::anyhow!(
-            "❌ CI Gate FAILED: Too many high severity issues ({} > {})",
+        return Err(CliError::ThresholdExceeded(format!(
+            "{} CI Gate FAILED: Too many high severity issues ({} > {})",
+            Icon::Failure,
             high_count,
             max_high
-        ));
+        ))
+        .into());
     }
 
-    println!("✅ CI Gate PASSED");
+    println!("{} CI Gate PASSED", Icon::Success);
     Ok(())
 }
 
@@ -257,23 +388,37 @@ pub fn handle_lang_scan(
     format: String,
     production: bool,
 ) -> Result<()> {
-    println!(
-        "🌍 {} Language-Specific Scan",
-        "Code-Guardian".bold().magenta()
-    );
-    println!("🎯 Languages: {}", languages.join(", "));
-
-    let extensions = map_languages_to_extensions(&languages);
-    println!("📁 File extensions: {}", extensions.join(", "));
-
     let detectors = if production {
         DetectorFactory::create_production_ready_detectors()
     } else {
         DetectorFactory::create_comprehensive_detectors()
     };
+    scan_languages(languages, path, format, detectors)
+}
+
+/// Scans the files of `languages` under `path` with `detectors`
+pub fn scan_languages(
+    languages: Vec<String>,
+    path: PathBuf,
+    format: String,
+    detectors: Vec<Box<dyn PatternDetector>>,
+) -> Result<()> {
+    println!(
+        "{} {} Language-Specific Scan",
+        Icon::Globe,
+        "Code-Guardian".bold().magenta()
+    );
+    println!("{} Languages: {}", Icon::Target, languages.join(", "));
+
+    let extensions = map_languages_to_extensions(&languages);
+    println!(
+        "{} File extensions: {}",
+        Icon::Folder,
+        extensions.join(", ")
+    );
 
     let scanner = Scanner::new(detectors);
-    let all_matches = scanner.scan(&path)?;
+    let (all_matches, metrics) = scanner.scan_with_metrics(&path)?;
 
     // Filter matches to only include specified language extensions
     let filtered_matches: Vec<Match> = all_matches
@@ -301,6 +446,7 @@ pub fn handle_lang_scan(
             println!("{}", text_output);
         }
     }
+    print_summary(&production_statistics(&filtered_matches, &metrics));
 
     Ok(())
 }
@@ -312,19 +458,51 @@ pub fn handle_watch(
     _exclude: Vec<String>,
     _delay: u64,
 ) -> Result<()> {
-    println!("👁️  {} File Watching", "Code-Guardian".bold().cyan());
-    println!("⚠️  File watching feature coming soon!");
-    println!("📖 This will enable real-time scanning as you edit files");
+    println!(
+        "{} {} File Watching",
+        Icon::Watch,
+        "Code-Guardian".bold().cyan()
+    );
+    println!("{} File watching feature coming soon!", Icon::Warning);
+    println!(
+        "{} This will enable real-time scanning as you edit files",
+        Icon::Docs
+    );
     Ok(())
 }
 
 // Helper functions
 
+/// Tracked findings of `db` that are still in the code, counted per file.
+/// Without a database every file counts as clean.
+fn finding_density(db: Option<PathBuf>) -> FindingDensity {
+    let db_path = get_db_path(db);
+    if !db_path.exists() {
+        return FindingDensity::default();
+    }
+    SqliteScanRepository::new(&db_path)
+        .map(|repo| tracked_density(&repo))
+        .unwrap_or_default()
+}
+
+/// One line per finding of a pre-commit check, colored by severity
+fn print_finding(m: &Match) {
+    let pattern = match severity_of(m).as_str() {
+        "Critical" => m.pattern.red(),
+        "High" => m.pattern.yellow(),
+        _ => m.pattern.normal(),
+    };
+    println!(
+        "  {}:{} [{}] {}",
+        m.file_path, m.line_number, pattern, m.message
+    );
+}
+
 fn filter_by_severity(matches: Vec<Match>, severity_filter: &[String]) -> Vec<Match> {
     matches
         .into_iter()
         .filter(|m| {
-            let severity = get_severity_for_pattern(&m.pattern);
+            let severity = severity_of(m);
             severity_filter.contains(&severity)
         })
         .collect()
@@ -333,13 +511,12 @@ fn filter_by_severity(matches: Vec<Match>, severity_filter: &[String]) -> Vec<Ma
 fn count_by_severity(matches: &[Match]) -> HashMap<String, usize> {
     let mut counts = HashMap::new();
     for m in matches {
-        let severity = get_severity_for_pattern(&m.pattern);
-        *counts.entry(severity).or_insert(0) += 1;
+        *counts.entry(severity_of(m)).or_insert(0) += 1;
     }
     counts
 }
 
-fn get_severity_for_pattern(pattern: &str) -> String {
+pub(crate) fn get_severity_for_pattern(pattern: &str) -> String {
     match pattern {
         "DEBUGGER" => "Critical",
         "DEV" | "STAGING" | "CONSOLE_LOG" | "ALERT" => "High",
@@ -350,12 +527,43 @@ fn get_severity_for_pattern(pattern: &str) -> String {
     .to_string()
 }
 
-fn is_critical_severity(pattern: &str) -> bool {
-    matches!(pattern, "DEBUGGER")
+/// Statistics of a scan whose `matches` are rated by [`severity_of`], so
+/// findings without a severity of their own count at their pattern's default
+fn production_statistics(matches: &[Match], metrics: &ScanMetrics) -> ScanStatistics {
+    ScanStatistics {
+        critical: matches
+            .iter()
+            .filter(|m| severity_of(m) == "Critical")
+            .count(),
+        ..ScanStatistics::new(matches, metrics)
+    }
+}
+
+/// Matches of a scan of `path`, with the number of files scanned and skipped
+fn scan_counted(scanner: &Scanner, path: &Path) -> Result<(Vec<Match>, usize, usize)> {
+    let (matches, metrics) = scanner.scan_with_metrics(path)?;
+    let statistics = ScanStatistics::new(&matches, &metrics);
+    Ok((matches, statistics.files, statistics.skipped))
+}
+
+/// Severity of a finding: the one its detector set, such as a custom
+/// detector's configured severity, or else the built-in one of its pattern
+pub(crate) fn severity_of(m: &Match) -> String {
+    match &m.severity {
+        Some(severity) => format!("{:?}", severity),
+        None => get_severity_for_pattern(&m.pattern),
+    }
 }
 
-fn is_high_severity(pattern: &str) -> bool {
-    matches!(pattern, "DEV" | "STAGING" | "CONSOLE_LOG" | "ALERT")
+/// Production-ready detectors plus any custom detectors defined in `custom_detectors`
+fn production_detectors(custom_detectors: Option<&Path>) -> Result<Vec<Box<dyn PatternDetector>>> {
+    let mut detectors = DetectorFactory::create_production_ready_detectors();
+    if let Some(path) = custom_detectors {
+        let mut manager = CustomDetectorManager::new();
+        manager.load_from_file(path)?;
+        detectors.extend(manager.get_detectors());
+    }
+    Ok(detectors)
 }
 
 fn map_languages_to_extensions(languages: &[String]) -> Vec<String> {
@@ -374,7 +582,9 @@ fn map_languages_to_extensions(languages: &[String]) -> Vec<String> {
             "kt" | "kotlin" => extensions.push("kt"),
             "swift" => extensions.push("swift"),
             "dart" => extensions.push("dart"),
-            "cpp" | "c++" => extensions.extend_from_slice(&["cpp", "cxx", "cc"]),
+            "cpp" | "c++" => {
+                extensions.extend_from_slice(&["cpp", "cxx", "cc", "hpp", "hh", "hxx"])
+            }
             "c" => extensions.extend_from_slice(&["c", "h"]),
             "vue" => extensions.push("vue"),
             "svelte" => extensions.push("svelte"),
@@ -398,17 +608,21 @@ fn generate_json_output(
 
 fn generate_summary_output(matches: &[Match], severity_counts: &HashMap<String, usize>) -> String {
     let mut output = String::new();
-    output.push_str(&format!("📊 {} Summary\n", "Code-Guardian".bold()));
+    output.push_str(&format!(
+        "{} {} Summary\n",
+        Icon::Stats,
+        "Code-Guardian".bold()
+    ));
     output.push_str(&format!("Total Issues: {}\n", matches.len()));
 
     for (severity, count) in severity_counts {
-        let icon = match severity.as_str() {
-            "Critical" => "🔴",
-            "High" => "🟠",
-            "Medium" => "🟡",
-            "Low" => "🟢",
-            _ => "⚪",
-        };
+        let icon = Icon::Severity(match severity.as_str() {
+            "Critical" => Severity::Critical,
+            "High" => Severity::High,
+            "Medium" => Severity::Medium,
+            "Low" => Severity::Low,
+            _ => Severity::Info,
+        });
         output.push_str(&format!("{} {}: {}\n", icon, severity, count));
     }
     output
@@ -421,7 +635,8 @@ fn generate_production_text_output(
     let mut output = String::new();
 
     output.push_str(&format!(
-        "🔍 {} Production Readiness Report\n\n",
+        "{} {} Production Readiness Report\n\n",
+        Icon::Search,
         "Code-Guardian".bold().blue()
     ));
 
@@ -432,114 +647,67 @@ fn generate_production_text_output(
     let mut low_issues = Vec::new();
 
     for m in matches {
-        match get_severity_for_pattern(&m.pattern).as_str() {
+        match severity_of(m).as_str() {
             "Critical" => critical_issues.push(m),
             "High" => high_issues.push(m),
             "Medium" => medium_issues.push(m),
-            "Low" => low_issues.push(m),
-            _ => {}
+            _ => low_issues.push(m),
         }
     }
 
-    // Display issues by severity
-    if !critical_issues.is_empty() {
-        output.push_str(&format!(
-            "🔴 {} ({}):\n",
-            "Critical Issues".red().bold(),
-            critical_issues.len()
-        ));
-        for issue in critical_issues {
-            output.push_str(&format!(
-                "├── {}:{} [{}] {}\n",
-                issue.file_path,
-                issue.line_number,
-                issue.pattern.red(),
-                issue.message
-            ));
+    // Display issues by severity; low severity is limited to the first 5
+    let sections = [
+        (Severity::Critical, "Critical Issues", critical_issues, None),
+        (Severity::High, "High Severity", high_issues, None),
+        (Severity::Medium, "Medium Severity", medium_issues, None),
+        (Severity::Low, "Low Severity", low_issues, Some(5)),
+    ];
+    for (severity, title, issues, limit) in sections {
+        if issues.is_empty() {
+            continue;
         }
-        output.push('\n');
-    }
-
-    if !high_issues.is_empty() {
         output.push_str(&format!(
-            "🟠 {} ({}):\n",
-            "High Severity".yellow().bold(),
-            high_issues.len()
+            "{} {} ({}):\n",
+            Icon::Severity(severity.clone()),
+            paint_severity(title, &severity).bold(),
+            issues.len()
         ));
-        for issue in high_issues {
+        let shown = limit.unwrap_or(issues.len());
+        for issue in issues.iter().take(shown) {
             output.push_str(&format!(
-                "├── {}:{} [{}] {}\n",
+                "{} {}:{} [{}] {}\n",
+                Icon::Branch,
                 issue.file_path,
                 issue.line_number,
-                issue.pattern.yellow(),
+                paint_severity(&issue.pattern, &severity),
                 issue.message
             ));
         }
-        output.push('\n');
-    }
-
-    if !medium_issues.is_empty() {
-        output.push_str(&format!(
-            "🟡 {} ({}):\n",
-            "Medium Severity".cyan().bold(),
-            medium_issues.len()
-        ));
-        for issue in medium_issues {
+        if issues.len() > shown {
             output.push_str(&format!(
-                "├── {}:{} [{}] {}\n",
-                issue.file_path,
-                issue.line_number,
-                issue.pattern.cyan(),
-                issue.message
-            ));
-        }
-        output.push('\n');
-    }
-
-    if !low_issues.is_empty() {
-        output.push_str(&format!(
-            "🟢 {} ({}):\n",
-            "Low Severity".green().bold(),
-            low_issues.len()
-        ));
-        for issue in low_issues.iter().take(5) {
-            // Limit low severity to first 5
-            output.push_str(&format!(
-                "├── {}:{} [{}] {}\n",
-                issue.file_path,
-                issue.line_number,
-                issue.pattern.green(),
-                issue.message
-            ));
-        }
-        if low_issues.len() > 5 {
-            output.push_str(&format!(
-                "└── ... and {} more low severity issues\n",
-                low_issues.len() - 5
+                "{} ... and {} more low severity issues\n",
+                Icon::LastBranch,
+                issues.len() - shown
             ));
         }
         output.push('\n');
     }
 
     // Summary
-    output.push_str("📊 Summary:\n");
-    output.push_str(&format!("• Total Issues: {}\n", matches.len()));
-    output.push_str(&format!(
-        "• Critical: {}\n",
-        severity_counts.get("Critical").unwrap_or(&0)
-    ));
-    output.push_str(&format!(
-        "• High: {}\n",
-        severity_counts.get("High").unwrap_or(&0)
-    ));
+    output.push_str(&format!("{} Summary:\n", Icon::Stats));
     output.push_str(&format!(
-        "• Medium: {}\n",
-        severity_counts.get("Medium").unwrap_or(&0)
-    ));
-    output.push_str(&format!(
-        "• Low: {}\n",
-        severity_counts.get("Low").unwrap_or(&0)
+        "{} Total Issues: {}\n",
+        Icon::Bullet,
+        matches.len()
     ));
+    for severity in ["Critical", "High", "Medium", "Low"] {
+        output.push_str(&format!(
+            "{} {}: {}\n",
+            Icon::Bullet,
+            severity,
+            severity_counts.get(severity).unwrap_or(&0)
+        ));
+    }
 
     output
 }
@@ -571,21 +739,22 @@ mod tests {
     }
 
     #[test]
-    fn test_is_critical_severity() {
-        assert!(is_critical_severity("DEBUGGER"));
-        assert!(!is_critical_severity("CONSOLE_LOG"));
-        assert!(!is_critical_severity("PRINT"));
-        assert!(!is_critical_severity("TODO"));
-    }
-
-    #[test]
-    fn test_is_high_severity() {
-        assert!(is_high_severity("DEV"));
-        assert!(is_high_severity("STAGING"));
-        assert!(is_high_severity("CONSOLE_LOG"));
-        assert!(is_high_severity("ALERT"));
-        assert!(!is_high_severity("DEBUGGER"));
-        assert!(!is_high_severity("PRINT"));
+    fn test_severity_of_prefers_detector_severity() {
+        let mut m = Match {
+            file_path: "app.js".into(),
+            line_number: 1,
+            column: 1,
+            pattern: "DEBUGGER".into(),
+            message: "debugger".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
+        };
+        assert_eq!(severity_of(&m), "Critical");
+        m.severity = Some(code_guardian_core::Severity::Low);
+        assert_eq!(severity_of(&m), "Low");
+        assert_eq!(count_by_severity(&[m]).get("Low"), Some(&1));
     }
 
     #[test]
@@ -626,25 +795,37 @@ mod tests {
 
         let matches = vec![
             Match {
-                file_path: "test1.rs".to_string(),
+                file_path: "test1.rs".into(),
                 line_number: 1,
                 column: 1,
-                pattern: "DEBUGGER".to_string(),
+                pattern: "DEBUGGER".into(),
                 message: "Debugger found".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
             Match {
-                file_path: "test2.rs".to_string(),
+                file_path: "test2.rs".into(),
                 line_number: 2,
                 column: 1,
-                pattern: "CONSOLE_LOG".to_string(),
+                pattern: "CONSOLE_LOG".into(),
                 message: "Console log found".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
             Match {
-                file_path: "test3.rs".to_string(),
+                file_path: "test3.rs".into(),
                 line_number: 3,
                 column: 1,
-                pattern: "PRINT".to_string(),
+                pattern: "PRINT".into(),
                 message: "Print statement found".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
         ];
 
@@ -674,39 +855,59 @@ mod tests {
 
         let matches = vec![
             Match {
-                file_path: "test1.rs".to_string(),
+                file_path: "test1.rs".into(),
                 line_number: 1,
                 column: 1,
-                pattern: "DEBUGGER".to_string(),
+                pattern: "DEBUGGER".into(),
                 message: "Debugger found".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
             Match {
-                file_path: "test2.rs".to_string(),
+                file_path: "test2.rs".into(),
                 line_number: 2,
                 column: 1,
-                pattern: "DEV".to_string(),
+                pattern: "DEV".into(),
                 message: "Dev marker found".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
             Match {
-                file_path: "test3.rs".to_string(),
+                file_path: "test3.rs".into(),
                 line_number: 3,
                 column: 1,
-                pattern: "CONSOLE_LOG".to_string(),
+                pattern: "CONSOLE_LOG".into(),
                 message: "Console log found".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
             Match {
-                file_path: "test4.rs".to_string(),
+                file_path: "test4.rs".into(),
                 line_number: 4,
                 column: 1,
-                pattern: "PRINT".to_string(),
+                pattern: "PRINT".into(),
                 message: "Print statement found".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
             Match {
-                file_path: "test5.rs".to_string(),
+                file_path: "test5.rs".into(),
                 line_number: 5,
                 column: 1,
-                pattern: "TODO".to_string(),
+                pattern: "TODO".into(),
                 message: "Todo found".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
         ];
 
@@ -752,30 +953,6 @@ mod tests {
         }
     }
 
-    #[test]
-    fn test_is_high_severity_comprehensive() {
-        assert!(is_high_severity("DEV"));
-        assert!(is_high_severity("STAGING"));
-        assert!(is_high_severity("CONSOLE_LOG"));
-        assert!(is_high_severity("ALERT"));
-
-        assert!(!is_high_severity("DEBUGGER"));
-        assert!(!is_high_severity("PRINT"));
-        assert!(!is_high_severity("TODO"));
-        assert!(!is_high_severity("UNKNOWN"));
-    }
-
-    #[test]
-    fn test_is_critical_severity_comprehensive() {
-        assert!(is_critical_severity("DEBUGGER"));
-
-        assert!(!is_critical_severity("DEV"));
-        assert!(!is_critical_severity("CONSOLE_LOG"));
-        assert!(!is_critical_severity("PRINT"));
-        assert!(!is_critical_severity("TODO"));
-        assert!(!is_critical_severity("UNKNOWN"));
-    }
-
     #[test]
     fn test_map_languages_to_extensions_all_languages() {
         let test_cases = vec![
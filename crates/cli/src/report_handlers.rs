@@ -1,26 +1,289 @@
 use anyhow::Result;
+use code_guardian_core::{
+    group_issues, summarize_by_control, Icon, IssueGroup, Match, TuningReport,
+};
 use code_guardian_output::formatters::{
-    CsvFormatter, Formatter, HtmlFormatter, JsonFormatter, MarkdownFormatter, TextFormatter,
+    CsvFormatter, DefectDojoFormatter, Formatter, HtmlFormatter, JsonFormatter, MarkdownFormatter,
+    SarifFormatter, SonarQubeFormatter, TextFormatter,
 };
-use code_guardian_storage::{ScanRepository, SqliteScanRepository};
-use std::path::PathBuf;
+use code_guardian_storage::{MatchFilter, ScanRepository, SqliteScanRepository};
+use std::path::{Path, PathBuf};
 
+use crate::cli_definitions::CliError;
 use crate::utils::get_db_path;
 
-pub fn handle_report(id: i64, format: String, db: Option<PathBuf>) -> Result<()> {
+/// Which matches of a scan `report` prints. Without a limit or filter the
+/// whole scan is loaded at once.
+#[derive(Debug, Clone, Default)]
+pub struct ReportPage {
+    pub offset: usize,
+    pub limit: Option<usize>,
+    pub filter: MatchFilter,
+}
+
+impl ReportPage {
+    fn is_whole_scan(&self) -> bool {
+        self.offset == 0 && self.limit.is_none() && self.filter.is_empty()
+    }
+}
+
+pub fn handle_report(
+    id: i64,
+    format: String,
+    db: Option<PathBuf>,
+    page: ReportPage,
+    group: bool,
+    project: Option<&str>,
+) -> Result<()> {
     let formatter = get_formatter(&format)?;
+    if page.limit == Some(0) {
+        return Err(CliError::Usage("--limit must be greater than 0".to_string()).into());
+    }
     let db_path = get_db_path(db);
     let repo = SqliteScanRepository::new(&db_path)?;
-    let scan = repo.get_scan(id)?;
-    match scan {
-        Some(scan) => {
-            println!("{}", formatter.format(&scan.matches));
+    ensure_project(&repo, id, project)?;
+    // On stderr so the formatted report can still be piped
+    if let Some(ci_run) = repo.get_ci_run(id)? {
+        eprintln!("Produced by {}", ci_run);
+    }
+    if let Some(reason) = repo.get_quarantine(id)? {
+        eprintln!("{} Quarantined: {}", Icon::Warning, reason);
+    }
+    if group {
+        let matches = if page.filter.is_empty() {
+            repo.get_scan(id)?.map(|scan| scan.matches)
+        } else {
+            repo.get_matches(id, 0, usize::MAX, &page.filter)?
+                .map(|matches| matches.matches)
+        };
+        match matches {
+            Some(matches) => print_issues(&matches, &format, formatter.as_ref())?,
+            None => println!("Scan with ID {} not found.", id),
+        }
+        return Ok(());
+    }
+    if page.is_whole_scan() {
+        match repo.get_scan(id)? {
+            Some(scan) => println!("{}", formatter.format(&scan.matches)),
+            None => println!("Scan with ID {} not found.", id),
+        }
+        return Ok(());
+    }
+
+    let limit = page.limit.unwrap_or(usize::MAX);
+    match repo.get_matches(id, page.offset, limit, &page.filter)? {
+        Some(matches) => {
+            println!("{}", formatter.format(&matches.matches));
+            // On stderr so the formatted report can still be piped
+            if matches.matches.is_empty() {
+                eprintln!("No matches at offset {} of {}", page.offset, matches.total);
+            } else {
+                eprintln!(
+                    "Showing matches {}-{} of {}{}",
+                    page.offset + 1,
+                    page.offset + matches.matches.len(),
+                    matches.total,
+                    if matches.has_more() {
+                        format!(
+                            " (next page: --offset {})",
+                            page.offset + matches.matches.len()
+                        )
+                    } else {
+                        String::new()
+                    }
+                );
+            }
         }
         None => println!("Scan with ID {} not found.", id),
     }
     Ok(())
 }
 
+/// Prints `matches` grouped into issues, see [`group_issues`]. JSON lists
+/// the issues with their occurrence counts; other formats list the first
+/// finding of each issue, its message noting the occurrences.
+fn print_issues(matches: &[Match], format: &str, formatter: &dyn Formatter) -> Result<()> {
+    let issues = group_issues(matches);
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&issues)?);
+    } else {
+        let firsts: Vec<Match> = issues.iter().map(IssueGroup::to_match).collect();
+        println!("{}", formatter.format(&firsts));
+    }
+    // On stderr so the formatted report can still be piped
+    eprintln!(
+        "Grouped {} findings into {} issues",
+        matches.len(),
+        issues.len()
+    );
+    Ok(())
+}
+
+/// Fails if scan `id` exists but doesn't belong to `project`, so a report
+/// asked for one project never shows the findings of another
+fn ensure_project(repo: &SqliteScanRepository, id: i64, project: Option<&str>) -> Result<()> {
+    let Some(project) = project else {
+        return Ok(());
+    };
+    let owner = repo
+        .get_all_scans()?
+        .into_iter()
+        .find(|scan| scan.id == Some(id))
+        .map(|scan| scan.project);
+    match owner {
+        Some(owner) if owner.as_deref() != Some(project) => Err(CliError::Usage(format!(
+            "scan {} belongs to {}, not to project '{}'",
+            id,
+            owner.map_or("no project".to_string(), |owner| format!(
+                "project '{}'",
+                owner
+            )),
+            project
+        ))
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+/// Prints the findings of scan `id` per compliance control, see
+/// [`summarize_by_control`]
+pub fn handle_compliance_report(
+    id: i64,
+    format: &str,
+    db: Option<PathBuf>,
+    project: Option<&str>,
+) -> Result<()> {
+    if !matches!(format, "text" | "json") {
+        return Err(CliError::Usage(format!(
+            "--by-compliance supports the text and json formats, not {}",
+            format
+        ))
+        .into());
+    }
+    let repo = SqliteScanRepository::new(get_db_path(db))?;
+    ensure_project(&repo, id, project)?;
+    let Some(scan) = repo.get_scan(id)? else {
+        println!("Scan with ID {} not found.", id);
+        return Ok(());
+    };
+    let (controls, unmapped) = summarize_by_control(&scan.matches);
+    if format == "json" {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "controls": controls,
+                "unmapped": unmapped,
+            }))?
+        );
+        return Ok(());
+    }
+    println!("{} Findings by compliance control, scan {}", Icon::List, id);
+    let width = controls
+        .iter()
+        .map(|summary| summary.control.len())
+        .max()
+        .unwrap_or(0);
+    for summary in &controls {
+        println!(
+            "  {:width$}  {:>5}  {}",
+            summary.control,
+            summary.findings,
+            summary.rules.iter().cloned().collect::<Vec<_>>().join(", "),
+            width = width
+        );
+    }
+    if controls.is_empty() {
+        println!("  No findings map to a compliance control");
+    }
+    println!("  {} findings map to no control", unmapped);
+    Ok(())
+}
+
+/// Prints the `top` rules of scan `id` with the most findings, where they
+/// come from, and the config that would quiet them
+pub fn handle_tuning_report(
+    id: i64,
+    format: &str,
+    db: Option<PathBuf>,
+    top: usize,
+    project: Option<&str>,
+) -> Result<()> {
+    if !matches!(format, "text" | "json") {
+        return Err(CliError::Usage(format!(
+            "--tune supports the text and json formats, not {}",
+            format
+        ))
+        .into());
+    }
+    let repo = SqliteScanRepository::new(get_db_path(db))?;
+    ensure_project(&repo, id, project)?;
+    let Some(scan) = repo.get_scan(id)? else {
+        println!("Scan with ID {} not found.", id);
+        return Ok(());
+    };
+    let report = TuningReport::new(&scan.matches, Path::new(&scan.root_path), top);
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!(
+        "{} Noisiest rules of scan {}, {} findings",
+        Icon::Stats,
+        id,
+        report.total_matches
+    );
+    for (rank, rule) in report.rules.iter().enumerate() {
+        println!(
+            "  {:>2}. {}: {} ({:.1}%) in {} files, {:?}",
+            rank + 1,
+            rule.rule,
+            rule.matches,
+            100.0 * rule.matches as f64 / report.total_matches as f64,
+            rule.files,
+            rule.severity
+        );
+        let directories: Vec<String> = rule
+            .directories
+            .iter()
+            .map(|dir| {
+                let path = if dir.path.is_empty() { "." } else { &dir.path };
+                format!("{} ({})", path, dir.matches)
+            })
+            .collect();
+        println!("      {}", directories.join(", "));
+    }
+    if report.rules.is_empty() {
+        println!("  No findings");
+        return Ok(());
+    }
+    if report.suggestions.is_empty() {
+        println!(
+            "\n{} No tuning suggested: no rule's findings gather in test, example or vendored code or in a single file, and none floods the scan",
+            Icon::Tip
+        );
+        return Ok(());
+    }
+    println!(
+        "\n{} Suggested config; merge into code-guardian.toml and rescan:\n",
+        Icon::Tip
+    );
+    print!("{}", report.config);
+    Ok(())
+}
+
+/// Formats [`get_formatter`] accepts
+pub const REPORT_FORMATS: &[&str] = &[
+    "text",
+    "json",
+    "csv",
+    "markdown",
+    "html",
+    "sarif",
+    "defectdojo",
+    "sonarqube",
+];
+
 pub fn get_formatter(format: &str) -> Result<Box<dyn Formatter>> {
     match format {
         "text" => Ok(Box::new(TextFormatter)),
@@ -28,6 +291,9 @@ pub fn get_formatter(format: &str) -> Result<Box<dyn Formatter>> {
         "csv" => Ok(Box::new(CsvFormatter)),
         "markdown" => Ok(Box::new(MarkdownFormatter)),
         "html" => Ok(Box::new(HtmlFormatter)),
-        _ => Err(anyhow::anyhow!("Unsupported format: {}", format)),
+        "sarif" => Ok(Box::new(SarifFormatter)),
+        "defectdojo" => Ok(Box::new(DefectDojoFormatter)),
+        "sonarqube" => Ok(Box::new(SonarQubeFormatter)),
+        _ => Err(CliError::Usage(format!("Unsupported format: {}", format)).into()),
     }
 }
@@ -1,52 +1,257 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use code_guardian_core::{
-    config::load_config, CustomDetectorManager, DistributedCoordinator, IncrementalScanner,
-    OptimizedScanner, Scanner, StreamingScanner, WorkerConfig,
+    config::{load_config, Config},
+    fire_triggers, invalid_excluded_dir, merge_matches, override_severities, read_stable,
+    scan_walker_with, select_targets, validate_triggers, validate_webhook_url, windows_paths,
+    ArtifactStore, Author, CacheKey, CiRun, CustomDetectorManager, DetectorSchedule,
+    DistributedCoordinator, EnhancedScanConfig, ExcludedDirs, FileLengthDetector, FindingTrigger,
+    FiredTrigger, FunctionLengthDetector, GeneratedFileFilter, HardcodedStringDetector, Icon,
+    IncrementalScanner, LlmTriage, MagicNumberDetector, Match, MatchLimits, MigrationRiskDetector,
+    OptimizedScanner, OrphanTodoDetector, PatternDetector, PiiDetector, RuleScopes, ScanBudget,
+    ScanCache, ScanCompletedPayload, ScanMetrics, ScanStatistics, ScanSummary, Scanner, Severity,
+    SeveritySampling, SkipReason, SkippedFile, StaleFeatureFlagDetector, StreamingScanner,
+    TestDetector, ThresholdStatus, TriggerAction, TriggerFiredPayload, WalkOptions,
+    WebhookNotifier, WorkerConfig, DEFAULT_ISSUE_REFERENCE_PATTERN, DEFAULT_TEST_PATH_GLOBS,
+    MAX_SCANNED_FILE_SIZE, SAMPLED_PATTERN, SCAN_CACHE_DIR, TRIGGER_FIRED_EVENT,
 };
-use code_guardian_output::formatters::Formatter;
-use code_guardian_storage::{Scan, ScanRepository, SqliteScanRepository};
+use code_guardian_output::formatters::{Formatter, JsonFormatter};
+use code_guardian_storage::{validate_project, Scan, ScanRepository, SqliteScanRepository};
 use indicatif::ProgressBar;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use crate::utils::get_detectors_from_profile;
+use crate::cli_definitions::CliError;
+use crate::git_integration::{BlameInfo, GitIntegration};
+use crate::utils;
+use crate::utils::{
+    get_detectors_from_profile, print_summary, report_budget_coverage, tracked_density,
+    DETECTOR_PROFILES,
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Default)]
 pub struct ScanOptions {
     pub path: PathBuf,
+    pub stdin: bool,
+    pub stdin_filename: Option<PathBuf>,
     pub db: Option<PathBuf>,
+    /// Project the scan is saved under, see [`Scan::project`]
+    pub project: Option<String>,
     pub config_path: Option<PathBuf>,
     pub profile: String,
     pub show_progress: bool,
     pub optimize: bool,
     pub streaming: bool,
     pub show_metrics: bool,
+    pub metrics_out: Option<PathBuf>,
+    pub upload: Option<String>,
+    pub webhooks: Vec<String>,
+    pub llm_triage: bool,
     pub incremental: bool,
+    /// Reuse the listings of unchanged directories, see
+    /// [`code_guardian_core::DirectoryCache`]
+    pub prune_dirs: bool,
     pub distributed: bool,
+    /// Skip detectors with no findings in earlier scans, see [`DetectorSchedule::fast`]
+    pub fast: bool,
+    /// Stop scanning files once used up, scanning the likeliest first
+    pub budget: Option<ScanBudget>,
     pub custom_detectors: Option<PathBuf>,
+    pub strict_rules: bool,
+    pub rule_timeout_ms: Option<u64>,
     pub cache_size: Option<usize>,
+    pub cache_key: Option<CacheKey>,
+    pub cache_dir: Option<PathBuf>,
     pub batch_size: Option<usize>,
     pub max_file_size: Option<usize>,
     pub max_threads: Option<usize>,
+    pub max_matches_per_file: Option<usize>,
+    pub max_matches_per_pattern: Option<usize>,
+    pub sample_above: Option<usize>,
+    pub sample_per_file: Option<usize>,
+    pub include_generated: bool,
+    pub follow_symlinks: bool,
+    pub max_depth: Option<usize>,
+    pub report_skipped: bool,
+    pub list_files: bool,
+    pub null_delimited: bool,
+    pub require_issue_ref: bool,
+    pub issue_pattern: Option<String>,
+    pub fail_on_stale_todos: Option<u64>,
+    pub blame: bool,
+    pub group_by: Option<String>,
+    /// Print the plan of the scan instead of running it, see [`print_scan_plan`]
+    pub dry_run: bool,
 }
 
-pub async fn handle_scan(options: ScanOptions) -> Result<()> {
-    if !options.path.exists() {
-        return Err(anyhow::anyhow!(
-            "Path '{}' does not exist",
-            options.path.display()
-        ));
+/// Builds the profile detectors plus any custom and orphan-TODO detectors
+/// requested by the scan options, applying the settings of configurable
+/// detectors from `config`. `announce` prints what was loaded to stdout.
+fn build_scan_detectors(
+    options: &ScanOptions,
+    config: &Config,
+    announce: bool,
+) -> Result<Vec<Box<dyn PatternDetector>>> {
+    // Load custom detectors if specified
+    let mut custom_detector_manager = CustomDetectorManager::new();
+    custom_detector_manager.set_strict_rules(options.strict_rules);
+    if let Some(timeout_ms) = options.rule_timeout_ms {
+        custom_detector_manager.set_match_timeout(std::time::Duration::from_millis(timeout_ms));
+    }
+    if let Some(custom_path) = &options.custom_detectors {
+        custom_detector_manager.load_from_file(custom_path)?;
+        if announce {
+            println!(
+                "{} Loaded custom detectors from {}",
+                Icon::Folder,
+                custom_path.display()
+            );
+        }
+    }
+
+    // Create scanner based on profile
+    let mut detectors = get_detectors_from_profile(&options.profile);
+    for detector in detectors.iter_mut() {
+        match detector.name() {
+            "MagicNumberDetector" => {
+                *detector = Box::new(MagicNumberDetector::new(&config.magic_numbers));
+            }
+            "FileLengthDetector" => {
+                *detector = Box::new(FileLengthDetector::new(&config.length_limits));
+            }
+            "FunctionLengthDetector" => {
+                *detector = Box::new(FunctionLengthDetector::new(&config.length_limits));
+            }
+            "MigrationRiskDetector" => {
+                *detector = Box::new(MigrationRiskDetector::new(&config.migrations)?);
+            }
+            "TestDetector" => {
+                let test_globs: Vec<&str> = if config.test_path_globs.is_empty() {
+                    DEFAULT_TEST_PATH_GLOBS.to_vec()
+                } else {
+                    config.test_path_globs.iter().map(String::as_str).collect()
+                };
+                let production_globs: Vec<&str> = config
+                    .production_path_globs
+                    .iter()
+                    .map(String::as_str)
+                    .collect();
+                let test_detector = TestDetector::new(&test_globs, &production_globs)?;
+                *detector = Box::new(test_detector.with_root(&options.path));
+            }
+            _ => {}
+        }
+    }
+    if config.pii.enabled {
+        detectors.push(Box::new(PiiDetector::new(&config.pii)));
+    }
+    if config.i18n.enabled {
+        detectors.push(Box::new(HardcodedStringDetector::new(&config.i18n)));
+    }
+    if let Some(detector) = StaleFeatureFlagDetector::from_config(&config.feature_flags)? {
+        detectors.push(Box::new(detector));
+    }
+
+    // Add custom detectors
+    let custom_detectors_vec = custom_detector_manager.get_detectors();
+    if !custom_detectors_vec.is_empty() {
+        let count = custom_detectors_vec.len();
+        detectors.extend(custom_detectors_vec);
+        if announce {
+            println!("{} Added {} custom detectors", Icon::Tool, count);
+        }
+    }
+
+    if options.require_issue_ref {
+        let pattern = options
+            .issue_pattern
+            .as_deref()
+            .unwrap_or(DEFAULT_ISSUE_REFERENCE_PATTERN);
+        let detector = OrphanTodoDetector::new(pattern)
+            .map_err(|e| CliError::Usage(format!("Invalid issue pattern '{}': {}", pattern, e)))?;
+        detectors.push(Box::new(detector));
+    }
+    Ok(detectors)
+}
+
+/// Default virtual path for stdin content when `--filename` is not given
+const STDIN_FILENAME: &str = "<stdin>";
+
+/// Scans stdin as the file `filename` and prints the findings as JSON.
+/// Nothing is read from or written to disk, so unsaved editor buffers can be linted.
+fn scan_stdin(
+    detectors: Vec<Box<dyn PatternDetector>>,
+    match_limits: MatchLimits,
+    rule_scopes: RuleScopes,
+    filename: Option<&Path>,
+) -> Result<()> {
+    let mut content = String::new();
+    std::io::stdin()
+        .read_to_string(&mut content)
+        .map_err(|e| CliError::Usage(format!("Failed to read stdin as UTF-8 text: {}", e)))?;
+    let filename = filename.unwrap_or_else(|| Path::new(STDIN_FILENAME));
+    let start = std::time::Instant::now();
+    let matches = Scanner::new(detectors)
+        .with_match_limits(match_limits)
+        .with_rule_scopes(rule_scopes)
+        .scan_content(&content, filename);
+    println!("{}", JsonFormatter.format(&matches));
+    print_summary(&ScanStatistics {
+        files: 1,
+        matches: matches.len(),
+        critical: matches
+            .iter()
+            .filter(|m| m.severity == Some(Severity::Critical))
+            .count(),
+        duration_ms: start.elapsed().as_millis() as u64,
+        ..ScanStatistics::default()
+    });
+    Ok(())
+}
+
+/// Patterns that mark comment debt and are subject to age tracking
+pub const COMMENT_DEBT_PATTERNS: &[&str] = &["TODO", "FIXME", "HACK", "XXX", "BUG", "ORPHAN_TODO"];
+
+pub async fn handle_scan(mut options: ScanOptions) -> Result<()> {
+    // Reported paths start with the root, so a verbatim `\\?\` prefix would
+    // show up on every one of them
+    options.path = windows_paths::simplified(&options.path).into_owned();
+    if !options.stdin && !options.path.exists() {
+        return Err(
+            CliError::Usage(format!("Path '{}' does not exist", options.path.display())).into(),
+        );
+    }
+    if let Some(project) = &options.project {
+        validate_project(project).map_err(CliError::Usage)?;
+    }
+    if let Some(group_by) = options.group_by.as_deref() {
+        if group_by != "author" {
+            return Err(CliError::Usage(format!(
+                "Unsupported --group-by '{}'. Supported: author",
+                group_by
+            ))
+            .into());
+        }
     }
-    if !options.path.is_dir() {
-        return Err(anyhow::anyhow!(
+    if !options.stdin && !options.path.is_dir() {
+        return Err(CliError::Usage(format!(
             "Path '{}' is not a directory",
             options.path.display()
-        ));
+        ))
+        .into());
     }
-    let mut config = load_config(options.config_path)?;
+    let mut config = load_config(options.config_path.as_ref())?;
     // Override config with CLI args if provided
     if let Some(val) = options.cache_size {
         config.cache_size = val;
     }
+    if let Some(val) = &options.cache_dir {
+        config.cache_dir = Some(val.to_string_lossy().to_string());
+    }
+    if let Some(val) = options.cache_key {
+        config.cache_key = val;
+    }
     if let Some(val) = options.batch_size {
         config.batch_size = val;
     }
@@ -56,31 +261,112 @@ pub async fn handle_scan(options: ScanOptions) -> Result<()> {
     if let Some(val) = options.max_threads {
         config.max_threads = val;
     }
-    let db_path = options
-        .db
-        .unwrap_or_else(|| PathBuf::from(&config.database_path));
-    let mut repo = SqliteScanRepository::new(&db_path)?;
-
-    // Load custom detectors if specified
-    let mut custom_detector_manager = CustomDetectorManager::new();
-    if let Some(custom_path) = options.custom_detectors {
-        custom_detector_manager.load_from_file(&custom_path)?;
-        println!("📁 Loaded custom detectors from {}", custom_path.display());
+    if options.max_matches_per_file.is_some() {
+        config.max_matches_per_file = options.max_matches_per_file;
+    }
+    if options.max_matches_per_pattern.is_some() {
+        config.max_matches_per_pattern = options.max_matches_per_pattern;
+    }
+    if options.sample_above.is_some() {
+        config.sample_above = options.sample_above;
+    }
+    if let Some(val) = options.sample_per_file {
+        config.sample_per_file = val;
     }
+    if options.follow_symlinks {
+        config.follow_symlinks = true;
+    }
+    if options.max_depth.is_some() {
+        config.max_depth = options.max_depth;
+    }
+    config.webhooks.extend(options.webhooks.iter().cloned());
+    for url in &config.webhooks {
+        validate_webhook_url(url).map_err(|e| CliError::Usage(e.to_string()))?;
+    }
+    let llm_triage = if options.llm_triage {
+        let endpoint = config.llm_triage_endpoint.clone().ok_or_else(|| {
+            CliError::Usage(
+                "--llm-triage needs 'llm_triage_endpoint' in the config or CODE_GUARDIAN_LLM_TRIAGE_ENDPOINT"
+                    .to_string(),
+            )
+        })?;
+        let triage = LlmTriage::new(endpoint).map_err(|e| CliError::Usage(e.to_string()))?;
+        Some(
+            triage
+                .with_api_key(config.llm_triage_api_key.clone())
+                .with_threshold(config.llm_triage_threshold),
+        )
+    } else {
+        None
+    };
+    if config.max_matches_per_file == Some(0) || config.max_matches_per_pattern == Some(0) {
+        return Err(CliError::Usage("Match limits must be greater than 0".to_string()).into());
+    }
+    let match_limits =
+        MatchLimits::new(config.max_matches_per_file, config.max_matches_per_pattern);
+    if config.sample_per_file == 0 {
+        return Err(CliError::Usage("--sample-per-file must be greater than 0".to_string()).into());
+    }
+    let sampling = config
+        .sample_above
+        .map(|threshold| SeveritySampling::new(threshold, config.sample_per_file));
+    if let Some((name, reason)) = config
+        .excluded_dirs
+        .iter()
+        .find_map(|name| invalid_excluded_dir(name).map(|reason| (name, reason)))
+    {
+        return Err(CliError::Usage(format!(
+            "Invalid excluded_dirs entry '{}': {}",
+            name, reason
+        ))
+        .into());
+    }
+    let walk_options = WalkOptions {
+        generated_filter: if options.include_generated {
+            None
+        } else {
+            let filter = GeneratedFileFilter::new(&config.generated_globs)
+                .map_err(|e| CliError::Usage(format!("Invalid generated_globs: {}", e)))?;
+            Some(Arc::new(filter))
+        },
+        excluded_dirs: Arc::new(ExcludedDirs::new(&config.excluded_dirs)),
+        follow_symlinks: config.follow_symlinks,
+        max_depth: config.max_depth,
+        respect_gitignore: config.respect_gitignore,
+        respect_global_ignore: config.respect_global_ignore,
+        hidden: config.hidden,
+    };
+    let rule_scopes =
+        RuleScopes::new(&config.rule_paths).map_err(|e| CliError::Usage(e.to_string()))?;
+    validate_scan_triggers(&config.triggers)?;
+    let artifact_store = options
+        .upload
+        .as_deref()
+        .map(ArtifactStore::parse)
+        .transpose()
+        .map_err(|e| CliError::Usage(e.to_string()))?;
 
-    // Create scanner based on profile
-    let mut detectors = get_detectors_from_profile(&options.profile);
+    if options.dry_run {
+        return print_scan_plan(&options, &config, &walk_options);
+    }
 
-    // Add custom detectors
-    let custom_detectors_vec = custom_detector_manager.get_detectors();
-    if !custom_detectors_vec.is_empty() {
-        detectors.extend(custom_detectors_vec);
-        println!(
-            "🔧 Added {} custom detectors",
-            detectors.len() - get_detectors_from_profile(&options.profile).len()
+    if options.stdin {
+        let detectors = build_scan_detectors(&options, &config, false)?;
+        return scan_stdin(
+            detectors,
+            match_limits,
+            rule_scopes,
+            options.stdin_filename.as_deref(),
         );
     }
 
+    let db_path = options
+        .db
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(&config.database_path));
+    let mut repo = SqliteScanRepository::new(&db_path)?;
+    let detectors = build_scan_detectors(&options, &config, !options.list_files)?;
+
     let pb = if options.show_progress {
         let pb = ProgressBar::new_spinner();
         pb.set_message("Scanning directory for patterns...");
@@ -89,14 +375,16 @@ pub async fn handle_scan(options: ScanOptions) -> Result<()> {
         None
     };
 
-    let (matches, scan_metrics) = if options.incremental {
+    let (matches, metrics) = if options.incremental {
         // Use incremental scanning
         if let Some(pb) = &pb {
             pb.set_message("Incremental scanning (only changed files)...");
         }
 
         let state_file = db_path.with_extension("incremental");
-        let mut incremental_scanner = IncrementalScanner::new(detectors, state_file)?;
+        let mut incremental_scanner = IncrementalScanner::new(detectors, state_file)?
+            .with_walk_options(walk_options.clone())
+            .with_directory_pruning(options.prune_dirs);
         let (matches, result) = incremental_scanner.scan_incremental(&options.path)?;
 
         // Convert incremental result to scan metrics
@@ -107,9 +395,17 @@ pub async fn handle_scan(options: ScanOptions) -> Result<()> {
             scan_duration_ms: result.scan_duration_ms,
             cache_hits: result.files_skipped,
             cache_misses: result.files_scanned,
+            cache_invalidated: 0,
+            detector_timings: Vec::new(),
+            worker_metrics: Vec::new(),
+            batch_sizing: None,
+            file_types: Vec::new(),
+            generated_files_skipped: walk_options.generated_files_skipped(),
+            skipped_files: result.skipped_files,
         };
 
-        (matches, Some(metrics))
+        let matches = rule_scopes.retain(matches, &options.path);
+        (match_limits.apply_per_file(matches), metrics)
     } else if options.distributed {
         // Use distributed scanning
         if let Some(pb) = &pb {
@@ -137,7 +433,7 @@ pub async fn handle_scan(options: ScanOptions) -> Result<()> {
         }
 
         // Collect files
-        let files: Vec<PathBuf> = ignore::WalkBuilder::new(&options.path)
+        let files: Vec<PathBuf> = scan_walker_with(&options.path, &walk_options)
             .build()
             .filter_map(|entry| {
                 entry.ok().and_then(|e| {
@@ -150,27 +446,37 @@ pub async fn handle_scan(options: ScanOptions) -> Result<()> {
             })
             .collect();
 
+        let start = std::time::Instant::now();
         coordinator.create_work_units(files, config.batch_size)?;
-        let matches = coordinator.execute_distributed_scan().await?;
+        let aggregated = coordinator.execute_distributed_scan().await?;
 
-        // Create basic metrics
+        // One consolidated scan, with the workers' share of it in the metrics
         let metrics = code_guardian_core::ScanMetrics {
-            total_files_scanned: coordinator.get_statistics().total_files_processed,
+            total_files_scanned: aggregated.files_processed,
             total_lines_processed: 0,
-            total_matches_found: matches.len(),
-            scan_duration_ms: 100, // Placeholder
+            total_matches_found: aggregated.matches.len(),
+            scan_duration_ms: start.elapsed().as_millis() as u64,
             cache_hits: 0,
             cache_misses: 0,
+            cache_invalidated: 0,
+            detector_timings: Vec::new(),
+            worker_metrics: aggregated.workers,
+            batch_sizing: None,
+            file_types: Vec::new(),
+            generated_files_skipped: walk_options.generated_files_skipped(),
+            skipped_files: aggregated.skipped_files,
         };
 
-        (matches, Some(metrics))
+        let matches = rule_scopes.retain(aggregated.matches, &options.path);
+        (match_limits.apply_per_file(matches), metrics)
     } else if options.streaming {
         // Use streaming scanner for large codebases
         if let Some(pb) = &pb {
             pb.set_message("Streaming scan of large codebase...");
         }
 
-        let streaming_scanner = StreamingScanner::new(detectors);
+        let streaming_scanner =
+            StreamingScanner::new(detectors).with_walk_options(walk_options.clone());
         let mut all_matches = Vec::new();
 
         let metrics = streaming_scanner.scan_streaming(&options.path, |batch_matches| {
@@ -178,68 +484,1006 @@ pub async fn handle_scan(options: ScanOptions) -> Result<()> {
             Ok(())
         })?;
 
-        (all_matches, Some(metrics))
-    } else if options.optimize {
+        let matches = rule_scopes.retain(all_matches, &options.path);
+        (match_limits.apply_per_file(matches), metrics)
+    } else if options.optimize
+        || (config.cache_key == CacheKey::Content && !options.fast && options.budget.is_none())
+    {
         // Use optimized scanner
         if let Some(pb) = &pb {
             pb.set_message("Optimized scanning with caching...");
         }
 
-        let optimized_scanner = OptimizedScanner::new(detectors).with_cache_size(config.cache_size);
+        let scan_cache = (config.cache_key == CacheKey::Content).then(|| {
+            let cache_dir = match &config.cache_dir {
+                Some(dir) => PathBuf::from(dir),
+                None => options.path.join(SCAN_CACHE_DIR),
+            };
+            ScanCache::open(
+                &cache_dir,
+                &detectors,
+                config.cache_max_mb.saturating_mul(1024 * 1024),
+            )
+        });
+        let content_keyed = scan_cache.is_some();
+        let mut optimized_scanner = OptimizedScanner::new(detectors)
+            .with_cache_size(config.cache_size)
+            .with_match_limits(match_limits)
+            .with_walk_options(walk_options.clone())
+            .with_detector_profiling(options.metrics_out.is_some());
+        if let Some(scan_cache) = scan_cache {
+            optimized_scanner = optimized_scanner.with_scan_cache(scan_cache);
+        }
         let (matches, metrics) = optimized_scanner.scan_optimized(&options.path)?;
-        (matches, Some(metrics))
+        // A cache that can't be written only costs the next run its warm start
+        if let Some(Err(e)) = optimized_scanner.save_scan_cache() {
+            eprintln!("{} Could not save the scan cache: {}", Icon::Warning, e);
+        }
+        // Cache notices go to stderr, keeping stdout clean for `--list-files`
+        if metrics.cache_invalidated > 0 {
+            eprintln!(
+                "{} Scan cache invalidated due to rule change: {} cached file result(s) were for another detector configuration",
+                Icon::Refresh,
+                metrics.cache_invalidated
+            );
+        }
+        if content_keyed {
+            let statistics = ScanStatistics::new(&matches, &metrics);
+            if let Some(hit_rate) = statistics.cache_hit_rate() {
+                eprintln!(
+                    "{} Scan cache: {} of {} files reused ({:.1}% hit rate)",
+                    Icon::Refresh,
+                    statistics.cache_hits,
+                    statistics.cache_hits + statistics.cache_misses,
+                    hit_rate * 100.0
+                );
+            }
+        }
+        // Scoped after caching so cached results stay valid when rule_paths change
+        (rule_scopes.retain(matches, &options.path), metrics)
     } else {
         // Use standard scanner
         if let Some(pb) = &pb {
             pb.set_message("Scanning directory for patterns...");
         }
 
-        let scanner = Scanner::new(detectors);
-        let matches = scanner.scan(&options.path)?;
-        (matches, None)
+        // Every standard scan adds to the detector yields fast scans go by
+        let yields = repo.get_detector_yields()?;
+        let schedule = Arc::new(if options.fast {
+            DetectorSchedule::fast(&yields)
+        } else {
+            DetectorSchedule::default()
+        });
+        // On stderr, keeping stdout clean for `--list-files`
+        if options.fast {
+            eprintln!(
+                "{} Fast scan: skipping {} detector and extension pair(s) without findings in earlier scans",
+                Icon::Fast,
+                schedule.skipped_count()
+            );
+        }
+        let mut scanner = Scanner::new(detectors)
+            .with_match_limits(match_limits)
+            .with_rule_scopes(rule_scopes)
+            .with_walk_options(walk_options.clone())
+            .with_detector_schedule(schedule.clone());
+        if let Some(budget) = &options.budget {
+            scanner = scanner.with_budget(budget.clone().with_density(tracked_density(&repo)));
+        }
+        let result = scanner.scan_with_metrics(&options.path)?;
+        repo.save_detector_yields(&schedule.updated_yields(&yields))?;
+        if let (Some(budget), Some(coverage)) = (&options.budget, scanner.budget_coverage()) {
+            report_budget_coverage(budget, coverage);
+        }
+        result
     };
 
     if let Some(pb) = pb {
         pb.finish_with_message("Scan completed.");
     }
+    let matches = override_severities(matches, &config.severity_overrides);
+    let fired = fire_triggers(&config.triggers, &matches);
+    let matches = run_deep_scans(&options, &config, &fired, matches)?;
+    let matches = match &llm_triage {
+        Some(triage) => apply_llm_triage(triage, matches),
+        None => matches,
+    };
+    let matches = match sampling {
+        Some(sampling) => apply_sampling(sampling, matches),
+        None => matches,
+    };
+    let matches = if options.blame {
+        with_blamed_authors(matches)
+    } else {
+        matches
+    };
     let timestamp = chrono::Utc::now().timestamp();
     let scan = Scan {
         id: None,
         timestamp,
         root_path: options.path.to_string_lossy().to_string(),
+        project: options.project.clone(),
         matches: matches.clone(),
     };
     let id = repo.save_scan(&scan)?;
+    let statistics = ScanStatistics::new(&matches, &metrics);
+    repo.save_statistics(id, &statistics)?;
+    let ci_run = CiRun::detect();
+    if let Some(ci_run) = &ci_run {
+        repo.save_ci_run(id, ci_run)?;
+    }
+    run_trigger_actions(&mut repo, &config, &fired, &scan, id)?;
+
+    let sidecar = metrics_sidecar_json(id, &scan, ci_run.as_ref(), &metrics)?;
+    if let Some(metrics_out) = &options.metrics_out {
+        std::fs::write(metrics_out, format!("{}\n", sidecar))
+            .with_context(|| format!("Failed to write metrics to {}", metrics_out.display()))?;
+    }
+    let artifacts = match &artifact_store {
+        Some(store) => upload_artifacts(
+            store,
+            &[
+                ("report.json", JsonFormatter.format(&matches)),
+                ("metrics.json", sidecar),
+            ],
+        ),
+        None => BTreeMap::new(),
+    };
+
+    let stale_check = options
+        .fail_on_stale_todos
+        .map(|max_age_days| check_stale_todos(&matches, max_age_days, timestamp));
+    let threshold = match &stale_check {
+        None => ThresholdStatus::NotConfigured,
+        Some(Ok(())) => ThresholdStatus::Passed,
+        Some(Err(e)) => ThresholdStatus::Failed {
+            message: e.to_string().lines().next().unwrap_or_default().to_string(),
+        },
+    };
+    let summary = ScanSummary::from_matches(&matches, metrics.total_files_scanned);
+    notify_webhooks(
+        &config,
+        &ScanCompletedPayload::new(Some(id), &scan.root_path, timestamp, summary, threshold)
+            .with_ci(ci_run)
+            .with_artifacts(artifacts.clone()),
+    );
+
+    // Keep stdout clean for piping into `xargs`
+    if options.list_files {
+        eprintln!("Scan saved with ID: {}", id);
+        for (name, url) in &artifacts {
+            eprintln!("{} Uploaded {}: {}", Icon::Package, name, url);
+        }
+        report_skipped_files(&metrics.skipped_files, options.report_skipped);
+        print!("{}", format_file_list(&matches, options.null_delimited));
+        print_summary(&statistics);
+        return stale_check.unwrap_or(Ok(()));
+    }
     println!("Scan saved with ID: {}", id);
+    for (name, url) in &artifacts {
+        println!("{} Uploaded {}: {}", Icon::Package, name, url);
+    }
+
+    let generated_skipped = walk_options.generated_files_skipped();
+    if generated_skipped > 0 {
+        println!(
+            "{} Skipped {} generated or vendored files (use --include-generated to scan them)",
+            Icon::Skipped,
+            generated_skipped
+        );
+    }
+    report_skipped_files(&metrics.skipped_files, options.report_skipped);
 
     // Show performance metrics if requested
     if options.show_metrics {
-        if let Some(metrics) = scan_metrics {
-            println!("\n📊 Performance Metrics:");
-            println!("   Files scanned: {}", metrics.total_files_scanned);
-            println!("   Lines processed: {}", metrics.total_lines_processed);
-            println!("   Matches found: {}", metrics.total_matches_found);
-            println!("   Scan duration: {}ms", metrics.scan_duration_ms);
-
-            if metrics.cache_hits > 0 || metrics.cache_misses > 0 {
-                let hit_rate =
-                    metrics.cache_hits as f64 / (metrics.cache_hits + metrics.cache_misses) as f64;
-                println!("   Cache hit rate: {:.1}%", hit_rate * 100.0);
-            }
+        println!("\n{} Performance Metrics:", Icon::Stats);
+        println!("   Files scanned: {}", metrics.total_files_scanned);
+        println!("   Lines processed: {}", metrics.total_lines_processed);
+        println!("   Matches found: {}", metrics.total_matches_found);
+        println!("   Scan duration: {}ms", metrics.scan_duration_ms);
+        if metrics.generated_files_skipped > 0 {
+            println!(
+                "   Generated files skipped: {}",
+                metrics.generated_files_skipped
+            );
+        }
+        let unstable = metrics
+            .skipped_files
+            .iter()
+            .filter(|file| file.reason == SkipReason::Unstable)
+            .count();
+        if metrics.skipped_files.len() > unstable {
+            println!(
+                "   Unreadable files skipped: {}",
+                metrics.skipped_files.len() - unstable
+            );
+        }
+        if unstable > 0 {
+            println!(
+                "   Unstable files skipped (changed while scanned): {}",
+                unstable
+            );
+        }
 
-            let files_per_sec =
-                metrics.total_files_scanned as f64 / (metrics.scan_duration_ms as f64 / 1000.0);
-            let lines_per_sec =
-                metrics.total_lines_processed as f64 / (metrics.scan_duration_ms as f64 / 1000.0);
+        if let Some(batch_sizing) = &metrics.batch_sizing {
             println!(
-                "   Performance: {:.1} files/sec, {:.1} lines/sec",
-                files_per_sec, lines_per_sec
+                "   Batch sizing: {} chunks, detectors parallel in {} files and sequential in {}, {}ns per KB per detector",
+                batch_sizing.chunks,
+                batch_sizing.parallel_detector_files,
+                batch_sizing.sequential_detector_files,
+                batch_sizing.detector_ns_per_kb
             );
         }
+
+        for worker in &metrics.worker_metrics {
+            println!(
+                "   {}: {} units, {} files, {} matches in {}ms",
+                worker.worker_id,
+                worker.units_completed,
+                worker.files_processed,
+                worker.matches_found,
+                worker.processing_time_ms
+            );
+        }
+
+        if let Some(hit_rate) = ScanStatistics::new(&matches, &metrics).cache_hit_rate() {
+            println!("   Cache hit rate: {:.1}%", hit_rate * 100.0);
+        }
+        if metrics.cache_invalidated > 0 {
+            println!(
+                "   Cache invalidated due to rule change: {} entries",
+                metrics.cache_invalidated
+            );
+        }
+
+        let files_per_sec =
+            metrics.total_files_scanned as f64 / (metrics.scan_duration_ms as f64 / 1000.0);
+        let lines_per_sec =
+            metrics.total_lines_processed as f64 / (metrics.scan_duration_ms as f64 / 1000.0);
+        println!(
+            "   Performance: {:.1} files/sec, {:.1} lines/sec",
+            files_per_sec, lines_per_sec
+        );
+        if !metrics.file_types.is_empty() {
+            println!("   By file type:");
+            for file_type in &metrics.file_types {
+                let name = match (file_type.extension.as_str(), &file_type.language) {
+                    ("", _) => "(no extension)".to_string(),
+                    (extension, Some(language)) => format!(".{} ({})", extension, language),
+                    (extension, None) => format!(".{}", extension),
+                };
+                println!(
+                    "     {}: {} files, {} lines, {} matches, {:.1}ms ({:.0}% of time)",
+                    name,
+                    file_type.files,
+                    file_type.lines,
+                    file_type.matches,
+                    file_type.duration_us as f64 / 1000.0,
+                    file_type.time_share(&metrics.file_types) * 100.0
+                );
+            }
+        }
         println!();
     }
 
-    let formatter = code_guardian_output::formatters::TextFormatter;
-    println!("{}", formatter.format(&matches));
+    if options.blame {
+        let group_by_author = options.group_by.is_some();
+        println!("{}", format_blamed_matches(&matches, group_by_author));
+    } else {
+        let formatter = code_guardian_output::formatters::TextFormatter;
+        println!("{}", formatter.format(&matches));
+    }
+    print_summary(&statistics);
+
+    if let (Some(max_age_days), Some(stale_check)) = (options.fail_on_stale_todos, stale_check) {
+        stale_check?;
+        println!(
+            "{} No comment debt older than {} days",
+            Icon::Success,
+            max_age_days
+        );
+    }
+    Ok(())
+}
+
+/// Scans the config's targets called `names`, or all of them when `names` is
+/// empty, each as its own stored scan. Every target is scanned even if an
+/// earlier one fails; the first failure is returned and the others printed.
+pub async fn handle_scan_targets(
+    options: ScanOptions,
+    names: Vec<String>,
+    parallel: bool,
+) -> Result<()> {
+    let config_path = options
+        .config_path
+        .clone()
+        .context("scanning targets needs --config")?;
+    let config = load_config(Some(&config_path))?;
+    let targets =
+        select_targets(&config.targets, &names).map_err(|e| CliError::Usage(e.to_string()))?;
+    let config_dir = config_path.parent().unwrap_or_else(|| Path::new(""));
+    let mut runs = Vec::new();
+    for (name, target) in targets {
+        let profile = target
+            .profile
+            .clone()
+            .unwrap_or_else(|| options.profile.clone());
+        if !DETECTOR_PROFILES.contains(&profile.as_str()) {
+            return Err(CliError::Usage(format!(
+                "Unknown profile '{}' of target '{}', expected one of: {}",
+                profile,
+                name,
+                DETECTOR_PROFILES.join(", ")
+            ))
+            .into());
+        }
+        let options = ScanOptions {
+            path: target.root(config_dir),
+            profile,
+            ..options.clone()
+        };
+        runs.push((name.to_string(), options));
+    }
+
+    let mut results = Vec::new();
+    if parallel {
+        // Every scan opens the database, and concurrent first opens would
+        // race to run its migrations, so it is created up front
+        if !options.dry_run && !options.stdin {
+            let db_path = options
+                .db
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(&config.database_path));
+            SqliteScanRepository::new(&db_path)?;
+        }
+        // Scans block on file I/O, so each gets a thread of its own
+        let runtime = tokio::runtime::Handle::current();
+        let tasks: Vec<_> = runs
+            .into_iter()
+            .map(|(name, options)| {
+                let runtime = runtime.clone();
+                tokio::task::spawn_blocking(move || {
+                    println!(
+                        "{} Target {}: {}",
+                        Icon::Target,
+                        name,
+                        options.path.display()
+                    );
+                    let result = runtime.block_on(handle_scan(options));
+                    (name, result)
+                })
+            })
+            .collect();
+        for task in tasks {
+            results.push(task.await?);
+        }
+    } else {
+        for (name, options) in runs {
+            println!(
+                "{} Target {}: {}",
+                Icon::Target,
+                name,
+                options.path.display()
+            );
+            let result = handle_scan(options).await;
+            results.push((name, result));
+        }
+    }
+
+    let mut first_error = None;
+    for (name, result) in results {
+        if let Err(e) = result {
+            let e = e.context(format!("Target '{}' failed", name));
+            if first_error.is_none() {
+                first_error = Some(e);
+            } else {
+                eprintln!("{} {:#}", Icon::Failure, e);
+            }
+        }
+    }
+    first_error.map_or(Ok(()), Err)
+}
+
+/// Earlier scans of a root whose throughput estimates the duration of a dry run
+const PLAN_HISTORY_SCANS: usize = 5;
+
+/// Prints what a scan with `options` would do: its detectors, the files the
+/// walk finds, from their metadata only, the effective settings and a
+/// duration estimated from earlier scans of the root. Nothing is saved, and a
+/// missing database is not created.
+fn print_scan_plan(
+    options: &ScanOptions,
+    config: &Config,
+    walk_options: &WalkOptions,
+) -> Result<()> {
+    let detectors = build_scan_detectors(options, config, false)?;
+    let mode = if options.incremental {
+        "incremental"
+    } else if options.distributed {
+        "distributed"
+    } else if options.streaming {
+        "streaming"
+    } else if options.optimize {
+        "optimized"
+    } else {
+        "standard"
+    };
+
+    let (mut files, mut bytes, mut too_large) = (0usize, 0u64, 0usize);
+    for entry in scan_walker_with(&options.path, walk_options)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+    {
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        if size > MAX_SCANNED_FILE_SIZE {
+            too_large += 1;
+        } else {
+            files += 1;
+            bytes += size;
+        }
+    }
+
+    println!(
+        "{} Dry run: {} scan of {} with the {} profile; no files are read and nothing is saved",
+        Icon::Inspect,
+        mode,
+        options.path.display(),
+        options.profile
+    );
+    let names: BTreeSet<&str> = detectors.iter().map(|d| d.name()).collect();
+    println!(
+        "\n{} Detectors ({}): {}",
+        Icon::Tool,
+        names.len(),
+        names.into_iter().collect::<Vec<_>>().join(", ")
+    );
+    println!(
+        "\n{} Files to scan: {} ({})",
+        Icon::Folder,
+        files,
+        utils::format_bytes(bytes)
+    );
+    if too_large > 0 {
+        println!(
+            "   Over {} and skipped: {}",
+            utils::format_bytes(MAX_SCANNED_FILE_SIZE),
+            too_large
+        );
+    }
+    let generated = walk_options.generated_files_skipped();
+    if generated > 0 {
+        println!("   Generated or vendored and skipped: {}", generated);
+    }
+
+    println!("\n{} Settings:", Icon::List);
+    println!("   max_threads: {}", config.max_threads);
+    println!("   batch_size: {}", config.batch_size);
+    println!(
+        "   cache: {:?}, {} entries",
+        config.cache_key, config.cache_size
+    );
+    let limit = |limit: Option<usize>| limit.map_or("none".to_string(), |n| n.to_string());
+    println!(
+        "   matches per file: {}, per pattern: {}",
+        limit(config.max_matches_per_file),
+        limit(config.max_matches_per_pattern)
+    );
+    if let Some(threshold) = config.sample_above {
+        println!(
+            "   sampling above {} findings, {} per file",
+            threshold, config.sample_per_file
+        );
+    }
+    println!(
+        "   follow_symlinks: {}, max_depth: {}, hidden: {}, respect_gitignore: {}",
+        config.follow_symlinks,
+        limit(config.max_depth),
+        config.hidden,
+        config.respect_gitignore
+    );
+    if !config.excluded_dirs.is_empty() {
+        println!("   excluded_dirs: {}", config.excluded_dirs.join(", "));
+    }
+    if !config.rule_paths.is_empty() {
+        println!(
+            "   rule_paths: {}",
+            config
+                .rule_paths
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    if let Some(budget) = &options.budget {
+        println!("   budget: {:?}", budget.limit);
+    }
+
+    let history = scan_history(options, config);
+    match ScanStatistics::estimate_duration_ms(&history, files) {
+        Some(ms) => println!(
+            "\n{} Estimated duration: {:.1}s, from the throughput of {} earlier scans of this root",
+            Icon::Timer,
+            ms as f64 / 1000.0,
+            history.len()
+        ),
+        None => println!(
+            "\n{} Estimated duration: unknown, no earlier scans of this root",
+            Icon::Timer
+        ),
+    }
+    Ok(())
+}
+
+/// Statistics of the latest scans of the root and project of `options`, if
+/// the database exists
+fn scan_history(options: &ScanOptions, config: &Config) -> Vec<ScanStatistics> {
+    let db_path = options
+        .db
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(&config.database_path));
+    if !db_path.is_file() {
+        return Vec::new();
+    }
+    let Ok(repo) = SqliteScanRepository::new(&db_path) else {
+        return Vec::new();
+    };
+    let root = options.path.to_string_lossy();
+    repo.get_all_scans()
+        .unwrap_or_default()
+        .iter()
+        .filter(|scan| scan.root_path == root && scan.project == options.project)
+        .filter_map(|scan| repo.get_statistics(scan.id?).ok().flatten())
+        .take(PLAN_HISTORY_SCANS)
+        .collect()
+}
+
+/// Samples Medium and Low findings. Findings without a severity of their own,
+/// unlike those of custom detectors, take the built-in default of their
+/// pattern; unknown patterns count as Medium.
+fn apply_sampling(sampling: SeveritySampling, matches: Vec<Match>) -> Vec<Match> {
+    let severities = EnhancedScanConfig::default().severity_levels;
+    let total = matches.len();
+    let sampled = sampling.apply(matches, |pattern| {
+        severities.get(pattern).cloned().unwrap_or(Severity::Medium)
+    });
+    if sampled.len() < total {
+        eprintln!(
+            "{} {} findings exceed --sample-above {}: kept all Critical/High and up to {} others per file ({} SAMPLED markers keep the counts)", Icon::Sampled,
+            total,
+            sampling.threshold,
+            sampling.per_file,
+            sampled
+                .iter()
+                .filter(|m| m.pattern == SAMPLED_PATTERN)
+                .count()
+        );
+    }
+    sampled
+}
+
+/// Annotates findings with the triage verdicts, warning if the endpoint
+/// stopped answering part way
+fn apply_llm_triage(triage: &LlmTriage, matches: Vec<Match>) -> Vec<Match> {
+    let outcome = triage.triage(matches);
+    if let Some(error) = outcome.error {
+        eprintln!(
+            "{} {}; remaining findings were not triaged",
+            Icon::Warning,
+            error
+        );
+    }
+    outcome
+        .matches
+        .into_iter()
+        .map(|m| m.into_annotated_match())
+        .collect()
+}
+
+/// Rejects invalid triggers and deep scans with unknown profiles before
+/// scanning, so a typo doesn't surface only once a trigger fires
+fn validate_scan_triggers(triggers: &[FindingTrigger]) -> Result<()> {
+    validate_triggers(triggers).map_err(|e| CliError::Usage(e.to_string()))?;
+    for trigger in triggers {
+        for action in &trigger.actions {
+            if let TriggerAction::DeepScan { profile } = action {
+                if !DETECTOR_PROFILES.contains(&profile.as_str()) {
+                    return Err(CliError::Usage(format!(
+                        "Unknown profile '{}' of trigger '{}', expected one of: {}",
+                        profile,
+                        trigger.name,
+                        DETECTOR_PROFILES.join(", ")
+                    ))
+                    .into());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs the deep scan actions of the `fired` triggers, scanning the files
+/// with the findings that fired each again with its profile, and adds what
+/// they find to `matches`. They run before the scan is saved, so the saved
+/// scan holds their findings.
+fn run_deep_scans(
+    options: &ScanOptions,
+    config: &Config,
+    fired: &[FiredTrigger<'_>],
+    mut matches: Vec<Match>,
+) -> Result<Vec<Match>> {
+    let rule_scopes = RuleScopes::new(&config.rule_paths)?;
+    for fired in fired {
+        let files = fired.files();
+        eprintln!(
+            "{} Trigger '{}' fired on {} finding(s) in {} file(s)",
+            Icon::Target,
+            fired.trigger.name,
+            fired.matches.len(),
+            files.len()
+        );
+        for action in &fired.trigger.actions {
+            let TriggerAction::DeepScan { profile } = action else {
+                continue;
+            };
+            let deep_options = ScanOptions {
+                profile: profile.clone(),
+                ..options.clone()
+            };
+            let scanner = Scanner::new(build_scan_detectors(&deep_options, config, false)?);
+            let mut found = Vec::new();
+            for file in &files {
+                match read_stable(Path::new(file)) {
+                    Ok(content) => found.extend(scanner.scan_content(&content, Path::new(file))),
+                    Err(e) => eprintln!("{} {}", Icon::Warning, e),
+                }
+            }
+            let found = override_severities(
+                rule_scopes.retain(found, &options.path),
+                &config.severity_overrides,
+            );
+            let before = matches.len();
+            matches = merge_matches(matches, found);
+            eprintln!(
+                "   {} profile rescan added {} finding(s)",
+                profile,
+                matches.len() - before
+            );
+        }
+    }
+    Ok(matches)
+}
+
+/// Runs the quarantine and webhook actions of the `fired` triggers for the
+/// saved scan `id`. Failed webhooks are reported and don't fail the scan.
+fn run_trigger_actions(
+    repo: &mut SqliteScanRepository,
+    config: &Config,
+    fired: &[FiredTrigger<'_>],
+    scan: &Scan,
+    id: i64,
+) -> Result<()> {
+    let mut reasons = Vec::new();
+    for fired in fired {
+        for action in &fired.trigger.actions {
+            match action {
+                TriggerAction::DeepScan { .. } => {}
+                TriggerAction::Quarantine => reasons.push(fired.quarantine_reason()),
+                TriggerAction::Webhook { url } => {
+                    let payload =
+                        TriggerFiredPayload::new(fired, id, &scan.root_path, scan.timestamp);
+                    let notifier = WebhookNotifier::new(vec![url.clone()])
+                        .with_secret(config.webhook_secret.clone());
+                    for error in notifier.notify_event(TRIGGER_FIRED_EVENT, &payload) {
+                        eprintln!("{} {}", Icon::Warning, error);
+                    }
+                }
+            }
+        }
+    }
+    if !reasons.is_empty() {
+        let reason = reasons.join("; ");
+        repo.quarantine_scan(id, &reason)?;
+        eprintln!("{} Scan {} quarantined: {}", Icon::Warning, id, reason);
+    }
     Ok(())
 }
+
+/// Posts the payload to the configured webhooks, warning about failed deliveries
+pub fn notify_webhooks(config: &Config, payload: &ScanCompletedPayload) {
+    if config.webhooks.is_empty() {
+        return;
+    }
+    let notifier =
+        WebhookNotifier::new(config.webhooks.clone()).with_secret(config.webhook_secret.clone());
+    for error in notifier.notify(payload) {
+        eprintln!("{} {}", Icon::Warning, error);
+    }
+}
+
+/// `--metrics-out` file contents: the scan's metrics plus the scan they belong to
+#[derive(serde::Serialize)]
+struct MetricsSidecar<'a> {
+    scan_id: i64,
+    timestamp: i64,
+    root_path: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ci: Option<&'a CiRun>,
+    #[serde(flatten)]
+    metrics: &'a ScanMetrics,
+}
+
+fn metrics_sidecar_json(
+    scan_id: i64,
+    scan: &Scan,
+    ci_run: Option<&CiRun>,
+    metrics: &ScanMetrics,
+) -> Result<String> {
+    let sidecar = MetricsSidecar {
+        scan_id,
+        timestamp: scan.timestamp,
+        root_path: &scan.root_path,
+        ci: ci_run,
+        metrics,
+    };
+    Ok(serde_json::to_string_pretty(&sidecar)?)
+}
+
+/// Uploads each named file to `store` and returns the URLs of those that
+/// were uploaded. Like webhook deliveries, failed uploads only warn.
+pub fn upload_artifacts(
+    store: &ArtifactStore,
+    files: &[(&str, String)],
+) -> BTreeMap<String, String> {
+    let mut urls = BTreeMap::new();
+    for (name, content) in files {
+        match store.upload(name, content.as_bytes()) {
+            Ok(url) => {
+                urls.insert(name.to_string(), url);
+            }
+            Err(e) => eprintln!("{} {}", Icon::Warning, e),
+        }
+    }
+    urls
+}
+
+/// Warns about files that could not be scanned; `list` prints each with its reason
+fn report_skipped_files(skipped: &[SkippedFile], list: bool) {
+    if skipped.is_empty() {
+        return;
+    }
+    if list {
+        eprintln!(
+            "{} {} files could not be scanned:",
+            Icon::Warning,
+            skipped.len()
+        );
+        for file in skipped {
+            eprintln!("   {}: {}", file.path, file.reason);
+        }
+    } else {
+        eprintln!(
+            "{} {} files could not be scanned (use --report-skipped to list them)",
+            Icon::Warning,
+            skipped.len()
+        );
+    }
+}
+
+/// Blame comment-debt matches and fail if any are older than `max_age_days`
+fn check_stale_todos(matches: &[Match], max_age_days: u64, now: i64) -> Result<()> {
+    let debt: Vec<Match> = matches
+        .iter()
+        .filter(|m| COMMENT_DEBT_PATTERNS.contains(&m.pattern.as_str()))
+        .cloned()
+        .collect();
+    let blames = GitIntegration::blame_matches(&debt);
+    let stale = find_stale_debt(&debt, &blames, max_age_days, now);
+    if stale.is_empty() {
+        return Ok(());
+    }
+    Err(CliError::ThresholdExceeded(format_stale_report(&stale, max_age_days, now)).into())
+}
+
+/// Comment-debt matches whose line was last touched more than `max_age_days`
+/// before `now`, oldest first. Lines flagged by several patterns are reported once.
+pub fn find_stale_debt<'a>(
+    matches: &'a [Match],
+    blames: &'a [Option<BlameInfo>],
+    max_age_days: u64,
+    now: i64,
+) -> Vec<(&'a Match, &'a BlameInfo)> {
+    let mut seen = BTreeSet::new();
+    let mut stale: Vec<_> = matches
+        .iter()
+        .zip(blames)
+        .filter_map(|(m, blame)| Some((m, blame.as_ref()?)))
+        .filter(|(m, blame)| {
+            COMMENT_DEBT_PATTERNS.contains(&m.pattern.as_str())
+                && blame.age_days(now) > max_age_days as i64
+        })
+        .filter(|(m, _)| seen.insert((m.file_path.as_str(), m.line_number)))
+        .collect();
+    stale.sort_by_key(|(_, blame)| blame.author_time);
+    stale
+}
+
+/// Sets the author of each match to the last author of its line, from `git blame`
+fn with_blamed_authors(matches: Vec<Match>) -> Vec<Match> {
+    let blames = GitIntegration::blame_matches(&matches);
+    matches
+        .into_iter()
+        .zip(blames)
+        .map(|(m, blame)| m.with_author(blame.as_ref().map(BlameInfo::to_author)))
+        .collect()
+}
+
+fn author_label(author: Option<&Author>) -> String {
+    match author {
+        Some(author) => author.to_string(),
+        None => "unknown".to_string(),
+    }
+}
+
+/// Format matches with their last-touch author, either inline or grouped by
+/// author with the largest groups first
+pub fn format_blamed_matches(matches: &[Match], group_by_author: bool) -> String {
+    if matches.is_empty() {
+        return "No matches found.".to_string();
+    }
+
+    let mut output = String::new();
+    if !group_by_author {
+        for m in matches {
+            output.push_str(&format!(
+                "{}:{}:{}: {} - {} ({})\n",
+                m.file_path,
+                m.line_number,
+                m.column,
+                m.pattern,
+                m.message,
+                author_label(m.author.as_ref())
+            ));
+        }
+        return output.trim_end().to_string();
+    }
+
+    let mut groups: BTreeMap<String, Vec<&Match>> = BTreeMap::new();
+    for m in matches {
+        groups
+            .entry(author_label(m.author.as_ref()))
+            .or_default()
+            .push(m);
+    }
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    groups.sort_by_key(|(_, author_matches)| std::cmp::Reverse(author_matches.len()));
+
+    for (author, author_matches) in groups {
+        output.push_str(&format!("{} ({})\n", author, author_matches.len()));
+        for m in author_matches {
+            output.push_str(&format!(
+                "   {}:{}:{}: {} - {}\n",
+                m.file_path, m.line_number, m.column, m.pattern, m.message
+            ));
+        }
+    }
+    output.trim_end().to_string()
+}
+
+fn format_stale_report(stale: &[(&Match, &BlameInfo)], max_age_days: u64, now: i64) -> String {
+    let mut report = format!(
+        "{} {} comment debt item(s) older than {} days:\n",
+        Icon::Hourglass,
+        stale.len(),
+        max_age_days
+    );
+    for (m, blame) in stale {
+        report.push_str(&format!(
+            "   {}:{} {} ({} days, {})\n",
+            m.file_path,
+            m.line_number,
+            m.pattern,
+            blame.age_days(now),
+            blame.author
+        ));
+    }
+    report.trim_end().to_string()
+}
+
+/// Format the unique, sorted set of files that contain findings.
+/// Each entry is terminated by NUL when `null_delimited` is set, newline otherwise.
+pub fn format_file_list(matches: &[Match], null_delimited: bool) -> String {
+    let files: BTreeSet<&str> = matches.iter().map(|m| m.file_path.as_str()).collect();
+    let terminator = if null_delimited { '\0' } else { '\n' };
+    let mut output = String::new();
+    for file in files {
+        output.push_str(file);
+        output.push(terminator);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blame_at(author_time: i64) -> Option<BlameInfo> {
+        Some(BlameInfo {
+            commit: "0".repeat(40),
+            author: "Alice".to_string(),
+            author_email: "alice@example.com".to_string(),
+            author_time,
+        })
+    }
+
+    #[test]
+    fn test_find_stale_debt() {
+        let day = 86_400;
+        let now = 1000 * day;
+        let mut orphan = match_in("a.rs");
+        orphan.pattern = "ORPHAN_TODO".into();
+        let mut unwrap = match_in("b.rs");
+        unwrap.pattern = "UNWRAP".into();
+        let matches = vec![
+            match_in("a.rs"),
+            orphan,
+            unwrap,
+            match_in("c.rs"),
+            match_in("d.rs"),
+        ];
+        let blames = vec![
+            blame_at(now - 400 * day),
+            blame_at(now - 400 * day),
+            blame_at(now - 400 * day),
+            blame_at(now - 30 * day),
+            None,
+        ];
+
+        let stale = find_stale_debt(&matches, &blames, 365, now);
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].0.file_path, "a.rs");
+        assert!(format_stale_report(&stale, 365, now).contains("a.rs:1 TODO (400 days, Alice)"));
+    }
+
+    fn match_in(file: &str) -> Match {
+        Match::new(file, 1, "TODO", "TODO")
+    }
+
+    #[test]
+    fn test_format_file_list_dedupes_and_sorts() {
+        let matches = vec![match_in("b.rs"), match_in("a.rs"), match_in("b.rs")];
+        assert_eq!(format_file_list(&matches, false), "a.rs\nb.rs\n");
+        assert_eq!(format_file_list(&matches, true), "a.rs\0b.rs\0");
+    }
+
+    #[test]
+    fn test_format_file_list_empty() {
+        assert_eq!(format_file_list(&[], true), "");
+    }
+
+    #[test]
+    fn test_format_blamed_matches() {
+        let alice = blame_at(0).map(|blame| blame.to_author());
+        let bob = Author {
+            name: "Bob".to_string(),
+            email: "bob@example.com".to_string(),
+        };
+        let matches = vec![
+            match_in("a.rs").with_author(alice.clone()),
+            match_in("b.rs").with_author(bob),
+            match_in("c.rs").with_author(alice),
+        ];
+
+        let flat = format_blamed_matches(&matches, false);
+        assert!(flat.starts_with("a.rs:1:1: TODO - TODO (Alice <alice@example.com>)"));
+
+        let grouped = format_blamed_matches(&matches, true);
+        let lines: Vec<&str> = grouped.lines().collect();
+        assert_eq!(lines[0], "Alice <alice@example.com> (2)");
+        assert_eq!(lines[3], "Bob <bob@example.com> (1)");
+
+        let unknown = format_blamed_matches(&[match_in("a.rs")], false);
+        assert!(unknown.ends_with("(unknown)"));
+    }
+}
@@ -1,7 +1,8 @@
 use anyhow::Result;
+use code_guardian_core::{DetectorFactory, PatternDetector};
 
 use crate::cli_definitions::StackPreset;
-use crate::production_handlers::handle_lang_scan;
+use crate::production_handlers::{handle_lang_scan, scan_languages};
 
 /// Handle stack preset commands by mapping them to appropriate language configurations
 pub fn handle_stack_preset(preset: StackPreset) -> Result<()> {
@@ -14,19 +15,29 @@ pub fn handle_stack_preset(preset: StackPreset) -> Result<()> {
                 "tsx".to_string(),
                 "vue".to_string(),
                 "svelte".to_string(),
+                "html".to_string(),
             ];
-            handle_lang_scan(languages, path, "text".to_string(), production)
+            let detectors = preset_detectors(
+                production,
+                DetectorFactory::create_accessibility_detectors(),
+            );
+            scan_languages(languages, path, "text".to_string(), detectors)
         }
         StackPreset::Backend { path, production } => {
             let languages = vec![
                 "py".to_string(),
                 "java".to_string(),
+                "kt".to_string(),
                 "go".to_string(),
                 "cs".to_string(),
                 "php".to_string(),
                 "rb".to_string(),
             ];
-            handle_lang_scan(languages, path, "text".to_string(), production)
+            let mut stack = DetectorFactory::create_go_detectors();
+            stack.extend(DetectorFactory::create_python_detectors());
+            stack.extend(DetectorFactory::create_jvm_detectors());
+            let detectors = preset_detectors(production, stack);
+            scan_languages(languages, path, "text".to_string(), detectors)
         }
         StackPreset::Fullstack { path, production } => {
             let languages = vec![
@@ -56,7 +67,24 @@ pub fn handle_stack_preset(preset: StackPreset) -> Result<()> {
                 "c".to_string(),
                 "go".to_string(),
             ];
-            handle_lang_scan(languages, path, "text".to_string(), production)
+            let mut stack = DetectorFactory::create_go_detectors();
+            stack.extend(DetectorFactory::create_c_detectors());
+            let detectors = preset_detectors(production, stack);
+            scan_languages(languages, path, "text".to_string(), detectors)
         }
     }
 }
+
+/// The detectors `lang` runs, plus those specific to the preset's stack
+fn preset_detectors(
+    production: bool,
+    stack: Vec<Box<dyn PatternDetector>>,
+) -> Vec<Box<dyn PatternDetector>> {
+    let mut detectors = if production {
+        DetectorFactory::create_production_ready_detectors()
+    } else {
+        DetectorFactory::create_comprehensive_detectors()
+    };
+    detectors.extend(stack);
+    detectors
+}
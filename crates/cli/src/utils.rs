@@ -1,11 +1,102 @@
-use code_guardian_core::{DetectorProfile, PatternDetector};
+use code_guardian_core::{
+    BudgetCoverage, DetectorProfile, FindingDensity, FindingState, Icon, PatternDetector,
+    ScanBudget, ScanStatistics,
+};
+use code_guardian_storage::ScanRepository;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Whether `--summary` was given; set once in `main`
+static PRINT_SUMMARY: AtomicBool = AtomicBool::new(false);
+
+/// Statistics of the scans the command ran, for the audit log
+static SCAN_STATISTICS: Mutex<Vec<ScanStatistics>> = Mutex::new(Vec::new());
+
+/// End the output of every scanning command with its statistics line
+pub fn set_print_summary(enabled: bool) {
+    PRINT_SUMMARY.store(enabled, Ordering::Relaxed);
+}
+
+/// Prints the statistics line of a scan if `--summary` was given. It goes to
+/// stderr so JSON reports and file lists on stdout stay parseable.
+pub fn print_summary(statistics: &ScanStatistics) {
+    if let Ok(mut recorded) = SCAN_STATISTICS.lock() {
+        recorded.push(statistics.clone());
+    }
+    if PRINT_SUMMARY.load(Ordering::Relaxed) {
+        eprintln!("{}", statistics);
+    }
+}
+
+/// Statistics of the scans run so far, in the order they finished
+pub fn take_scan_statistics() -> Vec<ScanStatistics> {
+    SCAN_STATISTICS
+        .lock()
+        .map(|mut recorded| std::mem::take(&mut *recorded))
+        .unwrap_or_default()
+}
 
 /// Get the database path, defaulting to "data/code-guardian.db" if not provided.
 pub fn get_db_path(db: Option<PathBuf>) -> PathBuf {
     db.unwrap_or_else(|| PathBuf::from("data/code-guardian.db"))
 }
 
+/// `bytes` in B, KiB or MiB
+pub fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1_048_575 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+/// Findings per file tracked in `repo` and not fixed, the files a budgeted
+/// or pre-commit scan goes through first
+pub fn tracked_density(repo: &impl ScanRepository) -> FindingDensity {
+    let findings = repo.get_findings(None, None).unwrap_or_default();
+    FindingDensity::from_paths(
+        findings
+            .iter()
+            .filter(|finding| !matches!(finding.state, FindingState::Fixed))
+            .map(|finding| finding.file_path.as_str()),
+    )
+}
+
+/// Warns that `budget` ran out before every file was scanned
+pub fn report_budget_coverage(budget: &ScanBudget, coverage: BudgetCoverage) {
+    if coverage.is_partial() {
+        eprintln!(
+            "{} Budget of {:?} used up: {}; findings are partial",
+            Icon::Hourglass,
+            budget.limit,
+            coverage
+        );
+    }
+}
+
+/// Profile names accepted by [`get_detectors_from_profile`]
+pub const DETECTOR_PROFILES: &[&str] = &[
+    "basic",
+    "comprehensive",
+    "security",
+    "performance",
+    "rust",
+    "rust-panics",
+    "go",
+    "c",
+    "python",
+    "jvm",
+    "llm-security",
+    "llm-quality",
+    "llm-comprehensive",
+    "production-ready-llm",
+    "iac",
+    "quality",
+    "accessibility",
+    "migrations",
+];
+
 /// Get detectors based on the profile string.
 pub fn get_detectors_from_profile(profile: &str) -> Vec<Box<dyn PatternDetector>> {
     match profile {
@@ -14,10 +105,19 @@ pub fn get_detectors_from_profile(profile: &str) -> Vec<Box<dyn PatternDetector>
         "security" => DetectorProfile::Security.get_detectors(),
         "performance" => DetectorProfile::Performance.get_detectors(),
         "rust" => DetectorProfile::Rust.get_detectors(),
+        "rust-panics" => DetectorProfile::RustPanics.get_detectors(),
+        "go" => DetectorProfile::Go.get_detectors(),
+        "c" => DetectorProfile::C.get_detectors(),
+        "python" => DetectorProfile::Python.get_detectors(),
+        "jvm" => DetectorProfile::Jvm.get_detectors(),
         "llm-security" => DetectorProfile::LLMSecurity.get_detectors(),
         "llm-quality" => DetectorProfile::LLMQuality.get_detectors(),
         "llm-comprehensive" => DetectorProfile::LLMComprehensive.get_detectors(),
         "production-ready-llm" => DetectorProfile::ProductionReadyWithLLM.get_detectors(),
+        "iac" => DetectorProfile::Iac.get_detectors(),
+        "quality" => DetectorProfile::Quality.get_detectors(),
+        "accessibility" => DetectorProfile::Accessibility.get_detectors(),
+        "migrations" => DetectorProfile::Migrations.get_detectors(),
         _ => {
             println!("Unknown profile '{}', using 'basic'", profile);
             DetectorProfile::Basic.get_detectors()
@@ -100,7 +100,13 @@ mod advanced_handler_tests {
         let action = DistributedAction::Scan {
             path: temp_dir.path().to_path_buf(),
             workers: 1,
+            worker_endpoints: Vec::new(),
+            discover_srv: None,
             batch_size: 10,
+            webhooks: Vec::new(),
+            tls_cert: None,
+            tls_key: None,
+            tls_ca: None,
         };
 
         let result = handle_distributed(action).await;
@@ -116,7 +122,13 @@ mod advanced_handler_tests {
         let action = DistributedAction::Scan {
             path: temp_dir.path().to_path_buf(),
             workers: 2,
+            worker_endpoints: Vec::new(),
+            discover_srv: None,
             batch_size: 5,
+            webhooks: Vec::new(),
+            tls_cert: None,
+            tls_key: None,
+            tls_ca: None,
         };
 
         let result = handle_distributed(action).await;
@@ -133,7 +145,13 @@ mod advanced_handler_tests {
         let action = DistributedAction::Scan {
             path: invalid_path,
             workers: 1,
+            worker_endpoints: Vec::new(),
+            discover_srv: None,
             batch_size: 10,
+            webhooks: Vec::new(),
+            tls_cert: None,
+            tls_key: None,
+            tls_ca: None,
         };
 
         let result = handle_distributed(action).await;
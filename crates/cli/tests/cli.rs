@@ -1,5 +1,5 @@
 use assert_cmd::Command;
-use code_guardian_core::Match;
+use code_guardian_core::{Match, Severity};
 use code_guardian_storage::{Scan, ScanRepository, SqliteScanRepository};
 use predicates::prelude::*;
 use std::fs;
@@ -39,6 +39,7 @@ fn test_history_command() {
             id: None,
             timestamp: chrono::Utc::now().timestamp(),
             root_path: "/test".to_string(),
+            project: None,
             matches: vec![],
         };
         repo.save_scan(&scan).unwrap();
@@ -53,6 +54,444 @@ fn test_history_command() {
         .stdout(predicate::str::contains("ID:"));
 }
 
+#[test]
+fn test_storage_stats_command() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    {
+        let mut repo = SqliteScanRepository::new(&db_path)
+            .unwrap()
+            .with_compression_threshold(1);
+        let matches = (0..200)
+            .map(|i| Match {
+                file_path: "src/lib.rs".into(),
+                line_number: i + 1,
+                column: 1,
+                pattern: "TODO".into(),
+                message: format!("TODO: remove the workaround for issue {}", i),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
+            })
+            .collect();
+        repo.save_scan(&Scan {
+            id: None,
+            timestamp: chrono::Utc::now().timestamp(),
+            root_path: "/test".to_string(),
+            project: None,
+            matches,
+        })
+        .unwrap();
+    }
+
+    let output = Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["storage", "stats", "--format", "json", "--db"])
+        .arg(&db_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats["matches"], 200);
+    assert_eq!(stats["compressed_scans"], 1);
+    assert!(stats["stored_message_bytes"].as_u64() < stats["message_bytes"].as_u64());
+
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["storage", "stats", "--db"])
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Compressed scans: 1"));
+}
+
+#[test]
+fn test_db_backup_restore_and_check() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let backup_path = temp_dir.path().join("backup.db");
+    let scan = |root_path: &str| Scan {
+        id: None,
+        timestamp: chrono::Utc::now().timestamp(),
+        root_path: root_path.to_string(),
+        project: None,
+        matches: vec![],
+    };
+    SqliteScanRepository::new(&db_path)
+        .unwrap()
+        .save_scan(&scan("/backed-up"))
+        .unwrap();
+
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["db", "backup"])
+        .arg(&backup_path)
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Backed up"));
+
+    SqliteScanRepository::new(&db_path)
+        .unwrap()
+        .save_scan(&scan("/lost"))
+        .unwrap();
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["db", "restore"])
+        .arg(&backup_path)
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(1 scans)"));
+    let scans = SqliteScanRepository::new(&db_path)
+        .unwrap()
+        .get_all_scans()
+        .unwrap();
+    assert_eq!(scans[0].root_path, "/backed-up");
+
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["db", "check", "--db"])
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("integrity check passed"));
+}
+
+#[test]
+fn test_db_migrations_and_newer_databases() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    drop(SqliteScanRepository::new(&db_path).unwrap());
+    let expected = code_guardian_storage::expected_schema_version();
+
+    let output = Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["db", "migrations", "--format", "json", "--db"])
+        .arg(&db_path)
+        .output()
+        .unwrap();
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(status["applied_version"], expected);
+    assert_eq!(status["expected_version"], expected);
+
+    // Opened by a newer code-guardian
+    rusqlite::Connection::open(&db_path)
+        .unwrap()
+        .execute(
+            "INSERT INTO refinery_schema_history (version, name, applied_on, checksum) VALUES (?1, 'future_table', '2030-01-01T00:00:00Z', '0')",
+            [expected + 1],
+        )
+        .unwrap();
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["history", "--db"])
+        .arg(&db_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "has schema version {}",
+            expected + 1
+        )))
+        .stderr(predicate::str::contains("upgrade code-guardian"));
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["db", "migrations", "--db"])
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("unknown to this build"));
+}
+
+#[test]
+fn test_project_namespaces() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("main.rs"), "// TODO: split\n").unwrap();
+
+    let scan = |project: &str| {
+        Command::cargo_bin("code_guardian_cli")
+            .unwrap()
+            .arg("scan")
+            .arg(&src)
+            .args(["--project", project, "--db"])
+            .arg(&db_path)
+            .assert()
+    };
+    scan("api").success();
+    scan("web").success();
+    scan("Web App")
+        .failure()
+        .stderr(predicate::str::contains("invalid project name 'Web App'"));
+
+    let scans = SqliteScanRepository::new(&db_path)
+        .unwrap()
+        .get_project_scans("api")
+        .unwrap();
+    assert_eq!(scans.len(), 1);
+    let api_scan = scans[0].id.unwrap().to_string();
+
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["history", "--project", "web", "--db"])
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Project: web"))
+        .stdout(predicate::str::contains("Project: api").not());
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["history", "--project", "docs", "--db"])
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "No scans found for project 'docs'",
+        ));
+
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["report", &api_scan, "--project", "api", "--db"])
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("TODO"));
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["report", &api_scan, "--project", "web", "--db"])
+        .arg(&db_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "belongs to project 'api', not to project 'web'",
+        ));
+}
+
+#[test]
+fn test_info() {
+    let output = Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["info", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let info: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+    assert!(info["features"].is_array());
+    assert!(info["profiles"]["basic"]
+        .as_array()
+        .unwrap()
+        .contains(&"TodoDetector".into()));
+    assert!(info["detectors"]
+        .as_array()
+        .unwrap()
+        .contains(&"TodoDetector".into()));
+    assert_eq!(info["languages"]["Rust"][0], "*.rs");
+    assert!(info["report_formats"]
+        .as_array()
+        .unwrap()
+        .contains(&"sarif".into()));
+    assert_eq!(info["storage"]["backend"], "sqlite");
+    assert!(info["storage"]["schema_version"].as_u64().unwrap() > 0);
+
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .arg("info")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Storage: SQLite"))
+        .stdout(predicate::str::contains("Report formats: text, json"));
+}
+
+#[test]
+fn test_audit_log() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("main.rs"), "// TODO: audit me\n").unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let audit_log = temp_dir.path().join("audit/log.jsonl");
+
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--audit-log")
+        .arg(&audit_log)
+        .env("USER", "auditor")
+        .assert()
+        .success();
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .arg("--audit-log")
+        .arg(&audit_log)
+        .args(["report", "999", "--format", "nope", "--db"])
+        .arg(&db_path)
+        .assert()
+        .failure();
+
+    let records: Vec<serde_json::Value> = fs::read_to_string(&audit_log)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["user"], "auditor");
+    assert_eq!(records[0]["command"], "scan");
+    assert_eq!(records[0]["exit_code"], 0);
+    assert_eq!(records[0]["config_hash"].as_str().unwrap().len(), 64);
+    assert_eq!(records[0]["scans"][0]["files"], 1);
+    assert_eq!(records[0]["scans"][0]["matches"], 1);
+    assert_eq!(records[1]["command"], "report");
+    assert_eq!(records[1]["exit_code"], 2);
+    assert!(records[1]["error"].is_string());
+}
+
+#[test]
+fn test_scan_dry_run() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("main.rs"), "// TODO: plan me\n").unwrap();
+    fs::write(src.join("lib.rs"), "fn lib() {}\n").unwrap();
+    let db_path = temp_dir.path().join("test.db");
+
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("TodoDetector"))
+        .stdout(predicate::str::contains("Files to scan: 2"))
+        .stdout(predicate::str::contains("Estimated duration: unknown"));
+    assert!(!db_path.exists());
+
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .success();
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "from the throughput of 1 earlier scans",
+        ));
+
+    let repo = SqliteScanRepository::new(&db_path).unwrap();
+    assert_eq!(repo.get_all_scans().unwrap().len(), 1);
+}
+
+#[test]
+fn test_tls_options() {
+    let temp_dir = TempDir::new().unwrap();
+    let not_a_cert = temp_dir.path().join("key.pem");
+    fs::write(&not_a_cert, "not a certificate").unwrap();
+
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["serve", "--port", "0", "--tls-cert"])
+        .arg(&not_a_cert)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--tls-key"));
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["serve", "--port", "0", "--tls-cert"])
+        .arg(&not_a_cert)
+        .arg("--tls-key")
+        .arg(&not_a_cert)
+        .arg("--db")
+        .arg(temp_dir.path().join("test.db"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("has no PEM certificate"));
+
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["distributed", "scan"])
+        .arg(temp_dir.path())
+        .args(["--worker-endpoint", "http://localhost:9", "--tls-ca"])
+        .arg(&not_a_cert)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "must start with https:// when worker TLS is configured",
+        ));
+}
+
+#[test]
+fn test_token_commands() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let token = |args: &[&str]| {
+        Command::cargo_bin("code_guardian_cli")
+            .unwrap()
+            .arg("token")
+            .args(args)
+            .arg("--db")
+            .arg(&db_path)
+            .assert()
+    };
+
+    let output = token(&["create", "ci", "--scope", "scan"])
+        .success()
+        .stdout(predicate::str::contains("Created scan token 'ci'"))
+        .get_output()
+        .stdout
+        .clone();
+    let created = String::from_utf8(output).unwrap();
+    let secret = created.lines().nth(1).unwrap();
+    assert!(secret.starts_with("cgt_"));
+    assert_eq!(
+        SqliteScanRepository::new(&db_path)
+            .unwrap()
+            .verify_api_token(secret)
+            .unwrap(),
+        Some(code_guardian_core::TokenScope::Scan)
+    );
+    token(&["create", "ci"])
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+    token(&["create", "dashboard", "--scope", "admin"])
+        .failure()
+        .stderr(predicate::str::contains("unknown token scope 'admin'"));
+
+    token(&["list"])
+        .success()
+        .stdout(predicate::str::contains("ci  scan  created"))
+        .stdout(predicate::str::contains(secret).not());
+    token(&["revoke", "ci"]).success();
+    token(&["revoke", "ci"])
+        .failure()
+        .stderr(predicate::str::contains("no token called 'ci'"));
+    token(&["list"])
+        .success()
+        .stdout(predicate::str::contains("No tokens."));
+}
+
 #[test]
 fn test_report_command() {
     let temp_dir = TempDir::new().unwrap();
@@ -62,12 +501,17 @@ fn test_report_command() {
         id: None,
         timestamp: chrono::Utc::now().timestamp(),
         root_path: "/test".to_string(),
+        project: None,
         matches: vec![Match {
-            file_path: "test.rs".to_string(),
+            file_path: "test.rs".into(),
             line_number: 1,
             column: 1,
-            pattern: "TODO".to_string(),
+            pattern: "TODO".into(),
             message: "TODO".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         }],
     };
     let id = repo.save_scan(&scan).unwrap();
@@ -91,12 +535,17 @@ fn test_compare_command() {
         id: None,
         timestamp: chrono::Utc::now().timestamp(),
         root_path: "/test".to_string(),
+        project: None,
         matches: vec![Match {
-            file_path: "test.rs".to_string(),
+            file_path: "test.rs".into(),
             line_number: 1,
             column: 1,
-            pattern: "TODO".to_string(),
+            pattern: "TODO".into(),
             message: "TODO".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         }],
     };
     let id1 = repo.save_scan(&scan1).unwrap();
@@ -104,20 +553,29 @@ fn test_compare_command() {
         id: None,
         timestamp: chrono::Utc::now().timestamp(),
         root_path: "/test".to_string(),
+        project: None,
         matches: vec![
             Match {
-                file_path: "test.rs".to_string(),
+                file_path: "test.rs".into(),
                 line_number: 1,
                 column: 1,
-                pattern: "TODO".to_string(),
+                pattern: "TODO".into(),
                 message: "TODO".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
             Match {
-                file_path: "test.js".to_string(),
+                file_path: "test.js".into(),
                 line_number: 2,
                 column: 1,
-                pattern: "FIXME".to_string(),
+                pattern: "FIXME".into(),
                 message: "FIXME".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
         ],
     };
@@ -143,6 +601,7 @@ fn test_invalid_format() {
         id: None,
         timestamp: chrono::Utc::now().timestamp(),
         root_path: "/test".to_string(),
+        project: None,
         matches: vec![],
     };
     let id = repo.save_scan(&scan).unwrap();
@@ -187,6 +646,67 @@ fn test_scan_non_existent_path() {
         .failure();
 }
 
+/// Scans `root` into a fresh database and returns the files with findings
+fn scanned_files(root: &std::path::Path) -> Vec<String> {
+    let db_dir = TempDir::new().unwrap();
+    let db_path = db_dir.path().join("test.db");
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .arg("scan")
+        .arg(root)
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .success();
+    let repo = SqliteScanRepository::new(&db_path).unwrap();
+    let scans = repo.get_all_scans().unwrap();
+    let scan = repo.get_scan(scans[0].id.unwrap()).unwrap().unwrap();
+    scan.matches
+        .into_iter()
+        .map(|m| m.file_path.to_string())
+        .collect()
+}
+
+#[test]
+fn test_scan_long_paths() {
+    // Well past the 260 characters of Windows' MAX_PATH
+    let temp_dir = TempDir::new().unwrap();
+    let mut deep = temp_dir.path().to_path_buf();
+    for i in 0..8 {
+        deep.push(format!("{}{}", "nested_directory_".repeat(2), i));
+    }
+    fs::create_dir_all(&deep).unwrap();
+    let file = deep.join("long_path.rs");
+    assert!(file.as_os_str().len() > 260);
+    fs::write(&file, "// TODO: found at any depth").unwrap();
+
+    let files = scanned_files(temp_dir.path());
+    assert_eq!(files, vec![file.to_string_lossy().to_string()]);
+}
+
+#[cfg(windows)]
+#[test]
+fn test_scan_verbatim_and_unc_paths() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("test.rs"), "// TODO: fix this").unwrap();
+    let canonical = std::fs::canonicalize(temp_dir.path()).unwrap();
+    let verbatim = canonical.to_string_lossy().to_string();
+    let plain = verbatim.trim_start_matches(r"\\?\").to_string();
+
+    // Reported without the prefix canonicalize adds
+    let files = scanned_files(&canonical);
+    assert_eq!(files, vec![format!(r"{}\test.rs", plain)]);
+
+    // The same directory through the administrative share of its drive,
+    // where the share is enabled
+    let drive = plain.chars().next().unwrap();
+    let unc = format!(r"\\localhost\{}${}", drive, &plain[2..]);
+    if std::path::Path::new(&unc).is_dir() {
+        let files = scanned_files(std::path::Path::new(&format!(r"\\?\UNC{}", &unc[1..])));
+        assert_eq!(files, vec![format!(r"{}\test.rs", unc)]);
+    }
+}
+
 #[test]
 fn test_scan_with_different_profiles() {
     let temp_dir = TempDir::new().unwrap();
@@ -303,6 +823,43 @@ fn test_scan_incremental() {
     }
 }
 
+#[test]
+fn test_scan_incremental_prune_dirs() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("lib.rs"), "// TODO: fix this").unwrap();
+    // Outside the scanned tree, so the state files don't show up as added
+    let db_dir = TempDir::new().unwrap();
+    let db_path = db_dir.path().join("test.db");
+    let scan = || {
+        let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+        cmd.arg("scan")
+            .arg(temp_dir.path())
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--incremental")
+            .arg("--prune-dirs");
+        cmd
+    };
+
+    scan().assert().success().stdout(predicate::str::contains(
+        "Directories reused unread: 0 of 2",
+    ));
+    fs::write(src.join("main.rs"), "// FIXME: and this").unwrap();
+    scan()
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added: 1"));
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(temp_dir.path())
+        .arg("--prune-dirs")
+        .assert()
+        .failure();
+}
+
 #[test]
 fn test_scan_distributed() {
     let temp_dir = TempDir::new().unwrap();
@@ -329,12 +886,17 @@ fn test_report_formats() {
         id: None,
         timestamp: chrono::Utc::now().timestamp(),
         root_path: "/test".to_string(),
+        project: None,
         matches: vec![Match {
-            file_path: "test.rs".to_string(),
+            file_path: "test.rs".into(),
             line_number: 1,
             column: 1,
-            pattern: "TODO".to_string(),
+            pattern: "TODO".into(),
             message: "TODO".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         }],
     };
     let id = repo.save_scan(&scan).unwrap();
@@ -397,12 +959,17 @@ fn test_compare_formats() {
         id: None,
         timestamp: chrono::Utc::now().timestamp(),
         root_path: "/test".to_string(),
+        project: None,
         matches: vec![Match {
-            file_path: "test.rs".to_string(),
+            file_path: "test.rs".into(),
             line_number: 1,
             column: 1,
-            pattern: "TODO".to_string(),
+            pattern: "TODO".into(),
             message: "TODO".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         }],
     };
     let id1 = repo.save_scan(&scan1).unwrap();
@@ -410,20 +977,29 @@ fn test_compare_formats() {
         id: None,
         timestamp: chrono::Utc::now().timestamp(),
         root_path: "/test".to_string(),
+        project: None,
         matches: vec![
             Match {
-                file_path: "test.rs".to_string(),
+                file_path: "test.rs".into(),
                 line_number: 1,
                 column: 1,
-                pattern: "TODO".to_string(),
+                pattern: "TODO".into(),
                 message: "TODO".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
             Match {
-                file_path: "test.js".to_string(),
+                file_path: "test.js".into(),
                 line_number: 2,
                 column: 1,
-                pattern: "FIXME".to_string(),
+                pattern: "FIXME".into(),
                 message: "FIXME".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
         ],
     };
@@ -484,6 +1060,58 @@ fn test_custom_detectors_load() {
         .stdout(predicate::str::contains("Loaded"));
 }
 
+#[test]
+fn test_migration_report_of_deprecated_apis() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    fs::create_dir(&project).unwrap();
+    fs::write(
+        project.join("billing.js"),
+        "payments.charge(1);\npayments.charge(2);\n",
+    )
+    .unwrap();
+    let rules = temp_dir.path().join("deprecations.json");
+    fs::write(
+        &rules,
+        r#"[
+  {"name": "OLD_CHARGE", "description": "", "pattern": "payments\\.charge\\(",
+   "file_extensions": ["js"], "case_sensitive": true, "multiline": false,
+   "capture_groups": [], "severity": "Medium", "category": "Deprecated",
+   "examples": [], "enabled": true,
+   "deprecation": {"since": "4.0", "replacement": "payments.createIntent()"}},
+  {"name": "OLD_REFUND", "description": "", "pattern": "payments\\.refund\\(",
+   "file_extensions": ["js"], "case_sensitive": true, "multiline": false,
+   "capture_groups": [], "severity": "Medium", "category": "Deprecated",
+   "examples": [], "enabled": true,
+   "deprecation": {"since": "4.2", "replacement": "payments.reverse()"}}
+]"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("migration")
+        .arg(&project)
+        .arg("--custom-detectors")
+        .arg(&rules)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "2 deprecated APIs, 1 fully migrated, 2 usages left",
+        ))
+        .stdout(predicate::str::contains(
+            "OLD_CHARGE: 2 usages in 1 files (deprecated since 4.0, use payments.createIntent())",
+        ))
+        .stdout(predicate::str::contains("OLD_REFUND: migrated"));
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("migration")
+        .arg(&project)
+        .arg("--custom-detectors")
+        .arg(temp_dir.path().join("missing.json"))
+        .assert()
+        .failure();
+}
+
 #[test]
 fn test_custom_detectors_test() {
     let temp_dir = TempDir::new().unwrap();
@@ -582,6 +1210,68 @@ fn test_completion() {
     cmd.arg("completion").arg("bash").assert().success();
 }
 
+#[test]
+fn test_dynamic_completion_and_man_pages() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("data")).unwrap();
+    fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+    fs::write(temp_dir.path().join("src/main.rs"), "// TODO: complete\n").unwrap();
+    fs::write(
+        temp_dir.path().join("code-guardian.toml"),
+        "[targets.api]\npath = \"services/api\"\n",
+    )
+    .unwrap();
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["scan", "src"])
+        .assert()
+        .success();
+    let complete = |words: &[&str]| {
+        let output = Command::cargo_bin("code_guardian_cli")
+            .unwrap()
+            .current_dir(temp_dir.path())
+            .env("COMPLETE", "fish")
+            .args(["--", "code-guardian"])
+            .args(words)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        String::from_utf8(output).unwrap()
+    };
+
+    assert!(complete(&["report", ""])
+        .lines()
+        .any(|l| l.starts_with("1\t")));
+    assert_eq!(complete(&["scan", "--profile", "rust-p"]), "rust-panics\n");
+    assert_eq!(
+        complete(&["scan", "--config", "code-guardian.toml", "--target", ""]),
+        "api\tservices/api\n"
+    );
+
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["completion", "bash", "--dynamic"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("COMPLETE=\"bash\""));
+
+    let man_dir = temp_dir.path().join("man");
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .arg("completion")
+        .arg("--man")
+        .arg(&man_dir)
+        .assert()
+        .success();
+    assert!(man_dir.join("code-guardian.1").is_file());
+    assert!(fs::read_to_string(man_dir.join("code-guardian-scan.1"))
+        .unwrap()
+        .contains("\\-\\-profile"));
+}
+
 #[test]
 fn test_scan_with_custom_detectors() {
     let temp_dir = TempDir::new().unwrap();
@@ -625,4 +1315,2370 @@ fn test_scan_with_custom_detectors() {
     let scan = repo.get_scan(scans[0].id.unwrap()).unwrap().unwrap();
     // Should find at least TODO
     assert!(!scan.matches.is_empty());
+
+    // Listing files prints nothing else to stdout
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(temp_dir.path())
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--custom-detectors")
+        .arg(&custom_detectors_file)
+        .arg("--list-files")
+        .arg("-0")
+        .assert()
+        .success()
+        .stdout(format!("{}\0", test_file.display()));
+}
+
+#[test]
+fn test_ci_gate_honors_custom_detector_severity() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("config.py"), "API_TOKEN = load()\n").unwrap();
+    let custom_detectors_file = temp_dir.path().join("custom.json");
+    fs::write(
+        &custom_detectors_file,
+        r#"[{
+            "name": "API_TOKEN",
+            "description": "Tokens must come from the vault",
+            "pattern": "API_TOKEN",
+            "file_extensions": [],
+            "case_sensitive": true,
+            "multiline": false,
+            "capture_groups": [],
+            "severity": "Critical",
+            "category": "Security",
+            "examples": [],
+            "enabled": true
+        }]"#,
+    )
+    .unwrap();
+    let report_path = temp_dir.path().join("report.json");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("ci-gate")
+        .arg(&src)
+        .arg("--custom-detectors")
+        .arg(&custom_detectors_file)
+        .arg("--max-critical")
+        .arg("0")
+        .arg("--output")
+        .arg(&report_path)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("Critical: 1/0"));
+
+    let report: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+    let finding = &report["matches"][0];
+    assert_eq!(finding["pattern"], "API_TOKEN");
+    assert_eq!(finding["severity"], "Critical");
+    assert_eq!(finding["category"], "Security");
+}
+
+#[test]
+fn test_exit_codes_for_threshold_and_usage_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("app.js"), "debugger;\n").unwrap();
+
+    // Findings above threshold exit with 1
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("ci-gate")
+        .arg(temp_dir.path())
+        .arg("--max-critical")
+        .arg("0")
+        .assert()
+        .code(1);
+
+    // --exit-zero turns threshold failures into success
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("ci-gate")
+        .arg(temp_dir.path())
+        .arg("--max-critical")
+        .arg("0")
+        .arg("--exit-zero")
+        .assert()
+        .code(0);
+
+    // Usage errors exit with 2, even with --exit-zero
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg("/non/existent/path")
+        .arg("--db")
+        .arg(temp_dir.path().join("test.db"))
+        .arg("--exit-zero")
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_scan_report_skipped() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("ok.rs"), "// TODO: readable\n").unwrap();
+    fs::write(temp_dir.path().join("latin1.rs"), b"// TODO: caf\xe9\n").unwrap();
+    let db_path = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(temp_dir.path())
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "1 files could not be scanned (use --report-skipped to list them)",
+        ));
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(temp_dir.path())
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--report-skipped")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("latin1.rs: invalid UTF-8"));
+}
+
+#[test]
+fn test_scan_metrics_out() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("main.rs"), "// TODO: one\n// FIXME: two\n").unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let metrics_path = temp_dir.path().join("metrics.json");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--metrics-out")
+        .arg(&metrics_path)
+        .assert()
+        .success();
+
+    let metrics: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&metrics_path).unwrap()).unwrap();
+    assert_eq!(metrics["scan_id"], 1);
+    assert_eq!(metrics["total_files_scanned"], 1);
+    assert_eq!(metrics["total_lines_processed"], 2);
+    assert_eq!(metrics["total_matches_found"], 2);
+    assert!(metrics["scan_duration_ms"].is_u64());
+    assert_eq!(metrics["skipped_files"], serde_json::json!([]));
+}
+
+#[test]
+fn test_distributed_scan_saves_one_consolidated_scan() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    for name in ["a.rs", "b.rs", "c.rs"] {
+        fs::write(src.join(name), "// FIXME: two\n// TODO: one\n").unwrap();
+    }
+    let db_path = temp_dir.path().join("test.db");
+    let metrics_path = temp_dir.path().join("metrics.json");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--distributed")
+        .arg("--batch-size")
+        .arg("1")
+        .arg("--metrics-out")
+        .arg(&metrics_path)
+        .assert()
+        .success();
+
+    let repo = SqliteScanRepository::new(&db_path).unwrap();
+    let scans = repo.get_all_scans().unwrap();
+    assert_eq!(scans.len(), 1);
+    let scan = repo.get_scan(scans[0].id.unwrap()).unwrap().unwrap();
+    let locations: Vec<_> = scan
+        .matches
+        .iter()
+        .map(|m| {
+            let file = m.file_path.rsplit('/').next().unwrap().to_string();
+            (file, m.line_number)
+        })
+        .collect();
+    let mut sorted = locations.clone();
+    sorted.sort();
+    assert_eq!(locations, sorted);
+    assert_eq!(locations.len(), 6);
+
+    let metrics: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&metrics_path).unwrap()).unwrap();
+    assert_eq!(metrics["total_files_scanned"], 3);
+    let workers = metrics["worker_metrics"].as_array().unwrap();
+    let units: u64 = workers
+        .iter()
+        .map(|w| w["units_completed"].as_u64().unwrap())
+        .sum();
+    assert_eq!(units, 3);
+}
+
+#[test]
+fn test_distributed_scan_rejects_incompatible_worker() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("main.rs"), "// TODO: one\n").unwrap();
+    let (url, server) = one_shot_http_server(
+        r#"{"worker_id":"old-worker","version":"0.0.1","protocol_version":1,"supported_detectors":["TODO"]}"#,
+    );
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("distributed")
+        .arg("scan")
+        .arg(".")
+        .arg("--worker-endpoint")
+        .arg(&url)
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Worker old-worker (http://127.0.0.1:",
+        ))
+        .stderr(predicate::str::contains("runs version 0.0.1"));
+
+    let (headers, body) = server.join().unwrap();
+    assert!(headers.starts_with("POST /hook/handshake"));
+    let request: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(request["protocol_version"], 1);
+    assert_eq!(request["detectors"], serde_json::json!(["FIXME", "TODO"]));
+}
+
+#[test]
+fn test_scan_content_cache_key() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("main.rs"), "// TODO: one\n").unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let metrics_path = temp_dir.path().join("metrics.json");
+    let scan = || {
+        let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+        cmd.arg("scan")
+            .arg(&src)
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--cache-key")
+            .arg("content")
+            .arg("--metrics-out")
+            .arg(&metrics_path)
+            .assert()
+            .success();
+        let metrics: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&metrics_path).unwrap()).unwrap();
+        (
+            metrics["cache_hits"].clone(),
+            metrics["total_matches_found"].clone(),
+        )
+    };
+
+    assert_eq!(scan(), (0.into(), 1.into()));
+    assert!(src.join(".code-guardian/cache/scan-cache.json").exists());
+    // Rewriting the file gives it a new timestamp but the same content
+    fs::write(src.join("main.rs"), "// TODO: one\n").unwrap();
+    assert_eq!(scan(), (1.into(), 1.into()));
+
+    // Another profile invalidates the cache, noted on stderr only
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--cache-key")
+        .arg("content")
+        .arg("--profile")
+        .arg("comprehensive")
+        .arg("--list-files")
+        .arg("-0")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Scan cache invalidated due to rule change: 1 cached file result(s)",
+        ))
+        .stdout(format!("{}\0", src.join("main.rs").display()));
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--cache-key")
+        .arg("atime")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Supported: mtime, content"));
+}
+
+#[test]
+fn test_scan_cache_shared_across_worktrees() {
+    let temp_dir = TempDir::new().unwrap();
+    let cache_dir = temp_dir.path().join("shared-cache");
+    let db_path = temp_dir.path().join("test.db");
+    for branch in ["main", "feature"] {
+        let worktree = temp_dir.path().join(branch);
+        fs::create_dir(&worktree).unwrap();
+        fs::write(worktree.join("lib.rs"), "// TODO: shared\n").unwrap();
+    }
+    fs::write(temp_dir.path().join("feature/new.rs"), "// FIXME: new\n").unwrap();
+    let scan = |branch: &str| {
+        let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+        cmd.arg("scan")
+            .arg(temp_dir.path().join(branch))
+            .arg("--db")
+            .arg(&db_path)
+            .arg("--cache-key")
+            .arg("content")
+            .arg("--cache-dir")
+            .arg(&cache_dir)
+            .assert()
+            .success()
+    };
+
+    scan("main").stderr(predicate::str::contains(
+        "Scan cache: 0 of 1 files reused (0.0% hit rate)",
+    ));
+    assert!(cache_dir.join("scan-cache.json").exists());
+    // lib.rs is identical on the other branch, new.rs isn't cached yet
+    scan("feature")
+        .stderr(predicate::str::contains(
+            "Scan cache: 1 of 2 files reused (50.0% hit rate)",
+        ))
+        .stdout(predicate::str::contains("feature/lib.rs"));
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("history")
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cache_hit_rate=50.0%"));
+
+    // Only the files are printed to stdout for `xargs -0`
+    let worktree = temp_dir.path().join("main");
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&worktree)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--cache-key")
+        .arg("content")
+        .arg("--cache-dir")
+        .arg(&cache_dir)
+        .arg("--list-files")
+        .arg("-0")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("hit rate"))
+        .stdout(format!("{}\0", worktree.join("lib.rs").display()));
+}
+
+/// Accepts one HTTP request on a local port, returning the URL and a handle
+/// yielding the request's headers and body
+/// Accepts one request, answers it with `response` as a JSON body and
+/// returns the request's headers and body
+fn one_shot_http_server(
+    response: &'static str,
+) -> (String, std::thread::JoinHandle<(String, String)>) {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut headers = String::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" {
+                break;
+            }
+            headers.push_str(&line);
+        }
+        let length: usize = headers
+            .lines()
+            .find_map(|l| {
+                l.to_lowercase()
+                    .strip_prefix("content-length:")
+                    .map(|v| v.trim().parse().unwrap())
+            })
+            .unwrap();
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        let reply = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            response.len(),
+            response
+        );
+        stream.write_all(reply.as_bytes()).unwrap();
+        (headers, String::from_utf8(body).unwrap())
+    });
+    (url, handle)
+}
+
+#[test]
+fn test_scan_webhook() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("main.rs"), "// TODO: one\n").unwrap();
+    let (url, server) = one_shot_http_server("");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.env("CODE_GUARDIAN_WEBHOOK_SECRET", "s3cret")
+        .arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(temp_dir.path().join("test.db"))
+        .arg("--webhook")
+        .arg(&url)
+        .assert()
+        .success();
+
+    let (headers, body) = server.join().unwrap();
+    let signature = code_guardian_core::sign_payload(b"s3cret", body.as_bytes());
+    assert!(headers.contains(&format!("X-Code-Guardian-Signature: {}", signature)));
+    let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(payload["event"], "scan.completed");
+    assert_eq!(payload["scan_id"], 1);
+    assert_eq!(payload["summary"]["total_matches"], 1);
+    assert_eq!(payload["threshold"]["status"], "not_configured");
+
+    // Invalid URLs are rejected before scanning
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--webhook")
+        .arg("example.com/hook")
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_scan_triggers() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(
+        src.join("main.rs"),
+        "// TODO: one\nlet key = load().unwrap();\n",
+    )
+    .unwrap();
+    fs::write(src.join("lib.rs"), "let value = parse().unwrap();\n").unwrap();
+    let db = temp_dir.path().join("test.db");
+    let (url, server) = one_shot_http_server("");
+    let config = temp_dir.path().join("code-guardian.toml");
+    fs::write(
+        &config,
+        format!(
+            r#"
+[[triggers]]
+name = "todos"
+patterns = ["TODO"]
+actions = [
+    {{ action = "deep_scan", profile = "security" }},
+    {{ action = "webhook", url = "{}" }},
+    {{ action = "quarantine" }},
+]
+"#,
+            url
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.env("CODE_GUARDIAN_WEBHOOK_SECRET", "s3cret")
+        .arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Trigger 'todos' fired on 1 finding(s) in 1 file(s)",
+        ))
+        .stderr(predicate::str::contains(
+            "security profile rescan added 1 finding(s)",
+        ));
+
+    // Only the file with the TODO is scanned again
+    let repo = SqliteScanRepository::new(&db).unwrap();
+    let scan = repo.get_scan(1).unwrap().unwrap();
+    let found: Vec<(&str, &str)> = scan
+        .matches
+        .iter()
+        .map(|m| (m.file_path.as_str(), m.pattern.as_str()))
+        .collect();
+    assert_eq!(found.len(), 2, "{:?}", found);
+    assert!(found.iter().all(|(file, _)| file.ends_with("main.rs")));
+    let reason = "trigger 'todos' fired on 1 finding(s) in 1 file(s)";
+    assert_eq!(repo.get_quarantine(1).unwrap().as_deref(), Some(reason));
+
+    let (headers, body) = server.join().unwrap();
+    let signature = code_guardian_core::sign_payload(b"s3cret", body.as_bytes());
+    assert!(headers.contains(&format!("X-Code-Guardian-Signature: {}", signature)));
+    assert!(headers.contains("X-Code-Guardian-Event: trigger.fired"));
+    let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(payload["event"], "trigger.fired");
+    assert_eq!(payload["trigger"], "todos");
+    assert_eq!(payload["scan_id"], 1);
+    assert_eq!(payload["quarantined"], true);
+    assert_eq!(payload["matches"].as_array().unwrap().len(), 1);
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("history")
+        .arg("--db")
+        .arg(&db)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("Quarantined: {}", reason)));
+
+    // Deep scans with unknown profiles are rejected before scanning
+    fs::write(
+        &config,
+        "[[triggers]]\nname = \"todos\"\npatterns = [\"TODO\"]\nactions = [{ action = \"deep_scan\", profile = \"deeper\" }]\n",
+    )
+    .unwrap();
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Unknown profile 'deeper' of trigger 'todos'",
+        ));
+}
+
+#[test]
+fn test_scan_sample_above() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    let todos: String = (1..=20).map(|i| format!("// TODO: item {}\n", i)).collect();
+    fs::write(src.join("main.rs"), format!("{}// XXX: critical\n", todos)).unwrap();
+    let db = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db)
+        .arg("--profile")
+        .arg("comprehensive")
+        .arg("--sample-above")
+        .arg("5")
+        .arg("--sample-per-file")
+        .arg("3")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "+17 more TODO findings sampled out of this file",
+        ))
+        .stderr(predicate::str::contains(
+            "21 findings exceed --sample-above 5",
+        ));
+
+    let repo = SqliteScanRepository::new(&db).unwrap();
+    let scan = repo.get_scan(1).unwrap().unwrap();
+    let count = |pattern: &str| scan.matches.iter().filter(|m| m.pattern == pattern).count();
+    assert_eq!(count("XXX"), 1);
+    assert_eq!(count("TODO"), 3);
+    assert_eq!(count("SAMPLED"), 1);
+}
+
+#[test]
+fn test_finding_state_workflow() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("main.rs"), "// TODO: keep\n// FIXME: remove\n").unwrap();
+    let db = temp_dir.path().join("test.db");
+    let scan = || {
+        let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+        cmd.arg("scan")
+            .arg(&src)
+            .arg("--db")
+            .arg(&db)
+            .assert()
+            .success();
+    };
+    let list = |state: &str| -> Vec<serde_json::Value> {
+        let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+        let output = cmd
+            .args([
+                "findings", "list", "--format", "json", "--state", state, "--db",
+            ])
+            .arg(&db)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        serde_json::from_slice(&output).unwrap()
+    };
+
+    scan();
+    let open = list("open");
+    assert_eq!(open.len(), 2);
+    let todo = open.iter().find(|f| f["pattern"] == "TODO").unwrap()["fingerprint"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.args(["findings", "ack", &todo, "--note", "tracked in #7", "--db"])
+        .arg(&db)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("is now acknowledged"));
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.args(["findings", "wontfix", "0000000000000000", "--db"])
+        .arg(&db)
+        .assert()
+        .code(2);
+
+    // The FIXME is resolved and the TODO moves down a line
+    fs::write(src.join("main.rs"), "\n// TODO: keep\n").unwrap();
+    scan();
+    assert_eq!(list("acknowledged").len(), 1);
+    assert_eq!(list("fixed")[0]["pattern"], "FIXME");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.args(["compare", "1", "2", "--db"])
+        .arg(&db)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "State changes from scan 1 to scan 2:",
+        ))
+        .stderr(
+            predicate::str::contains("FIXME")
+                .and(predicate::str::contains("open -> fixed (scan 2)")),
+        )
+        .stderr(predicate::str::contains(
+            "open -> acknowledged (set by hand: tracked in #7)",
+        ));
+}
+
+#[test]
+fn test_report_pagination() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    let todos: String = (1..=5).map(|i| format!("// TODO: item {}\n", i)).collect();
+    fs::write(src.join("main.rs"), format!("{}// FIXME: later\n", todos)).unwrap();
+    let db = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db)
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    let output = cmd
+        .args(["report", "1", "--format", "json", "--pattern", "todo"])
+        .args(["--offset", "1", "--limit", "2", "--db"])
+        .arg(&db)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Showing matches 2-3 of 5 (next page: --offset 3)",
+        ))
+        .get_output()
+        .stdout
+        .clone();
+    let matches: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let lines: Vec<u64> = matches
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["line_number"].as_u64().unwrap())
+        .collect();
+    assert_eq!(lines, vec![2, 3]);
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.args(["report", "1", "--limit", "0", "--db"])
+        .arg(&db)
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_scan_rule_paths() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    for dir in ["src", "examples"] {
+        fs::create_dir_all(project.join(dir)).unwrap();
+        fs::write(
+            project.join(dir).join("app.js"),
+            "console.log(1);\n// TODO: tidy\n",
+        )
+        .unwrap();
+    }
+    let config = temp_dir.path().join("code-guardian.toml");
+    fs::write(
+        &config,
+        "[rule_paths.CONSOLE_LOG]\ninclude = [\"src/**\"]\n\n[rule_paths.TODO]\nexclude = [\"examples/**\"]\n",
+    )
+    .unwrap();
+    let db = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&project)
+        .arg("--db")
+        .arg(&db)
+        .arg("--config")
+        .arg(&config)
+        .arg("--profile")
+        .arg("comprehensive")
+        .assert()
+        .success();
+
+    let repo = SqliteScanRepository::new(&db).unwrap();
+    let scan = repo.get_scan(1).unwrap().unwrap();
+    let mut found: Vec<(String, String)> = scan
+        .matches
+        .iter()
+        .filter(|m| m.pattern == "CONSOLE_LOG" || m.pattern == "TODO")
+        .map(|m| {
+            let dir = if m.file_path.contains("examples") {
+                "examples"
+            } else {
+                "src"
+            };
+            (m.pattern.to_string(), dir.to_string())
+        })
+        .collect();
+    found.sort();
+    assert_eq!(
+        found,
+        vec![
+            ("CONSOLE_LOG".to_string(), "src".to_string()),
+            ("TODO".to_string(), "src".to_string())
+        ]
+    );
+
+    // Invalid globs are rejected before scanning
+    fs::write(&config, "[rule_paths.TODO]\ninclude = [\"src/[\"]\n").unwrap();
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&project)
+        .arg("--db")
+        .arg(&db)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("rule_paths.todo"));
+}
+
+#[test]
+fn test_scan_llm_triage() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("a.js"), "const n = parseInt(str);\n").unwrap();
+    let (url, server) = one_shot_http_server(r#"{"verdict":"false_positive","confidence":0.05}"#);
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.env("CODE_GUARDIAN_LLM_TRIAGE_ENDPOINT", &url)
+        .env("CODE_GUARDIAN_LLM_TRIAGE_API_KEY", "sk-s3cret")
+        .arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(temp_dir.path().join("test.db"))
+        .arg("--profile")
+        .arg("llm-quality")
+        .arg("--llm-triage")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "[triage: likely false positive, confidence 0.05]",
+        ));
+
+    let (headers, body) = server.join().unwrap();
+    // Read by curl from a file, not from its command line
+    assert!(
+        headers.contains("Authorization: Bearer sk-s3cret"),
+        "{}",
+        headers
+    );
+    let request: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(request["pattern"], "LLM_JS_ISSUES");
+    assert!(request["context"]
+        .as_str()
+        .unwrap()
+        .contains("1 | const n = parseInt(str);"));
+
+    // An unreachable endpoint only warns
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.env(
+        "CODE_GUARDIAN_LLM_TRIAGE_ENDPOINT",
+        "http://127.0.0.1:1/triage",
+    )
+    .arg("scan")
+    .arg(&src)
+    .arg("--db")
+    .arg(temp_dir.path().join("test.db"))
+    .arg("--profile")
+    .arg("llm-quality")
+    .arg("--llm-triage")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("LLM_JS_ISSUES"))
+    .stderr(predicate::str::contains(
+        "remaining findings were not triaged",
+    ));
+
+    // No endpoint configured
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.env_remove("CODE_GUARDIAN_LLM_TRIAGE_ENDPOINT")
+        .arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(temp_dir.path().join("test.db"))
+        .arg("--llm-triage")
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_mcp_server() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("lib.rs"), "// TODO: wire up\n").unwrap();
+    let db = temp_dir.path().join("scans.db");
+
+    let requests = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"scan_path","arguments":{"path":"."}}}"#,
+    ];
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    let output = cmd
+        .arg("mcp")
+        .arg("--root")
+        .arg(temp_dir.path())
+        .arg("--db")
+        .arg(&db)
+        .write_stdin(requests.join("\n") + "\n")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    // Notifications get no response
+    let responses: Vec<serde_json::Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(responses.len(), 3);
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[1]["result"]["tools"].as_array().unwrap().len(), 3);
+
+    let text = responses[2]["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap();
+    let scan: serde_json::Value = serde_json::from_str(text).unwrap();
+    assert_eq!(scan["summary"]["total_matches"], 1);
+    assert!(db.exists());
+
+    // An unknown profile is a usage error
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("mcp")
+        .arg("--root")
+        .arg(temp_dir.path())
+        .arg("--profile")
+        .arg("nope")
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_scan_stdin() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    let output = cmd
+        .current_dir(temp_dir.path())
+        .arg("scan")
+        .arg("--stdin")
+        .arg("--filename")
+        .arg("src/unsaved.rs")
+        .write_stdin("fn main() {}\n// TODO: not on disk\n")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let matches: Vec<Match> = serde_json::from_slice(&output).unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].file_path, "src/unsaved.rs");
+    assert_eq!(matches[0].line_number, 2);
+    assert_eq!(matches[0].pattern, "TODO");
+    // Nothing is written to disk, not even the default database
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+
+    // A path and --stdin are mutually exclusive
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(temp_dir.path())
+        .arg("--stdin")
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_inventory_command() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("main.rs"), "fn main() {\n    x.unwrap();\n}\n").unwrap();
+    fs::write(src.join("app.py"), "print(1)\n").unwrap();
+    fs::write(src.join("notes.adoc"), "= Notes\n").unwrap();
+    let db = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    let output = cmd
+        .args(["inventory", "--profile", "rust", "--format", "json", "--db"])
+        .arg(&db)
+        .arg(&src)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Inventory saved with scan ID: 1"))
+        .get_output()
+        .stdout
+        .clone();
+    let inventory: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(inventory["files"], 3);
+    assert_eq!(inventory["languages"][0]["language"], "Rust");
+    assert_eq!(inventory["languages"][0]["lines"], 3);
+    assert_eq!(inventory["languages"][0]["matches"], 1);
+    assert_eq!(inventory["unrecognized"]["adoc"], 1);
+    let python = inventory["languages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|l| l["language"] == "Python")
+        .unwrap();
+    assert!(python["detectors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|d| d != "UnwrapDetector"));
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.args(["inventory", "--scan", "1", "--db"])
+        .arg(&db)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Rust"))
+        .stdout(predicate::str::contains(
+            "Unrecognized file types: .adoc (1)",
+        ));
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.args(["inventory", "--scan", "2", "--db"])
+        .arg(&db)
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_coverage_command() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+    fs::write(temp_dir.path().join("Dockerfile"), "FROM rust:latest\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    let output = cmd
+        .args(["coverage", "--profile", "iac", "--format", "json"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["files_scanned"], 2);
+    assert_eq!(report["uncovered_extensions"]["rs"], 1);
+    let silent = report["silent_detectors"].as_array().unwrap();
+    assert!(silent.iter().any(|d| d == "TerraformDetector"));
+    assert!(!silent.iter().any(|d| d == "DockerfileDetector"));
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.args(["coverage", "--profile", "iac"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Detectors with no matches"))
+        .stdout(predicate::str::contains(
+            "File types no detector looks at: .rs (1)",
+        ));
+}
+
+#[test]
+fn test_production_check_reports_commented_out_code() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("app.js"),
+        "// console.log('old');\nconst x = 1;\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.args(["production-check", "--format", "json"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("CONSOLE_LOG").not())
+        .stdout(predicate::str::contains("COMMENTED_OUT_CODE").not());
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.args([
+        "production-check",
+        "--format",
+        "json",
+        "--report-commented-out",
+        "--fail-on-high",
+    ])
+    .arg(temp_dir.path())
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("COMMENTED_OUT_CODE"));
+}
+
+#[test]
+fn test_scan_quality_profile_magic_numbers() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    fs::create_dir(&project).unwrap();
+    fs::write(
+        project.join("app.js"),
+        "const RETRIES = 5;\nsetTimeout(run, 86400);\nfetchPage(42);\n",
+    )
+    .unwrap();
+    let config = temp_dir.path().join("code-guardian.toml");
+    fs::write(&config, "[magic_numbers]\nallowlist = [42]\n").unwrap();
+    let db = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&project)
+        .arg("--db")
+        .arg(&db)
+        .arg("--config")
+        .arg(&config)
+        .arg("--profile")
+        .arg("quality")
+        .assert()
+        .success();
+
+    let repo = SqliteScanRepository::new(&db).unwrap();
+    let scan = repo.get_scan(1).unwrap().unwrap();
+    let found: Vec<(usize, &str)> = scan
+        .matches
+        .iter()
+        .map(|m| (m.line_number, m.pattern.as_str()))
+        .collect();
+    assert_eq!(found, vec![(2, "MAGIC_NUMBER")]);
+    assert_eq!(scan.matches[0].category.as_deref(), Some("CodeQuality"));
+}
+
+#[test]
+fn test_scan_length_limits_from_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    fs::create_dir(&project).unwrap();
+    fs::write(
+        project.join("app.py"),
+        "def long():\n    a = 1\n    b = 2\n    return a + b\n\ndef short():\n    return 0\n",
+    )
+    .unwrap();
+    let config = temp_dir.path().join("code-guardian.toml");
+    fs::write(
+        &config,
+        "[length_limits.languages.py]\nfile_lines = 5\nfunction_lines = 3\n",
+    )
+    .unwrap();
+    let db = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&project)
+        .arg("--db")
+        .arg(&db)
+        .arg("--config")
+        .arg(&config)
+        .arg("--profile")
+        .arg("quality")
+        .assert()
+        .success();
+
+    let repo = SqliteScanRepository::new(&db).unwrap();
+    let scan = repo.get_scan(1).unwrap().unwrap();
+    let mut found: Vec<&str> = scan.matches.iter().map(|m| m.message.as_str()).collect();
+    found.sort();
+    assert_eq!(
+        found,
+        vec![
+            "FILE_TOO_LONG: 7 lines, limit 5",
+            "FUNCTION_TOO_LONG: long spans 4 lines, limit 3",
+        ]
+    );
+}
+
+#[test]
+fn test_scan_stale_feature_flags_from_registry() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    fs::create_dir(&project).unwrap();
+    fs::write(
+        project.join("app.ts"),
+        "if (flags.isEnabled('new-checkout')) { checkout(); }\nif (flags.isEnabled('old-banner')) { banner(); }\n",
+    )
+    .unwrap();
+    let registry = temp_dir.path().join("flags.json");
+    fs::write(&registry, r#"["new-checkout"]"#).unwrap();
+    let config = temp_dir.path().join("code-guardian.toml");
+    fs::write(
+        &config,
+        format!(
+            "[feature_flags]\nregistry = {:?}\n",
+            registry.to_string_lossy()
+        ),
+    )
+    .unwrap();
+    let db = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&project)
+        .arg("--db")
+        .arg(&db)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success();
+
+    let repo = SqliteScanRepository::new(&db).unwrap();
+    let scan = repo.get_scan(1).unwrap().unwrap();
+    let found: Vec<(usize, &str)> = scan
+        .matches
+        .iter()
+        .map(|m| (m.line_number, m.message.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![(
+            2,
+            "STALE_FEATURE_FLAG: 'old-banner' is not in the flag registry"
+        )]
+    );
+}
+
+#[test]
+fn test_lang_audits_test_hygiene_outside_production_mode() {
+    let temp_dir = TempDir::new().unwrap();
+    let tests = temp_dir.path().join("__tests__");
+    fs::create_dir(&tests).unwrap();
+    fs::write(
+        tests.join("cart.test.js"),
+        "it.skip('adds items', () => {\n  expect(add()).toBe(1);\n});\nit('totals', async () => {\n  await sleep(500);\n  total();\n});\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("lang")
+        .arg("js")
+        .arg("--path")
+        .arg(temp_dir.path())
+        .arg("--production=false")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DISABLED_TEST"))
+        .stdout(predicate::str::contains("ASSERTLESS_TEST"))
+        .stdout(predicate::str::contains("SLEEP_IN_TEST"));
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("lang")
+        .arg("js")
+        .arg("--path")
+        .arg(temp_dir.path())
+        .arg("--production")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DISABLED_TEST").not());
+}
+
+#[test]
+fn test_stack_backend_runs_go_detectors() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("server.go"),
+        "package server\n\nfunc handle(w http.ResponseWriter, r *http.Request) {\n\ttime.Sleep(time.Second)\n\tpanic(\"unreachable\")\n}\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("stack")
+        .arg("backend")
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("GO_SLEEP_IN_HANDLER"))
+        .stdout(predicate::str::contains("GO_PANIC"));
+}
+
+#[test]
+fn test_stack_backend_runs_python_detectors() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("orders.py"),
+        "def add(item, items=[]):\n    assert item, \"item is required\"\n    items.append(item)\n    return items\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("stack")
+        .arg("backend")
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PY_MUTABLE_DEFAULT"))
+        .stdout(predicate::str::contains("PY_ASSERT"));
+}
+
+#[test]
+fn test_stack_backend_runs_jvm_detectors() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Users.kt"),
+        "fun load(id: Long): User {\n    try {\n        return repository.find(id)!!\n    } catch (e: Exception) {\n        e.printStackTrace()\n        throw e\n    }\n}\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("stack")
+        .arg("backend")
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("KOTLIN_NOT_NULL_ASSERTION"))
+        .stdout(predicate::str::contains("JVM_PRINT_STACK_TRACE"));
+}
+
+#[test]
+fn test_stack_systems_runs_c_detectors() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("main.c"),
+        "int main(void) {\n    char line[32];\n    gets(line);\n    return system(line);\n}\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("stack")
+        .arg("systems")
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("C_UNSAFE_STRING_FUNCTION"))
+        .stdout(predicate::str::contains("C_SYSTEM_CALL"));
+}
+
+#[test]
+fn test_scan_iac_profile_checks_shell_scripts() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    fs::write(
+        temp_dir.path().join("install.sh"),
+        "#!/bin/bash\nset -euo pipefail\ncurl -fsSL https://example.com/setup.sh | bash\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(temp_dir.path())
+        .arg("--profile")
+        .arg("iac")
+        .arg("--db")
+        .arg(&db_path)
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("report")
+        .arg("1")
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("SHELL_CURL_PIPE"))
+        .stdout(predicate::str::contains("SHELL_MISSING_STRICT_MODE").not());
+}
+
+#[test]
+fn test_scan_migrations_profile_uses_configured_paths() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    fs::create_dir_all(project.join("db/changes")).unwrap();
+    fs::write(
+        project.join("db/changes/002_backfill.sql"),
+        "CREATE INDEX users_email ON users (email);\nUPDATE users SET active = false;\n",
+    )
+    .unwrap();
+    let config = temp_dir.path().join("code-guardian.toml");
+    fs::write(
+        &config,
+        "[migrations]\npaths = [\"**/db/changes/*.sql\"]\nlarge_tables = [\"orders\"]\n",
+    )
+    .unwrap();
+    let db = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&project)
+        .arg("--profile")
+        .arg("migrations")
+        .arg("--db")
+        .arg(&db)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success();
+
+    let repo = SqliteScanRepository::new(&db).unwrap();
+    let scan = repo.get_scan(1).unwrap().unwrap();
+    let found: Vec<(usize, &str)> = scan
+        .matches
+        .iter()
+        .map(|m| (m.line_number, m.pattern.as_str()))
+        .collect();
+    assert_eq!(found, vec![(2, "MIGRATION_UPDATE_WITHOUT_WHERE")]);
+    assert_eq!(scan.matches[0].severity, Some(Severity::High));
+}
+
+#[test]
+fn test_stack_web_reports_accessibility_issues() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("index.html"),
+        "<img src=\"logo.png\">\n<div onclick=\"open()\" tabindex=\"3\">Menu</div>\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("stack")
+        .arg("web")
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("IMG_MISSING_ALT"))
+        .stdout(predicate::str::contains("NON_INTERACTIVE_CLICK"))
+        .stdout(predicate::str::contains("POSITIVE_TABINDEX"));
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("stack")
+        .arg("backend")
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("IMG_MISSING_ALT").not());
+}
+
+#[test]
+fn test_scan_hardcoded_strings_when_i18n_enabled() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    fs::create_dir(&project).unwrap();
+    fs::write(
+        project.join("Banner.jsx"),
+        "export const Banner = () => (\n  <div>\n    <h2>Limited offer</h2>\n    <p>{translate(\"offer.body\")}</p>\n  </div>\n);\n",
+    )
+    .unwrap();
+    let config = temp_dir.path().join("code-guardian.toml");
+    fs::write(
+        &config,
+        "[i18n]\nenabled = true\ntranslation_functions = [\"translate\"]\n",
+    )
+    .unwrap();
+    let db = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&project)
+        .arg("--db")
+        .arg(&db)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success();
+
+    let repo = SqliteScanRepository::new(&db).unwrap();
+    let scan = repo.get_scan(1).unwrap().unwrap();
+    let found: Vec<(usize, &str)> = scan
+        .matches
+        .iter()
+        .map(|m| (m.line_number, m.pattern.as_str()))
+        .collect();
+    assert_eq!(found, vec![(3, "HARDCODED_STRING")]);
+    assert_eq!(scan.matches[0].category.as_deref(), Some("Localization"));
+}
+
+#[test]
+fn test_scan_pii_only_when_enabled() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    fs::create_dir(&project).unwrap();
+    fs::write(
+        project.join("users.json"),
+        "{\"email\": \"jane.doe@gmail.com\", \"ssn\": \"123-45-6789\"}\n",
+    )
+    .unwrap();
+    let enabled = temp_dir.path().join("pii.toml");
+    fs::write(&enabled, "[pii]\nenabled = true\nkinds = [\"email\"]\n").unwrap();
+    let disabled = temp_dir.path().join("default.toml");
+    fs::write(&disabled, "").unwrap();
+    let db = temp_dir.path().join("test.db");
+
+    for config in [&disabled, &enabled] {
+        let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+        cmd.arg("scan")
+            .arg(&project)
+            .arg("--db")
+            .arg(&db)
+            .arg("--config")
+            .arg(config)
+            .assert()
+            .success();
+    }
+
+    let repo = SqliteScanRepository::new(&db).unwrap();
+    assert!(repo.get_scan(1).unwrap().unwrap().matches.is_empty());
+    let scan = repo.get_scan(2).unwrap().unwrap();
+    let found: Vec<&str> = scan.matches.iter().map(|m| m.pattern.as_str()).collect();
+    assert_eq!(found, vec!["PII_EMAIL"]);
+    assert!(!scan.matches[0].message.contains("jane.doe"));
+}
+
+#[test]
+fn test_config_test_and_production_path_globs() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    for file in ["src/lib.rs", "src/qa/lib.rs", "scripts/run.sh"] {
+        let path = project.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "// testing hook\n").unwrap();
+    }
+    let config = temp_dir.path().join("code-guardian.toml");
+    fs::write(
+        &config,
+        "test_path_globs = [\"**/qa/**\"]\nproduction_path_globs = [\"src/**\"]\n",
+    )
+    .unwrap();
+    let db = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("config")
+        .arg("validate")
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Configuration is valid"))
+        .stdout(predicate::str::contains("Unknown key").not());
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&project)
+        .arg("--profile")
+        .arg("comprehensive")
+        .arg("--db")
+        .arg(&db)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success();
+
+    // The globs are relative to the scanned directory
+    let repo = SqliteScanRepository::new(&db).unwrap();
+    let scan = repo.get_scan(1).unwrap().unwrap();
+    let found: Vec<&str> = scan
+        .matches
+        .iter()
+        .filter(|m| m.pattern.as_str() == "TEST")
+        .map(|m| m.file_path.as_str())
+        .collect();
+    assert_eq!(found.len(), 1, "{:?}", found);
+    assert!(found[0].ends_with("lib.rs") && !found[0].contains("qa"));
+}
+
+#[test]
+fn test_scan_blame_stores_authors() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_dir = temp_dir.path().join("repo");
+    fs::create_dir(&repo_dir).unwrap();
+    fs::write(repo_dir.join("main.rs"), "// TODO: blame me\n").unwrap();
+    for args in [
+        &["init", "-q"][..],
+        &["add", "main.rs"],
+        &[
+            "-c",
+            "user.name=Jane Doe",
+            "-c",
+            "user.email=jane@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "Add main",
+        ],
+    ] {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(&repo_dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+    let db = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&repo_dir)
+        .arg("--db")
+        .arg(&db)
+        .arg("--blame")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "blame me (Jane Doe <jane@example.com>)",
+        ));
+
+    let repo = SqliteScanRepository::new(&db).unwrap();
+    let scan = repo.get_scan(1).unwrap().unwrap();
+    let author = scan.matches[0].author.as_ref().unwrap();
+    assert_eq!(
+        (author.name.as_str(), author.email.as_str()),
+        ("Jane Doe", "jane@example.com")
+    );
+
+    // Reports of the stored scan carry the author too
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("report")
+        .arg("1")
+        .arg("--format")
+        .arg("csv")
+        .arg("--db")
+        .arg(&db)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(",Jane Doe,jane@example.com"));
+}
+
+#[test]
+fn test_no_emoji_prints_ascii() {
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("config")
+        .arg("validate")
+        .arg("--ascii")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[ok] Configuration is valid"))
+        .stdout(predicate::str::contains("✅").not());
+
+    let temp_dir = TempDir::new().unwrap();
+    let config = temp_dir.path().join("code-guardian.toml");
+    fs::write(&config, "[theme]\nemoji = false\ncolor = false\n").unwrap();
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("config")
+        .arg("validate")
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[ok] Configuration is valid"));
+}
+
+#[test]
+fn test_summary_line_is_printed_and_stored() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("main.rs"), "// TODO: one\n// FIXME: two\n").unwrap();
+    let db = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db)
+        .arg("--summary")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("files=").not())
+        .stderr(
+            predicate::str::is_match(
+                r"(?m)^files=1 skipped=0 matches=2 critical=0 duration_ms=\d+$",
+            )
+            .unwrap(),
+        );
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("history")
+        .arg("--db")
+        .arg(&db)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "files=1 skipped=0 matches=2 critical=0",
+        ));
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("ci-gate")
+        .arg(&src)
+        .arg("--summary")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("files=1 skipped=0"));
+}
+
+#[test]
+fn test_scan_named_targets() {
+    let temp_dir = TempDir::new().unwrap();
+    for (dir, content) in [
+        ("services/api", "// TODO: api\n"),
+        ("apps/web", "// FIXME: web\n"),
+    ] {
+        let dir = temp_dir.path().join(dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("main.rs"), content).unwrap();
+    }
+    let config = temp_dir.path().join("code-guardian.toml");
+    fs::write(
+        &config,
+        "[targets.api]\npath = \"services/api\"\nprofile = \"comprehensive\"\n\n[targets.web]\npath = \"apps/web\"\n",
+    )
+    .unwrap();
+    let db = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg("--config")
+        .arg(&config)
+        .arg("--target")
+        .arg("api")
+        .arg("--db")
+        .arg(&db)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Target api:"))
+        .stdout(predicate::str::contains("Target web:").not());
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg("--config")
+        .arg(&config)
+        .arg("--all-targets")
+        .arg("--parallel")
+        .arg("--db")
+        .arg(&db)
+        .assert()
+        .success();
+
+    let repo = SqliteScanRepository::new(&db).unwrap();
+    let scans = repo.get_all_scans().unwrap();
+    let roots: Vec<_> = scans.iter().map(|scan| scan.root_path.as_str()).collect();
+    assert_eq!(roots.len(), 3);
+    assert_eq!(roots.iter().filter(|root| root.ends_with("api")).count(), 2);
+    assert_eq!(roots.iter().filter(|root| root.ends_with("web")).count(), 1);
+
+    // Parallel targets share a database that doesn't exist yet
+    let fresh_db = temp_dir.path().join("fresh.db");
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg("--config")
+        .arg(&config)
+        .arg("--all-targets")
+        .arg("--parallel")
+        .arg("--db")
+        .arg(&fresh_db)
+        .assert()
+        .success();
+    let repo = SqliteScanRepository::new(&fresh_db).unwrap();
+    assert_eq!(repo.get_all_scans().unwrap().len(), 2);
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg("--config")
+        .arg(&config)
+        .arg("--target")
+        .arg("docs")
+        .arg("--db")
+        .arg(&db)
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("expected one of: api, web"));
+}
+
+#[test]
+fn test_scan_records_ci_run() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("main.rs"), "// TODO: ci\n").unwrap();
+    let db = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(temp_dir.path())
+        .arg("--db")
+        .arg(&db)
+        .env("GITHUB_ACTIONS", "true")
+        .env("GITHUB_SERVER_URL", "https://github.com")
+        .env("GITHUB_REPOSITORY", "acme/app")
+        .env("GITHUB_RUN_ID", "4242")
+        .env("GITHUB_EVENT_NAME", "schedule")
+        .assert()
+        .success();
+
+    let run_url = "https://github.com/acme/app/actions/runs/4242";
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("history")
+        .arg("--db")
+        .arg(&db)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "CI: GitHub Actions run 4242 ({})",
+            run_url
+        )));
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("report")
+        .arg("1")
+        .arg("--db")
+        .arg(&db)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(run_url));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_scan_uploads_reports() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("main.rs"), "// TODO: upload\n").unwrap();
+    // Stands in for the AWS CLI, recording how it was called
+    let bin = temp_dir.path().join("bin");
+    fs::create_dir(&bin).unwrap();
+    let log = temp_dir.path().join("aws.log");
+    fs::write(bin.join("aws"), "#!/bin/sh\necho \"$@\" >> \"$AWS_LOG\"\n").unwrap();
+    fs::set_permissions(bin.join("aws"), fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(temp_dir.path().join("test.db"))
+        .arg("--upload")
+        .arg("s3://ci-reports/main")
+        .env("PATH", &path)
+        .env("AWS_LOG", &log)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(
+            r"Uploaded report\.json: https://ci-reports\.s3\.amazonaws\.com/main/[0-9a-f]{16}-report\.json",
+        )
+        .unwrap())
+        .stdout(predicate::str::contains("Uploaded metrics.json"));
+    let calls = fs::read_to_string(&log).unwrap();
+    assert_eq!(calls.lines().count(), 2);
+    assert!(calls.starts_with("s3 cp --only-show-errors "));
+    assert!(calls.contains(" s3://ci-reports/main/"));
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(temp_dir.path().join("test.db"))
+        .arg("--upload")
+        .arg("ftp://ci-reports/main")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("unsupported scheme"));
+}
+
+#[test]
+fn test_scan_redacts_secrets() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Dockerfile"),
+        "FROM rust:1.80\nENV DB_PASSWORD=hunter2secret\n",
+    )
+    .unwrap();
+    let scan = |extra: &[&str]| {
+        let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+        cmd.arg("scan")
+            .arg(temp_dir.path())
+            .args(["--profile", "iac", "--db"])
+            .arg(temp_dir.path().join("test.db"))
+            .args(extra)
+            .env_remove("CI")
+            .env_remove("GITHUB_ACTIONS")
+            .env_remove("GITLAB_CI")
+            .env_remove("JENKINS_URL");
+        cmd
+    };
+
+    let output = scan(&[]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("DB_PASSWORD=hu*********et"), "{}", stdout);
+    assert!(!stdout.contains("hunter2secret"));
+
+    let output = scan(&["--no-redact"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("hunter2secret"));
+
+    scan(&["--no-redact"])
+        .env("CI", "true")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--no-redact is not allowed in CI"));
+}
+
+#[test]
+fn test_report_by_compliance() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Dockerfile"),
+        "FROM rust:latest\nENV DB_PASSWORD=hunter2secret\n",
+    )
+    .unwrap();
+    let db = temp_dir.path().join("test.db");
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(temp_dir.path())
+        .args(["--profile", "iac", "--db"])
+        .arg(&db)
+        .assert()
+        .success();
+
+    let report = |extra: &[&str]| {
+        let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+        cmd.args(["report", "1", "--db"]).arg(&db).args(extra);
+        cmd
+    };
+    report(&["--by-compliance"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("CWE-798"))
+        .stdout(predicate::str::contains("OWASP A07:2021"))
+        .stdout(predicate::str::contains("1 findings map to no control"));
+
+    let config = temp_dir.path().join("code-guardian.toml");
+    fs::write(&config, "[compliance.DOCKER_LATEST_TAG]\ncwe = [1104]\n").unwrap();
+    let output = report(&["--by-compliance", "--format", "json", "--config"])
+        .arg(&config)
+        .output()
+        .unwrap();
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(summary["unmapped"], 0);
+    assert!(summary["controls"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|control| control["control"] == "CWE-1104"));
+
+    let output = report(&["--format", "sarif"]).output().unwrap();
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+    assert_eq!(sarif["runs"][0]["results"].as_array().unwrap().len(), 2);
+
+    report(&["--by-compliance", "--format", "csv"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_report_tune() {
+    let temp_dir = TempDir::new().unwrap();
+    let tests = temp_dir.path().join("web/tests");
+    fs::create_dir_all(&tests).unwrap();
+    fs::create_dir_all(temp_dir.path().join("web/src")).unwrap();
+    for i in 0..3 {
+        fs::write(
+            tests.join(format!("t{}.js", i)),
+            "console.log(1);\n".repeat(10),
+        )
+        .unwrap();
+    }
+    fs::write(temp_dir.path().join("web/src/app.js"), "console.log(2);\n").unwrap();
+    let db = temp_dir.path().join("test.db");
+    let config = temp_dir.path().join("code-guardian.toml");
+    let scan = || {
+        let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+        cmd.arg("scan")
+            .arg(temp_dir.path())
+            .args(["--profile", "comprehensive", "--db"])
+            .arg(&db);
+        cmd
+    };
+    scan().assert().success();
+
+    let report = |id: &str, extra: &[&str]| {
+        let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+        cmd.args(["report", id, "--tune", "--db"])
+            .arg(&db)
+            .args(extra);
+        cmd
+    };
+    report("1", &[])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1. CONSOLE_LOG: 31"))
+        .stdout(predicate::str::contains("[rule_paths.CONSOLE_LOG]"))
+        .stdout(predicate::str::contains("\"web/tests/**\","))
+        .stdout(predicate::str::contains("CONSOLE_LOG = \"Medium\""));
+
+    let output = report("1", &["--format", "json", "--top", "1"])
+        .output()
+        .unwrap();
+    let tuning: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(tuning["rules"].as_array().unwrap().len(), 1);
+    assert_eq!(tuning["rules"][0]["directories"][0]["path"], "web/tests");
+    assert_eq!(tuning["suggestions"][0]["kind"], "exclude_path");
+
+    // Applying the suggested config quiets the rule
+    fs::write(&config, tuning["config"].as_str().unwrap()).unwrap();
+    scan().arg("--config").arg(&config).assert().success();
+    let output = Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args([
+            "report",
+            "2",
+            "--format",
+            "json",
+            "--pattern",
+            "CONSOLE_LOG",
+            "--db",
+        ])
+        .arg(&db)
+        .output()
+        .unwrap();
+    let matches: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let matches = matches.as_array().unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0]["severity"], "Medium");
+
+    report("1", &["--format", "csv"]).assert().code(2);
+    Command::cargo_bin("code_guardian_cli")
+        .unwrap()
+        .args(["report", "1", "--top", "3"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_report_group() {
+    let temp_dir = TempDir::new().unwrap();
+    let clones: String = (0..400)
+        .map(|i| format!("let a{} = b.clone();\n", i))
+        .collect();
+    fs::write(temp_dir.path().join("generated.rs"), clones).unwrap();
+    fs::write(
+        temp_dir.path().join("main.rs"),
+        "let x = y.clone();\n// TODO: one\n",
+    )
+    .unwrap();
+    let db = temp_dir.path().join("test.db");
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(temp_dir.path())
+        .args(["--profile", "comprehensive", "--db"])
+        .arg(&db)
+        .assert()
+        .success();
+
+    let report = |extra: &[&str]| {
+        let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+        cmd.args(["report", "1", "--group", "--db"])
+            .arg(&db)
+            .args(extra);
+        cmd
+    };
+    report(&["--pattern", "CLONE"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(400 occurrences, lines 1-400)"))
+        .stdout(predicate::str::contains("let a1 =").not())
+        .stderr(predicate::str::contains(
+            "Grouped 401 findings into 2 issues",
+        ));
+
+    let output = report(&["--format", "json"]).output().unwrap();
+    let issues: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let generated: Vec<&serde_json::Value> = issues
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|issue| {
+            issue["file_path"]
+                .as_str()
+                .unwrap()
+                .ends_with("generated.rs")
+        })
+        .collect();
+    assert_eq!(generated.len(), 1);
+    assert_eq!(generated[0]["pattern"], "CLONE");
+    assert_eq!(generated[0]["occurrences"], 400);
+    assert_eq!(generated[0]["last_line"], 400);
+
+    report(&["--limit", "10"]).assert().code(2);
+}
+
+#[test]
+fn test_gitlab_decorate_without_new_findings() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("main.rs"), "// TODO: ship it\n").unwrap();
+    let db = temp_dir.path().join("test.db");
+    for _ in 0..2 {
+        let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+        cmd.arg("scan")
+            .arg(temp_dir.path())
+            .arg("--db")
+            .arg(&db)
+            .assert()
+            .success();
+    }
+
+    let decorate = || {
+        let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+        cmd.args(["gitlab", "decorate", "--mr", "7", "--project", "group/app"])
+            .arg("--db")
+            .arg(&db)
+            .env_remove("GITLAB_TOKEN");
+        cmd
+    };
+    decorate()
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("GITLAB_TOKEN"));
+    // Scan 2 only repeats the findings of scan 1, so nothing is posted
+    decorate()
+        .env("GITLAB_TOKEN", "token")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No new findings in scan 2"));
+
+    // The token is read by curl from a file, not from its command line
+    fs::write(temp_dir.path().join("lib.rs"), "// FIXME: new\n").unwrap();
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(temp_dir.path())
+        .arg("--db")
+        .arg(&db)
+        .assert()
+        .success();
+    let (url, server) = one_shot_http_server(r#"{"id":"d1"}"#);
+    decorate()
+        .env("GITLAB_TOKEN", "glpat-s3cret")
+        .args(["--api-url", &url])
+        .assert()
+        .success();
+    let (headers, body) = server.join().unwrap();
+    assert!(
+        headers.contains("PRIVATE-TOKEN: glpat-s3cret"),
+        "{}",
+        headers
+    );
+    assert!(body.contains("FIXME"));
+}
+
+#[test]
+fn test_bitbucket_report() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.args(["bitbucket", "report", "--commit", "abc123"])
+        .args(["--workspace", "team", "--repo", "app", "--db"])
+        .arg(temp_dir.path().join("test.db"))
+        .env_remove("BITBUCKET_TOKEN")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("BITBUCKET_TOKEN"));
+
+    // A scan without findings is published without annotations, in one
+    // request whose token is read by curl from a file
+    let empty = temp_dir.path().join("empty");
+    fs::create_dir(&empty).unwrap();
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&empty)
+        .arg("--db")
+        .arg(temp_dir.path().join("test.db"))
+        .assert()
+        .success();
+    let (url, server) = one_shot_http_server("{}");
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.args(["bitbucket", "report", "--commit", "abc123"])
+        .args([
+            "--workspace",
+            "team",
+            "--repo",
+            "app",
+            "--api-url",
+            &url,
+            "--db",
+        ])
+        .arg(temp_dir.path().join("test.db"))
+        .env("BITBUCKET_TOKEN", "bb-s3cret")
+        .assert()
+        .success();
+    let (headers, body) = server.join().unwrap();
+    assert!(
+        headers.contains("Authorization: Bearer bb-s3cret"),
+        "{}",
+        headers
+    );
+    assert!(body.contains("\"result\""), "{}", body);
+}
+
+#[test]
+fn test_jira_create() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = temp_dir.path().join("code-guardian.toml");
+    fs::write(&config, "[jira]\nurl = \"https://example.atlassian.net\"\n").unwrap();
+    let jira = |extra: &[&str]| {
+        let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+        cmd.args(["jira", "create", "--config"])
+            .arg(&config)
+            .arg("--db")
+            .arg(temp_dir.path().join("test.db"))
+            .args(extra)
+            .env_remove("JIRA_API_TOKEN");
+        cmd
+    };
+    jira(&[])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("jira.project"));
+    jira(&["--project", "SEC"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("JIRA_API_TOKEN"));
+    jira(&["--severity", "severe"]).assert().code(2);
+
+    // The credentials are read by curl from a file, not from its command line
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("main.rs"), "// TODO: track this\n").unwrap();
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(temp_dir.path().join("test.db"))
+        .assert()
+        .success();
+    let (url, server) = one_shot_http_server(r#"{"key":"SEC-1"}"#);
+    jira(&["--project", "SEC", "--url", &url, "--severity", "low"])
+        .env("JIRA_API_TOKEN", "jira-s3cret")
+        .env_remove("JIRA_EMAIL")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created SEC-1"));
+    let (headers, body) = server.join().unwrap();
+    assert!(
+        headers.contains("Authorization: Bearer jira-s3cret"),
+        "{}",
+        headers
+    );
+    assert!(body.contains("\"SEC\""), "{}", body);
+}
+
+#[test]
+fn test_serve_atom_feed() {
+    use std::io::{Read, Write};
+
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let mut repo = SqliteScanRepository::new(&db_path).unwrap();
+    let finding = |pattern: &str, line_number: usize| Match {
+        file_path: "app.js".into(),
+        line_number,
+        column: 1,
+        pattern: pattern.into(),
+        message: format!("{}: found", pattern),
+        severity: None,
+        category: None,
+        end: None,
+        author: None,
+    };
+    let mut scan = Scan {
+        id: None,
+        timestamp: 1_700_000_000,
+        root_path: "/app".to_string(),
+        project: None,
+        matches: vec![finding("TODO", 1)],
+    };
+    repo.save_scan(&scan).unwrap();
+    scan.timestamp += 60;
+    scan.matches.push(finding("UNSAFE_EVAL", 2));
+    repo.save_scan(&scan).unwrap();
+    drop(repo);
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let mut server = std::process::Command::new(assert_cmd::cargo::cargo_bin("code_guardian_cli"))
+        .args(["serve", "--port", &port.to_string(), "--db"])
+        .arg(&db_path)
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut stream = (0..100)
+        .find_map(|_| {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            std::net::TcpStream::connect(("127.0.0.1", port)).ok()
+        })
+        .expect("serve did not start listening");
+    stream
+        .write_all(b"GET /feed.atom HTTP/1.1\r\nHost: feeds.test\r\nConnection: close\r\n\r\n")
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    server.kill().unwrap();
+    server.wait().unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("application/atom+xml"));
+    assert!(response.contains("<link rel=\"self\" href=\"http://feeds.test/feed.atom\"/>"));
+    assert!(response.contains("<title>Scan 2 of /app: 2 findings, 1 new Critical</title>"));
+    assert!(response.contains("<title>New Critical UNSAFE_EVAL in app.js:2</title>"));
+    assert!(response.contains("<title>Scan 1 of /app: 1 finding, 0 new Critical</title>"));
+}
+
+#[test]
+fn test_scan_fast_skips_detectors_without_yield() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("main.rs"), "// TODO: one\n").unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let mut repo = SqliteScanRepository::new(&db_path).unwrap();
+    repo.save_detector_yields(&[code_guardian_core::DetectorYield {
+        detector: "TodoDetector".to_string(),
+        extension: "rs".to_string(),
+        files: code_guardian_core::MIN_FILES_FOR_SKIP,
+        matches: 0,
+        skipped_scans: 0,
+    }])
+    .unwrap();
+    drop(repo);
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--fast")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("skipping 1 detector"))
+        .stdout(predicate::str::contains("TODO: one").not());
+
+    let repo = SqliteScanRepository::new(&db_path).unwrap();
+    let yields = repo.get_detector_yields().unwrap();
+    let todo = yields
+        .iter()
+        .find(|y| y.detector == "TodoDetector" && y.extension == "rs")
+        .unwrap();
+    assert_eq!((todo.files, todo.skipped_scans), (100, 1));
+    assert!(yields
+        .iter()
+        .any(|y| y.detector == "FixmeDetector" && y.files == 1));
+
+    // The notice stays off stdout, which only lists files for `xargs -0`
+    fs::write(src.join("lib.rs"), "// FIXME: two\n").unwrap();
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--fast")
+        .arg("--list-files")
+        .arg("-0")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("skipping 1 detector"))
+        .stdout(format!("{}\0", src.join("lib.rs").display()));
+}
+
+#[test]
+fn test_scan_budget_reports_partial_coverage() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    for i in 0..2000 {
+        fs::write(src.join(format!("file{}.rs", i)), "// TODO: one\n").unwrap();
+    }
+    let db_path = temp_dir.path().join("test.db");
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--budget")
+        .arg("1ms")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Budget of 1ms used up"))
+        .stderr(predicate::str::contains("/2000 files covered"));
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--db")
+        .arg(&db_path)
+        .arg("--budget")
+        .arg("10m")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("files covered").not());
+
+    let mut cmd = Command::cargo_bin("code_guardian_cli").unwrap();
+    cmd.arg("scan")
+        .arg(&src)
+        .arg("--budget")
+        .arg("1h")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Supported: ms, s, m"));
 }
@@ -14,14 +14,14 @@ mod cli_coverage_boost {
     #[test]
     fn test_handle_history_comprehensive() {
         // Test with None (default path) - may fail if no database, which is expected
-        let result = command_handlers::handle_history(None);
+        let result = command_handlers::handle_history(None, None);
         // Don't assert success since database may not exist in test environment
         println!("History with default path result: {:?}", result.is_ok());
 
         // Test with custom path - also may fail, but we're testing the function call
         let temp_dir = TempDir::new().unwrap();
         let db_path = temp_dir.path().join("test.db");
-        let result = command_handlers::handle_history(Some(db_path));
+        let result = command_handlers::handle_history(Some(db_path), None);
         println!("History with custom path result: {:?}", result.is_ok());
         // Test passes if function doesn't panic
     }
@@ -33,7 +33,7 @@ mod cli_coverage_boost {
         let shells = [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell];
 
         for shell in shells {
-            let result = command_handlers::handle_completion(shell);
+            let result = command_handlers::handle_completion(Some(shell), false, None);
             assert!(result.is_ok());
         }
     }
@@ -104,7 +104,14 @@ mod cli_coverage_boost {
 
         // Test report generation - may fail if database doesn't exist, but tests coverage
         test_coverage!(
-            report_handlers::handle_report(999, "json".to_string(), Some(db_path)),
+            report_handlers::handle_report(
+                999,
+                "json".to_string(),
+                Some(db_path),
+                Default::default(),
+                false,
+                None
+            ),
             "Report generation"
         );
     }
@@ -282,12 +289,11 @@ mod cli_coverage_boost {
 
         // Test handle_production_check with correct signature
         let result = production_handlers::handle_production_check(
-            temp_dir.path().to_path_buf(),
-            "json".to_string(), // format
-            false,              // fail_on_critical
-            false,              // fail_on_high
-            vec![],             // severity_filter
-            None,               // output
+            production_handlers::ProductionCheckOptions {
+                path: temp_dir.path().to_path_buf(),
+                format: "json".to_string(),
+                ..Default::default()
+            },
         );
         assert!(result.is_ok());
 
@@ -296,6 +302,8 @@ mod cli_coverage_boost {
             temp_dir.path().to_path_buf(),
             false, // staged_only
             true,  // fast
+            None,
+            None,
         );
         assert!(result.is_ok());
 
@@ -304,6 +312,8 @@ mod cli_coverage_boost {
             temp_dir.path().to_path_buf(),
             true,  // staged_only
             false, // fast
+            None,
+            None,
         );
         assert!(result.is_ok());
     }
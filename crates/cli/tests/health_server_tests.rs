@@ -1,5 +1,11 @@
 use anyhow::Result;
-use code_guardian_core::health_server::{start_health_server, HealthState};
+use code_guardian_core::feed::{FeedScan, ScanFeed};
+use code_guardian_core::health_server::{
+    feed_handler, health_handler, metrics_handler, scan_handler, start_health_server,
+    DatabaseIntegrity, HealthState, ScanRequest, ScanTrigger, TokenVerifier,
+};
+use code_guardian_core::{ScanResult, TokenScope};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::timeout;
 
@@ -52,6 +58,164 @@ mod health_server_tests {
         Ok(())
     }
 
+    struct CorruptDatabase;
+
+    impl DatabaseIntegrity for CorruptDatabase {
+        fn integrity_problems(&self) -> ScanResult<Vec<String>> {
+            Ok(vec!["row 3 missing from index idx_matches_scan".to_string()])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_health_reports_database_corruption() {
+        let state = HealthState {
+            database: Some(Arc::new(CorruptDatabase)),
+            ..HealthState::default()
+        };
+        let response = health_handler(axum::extract::State(Arc::new(state))).await;
+        assert_eq!(
+            response.err(),
+            Some(axum::http::StatusCode::SERVICE_UNAVAILABLE)
+        );
+    }
+
+    struct Tokens;
+
+    impl TokenVerifier for Tokens {
+        fn has_tokens(&self) -> ScanResult<bool> {
+            Ok(true)
+        }
+
+        fn verify(&self, token: &str) -> ScanResult<Option<TokenScope>> {
+            Ok(match token {
+                "cgt_read" => Some(TokenScope::Read),
+                "cgt_scan" => Some(TokenScope::Scan),
+                _ => None,
+            })
+        }
+    }
+
+    struct Scans;
+
+    impl ScanTrigger for Scans {
+        fn trigger_scan(&self, request: &ScanRequest) -> Result<serde_json::Value, String> {
+            Ok(serde_json::json!({ "scan_id": 7, "project": request.project }))
+        }
+    }
+
+    fn bearer(token: &str) -> axum::http::HeaderMap {
+        let mut headers = axum::http::HeaderMap::new();
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        headers
+    }
+
+    #[tokio::test]
+    async fn test_tokens_guard_metrics_and_scans() {
+        use axum::extract::State;
+        use axum::http::StatusCode;
+
+        let open = Arc::new(HealthState {
+            scans: Some(Arc::new(Scans)),
+            ..HealthState::default()
+        });
+        // Without tokens reading is open, but scans can't be triggered
+        assert!(metrics_handler(State(open.clone()), Default::default())
+            .await
+            .is_ok());
+        let refused = scan_handler(State(open), Default::default(), Default::default()).await;
+        assert_eq!(refused.err().unwrap().status(), StatusCode::UNAUTHORIZED);
+
+        let state = Arc::new(HealthState {
+            tokens: Some(Arc::new(Tokens)),
+            scans: Some(Arc::new(Scans)),
+            ..HealthState::default()
+        });
+        let status = |result: Result<axum::response::Response, axum::response::Response>| {
+            result.unwrap_or_else(|response| response).status()
+        };
+        let metrics = metrics_handler(State(state.clone()), Default::default()).await;
+        assert_eq!(metrics.err().unwrap().status(), StatusCode::UNAUTHORIZED);
+        assert!(metrics_handler(State(state.clone()), bearer("cgt_read"))
+            .await
+            .is_ok());
+        assert!(metrics_handler(State(state.clone()), bearer("cgt_guess"))
+            .await
+            .is_err());
+
+        let body = axum::body::Bytes::from(r#"{"project": "api"}"#);
+        assert_eq!(
+            status(scan_handler(State(state.clone()), bearer("cgt_read"), body.clone()).await),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(scan_handler(State(state.clone()), bearer("cgt_scan"), body).await),
+            StatusCode::CREATED
+        );
+        assert_eq!(
+            status(
+                scan_handler(
+                    State(state),
+                    bearer("cgt_scan"),
+                    axum::body::Bytes::from("not json")
+                )
+                .await
+            ),
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    struct NoScans;
+
+    impl ScanFeed for NoScans {
+        fn recent_scans(&self) -> ScanResult<Vec<FeedScan>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_feed_self_link_follows_tls() {
+        use axum::extract::State;
+
+        let feed = |tls: Option<Arc<rustls::ServerConfig>>| async move {
+            let state = Arc::new(HealthState {
+                feed: Some(Arc::new(NoScans)),
+                tls,
+                ..HealthState::default()
+            });
+            let mut headers = axum::http::HeaderMap::new();
+            headers.insert(axum::http::header::HOST, "feeds.test".parse().unwrap());
+            let response = feed_handler(State(state), headers).await.unwrap();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            String::from_utf8(body.to_vec()).unwrap()
+        };
+        // The handler only reads whether TLS is configured, not the certificate
+        let tls = rustls::ServerConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_no_client_auth()
+        .with_cert_resolver(Arc::new(rustls::server::ResolvesServerCertUsingSni::new()));
+
+        let plain = feed(None).await;
+        assert!(
+            plain.contains("<link rel=\"self\" href=\"http://feeds.test/feed.atom\"/>"),
+            "{}",
+            plain
+        );
+        let secure = feed(Some(Arc::new(tls))).await;
+        assert!(
+            secure.contains("<link rel=\"self\" href=\"https://feeds.test/feed.atom\"/>"),
+            "{}",
+            secure
+        );
+    }
+
     #[test]
     fn test_health_state_clone() {
         let state1 = HealthState::default();
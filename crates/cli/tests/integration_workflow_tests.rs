@@ -54,6 +54,7 @@ fn dangerous_operation() -> Result<String, &'static str> {
             batch_size: None,
             max_file_size: None,
             max_threads: None,
+            ..Default::default()
         };
 
         let scan_result = handle_scan(scan_options).await;
@@ -64,6 +65,9 @@ fn dangerous_operation() -> Result<String, &'static str> {
             1, // Use scan ID 1
             "json".to_string(),
             Some(db_path),
+            Default::default(),
+            false,
+            None,
         );
         // Note: This might fail due to database implementation, but we're testing the workflow
         let _report_result = report_result; // Allow either success or failure for now
@@ -107,6 +111,7 @@ fn initial_code() {
             batch_size: None,
             max_file_size: None,
             max_threads: None,
+            ..Default::default()
         };
 
         let first_scan = handle_scan(scan_options_1).await;
@@ -148,6 +153,7 @@ fn new_function() {
             batch_size: None,
             max_file_size: None,
             max_threads: None,
+            ..Default::default()
         };
 
         let second_scan = handle_scan(scan_options_2).await;
@@ -217,6 +223,7 @@ fn unsafe_operation() -> Result<String, &'static str> {
                 batch_size: None,
                 max_file_size: None,
                 max_threads: None,
+                ..Default::default()
             };
 
             let scan_result = handle_scan(scan_options).await;
@@ -291,6 +298,7 @@ fn main() {
             batch_size: None,
             max_file_size: None,
             max_threads: None,
+            ..Default::default()
         };
 
         let scan_result = handle_scan(scan_options).await;
@@ -324,6 +332,7 @@ fn main() {
             batch_size: None,
             max_file_size: None,
             max_threads: None,
+            ..Default::default()
         };
 
         let invalid_scan_result = handle_scan(invalid_scan_options).await;
@@ -352,6 +361,7 @@ fn main() {
             batch_size: None,
             max_file_size: None,
             max_threads: None,
+            ..Default::default()
         };
 
         let invalid_config_result = handle_scan(invalid_config_options).await;
@@ -457,6 +467,7 @@ impl Struct{} {{
             batch_size: Some(50),
             max_file_size: Some(1048576), // 1MB limit
             max_threads: Some(4),
+            ..Default::default()
         };
 
         let scan_result = handle_scan(scan_options).await;
@@ -504,6 +515,7 @@ fn concurrent_function() {
                     batch_size: None,
                     max_file_size: None,
                     max_threads: Some(2), // Limit threads for concurrent test
+                    ..Default::default()
                 };
 
                 handle_scan(scan_options).await
@@ -561,6 +573,7 @@ fn metrics_test() {
             batch_size: Some(100),
             max_file_size: Some(1048576),
             max_threads: Some(4),
+            ..Default::default()
         };
 
         let scan_result = handle_scan(scan_options).await;
@@ -10,8 +10,11 @@ mod production_handler_tests {
     #[test]
     fn test_handle_production_check_invalid_path() {
         let invalid_path = PathBuf::from("nonexistent/path");
-        let result =
-            handle_production_check(invalid_path, "json".to_string(), false, false, vec![], None);
+        let result = handle_production_check(ProductionCheckOptions {
+            path: invalid_path,
+            format: "json".to_string(),
+            ..Default::default()
+        });
         // Function handles invalid paths gracefully (returns empty results)
         assert!(result.is_ok());
     }
@@ -21,7 +24,11 @@ mod production_handler_tests {
         let temp_dir = TempDir::new()?;
         let path = temp_dir.path().to_path_buf();
 
-        let result = handle_production_check(path, "json".to_string(), false, false, vec![], None);
+        let result = handle_production_check(ProductionCheckOptions {
+            path,
+            format: "json".to_string(),
+            ..Default::default()
+        });
         // Should succeed even with empty directory
         assert!(result.is_ok());
         Ok(())
@@ -33,23 +40,27 @@ mod production_handler_tests {
         let path = temp_dir.path().to_path_buf();
 
         // Test JSON format
-        let result =
-            handle_production_check(path.clone(), "json".to_string(), false, false, vec![], None);
+        let result = handle_production_check(ProductionCheckOptions {
+            path: path.clone(),
+            format: "json".to_string(),
+            ..Default::default()
+        });
         assert!(result.is_ok());
 
         // Test summary format
-        let result = handle_production_check(
-            path.clone(),
-            "summary".to_string(),
-            false,
-            false,
-            vec![],
-            None,
-        );
+        let result = handle_production_check(ProductionCheckOptions {
+            path: path.clone(),
+            format: "summary".to_string(),
+            ..Default::default()
+        });
         assert!(result.is_ok());
 
         // Test text format
-        let result = handle_production_check(path, "text".to_string(), false, false, vec![], None);
+        let result = handle_production_check(ProductionCheckOptions {
+            path,
+            format: "text".to_string(),
+            ..Default::default()
+        });
         assert!(result.is_ok());
 
         Ok(())
@@ -58,7 +69,7 @@ mod production_handler_tests {
     #[test]
     fn test_handle_ci_gate_invalid_path() {
         let invalid_path = PathBuf::from("nonexistent/path");
-        let result = handle_ci_gate(invalid_path, None, None, 0, 0);
+        let result = handle_ci_gate(invalid_path, None, None, 0, 0, None, None);
         // Function handles invalid paths gracefully (returns empty results)
         assert!(result.is_ok());
     }
@@ -68,7 +79,7 @@ mod production_handler_tests {
         let temp_dir = TempDir::new()?;
         let path = temp_dir.path().to_path_buf();
 
-        let result = handle_ci_gate(path, None, None, 10, 20);
+        let result = handle_ci_gate(path, None, None, 10, 20, None, None);
         // Should succeed with empty directory
         assert!(result.is_ok());
         Ok(())
@@ -80,7 +91,7 @@ mod production_handler_tests {
         let path = temp_dir.path().to_path_buf();
         let output_file = temp_dir.path().join("ci_report.json");
 
-        let result = handle_ci_gate(path, None, Some(output_file.clone()), 5, 10);
+        let result = handle_ci_gate(path, None, Some(output_file.clone()), 5, 10, None, None);
         assert!(result.is_ok());
 
         // Check that output file was created
@@ -92,7 +103,7 @@ mod production_handler_tests {
     #[test]
     fn test_handle_pre_commit_invalid_path() {
         let invalid_path = PathBuf::from("nonexistent/path");
-        let result = handle_pre_commit(invalid_path, false, false);
+        let result = handle_pre_commit(invalid_path, false, false, None, None);
         // Function handles invalid paths gracefully (returns empty results)
         assert!(result.is_ok());
     }
@@ -102,7 +113,7 @@ mod production_handler_tests {
         let temp_dir = TempDir::new()?;
         let path = temp_dir.path().to_path_buf();
 
-        let result = handle_pre_commit(path, false, false);
+        let result = handle_pre_commit(path, false, false, None, None);
         // Should succeed with empty directory
         assert!(result.is_ok());
         Ok(())
@@ -113,7 +124,7 @@ mod production_handler_tests {
         let temp_dir = TempDir::new()?;
         let path = temp_dir.path().to_path_buf();
 
-        let result = handle_pre_commit(path, false, true);
+        let result = handle_pre_commit(path, false, true, None, None);
         // Should succeed in fast mode
         assert!(result.is_ok());
         Ok(())
@@ -195,14 +206,12 @@ mod production_handler_tests {
         let path = temp_dir.path().to_path_buf();
 
         // Test with specific severity filter
-        let result = handle_production_check(
+        let result = handle_production_check(ProductionCheckOptions {
             path,
-            "json".to_string(),
-            false,
-            false,
-            vec!["Critical".to_string(), "High".to_string()],
-            None,
-        );
+            format: "json".to_string(),
+            severity_filter: vec!["Critical".to_string(), "High".to_string()],
+            ..Default::default()
+        });
         assert!(result.is_ok());
         Ok(())
     }
@@ -213,14 +222,12 @@ mod production_handler_tests {
         let path = temp_dir.path().to_path_buf();
         let output_file = temp_dir.path().join("production_report.json");
 
-        let result = handle_production_check(
+        let result = handle_production_check(ProductionCheckOptions {
             path,
-            "json".to_string(),
-            false,
-            false,
-            vec![],
-            Some(output_file.clone()),
-        );
+            format: "json".to_string(),
+            output: Some(output_file.clone()),
+            ..Default::default()
+        });
         assert!(result.is_ok());
 
         // Check that output file was created
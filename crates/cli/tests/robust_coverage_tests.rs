@@ -25,7 +25,7 @@ mod robust_coverage_tests {
 
         // Test all shell completions
         for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
-            let result = command_handlers::handle_completion(shell);
+            let result = command_handlers::handle_completion(Some(shell), false, None);
             assert!(result.is_ok(), "Completion should work for {:?}", shell);
         }
 
@@ -48,13 +48,13 @@ mod robust_coverage_tests {
 
         // Test history handlers
         test_function_coverage!(
-            command_handlers::handle_history(None),
+            command_handlers::handle_history(None, None),
             "history with default path"
         );
 
         let db_path = workspace.path().join("test.db");
         test_function_coverage!(
-            command_handlers::handle_history(Some(db_path)),
+            command_handlers::handle_history(Some(db_path), None),
             "history with custom path"
         );
     }
@@ -66,24 +66,26 @@ mod robust_coverage_tests {
         // Test production check with different parameters
         test_function_coverage!(
             production_handlers::handle_production_check(
-                workspace.path().to_path_buf(),
-                "json".to_string(),
-                false,  // fail_on_critical
-                false,  // fail_on_high
-                vec![], // severity_filter
-                None,   // output
+                production_handlers::ProductionCheckOptions {
+                    path: workspace.path().to_path_buf(),
+                    format: "json".to_string(),
+                    ..Default::default()
+                }
             ),
             "production check - json format"
         );
 
         test_function_coverage!(
             production_handlers::handle_production_check(
-                workspace.path().to_path_buf(),
-                "text".to_string(),
-                true, // fail_on_critical
-                true, // fail_on_high
-                vec!["Critical".to_string(), "High".to_string()],
-                Some(workspace.path().join("output.txt")),
+                production_handlers::ProductionCheckOptions {
+                    path: workspace.path().to_path_buf(),
+                    format: "text".to_string(),
+                    fail_on_critical: true,
+                    fail_on_high: true,
+                    severity_filter: vec!["Critical".to_string(), "High".to_string()],
+                    output: Some(workspace.path().join("output.txt")),
+                    ..Default::default()
+                }
             ),
             "production check - text format with filters"
         );
@@ -94,6 +96,8 @@ mod robust_coverage_tests {
                 workspace.path().to_path_buf(),
                 false, // staged_only
                 true,  // fast
+                None,
+                None,
             ),
             "pre-commit fast mode"
         );
@@ -103,6 +107,8 @@ mod robust_coverage_tests {
                 workspace.path().to_path_buf(),
                 true,  // staged_only
                 false, // fast
+                None,
+                None,
             ),
             "pre-commit staged only"
         );
@@ -115,6 +121,8 @@ mod robust_coverage_tests {
                 None, // output
                 5,    // max_critical
                 10,   // max_high
+                None, // custom_detectors
+                None, // upload
             ),
             "CI gate"
         );
@@ -183,7 +191,14 @@ mod robust_coverage_tests {
         // Test report generation
         let db_path = workspace.path().join("test.db");
         test_function_coverage!(
-            report_handlers::handle_report(1, "json".to_string(), Some(db_path)),
+            report_handlers::handle_report(
+                1,
+                "json".to_string(),
+                Some(db_path),
+                Default::default(),
+                false,
+                None
+            ),
             "report generation"
         );
     }
@@ -323,12 +338,11 @@ mod robust_coverage_tests {
 
         test_function_coverage!(
             production_handlers::handle_production_check(
-                non_existent,
-                "json".to_string(),
-                false,
-                false,
-                vec![],
-                None
+                production_handlers::ProductionCheckOptions {
+                    path: non_existent,
+                    format: "json".to_string(),
+                    ..Default::default()
+                }
             ),
             "production check with non-existent path"
         );
@@ -366,7 +380,13 @@ mod robust_coverage_tests {
             advanced_handlers::handle_distributed(DistributedAction::Scan {
                 path: workspace.path().to_path_buf(),
                 workers: 2,
+                worker_endpoints: Vec::new(),
+                discover_srv: None,
                 batch_size: 10,
+                webhooks: Vec::new(),
+                tls_cert: None,
+                tls_key: None,
+                tls_ca: None,
             })
             .await
         });
@@ -401,7 +421,13 @@ mod robust_coverage_tests {
         );
 
         test_function_coverage!(
-            production_handlers::handle_pre_commit(workspace.path().to_path_buf(), false, true),
+            production_handlers::handle_pre_commit(
+                workspace.path().to_path_buf(),
+                false,
+                true,
+                None,
+                None
+            ),
             "sequential pre-commit"
         );
 
@@ -112,7 +112,7 @@ fn test_handle_history_invalid_db() {
     use code_guardian_cli::command_handlers::handle_history;
     // Use a path that SQLite cannot create (e.g., a directory that doesn't exist)
     let invalid_db = PathBuf::from("/nonexistent_directory/db.db");
-    let result = handle_history(Some(invalid_db));
+    let result = handle_history(Some(invalid_db), None);
     assert!(result.is_err());
 }
 
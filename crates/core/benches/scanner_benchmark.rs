@@ -1,8 +1,35 @@
 use code_guardian_core::{DetectorFactory, DetectorProfile, PatternDetector, Scanner};
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::alloc::{GlobalAlloc, Layout, System};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tempfile::TempDir;
 
+/// Counts heap allocations so benchmarks can report them alongside timings
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let result = f();
+    (result, ALLOCATIONS.load(Ordering::Relaxed) - before)
+}
+
 fn create_test_files(dir: &TempDir, num_files: usize, lines_per_file: usize) -> Vec<PathBuf> {
     let mut files = Vec::new();
 
@@ -147,6 +174,72 @@ fn bench_regex_performance(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_prefilter(c: &mut Criterion) {
+    use code_guardian_core::PatternPrefilter;
+    use std::path::Path;
+
+    // Mostly clean code, where the prefilter rules out most detectors
+    let content = format!(
+        "{}// TODO: one finding\n",
+        "fn regular_code_line() { let x = 42; }\n".repeat(1000)
+    );
+    let path = Path::new("test.rs");
+    let detectors = DetectorFactory::create_comprehensive_detectors();
+    let prefilter = PatternPrefilter::new(&detectors);
+
+    let mut group = c.benchmark_group("prefilter");
+    group.bench_function("every_detector", |b| {
+        b.iter(|| {
+            let matches: Vec<_> = detectors
+                .iter()
+                .flat_map(|d| d.detect(black_box(&content), black_box(path)))
+                .collect();
+            black_box(matches);
+        });
+    });
+    group.bench_function("prefiltered", |b| {
+        b.iter(|| {
+            let candidates = prefilter.candidates(black_box(&content));
+            let matches: Vec<_> = detectors
+                .iter()
+                .zip(candidates)
+                .filter(|(_, candidate)| *candidate)
+                .flat_map(|(d, _)| d.detect(black_box(&content), black_box(path)))
+                .collect();
+            black_box(matches);
+        });
+    });
+    group.finish();
+}
+
+fn bench_match_allocations(c: &mut Criterion) {
+    use code_guardian_core::TodoDetector;
+    use std::path::Path;
+
+    // Every line matches, so per-match allocations dominate
+    let content = "// TODO: fix this\n".repeat(1000);
+    let path = Path::new("src/some/deeply/nested/module/file.rs");
+    let detector = TodoDetector;
+
+    let (matches, allocations) = count_allocations(|| detector.detect(&content, path));
+    let (_, clone_allocations) = count_allocations(|| matches.clone());
+    println!(
+        "match_allocations: {} matches, {} allocations to detect, {} to clone",
+        matches.len(),
+        allocations,
+        clone_allocations
+    );
+
+    let mut group = c.benchmark_group("match_allocations");
+    group.bench_function("detect", |b| {
+        b.iter(|| black_box(detector.detect(black_box(&content), black_box(path))));
+    });
+    group.bench_function("clone", |b| {
+        b.iter(|| black_box(black_box(&matches).clone()));
+    });
+    group.finish();
+}
+
 fn bench_custom_detectors(c: &mut Criterion) {
     use code_guardian_core::custom_detectors::*;
     use std::path::Path;
@@ -170,6 +263,8 @@ fn bench_custom_detectors(c: &mut Criterion) {
         category: DetectorCategory::Testing,
         examples: vec![],
         enabled: true,
+        conditions: Vec::new(),
+        deprecation: None,
     };
     let simple_detector = CustomDetector::new(simple_config).unwrap();
 
@@ -186,6 +281,8 @@ fn bench_custom_detectors(c: &mut Criterion) {
         category: DetectorCategory::CodeQuality,
         examples: vec![],
         enabled: true,
+        conditions: Vec::new(),
+        deprecation: None,
     };
     let complex_detector = CustomDetector::new(complex_config).unwrap();
 
@@ -202,6 +299,8 @@ fn bench_custom_detectors(c: &mut Criterion) {
         category: DetectorCategory::Security,
         examples: vec![],
         enabled: true,
+        conditions: Vec::new(),
+        deprecation: None,
     };
     let capture_detector = CustomDetector::new(capture_config).unwrap();
 
@@ -256,6 +355,8 @@ fn bench_custom_detectors_large_files(c: &mut Criterion) {
             category: DetectorCategory::Security,
             examples: vec![],
             enabled: true,
+            conditions: Vec::new(),
+            deprecation: None,
         };
         let detector = CustomDetector::new(config).unwrap();
 
@@ -298,6 +399,8 @@ criterion_group!(
     bench_scanner_profiles,
     bench_large_files,
     bench_regex_performance,
+    bench_prefilter,
+    bench_match_allocations,
     bench_custom_detectors,
     bench_custom_detectors_large_files,
     bench_memory_usage
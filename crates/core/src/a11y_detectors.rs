@@ -0,0 +1,432 @@
+//! Accessibility smells in JSX, HTML, Vue and Svelte markup: images without
+//! alternative text, click handlers on elements that cannot take focus, form
+//! controls without a label and a `tabindex` that reorders keyboard focus
+
+use crate::comment_spans::CommentIndex;
+use crate::detectors::has_extension;
+use crate::line_index::LineIndex;
+use crate::{Match, PatternDetector, Severity, SharedStr};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Category reported by the accessibility detectors
+pub const ACCESSIBILITY_CATEGORY: &str = "Accessibility";
+
+/// Files holding markup. Plain `.js` is included since React code often
+/// keeps JSX there.
+const MARKUP_EXTENSIONS: &[&str] = &["js", "jsx", "tsx", "vue", "svelte", "html", "htm"];
+
+/// Native elements that take no focus or keyboard input of their own
+const NON_INTERACTIVE_ELEMENTS: &[&str] = &[
+    "div", "span", "p", "li", "ul", "ol", "td", "th", "tr", "table", "img", "section", "article",
+    "header", "footer", "main", "nav", "aside", "h1", "h2", "h3", "h4", "h5", "h6",
+];
+
+/// Click handlers of plain HTML, React, Vue, Svelte and Angular
+const CLICK_ATTRIBUTES: &[&str] = &["onclick", "@click", "v-on:click", "on:click", "(click)"];
+
+/// `<input>` types that are labeled by their own text or never shown
+const SELF_LABELED_INPUT_TYPES: &[&str] = &["hidden", "submit", "button", "reset", "image"];
+
+lazy_static! {
+    /// Opening, closing and self-closing tags, with arrow functions allowed
+    /// in attributes
+    static ref TAG_REGEX: Regex = Regex::new(
+        r"<(?P<close>/)?(?P<tag>[A-Za-z][\w.:-]*)(?P<attrs>(?:=>|[^<>])*)>"
+    ).unwrap();
+
+    /// One attribute, bare or with a quoted, `{expression}` or unquoted value
+    static ref ATTRIBUTE_REGEX: Regex = Regex::new(
+        r#"(?P<name>[@:(]?[A-Za-z][\w:.-]*\)?)(?:\s*=\s*(?P<value>"[^"]*"|'[^']*'|\{[^}]*\}|[^\s"'>/]+))?"#
+    ).unwrap();
+}
+
+/// An opening tag and its attributes, with names lowercased and Vue's
+/// `:` and `v-bind:` binding prefixes dropped
+struct Element<'a> {
+    start: usize,
+    tag: &'a str,
+    attributes: Vec<(String, &'a str)>,
+    /// Has a `{...props}` spread, which may supply any attribute
+    spread: bool,
+    self_closing: bool,
+}
+
+impl<'a> Element<'a> {
+    fn has(&self, names: &[&str]) -> bool {
+        self.attributes
+            .iter()
+            .any(|(name, _)| names.contains(&name.as_str()))
+    }
+
+    /// Value of an attribute without its quotes or braces; empty for a bare
+    /// attribute
+    fn value(&self, name: &str) -> Option<&'a str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| {
+                value
+                    .trim_matches(|c| matches!(c, '"' | '\'' | '{' | '}'))
+                    .trim()
+            })
+    }
+}
+
+/// Tags of `content` in order, opening ones as [`Element`]s and closing ones
+/// as their name. Tags preceded by an identifier, as in `Props<T>`, are
+/// generics and skipped, as are those in comments.
+fn tags<'a>(content: &'a str, file_path: &Path) -> Vec<Result<Element<'a>, &'a str>> {
+    let comments = CommentIndex::new(content, file_path);
+    TAG_REGEX
+        .captures_iter(content)
+        .filter_map(|caps| {
+            let start = caps.get(0).expect("group 0 always matches").start();
+            let close = caps.name("close").is_some();
+            let generic = !close
+                && content[..start]
+                    .chars()
+                    .next_back()
+                    .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$');
+            if generic || comments.is_comment(start) {
+                return None;
+            }
+            let tag = caps.name("tag").expect("tag always matches").as_str();
+            if close {
+                return Some(Err(tag));
+            }
+            let attrs = caps.name("attrs").map_or("", |m| m.as_str());
+            let attributes = ATTRIBUTE_REGEX
+                .captures_iter(attrs)
+                .map(|attr| {
+                    let name = attr["name"].to_ascii_lowercase();
+                    let name = name
+                        .strip_prefix("v-bind:")
+                        .or_else(|| name.strip_prefix(':'))
+                        .map(str::to_string)
+                        .unwrap_or(name);
+                    (name, attr.name("value").map_or("", |m| m.as_str()))
+                })
+                .collect();
+            Some(Ok(Element {
+                start,
+                tag,
+                attributes,
+                spread: attrs.contains("{..."),
+                self_closing: attrs.ends_with('/'),
+            }))
+        })
+        .collect()
+}
+
+/// Matches for `findings`, pairs of tag offset and message
+fn report(
+    content: &str,
+    file_path: &Path,
+    pattern: &'static str,
+    severity: Severity,
+    findings: Vec<(usize, String)>,
+) -> Vec<Match> {
+    if findings.is_empty() {
+        return Vec::new();
+    }
+    let lines = LineIndex::new(content);
+    let shared_path = SharedStr::from_path(file_path);
+    findings
+        .into_iter()
+        .map(|(start, message)| {
+            let (line_number, column) = lines.position(content, start);
+            Match {
+                file_path: shared_path.clone(),
+                line_number,
+                column,
+                pattern: SharedStr::interned(pattern),
+                message: format!("{}: {}", pattern, message),
+                severity: Some(severity.clone()),
+                category: Some(ACCESSIBILITY_CATEGORY.to_string()),
+                end: None,
+                author: None,
+            }
+        })
+        .collect()
+}
+
+/// Detector for `<img>` elements without an `alt` attribute. Decorative
+/// images still need an empty `alt=""` so screen readers skip them.
+pub struct ImgMissingAltDetector;
+
+impl PatternDetector for ImgMissingAltDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let findings = tags(content, file_path)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|element| {
+                element.tag.eq_ignore_ascii_case("img") && !element.spread && !element.has(&["alt"])
+            })
+            .map(|element| (element.start, "<img> has no alt text".to_string()))
+            .collect();
+        report(
+            content,
+            file_path,
+            "IMG_MISSING_ALT",
+            Severity::Medium,
+            findings,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, MARKUP_EXTENSIONS)
+    }
+}
+
+/// Detector for click handlers on native elements such as `<div>` that
+/// keyboard users cannot reach, unless a `role` says what the element acts as
+pub struct NonInteractiveClickDetector;
+
+impl PatternDetector for NonInteractiveClickDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let findings = tags(content, file_path)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|element| {
+                NON_INTERACTIVE_ELEMENTS.contains(&element.tag)
+                    && element.has(CLICK_ATTRIBUTES)
+                    && !element.has(&["role"])
+            })
+            .map(|element| {
+                (
+                    element.start,
+                    format!(
+                        "<{}> handles clicks but is not focusable; use a <button> or add a role and keyboard handling",
+                        element.tag
+                    ),
+                )
+            })
+            .collect();
+        report(
+            content,
+            file_path,
+            "NON_INTERACTIVE_CLICK",
+            Severity::Medium,
+            findings,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, MARKUP_EXTENSIONS)
+    }
+}
+
+/// Detector for `<input>`, `<select>` and `<textarea>` elements without a
+/// label: neither inside a `<label>`, named by a label's `for`, nor given
+/// `aria-label`, `aria-labelledby` or `title`
+pub struct MissingFormLabelDetector;
+
+impl PatternDetector for MissingFormLabelDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let tags = tags(content, file_path);
+        let labeled_ids: HashSet<&str> = tags
+            .iter()
+            .filter_map(|tag| tag.as_ref().ok())
+            .filter(|element| element.tag == "label")
+            .filter_map(|label| label.value("for").or_else(|| label.value("htmlfor")))
+            .collect();
+        let mut open_labels = 0usize;
+        let mut findings = Vec::new();
+        for tag in &tags {
+            let element = match tag {
+                Ok(element) => element,
+                Err("label") => {
+                    open_labels = open_labels.saturating_sub(1);
+                    continue;
+                }
+                Err(_) => continue,
+            };
+            if element.tag == "label" {
+                if !element.self_closing {
+                    open_labels += 1;
+                }
+                continue;
+            }
+            if !matches!(element.tag, "input" | "select" | "textarea")
+                || open_labels > 0
+                || element.spread
+                || element.has(&["aria-label", "aria-labelledby", "title"])
+                || element
+                    .value("id")
+                    .is_some_and(|id| labeled_ids.contains(id))
+                || (element.tag == "input"
+                    && element
+                        .value("type")
+                        .is_some_and(|kind| SELF_LABELED_INPUT_TYPES.contains(&kind)))
+            {
+                continue;
+            }
+            findings.push((element.start, format!("<{}> has no label", element.tag)));
+        }
+        report(
+            content,
+            file_path,
+            "MISSING_FORM_LABEL",
+            Severity::Medium,
+            findings,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, MARKUP_EXTENSIONS)
+    }
+}
+
+/// Detector for a `tabindex` above zero, which moves the element ahead of
+/// the page's natural focus order
+pub struct PositiveTabindexDetector;
+
+impl PatternDetector for PositiveTabindexDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let findings = tags(content, file_path)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter_map(|element| {
+                let tabindex: i64 = element.value("tabindex")?.parse().ok()?;
+                (tabindex > 0).then(|| {
+                    (
+                        element.start,
+                        format!(
+                            "<{}> has tabindex {}; use 0 and the document order instead",
+                            element.tag, tabindex
+                        ),
+                    )
+                })
+            })
+            .collect();
+        report(
+            content,
+            file_path,
+            "POSITIVE_TABINDEX",
+            Severity::Low,
+            findings,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, MARKUP_EXTENSIONS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(detector: &dyn PatternDetector, content: &str, path: &str) -> Vec<(usize, String)> {
+        detector
+            .detect(content, Path::new(path))
+            .into_iter()
+            .map(|m| (m.line_number, m.message))
+            .collect()
+    }
+
+    const FORM: &str = r#"export function Profile<T>(props: Props<T>) {
+  return (
+    <form>
+      <img src={avatar} />
+      <img src="/logo.svg" alt="" />
+      <img {...imageProps} />
+      {/* <img src="old.png"> */}
+      <div onClick={() => open()}>Edit</div>
+      <div role="button" tabIndex={0} onClick={() => open()}>Edit</div>
+      <Card onClick={select} />
+      <label htmlFor="name">Name</label>
+      <input id="name" />
+      <label>Email <input type="email" /></label>
+      <input type="text" placeholder="Search" />
+      <input type="hidden" name="csrf" />
+      <select aria-label="Country"></select>
+      <textarea tabIndex="2"></textarea>
+    </form>
+  );
+}"#;
+
+    #[test]
+    fn test_img_missing_alt_detector() {
+        let matches = ImgMissingAltDetector.detect(FORM, Path::new("Profile.tsx"));
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.line_number, m.column))
+                .collect::<Vec<_>>(),
+            vec![(4, 7)]
+        );
+        assert_eq!(matches[0].message, "IMG_MISSING_ALT: <img> has no alt text");
+        assert_eq!(matches[0].category.as_deref(), Some("Accessibility"));
+
+        let html = "<!-- <img src=\"a.png\"> -->\n<IMG SRC=\"b.png\">\n<img :alt=\"caption\" :src=\"url\">\n";
+        assert_eq!(found(&ImgMissingAltDetector, html, "index.html").len(), 1);
+        assert!(found(&ImgMissingAltDetector, "<img src=x>", "style.css").is_empty());
+    }
+
+    #[test]
+    fn test_non_interactive_click_detector() {
+        assert_eq!(
+            found(&NonInteractiveClickDetector, FORM, "Profile.tsx"),
+            vec![(
+                8,
+                "NON_INTERACTIVE_CLICK: <div> handles clicks but is not focusable; use a <button> or add a role and keyboard handling".to_string()
+            )]
+        );
+        let vue = "<template>\n  <span @click=\"toggle\">More</span>\n  <button @click=\"save\">Save</button>\n</template>\n";
+        assert_eq!(
+            found(&NonInteractiveClickDetector, vue, "Panel.vue").len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_missing_form_label_detector() {
+        assert_eq!(
+            found(&MissingFormLabelDetector, FORM, "Profile.tsx"),
+            vec![
+                (14, "MISSING_FORM_LABEL: <input> has no label".to_string()),
+                (
+                    17,
+                    "MISSING_FORM_LABEL: <textarea> has no label".to_string()
+                )
+            ]
+        );
+        let html = "<label for=\"q\">Search</label>\n<input id=\"q\">\n<input id=\"other\">\n<textarea></textarea>\n";
+        assert_eq!(
+            found(&MissingFormLabelDetector, html, "search.html")
+                .iter()
+                .map(|(line, _)| *line)
+                .collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+    }
+
+    #[test]
+    fn test_positive_tabindex_detector() {
+        assert_eq!(
+            found(&PositiveTabindexDetector, FORM, "Profile.tsx"),
+            vec![(
+                17,
+                "POSITIVE_TABINDEX: <textarea> has tabindex 2; use 0 and the document order instead"
+                    .to_string()
+            )]
+        );
+        assert!(found(&PositiveTabindexDetector, "<a tabindex=\"-1\">", "a.html").is_empty());
+    }
+}
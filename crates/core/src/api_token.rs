@@ -0,0 +1,125 @@
+//! Bearer tokens for `code-guardian serve`. Tokens carry a scope instead of
+//! a user or role; only their SHA-256 is stored, and presented tokens are
+//! compared against the stored hashes in constant time.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+
+/// Prefix of generated tokens, so leaked ones are easy to search for
+pub const TOKEN_PREFIX: &str = "cgt_";
+
+/// What a token may do on the server. `Scan` includes `Read`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenScope {
+    /// Read the feed and metrics
+    Read,
+    /// Also trigger scans
+    Scan,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::Read => "read",
+            TokenScope::Scan => "scan",
+        }
+    }
+
+    /// Whether a token of this scope may do what `needed` allows
+    pub fn allows(&self, needed: TokenScope) -> bool {
+        *self >= needed
+    }
+}
+
+impl fmt::Display for TokenScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TokenScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(TokenScope::Read),
+            "scan" => Ok(TokenScope::Scan),
+            _ => Err(format!(
+                "unknown token scope '{}', expected read or scan",
+                s
+            )),
+        }
+    }
+}
+
+/// A new random token: the prefix and 244 random bits as hex
+pub fn generate_token() -> String {
+    format!(
+        "{}{}{}",
+        TOKEN_PREFIX,
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// Hex SHA-256 of `token`, the form tokens are stored in
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Compares `a` and `b` in time that depends only on their lengths, so
+/// response times don't reveal how much of a guessed token was right
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// The token of an `Authorization: Bearer <token>` header value
+pub fn bearer_token(authorization: &str) -> Option<&str> {
+    let (scheme, token) = authorization.trim().split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_and_scopes() {
+        let token = generate_token();
+        assert!(token.starts_with(TOKEN_PREFIX));
+        assert_eq!(token.len(), TOKEN_PREFIX.len() + 64);
+        assert_ne!(token, generate_token());
+
+        let hash = hash_token(&token);
+        assert_eq!(hash.len(), 64);
+        assert!(constant_time_eq(
+            hash.as_bytes(),
+            hash_token(&token).as_bytes()
+        ));
+        assert!(!constant_time_eq(
+            hash.as_bytes(),
+            hash_token("cgt_guess").as_bytes()
+        ));
+        assert!(!constant_time_eq(b"abc", b"ab"));
+
+        assert_eq!(bearer_token("Bearer cgt_1"), Some("cgt_1"));
+        assert_eq!(bearer_token("bearer  cgt_1 "), Some("cgt_1"));
+        assert_eq!(bearer_token("Basic dXNlcg=="), None);
+        assert_eq!(bearer_token("Bearer"), None);
+
+        assert!(TokenScope::Scan.allows(TokenScope::Read));
+        assert!(!TokenScope::Read.allows(TokenScope::Scan));
+        assert_eq!("scan".parse(), Ok(TokenScope::Scan));
+        assert!("admin".parse::<TokenScope>().is_err());
+    }
+}
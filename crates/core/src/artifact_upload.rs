@@ -0,0 +1,265 @@
+//! Uploads of scan reports to object storage, so CI jobs can link to them
+//! from notifications and pull request comments. Files are named after a
+//! hash of their content and uploaded with the provider's own command-line
+//! tool, which brings its credentials: `aws`, and with the `gcs` and `azure`
+//! features `gcloud` and `az`.
+
+use crate::errors::{ScanError, ScanResult};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use tempfile::NamedTempFile;
+
+/// Hex digits of the content hash in uploaded file names
+const HASH_LENGTH: usize = 16;
+
+/// Where `--upload` puts artifacts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArtifactStore {
+    /// `s3://<bucket>/<prefix>`
+    S3 { bucket: String, prefix: String },
+    /// `gs://<bucket>/<prefix>`
+    #[cfg(feature = "gcs")]
+    Gcs { bucket: String, prefix: String },
+    /// `az://<account>/<container>/<prefix>`
+    #[cfg(feature = "azure")]
+    Azure {
+        account: String,
+        container: String,
+        prefix: String,
+    },
+}
+
+impl ArtifactStore {
+    /// Parses an upload destination such as `s3://bucket/prefix`
+    pub fn parse(destination: &str) -> ScanResult<Self> {
+        let invalid = |message: &str| ScanError::upload(destination, message);
+        let (scheme, path) = destination
+            .split_once("://")
+            .ok_or_else(|| invalid("expected a URL such as s3://bucket/prefix"))?;
+        let (bucket, prefix) = path.split_once('/').unwrap_or((path, ""));
+        if bucket.is_empty() {
+            return Err(invalid("the bucket is missing"));
+        }
+        let (bucket, prefix) = (bucket.to_string(), prefix.trim_matches('/').to_string());
+        match scheme {
+            "s3" => Ok(ArtifactStore::S3 { bucket, prefix }),
+            #[cfg(feature = "gcs")]
+            "gs" => Ok(ArtifactStore::Gcs { bucket, prefix }),
+            #[cfg(feature = "azure")]
+            "az" => {
+                let (container, prefix) = prefix.split_once('/').unwrap_or((&prefix, ""));
+                if container.is_empty() {
+                    return Err(invalid("expected az://<account>/<container>/<prefix>"));
+                }
+                Ok(ArtifactStore::Azure {
+                    account: bucket,
+                    container: container.to_string(),
+                    prefix: prefix.to_string(),
+                })
+            }
+            #[cfg(not(feature = "gcs"))]
+            "gs" => Err(invalid(
+                "this build cannot upload to gs://; rebuild with --features gcs",
+            )),
+            #[cfg(not(feature = "azure"))]
+            "az" => Err(invalid(
+                "this build cannot upload to az://; rebuild with --features azure",
+            )),
+            _ => Err(invalid(
+                "unsupported scheme; expected s3://, gs:// or az://",
+            )),
+        }
+    }
+
+    fn prefix(&self) -> &str {
+        match self {
+            ArtifactStore::S3 { prefix, .. } => prefix,
+            #[cfg(feature = "gcs")]
+            ArtifactStore::Gcs { prefix, .. } => prefix,
+            #[cfg(feature = "azure")]
+            ArtifactStore::Azure { prefix, .. } => prefix,
+        }
+    }
+
+    /// The object key of `content` uploaded as `name`: the prefix, then a hash
+    /// of the content, so each distinct report gets its own stable name
+    pub fn key(&self, name: &str, content: &[u8]) -> String {
+        let hash: String = Sha256::digest(content)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let file = format!("{}-{}", &hash[..HASH_LENGTH], name);
+        match self.prefix() {
+            "" => file,
+            prefix => format!("{}/{}", prefix, file),
+        }
+    }
+
+    /// HTTPS URL of the object stored under `key`
+    pub fn url(&self, key: &str) -> String {
+        match self {
+            ArtifactStore::S3 { bucket, .. } => {
+                format!("https://{}.s3.amazonaws.com/{}", bucket, key)
+            }
+            #[cfg(feature = "gcs")]
+            ArtifactStore::Gcs { bucket, .. } => {
+                format!("https://storage.googleapis.com/{}/{}", bucket, key)
+            }
+            #[cfg(feature = "azure")]
+            ArtifactStore::Azure {
+                account, container, ..
+            } => format!(
+                "https://{}.blob.core.windows.net/{}/{}",
+                account, container, key
+            ),
+        }
+    }
+
+    /// The command uploading `file` as `key`
+    fn command(&self, file: &Path, key: &str) -> Command {
+        match self {
+            ArtifactStore::S3 { bucket, .. } => {
+                let mut command = Command::new("aws");
+                command
+                    .args(["s3", "cp", "--only-show-errors"])
+                    .arg(file)
+                    .arg(format!("s3://{}/{}", bucket, key));
+                command
+            }
+            #[cfg(feature = "gcs")]
+            ArtifactStore::Gcs { bucket, .. } => {
+                let mut command = Command::new("gcloud");
+                command
+                    .args(["storage", "cp", "--quiet"])
+                    .arg(file)
+                    .arg(format!("gs://{}/{}", bucket, key));
+                command
+            }
+            #[cfg(feature = "azure")]
+            ArtifactStore::Azure {
+                account, container, ..
+            } => {
+                let mut command = Command::new("az");
+                command
+                    .args([
+                        "storage",
+                        "blob",
+                        "upload",
+                        "--only-show-errors",
+                        "--overwrite",
+                    ])
+                    .args(["--account-name", account, "--container-name", container])
+                    .args(["--name", key, "--file"])
+                    .arg(file);
+                command
+            }
+        }
+    }
+
+    /// Uploads `content` as `name` and returns its URL
+    pub fn upload(&self, name: &str, content: &[u8]) -> ScanResult<String> {
+        let key = self.key(name, content);
+        let url = self.url(&key);
+        let file =
+            upload_file(name, content).map_err(|e| ScanError::io(std::env::temp_dir(), e))?;
+        let output = self.command(file.path(), &key).output();
+        drop(file);
+        let output =
+            output.map_err(|e| ScanError::upload(&url, format!("could not run: {}", e)))?;
+        if output.status.success() {
+            Ok(url)
+        } else {
+            Err(ScanError::upload(
+                &url,
+                String::from_utf8_lossy(&output.stderr).trim(),
+            ))
+        }
+    }
+}
+
+/// A temporary copy of `content` for the upload tool, ending in `name` so the
+/// tool can tell its content type. It has a random name and is created with
+/// mode 0600 on Unix, so other users of a shared temp directory can neither
+/// read it nor plant a symlink in its place; it is removed when dropped.
+fn upload_file(name: &str, content: &[u8]) -> std::io::Result<NamedTempFile> {
+    let mut file = tempfile::Builder::new()
+        .prefix("code-guardian-")
+        .suffix(&format!("-{}", name.replace('/', "_")))
+        .tempfile()?;
+    file.write_all(content)?;
+    file.flush()?;
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_s3_destination() {
+        let store = ArtifactStore::parse("s3://ci-reports/code-guardian/main/").unwrap();
+        assert_eq!(
+            store,
+            ArtifactStore::S3 {
+                bucket: "ci-reports".to_string(),
+                prefix: "code-guardian/main".to_string(),
+            }
+        );
+        let key = store.key("report.json", b"[]");
+        assert_eq!(key, "code-guardian/main/4f53cda18c2baa0c-report.json");
+        assert_eq!(
+            store.url(&key),
+            "https://ci-reports.s3.amazonaws.com/code-guardian/main/4f53cda18c2baa0c-report.json"
+        );
+        assert_eq!(
+            ArtifactStore::parse("s3://ci-reports")
+                .unwrap()
+                .key("a.json", b"[]"),
+            "4f53cda18c2baa0c-a.json"
+        );
+
+        assert!(ArtifactStore::parse("ci-reports/prefix").is_err());
+        assert!(ArtifactStore::parse("s3:///prefix").is_err());
+        assert!(ArtifactStore::parse("ftp://host/prefix").is_err());
+    }
+
+    #[cfg(not(feature = "gcs"))]
+    #[test]
+    fn test_gcs_needs_feature() {
+        let err = ArtifactStore::parse("gs://bucket/prefix").unwrap_err();
+        assert!(err.to_string().contains("--features gcs"));
+    }
+
+    #[cfg(feature = "azure")]
+    #[test]
+    fn test_parse_azure_destination() {
+        let store = ArtifactStore::parse("az://acme/reports/ci").unwrap();
+        assert_eq!(
+            store.url("ci/x.json"),
+            "https://acme.blob.core.windows.net/reports/ci/x.json"
+        );
+        assert!(ArtifactStore::parse("az://acme").is_err());
+    }
+
+    #[test]
+    fn test_upload_file_is_private_and_unique() {
+        let file = upload_file("report.json", b"[]").unwrap();
+        let other = upload_file("report.json", b"[]").unwrap();
+        assert_ne!(file.path(), other.path());
+        assert_eq!(std::fs::read(file.path()).unwrap(), b"[]");
+        let file_name = file.path().file_name().unwrap().to_string_lossy();
+        assert!(file_name.ends_with("-report.json"), "{}", file_name);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = file.as_file().metadata().unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let path = file.path().to_path_buf();
+        drop(file);
+        assert!(!path.exists());
+    }
+}
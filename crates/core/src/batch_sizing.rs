@@ -0,0 +1,275 @@
+//! Parallelism tuned to costs measured while scanning: the time of each
+//! detector run decides whether the detectors of a file run in parallel, and
+//! the time of each file how many files of its directory make up one rayon
+//! task.
+
+use crate::{LineIndex, Match, PatternDetector};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Estimated detector time of a file above which its detectors run in
+/// parallel; below it, spawning the tasks costs more than it saves
+pub const PARALLEL_DETECTORS_MIN_COST: Duration = Duration::from_micros(200);
+
+/// Time one chunk of files should take
+pub const TARGET_CHUNK_COST: Duration = Duration::from_millis(5);
+
+/// Files per chunk of a directory before any file has been measured
+pub const DEFAULT_CHUNK_FILES: usize = 8;
+
+/// Most files per chunk, so a directory of tiny files still spreads across
+/// the pool
+pub const MAX_CHUNK_FILES: usize = 256;
+
+/// Batch sizing of a scan, see [`crate::ScanMetrics::batch_sizing`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchSizing {
+    /// Chunks the files were handed to the pool in
+    pub chunks: usize,
+    /// Files whose detectors ran in parallel
+    pub parallel_detector_files: usize,
+    /// Files whose detectors ran one after another
+    pub sequential_detector_files: usize,
+    /// Measured time of one detector over 1KB of content
+    pub detector_ns_per_kb: u64,
+    /// Measured directories, sorted by path
+    pub directories: Vec<DirectoryBatch>,
+}
+
+/// Measured cost of the files of one directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectoryBatch {
+    pub directory: String,
+    pub files: usize,
+    pub mean_file_cost_us: u64,
+    /// Files per chunk its cost calls for
+    pub chunk_size: usize,
+}
+
+/// Measures files and detector runs during a scan and sizes its parallel
+/// work from them. Detector costs are kept across scans; the rest is
+/// cleared by [`BatchController::reset`].
+#[derive(Debug, Default)]
+pub struct BatchController {
+    detector_ns: AtomicU64,
+    detector_bytes: AtomicU64,
+    /// Nanoseconds and files measured per directory
+    directories: DashMap<PathBuf, (u64, usize)>,
+    file_ns: AtomicU64,
+    files: AtomicUsize,
+    chunks: AtomicUsize,
+    parallel_files: AtomicUsize,
+    sequential_files: AtomicUsize,
+}
+
+impl BatchController {
+    /// Clears the file measurements and counts of the previous scan
+    pub fn reset(&self) {
+        self.directories.clear();
+        self.file_ns.store(0, Ordering::Relaxed);
+        self.files.store(0, Ordering::Relaxed);
+        self.chunks.store(0, Ordering::Relaxed);
+        self.parallel_files.store(0, Ordering::Relaxed);
+        self.sequential_files.store(0, Ordering::Relaxed);
+    }
+
+    /// Whether `detectors` detectors over `bytes` of content are worth
+    /// running in parallel. Until a detector run has been measured, more
+    /// than three detectors are.
+    pub fn parallel_detectors(&self, detectors: usize, bytes: usize) -> bool {
+        let measured_bytes = self.detector_bytes.load(Ordering::Relaxed);
+        let parallel = if detectors < 2 {
+            false
+        } else if measured_bytes == 0 {
+            detectors > 3
+        } else {
+            let ns_per_byte =
+                self.detector_ns.load(Ordering::Relaxed) as f64 / measured_bytes as f64;
+            ns_per_byte * (bytes * detectors) as f64 > PARALLEL_DETECTORS_MIN_COST.as_nanos() as f64
+        };
+        let counter = if parallel {
+            &self.parallel_files
+        } else {
+            &self.sequential_files
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        parallel
+    }
+
+    /// Runs `detector` on `content`, measuring how long it takes
+    pub fn detect(
+        &self,
+        detector: &dyn PatternDetector,
+        content: &str,
+        path: &Path,
+        lines: &LineIndex,
+    ) -> Vec<Match> {
+        let start = Instant::now();
+        let matches = detector.detect_with_lines(content, path, lines);
+        self.detector_ns
+            .fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        self.detector_bytes
+            .fetch_add(content.len() as u64, Ordering::Relaxed);
+        matches
+    }
+
+    /// Records that reading and scanning the file at `path` took `elapsed`
+    pub fn record_file(&self, path: &Path, elapsed: Duration) {
+        let ns = elapsed.as_nanos() as u64;
+        let directory = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let mut entry = self.directories.entry(directory).or_default();
+        entry.0 += ns;
+        entry.1 += 1;
+        self.file_ns.fetch_add(ns, Ordering::Relaxed);
+        self.files.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Files of `directory` per chunk, from the mean cost of its files
+    /// measured so far, else of all files
+    pub fn chunk_size(&self, directory: &Path) -> usize {
+        let (ns, files) = self.directories.get(directory).map(|e| *e).unwrap_or((
+            self.file_ns.load(Ordering::Relaxed),
+            self.files.load(Ordering::Relaxed),
+        ));
+        chunk_size_for(ns, files)
+    }
+
+    /// `files` in chunks of files of the same directory. Each chunk is
+    /// sized when it is taken, so chunks taken by the pool later use the
+    /// costs measured by then.
+    pub fn chunks(&self, files: Vec<PathBuf>) -> FileChunks<'_> {
+        FileChunks {
+            controller: self,
+            files: files.into_iter().peekable(),
+        }
+    }
+
+    /// Counts and measurements of the scan so far
+    pub fn stats(&self) -> BatchSizing {
+        let mut directories: Vec<DirectoryBatch> = self
+            .directories
+            .iter()
+            .map(|entry| {
+                let (ns, files) = *entry.value();
+                DirectoryBatch {
+                    directory: entry.key().to_string_lossy().to_string(),
+                    files,
+                    mean_file_cost_us: ns / files.max(1) as u64 / 1000,
+                    chunk_size: chunk_size_for(ns, files),
+                }
+            })
+            .collect();
+        directories.sort_by(|a, b| a.directory.cmp(&b.directory));
+        let bytes = self.detector_bytes.load(Ordering::Relaxed);
+        BatchSizing {
+            chunks: self.chunks.load(Ordering::Relaxed),
+            parallel_detector_files: self.parallel_files.load(Ordering::Relaxed),
+            sequential_detector_files: self.sequential_files.load(Ordering::Relaxed),
+            detector_ns_per_kb: (self.detector_ns.load(Ordering::Relaxed) * 1024)
+                .checked_div(bytes)
+                .unwrap_or(0),
+            directories,
+        }
+    }
+}
+
+fn chunk_size_for(ns: u64, files: usize) -> usize {
+    if files == 0 {
+        return DEFAULT_CHUNK_FILES;
+    }
+    let mean_ns = (ns / files as u64).max(1);
+    (TARGET_CHUNK_COST.as_nanos() as u64 / mean_ns).clamp(1, MAX_CHUNK_FILES as u64) as usize
+}
+
+/// Iterator over chunks of files, see [`BatchController::chunks`]
+pub struct FileChunks<'a> {
+    controller: &'a BatchController,
+    files: Peekable<std::vec::IntoIter<PathBuf>>,
+}
+
+impl Iterator for FileChunks<'_> {
+    type Item = Vec<PathBuf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.files.next()?;
+        let directory = first.parent().unwrap_or(Path::new("")).to_path_buf();
+        let size = self.controller.chunk_size(&directory);
+        let mut chunk = vec![first];
+        while chunk.len() < size {
+            match self
+                .files
+                .next_if(|path| path.parent() == Some(directory.as_path()))
+            {
+                Some(path) => chunk.push(path),
+                None => break,
+            }
+        }
+        self.controller.chunks.fetch_add(1, Ordering::Relaxed);
+        Some(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_follow_measured_costs() {
+        let controller = BatchController::default();
+        let files: Vec<PathBuf> = (0..20)
+            .map(|i| PathBuf::from(format!("src/{}.rs", i)))
+            .chain((0..3).map(|i| PathBuf::from(format!("vendor/{}.rs", i))))
+            .collect();
+
+        // Unmeasured: default chunks, never across directories
+        let sizes: Vec<usize> = controller.chunks(files.clone()).map(|c| c.len()).collect();
+        assert_eq!(sizes, vec![8, 8, 4, 3]);
+
+        // Slow files get a chunk each, fast ones share the largest chunks
+        controller.record_file(Path::new("src/0.rs"), Duration::from_millis(10));
+        controller.record_file(Path::new("vendor/0.rs"), Duration::from_micros(1));
+        assert_eq!(controller.chunk_size(Path::new("src")), 1);
+        assert_eq!(controller.chunk_size(Path::new("vendor")), MAX_CHUNK_FILES);
+        let sizes: Vec<usize> = controller.chunks(files).map(|c| c.len()).collect();
+        assert_eq!(sizes.len(), 21);
+        assert_eq!(sizes[20], 3);
+
+        let stats = controller.stats();
+        assert_eq!(stats.chunks, 25);
+        assert_eq!(stats.directories.len(), 2);
+        assert_eq!(stats.directories[0].directory, "src");
+        assert_eq!(stats.directories[0].mean_file_cost_us, 10_000);
+
+        controller.reset();
+        assert_eq!(controller.stats(), BatchSizing::default());
+    }
+
+    #[test]
+    fn test_parallel_detectors_by_cost() {
+        let controller = BatchController::default();
+        // Unmeasured: by detector count
+        assert!(!controller.parallel_detectors(3, 1_000_000));
+        assert!(controller.parallel_detectors(4, 10));
+
+        // 1ns per byte: 4 detectors over 100KB take 400µs, over 1KB 4µs
+        controller.detector_ns.store(1000, Ordering::Relaxed);
+        controller.detector_bytes.store(1000, Ordering::Relaxed);
+        assert!(controller.parallel_detectors(4, 100_000));
+        assert!(!controller.parallel_detectors(4, 1_000));
+        assert!(!controller.parallel_detectors(1, 10_000_000));
+
+        let stats = controller.stats();
+        assert_eq!(
+            (
+                stats.parallel_detector_files,
+                stats.sequential_detector_files
+            ),
+            (2, 3)
+        );
+        assert_eq!(stats.detector_ns_per_kb, 1024);
+    }
+}
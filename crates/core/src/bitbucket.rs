@@ -0,0 +1,188 @@
+//! Bitbucket Code Insights: a report on the scanned commit with an
+//! annotation per finding, which Bitbucket shows on pull request diffs.
+
+use crate::compliance::compliance_of;
+use crate::enhanced_config::EnhancedScanConfig;
+use crate::errors::{ScanError, ScanResult};
+use crate::finding_state::finding_fingerprints;
+use crate::webhook::send_json;
+use crate::{Match, Severity};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Environment variable holding the access token, which needs the
+/// `repository:write` scope
+pub const BITBUCKET_TOKEN_ENV: &str = "BITBUCKET_TOKEN";
+
+pub const DEFAULT_BITBUCKET_API_URL: &str = "https://api.bitbucket.org/2.0";
+
+/// ID of the Code Insights report, so each run replaces the last one
+pub const INSIGHTS_REPORT_ID: &str = "code-guardian";
+
+/// Annotations Bitbucket accepts per report, and per request
+const MAX_ANNOTATIONS: usize = 1000;
+const ANNOTATIONS_PER_REQUEST: usize = 100;
+
+/// Client of the Code Insights API of one Bitbucket Cloud repository
+#[derive(Debug, Clone)]
+pub struct BitbucketClient {
+    api_url: String,
+    workspace: String,
+    repository: String,
+    token: String,
+    timeout: Duration,
+}
+
+impl BitbucketClient {
+    pub fn new(
+        api_url: impl Into<String>,
+        workspace: impl Into<String>,
+        repository: impl Into<String>,
+        token: impl Into<String>,
+    ) -> Self {
+        Self {
+            api_url: api_url.into().trim_end_matches('/').to_string(),
+            workspace: workspace.into(),
+            repository: repository.into(),
+            token: token.into(),
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    fn report_url(&self, commit: &str) -> String {
+        format!(
+            "{}/repositories/{}/{}/commit/{}/reports/{}",
+            self.api_url, self.workspace, self.repository, commit, INSIGHTS_REPORT_ID
+        )
+    }
+
+    /// Replaces the report on `commit` with one on `matches` and annotates
+    /// the findings, returning the number of annotations
+    pub fn publish_report(&self, commit: &str, matches: &[Match]) -> ScanResult<usize> {
+        let url = self.report_url(commit);
+        self.send("PUT", &url, &insights_report(matches))?;
+        let annotations = insights_annotations(matches);
+        for batch in annotations.chunks(ANNOTATIONS_PER_REQUEST) {
+            self.send("POST", &format!("{}/annotations", url), &json!(batch))?;
+        }
+        Ok(annotations.len())
+    }
+
+    fn send(&self, method: &str, url: &str, body: &Value) -> ScanResult<()> {
+        let token = [format!("Authorization: Bearer {}", self.token)];
+        send_json(
+            method,
+            url,
+            &[],
+            &token,
+            body.to_string().as_bytes(),
+            self.timeout,
+        )
+        .map(|_| ())
+        .map_err(|e| ScanError::integration("Bitbucket", e))
+    }
+}
+
+/// Severity of a finding: its own, else the built-in default of its pattern;
+/// unknown patterns count as Medium
+fn severity(m: &Match, defaults: &EnhancedScanConfig) -> Severity {
+    m.severity
+        .clone()
+        .or_else(|| defaults.severity_levels.get(m.pattern.as_str()).cloned())
+        .unwrap_or(Severity::Medium)
+}
+
+/// The Code Insights report on `matches`, failed if any is Critical or High
+pub fn insights_report(matches: &[Match]) -> Value {
+    let defaults = EnhancedScanConfig::default();
+    let severe = matches
+        .iter()
+        .filter(|m| matches!(severity(m, &defaults), Severity::Critical | Severity::High))
+        .count();
+    json!({
+        "title": "code-guardian",
+        "details": format!(
+            "{} findings, {} of them Critical or High",
+            matches.len(),
+            severe
+        ),
+        "report_type": "SECURITY",
+        "reporter": "code-guardian",
+        "link": "https://github.com/d-oit/code-guardian",
+        "result": if severe == 0 { "PASSED" } else { "FAILED" },
+        "data": [
+            { "title": "Findings", "type": "NUMBER", "value": matches.len() },
+            { "title": "Critical or High", "type": "NUMBER", "value": severe },
+        ],
+    })
+}
+
+/// One annotation per finding, most severe first, at most [`MAX_ANNOTATIONS`].
+/// Fingerprints serve as external IDs.
+pub fn insights_annotations(matches: &[Match]) -> Vec<Value> {
+    let defaults = EnhancedScanConfig::default();
+    let mut annotated: Vec<(&Match, Severity, String)> = matches
+        .iter()
+        .zip(finding_fingerprints(matches, ""))
+        .map(|(m, fingerprint)| (m, severity(m, &defaults), fingerprint))
+        .collect();
+    annotated.sort_by_key(|(_, severity, _)| std::cmp::Reverse(severity.clone() as u8));
+    annotated
+        .into_iter()
+        .take(MAX_ANNOTATIONS)
+        .map(|(m, severity, fingerprint)| {
+            let security = compliance_of(&m.pattern).is_some()
+                || m.category
+                    .as_deref()
+                    .is_some_and(|category| category.eq_ignore_ascii_case("security"));
+            json!({
+                "external_id": fingerprint,
+                "annotation_type": if security { "VULNERABILITY" } else { "CODE_SMELL" },
+                "summary": m.message.chars().take(450).collect::<String>(),
+                "severity": match severity {
+                    Severity::Critical => "CRITICAL",
+                    Severity::High => "HIGH",
+                    Severity::Medium => "MEDIUM",
+                    Severity::Low | Severity::Info => "LOW",
+                },
+                "path": m.file_path.as_str().trim_start_matches("./").replace('\\', "/"),
+                "line": m.line_number,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(pattern: &str, line_number: usize) -> Match {
+        Match::new(
+            "./src/app.js",
+            line_number,
+            pattern,
+            format!("{}: found", pattern),
+        )
+    }
+
+    #[test]
+    fn test_insights_report() {
+        let report = insights_report(&[finding("TODO", 1), finding("UNSAFE_EVAL", 2)]);
+        assert_eq!(report["result"], "FAILED");
+        assert_eq!(report["data"][0]["value"], 2);
+        assert_eq!(report["data"][1]["value"], 1);
+        assert_eq!(insights_report(&[finding("TODO", 1)])["result"], "PASSED");
+    }
+
+    #[test]
+    fn test_annotations() {
+        let annotations = insights_annotations(&[finding("TODO", 1), finding("UNSAFE_EVAL", 2)]);
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(annotations[0]["severity"], "CRITICAL");
+        assert_eq!(annotations[0]["annotation_type"], "VULNERABILITY");
+        assert_eq!(annotations[0]["path"], "src/app.js");
+        assert_eq!(annotations[0]["line"], 2);
+        assert_eq!(annotations[1]["annotation_type"], "CODE_SMELL");
+        assert_eq!(annotations[1]["external_id"].as_str().unwrap().len(), 16);
+    }
+}
@@ -0,0 +1,330 @@
+//! C and C++ detectors, reported under the `Security` category: unbounded
+//! string functions, shell commands, `malloc` results used without a null
+//! check and compiler warnings switched off by pragma
+
+use crate::comment_spans::CommentIndex;
+use crate::detectors::{classify, detect_code_pattern, has_extension};
+use crate::line_index::LineIndex;
+use crate::security_detectors::SECURITY_CATEGORY;
+use crate::{Match, PatternDetector, Severity, SharedStr};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::Path;
+
+/// C and C++ sources and headers
+const C_EXTENSIONS: &[&str] = &["c", "h", "cpp", "cc", "cxx", "hpp", "hh", "hxx"];
+
+lazy_static! {
+    /// Functions that write to a buffer without knowing its size
+    static ref UNSAFE_STRING_FUNCTION_REGEX: Regex = Regex::new(
+        r"\b(?:gets|strcpy|strcat|sprintf|vsprintf)\s*\("
+    ).unwrap();
+
+    static ref SYSTEM_CALL_REGEX: Regex = Regex::new(
+        r"\b(?:system|popen|_popen|_wsystem)\s*\("
+    ).unwrap();
+
+    /// A pointer assigned the result of an allocation, with or without a cast
+    static ref ALLOCATION_REGEX: Regex = Regex::new(
+        r"(?P<var>[A-Za-z_][\w.]*(?:->\w+)*)\s*=\s*(?:\([^()]*\)\s*)?(?:malloc|calloc|realloc)\s*\("
+    ).unwrap();
+
+    static ref WARNING_PRAGMA_REGEX: Regex = Regex::new(
+        r"(?m)^[ \t]*#[ \t]*pragma[ \t]+(?:warning[ \t]*\([ \t]*disable\b|(?:GCC|clang)[ \t]+diagnostic[ \t]+ignored\b)"
+    ).unwrap();
+}
+
+/// Matches of `re` that call the C library function rather than a method of
+/// the same name, like `obj.strcpy(` or `obj->system(`. `std::` and `::`
+/// qualified calls count.
+fn detect_function_calls(
+    content: &str,
+    file_path: &Path,
+    pattern_name: &str,
+    re: &Regex,
+) -> Vec<Match> {
+    let mut matches = detect_code_pattern(content, file_path, pattern_name, re);
+    if matches.is_empty() {
+        return matches;
+    }
+    let index = LineIndex::new(content);
+    matches.retain(|m| {
+        let offset = index.offset(content, m.line_number, m.column);
+        !content[..offset].ends_with(['.', '>'])
+    });
+    matches
+}
+
+/// Detector for `gets`, `strcpy`, `strcat`, `sprintf` and `vsprintf`, which
+/// overflow their destination when the input is longer than expected.
+/// `gets` cannot be used safely at all and is Critical; the others are High.
+pub struct UnsafeStringFunctionDetector;
+
+impl PatternDetector for UnsafeStringFunctionDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let mut matches = detect_function_calls(
+            content,
+            file_path,
+            "C_UNSAFE_STRING_FUNCTION",
+            &UNSAFE_STRING_FUNCTION_REGEX,
+        );
+        let index = LineIndex::new(content);
+        for m in &mut matches {
+            let offset = index.offset(content, m.line_number, m.column);
+            let critical = content[offset..].starts_with("gets");
+            m.severity = Some(if critical {
+                Severity::Critical
+            } else {
+                Severity::High
+            });
+            m.category = Some(SECURITY_CATEGORY.to_string());
+        }
+        matches
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, C_EXTENSIONS)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&UNSAFE_STRING_FUNCTION_REGEX)
+    }
+}
+
+/// Detector for `system` and `popen`, which run their argument through the
+/// shell and so inject whatever untrusted input is spliced into it
+pub struct SystemCallDetector;
+
+impl PatternDetector for SystemCallDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        classify(
+            detect_function_calls(content, file_path, "C_SYSTEM_CALL", &SYSTEM_CALL_REGEX),
+            Severity::High,
+            SECURITY_CATEGORY,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, C_EXTENSIONS)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&SYSTEM_CALL_REGEX)
+    }
+}
+
+/// Detector for `malloc`, `calloc` and `realloc` results whose first use is
+/// not a null check, e.g. `p = malloc(n); memcpy(p, src, n);`. A check is a
+/// line mentioning the pointer that starts with `if`, `while`, `assert` or
+/// `return`, or tests it with `NULL`, `nullptr`, `!` or `?:`. Allocations
+/// made inside an `if` count as checked.
+pub struct UncheckedMallocDetector;
+
+impl UncheckedMallocDetector {
+    fn is_check(line: &str, var: &Regex) -> bool {
+        let code = line.trim_start();
+        var.is_match(line)
+            && (code.starts_with("if")
+                || code.starts_with("assert")
+                || code.starts_with("while")
+                || code.starts_with("return")
+                || line.contains("NULL")
+                || line.contains("nullptr")
+                || line.contains('!')
+                || line.contains('?'))
+    }
+}
+
+impl PatternDetector for UncheckedMallocDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let comments = CommentIndex::new(content, file_path);
+        let index = LineIndex::new(content);
+        let shared_path = SharedStr::from_path(file_path);
+        let mut matches = Vec::new();
+        for caps in ALLOCATION_REGEX.captures_iter(content) {
+            let var = caps.name("var").expect("var always matches");
+            if comments.is_comment(var.start()) {
+                continue;
+            }
+            let line_number = index.line_of(var.start());
+            let line = index.line(content, line_number);
+            // `if ((p = malloc(n)) == NULL)`
+            if line.trim_start().starts_with("if") || line.contains("NULL") {
+                continue;
+            }
+            let var_regex = Regex::new(&format!(r"(?:^|[^\w.>]){}\b", regex::escape(var.as_str())))
+                .expect("escaped name is a valid regex");
+            let first_use = (line_number + 1..=index.line_count())
+                .map(|n| (n, index.line(content, n)))
+                // The end of the function
+                .take_while(|(_, text)| !text.starts_with('}'))
+                .find(|(n, text)| {
+                    var_regex.is_match(text)
+                        && !comments.is_comment(index.line_start(*n) + indent(text))
+                });
+            let Some((_, text)) = first_use else {
+                continue;
+            };
+            if Self::is_check(text, &var_regex) {
+                continue;
+            }
+            let (line_number, column) = index.position(content, var.start());
+            matches.push(Match {
+                file_path: shared_path.clone(),
+                line_number,
+                column,
+                pattern: SharedStr::interned("C_UNCHECKED_MALLOC"),
+                message: format!(
+                    "C_UNCHECKED_MALLOC: {} is used without a NULL check",
+                    var.as_str()
+                ),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
+            });
+        }
+        classify(matches, Severity::Medium, SECURITY_CATEGORY)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, C_EXTENSIONS)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&ALLOCATION_REGEX)
+    }
+}
+
+/// Width of the indentation of `line`
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Detector for `#pragma warning(disable: ...)` and the GCC and Clang
+/// `#pragma ... diagnostic ignored`, which hide the compiler's own warnings
+/// about code like the above
+pub struct WarningPragmaDetector;
+
+impl PatternDetector for WarningPragmaDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        classify(
+            detect_code_pattern(
+                content,
+                file_path,
+                "C_DISABLED_WARNING",
+                &WARNING_PRAGMA_REGEX,
+            ),
+            Severity::Low,
+            SECURITY_CATEGORY,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, C_EXTENSIONS)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&WARNING_PRAGMA_REGEX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"#pragma warning(disable: 4996)
+#pragma GCC diagnostic ignored "-Wformat"
+#pragma once
+
+char *copy(const char *src) {
+    char *dst = malloc(strlen(src) + 1);
+    strcpy(dst, src);
+    return dst;
+}
+
+int run(const char *name) {
+    char cmd[64];
+    char *buf = (char *)calloc(16, 1);
+    if (!buf) {
+        return -1;
+    }
+    // sprintf(cmd, "old %s", name);
+    snprintf(cmd, sizeof cmd, "ls %s", name);
+    sprintf(buf, "%s", name);
+    gets(cmd);
+    logger.strcpy(cmd);
+    return system(cmd);
+}
+"#;
+
+    fn found(detector: &dyn PatternDetector) -> Vec<(usize, Option<Severity>)> {
+        detector
+            .detect(SOURCE, Path::new("src/run.c"))
+            .into_iter()
+            .map(|m| (m.line_number, m.severity))
+            .collect()
+    }
+
+    #[test]
+    fn test_unsafe_string_function_detector() {
+        assert_eq!(
+            found(&UnsafeStringFunctionDetector),
+            vec![
+                (7, Some(Severity::High)),
+                (19, Some(Severity::High)),
+                (20, Some(Severity::Critical)),
+            ]
+        );
+        assert!(UnsafeStringFunctionDetector
+            .detect(SOURCE, Path::new("src/run.rs"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_system_call_detector() {
+        assert_eq!(found(&SystemCallDetector), vec![(22, Some(Severity::High))]);
+    }
+
+    #[test]
+    fn test_unchecked_malloc_detector() {
+        let matches = UncheckedMallocDetector.detect(SOURCE, Path::new("src/run.c"));
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.line_number, m.column))
+                .collect::<Vec<_>>(),
+            vec![(6, 11)]
+        );
+        assert_eq!(
+            matches[0].message,
+            "C_UNCHECKED_MALLOC: dst is used without a NULL check"
+        );
+        assert_eq!(matches[0].category.as_deref(), Some("Security"));
+
+        let checked = "void f() {\n    node->next = malloc(sizeof *node);\n    if (node->next == NULL) abort();\n    if ((p = malloc(8)) == NULL) return;\n}\n";
+        assert!(UncheckedMallocDetector
+            .detect(checked, Path::new("list.cpp"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_warning_pragma_detector() {
+        assert_eq!(
+            found(&WarningPragmaDetector),
+            vec![(1, Some(Severity::Low)), (2, Some(Severity::Low))]
+        );
+    }
+}
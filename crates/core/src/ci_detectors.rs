@@ -0,0 +1,243 @@
+use crate::{Match, PatternDetector, SharedStr};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::{Component, Path};
+
+/// Pattern name shared by every CI configuration finding
+pub const CI_CONFIG_PATTERN: &str = "CI_CONFIG";
+
+lazy_static! {
+    // Failures that are silently ignored
+    pub static ref CI_CONTINUE_ON_ERROR_REGEX: Regex = Regex::new(
+        r"^\s*(?:-\s*)?(?:continue-on-error|allow_failure):\s*true\b"
+    ).unwrap();
+
+    // Third-party actions pinned to a mutable branch instead of a tag or SHA
+    pub static ref CI_BRANCH_PINNED_ACTION_REGEX: Regex = Regex::new(
+        r#"^\s*(?:-\s*)?uses:\s*["']?[\w.-]+/[\w./-]+@(?:main|master|develop|dev|trunk|HEAD)["']?\s*$"#
+    ).unwrap();
+
+    // Workflows triggered with write access and secrets for forked PRs
+    pub static ref CI_PULL_REQUEST_TARGET_REGEX: Regex = Regex::new(
+        r"^\s*(?:on:\s*\[?.*\bpull_request_target\b|-?\s*pull_request_target\s*:?\s*$)"
+    ).unwrap();
+
+    // Checking out untrusted PR code
+    pub static ref CI_PR_HEAD_CHECKOUT_REGEX: Regex = Regex::new(
+        r"\$\{\{\s*github\.(?:event\.pull_request\.head\.(?:sha|ref)|head_ref)\s*\}\}"
+    ).unwrap();
+
+    // A commented-out mapping key, e.g. `# build:`
+    pub static ref CI_COMMENTED_KEY_REGEX: Regex = Regex::new(
+        r"^\s*#\s*[\w.-]+:\s*$"
+    ).unwrap();
+
+    // A commented-out key that only appears inside a job definition
+    pub static ref CI_COMMENTED_JOB_BODY_REGEX: Regex = Regex::new(
+        r"^\s*#\s*(?:runs-on|steps|script|stage|needs|image|uses):"
+    ).unwrap();
+}
+
+/// Returns true for GitHub Actions workflows and GitLab CI configuration files
+pub fn is_ci_config(file_path: &Path) -> bool {
+    let is_yaml = file_path
+        .extension()
+        .is_some_and(|ext| ext == "yml" || ext == "yaml");
+    if !is_yaml {
+        return false;
+    }
+
+    let name = file_path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    if name.starts_with(".gitlab-ci") {
+        return true;
+    }
+
+    let dirs: Vec<_> = file_path
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect();
+    dirs.windows(2)
+        .any(|pair| pair[0] == ".github" && pair[1] == "workflows")
+        || dirs.iter().any(|dir| dir == ".gitlab")
+}
+
+/// Detector for CI configuration debt in GitHub Actions and GitLab CI files:
+/// ignored failures, branch-pinned actions, `pull_request_target` workflows
+/// that check out PR code and commented-out jobs. All findings are reported
+/// under the `CI_CONFIG` pattern.
+pub struct CiConfigDetector;
+
+impl CiConfigDetector {
+    fn push(matches: &mut Vec<Match>, file_path: &Path, line_idx: usize, line: &str, issue: &str) {
+        let column = line.len() - line.trim_start().len() + 1;
+        matches.push(Match {
+            file_path: SharedStr::from_path(file_path),
+            line_number: line_idx + 1,
+            column,
+            pattern: SharedStr::interned(CI_CONFIG_PATTERN),
+            message: format!("{} ({}): {}", CI_CONFIG_PATTERN, issue, line.trim()),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
+        });
+    }
+}
+
+impl PatternDetector for CiConfigDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+
+        let lines: Vec<&str> = content.lines().collect();
+        let has_pull_request_target = lines
+            .iter()
+            .any(|line| CI_PULL_REQUEST_TARGET_REGEX.is_match(line));
+
+        let mut matches = Vec::new();
+        let mut in_commented_job = false;
+        for (line_idx, line) in lines.iter().enumerate() {
+            if line.trim_start().starts_with('#') {
+                // Report each commented-out job once, at its key line
+                let next_is_job_body = lines
+                    .get(line_idx + 1)
+                    .is_some_and(|next| CI_COMMENTED_JOB_BODY_REGEX.is_match(next));
+                if !in_commented_job && CI_COMMENTED_KEY_REGEX.is_match(line) && next_is_job_body {
+                    Self::push(&mut matches, file_path, line_idx, line, "commented-out job");
+                    in_commented_job = true;
+                }
+                continue;
+            }
+            in_commented_job = false;
+
+            if CI_CONTINUE_ON_ERROR_REGEX.is_match(line) {
+                Self::push(&mut matches, file_path, line_idx, line, "failure ignored");
+            }
+            if CI_BRANCH_PINNED_ACTION_REGEX.is_match(line) {
+                Self::push(
+                    &mut matches,
+                    file_path,
+                    line_idx,
+                    line,
+                    "action pinned to branch",
+                );
+            }
+            if has_pull_request_target && CI_PR_HEAD_CHECKOUT_REGEX.is_match(line) {
+                Self::push(
+                    &mut matches,
+                    file_path,
+                    line_idx,
+                    line,
+                    "pull_request_target checks out PR code",
+                );
+            }
+        }
+        matches
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        is_ci_config(file_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issues(matches: &[Match]) -> Vec<(usize, &str)> {
+        matches
+            .iter()
+            .map(|m| {
+                let issue = m.message.split(['(', ')']).nth(1).unwrap_or_default();
+                (m.line_number, issue)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ci_config_file_detection() {
+        assert!(is_ci_config(Path::new(".github/workflows/ci.yml")));
+        assert!(is_ci_config(Path::new(
+            "repo/.github/workflows/release.yaml"
+        )));
+        assert!(is_ci_config(Path::new(".gitlab-ci.yml")));
+        assert!(is_ci_config(Path::new(".gitlab/ci/test.yml")));
+        assert!(!is_ci_config(Path::new(".github/dependabot.yml")));
+        assert!(!is_ci_config(Path::new("config/app.yml")));
+    }
+
+    #[test]
+    fn test_github_actions_debt() {
+        let content = r#"on:
+  pull_request_target:
+    types: [opened]
+jobs:
+  test:
+    runs-on: ubuntu-latest
+    continue-on-error: true
+    steps:
+      - uses: actions/checkout@v4
+        with:
+          ref: ${{ github.event.pull_request.head.sha }}
+      - uses: some-org/setup-tool@main
+      - uses: ./local-action
+#  lint:
+#    runs-on: ubuntu-latest
+#    steps:
+#      - run: cargo clippy
+"#;
+        let matches = CiConfigDetector.detect(content, Path::new(".github/workflows/ci.yml"));
+        assert!(matches.iter().all(|m| m.pattern == CI_CONFIG_PATTERN));
+        assert_eq!(
+            issues(&matches),
+            vec![
+                (7, "failure ignored"),
+                (11, "pull_request_target checks out PR code"),
+                (12, "action pinned to branch"),
+                (14, "commented-out job"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pr_head_checkout_without_pull_request_target() {
+        let content = r#"on: [pull_request]
+jobs:
+  test:
+    steps:
+      - uses: actions/checkout@v4
+        with:
+          ref: ${{ github.event.pull_request.head.sha }}
+"#;
+        let matches = CiConfigDetector.detect(content, Path::new(".github/workflows/ci.yml"));
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn test_gitlab_ci_debt() {
+        let content = r#"test:
+  script: cargo test
+  allow_failure: true
+# Plain comment about the pipeline:
+# deploy:
+#   stage: deploy
+"#;
+        let matches = CiConfigDetector.detect(content, Path::new(".gitlab-ci.yml"));
+        assert_eq!(
+            issues(&matches),
+            vec![(3, "failure ignored"), (5, "commented-out job")]
+        );
+
+        // Non-CI YAML is ignored
+        assert!(CiConfigDetector
+            .detect(content, Path::new("config.yml"))
+            .is_empty());
+    }
+}
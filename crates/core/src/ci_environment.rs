@@ -0,0 +1,159 @@
+//! The CI pipeline a scan runs in, detected from the variables the CI
+//! provider sets, so stored scans and notifications can link back to it
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// CI providers recognized by [`CiRun::from_env`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CiProvider {
+    GithubActions,
+    Gitlab,
+    Jenkins,
+}
+
+impl fmt::Display for CiProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CiProvider::GithubActions => "GitHub Actions",
+            CiProvider::Gitlab => "GitLab CI",
+            CiProvider::Jenkins => "Jenkins",
+        })
+    }
+}
+
+/// The pipeline run that produced a scan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CiRun {
+    pub provider: CiProvider,
+    /// ID of the workflow run, pipeline or build
+    pub run_id: Option<String>,
+    /// Web page of the run or job
+    pub job_url: Option<String>,
+    /// What started the run, such as `push`, `schedule` or `merge_request_event`
+    pub trigger: Option<String>,
+}
+
+impl CiRun {
+    /// The run the process is part of, if it runs in a recognized CI provider
+    pub fn detect() -> Option<Self> {
+        Self::from_env(&std::env::vars().collect())
+    }
+
+    /// The run described by the CI variables in `env`
+    pub fn from_env(env: &HashMap<String, String>) -> Option<Self> {
+        let var = |name: &str| env.get(name).filter(|value| !value.is_empty()).cloned();
+        if var("GITHUB_ACTIONS").as_deref() == Some("true") {
+            let run_id = var("GITHUB_RUN_ID");
+            let job_url = match (var("GITHUB_SERVER_URL"), var("GITHUB_REPOSITORY"), &run_id) {
+                (Some(server), Some(repository), Some(run_id)) => Some(format!(
+                    "{}/{}/actions/runs/{}",
+                    server.trim_end_matches('/'),
+                    repository,
+                    run_id
+                )),
+                _ => None,
+            };
+            Some(Self {
+                provider: CiProvider::GithubActions,
+                run_id,
+                job_url,
+                trigger: var("GITHUB_EVENT_NAME"),
+            })
+        } else if var("GITLAB_CI").is_some() {
+            Some(Self {
+                provider: CiProvider::Gitlab,
+                run_id: var("CI_PIPELINE_ID"),
+                job_url: var("CI_JOB_URL").or_else(|| var("CI_PIPELINE_URL")),
+                trigger: var("CI_PIPELINE_SOURCE"),
+            })
+        } else if var("JENKINS_URL").is_some() {
+            Some(Self {
+                provider: CiProvider::Jenkins,
+                run_id: var("BUILD_ID").or_else(|| var("BUILD_NUMBER")),
+                job_url: var("BUILD_URL"),
+                trigger: None,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for CiRun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.provider)?;
+        if let Some(run_id) = &self.run_id {
+            write!(f, " run {}", run_id)?;
+        }
+        if let Some(job_url) = &self.job_url {
+            write!(f, " ({})", job_url)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> HashMap<String, String> {
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_github_actions() {
+        let run = CiRun::from_env(&env(&[
+            ("GITHUB_ACTIONS", "true"),
+            ("GITHUB_SERVER_URL", "https://github.com"),
+            ("GITHUB_REPOSITORY", "d-oit/code-guardian"),
+            ("GITHUB_RUN_ID", "4242"),
+            ("GITHUB_EVENT_NAME", "schedule"),
+        ]))
+        .unwrap();
+        assert_eq!(run.provider, CiProvider::GithubActions);
+        assert_eq!(
+            run.job_url.as_deref(),
+            Some("https://github.com/d-oit/code-guardian/actions/runs/4242")
+        );
+        assert_eq!(run.trigger.as_deref(), Some("schedule"));
+        assert_eq!(
+            run.to_string(),
+            "GitHub Actions run 4242 (https://github.com/d-oit/code-guardian/actions/runs/4242)"
+        );
+    }
+
+    #[test]
+    fn test_detect_gitlab_and_jenkins() {
+        let run = CiRun::from_env(&env(&[
+            ("GITLAB_CI", "true"),
+            ("CI_PIPELINE_ID", "77"),
+            ("CI_JOB_URL", "https://gitlab.example.com/g/p/-/jobs/901"),
+            ("CI_PIPELINE_SOURCE", "merge_request_event"),
+        ]))
+        .unwrap();
+        assert_eq!(run.provider, CiProvider::Gitlab);
+        assert_eq!(run.run_id.as_deref(), Some("77"));
+        assert_eq!(
+            run.job_url.as_deref(),
+            Some("https://gitlab.example.com/g/p/-/jobs/901")
+        );
+
+        let run = CiRun::from_env(&env(&[
+            ("JENKINS_URL", "https://ci.example.com/"),
+            ("BUILD_NUMBER", "15"),
+            ("BUILD_URL", "https://ci.example.com/job/app/15/"),
+        ]))
+        .unwrap();
+        assert_eq!(run.provider, CiProvider::Jenkins);
+        assert_eq!(run.run_id.as_deref(), Some("15"));
+        assert_eq!(run.trigger, None);
+
+        assert_eq!(CiRun::from_env(&env(&[("CI", "true")])), None);
+        assert_eq!(CiRun::from_env(&env(&[("GITHUB_ACTIONS", "false")])), None);
+    }
+}
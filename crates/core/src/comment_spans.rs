@@ -0,0 +1,326 @@
+//! Where the comments of a source file are, so detectors can tell comments
+//! from code: debt markers live in comments, including block comments and
+//! docstrings spanning lines, while code-only detectors such as `UNWRAP`
+//! must not fire on commented-out code. String literals are found on the way
+//! for detectors of calls, such as `UNSAFE_EVAL`, that must not fire on text.
+
+use std::ops::Range;
+use std::path::Path;
+
+/// How comments and string literals are written in a language
+struct CommentSyntax {
+    line: &'static [&'static str],
+    block: &'static [(&'static str, &'static str)],
+    /// Block comments nest, as in Rust
+    nested: bool,
+    /// Triple-quoted strings, read as comments since they are mostly docstrings
+    docstrings: &'static [&'static str],
+    /// String delimiters, skipped so comment markers inside strings are ignored
+    strings: &'static [&'static str],
+    /// `'` starts a char literal or a lifetime rather than a string
+    char_literals: bool,
+    /// Strings may span lines; backtick strings always may
+    multi_line_strings: bool,
+}
+
+const C_LIKE: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    nested: false,
+    docstrings: &[],
+    strings: &["\"", "'", "`"],
+    char_literals: false,
+    multi_line_strings: false,
+};
+
+const RUST: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    nested: true,
+    docstrings: &[],
+    strings: &["\""],
+    char_literals: true,
+    multi_line_strings: true,
+};
+
+const HASH: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: &[],
+    nested: false,
+    docstrings: &[],
+    strings: &["\"", "'"],
+    char_literals: false,
+    multi_line_strings: false,
+};
+
+const PYTHON: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: &[],
+    nested: false,
+    docstrings: &["\"\"\"", "'''"],
+    strings: &["\"", "'"],
+    char_literals: false,
+    multi_line_strings: false,
+};
+
+const SQL: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    block: &[("/*", "*/")],
+    nested: false,
+    docstrings: &[],
+    strings: &["'", "\""],
+    char_literals: false,
+    multi_line_strings: false,
+};
+
+const LUA: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    block: &[("--[[", "]]")],
+    nested: false,
+    docstrings: &[],
+    strings: &["\"", "'"],
+    char_literals: false,
+    multi_line_strings: false,
+};
+
+const MARKUP: CommentSyntax = CommentSyntax {
+    line: &[],
+    block: &[("<!--", "-->")],
+    nested: false,
+    docstrings: &[],
+    strings: &[],
+    char_literals: false,
+    multi_line_strings: false,
+};
+
+const CSS: CommentSyntax = CommentSyntax {
+    line: &[],
+    block: &[("/*", "*/")],
+    nested: false,
+    docstrings: &[],
+    strings: &["\"", "'"],
+    char_literals: false,
+    multi_line_strings: false,
+};
+
+fn syntax_for(path: &Path) -> Option<&'static CommentSyntax> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match ext.as_str() {
+        "rs" => &RUST,
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "java" | "kt" | "kts" | "scala" | "c"
+        | "h" | "cpp" | "cc" | "cxx" | "hpp" | "cs" | "go" | "swift" | "dart" | "php" | "vue"
+        | "svelte" | "scss" | "less" => &C_LIKE,
+        "py" => &PYTHON,
+        "rb" | "sh" | "bash" | "zsh" | "fish" | "pl" | "pm" | "r" | "yaml" | "yml" | "toml"
+        | "ex" | "exs" => &HASH,
+        "sql" => &SQL,
+        "lua" => &LUA,
+        "html" | "htm" | "xml" | "md" => &MARKUP,
+        "css" => &CSS,
+        _ => return None,
+    })
+}
+
+/// Byte ranges of the comments in a file, in order, and of its string
+/// literals. Docstrings count as comments. Files of unknown languages have
+/// neither, so code-only detectors behave there as if everything were code.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommentIndex {
+    spans: Vec<Range<usize>>,
+    strings: Vec<Range<usize>>,
+}
+
+impl CommentIndex {
+    /// Finds the comments of `content`, read as the language of `path`
+    pub fn new(content: &str, path: &Path) -> Self {
+        match syntax_for(path) {
+            Some(syntax) => {
+                let (spans, strings) = comment_spans(content.as_bytes(), syntax);
+                Self { spans, strings }
+            }
+            None => Self::default(),
+        }
+    }
+
+    pub fn spans(&self) -> &[Range<usize>] {
+        &self.spans
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Whether the byte `offset` lies inside a comment
+    pub fn is_comment(&self, offset: usize) -> bool {
+        contains(&self.spans, offset)
+    }
+
+    /// Whether the byte `offset` lies inside a string literal, quotes included
+    pub fn is_string(&self, offset: usize) -> bool {
+        contains(&self.strings, offset)
+    }
+}
+
+/// Whether one of the ordered `spans` contains `offset`
+fn contains(spans: &[Range<usize>], offset: usize) -> bool {
+    let index = spans.partition_point(|span| span.end <= offset);
+    spans.get(index).is_some_and(|span| span.start <= offset)
+}
+
+/// Comment and string literal spans of `bytes`
+fn comment_spans(bytes: &[u8], syntax: &CommentSyntax) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let mut spans = Vec::new();
+    let mut strings = Vec::new();
+    let at = |i: usize, token: &str| bytes[i..].starts_with(token.as_bytes());
+    let mut i = 0;
+    'scan: while i < bytes.len() {
+        for quote in syntax.docstrings {
+            if at(i, quote) {
+                let end = find(bytes, i + quote.len(), quote)
+                    .map_or(bytes.len(), |end| end + quote.len());
+                spans.push(i..end);
+                i = end;
+                continue 'scan;
+            }
+        }
+        for (open, close) in syntax.block {
+            if at(i, open) {
+                let end = block_end(bytes, i + open.len(), open, close, syntax.nested);
+                spans.push(i..end);
+                i = end;
+                continue 'scan;
+            }
+        }
+        for marker in syntax.line {
+            if at(i, marker) {
+                let end = memchr::memchr(b'\n', &bytes[i..]).map_or(bytes.len(), |end| i + end);
+                spans.push(i..end);
+                i = end;
+                continue 'scan;
+            }
+        }
+        if syntax.char_literals && bytes[i] == b'\'' {
+            i += char_literal_len(&bytes[i..]);
+            continue;
+        }
+        for quote in syntax.strings {
+            if at(i, quote) {
+                let end = string_end(
+                    bytes,
+                    i + quote.len(),
+                    quote.as_bytes()[0],
+                    syntax.multi_line_strings,
+                );
+                strings.push(i..end);
+                i = end;
+                continue 'scan;
+            }
+        }
+        i += 1;
+    }
+    (spans, strings)
+}
+
+fn find(bytes: &[u8], from: usize, token: &str) -> Option<usize> {
+    (from..bytes.len()).find(|&i| bytes[i..].starts_with(token.as_bytes()))
+}
+
+/// Offset just past the `close` that ends a block comment whose body starts
+/// at `from`, or the end of the file if it is unterminated
+fn block_end(bytes: &[u8], from: usize, open: &str, close: &str, nested: bool) -> usize {
+    let mut depth = 1;
+    let mut i = from;
+    while i < bytes.len() {
+        if bytes[i..].starts_with(close.as_bytes()) {
+            depth -= 1;
+            i += close.len();
+            if depth == 0 {
+                return i;
+            }
+        } else if nested && bytes[i..].starts_with(open.as_bytes()) {
+            depth += 1;
+            i += open.len();
+        } else {
+            i += 1;
+        }
+    }
+    bytes.len()
+}
+
+/// Offset just past the closing `quote` of a string whose body starts at
+/// `from`. Unless strings may span lines, or it is a backtick string, an
+/// unterminated string ends with its line.
+fn string_end(bytes: &[u8], from: usize, quote: u8, multi_line: bool) -> usize {
+    let mut i = from;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'\n' if !multi_line && quote != b'`' => return i,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Length of a Rust char literal such as `'"'` or `'\''` at the start of
+/// `bytes`, or 1 for a lifetime or a stray quote
+fn char_literal_len(bytes: &[u8]) -> usize {
+    match bytes {
+        [b'\'', b'\\', _, rest @ ..] => rest
+            .iter()
+            .position(|&b| b == b'\'')
+            .map_or(1, |end| end + 4),
+        [b'\'', _, b'\'', ..] => 3,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comments<'a>(content: &'a str, path: &str) -> Vec<&'a str> {
+        CommentIndex::new(content, Path::new(path))
+            .spans()
+            .iter()
+            .map(|span| &content[span.clone()])
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_comments() {
+        let content = "let s = \"// not a comment\"; // TODO: real\nlet c = '\"'; /* outer /* inner */\n still */ x.unwrap();\nfn f<'a>(x: &'a str) {} // end";
+        assert_eq!(
+            comments(content, "lib.rs"),
+            vec!["// TODO: real", "/* outer /* inner */\n still */", "// end"]
+        );
+
+        let index = CommentIndex::new(content, Path::new("lib.rs"));
+        assert!(!index.is_comment(content.find("x.unwrap").unwrap()));
+        assert!(index.is_comment(content.find("still").unwrap()));
+        assert!(!index.is_comment(content.find("not a comment").unwrap()));
+        assert!(index.is_string(content.find("not a comment").unwrap()));
+        assert!(!index.is_string(content.find("x.unwrap").unwrap()));
+    }
+
+    #[test]
+    fn test_other_languages() {
+        assert_eq!(
+            comments(
+                "def f():\n    \"\"\"FIXME: spans\n    lines\"\"\"\n    s = '#'  # note\n",
+                "app.py"
+            ),
+            vec!["\"\"\"FIXME: spans\n    lines\"\"\"", "# note"]
+        );
+        assert_eq!(
+            comments("const s = `a\n// b`; /* c */", "app.js"),
+            vec!["/* c */"]
+        );
+        assert_eq!(
+            comments("SELECT 1; -- TODO\n/* x */", "q.sql"),
+            vec!["-- TODO", "/* x */"]
+        );
+        assert!(CommentIndex::new("// x", Path::new("notes.adoc")).is_empty());
+    }
+}
@@ -0,0 +1,244 @@
+//! Mapping of rules to the compliance controls their findings bear on: CWE
+//! weaknesses, OWASP Top 10 categories and SOC 2 criteria. Security rules
+//! have built-in mappings; the `compliance` config section adds or replaces
+//! them, e.g. for custom detectors.
+
+use crate::redaction::SECRET_PATTERNS;
+use crate::Match;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::RwLock;
+
+/// Compliance controls of a rule, a `[compliance.<PATTERN>]` config section
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ComplianceMapping {
+    /// CWE IDs, e.g. `798` for CWE-798
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cwe: Vec<u32>,
+    /// OWASP Top 10 categories, e.g. `A07:2021`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub owasp: Vec<String>,
+    /// SOC 2 trust services criteria the finding is evidence for, e.g. `CC6.1`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub soc2: Vec<String>,
+}
+
+impl ComplianceMapping {
+    fn new(cwe: &[u32], owasp: &[&str], soc2: &[&str]) -> Self {
+        Self {
+            cwe: cwe.to_vec(),
+            owasp: owasp.iter().map(|id| id.to_string()).collect(),
+            soc2: soc2.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cwe.is_empty() && self.owasp.is_empty() && self.soc2.is_empty()
+    }
+
+    /// The controls as labels such as `CWE-798`, `OWASP A07:2021` and `SOC2 CC6.1`
+    pub fn controls(&self) -> Vec<String> {
+        self.cwe
+            .iter()
+            .map(|id| format!("CWE-{}", id))
+            .chain(self.owasp.iter().map(|id| format!("OWASP {}", id)))
+            .chain(self.soc2.iter().map(|id| format!("SOC2 {}", id)))
+            .collect()
+    }
+}
+
+/// The built-in controls of `pattern`, for security rules
+pub fn builtin_compliance(pattern: &str) -> Option<ComplianceMapping> {
+    let (cwe, owasp, soc2): (&[u32], &[&str], &[&str]) = match pattern {
+        pattern if SECRET_PATTERNS.contains(&pattern) || pattern == "HARDCODED_PASSWORD" => {
+            (&[798], &["A07:2021"], &["CC6.1"])
+        }
+        "UNSAFE_EVAL" => (&[95], &["A03:2021"], &["CC7.1"]),
+        "LLM_SQL_INJECTION" => (&[89], &["A03:2021"], &["CC7.1"]),
+        "LLM_XSS_INJECTION" => (&[79], &["A03:2021"], &["CC7.1"]),
+        "C_SYSTEM_CALL" => (&[78], &["A03:2021"], &["CC7.1"]),
+        "C_UNSAFE_STRING_FUNCTION" => (&[120], &[], &["CC7.1"]),
+        "INSECURE_DESERIALIZATION" => (&[502], &["A08:2021"], &["CC7.1"]),
+        "SHELL_CURL_PIPE" => (&[494], &["A08:2021"], &["CC8.1"]),
+        "WEAK_HASH" => (&[328], &["A02:2021"], &["CC6.1"]),
+        "HARDCODED_IV" => (&[329], &["A02:2021"], &["CC6.1"]),
+        "INSECURE_RANDOM" | "LLM_INSECURE_RANDOM" => (&[338], &["A02:2021"], &["CC6.1"]),
+        "TIMING_UNSAFE_COMPARISON" => (&[208], &["A02:2021"], &["CC6.1"]),
+        "LLM_CRYPTO_ANTIPATTERN" => (&[327], &["A02:2021"], &["CC6.1"]),
+        "LLM_FILESYSTEM_SECURITY" => (&[22], &["A01:2021"], &["CC6.1"]),
+        "TF_OPEN_SECURITY_GROUP" => (&[284], &["A01:2021"], &["CC6.6"]),
+        "DOCKER_ROOT_USER" | "COMPOSE_PRIVILEGED" => (&[250], &["A05:2021"], &["CC6.1"]),
+        "PII_EMAIL" | "PII_PHONE" | "PII_CREDIT_CARD" | "PII_NATIONAL_ID" => {
+            (&[359], &[], &["C1.1"])
+        }
+        _ => return None,
+    };
+    Some(ComplianceMapping::new(cwe, owasp, soc2))
+}
+
+/// Name of a CWE weakness that built-in rules map to
+pub fn cwe_name(id: u32) -> Option<&'static str> {
+    Some(match id {
+        22 => "Improper Limitation of a Pathname to a Restricted Directory ('Path Traversal')",
+        78 => "Improper Neutralization of Special Elements used in an OS Command ('OS Command Injection')",
+        79 => "Improper Neutralization of Input During Web Page Generation ('Cross-site Scripting')",
+        89 => "Improper Neutralization of Special Elements used in an SQL Command ('SQL Injection')",
+        95 => "Improper Neutralization of Directives in Dynamically Evaluated Code ('Eval Injection')",
+        120 => "Buffer Copy without Checking Size of Input ('Classic Buffer Overflow')",
+        208 => "Observable Timing Discrepancy",
+        250 => "Execution with Unnecessary Privileges",
+        284 => "Improper Access Control",
+        327 => "Use of a Broken or Risky Cryptographic Algorithm",
+        328 => "Use of Weak Hash",
+        329 => "Generation of Predictable IV with CBC Mode",
+        338 => "Use of Cryptographically Weak Pseudo-Random Number Generator (PRNG)",
+        359 => "Exposure of Private Personal Information to an Unauthorized Actor",
+        494 => "Download of Code Without Integrity Check",
+        502 => "Deserialization of Untrusted Data",
+        798 => "Use of Hard-coded Credentials",
+        _ => return None,
+    })
+}
+
+/// Name of an OWASP Top 10 2021 category
+pub fn owasp_name(id: &str) -> Option<&'static str> {
+    Some(match id {
+        "A01:2021" => "Broken Access Control",
+        "A02:2021" => "Cryptographic Failures",
+        "A03:2021" => "Injection",
+        "A04:2021" => "Insecure Design",
+        "A05:2021" => "Security Misconfiguration",
+        "A06:2021" => "Vulnerable and Outdated Components",
+        "A07:2021" => "Identification and Authentication Failures",
+        "A08:2021" => "Software and Data Integrity Failures",
+        "A09:2021" => "Security Logging and Monitoring Failures",
+        "A10:2021" => "Server-Side Request Forgery (SSRF)",
+        _ => return None,
+    })
+}
+
+/// True for OWASP Top 10 category IDs such as `A07:2021`
+pub fn is_owasp_category(id: &str) -> bool {
+    let bytes = id.as_bytes();
+    bytes.len() == 8
+        && bytes[0] == b'A'
+        && bytes[1..3].iter().all(u8::is_ascii_digit)
+        && bytes[3] == b':'
+        && bytes[4..].iter().all(u8::is_ascii_digit)
+}
+
+/// Mappings of the `compliance` config section, see [`set_compliance_mappings`]
+static MAPPINGS: RwLock<BTreeMap<String, ComplianceMapping>> = RwLock::new(BTreeMap::new());
+
+/// Use `mappings` from the config on top of the built-in ones from now on.
+/// An empty mapping removes the built-in one of its rule. Rule names match
+/// case-insensitively, as the config loader lowercases keys.
+pub fn set_compliance_mappings(mappings: BTreeMap<String, ComplianceMapping>) {
+    *MAPPINGS.write().unwrap_or_else(|e| e.into_inner()) = mappings
+        .into_iter()
+        .map(|(pattern, mapping)| (pattern.to_uppercase(), mapping))
+        .collect();
+}
+
+/// The controls of `pattern`: its configured mapping, or else its built-in one
+pub fn compliance_of(pattern: &str) -> Option<ComplianceMapping> {
+    let configured = MAPPINGS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&pattern.to_uppercase())
+        .cloned();
+    match configured {
+        Some(mapping) if mapping.is_empty() => None,
+        Some(mapping) => Some(mapping),
+        None => builtin_compliance(pattern),
+    }
+}
+
+/// Findings of one control, a row of `report --by-compliance`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ControlSummary {
+    /// Label of the control, see [`ComplianceMapping::controls`]
+    pub control: String,
+    pub findings: usize,
+    /// Patterns of the findings
+    pub rules: BTreeSet<String>,
+}
+
+/// Findings of `matches` per control, most findings first, and the number
+/// of findings that map to no control
+pub fn summarize_by_control(matches: &[Match]) -> (Vec<ControlSummary>, usize) {
+    let mut controls: BTreeMap<String, ControlSummary> = BTreeMap::new();
+    let mut unmapped = 0;
+    for m in matches {
+        let Some(mapping) = compliance_of(&m.pattern) else {
+            unmapped += 1;
+            continue;
+        };
+        for control in mapping.controls() {
+            let summary = controls
+                .entry(control.clone())
+                .or_insert_with(|| ControlSummary {
+                    control,
+                    findings: 0,
+                    rules: BTreeSet::new(),
+                });
+            summary.findings += 1;
+            summary.rules.insert(m.pattern.to_string());
+        }
+    }
+    let mut summaries: Vec<ControlSummary> = controls.into_values().collect();
+    summaries.sort_by_key(|summary| std::cmp::Reverse(summary.findings));
+    (summaries, unmapped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn m(pattern: &str) -> Match {
+        Match::new("Dockerfile", 1, pattern, "")
+    }
+
+    #[test]
+    fn test_builtin_compliance() {
+        let mapping = builtin_compliance("TF_HARDCODED_CREDENTIALS").unwrap();
+        assert_eq!(
+            mapping.controls(),
+            ["CWE-798", "OWASP A07:2021", "SOC2 CC6.1"]
+        );
+        assert_eq!(builtin_compliance("UNSAFE_EVAL").unwrap().cwe, [95]);
+        assert_eq!(builtin_compliance("TODO"), None);
+        assert!(is_owasp_category("A07:2021"));
+        for mapping in ["UNSAFE_EVAL", "WEAK_HASH", "PII_EMAIL", "DOCKER_ROOT_USER"]
+            .iter()
+            .filter_map(|pattern| builtin_compliance(pattern))
+        {
+            assert!(mapping.cwe.iter().all(|id| cwe_name(*id).is_some()));
+            assert!(mapping.owasp.iter().all(|id| owasp_name(id).is_some()));
+        }
+        assert!(!is_owasp_category("A7:2021"));
+    }
+
+    #[test]
+    fn test_summarize_by_control() {
+        let matches = [
+            m("DOCKER_ENV_SECRET"),
+            m("SHELL_EXPORTED_SECRET"),
+            m("UNSAFE_EVAL"),
+            m("TODO"),
+        ];
+        let (summaries, unmapped) = summarize_by_control(&matches);
+        assert_eq!(unmapped, 1);
+        assert_eq!(summaries[0].control, "CWE-798");
+        assert_eq!(summaries[0].findings, 2);
+        assert_eq!(
+            summaries[0].rules.iter().collect::<Vec<_>>(),
+            ["DOCKER_ENV_SECRET", "SHELL_EXPORTED_SECRET"]
+        );
+        assert!(summaries
+            .iter()
+            .any(|s| s.control == "OWASP A03:2021" && s.findings == 1));
+    }
+}
@@ -1,15 +1,140 @@
+use crate::compliance::ComplianceMapping;
+use crate::errors::{ScanError, ScanResult};
+use crate::feature_flags::FeatureFlagConfig;
+use crate::i18n_detectors::I18nConfig;
+use crate::jira::JiraConfig;
+use crate::migration_detectors::MigrationConfig;
+use crate::output_style::ThemeConfig;
+use crate::pii_detectors::PiiConfig;
+use crate::quality_detectors::{LengthLimitsConfig, MagicNumberConfig};
+use crate::rule_scopes::RuleScope;
+use crate::scan_targets::ScanTarget;
+use crate::triggers::FindingTrigger;
+use crate::{CacheKey, Severity};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Prefix for environment variable overrides, e.g. `CODE_GUARDIAN_MAX_THREADS=8`
+pub const ENV_PREFIX: &str = "CODE_GUARDIAN";
+
+/// Config keys holding lists, parsed from comma-separated environment variables
+const LIST_KEYS: &[&str] = &[
+    "scan_patterns",
+    "output_formats",
+    "generated_globs",
+    "excluded_dirs",
+    "test_path_globs",
+    "production_path_globs",
+    "webhooks",
+    "worker_endpoints",
+];
+
+/// Config keys whose values are never printed
+pub const SECRET_KEYS: &[&str] = &["webhook_secret", "llm_triage_api_key"];
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
 pub struct Config {
     pub scan_patterns: Vec<String>,
     pub output_formats: Vec<String>,
     pub database_path: String,
     pub max_threads: usize,
     pub cache_size: usize,
+    /// `content` keys the scan cache on a hash of each file and persists it
+    /// under `.code-guardian/cache` in the scan root, so restored CI
+    /// workspaces with fresh timestamps still hit; `mtime` keeps it in memory
+    pub cache_key: CacheKey,
+    /// Size limit of the persisted scan cache in MiB; least recently used entries are evicted
+    pub cache_max_mb: usize,
+    /// Directory of the persisted scan cache instead of `.code-guardian/cache`
+    /// in the scan root; checkouts and worktrees pointing at the same one
+    /// reuse each other's results for identical files
+    pub cache_dir: Option<String>,
     pub batch_size: usize,
     pub max_file_size: usize,
+    /// Maximum findings kept per file; the rest are summarized by a TRUNCATED marker
+    pub max_matches_per_file: Option<usize>,
+    /// Maximum findings kept per pattern within a file
+    pub max_matches_per_pattern: Option<usize>,
+    /// When a scan has more findings than this, Medium and Low findings are
+    /// sampled per file; Critical and High findings are always kept
+    pub sample_above: Option<usize>,
+    /// Medium and Low findings kept per file when sampling
+    pub sample_per_file: usize,
+    /// Extra globs for generated or vendored files, skipped unless `--include-generated` is passed
+    pub generated_globs: Vec<String>,
+    /// Directory names skipped wherever they appear, on top of build and
+    /// dependency directories such as `target`, `node_modules` and `.venv`
+    pub excluded_dirs: Vec<String>,
+    /// Globs, relative to the scan root, of test files the TEST check skips;
+    /// empty uses the built-in per-language globs such as `**/*_test.go`
+    pub test_path_globs: Vec<String>,
+    /// Globs, relative to the scan root, of the production code the TEST
+    /// check is limited to; empty checks every file that is not a test
+    pub production_path_globs: Vec<String>,
+    /// Include and exclude globs, relative to the scan root, limiting where
+    /// a rule applies, keyed by pattern name such as `CONSOLE_LOG`
+    pub rule_paths: BTreeMap<String, RuleScope>,
+    /// Severities replacing the built-in ones of rules, keyed by pattern name
+    /// such as `CONSOLE_LOG`
+    pub severity_overrides: BTreeMap<String, Severity>,
+    /// Named directories scanned on their own with `scan --target <name>`
+    /// or `scan --all-targets`, each stored as a separate scan
+    pub targets: BTreeMap<String, ScanTarget>,
+    /// CWE, OWASP Top 10 and SOC 2 controls of rules, keyed by pattern name,
+    /// replacing the built-in mappings of security rules; an empty section
+    /// removes a rule's mapping
+    pub compliance: BTreeMap<String, ComplianceMapping>,
+    /// Jira site, project and labels of the issues `jira create` files for findings
+    pub jira: JiraConfig,
+    /// Settings of the MAGIC_NUMBER check of the `quality` profile
+    pub magic_numbers: MagicNumberConfig,
+    /// Line limits of the FILE_TOO_LONG and FUNCTION_TOO_LONG checks
+    pub length_limits: LengthLimitsConfig,
+    /// Flag registry and lookup patterns of the STALE_FEATURE_FLAG check
+    pub feature_flags: FeatureFlagConfig,
+    /// Settings of the optional check for personal data, off by default
+    pub pii: PiiConfig,
+    /// Settings of the optional check for untranslated UI strings, off by default
+    pub i18n: I18nConfig,
+    /// Which SQL files are migrations, and which tables are large, for the
+    /// MIGRATION_* checks
+    pub migrations: MigrationConfig,
+    /// Emoji and colors of the terminal output
+    pub theme: ThemeConfig,
+    /// Descend into symlinked directories, walking each directory once
+    pub follow_symlinks: bool,
+    /// Maximum directory depth below the scan root
+    pub max_depth: Option<usize>,
+    /// Skip files ignored by the repository's `.gitignore`, `.git/info/exclude` and `.ignore` files
+    pub respect_gitignore: bool,
+    /// Skip files ignored by the user's global git excludes file
+    pub respect_global_ignore: bool,
+    /// Scan hidden files and directories; `.github` and `.gitlab-ci.yml` are always scanned
+    pub hidden: bool,
+    /// URLs notified with a JSON payload when a scan completes
+    pub webhooks: Vec<String>,
+    /// Actions run after a scan that found given patterns or severities,
+    /// e.g. a deeper scan of the affected files or quarantining the scan
+    pub triggers: Vec<FindingTrigger>,
+    /// Key for the HMAC-SHA256 signature of webhook payloads; prefer setting
+    /// `CODE_GUARDIAN_WEBHOOK_SECRET` over storing it in the config file
+    pub webhook_secret: Option<String>,
+    /// Endpoint asked to classify low-confidence LLM findings when scanning with `--llm-triage`
+    pub llm_triage_endpoint: Option<String>,
+    /// Bearer token for the triage endpoint; prefer `CODE_GUARDIAN_LLM_TRIAGE_API_KEY`
+    pub llm_triage_api_key: Option<String>,
+    /// Findings whose baseline confidence is at or below this (0.0-1.0) are triaged
+    pub llm_triage_threshold: f64,
+    /// Base URLs of the workers `distributed scan` hands work to
+    pub worker_endpoints: Vec<String>,
+    /// DNS SRV record listing distributed scan workers, resolved with `dig`
+    pub worker_srv_record: Option<String>,
+    /// JSONL file each command appends an audit record to: who ran it, its
+    /// arguments, a hash of the configuration and the scans' statistics
+    pub audit_log: Option<String>,
 }
 
 impl Default for Config {
@@ -20,13 +145,70 @@ impl Default for Config {
             database_path: "data/code-guardian.db".to_string(),
             max_threads: num_cpus::get(),
             cache_size: 50000,
+            cache_key: CacheKey::Mtime,
+            cache_max_mb: 64,
+            cache_dir: None,
             batch_size: 100,
             max_file_size: 10 * 1024 * 1024, // 10MB
+            max_matches_per_file: None,
+            max_matches_per_pattern: None,
+            sample_above: None,
+            sample_per_file: 10,
+            generated_globs: Vec::new(),
+            excluded_dirs: Vec::new(),
+            test_path_globs: Vec::new(),
+            production_path_globs: Vec::new(),
+            rule_paths: BTreeMap::new(),
+            severity_overrides: BTreeMap::new(),
+            targets: BTreeMap::new(),
+            compliance: BTreeMap::new(),
+            jira: JiraConfig::default(),
+            magic_numbers: MagicNumberConfig::default(),
+            length_limits: LengthLimitsConfig::default(),
+            feature_flags: FeatureFlagConfig::default(),
+            pii: PiiConfig::default(),
+            i18n: I18nConfig::default(),
+            migrations: MigrationConfig::default(),
+            theme: ThemeConfig::default(),
+            follow_symlinks: false,
+            max_depth: None,
+            respect_gitignore: true,
+            respect_global_ignore: true,
+            hidden: false,
+            webhooks: Vec::new(),
+            triggers: Vec::new(),
+            webhook_secret: None,
+            llm_triage_endpoint: None,
+            llm_triage_api_key: None,
+            llm_triage_threshold: 0.5,
+            worker_endpoints: Vec::new(),
+            worker_srv_record: None,
+            audit_log: None,
         }
     }
 }
 
-pub fn load_config<P: AsRef<Path>>(path: Option<P>) -> anyhow::Result<Config> {
+/// Load configuration from defaults, an optional file and `CODE_GUARDIAN_*` environment variables
+pub fn load_config<P: AsRef<Path>>(path: Option<P>) -> ScanResult<Config> {
+    load_config_with_env(path, std::env::vars().collect())
+}
+
+/// Load configuration like [`load_config`], reading overrides from `env` instead of the process environment
+pub fn load_config_with_env<P: AsRef<Path>>(
+    path: Option<P>,
+    env: HashMap<String, String>,
+) -> ScanResult<Config> {
+    let file_values = match path {
+        Some(path) if path.as_ref().exists() => Some(read_config_file(path.as_ref(), &env)?),
+        _ => None,
+    };
+    build_config(file_values, env).map_err(ScanError::config)
+}
+
+fn build_config(
+    file_values: Option<serde_json::Map<String, serde_json::Value>>,
+    env: HashMap<String, String>,
+) -> Result<Config, config::ConfigError> {
     let mut builder = config::Config::builder();
 
     // Add default values
@@ -35,34 +217,139 @@ pub fn load_config<P: AsRef<Path>>(path: Option<P>) -> anyhow::Result<Config> {
     builder = builder.set_default("database_path", "data/code-guardian.db")?;
     builder = builder.set_default("max_threads", num_cpus::get() as i64)?;
     builder = builder.set_default("cache_size", 50000i64)?;
+    builder = builder.set_default("cache_key", "mtime")?;
+    builder = builder.set_default("cache_max_mb", 64i64)?;
     builder = builder.set_default("batch_size", 100i64)?;
     builder = builder.set_default("max_file_size", (10 * 1024 * 1024) as i64)?;
+    builder = builder.set_default("sample_per_file", 10i64)?;
+    builder = builder.set_default("llm_triage_threshold", 0.5)?;
 
     // Add file source if provided
-    if let Some(path) = path {
-        let path = path.as_ref();
-        if path.exists() {
-            let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
-            match extension {
-                "toml" => {
-                    builder = builder.add_source(config::File::with_name(path.to_str().unwrap()));
-                }
-                "json" => {
-                    builder = builder.add_source(config::File::with_name(path.to_str().unwrap()));
-                }
-                _ => {
-                    return Err(anyhow::anyhow!(
-                        "Unsupported config file format: {}",
-                        extension
+    if let Some(values) = file_values {
+        builder = builder.add_source(config::File::from_str(
+            &serde_json::Value::Object(values).to_string(),
+            config::FileFormat::Json,
+        ));
+    }
+
+    // Environment variables take precedence over the file
+    let mut environment = config::Environment::with_prefix(ENV_PREFIX)
+        .try_parsing(true)
+        .list_separator(",")
+        .source(Some(env));
+    for key in LIST_KEYS {
+        environment = environment.with_list_parse_key(key);
+    }
+    builder = builder.add_source(environment);
+
+    builder.build()?.try_deserialize()
+}
+
+/// Read a TOML or JSON config file into its top-level settings, expanding
+/// `${VAR}` references in string values from `env`
+pub fn read_config_file(
+    path: &Path,
+    env: &HashMap<String, String>,
+) -> ScanResult<serde_json::Map<String, serde_json::Value>> {
+    let content = std::fs::read_to_string(path).map_err(|e| ScanError::io(path, e))?;
+    let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+    let invalid =
+        |e: &dyn std::fmt::Display| ScanError::config(format!("{}: {}", path.display(), e));
+    let value: serde_json::Value = match extension {
+        "toml" => toml::from_str::<toml::Value>(&content)
+            .map_err(|e| invalid(&e))
+            .and_then(|value| serde_json::to_value(value).map_err(|e| invalid(&e)))?,
+        "json" => serde_json::from_str(&content).map_err(|e| invalid(&e))?,
+        _ => {
+            return Err(ScanError::config(format!(
+                "Unsupported config file format: {}",
+                extension
+            )))
+        }
+    };
+    let serde_json::Value::Object(mut values) = value else {
+        return Err(invalid(&"top level must be a table of settings"));
+    };
+
+    for (key, value) in values.iter_mut() {
+        interpolate_value(value, env).map_err(|e| {
+            ScanError::config(format!(
+                "Invalid value for '{}' in {}: {}",
+                key,
+                path.display(),
+                e
+            ))
+        })?;
+    }
+    Ok(values)
+}
+
+fn interpolate_value(
+    value: &mut serde_json::Value,
+    env: &HashMap<String, String>,
+) -> Result<(), String> {
+    match value {
+        serde_json::Value::String(s) => *s = interpolate(s, env)?,
+        serde_json::Value::Array(items) => {
+            for item in items {
+                interpolate_value(item, env)?;
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for item in map.values_mut() {
+                interpolate_value(item, env)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Expand `${VAR}` and `${VAR:-default}` references using `env`.
+/// `$$` produces a literal `$`; a reference to an unset variable without a default is an error.
+pub fn interpolate_env(input: &str, env: &HashMap<String, String>) -> ScanResult<String> {
+    interpolate(input, env).map_err(ScanError::config)
+}
+
+fn interpolate(input: &str, env: &HashMap<String, String>) -> Result<String, String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(pos) = rest.find('$') {
+        output.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        if let Some(stripped) = after.strip_prefix('$') {
+            output.push('$');
+            rest = stripped;
+        } else if let Some(reference) = after.strip_prefix('{') {
+            let end = reference
+                .find('}')
+                .ok_or_else(|| format!("unterminated '${{' in \"{}\"", input))?;
+            let (name, default) = match reference[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (&reference[..end], None),
+            };
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(format!("invalid variable name '{}'", name));
+            }
+            match (env.get(name), default) {
+                (Some(value), _) => output.push_str(value),
+                (None, Some(default)) => output.push_str(default),
+                (None, None) => {
+                    return Err(format!(
+                        "environment variable '{}' is not set (use ${{{}:-default}} to provide a fallback)",
+                        name,
+                        name
                     ))
                 }
             }
+            rest = &reference[end + 1..];
+        } else {
+            output.push('$');
+            rest = after;
         }
     }
-
-    let config = builder.build()?;
-    let parsed: Config = config.try_deserialize()?;
-    Ok(parsed)
+    output.push_str(rest);
+    Ok(output)
 }
 
 #[cfg(test)]
@@ -95,6 +382,10 @@ max_threads = 4
 cache_size = 100000
 batch_size = 200
 max_file_size = 20971520
+
+[rule_paths.CONSOLE_LOG]
+include = ["src/**"]
+exclude = ["examples/**"]
 "#;
         fs::write(&config_path, toml_content).unwrap();
 
@@ -106,6 +397,10 @@ max_file_size = 20971520
         assert_eq!(config.cache_size, 100_000);
         assert_eq!(config.batch_size, 200);
         assert_eq!(config.max_file_size, 20_971_520);
+        // Rule names are matched case-insensitively, as the config loader lowercases keys
+        let scopes = crate::rule_scopes::RuleScopes::new(&config.rule_paths).unwrap();
+        assert!(scopes.allows("CONSOLE_LOG", Path::new("src/app.js")));
+        assert!(!scopes.allows("CONSOLE_LOG", Path::new("examples/demo.js")));
     }
 
     #[test]
@@ -146,6 +441,77 @@ max_file_size = 20971520
         assert_eq!(config.max_file_size, default.max_file_size);
     }
 
+    #[test]
+    fn test_load_config_env_overrides_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        fs::write(&config_path, "max_threads = 4\nbatch_size = 10\n").unwrap();
+
+        let env = HashMap::from([
+            ("CODE_GUARDIAN_MAX_THREADS".to_string(), "8".to_string()),
+            (
+                "CODE_GUARDIAN_SCAN_PATTERNS".to_string(),
+                "*.go,*.py".to_string(),
+            ),
+        ]);
+        let config = load_config_with_env(Some(&config_path), env).unwrap();
+        assert_eq!(config.max_threads, 8);
+        assert_eq!(config.batch_size, 10);
+        assert_eq!(config.scan_patterns, vec!["*.go", "*.py"]);
+    }
+
+    #[test]
+    fn test_interpolate_env() {
+        let env = HashMap::from([
+            ("HOME".to_string(), "/home/ci".to_string()),
+            ("EMPTY".to_string(), String::new()),
+        ]);
+        assert_eq!(
+            interpolate_env("${HOME}/cg.db", &env).unwrap(),
+            "/home/ci/cg.db"
+        );
+        assert_eq!(
+            interpolate_env("${MISSING:-/tmp}/cg.db", &env).unwrap(),
+            "/tmp/cg.db"
+        );
+        assert_eq!(interpolate_env("x${EMPTY}y", &env).unwrap(), "xy");
+        assert_eq!(
+            interpolate_env("cost $5 or $$HOME", &env).unwrap(),
+            "cost $5 or $HOME"
+        );
+
+        let err = interpolate_env("${MISSING}", &env).unwrap_err().to_string();
+        assert!(err.contains("'MISSING' is not set"));
+        assert!(interpolate_env("${HOME", &env).is_err());
+        assert!(interpolate_env("${BAD-NAME}", &env).is_err());
+    }
+
+    #[test]
+    fn test_load_config_interpolates_file_values() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            "database_path = \"${CI_PROJECT_DIR}/cg.db\"\nscan_patterns = [\"*.${EXT:-rs}\"]\n",
+        )
+        .unwrap();
+
+        let env = HashMap::from([("CI_PROJECT_DIR".to_string(), "/builds/app".to_string())]);
+        let config = load_config_with_env(Some(&config_path), env).unwrap();
+        assert_eq!(config.database_path, "/builds/app/cg.db");
+        assert_eq!(config.scan_patterns, vec!["*.rs"]);
+
+        let err = load_config_with_env(Some(&config_path), HashMap::new()).unwrap_err();
+        assert!(matches!(err, ScanError::Config { .. }));
+        let message = err.to_string();
+        assert!(message.contains("'database_path'"), "{}", message);
+        assert!(
+            message.contains("'CI_PROJECT_DIR' is not set"),
+            "{}",
+            message
+        );
+    }
+
     #[test]
     fn test_load_config_unsupported_format() {
         let temp_dir = TempDir::new().unwrap();
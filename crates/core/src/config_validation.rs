@@ -0,0 +1,684 @@
+use crate::compliance::is_owasp_category;
+use crate::config::{load_config_with_env, read_config_file, Config, ENV_PREFIX, SECRET_KEYS};
+use crate::custom_detectors::{parse_custom_detector_file, CustomDetector};
+use crate::detectors::build_glob_set;
+use crate::errors::ScanError;
+use crate::excluded_dirs::invalid_excluded_dir;
+use crate::feature_flags::StaleFeatureFlagDetector;
+use crate::generated::GeneratedFileFilter;
+use crate::migration_detectors::MigrationRiskDetector;
+use crate::regex_safety::analyze_pattern;
+use crate::rule_scopes::RuleScopes;
+use crate::triggers::validate_triggers;
+use crate::webhook::validate_webhook_url;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Shown instead of the values of [`SECRET_KEYS`]
+const REDACTED: &str = "********";
+
+/// Where an effective configuration value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueSource {
+    /// Built-in default
+    Default,
+    /// Configuration file
+    File(PathBuf),
+    /// Environment variable, by name
+    Env(String),
+    /// Command-line flag, by name
+    Cli(String),
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueSource::Default => write!(f, "default"),
+            ValueSource::File(path) => write!(f, "file {}", path.display()),
+            ValueSource::Env(name) => write!(f, "env {}", name),
+            ValueSource::Cli(flag) => write!(f, "cli {}", flag),
+        }
+    }
+}
+
+/// Severity of a configuration diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticLevel {
+    Error,
+    Warning,
+}
+
+/// A problem found while validating configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigDiagnostic {
+    pub level: DiagnosticLevel,
+    pub message: String,
+}
+
+impl ConfigDiagnostic {
+    fn error(message: String) -> Self {
+        Self {
+            level: DiagnosticLevel::Error,
+            message,
+        }
+    }
+
+    fn warning(message: String) -> Self {
+        Self {
+            level: DiagnosticLevel::Warning,
+            message,
+        }
+    }
+}
+
+/// A command-line override of a config key, e.g. `--max-threads 4` for `max_threads`
+#[derive(Debug, Clone)]
+pub struct CliOverride {
+    pub key: &'static str,
+    pub flag: &'static str,
+    pub value: Value,
+}
+
+/// Result of validating the merged configuration
+#[derive(Debug, Clone)]
+pub struct ConfigValidation {
+    /// Effective configuration, if it could be resolved
+    pub config: Option<Config>,
+    /// Source of each effective value, by config key
+    pub provenance: BTreeMap<String, ValueSource>,
+    pub diagnostics: Vec<ConfigDiagnostic>,
+}
+
+impl ConfigValidation {
+    /// Returns true if any diagnostic is an error
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.level == DiagnosticLevel::Error)
+    }
+
+    /// Effective values as `(key, value, source)`, sorted by key
+    pub fn effective_values(&self) -> Vec<(String, Value, &ValueSource)> {
+        let Some(config) = &self.config else {
+            return Vec::new();
+        };
+        let values = config_to_map(config);
+        self.provenance
+            .iter()
+            .filter_map(|(key, source)| {
+                let value = values.get(key)?;
+                let value = if SECRET_KEYS.contains(&key.as_str()) && !value.is_null() {
+                    Value::String(REDACTED.to_string())
+                } else {
+                    value.clone()
+                };
+                Some((key.clone(), value, source))
+            })
+            .collect()
+    }
+}
+
+/// Validate configuration merged from defaults, a config file, `CODE_GUARDIAN_*`
+/// environment variables and command-line overrides (in increasing precedence),
+/// plus an optional custom detector file.
+pub fn validate_config(
+    config_path: Option<&Path>,
+    env: &HashMap<String, String>,
+    cli_overrides: &[CliOverride],
+    custom_detectors_path: Option<&Path>,
+) -> ConfigValidation {
+    let mut diagnostics = Vec::new();
+    let known_keys: Vec<String> = config_to_map(&Config::default()).keys().cloned().collect();
+
+    // Layer 1: config file
+    let mut file_values = Map::new();
+    if let Some(path) = config_path {
+        if !path.exists() {
+            diagnostics.push(ConfigDiagnostic::warning(format!(
+                "Config file {} does not exist; using defaults",
+                path.display()
+            )));
+        } else {
+            match read_config_file(path, env) {
+                Ok(values) => file_values = values,
+                Err(e) => diagnostics.push(ConfigDiagnostic::error(format!(
+                    "Cannot parse {}: {}",
+                    path.display(),
+                    e
+                ))),
+            }
+        }
+    }
+    for key in file_values.keys() {
+        if !known_keys.contains(key) {
+            diagnostics.push(ConfigDiagnostic::warning(format!(
+                "Unknown key '{}' in {}{}",
+                key,
+                config_path
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default(),
+                suggestion(key, &known_keys)
+            )));
+        }
+    }
+
+    // Layer 2: environment
+    let env_prefix = format!("{}_", ENV_PREFIX);
+    let mut env_values: BTreeMap<String, (String, String)> = BTreeMap::new();
+    for (name, value) in env {
+        let Some(key) = name.strip_prefix(&env_prefix) else {
+            continue;
+        };
+        let key = key.to_lowercase();
+        if known_keys.contains(&key) {
+            env_values.insert(key, (name.clone(), value.clone()));
+        } else {
+            diagnostics.push(ConfigDiagnostic::warning(format!(
+                "Unknown environment variable {}{}",
+                name,
+                suggestion(&key, &known_keys)
+            )));
+        }
+    }
+
+    // Provenance and conflicts across layers
+    let mut provenance = BTreeMap::new();
+    for key in &known_keys {
+        let mut settings: Vec<(ValueSource, String)> = Vec::new();
+        if let (Some(value), Some(path)) = (file_values.get(key), config_path) {
+            settings.push((ValueSource::File(path.to_path_buf()), display_value(value)));
+        }
+        if let Some((name, value)) = env_values.get(key) {
+            settings.push((ValueSource::Env(name.clone()), value.clone()));
+        }
+        for cli in cli_overrides.iter().filter(|cli| cli.key == key) {
+            settings.push((
+                ValueSource::Cli(cli.flag.to_string()),
+                display_value(&cli.value),
+            ));
+        }
+
+        for pair in settings.windows(2) {
+            let ((earlier, earlier_value), (later, later_value)) = (&pair[0], &pair[1]);
+            if earlier_value != later_value {
+                let (earlier_value, later_value) = if SECRET_KEYS.contains(&key.as_str()) {
+                    (REDACTED, REDACTED)
+                } else {
+                    (earlier_value.as_str(), later_value.as_str())
+                };
+                diagnostics.push(ConfigDiagnostic::warning(format!(
+                    "'{}' = {} from {} is overridden by {} from {}",
+                    key, earlier_value, earlier, later_value, later
+                )));
+            }
+        }
+
+        let source = settings
+            .pop()
+            .map(|(source, _)| source)
+            .unwrap_or(ValueSource::Default);
+        provenance.insert(key.clone(), source);
+    }
+
+    // Resolve the effective config the same way a scan does
+    let config = match load_config_with_env(config_path, env.clone()) {
+        Ok(config) => apply_cli_overrides(config, cli_overrides, &mut diagnostics),
+        Err(e) => {
+            diagnostics.push(ConfigDiagnostic::error(e.to_string()));
+            None
+        }
+    };
+
+    if let Some(config) = &config {
+        for (key, value) in [
+            ("max_threads", config.max_threads),
+            ("batch_size", config.batch_size),
+            ("max_file_size", config.max_file_size),
+            ("sample_per_file", config.sample_per_file),
+            ("cache_max_mb", config.cache_max_mb),
+        ] {
+            if value == 0 {
+                diagnostics.push(ConfigDiagnostic::error(format!(
+                    "'{}' must be greater than 0",
+                    key
+                )));
+            }
+        }
+        for (key, value) in [
+            ("max_matches_per_file", config.max_matches_per_file),
+            ("max_matches_per_pattern", config.max_matches_per_pattern),
+        ] {
+            if value == Some(0) {
+                diagnostics.push(ConfigDiagnostic::error(format!(
+                    "'{}' must be greater than 0 (omit it for no limit)",
+                    key
+                )));
+            }
+        }
+        if let Err(e) = GeneratedFileFilter::new(&config.generated_globs) {
+            diagnostics.push(ConfigDiagnostic::error(format!(
+                "Invalid glob in 'generated_globs': {}",
+                e
+            )));
+        }
+        for name in &config.excluded_dirs {
+            if let Some(reason) = invalid_excluded_dir(name) {
+                diagnostics.push(ConfigDiagnostic::error(format!(
+                    "'excluded_dirs' entry '{}' {}",
+                    name, reason
+                )));
+            }
+        }
+        for (key, globs) in [
+            ("test_path_globs", &config.test_path_globs),
+            ("production_path_globs", &config.production_path_globs),
+        ] {
+            if let Err(e) = build_glob_set(globs) {
+                diagnostics.push(ConfigDiagnostic::error(format!(
+                    "Invalid glob in '{}': {}",
+                    key, e
+                )));
+            }
+        }
+        if let Err(e) = RuleScopes::new(&config.rule_paths) {
+            diagnostics.push(ConfigDiagnostic::error(e.to_string()));
+        }
+        let config_dir = config_path
+            .and_then(Path::parent)
+            .unwrap_or_else(|| Path::new(""));
+        for (name, target) in &config.targets {
+            if target.path.as_os_str().is_empty() {
+                diagnostics.push(ConfigDiagnostic::error(format!(
+                    "'targets.{}.path' must not be empty",
+                    name
+                )));
+            } else if !target.root(config_dir).is_dir() {
+                diagnostics.push(ConfigDiagnostic::warning(format!(
+                    "'targets.{}.path' {} is not a directory",
+                    name,
+                    target.path.display()
+                )));
+            }
+        }
+        for (pattern, mapping) in &config.compliance {
+            for id in mapping.owasp.iter().filter(|id| !is_owasp_category(id)) {
+                diagnostics.push(ConfigDiagnostic::warning(format!(
+                    "'compliance.{}.owasp' entry '{}' is not an OWASP Top 10 category such as A07:2021",
+                    pattern, id
+                )));
+            }
+        }
+        if config.magic_numbers.min_value < 0.0 {
+            diagnostics.push(ConfigDiagnostic::error(
+                "'magic_numbers.min_value' must not be negative".to_string(),
+            ));
+        }
+        let limits = &config.length_limits;
+        let mut zero_limits = Vec::new();
+        if limits.file_lines == 0 {
+            zero_limits.push("file_lines".to_string());
+        }
+        if limits.function_lines == 0 {
+            zero_limits.push("function_lines".to_string());
+        }
+        for (ext, language) in &limits.languages {
+            if language.file_lines == Some(0) {
+                zero_limits.push(format!("languages.{}.file_lines", ext));
+            }
+            if language.function_lines == Some(0) {
+                zero_limits.push(format!("languages.{}.function_lines", ext));
+            }
+        }
+        for key in zero_limits {
+            diagnostics.push(ConfigDiagnostic::error(format!(
+                "'length_limits.{}' must be at least 1",
+                key
+            )));
+        }
+        if let Some(registry) = &config.feature_flags.registry {
+            if !registry.is_file() {
+                diagnostics.push(ConfigDiagnostic::error(format!(
+                    "'feature_flags.registry' {} does not exist",
+                    registry.display()
+                )));
+            }
+        }
+        if let Err(e) =
+            StaleFeatureFlagDetector::new(BTreeSet::new(), &config.feature_flags.patterns)
+        {
+            diagnostics.push(ConfigDiagnostic::error(format!(
+                "Invalid pattern in 'feature_flags.patterns': {}",
+                e
+            )));
+        }
+        if let Err(e) = MigrationRiskDetector::new(&config.migrations) {
+            diagnostics.push(ConfigDiagnostic::error(format!(
+                "Invalid glob in 'migrations.paths': {}",
+                e
+            )));
+        }
+        for url in &config.webhooks {
+            if validate_webhook_url(url).is_err() {
+                diagnostics.push(ConfigDiagnostic::error(format!(
+                    "Invalid URL in 'webhooks': '{}' must start with http:// or https://",
+                    url
+                )));
+            }
+        }
+        if let Err(e) = validate_triggers(&config.triggers) {
+            diagnostics.push(ConfigDiagnostic::error(e.to_string()));
+        }
+        for endpoint in &config.worker_endpoints {
+            if validate_webhook_url(endpoint).is_err() {
+                diagnostics.push(ConfigDiagnostic::error(format!(
+                    "Invalid URL in 'worker_endpoints': '{}' must start with http:// or https://",
+                    endpoint
+                )));
+            }
+        }
+        if let Some(endpoint) = &config.llm_triage_endpoint {
+            if validate_webhook_url(endpoint).is_err() {
+                diagnostics.push(ConfigDiagnostic::error(format!(
+                    "Invalid URL in 'llm_triage_endpoint': '{}' must start with http:// or https://",
+                    endpoint
+                )));
+            }
+        }
+        if !(0.0..=1.0).contains(&config.llm_triage_threshold) {
+            diagnostics.push(ConfigDiagnostic::error(
+                "'llm_triage_threshold' must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+    }
+
+    if let Some(path) = custom_detectors_path {
+        validate_custom_detectors(path, &mut diagnostics);
+    }
+
+    ConfigValidation {
+        config,
+        provenance,
+        diagnostics,
+    }
+}
+
+fn apply_cli_overrides(
+    config: Config,
+    cli_overrides: &[CliOverride],
+    diagnostics: &mut Vec<ConfigDiagnostic>,
+) -> Option<Config> {
+    let mut values = config_to_map(&config);
+    for cli in cli_overrides {
+        values.insert(cli.key.to_string(), cli.value.clone());
+    }
+    match serde_json::from_value(Value::Object(values)) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            diagnostics.push(ConfigDiagnostic::error(format!(
+                "Invalid command-line override: {}",
+                e
+            )));
+            None
+        }
+    }
+}
+
+fn validate_custom_detectors(path: &Path, diagnostics: &mut Vec<ConfigDiagnostic>) {
+    let configs = match parse_custom_detector_file(path) {
+        Ok(configs) => configs,
+        Err(e) => {
+            diagnostics.push(ConfigDiagnostic::error(format!(
+                "Cannot parse custom detectors {}: {}",
+                path.display(),
+                e
+            )));
+            return;
+        }
+    };
+
+    let mut names = HashSet::new();
+    for config in configs {
+        if !names.insert(config.name.clone()) {
+            diagnostics.push(ConfigDiagnostic::warning(format!(
+                "Custom detector '{}' is defined more than once; the last definition wins",
+                config.name
+            )));
+        }
+        for risk in analyze_pattern(&config.pattern).unwrap_or_default() {
+            diagnostics.push(ConfigDiagnostic::warning(format!(
+                "Custom detector '{}': {}",
+                config.name, risk
+            )));
+        }
+        let name = config.name.clone();
+        match CustomDetector::new(config) {
+            Ok(_) => {}
+            Err(ScanError::Detector { message, .. }) => diagnostics.push(ConfigDiagnostic::error(
+                format!("Custom detector '{}': {}", name, message),
+            )),
+            Err(e) => diagnostics.push(ConfigDiagnostic::error(format!(
+                "Custom detector '{}': {}",
+                name, e
+            ))),
+        }
+    }
+}
+
+fn config_to_map(config: &Config) -> Map<String, Value> {
+    match serde_json::to_value(config) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Render a value the way it would be written in an environment variable
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "unset".to_string(),
+        Value::Array(items) => items
+            .iter()
+            .map(display_value)
+            .collect::<Vec<_>>()
+            .join(","),
+        other => other.to_string(),
+    }
+}
+
+/// " (did you mean 'x'?)" for a known key that differs only by case, separators or a typo
+fn suggestion(key: &str, known_keys: &[String]) -> String {
+    let normalized = |s: &str| s.to_lowercase().replace(['-', '_'], "");
+    known_keys
+        .iter()
+        .find(|known| normalized(known) == normalized(key) || edit_distance(known, key) <= 2)
+        .map(|known| format!(" (did you mean '{}'?)", known))
+        .unwrap_or_default()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(current)
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn messages(validation: &ConfigValidation, level: DiagnosticLevel) -> Vec<&str> {
+        validation
+            .diagnostics
+            .iter()
+            .filter(|d| d.level == level)
+            .map(|d| d.message.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_defaults() {
+        let validation = validate_config(None, &HashMap::new(), &[], None);
+        assert!(validation.diagnostics.is_empty());
+        assert!(validation
+            .provenance
+            .values()
+            .all(|source| *source == ValueSource::Default));
+        assert_eq!(validation.effective_values().len(), 44);
+    }
+
+    #[test]
+    fn test_validate_provenance_and_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("code-guardian.toml");
+        fs::write(
+            &config_path,
+            "max_threads = 4\nbatch_size = 50\nmax_thread = 2\n",
+        )
+        .unwrap();
+        let env = HashMap::from([
+            ("CODE_GUARDIAN_MAX_THREADS".to_string(), "8".to_string()),
+            ("CODE_GUARDIAN_CACHESIZE".to_string(), "1".to_string()),
+            ("PATH".to_string(), "/usr/bin".to_string()),
+        ]);
+        let cli = [CliOverride {
+            key: "batch_size",
+            flag: "--batch-size",
+            value: Value::from(50),
+        }];
+
+        let validation = validate_config(Some(&config_path), &env, &cli, None);
+        assert!(!validation.has_errors());
+        let config = validation.config.as_ref().unwrap();
+        assert_eq!(config.max_threads, 8);
+        assert_eq!(config.batch_size, 50);
+        assert_eq!(
+            validation.provenance["max_threads"],
+            ValueSource::Env("CODE_GUARDIAN_MAX_THREADS".to_string())
+        );
+        assert_eq!(
+            validation.provenance["batch_size"],
+            ValueSource::Cli("--batch-size".to_string())
+        );
+        assert_eq!(validation.provenance["cache_size"], ValueSource::Default);
+
+        let warnings = messages(&validation, DiagnosticLevel::Warning);
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+        assert!(warnings
+            .iter()
+            .any(|w| w.contains("'max_thread'") && w.contains("did you mean 'max_threads'")));
+        assert!(warnings
+            .iter()
+            .any(|w| w.contains("CODE_GUARDIAN_CACHESIZE") && w.contains("'cache_size'")));
+        assert!(warnings
+            .iter()
+            .any(|w| w.starts_with("'max_threads' = 4 from file")));
+    }
+
+    #[test]
+    fn test_validate_webhooks_and_secret_redaction() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("code-guardian.toml");
+        fs::write(
+            &config_path,
+            "webhooks = [\"https://ci.example.com/hook\", \"ci.example.com\"]\nwebhook_secret = \"from-file\"\n",
+        )
+        .unwrap();
+        let env = HashMap::from([(
+            "CODE_GUARDIAN_WEBHOOK_SECRET".to_string(),
+            "from-env".to_string(),
+        )]);
+
+        let validation = validate_config(Some(&config_path), &env, &[], None);
+        let errors = messages(&validation, DiagnosticLevel::Error);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].contains("'ci.example.com' must start with http://"));
+
+        let (_, secret, _) = validation
+            .effective_values()
+            .into_iter()
+            .find(|(key, _, _)| key == "webhook_secret")
+            .unwrap();
+        assert_eq!(secret, Value::from(REDACTED));
+        assert!(validation
+            .diagnostics
+            .iter()
+            .all(|d| !d.message.contains("from-file") && !d.message.contains("from-env")));
+    }
+
+    #[test]
+    fn test_validate_invalid_values_and_detectors() {
+        let temp_dir = TempDir::new().unwrap();
+        let detectors_path = temp_dir.path().join("detectors.json");
+        fs::write(
+            &detectors_path,
+            r#"[
+  {"name": "BROKEN", "description": "", "pattern": "[unclosed", "file_extensions": [],
+   "case_sensitive": true, "multiline": false, "capture_groups": [], "severity": "High",
+   "category": "Security", "examples": [], "enabled": true}
+]"#,
+        )
+        .unwrap();
+        let cli = [CliOverride {
+            key: "max_threads",
+            flag: "--max-threads",
+            value: Value::from(0),
+        }];
+
+        let validation = validate_config(None, &HashMap::new(), &cli, Some(&detectors_path));
+        assert!(validation.has_errors());
+        let errors = messages(&validation, DiagnosticLevel::Error);
+        assert!(errors.iter().any(|e| e.contains("'max_threads' must be")));
+        assert!(errors
+            .iter()
+            .any(|e| e.starts_with("Custom detector 'BROKEN': Invalid regex")));
+    }
+
+    #[test]
+    fn test_validate_targets() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("services/api")).unwrap();
+        let config_path = temp_dir.path().join("code-guardian.toml");
+        fs::write(
+            &config_path,
+            "[targets.api]\npath = \"services/api\"\n[targets.web]\npath = \"apps/web\"\n[targets.empty]\nprofile = \"security\"\n",
+        )
+        .unwrap();
+
+        let validation = validate_config(Some(&config_path), &HashMap::new(), &[], None);
+        assert_eq!(
+            messages(&validation, DiagnosticLevel::Error),
+            ["'targets.empty.path' must not be empty"]
+        );
+        assert_eq!(
+            messages(&validation, DiagnosticLevel::Warning),
+            ["'targets.web.path' apps/web is not a directory"]
+        );
+    }
+
+    #[test]
+    fn test_validate_unparseable_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("code-guardian.toml");
+        fs::write(&config_path, "max_threads = [").unwrap();
+
+        let validation = validate_config(Some(&config_path), &HashMap::new(), &[], None);
+        assert!(validation.has_errors());
+        assert!(validation.config.is_none());
+    }
+}
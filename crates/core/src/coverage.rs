@@ -0,0 +1,54 @@
+//! Whether the configured rules actually exercise a codebase: detectors that
+//! found nothing, files the scan could not read and file types no detector
+//! looks at
+
+use crate::optimized_scanner::ScanMetrics;
+use crate::skipped::SkippedFile;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Coverage of a scan by its detectors, from a scan with detector profiling
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageReport {
+    pub files_scanned: usize,
+    pub detectors: usize,
+    /// Detectors that ran but reported no matches, by name
+    pub silent_detectors: Vec<String>,
+    /// Files that were found but could not be scanned, with the reason
+    pub skipped_files: Vec<SkippedFile>,
+    /// Generated or vendored files the walker left out
+    pub generated_files_skipped: usize,
+    /// Scanned files per extension that none of the detectors looks at;
+    /// `""` stands for files without an extension
+    pub uncovered_extensions: BTreeMap<String, usize>,
+}
+
+impl CoverageReport {
+    /// Builds the report from the metrics of a profiled scan, see
+    /// [`crate::OptimizedScanner::with_detector_profiling`]
+    pub fn new(metrics: &ScanMetrics, uncovered_extensions: BTreeMap<String, usize>) -> Self {
+        let mut silent_detectors: Vec<String> = metrics
+            .detector_timings
+            .iter()
+            .filter(|timing| timing.matches_found == 0)
+            .map(|timing| timing.detector.clone())
+            .collect();
+        silent_detectors.sort();
+        Self {
+            files_scanned: metrics.total_files_scanned,
+            detectors: metrics.detector_timings.len(),
+            silent_detectors,
+            skipped_files: metrics.skipped_files.clone(),
+            generated_files_skipped: metrics.generated_files_skipped,
+            uncovered_extensions,
+        }
+    }
+
+    /// Whether every detector matched something, every file was read and
+    /// every file type is looked at
+    pub fn is_complete(&self) -> bool {
+        self.silent_detectors.is_empty()
+            && self.skipped_files.is_empty()
+            && self.uncovered_extensions.is_empty()
+    }
+}
@@ -1,12 +1,24 @@
-use crate::{Match, PatternDetector, Severity};
-use anyhow::Result;
+use crate::deprecation::Deprecation;
+use crate::detector_conditions::{ConditionSet, DetectorCondition};
+use crate::errors::{ScanError, ScanResult};
+use crate::redaction::finding_message;
+use crate::regex_cache::{cached_regex_with, RegexFlags};
+use crate::regex_safety::analyze_pattern;
+use crate::{Icon, LineIndex, Match, PatternDetector, Severity, SharedStr};
 use regex::Regex;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Default time after which a custom detector stops reporting matches in a
+/// single file, see [`CustomDetector::with_match_timeout`]
+pub const DEFAULT_MATCH_TIMEOUT: Duration = Duration::from_millis(1000);
 
 /// Configuration for a custom detector
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CustomDetectorConfig {
     pub name: String,
     pub description: String,
@@ -19,10 +31,17 @@ pub struct CustomDetectorConfig {
     pub category: DetectorCategory,
     pub examples: Vec<String>,
     pub enabled: bool,
+    /// Conditions every match must meet, e.g. another pattern appearing nearby
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<DetectorCondition>,
+    /// Marks the pattern as a deprecated API; usages are reported with the
+    /// replacement to migrate to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecation: Option<Deprecation>,
 }
 
 /// Categories for organizing custom detectors
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub enum DetectorCategory {
     CodeQuality,
     Security,
@@ -30,36 +49,86 @@ pub enum DetectorCategory {
     Documentation,
     Testing,
     Deprecated,
+    CiConfig,
     Custom(String),
 }
 
+impl std::fmt::Display for DetectorCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Custom(name) => f.write_str(name),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// Parse custom detector definitions from a JSON, YAML or TOML file without compiling them
+pub fn parse_custom_detector_file(config_file: &Path) -> ScanResult<Vec<CustomDetectorConfig>> {
+    let content =
+        std::fs::read_to_string(config_file).map_err(|e| ScanError::io(config_file, e))?;
+    let invalid =
+        |e: &dyn std::fmt::Display| ScanError::config(format!("{}: {}", config_file.display(), e));
+    match config_file.extension().and_then(|s| s.to_str()) {
+        Some("json") => serde_json::from_str(&content).map_err(|e| invalid(&e)),
+        Some("yaml" | "yml") => serde_yaml::from_str(&content).map_err(|e| invalid(&e)),
+        Some("toml") => toml::from_str(&content).map_err(|e| invalid(&e)),
+        _ => Err(invalid(&"Unsupported config file format")),
+    }
+}
+
 /// A custom pattern detector built from configuration
 pub struct CustomDetector {
     config: CustomDetectorConfig,
     regex: Regex,
+    conditions: ConditionSet,
+    match_timeout: Duration,
+    timeout_reported: AtomicBool,
 }
 
 impl Clone for CustomDetector {
     fn clone(&self) -> Self {
-        Self::new(self.config.clone()).unwrap()
+        Self::new(self.config.clone())
+            .unwrap()
+            .with_match_timeout(self.match_timeout)
     }
 }
 
 impl CustomDetector {
     /// Create a new custom detector from configuration
-    pub fn new(config: CustomDetectorConfig) -> Result<Self> {
-        let pattern = config.pattern.clone();
-
-        // Build regex flags
-        let mut regex_flags = regex::RegexBuilder::new(&pattern);
-        regex_flags.case_insensitive(!config.case_sensitive);
-        regex_flags.multi_line(config.multiline);
-
-        let regex = regex_flags
-            .build()
-            .map_err(|e| anyhow::anyhow!("Invalid regex pattern '{}': {}", pattern, e))?;
+    pub fn new(config: CustomDetectorConfig) -> ScanResult<Self> {
+        let flags = RegexFlags {
+            case_insensitive: !config.case_sensitive,
+            multi_line: config.multiline,
+        };
+        let regex = cached_regex_with(&config.pattern, flags).map_err(|e| {
+            ScanError::detector(
+                &config.name,
+                format!("Invalid regex pattern '{}': {}", config.pattern, e),
+            )
+        })?;
+        let conditions =
+            ConditionSet::new(&config.conditions, config.case_sensitive).map_err(|e| {
+                ScanError::detector(&config.name, format!("Invalid condition pattern: {}", e))
+            })?;
+
+        Ok(Self {
+            config,
+            regex,
+            conditions,
+            match_timeout: DEFAULT_MATCH_TIMEOUT,
+            timeout_reported: AtomicBool::new(false),
+        })
+    }
 
-        Ok(Self { config, regex })
+    /// Set how long the detector may keep reporting matches in a single
+    /// file. The timeout is checked as each match is found: matching stops
+    /// at the first match found after it, and the remaining matches in that
+    /// file are skipped. It can't interrupt a search in progress, so a
+    /// search finding nothing runs to the end of the file; the regex engine
+    /// takes time linear in the file's size for that, never backtracking.
+    pub fn with_match_timeout(mut self, timeout: Duration) -> Self {
+        self.match_timeout = timeout;
+        self
     }
 
     /// Get detector configuration
@@ -86,16 +155,41 @@ impl CustomDetector {
 
 impl PatternDetector for CustomDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        if !self.config.enabled || !self.should_process_file(file_path) {
+        self.detect_with_lines(content, file_path, &LineIndex::new(content))
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        self.config.enabled && self.should_process_file(file_path)
+    }
+
+    fn detect_with_lines(&self, content: &str, file_path: &Path, lines: &LineIndex) -> Vec<Match> {
+        if !self.applies_to(file_path) {
             return Vec::new();
         }
 
         let mut matches = Vec::new();
+        let mut shared_path = None;
+        let conditions = self.conditions.for_file(content, file_path, lines);
+        let start = Instant::now();
 
         for cap in self.regex.captures_iter(content) {
+            if start.elapsed() > self.match_timeout {
+                if !self.timeout_reported.swap(true, Ordering::Relaxed) {
+                    eprintln!(
+                        "{} Custom detector '{}' exceeded its {}ms match timeout in {}; remaining matches in slow files are skipped", Icon::Warning,
+                        self.config.name,
+                        self.match_timeout.as_millis(),
+                        file_path.display()
+                    );
+                }
+                break;
+            }
             if let Some(full_match) = cap.get(0) {
                 // Find line and column
-                let (line_number, column) = find_line_column(content, full_match.start());
+                let (line_number, column) = lines.position(content, full_match.start());
+                if !conditions.allows(line_number) {
+                    continue;
+                }
 
                 // Extract message from capture groups or use full match
                 let message = if !self.config.capture_groups.is_empty() {
@@ -103,19 +197,43 @@ impl PatternDetector for CustomDetector {
                 } else {
                     full_match.as_str().trim().to_string()
                 };
+                let message = match &self.config.deprecation {
+                    Some(deprecation) => format!(
+                        "{} is deprecated since {}; use {} instead",
+                        message, deprecation.since, deprecation.replacement
+                    ),
+                    None => message,
+                };
 
                 matches.push(Match {
-                    file_path: file_path.to_string_lossy().to_string(),
+                    file_path: shared_path
+                        .get_or_insert_with(|| SharedStr::from_path(file_path))
+                        .clone(),
                     line_number,
                     column,
-                    pattern: self.config.name.clone(),
-                    message: format!("{}: {}", self.config.name, message),
+                    pattern: SharedStr::interned(&self.config.name),
+                    message: finding_message(
+                        &self.config.name,
+                        format!("{}: {}", self.config.name, message),
+                    ),
+                    severity: Some(self.config.severity.clone()),
+                    category: Some(self.config.category.to_string()),
+                    end: lines.span_end(content, full_match.start(), full_match.end()),
+                    author: None,
                 });
             }
         }
 
         matches
     }
+
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn config_fingerprint(&self) -> String {
+        serde_json::to_string(&self.config).unwrap_or_default()
+    }
 }
 
 impl CustomDetector {
@@ -141,6 +259,8 @@ impl CustomDetector {
 pub struct CustomDetectorManager {
     detectors: HashMap<String, CustomDetector>,
     config_file: Option<std::path::PathBuf>,
+    strict_rules: bool,
+    match_timeout: Duration,
 }
 
 impl CustomDetectorManager {
@@ -148,30 +268,76 @@ impl CustomDetectorManager {
         Self {
             detectors: HashMap::new(),
             config_file: None,
+            strict_rules: false,
+            match_timeout: DEFAULT_MATCH_TIMEOUT,
         }
     }
 
+    /// Reject, rather than warn about, detectors whose patterns are slow or overly broad
+    pub fn set_strict_rules(&mut self, strict: bool) {
+        self.strict_rules = strict;
+    }
+
+    /// Set the per-file match timeout for detectors loaded or added afterwards
+    pub fn set_match_timeout(&mut self, timeout: Duration) {
+        self.match_timeout = timeout;
+    }
+
+    /// Check a detector pattern with [`analyze_pattern`], warning about risky
+    /// constructs or, in strict mode, rejecting them
+    fn check_pattern_safety(&self, config: &CustomDetectorConfig) -> ScanResult<()> {
+        let patterns = std::iter::once(config.pattern.as_str()).chain(
+            config
+                .conditions
+                .iter()
+                .filter_map(DetectorCondition::pattern),
+        );
+        let risks: Vec<_> = patterns
+            .flat_map(|pattern| analyze_pattern(pattern).unwrap_or_default())
+            .collect();
+        if risks.is_empty() {
+            return Ok(());
+        }
+
+        let descriptions: Vec<&str> = risks.iter().map(|risk| risk.description()).collect();
+        if self.strict_rules {
+            return Err(ScanError::config(format!(
+                "Custom detector '{}' rejected by strict rules: {}",
+                config.name,
+                descriptions.join("; ")
+            )));
+        }
+        for description in descriptions {
+            eprintln!(
+                "{} Custom detector '{}': {}",
+                Icon::Warning,
+                config.name,
+                description
+            );
+        }
+        Ok(())
+    }
+
+    fn build_detector(&self, config: CustomDetectorConfig) -> ScanResult<CustomDetector> {
+        Ok(CustomDetector::new(config)?.with_match_timeout(self.match_timeout))
+    }
+
     /// Load detectors from configuration file
-    pub fn load_from_file<P: AsRef<Path>>(&mut self, config_file: P) -> Result<()> {
+    pub fn load_from_file<P: AsRef<Path>>(&mut self, config_file: P) -> ScanResult<()> {
         let config_file = config_file.as_ref();
-        let content = std::fs::read_to_string(config_file)?;
-
-        let configs: Vec<CustomDetectorConfig> =
-            match config_file.extension().and_then(|s| s.to_str()) {
-                Some("json") => serde_json::from_str(&content)?,
-                Some("yaml" | "yml") => serde_yaml::from_str(&content)?,
-                Some("toml") => toml::from_str(&content)?,
-                _ => return Err(anyhow::anyhow!("Unsupported config file format")),
-            };
+        let configs = parse_custom_detector_file(config_file)?;
 
         for config in configs {
-            let detector = CustomDetector::new(config.clone())?;
+            self.check_pattern_safety(&config)?;
+            let detector = self.build_detector(config.clone())?;
             self.detectors.insert(config.name.clone(), detector);
         }
 
         self.config_file = Some(config_file.to_path_buf());
-        println!(
-            "📁 Loaded {} custom detectors from {}",
+        // On stderr like the safety warnings, so listings and JSON on stdout stay parseable
+        eprintln!(
+            "{} Loaded {} custom detectors from {}",
+            Icon::Folder,
             self.detectors.len(),
             config_file.display()
         );
@@ -180,7 +346,7 @@ impl CustomDetectorManager {
     }
 
     /// Save detectors to configuration file
-    pub fn save_to_file<P: AsRef<Path>>(&self, config_file: P) -> Result<()> {
+    pub fn save_to_file<P: AsRef<Path>>(&self, config_file: P) -> ScanResult<()> {
         let configs: Vec<CustomDetectorConfig> = self
             .detectors
             .values()
@@ -189,15 +355,16 @@ impl CustomDetectorManager {
 
         let config_file = config_file.as_ref();
         let content = match config_file.extension().and_then(|s| s.to_str()) {
-            Some("json") => serde_json::to_string_pretty(&configs)?,
-            Some("yaml" | "yml") => serde_yaml::to_string(&configs)?,
-            Some("toml") => toml::to_string_pretty(&configs)?,
-            _ => return Err(anyhow::anyhow!("Unsupported config file format")),
+            Some("json") => serde_json::to_string_pretty(&configs).map_err(ScanError::config)?,
+            Some("yaml" | "yml") => serde_yaml::to_string(&configs).map_err(ScanError::config)?,
+            Some("toml") => toml::to_string_pretty(&configs).map_err(ScanError::config)?,
+            _ => return Err(ScanError::config("Unsupported config file format")),
         };
 
-        std::fs::write(config_file, content)?;
+        std::fs::write(config_file, content).map_err(|e| ScanError::io(config_file, e))?;
         println!(
-            "💾 Saved {} custom detectors to {}",
+            "{} Saved {} custom detectors to {}",
+            Icon::Save,
             configs.len(),
             config_file.display()
         );
@@ -206,18 +373,19 @@ impl CustomDetectorManager {
     }
 
     /// Add a new custom detector
-    pub fn add_detector(&mut self, config: CustomDetectorConfig) -> Result<()> {
+    pub fn add_detector(&mut self, config: CustomDetectorConfig) -> ScanResult<()> {
         let name = config.name.clone();
-        let detector = CustomDetector::new(config)?;
+        self.check_pattern_safety(&config)?;
+        let detector = self.build_detector(config)?;
         self.detectors.insert(name.clone(), detector);
-        println!("➕ Added custom detector: {}", name);
+        println!("{} Added custom detector: {}", Icon::Add, name);
         Ok(())
     }
 
     /// Remove a custom detector
     pub fn remove_detector(&mut self, name: &str) -> bool {
         if self.detectors.remove(name).is_some() {
-            println!("➖ Removed custom detector: {}", name);
+            println!("{} Removed custom detector: {}", Icon::Remove, name);
             true
         } else {
             false
@@ -239,27 +407,28 @@ impl CustomDetectorManager {
     }
 
     /// Enable/disable a detector
-    pub fn set_detector_enabled(&mut self, name: &str, enabled: bool) -> Result<()> {
+    pub fn set_detector_enabled(&mut self, name: &str, enabled: bool) -> ScanResult<()> {
         if let Some(detector) = self.detectors.get_mut(name) {
             // Note: We'd need to modify CustomDetector to allow config mutation
             // For now, we'll recreate the detector with updated config
             let mut config = detector.config().clone();
             config.enabled = enabled;
-            let new_detector = CustomDetector::new(config)?;
+            let new_detector = self.build_detector(config)?;
             self.detectors.insert(name.to_string(), new_detector);
             println!(
-                "🔄 {} detector: {}",
+                "{} {} detector: {}",
+                Icon::Refresh,
                 if enabled { "Enabled" } else { "Disabled" },
                 name
             );
             Ok(())
         } else {
-            Err(anyhow::anyhow!("Detector '{}' not found", name))
+            Err(ScanError::detector(name, "detector not found"))
         }
     }
 
     /// Create some example detectors
-    pub fn create_examples(&mut self) -> Result<()> {
+    pub fn create_examples(&mut self) -> ScanResult<()> {
         let examples = vec![
             CustomDetectorConfig {
                 name: "SQL_INJECTION".to_string(),
@@ -273,6 +442,8 @@ impl CustomDetectorManager {
                 category: DetectorCategory::Security,
                 examples: vec![r#"query("SELECT * FROM users WHERE id = " + user_id)"#.to_string()],
                 enabled: true,
+                conditions: Vec::new(),
+                deprecation: None,
             },
             CustomDetectorConfig {
                 name: "HARDCODED_PASSWORD".to_string(),
@@ -287,19 +458,8 @@ impl CustomDetectorManager {
                 category: DetectorCategory::Security,
                 examples: vec![r#"password = "secretpassword123""#.to_string()],
                 enabled: true,
-            },
-            CustomDetectorConfig {
-                name: "LARGE_FUNCTION".to_string(),
-                description: "Detect functions that might be too large".to_string(),
-                pattern: r"fn\s+\w+[^{]*\{(?:[^{}]*\{[^{}]*\})*[^{}]{500,}\}".to_string(),
-                file_extensions: vec!["rs".to_string()],
-                case_sensitive: true,
-                multiline: true,
-                capture_groups: vec![],
-                severity: Severity::Medium,
-                category: DetectorCategory::CodeQuality,
-                examples: vec!["Functions with more than 500 characters in body".to_string()],
-                enabled: true,
+                conditions: Vec::new(),
+                deprecation: None,
             },
         ];
 
@@ -317,27 +477,6 @@ impl Default for CustomDetectorManager {
     }
 }
 
-/// Helper function to find line and column from byte offset
-fn find_line_column(content: &str, offset: usize) -> (usize, usize) {
-    let mut line = 1;
-    let mut column = 1;
-
-    for (i, ch) in content.char_indices() {
-        if i >= offset {
-            break;
-        }
-
-        if ch == '\n' {
-            line += 1;
-            column = 1;
-        } else {
-            column += 1;
-        }
-    }
-
-    (line, column)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -356,6 +495,8 @@ mod tests {
             category: DetectorCategory::Testing,
             examples: vec![],
             enabled: true,
+            conditions: Vec::new(),
+            deprecation: None,
         };
 
         let detector = CustomDetector::new(config);
@@ -376,6 +517,8 @@ mod tests {
             category: DetectorCategory::Documentation,
             examples: vec![],
             enabled: true,
+            conditions: Vec::new(),
+            deprecation: None,
         };
 
         let detector = CustomDetector::new(config).unwrap();
@@ -384,6 +527,8 @@ mod tests {
 
         assert_eq!(matches.len(), 1);
         assert_eq!(matches[0].line_number, 1);
+        assert_eq!(matches[0].severity, Some(Severity::Low));
+        assert_eq!(matches[0].category.as_deref(), Some("Documentation"));
     }
 
     #[test]
@@ -412,6 +557,8 @@ mod tests {
             category: DetectorCategory::Testing,
             examples: vec![],
             enabled: true,
+            conditions: Vec::new(),
+            deprecation: None,
         };
 
         let detector = CustomDetector::new(config);
@@ -433,6 +580,8 @@ mod tests {
             category: DetectorCategory::CodeQuality,
             examples: vec![],
             enabled: true,
+            conditions: Vec::new(),
+            deprecation: None,
         };
 
         let detector = CustomDetector::new(config).unwrap();
@@ -455,6 +604,8 @@ mod tests {
             category: DetectorCategory::Testing,
             examples: vec![],
             enabled: true,
+            conditions: Vec::new(),
+            deprecation: None,
         };
 
         let detector = CustomDetector::new(config).unwrap();
@@ -480,6 +631,8 @@ mod tests {
             category: DetectorCategory::Testing,
             examples: vec![],
             enabled: true,
+            conditions: Vec::new(),
+            deprecation: None,
         };
 
         let detector = CustomDetector::new(config).unwrap();
@@ -502,6 +655,8 @@ mod tests {
             category: DetectorCategory::Testing,
             examples: vec![],
             enabled: true,
+            conditions: Vec::new(),
+            deprecation: None,
         };
 
         let detector = CustomDetector::new(config).unwrap();
@@ -524,6 +679,8 @@ mod tests {
             category: DetectorCategory::Testing,
             examples: vec![],
             enabled: true,
+            conditions: Vec::new(),
+            deprecation: None,
         };
 
         let detector = CustomDetector::new(config).unwrap();
@@ -552,6 +709,8 @@ mod tests {
             category: DetectorCategory::Testing,
             examples: vec![],
             enabled: true,
+            conditions: Vec::new(),
+            deprecation: None,
         };
 
         let detector = CustomDetector::new(config).unwrap();
@@ -576,6 +735,8 @@ mod tests {
             category: DetectorCategory::Testing,
             examples: vec![],
             enabled: false,
+            conditions: Vec::new(),
+            deprecation: None,
         };
 
         let detector = CustomDetector::new(config).unwrap();
@@ -598,9 +759,112 @@ mod tests {
             category: DetectorCategory::Testing,
             examples: vec![],
             enabled: true,
+            conditions: Vec::new(),
+            deprecation: None,
         };
 
         let detector = CustomDetector::new(config);
         assert!(detector.is_err());
     }
+
+    fn detector_config(name: &str, pattern: &str) -> CustomDetectorConfig {
+        CustomDetectorConfig {
+            name: name.to_string(),
+            description: String::new(),
+            pattern: pattern.to_string(),
+            file_extensions: vec![],
+            case_sensitive: true,
+            multiline: false,
+            capture_groups: vec![],
+            severity: Severity::Low,
+            category: DetectorCategory::Testing,
+            examples: vec![],
+            enabled: true,
+            conditions: Vec::new(),
+            deprecation: None,
+        }
+    }
+
+    #[test]
+    fn test_strict_rules_reject_risky_patterns() {
+        let mut manager = CustomDetectorManager::new();
+        manager
+            .add_detector(detector_config("BROAD", r".*password"))
+            .unwrap();
+
+        manager.set_strict_rules(true);
+        let err = manager
+            .add_detector(detector_config("NESTED", r"(\w+\s*)+;"))
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("'NESTED' rejected by strict rules"));
+        assert!(manager
+            .add_detector(detector_config("SAFE", r"\bTODO\b"))
+            .is_ok());
+        assert_eq!(manager.list_detectors().len(), 2);
+    }
+
+    #[test]
+    fn test_detector_conditions() {
+        let mut config = detector_config("UNCHECKED_UNWRAP", r"\.unwrap\(\)");
+        config.conditions = serde_json::from_str(
+            r#"[{"excludes": {"pattern": "is_some\\(\\)", "within_lines": 2, "direction": "before"}}, "outside_tests"]"#,
+        )
+        .unwrap();
+        let detector = CustomDetector::new(config).unwrap();
+        let content =
+            "if x.is_some() {\n    x.unwrap();\n}\ny.unwrap();\n#[test]\nfn t() { z.unwrap(); }\n";
+        let lines: Vec<usize> = detector
+            .detect(content, Path::new("src/lib.rs"))
+            .iter()
+            .map(|m| m.line_number)
+            .collect();
+        assert_eq!(lines, vec![4]);
+
+        let mut config = detector_config("BAD_CONDITION", "x");
+        config.conditions = vec![DetectorCondition::Requires(
+            crate::detector_conditions::ConditionPattern {
+                pattern: "[unclosed".to_string(),
+                within_lines: None,
+                direction: Default::default(),
+            },
+        )];
+        assert!(CustomDetector::new(config).is_err());
+    }
+
+    #[test]
+    fn test_match_timeout_stops_matching() {
+        let content = "TODO\n".repeat(100);
+        let detector = CustomDetector::new(detector_config("TODO_CUSTOM", "TODO")).unwrap();
+        assert_eq!(detector.detect(&content, Path::new("a.rs")).len(), 100);
+
+        let detector = detector.with_match_timeout(Duration::ZERO);
+        assert!(detector.detect(&content, Path::new("a.rs")).is_empty());
+
+        let mut manager = CustomDetectorManager::new();
+        manager.set_match_timeout(Duration::ZERO);
+        manager
+            .add_detector(detector_config("TODO_CUSTOM", "TODO"))
+            .unwrap();
+        let detectors = manager.get_detectors();
+        assert!(detectors[0].detect(&content, Path::new("a.rs")).is_empty());
+    }
+
+    #[test]
+    fn test_error_kinds() {
+        let mut manager = CustomDetectorManager::new();
+        let err = manager
+            .load_from_file("/nonexistent/detectors.json")
+            .unwrap_err();
+        assert!(matches!(err, ScanError::Io { .. }));
+
+        let err = manager
+            .add_detector(detector_config("BROKEN", "[unclosed"))
+            .unwrap_err();
+        assert!(matches!(err, ScanError::Detector { ref detector, .. } if detector == "BROKEN"));
+
+        let err = manager.set_detector_enabled("MISSING", false).unwrap_err();
+        assert!(matches!(err, ScanError::Detector { .. }));
+    }
 }
@@ -0,0 +1,152 @@
+//! Deprecated API rules and the progress of migrating off them. A custom
+//! detector with a `deprecation` block matches usages of an API that is on
+//! its way out, such as an internal SDK call, and names its replacement.
+
+use crate::custom_detectors::CustomDetectorConfig;
+use crate::Match;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Marks a custom detector's pattern as a deprecated API
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Deprecation {
+    /// Version or date the API was deprecated in, e.g. `payments-sdk 4.0`
+    pub since: String,
+    /// What to use instead, reported with every usage
+    pub replacement: String,
+}
+
+/// Remaining usages of one deprecated API
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeprecatedApiUsage {
+    /// Name of the rule matching the API
+    pub rule: String,
+    pub since: String,
+    pub replacement: String,
+    pub usages: usize,
+    /// Usages per file
+    pub files: BTreeMap<String, usize>,
+}
+
+/// How far a codebase is in migrating off the deprecated APIs of a rule
+/// pack, grouped by API, including APIs with no usages left
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationReport {
+    /// Deprecated APIs by rule name
+    pub apis: Vec<DeprecatedApiUsage>,
+}
+
+impl MigrationReport {
+    /// Groups `matches` by the deprecated API rules among `rules`; matches
+    /// of other rules are ignored
+    pub fn new(rules: &[CustomDetectorConfig], matches: &[Match]) -> Self {
+        let mut apis: Vec<DeprecatedApiUsage> = rules
+            .iter()
+            .filter_map(|rule| {
+                let deprecation = rule.deprecation.as_ref()?;
+                let mut files = BTreeMap::new();
+                for m in matches.iter().filter(|m| m.pattern == rule.name.as_str()) {
+                    *files.entry(m.file_path.to_string()).or_insert(0) += 1;
+                }
+                Some(DeprecatedApiUsage {
+                    rule: rule.name.clone(),
+                    since: deprecation.since.clone(),
+                    replacement: deprecation.replacement.clone(),
+                    usages: files.values().sum(),
+                    files,
+                })
+            })
+            .collect();
+        apis.sort_by(|a, b| a.rule.cmp(&b.rule));
+        Self { apis }
+    }
+
+    /// Number of deprecated APIs with no usages left
+    pub fn migrated(&self) -> usize {
+        self.apis.iter().filter(|api| api.usages == 0).count()
+    }
+
+    /// Usages left across all deprecated APIs
+    pub fn remaining_usages(&self) -> usize {
+        self.apis.iter().map(|api| api.usages).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_detectors::{CustomDetector, DetectorCategory};
+    use crate::{PatternDetector, Severity};
+    use std::path::Path;
+
+    fn rule(name: &str, pattern: &str, deprecation: Option<Deprecation>) -> CustomDetectorConfig {
+        CustomDetectorConfig {
+            name: name.to_string(),
+            description: String::new(),
+            pattern: pattern.to_string(),
+            file_extensions: vec![],
+            case_sensitive: true,
+            multiline: false,
+            capture_groups: vec![],
+            severity: Severity::Medium,
+            category: DetectorCategory::Deprecated,
+            examples: vec![],
+            enabled: true,
+            conditions: Vec::new(),
+            deprecation,
+        }
+    }
+
+    #[test]
+    fn test_migration_report() {
+        let deprecated = |since: &str, replacement: &str| {
+            Some(Deprecation {
+                since: since.to_string(),
+                replacement: replacement.to_string(),
+            })
+        };
+        let rules = vec![
+            rule(
+                "OLD_CHARGE",
+                r"payments\.charge\(",
+                deprecated("4.0", "payments.createIntent()"),
+            ),
+            rule(
+                "OLD_REFUND",
+                r"payments\.refund\(",
+                deprecated("4.2", "payments.reverse()"),
+            ),
+            rule("TODO_MARKER", r"TODO", None),
+        ];
+        let detectors: Vec<CustomDetector> = rules
+            .iter()
+            .map(|rule| CustomDetector::new(rule.clone()).unwrap())
+            .collect();
+        let mut matches = Vec::new();
+        for (path, content) in [
+            ("a.js", "payments.charge(1);\npayments.charge(2); // TODO\n"),
+            ("b.js", "payments.charge(3);\n"),
+        ] {
+            for detector in &detectors {
+                matches.extend(detector.detect(content, Path::new(path)));
+            }
+        }
+        assert_eq!(
+            matches[0].message,
+            "OLD_CHARGE: payments.charge( is deprecated since 4.0; use payments.createIntent() instead"
+        );
+
+        let report = MigrationReport::new(&rules, &matches);
+        assert_eq!(report.apis.len(), 2);
+        assert_eq!(report.apis[0].rule, "OLD_CHARGE");
+        assert_eq!(report.apis[0].usages, 3);
+        assert_eq!(
+            report.apis[0].files,
+            BTreeMap::from([("a.js".to_string(), 2), ("b.js".to_string(), 1)])
+        );
+        assert_eq!(report.apis[1].usages, 0);
+        assert_eq!(report.migrated(), 1);
+        assert_eq!(report.remaining_usages(), 3);
+    }
+}
@@ -0,0 +1,282 @@
+//! Conditions that compose custom detectors: a match is only reported when
+//! another pattern does, or does not, appear in its file or near it, or when
+//! it lies outside test code
+
+use crate::detectors::{build_glob_set, DEFAULT_TEST_PATH_GLOBS};
+use crate::regex_cache::{cached_regex_with, RegexFlags};
+use crate::LineIndex;
+use globset::GlobSet;
+use lazy_static::lazy_static;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::cell::OnceCell;
+use std::path::Path;
+
+lazy_static! {
+    static ref TEST_PATHS: GlobSet =
+        build_glob_set(DEFAULT_TEST_PATH_GLOBS).expect("default test globs are valid");
+    static ref TEST_ATTRIBUTE: Regex =
+        Regex::new(r"^\s*#\[(cfg\(test\)|test|[a-z_]+::test)\b").unwrap();
+}
+
+/// Lines searched by a [`ConditionPattern`] with `within_lines` set
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchDirection {
+    /// Lines before and after the match
+    #[default]
+    Around,
+    /// The match's line and the lines before it
+    Before,
+    /// The match's line and the lines after it
+    After,
+}
+
+impl SearchDirection {
+    fn is_around(&self) -> bool {
+        *self == Self::Around
+    }
+}
+
+/// A regex searched for in the file of a match, or only near the match
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ConditionPattern {
+    pub pattern: String,
+    /// Only search this many lines from the match; unset searches the whole file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub within_lines: Option<usize>,
+    /// Which side of the match to search when `within_lines` is set
+    #[serde(default, skip_serializing_if = "SearchDirection::is_around")]
+    pub direction: SearchDirection,
+}
+
+/// A condition on the matches of a custom detector. A match is reported only
+/// if every condition of its detector holds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DetectorCondition {
+    /// Another pattern must also appear
+    Requires(ConditionPattern),
+    /// Another pattern must not appear
+    Excludes(ConditionPattern),
+    /// The match must not be in a test file or inside a Rust `#[cfg(test)]`
+    /// or `#[test]` item
+    OutsideTests,
+}
+
+impl DetectorCondition {
+    /// The regex the condition searches for, if any
+    pub fn pattern(&self) -> Option<&str> {
+        match self {
+            Self::Requires(condition) | Self::Excludes(condition) => Some(&condition.pattern),
+            Self::OutsideTests => None,
+        }
+    }
+}
+
+enum CompiledCondition {
+    Pattern {
+        regex: Regex,
+        within_lines: Option<usize>,
+        direction: SearchDirection,
+        required: bool,
+    },
+    OutsideTests,
+}
+
+/// The compiled conditions of one detector
+pub(crate) struct ConditionSet {
+    conditions: Vec<CompiledCondition>,
+}
+
+impl ConditionSet {
+    pub(crate) fn new(
+        conditions: &[DetectorCondition],
+        case_sensitive: bool,
+    ) -> Result<Self, regex::Error> {
+        let conditions = conditions
+            .iter()
+            .map(|condition| {
+                let (pattern, required) = match condition {
+                    DetectorCondition::Requires(pattern) => (pattern, true),
+                    DetectorCondition::Excludes(pattern) => (pattern, false),
+                    DetectorCondition::OutsideTests => return Ok(CompiledCondition::OutsideTests),
+                };
+                Ok(CompiledCondition::Pattern {
+                    regex: cached_regex_with(
+                        &pattern.pattern,
+                        RegexFlags {
+                            case_insensitive: !case_sensitive,
+                            multi_line: false,
+                        },
+                    )?,
+                    within_lines: pattern.within_lines,
+                    direction: pattern.direction,
+                    required,
+                })
+            })
+            .collect::<Result<_, regex::Error>>()?;
+        Ok(Self { conditions })
+    }
+
+    /// Evaluates the conditions for matches in one file, searching each
+    /// file-wide pattern and finding test items at most once
+    pub(crate) fn for_file<'a>(
+        &'a self,
+        content: &'a str,
+        file_path: &Path,
+        lines: &'a LineIndex,
+    ) -> FileConditions<'a> {
+        FileConditions {
+            conditions: &self.conditions,
+            content,
+            lines,
+            in_test_file: TEST_PATHS.is_match(file_path),
+            file_hits: self.conditions.iter().map(|_| OnceCell::new()).collect(),
+            test_items: OnceCell::new(),
+        }
+    }
+}
+
+pub(crate) struct FileConditions<'a> {
+    conditions: &'a [CompiledCondition],
+    content: &'a str,
+    lines: &'a LineIndex,
+    in_test_file: bool,
+    /// Whether each file-wide pattern appears in the file
+    file_hits: Vec<OnceCell<bool>>,
+    /// First and last line of each test item
+    test_items: OnceCell<Vec<(usize, usize)>>,
+}
+
+impl FileConditions<'_> {
+    /// Whether a match on `line` (1-based) meets every condition
+    pub(crate) fn allows(&self, line: usize) -> bool {
+        self.conditions
+            .iter()
+            .zip(&self.file_hits)
+            .all(|(condition, file_hit)| match condition {
+                CompiledCondition::Pattern {
+                    regex,
+                    within_lines,
+                    direction,
+                    required,
+                } => {
+                    let found = match within_lines {
+                        None => *file_hit.get_or_init(|| regex.is_match(self.content)),
+                        Some(distance) => regex.is_match(self.nearby(line, *distance, *direction)),
+                    };
+                    found == *required
+                }
+                CompiledCondition::OutsideTests => {
+                    !self.in_test_file
+                        && !self
+                            .test_items
+                            .get_or_init(|| test_items(self.content, self.lines))
+                            .iter()
+                            .any(|&(first, last)| (first..=last).contains(&line))
+                }
+            })
+    }
+
+    /// The text of the lines within `distance` of `line` in `direction`
+    fn nearby(&self, line: usize, distance: usize, direction: SearchDirection) -> &str {
+        let (first, last) = match direction {
+            SearchDirection::Around => (line.saturating_sub(distance), line + distance),
+            SearchDirection::Before => (line.saturating_sub(distance), line),
+            SearchDirection::After => (line, line + distance),
+        };
+        let start = self.lines.line_start(first.max(1));
+        let end = if last >= self.lines.line_count() {
+            self.content.len()
+        } else {
+            self.lines.line_start(last + 1)
+        };
+        &self.content[start..end]
+    }
+}
+
+/// First and last lines of the Rust items marked `#[cfg(test)]` or `#[test]`,
+/// found by matching the braces that follow the attribute
+fn test_items(content: &str, lines: &LineIndex) -> Vec<(usize, usize)> {
+    let mut items = Vec::new();
+    let mut line = 1;
+    while line <= lines.line_count() {
+        if !TEST_ATTRIBUTE.is_match(lines.line(content, line)) {
+            line += 1;
+            continue;
+        }
+        let start = lines.line_start(line);
+        let mut depth = 0usize;
+        let mut end = content.len();
+        for (offset, c) in content[start..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth > 0 => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = start + offset;
+                        break;
+                    }
+                }
+                // An item without a body, e.g. `#[cfg(test)] mod tests;`
+                ';' if depth == 0 => {
+                    end = start + offset;
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let last = lines.line_of(end).max(line);
+        items.push((line, last));
+        line = last + 1;
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allows(conditions: &[DetectorCondition], content: &str, path: &str, line: usize) -> bool {
+        let set = ConditionSet::new(conditions, true).unwrap();
+        let lines = LineIndex::new(content);
+        set.for_file(content, Path::new(path), &lines).allows(line)
+    }
+
+    #[test]
+    fn test_nearby_conditions() {
+        let content = "if x.is_some() {\n    x.unwrap();\n}\n\n\ny.unwrap();\n";
+        let excludes_check = [DetectorCondition::Excludes(ConditionPattern {
+            pattern: r"is_some\(\)".to_string(),
+            within_lines: Some(2),
+            direction: SearchDirection::Before,
+        })];
+        assert!(!allows(&excludes_check, content, "src/lib.rs", 2));
+        assert!(allows(&excludes_check, content, "src/lib.rs", 6));
+
+        let requires_anywhere = [DetectorCondition::Requires(ConditionPattern {
+            pattern: "is_some".to_string(),
+            within_lines: None,
+            direction: SearchDirection::Around,
+        })];
+        assert!(allows(&requires_anywhere, content, "src/lib.rs", 6));
+        assert!(!allows(
+            &requires_anywhere,
+            "y.unwrap();\n",
+            "src/lib.rs",
+            1
+        ));
+    }
+
+    #[test]
+    fn test_outside_tests_condition() {
+        let content = "fn a() { x.unwrap(); }\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn t() {\n        x.unwrap();\n    }\n}\nfn b() {}\n";
+        let outside = [DetectorCondition::OutsideTests];
+        assert!(allows(&outside, content, "src/lib.rs", 1));
+        assert!(!allows(&outside, content, "src/lib.rs", 7));
+        assert!(allows(&outside, content, "src/lib.rs", 10));
+        assert!(!allows(&outside, content, "tests/cli.rs", 1));
+    }
+}
@@ -1,6 +1,21 @@
+use crate::a11y_detectors::*;
+use crate::c_detectors::*;
+use crate::ci_detectors::*;
 use crate::detectors::*;
 use crate::enhanced_config::{DetectorType, EnhancedScanConfig};
+use crate::feature_flags::*;
+use crate::go_detectors::*;
+use crate::i18n_detectors::*;
+use crate::iac_detectors::*;
+use crate::jvm_detectors::*;
 use crate::llm_detectors::*;
+use crate::migration_detectors::*;
+use crate::pii_detectors::*;
+use crate::python_detectors::*;
+use crate::quality_detectors::*;
+use crate::rust_panic_audit::*;
+use crate::security_detectors::*;
+use crate::test_quality_detectors::*;
 use crate::PatternDetector;
 use anyhow::Result;
 
@@ -54,7 +69,7 @@ impl DetectorFactory {
             // Development/Phase patterns
             Box::new(DevDetector),
             Box::new(DebugDetector),
-            Box::new(TestDetector),
+            Box::new(TestDetector::default()),
             Box::new(PhaseDetector),
             Box::new(StagingDetector),
             // Non-production code patterns
@@ -65,6 +80,16 @@ impl DetectorFactory {
             Box::new(UnusedVarDetector),
             Box::new(DeadCodeDetector),
             Box::new(ExperimentalDetector),
+            // Code quality patterns
+            Box::new(FileLengthDetector::default()),
+            Box::new(FunctionLengthDetector::default()),
+            Box::new(DisabledCodeDetector),
+            // Test hygiene patterns, on test code only
+            Box::new(DisabledTestDetector::default()),
+            Box::new(AssertlessTestDetector::default()),
+            Box::new(SleepInTestDetector::default()),
+            // SQL migration patterns, on migration files only
+            Box::new(MigrationRiskDetector::default()),
         ]
     }
 
@@ -74,7 +99,7 @@ impl DetectorFactory {
             // Development/Phase patterns
             Box::new(DevDetector),
             Box::new(DebugDetector),
-            Box::new(TestDetector),
+            Box::new(TestDetector::default()),
             Box::new(PhaseDetector),
             Box::new(StagingDetector),
             // Non-production code patterns
@@ -89,6 +114,11 @@ impl DetectorFactory {
             Box::new(PanicDetector),
             Box::new(UnwrapDetector),
             Box::new(UnsafeDetector),
+            // Insecure cryptography
+            Box::new(WeakHashDetector),
+            Box::new(HardcodedIvDetector),
+            Box::new(InsecureRandomTokenDetector),
+            Box::new(TimingUnsafeComparisonDetector),
         ]
     }
 
@@ -99,6 +129,12 @@ impl DetectorFactory {
             Box::new(PanicDetector),
             Box::new(UnwrapDetector),
             Box::new(ExpectDetector),
+            Box::new(WeakHashDetector),
+            Box::new(HardcodedIvDetector),
+            Box::new(InsecureRandomTokenDetector),
+            Box::new(TimingUnsafeComparisonDetector),
+            Box::new(EvalDetector),
+            Box::new(InsecureDeserializationDetector),
         ]
     }
 
@@ -145,6 +181,81 @@ impl DetectorFactory {
         detectors
     }
 
+    /// Create infrastructure-as-code detectors (Dockerfile, docker-compose,
+    /// Terraform, CI, shell scripts)
+    pub fn create_iac_detectors() -> Vec<Box<dyn PatternDetector>> {
+        vec![
+            Box::new(DockerfileDetector),
+            Box::new(DockerComposeDetector),
+            Box::new(TerraformDetector),
+            Box::new(CiConfigDetector),
+            Box::new(ShellScriptDetector),
+        ]
+    }
+
+    /// Create code quality detectors, reported with Low severity
+    pub fn create_code_quality_detectors() -> Vec<Box<dyn PatternDetector>> {
+        vec![
+            Box::new(MagicNumberDetector::default()),
+            Box::new(ErrorSwallowedDetector),
+            Box::new(FileLengthDetector::default()),
+            Box::new(FunctionLengthDetector::default()),
+            Box::new(DisabledCodeDetector),
+        ]
+    }
+
+    /// Create accessibility detectors for JSX, HTML, Vue and Svelte markup
+    pub fn create_accessibility_detectors() -> Vec<Box<dyn PatternDetector>> {
+        vec![
+            Box::new(ImgMissingAltDetector),
+            Box::new(NonInteractiveClickDetector),
+            Box::new(MissingFormLabelDetector),
+            Box::new(PositiveTabindexDetector),
+        ]
+    }
+
+    /// Create Go detectors, for `.go` files in the backend and systems presets
+    pub fn create_go_detectors() -> Vec<Box<dyn PatternDetector>> {
+        vec![
+            Box::new(GoPrintDetector),
+            Box::new(GoIgnoredErrorDetector),
+            Box::new(GoPanicDetector),
+            Box::new(GoSleepInHandlerDetector),
+        ]
+    }
+
+    /// Create C and C++ detectors, for the systems preset
+    pub fn create_c_detectors() -> Vec<Box<dyn PatternDetector>> {
+        vec![
+            Box::new(UnsafeStringFunctionDetector),
+            Box::new(SystemCallDetector),
+            Box::new(UncheckedMallocDetector),
+            Box::new(WarningPragmaDetector),
+        ]
+    }
+
+    /// Create Python detectors, for `.py` files in the backend preset
+    pub fn create_python_detectors() -> Vec<Box<dyn PatternDetector>> {
+        vec![
+            Box::new(PythonPrintDetector),
+            Box::new(PythonDebuggerDetector),
+            Box::new(BroadExceptDetector),
+            Box::new(MutableDefaultArgumentDetector),
+            Box::new(PythonAssertDetector),
+        ]
+    }
+
+    /// Create Java and Kotlin detectors, for the backend preset
+    pub fn create_jvm_detectors() -> Vec<Box<dyn PatternDetector>> {
+        vec![
+            Box::new(SystemOutDetector),
+            Box::new(PrintStackTraceDetector),
+            Box::new(SleepInRequestDetector),
+            Box::new(SuppressAllWarningsDetector),
+            Box::new(NotNullAssertionDetector),
+        ]
+    }
+
     /// Create performance-focused detectors
     pub fn create_performance_detectors() -> Vec<Box<dyn PatternDetector>> {
         vec![
@@ -179,7 +290,23 @@ impl DetectorFactory {
             // Development/Phase patterns
             DetectorType::Dev => Ok(Some(Box::new(DevDetector))),
             DetectorType::Debug => Ok(Some(Box::new(DebugDetector))),
-            DetectorType::Test => Ok(Some(Box::new(TestDetector))),
+            DetectorType::Test => match config {
+                Some(config) => {
+                    let test_globs = if config.test_path_globs.is_empty() {
+                        DEFAULT_TEST_PATH_GLOBS
+                            .iter()
+                            .map(|g| g.to_string())
+                            .collect()
+                    } else {
+                        config.test_path_globs.clone()
+                    };
+                    Ok(Some(Box::new(TestDetector::new(
+                        &test_globs,
+                        &config.production_path_globs,
+                    )?)))
+                }
+                None => Ok(Some(Box::new(TestDetector::default()))),
+            },
             DetectorType::Phase => Ok(Some(Box::new(PhaseDetector))),
             DetectorType::Staging => Ok(Some(Box::new(StagingDetector))),
 
@@ -191,6 +318,12 @@ impl DetectorFactory {
             DetectorType::UnusedVar => Ok(Some(Box::new(UnusedVarDetector))),
             DetectorType::DeadCode => Ok(Some(Box::new(DeadCodeDetector))),
             DetectorType::Experimental => Ok(Some(Box::new(ExperimentalDetector))),
+            DetectorType::OrphanTodo => {
+                let pattern = config
+                    .and_then(|c| c.issue_reference_pattern.as_deref())
+                    .unwrap_or(DEFAULT_ISSUE_REFERENCE_PATTERN);
+                Ok(Some(Box::new(OrphanTodoDetector::new(pattern)?)))
+            }
 
             // LLM-specific security patterns
             DetectorType::LLMHallucinatedApi => Ok(Some(Box::new(HallucinatedApiDetector))),
@@ -236,6 +369,83 @@ impl DetectorFactory {
             // Comprehensive LLM detector
             DetectorType::LLMComprehensive => Ok(Some(Box::new(ComprehensiveLLMDetector::new()))),
 
+            // Infrastructure-as-code patterns
+            DetectorType::Dockerfile => Ok(Some(Box::new(DockerfileDetector))),
+            DetectorType::DockerCompose => Ok(Some(Box::new(DockerComposeDetector))),
+            DetectorType::Terraform => Ok(Some(Box::new(TerraformDetector))),
+            DetectorType::ShellScript => Ok(Some(Box::new(ShellScriptDetector))),
+            DetectorType::MigrationRisk => Ok(Some(Box::new(MigrationRiskDetector::default()))),
+
+            // CI configuration patterns
+            DetectorType::CiConfig => Ok(Some(Box::new(CiConfigDetector))),
+
+            // Code quality patterns
+            DetectorType::MagicNumber => Ok(Some(Box::new(MagicNumberDetector::default()))),
+            DetectorType::ErrorSwallowed => Ok(Some(Box::new(ErrorSwallowedDetector))),
+            DetectorType::FileLength => Ok(Some(Box::new(FileLengthDetector::default()))),
+            DetectorType::FunctionLength => Ok(Some(Box::new(FunctionLengthDetector::default()))),
+            DetectorType::DisabledCode => Ok(Some(Box::new(DisabledCodeDetector))),
+
+            // Test hygiene patterns
+            DetectorType::DisabledTest => Ok(Some(Box::new(DisabledTestDetector::default()))),
+            DetectorType::AssertlessTest => Ok(Some(Box::new(AssertlessTestDetector::default()))),
+            DetectorType::SleepInTest => Ok(Some(Box::new(SleepInTestDetector::default()))),
+
+            // Insecure cryptography patterns
+            DetectorType::WeakHash => Ok(Some(Box::new(WeakHashDetector))),
+            DetectorType::HardcodedIv => Ok(Some(Box::new(HardcodedIvDetector))),
+            DetectorType::InsecureRandom => Ok(Some(Box::new(InsecureRandomTokenDetector))),
+            DetectorType::TimingUnsafeComparison => {
+                Ok(Some(Box::new(TimingUnsafeComparisonDetector)))
+            }
+
+            // Personal data and localization, off unless selected
+            DetectorType::Pii => Ok(Some(Box::new(PiiDetector::default()))),
+            DetectorType::HardcodedString => Ok(Some(Box::new(HardcodedStringDetector::default()))),
+
+            // Accessibility patterns
+            DetectorType::ImgMissingAlt => Ok(Some(Box::new(ImgMissingAltDetector))),
+            DetectorType::NonInteractiveClick => Ok(Some(Box::new(NonInteractiveClickDetector))),
+            DetectorType::MissingFormLabel => Ok(Some(Box::new(MissingFormLabelDetector))),
+            DetectorType::PositiveTabindex => Ok(Some(Box::new(PositiveTabindexDetector))),
+
+            // Go patterns
+            DetectorType::GoPrint => Ok(Some(Box::new(GoPrintDetector))),
+            DetectorType::GoIgnoredError => Ok(Some(Box::new(GoIgnoredErrorDetector))),
+            DetectorType::GoPanic => Ok(Some(Box::new(GoPanicDetector))),
+            DetectorType::GoSleepInHandler => Ok(Some(Box::new(GoSleepInHandlerDetector))),
+
+            // C and C++ patterns
+            DetectorType::UnsafeStringFunction => Ok(Some(Box::new(UnsafeStringFunctionDetector))),
+            DetectorType::SystemCall => Ok(Some(Box::new(SystemCallDetector))),
+            DetectorType::UncheckedMalloc => Ok(Some(Box::new(UncheckedMallocDetector))),
+            DetectorType::WarningPragma => Ok(Some(Box::new(WarningPragmaDetector))),
+
+            // Python patterns
+            DetectorType::PythonPrint => Ok(Some(Box::new(PythonPrintDetector))),
+            DetectorType::PythonDebugger => Ok(Some(Box::new(PythonDebuggerDetector))),
+            DetectorType::BroadExcept => Ok(Some(Box::new(BroadExceptDetector))),
+            DetectorType::MutableDefaultArgument => {
+                Ok(Some(Box::new(MutableDefaultArgumentDetector)))
+            }
+            DetectorType::PythonAssert => Ok(Some(Box::new(PythonAssertDetector))),
+
+            // Java and Kotlin patterns
+            DetectorType::SystemOut => Ok(Some(Box::new(SystemOutDetector))),
+            DetectorType::PrintStackTrace => Ok(Some(Box::new(PrintStackTraceDetector))),
+            DetectorType::SleepInRequest => Ok(Some(Box::new(SleepInRequestDetector))),
+            DetectorType::SuppressAllWarnings => Ok(Some(Box::new(SuppressAllWarningsDetector))),
+            DetectorType::NotNullAssertion => Ok(Some(Box::new(NotNullAssertionDetector))),
+
+            // Rust panic-path audit
+            DetectorType::RustPanicPath => Ok(Some(Box::new(RustPanicPathDetector::default()))),
+
+            // Code execution patterns
+            DetectorType::UnsafeEval => Ok(Some(Box::new(EvalDetector))),
+            DetectorType::InsecureDeserialization => {
+                Ok(Some(Box::new(InsecureDeserializationDetector)))
+            }
+
             DetectorType::Custom(name) => {
                 if let Some(config) = config {
                     if let Some(pattern) = config.custom_patterns.get(name) {
@@ -264,6 +474,17 @@ pub enum DetectorProfile {
     Performance,
     /// Rust-specific patterns only
     Rust,
+    /// Every Rust panic source, rated by whether request handlers or `main`
+    /// can reach it
+    RustPanics,
+    /// Go-specific patterns only
+    Go,
+    /// C and C++ patterns only
+    C,
+    /// Python-specific patterns, with `DEBUGGER` for `pdb.set_trace()`
+    Python,
+    /// Java and Kotlin patterns only
+    Jvm,
     /// Production-readiness scanning (finds non-production code)
     ProductionReady,
     /// LLM security vulnerabilities only
@@ -274,6 +495,14 @@ pub enum DetectorProfile {
     LLMComprehensive,
     /// Production-ready with LLM detection
     ProductionReadyWithLLM,
+    /// Infrastructure-as-code misconfigurations
+    Iac,
+    /// Code quality issues such as magic numbers and swallowed errors
+    Quality,
+    /// Accessibility issues in frontend markup
+    Accessibility,
+    /// Risky statements in SQL migrations
+    Migrations,
     /// Custom configuration
     Custom(Box<EnhancedScanConfig>),
 }
@@ -297,6 +526,10 @@ impl DetectorProfile {
             DetectorProfile::ProductionReadyWithLLM => {
                 DetectorFactory::create_production_ready_with_llm_detectors()
             }
+            DetectorProfile::Iac => DetectorFactory::create_iac_detectors(),
+            DetectorProfile::Quality => DetectorFactory::create_code_quality_detectors(),
+            DetectorProfile::Accessibility => DetectorFactory::create_accessibility_detectors(),
+            DetectorProfile::Migrations => vec![Box::new(MigrationRiskDetector::default())],
             DetectorProfile::Rust => vec![
                 Box::new(PanicDetector),
                 Box::new(UnwrapDetector),
@@ -307,6 +540,15 @@ impl DetectorProfile {
                 Box::new(ToStringDetector),
                 Box::new(UnsafeDetector),
             ],
+            DetectorProfile::RustPanics => vec![Box::new(RustPanicPathDetector::default())],
+            DetectorProfile::Go => DetectorFactory::create_go_detectors(),
+            DetectorProfile::C => DetectorFactory::create_c_detectors(),
+            DetectorProfile::Jvm => DetectorFactory::create_jvm_detectors(),
+            DetectorProfile::Python => {
+                let mut detectors = DetectorFactory::create_python_detectors();
+                detectors.push(Box::new(DebuggerDetector));
+                detectors
+            }
             DetectorProfile::Custom(config) => DetectorFactory::create_detectors(config),
         }
     }
@@ -315,6 +557,7 @@ impl DetectorProfile {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::Path;
 
     #[test]
     fn test_default_detectors() {
@@ -334,6 +577,19 @@ mod tests {
         assert!(detectors.len() >= 4);
     }
 
+    #[test]
+    fn test_iac_detectors() {
+        let detectors = DetectorProfile::Iac.get_detectors();
+        assert_eq!(detectors.len(), 5);
+
+        let matches: Vec<_> = detectors
+            .iter()
+            .flat_map(|d| d.detect("FROM ubuntu:latest\n", Path::new("Dockerfile")))
+            .collect();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].pattern, "DOCKER_LATEST_TAG");
+    }
+
     #[test]
     fn test_detector_profiles() {
         let basic = DetectorProfile::Basic.get_detectors();
@@ -0,0 +1,188 @@
+//! Findings of each detector per file extension, kept across scans so that
+//! `scan --fast` can skip detectors that never find anything in a language.
+//! Skipped detectors run again every [`REVALIDATE_AFTER_SCANS`] fast scans,
+//! so a detector that starts finding something is no longer skipped.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Files of an extension a detector must have run on without a finding
+/// before fast scans skip it there
+pub const MIN_FILES_FOR_SKIP: u64 = 100;
+
+/// Fast scans in a row a detector is skipped in before it runs again
+pub const REVALIDATE_AFTER_SCANS: u32 = 10;
+
+/// Totals of one detector on the files of one extension
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectorYield {
+    /// Detector name, see [`crate::PatternDetector::name`]
+    pub detector: String,
+    /// Lowercase file extension, empty for files without one
+    pub extension: String,
+    /// Files the detector ran on
+    pub files: u64,
+    /// Findings it reported in them
+    pub matches: u64,
+    /// Fast scans in a row that skipped it
+    pub skipped_scans: u32,
+}
+
+/// Which detectors a scan skips per extension, and the yield of the ones it
+/// runs. The default skips nothing and only records.
+#[derive(Debug, Default)]
+pub struct DetectorSchedule {
+    /// Skipped detector names by extension
+    skipped: HashMap<String, HashSet<String>>,
+    observed: Mutex<HashMap<(String, String), (u64, u64)>>,
+}
+
+impl DetectorSchedule {
+    /// Skips each detector on the extensions where it ran on at least
+    /// [`MIN_FILES_FOR_SKIP`] files of `history` without a finding, unless
+    /// it is due to re-validate
+    pub fn fast(history: &[DetectorYield]) -> Self {
+        let mut skipped: HashMap<String, HashSet<String>> = HashMap::new();
+        for y in history.iter().filter(|y| {
+            y.matches == 0
+                && y.files >= MIN_FILES_FOR_SKIP
+                && y.skipped_scans < REVALIDATE_AFTER_SCANS
+        }) {
+            skipped
+                .entry(y.extension.clone())
+                .or_default()
+                .insert(y.detector.clone());
+        }
+        Self {
+            skipped,
+            observed: Mutex::default(),
+        }
+    }
+
+    /// Whether `detector` is skipped on files with `extension`
+    pub fn skips(&self, detector: &str, extension: &str) -> bool {
+        self.skipped
+            .get(extension)
+            .is_some_and(|detectors| detectors.contains(detector))
+    }
+
+    /// Number of detector and extension pairs skipped
+    pub fn skipped_count(&self) -> usize {
+        self.skipped.values().map(HashSet::len).sum()
+    }
+
+    /// Records the findings of detectors that ran on one file with `extension`
+    pub(crate) fn record<'a>(
+        &self,
+        extension: &str,
+        findings: impl IntoIterator<Item = (&'a str, usize)>,
+    ) {
+        let mut observed = self.observed.lock().unwrap_or_else(|e| e.into_inner());
+        for (detector, matches) in findings {
+            let totals = observed
+                .entry((detector.to_string(), extension.to_string()))
+                .or_default();
+            totals.0 += 1;
+            totals.1 += matches as u64;
+        }
+    }
+
+    /// `history` with the runs recorded by this scan added and the skipped
+    /// pairs counted, plus the pairs seen for the first time
+    pub fn updated_yields(&self, history: &[DetectorYield]) -> Vec<DetectorYield> {
+        let mut observed = self
+            .observed
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        let mut yields: Vec<DetectorYield> = history
+            .iter()
+            .map(|y| {
+                let key = (y.detector.clone(), y.extension.clone());
+                let mut y = y.clone();
+                if let Some((files, matches)) = observed.remove(&key) {
+                    y.files += files;
+                    y.matches += matches;
+                    y.skipped_scans = 0;
+                } else if self.skips(&y.detector, &y.extension) {
+                    y.skipped_scans += 1;
+                }
+                y
+            })
+            .collect();
+        let mut added: Vec<DetectorYield> = observed
+            .into_iter()
+            .map(|((detector, extension), (files, matches))| DetectorYield {
+                detector,
+                extension,
+                files,
+                matches,
+                skipped_scans: 0,
+            })
+            .collect();
+        added.sort_by(|a, b| (&a.detector, &a.extension).cmp(&(&b.detector, &b.extension)));
+        yields.extend(added);
+        yields
+    }
+}
+
+/// Extension of `path` as yields are keyed by it
+pub fn yield_extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(matches: u64, skipped_scans: u32) -> DetectorYield {
+        DetectorYield {
+            detector: "ConsoleLogDetector".to_string(),
+            extension: "rs".to_string(),
+            files: MIN_FILES_FOR_SKIP,
+            matches,
+            skipped_scans,
+        }
+    }
+
+    #[test]
+    fn test_fast_skips_zero_yield_detectors() {
+        assert!(DetectorSchedule::fast(&[history(0, 0)]).skips("ConsoleLogDetector", "rs"));
+        assert!(!DetectorSchedule::fast(&[history(0, 0)]).skips("ConsoleLogDetector", "js"));
+        assert!(!DetectorSchedule::fast(&[history(1, 0)]).skips("ConsoleLogDetector", "rs"));
+        let few_files = DetectorYield {
+            files: MIN_FILES_FOR_SKIP - 1,
+            ..history(0, 0)
+        };
+        assert!(!DetectorSchedule::fast(&[few_files]).skips("ConsoleLogDetector", "rs"));
+        // Due to re-validate
+        let due = history(0, REVALIDATE_AFTER_SCANS);
+        assert!(!DetectorSchedule::fast(&[due]).skips("ConsoleLogDetector", "rs"));
+        assert!(!DetectorSchedule::default().skips("ConsoleLogDetector", "rs"));
+    }
+
+    #[test]
+    fn test_updated_yields() {
+        let schedule = DetectorSchedule::fast(&[history(0, 3)]);
+        schedule.record("js", [("ConsoleLogDetector", 2), ("TodoDetector", 0)]);
+        schedule.record("js", [("ConsoleLogDetector", 0)]);
+        let yields = schedule.updated_yields(&[history(0, 3)]);
+        assert_eq!(yields[0].skipped_scans, 4);
+        assert_eq!(yields[0].files, MIN_FILES_FOR_SKIP);
+        assert_eq!(yields[1].extension, "js");
+        assert_eq!((yields[1].files, yields[1].matches), (2, 2));
+        assert_eq!(yields[2].detector, "TodoDetector");
+
+        // A re-validation run resets the count
+        let schedule = DetectorSchedule::fast(&[history(0, REVALIDATE_AFTER_SCANS)]);
+        schedule.record("rs", [("ConsoleLogDetector", 0)]);
+        let yields = schedule.updated_yields(&[history(0, REVALIDATE_AFTER_SCANS)]);
+        assert_eq!(yields[0].skipped_scans, 0);
+        assert_eq!(yields[0].files, MIN_FILES_FOR_SKIP + 1);
+    }
+}
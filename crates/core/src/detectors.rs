@@ -1,9 +1,13 @@
-use crate::{Match, PatternDetector};
+use crate::comment_spans::CommentIndex;
+use crate::errors::{ScanError, ScanResult};
+use crate::redaction::finding_message;
+use crate::regex_cache::cached_regex;
+use crate::{LineIndex, Match, PatternDetector, Severity, SharedStr};
 use aho_corasick::AhoCorasick;
-use anyhow::Result;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 lazy_static! {
     pub static ref TODO_REGEX: Regex = Regex::new(r"\b(?i)todo\b").unwrap();
@@ -40,49 +44,147 @@ lazy_static! {
     pub static ref UNUSED_VAR_REGEX: Regex = Regex::new(r"\b(let|var|const)\s+(\w+)\s*[=;].*?\/\/\s*(?i)(unused|not\s+used)").unwrap();
     pub static ref DEAD_CODE_REGEX: Regex = Regex::new(r"\/\/\s*(?i)(dead\s*code|unreachable|never\s+called)").unwrap();
     pub static ref EXPERIMENTAL_REGEX: Regex = Regex::new(r"\b(?i)(experimental|prototype|poc|proof[\s-]of[\s-]concept)\b").unwrap();
+
+    // Issue-tracking patterns (uppercase markers only, so `todo!()` is left alone)
+    pub static ref TRACKED_MARKER_REGEX: Regex = Regex::new(r"\b(TODO|FIXME)\b(\([^)]*\))?").unwrap();
+}
+
+/// Source files of programming languages, for detectors of code constructs
+pub(crate) const CODE_EXTENSIONS: &[&str] = &[
+    "rs", "js", "jsx", "mjs", "cjs", "ts", "tsx", "py", "go", "java", "kt", "kts", "scala", "cs",
+    "c", "h", "cpp", "cc", "cxx", "hpp", "php", "rb", "swift", "dart",
+];
+
+/// Whether `file_path` has one of `extensions`
+pub(crate) fn has_extension(file_path: &Path, extensions: &[&str]) -> bool {
+    file_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.contains(&ext))
+}
+
+/// Sets the severity and category of `matches`, for detectors that report
+/// their own rather than leaving it to the pattern's built-in severity
+pub(crate) fn classify(mut matches: Vec<Match>, severity: Severity, category: &str) -> Vec<Match> {
+    for m in &mut matches {
+        m.severity = Some(severity.clone());
+        m.category = Some(category.to_string());
+    }
+    matches
 }
 
+/// Finds `re` in the whole of `content`, so a match may span lines, e.g.
+/// `unsafe` with its `{` on the next line. Matches are reported where they
+/// start, with the text around the start on its line, and with their end if
+/// they span lines.
 fn detect_pattern_with_context(
     content: &str,
     file_path: &Path,
     pattern_name: &str,
     re: &Regex,
+) -> Vec<Match> {
+    detect_pattern(content, file_path, pattern_name, re, Scope::Anywhere)
+}
+
+/// Like [`detect_pattern_with_context`] for code constructs such as
+/// `.unwrap()`, skipping matches that start inside a comment so commented-out
+/// code is not reported
+pub(crate) fn detect_code_pattern(
+    content: &str,
+    file_path: &Path,
+    pattern_name: &str,
+    re: &Regex,
+) -> Vec<Match> {
+    detect_pattern(content, file_path, pattern_name, re, Scope::Code)
+}
+
+/// Like [`detect_code_pattern`], also skipping matches that start inside a
+/// string literal, for calls such as `eval(` that strings often mention
+pub(crate) fn detect_call_pattern(
+    content: &str,
+    file_path: &Path,
+    pattern_name: &str,
+    re: &Regex,
+) -> Vec<Match> {
+    detect_pattern(
+        content,
+        file_path,
+        pattern_name,
+        re,
+        Scope::CodeOutsideStrings,
+    )
+}
+
+/// Where in a file a pattern counts, by whether its match starts in a comment
+/// or a string literal
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Anywhere,
+    Code,
+    CodeOutsideStrings,
+    Comments,
+}
+
+fn detect_pattern(
+    content: &str,
+    file_path: &Path,
+    pattern_name: &str,
+    re: &Regex,
+    scope: Scope,
 ) -> Vec<Match> {
     let mut matches = smallvec::SmallVec::<[Match; 4]>::new();
-    for (line_idx, line) in content.lines().enumerate() {
-        for mat in re.find_iter(line) {
-            // Extract more context around the match
-            let context_start = mat.start().saturating_sub(10);
-            let context_end = (mat.end() + 20).min(line.len());
-
-            // Find char boundaries for safe slicing
-            let char_indices: Vec<(usize, char)> = line.char_indices().collect();
-            let start_idx = char_indices
-                .iter()
-                .position(|(i, _)| *i >= context_start)
-                .unwrap_or(0);
-            let end_idx = char_indices
-                .iter()
-                .position(|(i, _)| *i >= context_end)
-                .unwrap_or(char_indices.len());
-
-            let match_context = if start_idx < end_idx {
-                char_indices[start_idx..end_idx]
-                    .iter()
-                    .map(|(_, c)| c)
-                    .collect::<String>()
-            } else {
-                line[mat.start()..mat.end()].to_string()
+    // One allocation for the path and none for the pattern, however many hits
+    let mut shared_path = None;
+    let pattern = SharedStr::interned(pattern_name);
+    // Only indexed once there is a match to locate
+    let mut lines = None;
+    let mut comments = None;
+    for mat in re.find_iter(content) {
+        if scope != Scope::Anywhere {
+            let comments = comments.get_or_insert_with(|| CommentIndex::new(content, file_path));
+            let in_comment = comments.is_comment(mat.start());
+            let skip = match scope {
+                Scope::Comments => !in_comment,
+                Scope::CodeOutsideStrings => in_comment || comments.is_string(mat.start()),
+                Scope::Anywhere | Scope::Code => in_comment,
             };
-
-            matches.push(Match {
-                file_path: file_path.to_string_lossy().to_string(),
-                line_number: line_idx + 1,
-                column: mat.start() + 1,
-                pattern: pattern_name.to_string(),
-                message: format!("{}: {}", pattern_name, match_context.trim()),
-            });
+            if skip {
+                continue;
+            }
+        }
+        let lines = lines.get_or_insert_with(|| LineIndex::new(content));
+        let (line_number, column) = lines.position(content, mat.start());
+        let line = lines.line(content, line_number);
+        let start = mat.start() - lines.line_start(line_number);
+
+        // Extract more context around the match, moving both ends forward
+        // to char boundaries for safe slicing
+        let mut context_start = start.saturating_sub(10);
+        while !line.is_char_boundary(context_start) {
+            context_start += 1;
         }
+        let mut context_end = (start + mat.len() + 20).min(line.len());
+        while !line.is_char_boundary(context_end) {
+            context_end += 1;
+        }
+        let match_context = &line[context_start..context_end];
+
+        matches.push(Match {
+            file_path: shared_path
+                .get_or_insert_with(|| SharedStr::from_path(file_path))
+                .clone(),
+            line_number,
+            column,
+            pattern: pattern.clone(),
+            message: finding_message(
+                pattern_name,
+                format!("{}: {}", pattern_name, match_context.trim()),
+            ),
+            severity: None,
+            category: None,
+            end: lines.span_end(content, mat.start(), mat.end()),
+            author: None,
+        });
     }
     matches.into_vec()
 }
@@ -94,6 +196,10 @@ impl PatternDetector for TodoDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
         detect_pattern_with_context(content, file_path, "TODO", &TODO_REGEX)
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&TODO_REGEX)
+    }
 }
 
 /// Default detector for FIXME comments (case-insensitive)
@@ -103,6 +209,10 @@ impl PatternDetector for FixmeDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
         detect_pattern_with_context(content, file_path, "FIXME", &FIXME_REGEX)
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&FIXME_REGEX)
+    }
 }
 
 /// Detector for HACK comments indicating temporary workarounds
@@ -112,6 +222,10 @@ impl PatternDetector for HackDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
         detect_pattern_with_context(content, file_path, "HACK", &HACK_REGEX)
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&HACK_REGEX)
+    }
 }
 
 /// Detector for BUG comments indicating known issues
@@ -121,6 +235,10 @@ impl PatternDetector for BugDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
         detect_pattern_with_context(content, file_path, "BUG", &BUG_REGEX)
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&BUG_REGEX)
+    }
 }
 
 /// Detector for XXX comments indicating urgent attention needed
@@ -130,6 +248,10 @@ impl PatternDetector for XxxDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
         detect_pattern_with_context(content, file_path, "XXX", &XXX_REGEX)
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&XXX_REGEX)
+    }
 }
 
 /// Detector for NOTE comments
@@ -139,6 +261,10 @@ impl PatternDetector for NoteDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
         detect_pattern_with_context(content, file_path, "NOTE", &NOTE_REGEX)
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&NOTE_REGEX)
+    }
 }
 
 /// Detector for WARNING comments
@@ -148,6 +274,10 @@ impl PatternDetector for WarningDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
         detect_pattern_with_context(content, file_path, "WARNING", &WARNING_REGEX)
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&WARNING_REGEX)
+    }
 }
 
 /// Detector for panic! macros in Rust code
@@ -155,13 +285,18 @@ pub struct PanicDetector;
 
 impl PatternDetector for PanicDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        // Only detect in Rust files
-        if let Some(ext) = file_path.extension() {
-            if ext == "rs" {
-                return detect_pattern_with_context(content, file_path, "PANIC", &PANIC_REGEX);
-            }
+        if !self.applies_to(file_path) {
+            return Vec::new();
         }
-        Vec::new()
+        detect_code_pattern(content, file_path, "PANIC", &PANIC_REGEX)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["rs"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&PANIC_REGEX)
     }
 }
 
@@ -170,13 +305,18 @@ pub struct UnwrapDetector;
 
 impl PatternDetector for UnwrapDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        // Only detect in Rust files
-        if let Some(ext) = file_path.extension() {
-            if ext == "rs" {
-                return detect_pattern_with_context(content, file_path, "UNWRAP", &UNWRAP_REGEX);
-            }
+        if !self.applies_to(file_path) {
+            return Vec::new();
         }
-        Vec::new()
+        detect_code_pattern(content, file_path, "UNWRAP", &UNWRAP_REGEX)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["rs"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&UNWRAP_REGEX)
     }
 }
 
@@ -185,13 +325,18 @@ pub struct ExpectDetector;
 
 impl PatternDetector for ExpectDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        // Only detect in Rust files
-        if let Some(ext) = file_path.extension() {
-            if ext == "rs" {
-                return detect_pattern_with_context(content, file_path, "EXPECT", &EXPECT_REGEX);
-            }
+        if !self.applies_to(file_path) {
+            return Vec::new();
         }
-        Vec::new()
+        detect_code_pattern(content, file_path, "EXPECT", &EXPECT_REGEX)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["rs"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&EXPECT_REGEX)
     }
 }
 
@@ -200,18 +345,18 @@ pub struct UnimplementedDetector;
 
 impl PatternDetector for UnimplementedDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        // Only detect in Rust files
-        if let Some(ext) = file_path.extension() {
-            if ext == "rs" {
-                return detect_pattern_with_context(
-                    content,
-                    file_path,
-                    "UNIMPLEMENTED",
-                    &UNIMPLEMENTED_REGEX,
-                );
-            }
+        if !self.applies_to(file_path) {
+            return Vec::new();
         }
-        Vec::new()
+        detect_code_pattern(content, file_path, "UNIMPLEMENTED", &UNIMPLEMENTED_REGEX)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["rs"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&UNIMPLEMENTED_REGEX)
     }
 }
 
@@ -220,18 +365,18 @@ pub struct UnreachableDetector;
 
 impl PatternDetector for UnreachableDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        // Only detect in Rust files
-        if let Some(ext) = file_path.extension() {
-            if ext == "rs" {
-                return detect_pattern_with_context(
-                    content,
-                    file_path,
-                    "UNREACHABLE",
-                    &UNREACHABLE_REGEX,
-                );
-            }
+        if !self.applies_to(file_path) {
+            return Vec::new();
         }
-        Vec::new()
+        detect_code_pattern(content, file_path, "UNREACHABLE", &UNREACHABLE_REGEX)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["rs"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&UNREACHABLE_REGEX)
     }
 }
 
@@ -240,13 +385,18 @@ pub struct CloneDetector;
 
 impl PatternDetector for CloneDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        // Only detect in Rust files
-        if let Some(ext) = file_path.extension() {
-            if ext == "rs" {
-                return detect_pattern_with_context(content, file_path, "CLONE", &CLONE_REGEX);
-            }
+        if !self.applies_to(file_path) {
+            return Vec::new();
         }
-        Vec::new()
+        detect_code_pattern(content, file_path, "CLONE", &CLONE_REGEX)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["rs"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&CLONE_REGEX)
     }
 }
 
@@ -255,18 +405,18 @@ pub struct ToStringDetector;
 
 impl PatternDetector for ToStringDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        // Only detect in Rust files
-        if let Some(ext) = file_path.extension() {
-            if ext == "rs" {
-                return detect_pattern_with_context(
-                    content,
-                    file_path,
-                    "TO_STRING",
-                    &TO_STRING_REGEX,
-                );
-            }
+        if !self.applies_to(file_path) {
+            return Vec::new();
         }
-        Vec::new()
+        detect_code_pattern(content, file_path, "TO_STRING", &TO_STRING_REGEX)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["rs"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&TO_STRING_REGEX)
     }
 }
 
@@ -275,13 +425,18 @@ pub struct UnsafeDetector;
 
 impl PatternDetector for UnsafeDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        // Only detect in Rust files
-        if let Some(ext) = file_path.extension() {
-            if ext == "rs" {
-                return detect_pattern_with_context(content, file_path, "UNSAFE", &UNSAFE_REGEX);
-            }
+        if !self.applies_to(file_path) {
+            return Vec::new();
         }
-        Vec::new()
+        detect_code_pattern(content, file_path, "UNSAFE", &UNSAFE_REGEX)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["rs"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&UNSAFE_REGEX)
     }
 }
 
@@ -292,6 +447,10 @@ impl PatternDetector for DevDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
         detect_pattern_with_context(content, file_path, "DEV", &DEV_REGEX)
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&DEV_REGEX)
+    }
 }
 
 /// Detector for debug-related code
@@ -301,21 +460,116 @@ impl PatternDetector for DebugDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
         detect_pattern_with_context(content, file_path, "DEBUG", &DEBUG_REGEX)
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&DEBUG_REGEX)
+    }
+}
+
+/// Default glob patterns identifying test files, grouped by language
+pub const DEFAULT_TEST_PATH_GLOBS: &[&str] = &[
+    // Conventional test directories
+    "**/test/**",
+    "**/tests/**",
+    "**/__tests__/**",
+    "**/spec/**",
+    "**/testdata/**",
+    // Rust
+    "**/*_test.rs",
+    // Python
+    "**/test_*.py",
+    "**/*_test.py",
+    "**/conftest.py",
+    // JavaScript/TypeScript
+    "**/*.test.{js,jsx,ts,tsx,mjs,cjs}",
+    "**/*.spec.{js,jsx,ts,tsx,mjs,cjs}",
+    // Go
+    "**/*_test.go",
+    // Java/Kotlin/C#
+    "**/src/test/**",
+    "**/*Test.{java,kt,cs}",
+    "**/*Tests.{java,kt,cs}",
+    // Ruby
+    "**/*_spec.rb",
+    "**/*_test.rb",
+];
+
+/// Build a glob set where `*` does not cross path separators
+pub(crate) fn build_glob_set<S: AsRef<str>>(globs: &[S]) -> ScanResult<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(
+            GlobBuilder::new(glob.as_ref())
+                .literal_separator(true)
+                .build()
+                .map_err(ScanError::config)?,
+        );
+    }
+    builder.build().map_err(ScanError::config)
+}
+
+/// Detector for test-related code in production files.
+/// Files matching the test path globs are skipped; when production path globs
+/// are configured, only files matching them are checked.
+pub struct TestDetector {
+    test_paths: GlobSet,
+    production_paths: Option<GlobSet>,
+    root: PathBuf,
+}
+
+impl TestDetector {
+    /// Creates a detector with custom test and production path globs.
+    /// An empty `production_globs` list treats every non-test file as production code.
+    pub fn new<S: AsRef<str>>(test_globs: &[S], production_globs: &[S]) -> ScanResult<Self> {
+        let production_paths = if production_globs.is_empty() {
+            None
+        } else {
+            Some(build_glob_set(production_globs)?)
+        };
+        Ok(Self {
+            test_paths: build_glob_set(test_globs)?,
+            production_paths,
+            root: PathBuf::new(),
+        })
+    }
+
+    /// Matches the globs against paths relative to `root`, the directory
+    /// being scanned, so `src/**` means the `src` directory of the scan root
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = root.into();
+        self
+    }
+
+    /// Returns true if the path should be treated as production code
+    pub fn is_production_path(&self, file_path: &Path) -> bool {
+        let file_path = file_path.strip_prefix(&self.root).unwrap_or(file_path);
+        if self.test_paths.is_match(file_path) {
+            return false;
+        }
+        match &self.production_paths {
+            Some(globs) => globs.is_match(file_path),
+            None => true,
+        }
+    }
 }
 
-/// Detector for test-related code in production files
-pub struct TestDetector;
+impl Default for TestDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_TEST_PATH_GLOBS, &[]).expect("default test globs are valid")
+    }
+}
 
 impl PatternDetector for TestDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        // Skip actual test files
-        if let Some(path_str) = file_path.to_str() {
-            if path_str.contains("test") || path_str.contains("spec") {
-                return Vec::new();
-            }
+        if !self.is_production_path(file_path) {
+            return Vec::new();
         }
         detect_pattern_with_context(content, file_path, "TEST", &TEST_REGEX)
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&TEST_REGEX)
+    }
 }
 
 /// Detector for phase markers in code
@@ -325,6 +579,10 @@ impl PatternDetector for PhaseDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
         detect_pattern_with_context(content, file_path, "PHASE", &PHASE_REGEX)
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&PHASE_REGEX)
+    }
 }
 
 /// Detector for staging environment references
@@ -334,6 +592,10 @@ impl PatternDetector for StagingDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
         detect_pattern_with_context(content, file_path, "STAGING", &STAGING_REGEX)
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&STAGING_REGEX)
+    }
 }
 
 /// Detector for console.log statements (JavaScript/TypeScript)
@@ -341,22 +603,18 @@ pub struct ConsoleLogDetector;
 
 impl PatternDetector for ConsoleLogDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        // Detect in JavaScript/TypeScript files
-        if let Some(ext) = file_path.extension() {
-            let ext_str = ext.to_string_lossy();
-            if matches!(
-                ext_str.as_ref(),
-                "js" | "ts" | "jsx" | "tsx" | "vue" | "svelte"
-            ) {
-                return detect_pattern_with_context(
-                    content,
-                    file_path,
-                    "CONSOLE_LOG",
-                    &CONSOLE_LOG_REGEX,
-                );
-            }
+        if !self.applies_to(file_path) {
+            return Vec::new();
         }
-        Vec::new()
+        detect_code_pattern(content, file_path, "CONSOLE_LOG", &CONSOLE_LOG_REGEX)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["js", "ts", "jsx", "tsx", "vue", "svelte"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&CONSOLE_LOG_REGEX)
     }
 }
 
@@ -365,7 +623,11 @@ pub struct PrintDetector;
 
 impl PatternDetector for PrintDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        detect_pattern_with_context(content, file_path, "PRINT", &PRINT_REGEX)
+        detect_code_pattern(content, file_path, "PRINT", &PRINT_REGEX)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&PRINT_REGEX)
     }
 }
 
@@ -374,17 +636,21 @@ pub struct AlertDetector;
 
 impl PatternDetector for AlertDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        // Detect in JavaScript/TypeScript files
-        if let Some(ext) = file_path.extension() {
-            let ext_str = ext.to_string_lossy();
-            if matches!(
-                ext_str.as_ref(),
-                "js" | "ts" | "jsx" | "tsx" | "html" | "vue" | "svelte"
-            ) {
-                return detect_pattern_with_context(content, file_path, "ALERT", &ALERT_REGEX);
-            }
+        if !self.applies_to(file_path) {
+            return Vec::new();
         }
-        Vec::new()
+        detect_code_pattern(content, file_path, "ALERT", &ALERT_REGEX)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(
+            file_path,
+            &["js", "ts", "jsx", "tsx", "html", "vue", "svelte"],
+        )
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&ALERT_REGEX)
     }
 }
 
@@ -393,7 +659,40 @@ pub struct DebuggerDetector;
 
 impl PatternDetector for DebuggerDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        detect_pattern_with_context(content, file_path, "DEBUGGER", &DEBUGGER_REGEX)
+        detect_code_pattern(content, file_path, "DEBUGGER", &DEBUGGER_REGEX)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&DEBUGGER_REGEX)
+    }
+}
+
+/// Detector for `console.log` and debugger statements that are commented
+/// out. [`ConsoleLogDetector`] and [`DebuggerDetector`] skip these since they
+/// never run; this reports them as `COMMENTED_OUT_CODE` for cleanup instead.
+pub struct CommentedOutCodeDetector;
+
+impl PatternDetector for CommentedOutCodeDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        let mut matches = Vec::new();
+        if ConsoleLogDetector.applies_to(file_path) {
+            matches.extend(detect_pattern(
+                content,
+                file_path,
+                "COMMENTED_OUT_CODE",
+                &CONSOLE_LOG_REGEX,
+                Scope::Comments,
+            ));
+        }
+        matches.extend(detect_pattern(
+            content,
+            file_path,
+            "COMMENTED_OUT_CODE",
+            &DEBUGGER_REGEX,
+            Scope::Comments,
+        ));
+        matches.sort_by_key(|m| (m.line_number, m.column));
+        matches
     }
 }
 
@@ -404,6 +703,10 @@ impl PatternDetector for UnusedVarDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
         detect_pattern_with_context(content, file_path, "UNUSED_VAR", &UNUSED_VAR_REGEX)
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&UNUSED_VAR_REGEX)
+    }
 }
 
 /// Detector for dead code comments
@@ -413,6 +716,10 @@ impl PatternDetector for DeadCodeDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
         detect_pattern_with_context(content, file_path, "DEAD_CODE", &DEAD_CODE_REGEX)
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&DEAD_CODE_REGEX)
+    }
 }
 
 /// Detector for experimental/prototype code
@@ -422,6 +729,10 @@ impl PatternDetector for ExperimentalDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
         detect_pattern_with_context(content, file_path, "EXPERIMENTAL", &EXPERIMENTAL_REGEX)
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&EXPERIMENTAL_REGEX)
+    }
 }
 
 /// Custom pattern detector that uses user-defined regex patterns
@@ -432,8 +743,8 @@ pub struct CustomPatternDetector {
 
 impl CustomPatternDetector {
     /// Creates a new custom pattern detector with the given name and regex pattern
-    pub fn new(name: &str, pattern: &str) -> Result<Self> {
-        let regex = Regex::new(pattern)?;
+    pub fn new(name: &str, pattern: &str) -> ScanResult<Self> {
+        let regex = cached_regex(pattern).map_err(|e| ScanError::detector(name, e))?;
         Ok(Self {
             name: name.to_string(),
             regex,
@@ -445,23 +756,156 @@ impl PatternDetector for CustomPatternDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
         detect_pattern_with_context(content, file_path, &self.name, &self.regex)
     }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&self.regex)
+    }
+
+    fn config_fingerprint(&self) -> String {
+        self.regex.as_str().to_string()
+    }
+}
+
+/// Default issue reference: `#123`, a JIRA-style key such as `PROJ-42`, or an issue URL
+pub const DEFAULT_ISSUE_REFERENCE_PATTERN: &str =
+    r"#\d+|\b[A-Z][A-Z0-9]+-\d+\b|https?://\S+/issues/\d+";
+
+/// Detector for TODO/FIXME markers in comments that lack an issue reference
+/// on the same line. Markers in code, such as a `"TODO"` string literal or an
+/// identifier, and in files of languages without known comment syntax are
+/// left alone, see [`CommentIndex`].
+pub struct OrphanTodoDetector {
+    issue_reference: Regex,
+}
+
+impl OrphanTodoDetector {
+    /// Creates a detector that accepts lines matching the given issue reference regex
+    pub fn new(issue_reference_pattern: &str) -> ScanResult<Self> {
+        Ok(Self {
+            issue_reference: cached_regex(issue_reference_pattern)
+                .map_err(|e| ScanError::detector("ORPHAN_TODO", e))?,
+        })
+    }
+
+    /// The first marker of `line`, which starts at byte `offset` of its file,
+    /// that is in a comment, unless the line references an issue
+    fn orphan_marker<'l>(
+        &self,
+        line: &'l str,
+        offset: usize,
+        comments: &CommentIndex,
+    ) -> Option<regex::Captures<'l>> {
+        if self.issue_reference.is_match(line) {
+            return None;
+        }
+        TRACKED_MARKER_REGEX
+            .captures_iter(line)
+            .find(|caps| comments.is_comment(offset + caps.get(0).map_or(0, |m| m.start())))
+    }
+
+    /// Inserts `placeholder` into every orphan TODO/FIXME marker of
+    /// `content`, read as the language of `file_path`, turning `TODO: x` into
+    /// `TODO(#TBD): x` and `TODO(alice): x` into `TODO(alice, #TBD): x`. Only
+    /// markers in comments are changed. Returns the rewritten content and the
+    /// number of markers changed.
+    pub fn add_issue_placeholder(
+        &self,
+        content: &str,
+        file_path: &Path,
+        placeholder: &str,
+    ) -> (String, usize) {
+        let comments = CommentIndex::new(content, file_path);
+        let mut fixed = String::with_capacity(content.len());
+        let mut count = 0;
+        let mut offset = 0;
+        for line in content.split_inclusive('\n') {
+            match self.orphan_marker(line, offset, &comments) {
+                Some(caps) => {
+                    let marker = caps.get(0).expect("group 0 is the whole match");
+                    fixed.push_str(&line[..marker.start()]);
+                    match caps.get(2) {
+                        Some(owner) => fixed.push_str(&format!(
+                            "{}({}, {})",
+                            &caps[1],
+                            &owner.as_str()[1..owner.as_str().len() - 1],
+                            placeholder
+                        )),
+                        None => fixed.push_str(&format!("{}({})", &caps[1], placeholder)),
+                    }
+                    fixed.push_str(&line[marker.end()..]);
+                    count += 1;
+                }
+                None => fixed.push_str(line),
+            }
+            offset += line.len();
+        }
+        (fixed, count)
+    }
+}
+
+impl Default for OrphanTodoDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_ISSUE_REFERENCE_PATTERN).expect("default issue pattern is valid")
+    }
+}
+
+impl PatternDetector for OrphanTodoDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        let shared_path = SharedStr::from_path(file_path);
+        let comments = CommentIndex::new(content, file_path);
+        let mut matches = Vec::new();
+        let mut offset = 0;
+        for (line_idx, line) in content.split_inclusive('\n').enumerate() {
+            if let Some(mat) = self
+                .orphan_marker(line, offset, &comments)
+                .and_then(|caps| caps.get(0))
+            {
+                matches.push(Match {
+                    file_path: shared_path.clone(),
+                    line_number: line_idx + 1,
+                    column: mat.start() + 1,
+                    pattern: SharedStr::interned("ORPHAN_TODO"),
+                    message: format!("ORPHAN_TODO: {}", line.trim()),
+                    severity: None,
+                    category: None,
+                    end: None,
+                    author: None,
+                });
+            }
+            offset += line.len();
+        }
+        matches
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&TRACKED_MARKER_REGEX)
+    }
+
+    fn config_fingerprint(&self) -> String {
+        self.issue_reference.as_str().to_string()
+    }
 }
 
 /// High-performance detector using Aho-Corasick algorithm for multiple pattern matching
 pub struct HighPerformanceDetector {
-    pattern_names: Vec<String>,
+    pattern_names: Vec<SharedStr>,
     ac: AhoCorasick,
 }
 
 impl HighPerformanceDetector {
     /// Creates a new high-performance detector with the given patterns
-    pub fn new(patterns: Vec<(&str, &str)>) -> Result<Self> {
-        let (pattern_names, pattern_strings): (Vec<String>, Vec<String>) = patterns
+    pub fn new(patterns: Vec<(&str, &str)>) -> ScanResult<Self> {
+        let (pattern_names, pattern_strings): (Vec<SharedStr>, Vec<String>) = patterns
             .into_iter()
-            .map(|(name, pattern)| (name.to_string(), pattern.to_string()))
+            .map(|(name, pattern)| (SharedStr::interned(name), pattern.to_string()))
             .unzip();
 
-        let ac = AhoCorasick::new(&pattern_strings)?;
+        let ac = AhoCorasick::new(&pattern_strings)
+            .map_err(|e| ScanError::detector("HighPerformanceDetector", e))?;
 
         Ok(Self { pattern_names, ac })
     }
@@ -494,7 +938,12 @@ impl HighPerformanceDetector {
 
 impl PatternDetector for HighPerformanceDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        self.detect_with_lines(content, file_path, &LineIndex::new(content))
+    }
+
+    fn detect_with_lines(&self, content: &str, file_path: &Path, lines: &LineIndex) -> Vec<Match> {
         let mut matches = Vec::new();
+        let mut shared_path = None;
 
         for mat in self.ac.find_iter(content) {
             let pattern_id = mat.pattern();
@@ -505,20 +954,21 @@ impl PatternDetector for HighPerformanceDetector {
             let end = (mat.end() + 25).min(content.len());
             let match_context = &content[start..end];
 
-            // Find the line number
-            let line_start = content[..mat.start()]
-                .rfind('\n')
-                .map(|pos| pos + 1)
-                .unwrap_or(0);
-            let line_number = content[..line_start].lines().count() + 1;
-            let column = mat.start() - line_start + 1;
+            let line_number = lines.line_of(mat.start());
+            let column = mat.start() - lines.line_start(line_number) + 1;
 
             matches.push(Match {
-                file_path: file_path.to_string_lossy().to_string(),
+                file_path: shared_path
+                    .get_or_insert_with(|| SharedStr::from_path(file_path))
+                    .clone(),
                 line_number,
                 column,
                 pattern: pattern_name.clone(),
                 message: format!("{}: {}", pattern_name, match_context.trim()),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             });
         }
 
@@ -529,6 +979,7 @@ impl PatternDetector for HighPerformanceDetector {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::LineColumn;
     use std::path::PathBuf;
 
     #[test]
@@ -567,6 +1018,42 @@ mod tests {
         assert_eq!(matches[0].pattern, "UNWRAP");
     }
 
+    #[test]
+    fn test_multi_line_matches() {
+        let content =
+            "fn f() {\n    let p = unsafe\n    {\n        *ptr\n    };\n    x.unwrap();\n}\n";
+        let path = PathBuf::from("test.rs");
+
+        let matches = UnsafeDetector.detect(content, &path);
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].line_number, matches[0].column), (2, 13));
+        assert_eq!(matches[0].end, Some(LineColumn { line: 3, column: 6 }));
+        assert_eq!(matches[0].message, "UNSAFE: let p = unsafe");
+
+        let matches = UnwrapDetector.detect(content, &path);
+        assert_eq!((matches[0].line_number, matches[0].column), (6, 6));
+        assert_eq!(matches[0].end, None);
+    }
+
+    #[test]
+    fn test_comment_awareness() {
+        let content = "/*\n * FIXME: first\n * and FIXME: second\n */\n// let v = x.unwrap();\nlet w = y.unwrap(); // was z.unwrap()\n";
+        let path = PathBuf::from("lib.rs");
+
+        let fixmes = FixmeDetector.detect(content, &path);
+        let positions: Vec<_> = fixmes.iter().map(|m| (m.line_number, m.column)).collect();
+        assert_eq!(positions, vec![(2, 4), (3, 8)]);
+
+        let unwraps = UnwrapDetector.detect(content, &path);
+        assert_eq!(unwraps.len(), 1);
+        assert_eq!((unwraps[0].line_number, unwraps[0].column), (6, 10));
+
+        let docstring = "def f():\n    \"\"\"Example:\n    print(x)\n    \"\"\"\n    print(y)\n";
+        let prints = PrintDetector.detect(docstring, Path::new("app.py"));
+        assert_eq!(prints.len(), 1);
+        assert_eq!(prints[0].line_number, 5);
+    }
+
     #[test]
     fn test_case_insensitive_todo() {
         let detector = TodoDetector;
@@ -621,6 +1108,23 @@ mod tests {
         assert!(matches.iter().all(|m| m.pattern == "DEBUGGER"));
     }
 
+    #[test]
+    fn test_commented_out_code_detector() {
+        let content = "console.log('live');\n// console.log('old');\n/* debugger; */\ndebugger;";
+        let path = PathBuf::from("app.js");
+        let matches = CommentedOutCodeDetector.detect(content, &path);
+        let lines: Vec<usize> = matches.iter().map(|m| m.line_number).collect();
+        assert_eq!(lines, vec![2, 3]);
+        assert!(matches.iter().all(|m| m.pattern == "COMMENTED_OUT_CODE"));
+        assert_eq!(ConsoleLogDetector.detect(content, &path).len(), 1);
+        assert_eq!(DebuggerDetector.detect(content, &path).len(), 1);
+
+        let python = "# console.log('x')\n# breakpoint()";
+        let matches = CommentedOutCodeDetector.detect(python, Path::new("app.py"));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line_number, 2);
+    }
+
     #[test]
     fn test_phase_detector() {
         let detector = PhaseDetector;
@@ -673,7 +1177,7 @@ mod tests {
 
     #[test]
     fn test_test_detector_skips_test_files() {
-        let detector = TestDetector;
+        let detector = TestDetector::default();
         let content = "// test implementation";
 
         let test_path = PathBuf::from("src/test/test_module.rs");
@@ -685,4 +1189,130 @@ mod tests {
         assert_eq!(test_matches.len(), 0); // Should skip test files
         assert_eq!(prod_matches.len(), 1); // Should detect in production files
     }
+
+    #[test]
+    fn test_test_detector_language_defaults() {
+        let detector = TestDetector::default();
+        let content = "// test implementation";
+
+        for test_file in [
+            "pkg/server_test.go",
+            "web/app.spec.ts",
+            "web/__tests__/app.js",
+            "py/test_models.py",
+            "java/src/test/java/AppTest.java",
+        ] {
+            assert!(
+                detector.detect(content, Path::new(test_file)).is_empty(),
+                "{} should be treated as a test file",
+                test_file
+            );
+        }
+
+        // Substrings of "test"/"spec" in production names are no longer skipped
+        for prod_file in ["src/contest.rs", "src/inspector.ts", "src/latest.py"] {
+            assert_eq!(detector.detect(content, Path::new(prod_file)).len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_test_detector_custom_globs() {
+        let detector = TestDetector::new(&["**/qa/**"], &["src/**"]).unwrap();
+        let content = "// testing hook";
+
+        assert_eq!(detector.detect(content, Path::new("src/lib.rs")).len(), 1);
+        assert!(detector
+            .detect(content, Path::new("src/qa/lib.rs"))
+            .is_empty());
+        assert!(detector
+            .detect(content, Path::new("scripts/run.sh"))
+            .is_empty());
+        assert!(TestDetector::new(&["[invalid"], &[]).is_err());
+    }
+
+    #[test]
+    fn test_test_detector_globs_relative_to_scan_root() {
+        let dir = tempfile::tempdir().unwrap();
+        for file in ["src/lib.rs", "src/qa/lib.rs", "scripts/run.sh"] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "// testing hook\n").unwrap();
+        }
+
+        let scan = |detector: TestDetector| {
+            let matches = crate::Scanner::new(vec![Box::new(detector)])
+                .scan(dir.path())
+                .unwrap();
+            let mut files: Vec<String> = matches
+                .iter()
+                .map(|m| m.file_path.as_str().replace('\\', "/"))
+                .collect();
+            files.sort();
+            files
+        };
+        let detector = TestDetector::new(&["**/qa/**"], &["src/**"]).unwrap();
+        // The scanner reports paths below the root, such as /tmp/x/src/lib.rs
+        assert_eq!(
+            scan(detector.with_root(dir.path())),
+            vec![format!("{}/src/lib.rs", dir.path().display()).replace('\\', "/")]
+        );
+    }
+
+    #[test]
+    fn test_orphan_todo_detector() {
+        let detector = OrphanTodoDetector::default();
+        let content = "// TODO: refactor\n// TODO(#123): tracked\n// FIXME PROJ-42 later\n// FIXME(alice): check\ntodo!()\n";
+        let matches = detector.detect(content, Path::new("lib.rs"));
+        let lines: Vec<usize> = matches.iter().map(|m| m.line_number).collect();
+        assert_eq!(lines, vec![1, 4]);
+        assert!(matches.iter().all(|m| m.pattern == "ORPHAN_TODO"));
+
+        let custom = OrphanTodoDetector::new(r"GH-\d+").unwrap();
+        assert_eq!(
+            custom
+                .detect("// TODO GH-7\n// TODO #7\n", Path::new("a.rs"))
+                .len(),
+            1
+        );
+        assert!(OrphanTodoDetector::new("(").is_err());
+    }
+
+    #[test]
+    fn test_orphan_todo_add_issue_placeholder() {
+        let detector = OrphanTodoDetector::default();
+        let content = "// TODO: refactor\n// TODO(#1): done\n/* FIXME(alice): check */\nlet x = 1;";
+        let (fixed, count) = detector.add_issue_placeholder(content, Path::new("lib.rs"), "#TBD");
+        assert_eq!(count, 2);
+        assert_eq!(
+            fixed,
+            "// TODO(#TBD): refactor\n// TODO(#1): done\n/* FIXME(alice, #TBD): check */\nlet x = 1;"
+        );
+        let (fixed, count) =
+            detector.add_issue_placeholder("# TODO: pin\n", Path::new("run.sh"), "#TBD");
+        assert_eq!((fixed.as_str(), count), ("# TODO(#TBD): pin\n", 1));
+    }
+
+    #[test]
+    fn test_orphan_todo_ignores_code() {
+        let detector = OrphanTodoDetector::default();
+        // String literals and identifiers are code, so only the comment changes
+        let content =
+            "let kind = \"TODO\";\nlet TODO = FIXME(1);\nlet s = \"TODO\"; // TODO: rename\n";
+        let matches = detector.detect(content, Path::new("lib.rs"));
+        let positions: Vec<(usize, usize)> =
+            matches.iter().map(|m| (m.line_number, m.column)).collect();
+        assert_eq!(positions, vec![(3, 20)]);
+
+        let (fixed, count) = detector.add_issue_placeholder(content, Path::new("lib.rs"), "#TBD");
+        assert_eq!(count, 1);
+        assert_eq!(
+            fixed,
+            "let kind = \"TODO\";\nlet TODO = FIXME(1);\nlet s = \"TODO\"; // TODO(#TBD): rename\n"
+        );
+        // Without known comment syntax nothing is a comment
+        assert_eq!(
+            detector.add_issue_placeholder("TODO: x\n", Path::new("notes.adoc"), "#TBD"),
+            ("TODO: x\n".to_string(), 0)
+        );
+    }
 }
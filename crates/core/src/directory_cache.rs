@@ -0,0 +1,373 @@
+//! Directory listings kept between incremental scans. A directory whose
+//! modification time hasn't changed still has the entries it was listed
+//! with, so its recorded listing stands in for reading it again, which is
+//! most of a walk on network file systems and large checkouts. Files are
+//! still checked one by one by the incremental scanner, so edits to them
+//! are never missed.
+
+use crate::{content_hash, scan_walker_with, windows_paths, ScanError, ScanResult, WalkOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Ignore files whose rules apply to a directory and everything below it
+const IGNORE_FILES: &[&str] = &[".gitignore", ".ignore"];
+
+/// A directory modified this close to when it was listed may have changed
+/// again within the same timestamp tick, which is coarse on FAT and some
+/// NFS servers, so its listing is not trusted
+const MTIME_GRANULARITY: Duration = Duration::from_secs(2);
+
+/// The entries of one directory that pass the walk's filters
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectoryListing {
+    /// Modification time of the directory, in nanoseconds since the epoch
+    pub modified_ns: u64,
+    /// When the directory was read, in nanoseconds since the epoch
+    pub listed_at_ns: u64,
+    /// Modification times of the ignore files in the directory, by name
+    #[serde(default)]
+    pub ignore_files: BTreeMap<String, u64>,
+    pub files: Vec<PathBuf>,
+    pub dirs: Vec<PathBuf>,
+}
+
+impl DirectoryListing {
+    /// Whether the listing still holds for a directory last modified at
+    /// `modified_ns` with `ignore_files`
+    fn is_current(&self, modified_ns: u64, ignore_files: &BTreeMap<String, u64>) -> bool {
+        self.modified_ns == modified_ns
+            && &self.ignore_files == ignore_files
+            && self.modified_ns + (MTIME_GRANULARITY.as_nanos() as u64) < self.listed_at_ns
+    }
+}
+
+/// Directories read and reused by [`DirectoryCache::collect_files`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DirectoryWalkStats {
+    pub reused: usize,
+    pub listed: usize,
+}
+
+/// Listings of the directories of a tree, keyed by path
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DirectoryCache {
+    /// Hash of the walk settings and the ignore files above the root the
+    /// listings were made with; listings made with others are dropped
+    fingerprint: u64,
+    listings: HashMap<PathBuf, DirectoryListing>,
+}
+
+impl DirectoryCache {
+    /// Whether listings can stand in for walking with `options`. Depth
+    /// limits and followed symlinks depend on the path a directory is
+    /// reached by, which a listing doesn't record.
+    pub fn supports(options: &WalkOptions) -> bool {
+        options.max_depth.is_none() && !options.follow_symlinks
+    }
+
+    pub fn len(&self) -> usize {
+        self.listings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.listings.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.listings.clear();
+    }
+
+    /// The files under `root` that [`scan_walker_with`] finds with
+    /// `options`, reading only directories that changed since they were
+    /// listed and recording the listings of those that did. A directory is
+    /// read again when its modification time or its ignore files changed,
+    /// and every directory below it too when its ignore files did. The
+    /// generated file filter reads file headers, so it is applied to listed
+    /// files every time instead of being recorded.
+    pub fn collect_files(
+        &mut self,
+        root: &Path,
+        options: &WalkOptions,
+    ) -> ScanResult<(Vec<PathBuf>, DirectoryWalkStats)> {
+        let fingerprint = walk_fingerprint(root, options);
+        if fingerprint != self.fingerprint {
+            self.listings.clear();
+            self.fingerprint = fingerprint;
+        }
+        let listing_options = WalkOptions {
+            generated_filter: None,
+            ..options.clone()
+        };
+
+        let mut previous = std::mem::take(&mut self.listings);
+        let mut stats = DirectoryWalkStats::default();
+        let mut files = Vec::new();
+        // Directories to visit, and whether ignore rules above them changed
+        let mut pending = vec![(root.to_path_buf(), false)];
+        while let Some((dir, ignores_changed)) = pending.pop() {
+            let modified_ns = modified_ns(&dir).map_err(|e| ScanError::io(&dir, e))?;
+            let ignore_files = ignore_file_times(&dir, options);
+            let recorded = previous.remove(&dir);
+            let child_ignores_changed = ignores_changed
+                || recorded
+                    .as_ref()
+                    .is_some_and(|listing| listing.ignore_files != ignore_files);
+            let listing = match recorded {
+                Some(listing)
+                    if !ignores_changed && listing.is_current(modified_ns, &ignore_files) =>
+                {
+                    stats.reused += 1;
+                    listing
+                }
+                _ => {
+                    stats.listed += 1;
+                    list_directory(&dir, &listing_options, modified_ns, ignore_files)?
+                }
+            };
+            pending.extend(
+                listing
+                    .dirs
+                    .iter()
+                    .map(|subdir| (subdir.clone(), child_ignores_changed)),
+            );
+            files.extend(
+                listing
+                    .files
+                    .iter()
+                    .filter(|file| {
+                        options
+                            .generated_filter
+                            .as_ref()
+                            .map_or(true, |filter| !filter.skip(file))
+                    })
+                    .cloned(),
+            );
+            self.listings.insert(dir, listing);
+        }
+        Ok((files, stats))
+    }
+}
+
+/// Reads the entries of `dir` that pass the filters of `options`
+fn list_directory(
+    dir: &Path,
+    options: &WalkOptions,
+    modified_ns: u64,
+    ignore_files: BTreeMap<String, u64>,
+) -> ScanResult<DirectoryListing> {
+    let mut listing = DirectoryListing {
+        modified_ns,
+        listed_at_ns: nanos_since_epoch(SystemTime::now()),
+        ignore_files,
+        ..DirectoryListing::default()
+    };
+    let mut walker = scan_walker_with(dir, options);
+    walker.max_depth(Some(1));
+    for entry in walker.build() {
+        let entry = entry.map_err(|e| ScanError::from_walk(dir, e))?;
+        if entry.depth() == 0 {
+            continue;
+        }
+        match entry.file_type() {
+            Some(file_type) if file_type.is_dir() => listing.dirs.push(entry.into_path()),
+            Some(file_type) if file_type.is_file() => listing.files.push(entry.into_path()),
+            _ => {}
+        }
+    }
+    Ok(listing)
+}
+
+fn modified_ns(path: &Path) -> std::io::Result<u64> {
+    Ok(nanos_since_epoch(std::fs::metadata(path)?.modified()?))
+}
+
+fn nanos_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
+}
+
+/// Modification times of the ignore files in `dir` the walk reads
+fn ignore_file_times(dir: &Path, options: &WalkOptions) -> BTreeMap<String, u64> {
+    if !options.respect_gitignore {
+        return BTreeMap::new();
+    }
+    IGNORE_FILES
+        .iter()
+        .filter_map(|name| Some((name.to_string(), modified_ns(&dir.join(name)).ok()?)))
+        .collect()
+}
+
+/// Hash of the settings of `options` that decide which entries a walk
+/// keeps, and of the ignore files above `root` and the repository's
+/// `.git/info/exclude`, which apply to the whole tree
+fn walk_fingerprint(root: &Path, options: &WalkOptions) -> u64 {
+    let mut key = format!(
+        "hidden={} gitignore={} global={} excluded={:?}",
+        options.hidden,
+        options.respect_gitignore,
+        options.respect_global_ignore,
+        options.excluded_dirs.names()
+    );
+    if options.respect_gitignore {
+        let root = windows_paths::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+        for (depth, dir) in root.ancestors().enumerate() {
+            let ignore_files = if depth == 0 { &[][..] } else { IGNORE_FILES };
+            for name in ignore_files.iter().chain([&".git/info/exclude"]) {
+                if let Ok(modified) = modified_ns(&dir.join(name)) {
+                    key.push_str(&format!("\n{}/{}={}", dir.display(), name, modified));
+                }
+            }
+        }
+    }
+    content_hash(key.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Ages the listings so they count as settled, as they would be by the
+    /// next scan
+    fn settle(cache: &mut DirectoryCache) {
+        for listing in cache.listings.values_mut() {
+            listing.listed_at_ns += 2 * MTIME_GRANULARITY.as_nanos() as u64;
+        }
+    }
+
+    fn sorted(mut files: Vec<PathBuf>) -> Vec<PathBuf> {
+        files.sort();
+        files
+    }
+
+    fn walked(root: &Path, options: &WalkOptions) -> Vec<PathBuf> {
+        sorted(
+            scan_walker_with(root, options)
+                .build()
+                .flatten()
+                .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+                .map(|entry| entry.into_path())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_reuses_unchanged_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::create_dir_all(root.join("c")).unwrap();
+        fs::create_dir_all(root.join("node_modules/x")).unwrap();
+        fs::write(root.join("main.rs"), "").unwrap();
+        fs::write(root.join("a/b/lib.rs"), "").unwrap();
+        fs::write(root.join("c/util.rs"), "").unwrap();
+        fs::write(root.join("node_modules/x/index.js"), "").unwrap();
+        fs::write(root.join(".hidden.rs"), "").unwrap();
+        let options = WalkOptions::default();
+        assert!(DirectoryCache::supports(&options));
+
+        let mut cache = DirectoryCache::default();
+        let (files, stats) = cache.collect_files(root, &options).unwrap();
+        assert_eq!(sorted(files), walked(root, &options));
+        assert_eq!(
+            stats,
+            DirectoryWalkStats {
+                reused: 0,
+                listed: 4
+            }
+        );
+
+        settle(&mut cache);
+        let (files, stats) = cache.collect_files(root, &options).unwrap();
+        assert_eq!(sorted(files), walked(root, &options));
+        assert_eq!(
+            stats,
+            DirectoryWalkStats {
+                reused: 4,
+                listed: 0
+            }
+        );
+
+        // A new file changes its directory's mtime
+        fs::write(root.join("c/new.rs"), "").unwrap();
+        let (files, stats) = cache.collect_files(root, &options).unwrap();
+        assert_eq!(sorted(files), walked(root, &options));
+        assert!(stats.listed >= 1);
+        assert!(cache.listings[&root.join("c")]
+            .files
+            .contains(&root.join("c/new.rs")));
+    }
+
+    #[test]
+    fn test_recent_listings_are_not_trusted() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.rs"), "").unwrap();
+        let options = WalkOptions::default();
+        let mut cache = DirectoryCache::default();
+        cache.collect_files(temp_dir.path(), &options).unwrap();
+        // Listed within the timestamp granularity of the directory's mtime
+        let (_, stats) = cache.collect_files(temp_dir.path(), &options).unwrap();
+        assert_eq!(
+            stats,
+            DirectoryWalkStats {
+                reused: 0,
+                listed: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_changed_ignore_file_relists_subtree() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join("src/gen")).unwrap();
+        fs::write(root.join(".gitignore"), "").unwrap();
+        fs::write(root.join("src/gen/out.rs"), "").unwrap();
+        let options = WalkOptions::default();
+        let mut cache = DirectoryCache::default();
+        cache.collect_files(root, &options).unwrap();
+        settle(&mut cache);
+
+        // Edited in place, which leaves the directory's mtime alone
+        fs::write(root.join(".gitignore"), "gen/\n").unwrap();
+        let (files, stats) = cache.collect_files(root, &options).unwrap();
+        assert!(files.is_empty());
+        assert_eq!(stats.reused, 0);
+        assert_eq!(sorted(files), walked(root, &options));
+    }
+
+    #[test]
+    fn test_changed_walk_options_drop_listings() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("out")).unwrap();
+        fs::write(temp_dir.path().join("out/a.rs"), "").unwrap();
+        let mut cache = DirectoryCache::default();
+        cache
+            .collect_files(temp_dir.path(), &WalkOptions::default())
+            .unwrap();
+        settle(&mut cache);
+
+        let options = WalkOptions {
+            excluded_dirs: std::sync::Arc::new(crate::ExcludedDirs::new(&["out"])),
+            ..WalkOptions::default()
+        };
+        let (files, stats) = cache.collect_files(temp_dir.path(), &options).unwrap();
+        assert!(files.is_empty());
+        assert_eq!(
+            stats,
+            DirectoryWalkStats {
+                reused: 0,
+                listed: 1
+            }
+        );
+        assert!(!DirectoryCache::supports(&WalkOptions {
+            max_depth: Some(2),
+            ..WalkOptions::default()
+        }));
+    }
+}
@@ -1,7 +1,9 @@
-use crate::{Match, PatternDetector, PerformanceMonitor};
-use anyhow::Result;
+use crate::{
+    read_stable, Icon, Match, PatternDetector, PerformanceMonitor, ScanError, ScanResult,
+    SkipReason, SkippedFile,
+};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -28,7 +30,8 @@ pub struct WorkResult {
     pub files_processed: usize,
     pub processing_time_ms: u64,
     pub timestamp: u64,
-    pub errors: Vec<String>,
+    /// Files in the unit that could not be read; binary files are not listed
+    pub skipped_files: Vec<SkippedFile>,
 }
 
 /// Worker node configuration
@@ -42,6 +45,116 @@ pub struct WorkerConfig {
     pub endpoint: Option<String>, // For remote workers
 }
 
+/// Totals for one worker across the work units it completed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkerMetrics {
+    pub worker_id: String,
+    pub units_completed: usize,
+    pub files_processed: usize,
+    pub matches_found: usize,
+    pub processing_time_ms: u64,
+    pub files_skipped: usize,
+}
+
+/// One consolidated result built from every worker's [`WorkResult`]s
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AggregatedScan {
+    /// Matches sorted by file, line, column and pattern, without duplicates
+    pub matches: Vec<Match>,
+    pub files_processed: usize,
+    /// Unreadable files, sorted by path, each listed once
+    pub skipped_files: Vec<SkippedFile>,
+    /// Per-worker totals, sorted by worker ID
+    pub workers: Vec<WorkerMetrics>,
+    /// Results for a unit that had already been received, e.g. after a retry
+    pub duplicate_units: usize,
+    /// Matches reported more than once, e.g. for a file at a unit boundary
+    pub duplicate_matches: usize,
+}
+
+/// Merges the results streamed back by workers into one scan. Results may
+/// arrive in any order and more than once; the aggregate is the same for any
+/// arrival order.
+#[derive(Debug, Default)]
+pub struct ResultAggregator {
+    units: HashSet<String>,
+    matches: Vec<Match>,
+    skipped_files: BTreeMap<String, SkippedFile>,
+    workers: BTreeMap<String, WorkerMetrics>,
+    files_processed: usize,
+    duplicate_units: usize,
+}
+
+impl ResultAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a worker's result; a unit already received is only counted as a duplicate
+    pub fn add(&mut self, result: WorkResult) {
+        if !self.units.insert(result.unit_id.clone()) {
+            self.duplicate_units += 1;
+            return;
+        }
+        let worker = self
+            .workers
+            .entry(result.worker_id.clone())
+            .or_insert_with(|| WorkerMetrics {
+                worker_id: result.worker_id.clone(),
+                ..WorkerMetrics::default()
+            });
+        worker.units_completed += 1;
+        worker.files_processed += result.files_processed;
+        worker.matches_found += result.matches.len();
+        worker.processing_time_ms += result.processing_time_ms;
+        worker.files_skipped += result.skipped_files.len();
+
+        self.files_processed += result.files_processed;
+        self.matches.extend(result.matches);
+        for file in result.skipped_files {
+            self.skipped_files.entry(file.path.clone()).or_insert(file);
+        }
+    }
+
+    pub fn finish(mut self) -> AggregatedScan {
+        self.matches.sort_by(|a, b| {
+            (
+                &a.file_path,
+                a.line_number,
+                a.column,
+                &a.pattern,
+                &a.message,
+            )
+                .cmp(&(
+                    &b.file_path,
+                    b.line_number,
+                    b.column,
+                    &b.pattern,
+                    &b.message,
+                ))
+        });
+        let reported = self.matches.len();
+        self.matches.dedup();
+
+        AggregatedScan {
+            duplicate_matches: reported - self.matches.len(),
+            matches: self.matches,
+            files_processed: self.files_processed,
+            skipped_files: self.skipped_files.into_values().collect(),
+            workers: self.workers.into_values().collect(),
+            duplicate_units: self.duplicate_units,
+        }
+    }
+}
+
+impl Extend<WorkResult> for ResultAggregator {
+    fn extend<I: IntoIterator<Item = WorkResult>>(&mut self, results: I) {
+        for result in results {
+            self.add(result);
+        }
+    }
+}
+
 /// Distributed scan coordinator with performance monitoring
 pub struct DistributedCoordinator {
     workers: Vec<WorkerConfig>,
@@ -65,8 +178,11 @@ impl DistributedCoordinator {
     /// Register a worker node
     pub fn register_worker(&mut self, config: WorkerConfig) {
         info!(
-            "🤖 Registered worker: {} (cores: {}, memory: {}MB)",
-            config.worker_id, config.cpu_cores, config.memory_limit_mb
+            "{} Registered worker: {} (cores: {}, memory: {}MB)",
+            Icon::Worker,
+            config.worker_id,
+            config.cpu_cores,
+            config.memory_limit_mb
         );
         self.workers.push(config);
     }
@@ -76,8 +192,15 @@ impl DistributedCoordinator {
         self.detectors.insert(name, detector);
     }
 
+    /// Names of the registered detectors, sorted
+    pub fn detector_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.detectors.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
     /// Create work units from file list
-    pub fn create_work_units(&mut self, files: Vec<PathBuf>, batch_size: usize) -> Result<()> {
+    pub fn create_work_units(&mut self, files: Vec<PathBuf>, batch_size: usize) -> ScanResult<()> {
         for (unit_id, chunk) in files.chunks(batch_size).enumerate() {
             let estimated_duration = self.estimate_processing_time(chunk);
 
@@ -93,23 +216,27 @@ impl DistributedCoordinator {
         }
 
         // Sort by priority (higher priority first)
-        self.work_queue.sort_by(|a, b| b.priority.cmp(&a.priority));
+        self.work_queue
+            .sort_by_key(|b| std::cmp::Reverse(b.priority));
 
         info!(
-            "📦 Created {} work units from {} files",
+            "{} Created {} work units from {} files",
+            Icon::Package,
             self.work_queue.len(),
             files.len()
         );
         Ok(())
     }
 
-    /// Distribute and execute work units with performance monitoring
-    pub async fn execute_distributed_scan(&mut self) -> Result<Vec<Match>> {
+    /// Distribute and execute work units with performance monitoring,
+    /// returning the workers' results merged by [`ResultAggregator`]
+    pub async fn execute_distributed_scan(&mut self) -> ScanResult<AggregatedScan> {
         let start_time = Instant::now();
         let total_units = self.work_queue.len();
 
         info!(
-            "🚀 Starting distributed scan with {} workers and {} work units",
+            "{} Starting distributed scan with {} workers and {} work units",
+            Icon::Launch,
             self.workers.len(),
             total_units
         );
@@ -122,29 +249,36 @@ impl DistributedCoordinator {
 
         if self.workers.is_empty() {
             // Fallback to local processing
-            return self.execute_local_fallback().await;
+            self.execute_local_fallback().await?;
+            return Ok(self.aggregate());
         }
 
         // Simulate distributed processing (in real implementation, this would use
         // actual network communication, message queues, etc.)
         self.simulate_distributed_execution().await?;
 
-        let total_matches: Vec<Match> = self
-            .completed_work
-            .values()
-            .flat_map(|result| result.matches.clone())
-            .collect();
+        let aggregated = self.aggregate();
 
         let duration = start_time.elapsed();
-        self.print_execution_summary(duration, total_matches.len());
+        self.print_execution_summary(duration, aggregated.matches.len());
 
         // End monitoring
         {
             let mut monitor = self.monitor.lock().await;
-            monitor.end_operation("distributed_scan").await?;
+            // Monitoring is best effort and never fails the scan
+            if let Err(e) = monitor.end_operation("distributed_scan").await {
+                warn!("Failed to record distributed scan metrics: {}", e);
+            }
         }
 
-        Ok(total_matches)
+        Ok(aggregated)
+    }
+
+    /// Merge the results of all completed work units
+    pub fn aggregate(&self) -> AggregatedScan {
+        let mut aggregator = ResultAggregator::new();
+        aggregator.extend(self.completed_work.values().cloned());
+        aggregator.finish()
     }
 
     /// Get distributed scan statistics
@@ -196,7 +330,18 @@ impl DistributedCoordinator {
         }
     }
 
-    async fn simulate_distributed_execution(&mut self) -> Result<()> {
+    /// Files from all completed work units that could not be read, sorted by path
+    pub fn skipped_files(&self) -> Vec<SkippedFile> {
+        let mut files: Vec<SkippedFile> = self
+            .completed_work
+            .values()
+            .flat_map(|r| r.skipped_files.iter().cloned())
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files
+    }
+
+    async fn simulate_distributed_execution(&mut self) -> ScanResult<()> {
         use rayon::prelude::*;
 
         // Process work units in parallel (simulating distributed workers)
@@ -208,7 +353,7 @@ impl DistributedCoordinator {
                 let worker_id = format!("worker_{}", i % self.workers.len());
                 self.process_work_unit(unit, &worker_id)
             })
-            .collect::<Result<Vec<_>>>()?;
+            .collect::<ScanResult<Vec<_>>>()?;
 
         // Store results
         for result in results {
@@ -218,14 +363,14 @@ impl DistributedCoordinator {
         Ok(())
     }
 
-    fn process_work_unit(&self, unit: &WorkUnit, worker_id: &str) -> Result<WorkResult> {
+    fn process_work_unit(&self, unit: &WorkUnit, worker_id: &str) -> ScanResult<WorkResult> {
         let start_time = Instant::now();
         let mut all_matches = Vec::new();
-        let mut errors = Vec::new();
+        let mut skipped_files = Vec::new();
         let mut files_processed = 0;
 
         for file_path in &unit.files {
-            match std::fs::read_to_string(file_path) {
+            match read_stable(file_path) {
                 Ok(content) => {
                     for detector_name in &unit.detector_types {
                         if let Some(detector) = self.detectors.get(detector_name) {
@@ -235,9 +380,11 @@ impl DistributedCoordinator {
                     }
                     files_processed += 1;
                 }
-                Err(e) => {
-                    errors.push(format!("Failed to read {}: {}", file_path.display(), e));
-                }
+                Err(ScanError::Encoding { .. }) => {}
+                Err(e) => skipped_files.push(SkippedFile {
+                    path: file_path.to_string_lossy().to_string(),
+                    reason: SkipReason::from_error(&e),
+                }),
             }
         }
 
@@ -250,24 +397,25 @@ impl DistributedCoordinator {
             files_processed,
             processing_time_ms: processing_time.as_millis() as u64,
             timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)?
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
                 .as_secs(),
-            errors,
+            skipped_files,
         })
     }
 
-    async fn execute_local_fallback(&mut self) -> Result<Vec<Match>> {
-        warn!("⚠️  No workers available, falling back to local processing");
+    async fn execute_local_fallback(&mut self) -> ScanResult<()> {
+        warn!(
+            "{} No workers available, falling back to local processing",
+            Icon::Warning
+        );
 
-        let mut all_matches = Vec::new();
         for unit in &self.work_queue {
-            let mut result = self.process_work_unit(unit, "local_worker")?;
-            let matches = std::mem::take(&mut result.matches);
+            let result = self.process_work_unit(unit, "local_worker")?;
             self.completed_work.insert(unit.id.clone(), result);
-            all_matches.extend(matches);
         }
 
-        Ok(all_matches)
+        Ok(())
     }
 
     fn estimate_processing_time(&self, files: &[PathBuf]) -> u64 {
@@ -309,7 +457,7 @@ impl DistributedCoordinator {
     }
 
     fn print_execution_summary(&self, duration: std::time::Duration, total_matches: usize) {
-        info!("✅ Distributed scan completed!");
+        info!("{} Distributed scan completed!", Icon::Success);
         info!("   Duration: {:?}", duration);
         info!("   Total matches: {}", total_matches);
         info!("   Work units processed: {}", self.completed_work.len());
@@ -388,4 +536,68 @@ mod tests {
         assert_eq!(coordinator.work_queue.len(), 1);
         assert_eq!(coordinator.work_queue[0].files.len(), 1);
     }
+
+    #[test]
+    fn test_result_aggregation() {
+        let finding = |file: &str, line: usize| Match {
+            file_path: file.into(),
+            line_number: line,
+            column: 1,
+            pattern: "TODO".into(),
+            message: "TODO: x".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
+        };
+        let result = |unit: &str, worker: &str, matches: Vec<Match>| WorkResult {
+            unit_id: unit.to_string(),
+            worker_id: worker.to_string(),
+            files_processed: 1,
+            processing_time_ms: 5,
+            timestamp: 0,
+            skipped_files: Vec::new(),
+            matches,
+        };
+        let results = vec![
+            result(
+                "unit_1",
+                "worker_1",
+                vec![finding("b.rs", 1), finding("a.rs", 2)],
+            ),
+            // b.rs sat on the unit boundary and was scanned twice
+            result(
+                "unit_0",
+                "worker_0",
+                vec![finding("a.rs", 1), finding("b.rs", 1)],
+            ),
+            result(
+                "unit_1",
+                "worker_0",
+                vec![finding("b.rs", 1), finding("a.rs", 2)],
+            ),
+        ];
+
+        let mut forward = ResultAggregator::new();
+        forward.extend(results.clone());
+        let mut reverse = ResultAggregator::new();
+        reverse.extend(results.into_iter().rev());
+        let aggregated = forward.finish();
+
+        let locations: Vec<_> = aggregated
+            .matches
+            .iter()
+            .map(|m| (m.file_path.as_str(), m.line_number))
+            .collect();
+        assert_eq!(locations, vec![("a.rs", 1), ("a.rs", 2), ("b.rs", 1)]);
+        assert_eq!(aggregated.duplicate_matches, 1);
+        assert_eq!(aggregated.duplicate_units, 1);
+        assert_eq!(aggregated.files_processed, 2);
+        assert_eq!(aggregated.workers[0].worker_id, "worker_0");
+        assert_eq!(aggregated.workers[0].units_completed, 1);
+        assert_eq!(aggregated.workers[1].matches_found, 2);
+
+        // Arrival order only decides which copy of a retried unit counts
+        assert_eq!(reverse.finish().matches, aggregated.matches);
+    }
 }
@@ -20,6 +20,15 @@ pub struct EnhancedScanConfig {
     pub custom_patterns: HashMap<String, String>,
     /// Severity levels for different pattern types
     pub severity_levels: HashMap<String, Severity>,
+    /// Globs identifying test files (empty = built-in per-language defaults)
+    #[serde(default)]
+    pub test_path_globs: Vec<String>,
+    /// Globs identifying production code (empty = every non-test file)
+    #[serde(default)]
+    pub production_path_globs: Vec<String>,
+    /// Regex an ORPHAN_TODO check accepts as an issue reference (None = built-in default)
+    #[serde(default)]
+    pub issue_reference_pattern: Option<String>,
 }
 
 /// Types of available pattern detectors
@@ -63,6 +72,7 @@ pub enum DetectorType {
     UnusedVar,
     DeadCode,
     Experimental,
+    OrphanTodo,
 
     // LLM-specific security patterns
     LLMHallucinatedApi,
@@ -98,6 +108,79 @@ pub enum DetectorType {
     // Comprehensive LLM detector
     LLMComprehensive,
 
+    // Infrastructure-as-code patterns
+    Dockerfile,
+    DockerCompose,
+    Terraform,
+    ShellScript,
+    MigrationRisk,
+
+    // CI configuration patterns
+    CiConfig,
+
+    // Code quality patterns
+    MagicNumber,
+    ErrorSwallowed,
+    FileLength,
+    FunctionLength,
+    DisabledCode,
+
+    // Test hygiene patterns
+    DisabledTest,
+    AssertlessTest,
+    SleepInTest,
+
+    // Insecure cryptography patterns
+    WeakHash,
+    HardcodedIv,
+    InsecureRandom,
+    TimingUnsafeComparison,
+
+    // Code execution patterns
+    UnsafeEval,
+    InsecureDeserialization,
+
+    // Personal data patterns
+    Pii,
+
+    // Localization patterns
+    HardcodedString,
+
+    // Accessibility patterns
+    ImgMissingAlt,
+    NonInteractiveClick,
+    MissingFormLabel,
+    PositiveTabindex,
+
+    // Go patterns
+    GoPrint,
+    GoIgnoredError,
+    GoPanic,
+    GoSleepInHandler,
+
+    // C and C++ patterns
+    UnsafeStringFunction,
+    SystemCall,
+    UncheckedMalloc,
+    WarningPragma,
+
+    // Python patterns
+    PythonPrint,
+    PythonDebugger,
+    BroadExcept,
+    MutableDefaultArgument,
+    PythonAssert,
+
+    // Java and Kotlin patterns
+    SystemOut,
+    PrintStackTrace,
+    SleepInRequest,
+    SuppressAllWarnings,
+    NotNullAssertion,
+
+    // Rust panic-path audit
+    RustPanicPath,
+
     // Custom pattern with name
     Custom(String),
 }
@@ -129,6 +212,84 @@ impl Default for EnhancedScanConfig {
         severity_levels.insert("UNUSED_VAR".to_string(), Severity::Low);
         severity_levels.insert("DEAD_CODE".to_string(), Severity::Medium);
         severity_levels.insert("EXPERIMENTAL".to_string(), Severity::Medium);
+        severity_levels.insert("ORPHAN_TODO".to_string(), Severity::Low);
+        severity_levels.insert("MAGIC_NUMBER".to_string(), Severity::Low);
+        severity_levels.insert("ERROR_SWALLOWED".to_string(), Severity::Medium);
+        severity_levels.insert("FILE_TOO_LONG".to_string(), Severity::Low);
+        severity_levels.insert("FUNCTION_TOO_LONG".to_string(), Severity::Medium);
+        severity_levels.insert("DISABLED_CODE".to_string(), Severity::Low);
+        severity_levels.insert("STALE_FEATURE_FLAG".to_string(), Severity::Low);
+        severity_levels.insert("DISABLED_TEST".to_string(), Severity::Low);
+        severity_levels.insert("ASSERTLESS_TEST".to_string(), Severity::Low);
+        severity_levels.insert("SLEEP_IN_TEST".to_string(), Severity::Low);
+
+        // Insecure cryptography patterns
+        severity_levels.insert("WEAK_HASH".to_string(), Severity::High);
+        severity_levels.insert("HARDCODED_IV".to_string(), Severity::High);
+        severity_levels.insert("INSECURE_RANDOM".to_string(), Severity::High);
+        severity_levels.insert("TIMING_UNSAFE_COMPARISON".to_string(), Severity::High);
+
+        // Code execution patterns
+        severity_levels.insert("UNSAFE_EVAL".to_string(), Severity::Critical);
+        severity_levels.insert("INSECURE_DESERIALIZATION".to_string(), Severity::Critical);
+
+        // Personal data patterns
+        for pattern in [
+            "PII_EMAIL",
+            "PII_PHONE",
+            "PII_CREDIT_CARD",
+            "PII_NATIONAL_ID",
+        ] {
+            severity_levels.insert(pattern.to_string(), Severity::Medium);
+        }
+
+        // Localization patterns
+        severity_levels.insert("HARDCODED_STRING".to_string(), Severity::Low);
+
+        // Accessibility patterns
+        severity_levels.insert("IMG_MISSING_ALT".to_string(), Severity::Medium);
+        severity_levels.insert("NON_INTERACTIVE_CLICK".to_string(), Severity::Medium);
+        severity_levels.insert("MISSING_FORM_LABEL".to_string(), Severity::Medium);
+        severity_levels.insert("POSITIVE_TABINDEX".to_string(), Severity::Low);
+
+        // Go patterns
+        severity_levels.insert("GO_PRINT".to_string(), Severity::Medium);
+        severity_levels.insert("GO_IGNORED_ERROR".to_string(), Severity::Medium);
+        severity_levels.insert("GO_PANIC".to_string(), Severity::Medium);
+        severity_levels.insert("GO_SLEEP_IN_HANDLER".to_string(), Severity::Medium);
+
+        // C and C++ patterns; `gets` is rated Critical by the detector itself
+        severity_levels.insert("C_UNSAFE_STRING_FUNCTION".to_string(), Severity::High);
+        severity_levels.insert("C_SYSTEM_CALL".to_string(), Severity::High);
+        severity_levels.insert("C_UNCHECKED_MALLOC".to_string(), Severity::Medium);
+        severity_levels.insert("C_DISABLED_WARNING".to_string(), Severity::Low);
+
+        // Python patterns
+        severity_levels.insert("PY_PRINT".to_string(), Severity::Medium);
+        severity_levels.insert("PY_DEBUGGER".to_string(), Severity::High);
+        severity_levels.insert("PY_BROAD_EXCEPT".to_string(), Severity::Medium);
+        severity_levels.insert("PY_MUTABLE_DEFAULT".to_string(), Severity::Medium);
+        severity_levels.insert("PY_ASSERT".to_string(), Severity::Medium);
+
+        // Java and Kotlin patterns
+        severity_levels.insert("JVM_SYSTEM_OUT".to_string(), Severity::Medium);
+        severity_levels.insert("JVM_PRINT_STACK_TRACE".to_string(), Severity::Medium);
+        severity_levels.insert("JVM_SLEEP_IN_REQUEST".to_string(), Severity::Medium);
+        severity_levels.insert("JVM_SUPPRESS_ALL".to_string(), Severity::Low);
+        severity_levels.insert("KOTLIN_NOT_NULL_ASSERTION".to_string(), Severity::Low);
+
+        // Rust panic-path audit, rated by reachability by the detector itself
+        for pattern in [
+            "PANIC_UNWRAP",
+            "PANIC_EXPECT",
+            "PANIC_EXPLICIT",
+            "PANIC_TODO",
+            "PANIC_UNREACHABLE",
+            "PANIC_INDEX",
+            "PANIC_DIVISION",
+        ] {
+            severity_levels.insert(pattern.to_string(), Severity::Medium);
+        }
 
         // LLM-specific security patterns (high priority)
         severity_levels.insert("LLM_HALLUCINATED_API".to_string(), Severity::High);
@@ -161,6 +322,27 @@ impl Default for EnhancedScanConfig {
         severity_levels.insert("LLM_PERFORMANCE_MISTAKE".to_string(), Severity::Medium);
         severity_levels.insert("LLM_TYPE_MISTAKE".to_string(), Severity::Low);
 
+        // Infrastructure-as-code patterns
+        severity_levels.insert("DOCKER_ADD".to_string(), Severity::Low);
+        severity_levels.insert("DOCKER_LATEST_TAG".to_string(), Severity::Medium);
+        severity_levels.insert("DOCKER_ROOT_USER".to_string(), Severity::High);
+        severity_levels.insert("DOCKER_ENV_SECRET".to_string(), Severity::Critical);
+        severity_levels.insert("COMPOSE_LATEST_TAG".to_string(), Severity::Medium);
+        severity_levels.insert("COMPOSE_PRIVILEGED".to_string(), Severity::High);
+        severity_levels.insert("COMPOSE_ENV_SECRET".to_string(), Severity::Critical);
+        severity_levels.insert("TF_OPEN_SECURITY_GROUP".to_string(), Severity::High);
+        severity_levels.insert("TF_HARDCODED_CREDENTIALS".to_string(), Severity::Critical);
+        severity_levels.insert("SHELL_MISSING_STRICT_MODE".to_string(), Severity::Low);
+        severity_levels.insert("SHELL_UNQUOTED_VARIABLE".to_string(), Severity::Medium);
+        severity_levels.insert("SHELL_CURL_PIPE".to_string(), Severity::High);
+        severity_levels.insert("SHELL_EXPORTED_SECRET".to_string(), Severity::Critical);
+        severity_levels.insert("MIGRATION_DROP".to_string(), Severity::High);
+        severity_levels.insert("MIGRATION_INDEX_NOT_CONCURRENT".to_string(), Severity::High);
+        severity_levels.insert("MIGRATION_UPDATE_WITHOUT_WHERE".to_string(), Severity::High);
+
+        // CI configuration patterns
+        severity_levels.insert("CI_CONFIG".to_string(), Severity::Medium);
+
         Self {
             enabled_detectors: vec![DetectorType::Todo, DetectorType::Fixme],
             include_extensions: vec![
@@ -220,6 +402,9 @@ impl Default for EnhancedScanConfig {
             max_file_size: Some(1024 * 1024), // 1MB default
             custom_patterns: HashMap::new(),
             severity_levels,
+            test_path_globs: Vec::new(),
+            production_path_globs: Vec::new(),
+            issue_reference_pattern: None,
         }
     }
 }
@@ -1,172 +1,220 @@
-//! Standardized error types and recovery mechanisms for Code Guardian
+//! Typed errors returned by the scanning, configuration, detector and storage APIs
 
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
-use std::path::PathBuf;
 
-/// Standard error types across all Code Guardian operations
+/// Result alias for fallible Code Guardian library operations
+pub type ScanResult<T> = std::result::Result<T, ScanError>;
+
+/// Failure kinds of the Code Guardian library, so programmatic consumers can
+/// branch on what went wrong instead of matching on message text
 #[derive(Error, Debug)]
 pub enum ScanError {
-    #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
+    /// Reading or writing a file or directory failed
+    #[error("I/O error on {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// File content is not valid text in the expected encoding or format
+    #[error("Invalid content in {}: {reason}", path.display())]
+    Encoding { path: PathBuf, reason: String },
 
+    /// A file kept changing while it was read, so its content is unreliable
+    #[error("{} changed while it was read", path.display())]
+    Unstable { path: PathBuf },
+
+    /// Configuration or rule definitions are invalid
     #[error("Configuration error: {message}")]
     Config { message: String },
 
+    /// A detector could not be built or run
     #[error("Detector error in {detector}: {message}")]
     Detector { detector: String, message: String },
 
-    #[error("Database error: {0}")]
-    Database(#[from] rusqlite::Error),
+    /// The scan database failed
+    #[error("Storage error: {message}")]
+    Storage { message: String },
 
-    #[error("File too large: {path} ({size} bytes exceeds limit of {limit} bytes)")]
-    FileTooLarge {
-        path: PathBuf,
-        size: u64,
-        limit: u64,
-    },
+    /// A webhook notification could not be delivered
+    #[error("Webhook delivery to {url} failed: {message}")]
+    Webhook { url: String, message: String },
 
-    #[error("Timeout during {operation} after {timeout_seconds} seconds")]
-    Timeout {
-        operation: String,
-        timeout_seconds: u64,
+    /// A report could not be uploaded to object storage
+    #[error("Upload to {destination} failed: {message}")]
+    Upload {
+        destination: String,
+        message: String,
     },
 
-    #[error("Permission denied accessing {path}")]
-    PermissionDenied { path: PathBuf },
+    /// The LLM triage endpoint could not classify a finding
+    #[error("LLM triage via {endpoint} failed: {message}")]
+    Triage { endpoint: String, message: String },
 
-    #[error("Invalid file format for {path}: {reason}")]
-    InvalidFileFormat { path: PathBuf, reason: String },
+    /// A distributed scan worker could not be discovered or is incompatible
+    #[error("Worker {worker}: {message}")]
+    Worker { worker: String, message: String },
 
-    #[error("Memory limit exceeded: {current_mb}MB > {limit_mb}MB")]
-    MemoryLimitExceeded { current_mb: u64, limit_mb: u64 },
+    /// A code hosting or issue tracking API, e.g. GitLab, rejected a request
+    #[error("{service} API request failed: {message}")]
+    Integration { service: String, message: String },
+}
 
-    #[error("Network error: {message}")]
-    Network { message: String },
+impl ScanError {
+    pub fn io(path: impl AsRef<Path>, source: std::io::Error) -> Self {
+        ScanError::Io {
+            path: path.as_ref().to_path_buf(),
+            source,
+        }
+    }
 
-    #[error("Serialization error: {0}")]
-    Serialization(#[from] serde_json::Error),
+    pub fn encoding(path: impl AsRef<Path>, reason: impl Display) -> Self {
+        ScanError::Encoding {
+            path: path.as_ref().to_path_buf(),
+            reason: reason.to_string(),
+        }
+    }
 
-    #[error("Git operation failed: {message}")]
-    Git { message: String },
+    pub fn unstable(path: impl AsRef<Path>) -> Self {
+        ScanError::Unstable {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
 
-    #[error("Custom detector error: {message}")]
-    CustomDetector { message: String },
+    pub fn config(message: impl Display) -> Self {
+        ScanError::Config {
+            message: message.to_string(),
+        }
+    }
 
-    #[error("Distributed scanning error: {message}")]
-    Distributed { message: String },
-}
+    pub fn detector(detector: impl Into<String>, message: impl Display) -> Self {
+        ScanError::Detector {
+            detector: detector.into(),
+            message: message.to_string(),
+        }
+    }
 
-/// Recovery strategies for different error types
-#[derive(Debug, Clone)]
-pub enum RecoveryStrategy {
-    /// Retry the operation with exponential backoff
-    Retry { max_attempts: u32, base_delay_ms: u64 },
-    /// Skip the problematic item and continue
-    Skip,
-    /// Fall back to alternative implementation
-    Fallback,
-    /// Fail immediately without recovery
-    FailFast,
-    /// Continue with degraded functionality
-    Degrade,
-}
+    pub fn storage(message: impl Display) -> Self {
+        ScanError::Storage {
+            message: message.to_string(),
+        }
+    }
+
+    pub fn webhook(url: impl Into<String>, message: impl Display) -> Self {
+        ScanError::Webhook {
+            url: url.into(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn upload(destination: impl Into<String>, message: impl Display) -> Self {
+        ScanError::Upload {
+            destination: destination.into(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn triage(endpoint: impl Into<String>, message: impl Display) -> Self {
+        ScanError::Triage {
+            endpoint: endpoint.into(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn worker(worker: impl Into<String>, message: impl Display) -> Self {
+        ScanError::Worker {
+            worker: worker.into(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn integration(service: impl Into<String>, message: impl Display) -> Self {
+        ScanError::Integration {
+            service: service.into(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Classifies a failure to read `path` as text: invalid UTF-8 is an
+    /// encoding error, anything else an I/O error
+    pub(crate) fn from_read(path: impl AsRef<Path>, source: std::io::Error) -> Self {
+        if source.kind() == std::io::ErrorKind::InvalidData {
+            ScanError::encoding(path, source)
+        } else {
+            ScanError::io(path, source)
+        }
+    }
+
+    /// Converts an error from walking the tree below `root`
+    pub(crate) fn from_walk(root: impl AsRef<Path>, error: ignore::Error) -> Self {
+        let message = error.to_string();
+        let source = error
+            .into_io_error()
+            .unwrap_or_else(|| std::io::Error::new(std::io::ErrorKind::Other, message));
+        ScanError::io(root, source)
+    }
 
-impl ScanError {
     /// Get the appropriate recovery strategy for this error type
     pub fn recovery_strategy(&self) -> RecoveryStrategy {
         match self {
-            ScanError::Io(_) => RecoveryStrategy::Retry {
-                max_attempts: 3,
-                base_delay_ms: 100,
-            },
-            ScanError::FileTooLarge { .. } => RecoveryStrategy::Skip,
-            ScanError::Timeout { .. } => RecoveryStrategy::Retry {
-                max_attempts: 2,
-                base_delay_ms: 1000,
-            },
-            ScanError::PermissionDenied { .. } => RecoveryStrategy::Skip,
-            ScanError::InvalidFileFormat { .. } => RecoveryStrategy::Skip,
-            ScanError::MemoryLimitExceeded { .. } => RecoveryStrategy::Degrade,
-            ScanError::Network { .. } => RecoveryStrategy::Retry {
-                max_attempts: 5,
-                base_delay_ms: 500,
-            },
-            ScanError::Config { .. } => RecoveryStrategy::FailFast,
-            ScanError::Database(_) => RecoveryStrategy::Retry {
+            ScanError::Io { .. } | ScanError::Encoding { .. } | ScanError::Unstable { .. } => {
+                RecoveryStrategy::Skip
+            }
+            ScanError::Config { .. } | ScanError::Worker { .. } => RecoveryStrategy::FailFast,
+            ScanError::Detector { .. } | ScanError::Triage { .. } => RecoveryStrategy::Fallback,
+            ScanError::Storage { .. }
+            | ScanError::Webhook { .. }
+            | ScanError::Upload { .. }
+            | ScanError::Integration { .. } => RecoveryStrategy::Retry {
                 max_attempts: 3,
                 base_delay_ms: 200,
             },
-            ScanError::Detector { .. } => RecoveryStrategy::Fallback,
-            ScanError::Serialization(_) => RecoveryStrategy::FailFast,
-            ScanError::Git { .. } => RecoveryStrategy::Fallback,
-            ScanError::CustomDetector { .. } => RecoveryStrategy::Skip,
-            ScanError::Distributed { .. } => RecoveryStrategy::Retry {
-                max_attempts: 2,
-                base_delay_ms: 1000,
-            },
         }
     }
 
     /// Get severity level for monitoring and alerting
     pub fn severity(&self) -> ErrorSeverity {
         match self {
-            ScanError::Config { .. } | ScanError::Serialization(_) => ErrorSeverity::Critical,
-            ScanError::Database(_) | ScanError::MemoryLimitExceeded { .. } => ErrorSeverity::High,
-            ScanError::Io(_) | ScanError::Network { .. } | ScanError::Timeout { .. } => ErrorSeverity::Medium,
-            ScanError::FileTooLarge { .. } | ScanError::PermissionDenied { .. } | 
-            ScanError::InvalidFileFormat { .. } => ErrorSeverity::Low,
-            ScanError::Detector { .. } | ScanError::Git { .. } | 
-            ScanError::CustomDetector { .. } | ScanError::Distributed { .. } => ErrorSeverity::Medium,
+            ScanError::Config { .. } => ErrorSeverity::Critical,
+            ScanError::Storage { .. } | ScanError::Worker { .. } => ErrorSeverity::High,
+            ScanError::Detector { .. }
+            | ScanError::Webhook { .. }
+            | ScanError::Upload { .. }
+            | ScanError::Integration { .. } => ErrorSeverity::Medium,
+            ScanError::Io { .. }
+            | ScanError::Encoding { .. }
+            | ScanError::Unstable { .. }
+            | ScanError::Triage { .. } => ErrorSeverity::Low,
         }
     }
 
     /// Check if error is retryable
     pub fn is_retryable(&self) -> bool {
-        matches!(
-            self.recovery_strategy(),
-            RecoveryStrategy::Retry { .. }
-        )
+        matches!(self.recovery_strategy(), RecoveryStrategy::Retry { .. })
     }
+}
 
-    /// Get error context for structured logging
-    pub fn context(&self) -> ErrorContext {
-        match self {
-            ScanError::FileTooLarge { path, size, limit } => ErrorContext {
-                operation: "file_scan".to_string(),
-                resource: Some(path.display().to_string()),
-                details: Some(format!("size={}, limit={}", size, limit)),
-            },
-            ScanError::PermissionDenied { path } => ErrorContext {
-                operation: "file_access".to_string(),
-                resource: Some(path.display().to_string()),
-                details: None,
-            },
-            ScanError::Timeout { operation, timeout_seconds } => ErrorContext {
-                operation: operation.clone(),
-                resource: None,
-                details: Some(format!("timeout={}s", timeout_seconds)),
-            },
-            ScanError::MemoryLimitExceeded { current_mb, limit_mb } => ErrorContext {
-                operation: "memory_management".to_string(),
-                resource: None,
-                details: Some(format!("current={}MB, limit={}MB", current_mb, limit_mb)),
-            },
-            ScanError::Detector { detector, .. } => ErrorContext {
-                operation: "detection".to_string(),
-                resource: Some(detector.clone()),
-                details: None,
-            },
-            _ => ErrorContext {
-                operation: "unknown".to_string(),
-                resource: None,
-                details: None,
-            },
-        }
-    }
+/// Recovery strategies for different error types
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecoveryStrategy {
+    /// Retry the operation with exponential backoff
+    Retry {
+        max_attempts: u32,
+        base_delay_ms: u64,
+    },
+    /// Skip the problematic item and continue
+    Skip,
+    /// Fall back to alternative implementation
+    Fallback,
+    /// Fail immediately without recovery
+    FailFast,
 }
 
 /// Error severity levels for monitoring
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ErrorSeverity {
     Critical,
     High,
@@ -174,165 +222,42 @@ pub enum ErrorSeverity {
     Low,
 }
 
-/// Structured error context for logging and monitoring
-#[derive(Debug, Clone)]
-pub struct ErrorContext {
-    pub operation: String,
-    pub resource: Option<String>,
-    pub details: Option<String>,
-}
-
-/// Error recovery manager with retry logic and circuit breaker
-pub struct ErrorRecoveryManager {
-    max_retries: u32,
-    circuit_breaker_threshold: u32,
-    failure_count: std::sync::atomic::AtomicU32,
-}
-
-impl ErrorRecoveryManager {
-    pub fn new() -> Self {
-        Self {
-            max_retries: 3,
-            circuit_breaker_threshold: 10,
-            failure_count: std::sync::atomic::AtomicU32::new(0),
-        }
-    }
-
-    /// Execute operation with automatic retry and recovery
-    pub async fn execute_with_recovery<T, F, Fut>(
-        &self,
-        operation: F,
-        error_context: &str,
-    ) -> Result<T, ScanError>
-    where
-        F: Fn() -> Fut,
-        Fut: std::future::Future<Output = Result<T, ScanError>>,
-    {
-        let mut attempts = 0;
-        let mut last_error = None;
-
-        while attempts < self.max_retries {
-            match operation().await {
-                Ok(result) => {
-                    // Reset failure count on success
-                    self.failure_count.store(0, std::sync::atomic::Ordering::Relaxed);
-                    return Ok(result);
-                }
-                Err(error) => {
-                    attempts += 1;
-                    last_error = Some(error.clone());
-                    
-                    // Check if error is retryable
-                    if !error.is_retryable() {
-                        tracing::warn!(
-                            error = %error,
-                            context = error_context,
-                            "Non-retryable error encountered"
-                        );
-                        return Err(error);
-                    }
-
-                    // Implement exponential backoff
-                    if let RecoveryStrategy::Retry { base_delay_ms, .. } = error.recovery_strategy() {
-                        let delay = base_delay_ms * 2_u64.pow(attempts - 1);
-                        tracing::warn!(
-                            error = %error,
-                            attempt = attempts,
-                            delay_ms = delay,
-                            context = error_context,
-                            "Retrying operation after error"
-                        );
-                        tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
-                    }
-                }
-            }
-        }
-
-        // Increment failure count for circuit breaker
-        self.failure_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-        
-        let error = last_error.unwrap();
-        tracing::error!(
-            error = %error,
-            attempts = attempts,
-            context = error_context,
-            "Operation failed after all retry attempts"
-        );
-        
-        Err(error)
-    }
-
-    /// Check if circuit breaker should trip
-    pub fn should_circuit_break(&self) -> bool {
-        self.failure_count.load(std::sync::atomic::Ordering::Relaxed) >= self.circuit_breaker_threshold
-    }
-}
-
-impl Default for ErrorRecoveryManager {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_error_recovery_strategies() {
-        let io_error = ScanError::Io(std::io::Error::from(std::io::ErrorKind::ConnectionRefused));
-        assert!(matches!(io_error.recovery_strategy(), RecoveryStrategy::Retry { .. }));
-        assert!(io_error.is_retryable());
+        let io_error = ScanError::io(
+            "src/main.rs",
+            std::io::Error::from(std::io::ErrorKind::PermissionDenied),
+        );
+        assert_eq!(io_error.recovery_strategy(), RecoveryStrategy::Skip);
+        assert!(!io_error.is_retryable());
 
-        let config_error = ScanError::Config { message: "Invalid config".to_string() };
-        assert!(matches!(config_error.recovery_strategy(), RecoveryStrategy::FailFast));
-        assert!(!config_error.is_retryable());
-    }
+        let storage_error = ScanError::storage("database is locked");
+        assert!(storage_error.is_retryable());
 
-    #[test]
-    fn test_error_severity() {
-        let config_error = ScanError::Config { message: "Invalid".to_string() };
-        assert_eq!(config_error.severity(), ErrorSeverity::Critical);
-
-        let file_error = ScanError::FileTooLarge { 
-            path: PathBuf::from("/test"), 
-            size: 1000, 
-            limit: 500 
-        };
-        assert_eq!(file_error.severity(), ErrorSeverity::Low);
+        let config_error = ScanError::config("Invalid config");
+        assert_eq!(config_error.recovery_strategy(), RecoveryStrategy::FailFast);
     }
 
     #[test]
-    fn test_error_context() {
-        let timeout_error = ScanError::Timeout {
-            operation: "scan".to_string(),
-            timeout_seconds: 30,
-        };
-        let context = timeout_error.context();
-        assert_eq!(context.operation, "scan");
-        assert!(context.details.unwrap().contains("timeout=30s"));
-    }
+    fn test_error_severity_and_display() {
+        assert_eq!(
+            ScanError::config("Invalid").severity(),
+            ErrorSeverity::Critical
+        );
 
-    #[tokio::test]
-    async fn test_recovery_manager() {
-        let manager = ErrorRecoveryManager::new();
-        
-        // Test successful operation
-        let result = manager.execute_with_recovery(
-            || async { Ok::<i32, ScanError>(42) },
-            "test_operation"
-        ).await;
-        assert_eq!(result.unwrap(), 42);
-        
-        // Test operation that always fails with non-retryable error
-        let result = manager.execute_with_recovery(
-            || async { 
-                Err::<i32, ScanError>(ScanError::Config { 
-                    message: "Invalid config".to_string() 
-                })
-            },
-            "test_operation"
-        ).await;
-        assert!(result.is_err());
+        let encoding_error = ScanError::encoding("logo.png", "invalid UTF-8");
+        assert_eq!(encoding_error.severity(), ErrorSeverity::Low);
+        assert_eq!(
+            encoding_error.to_string(),
+            "Invalid content in logo.png: invalid UTF-8"
+        );
+        assert_eq!(
+            ScanError::detector("SECRET", "regex parse error").to_string(),
+            "Detector error in SECRET: regex parse error"
+        );
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,124 @@
+use std::collections::HashSet;
+use std::ffi::{OsStr, OsString};
+use std::path::{Component, Path};
+
+/// Build output, dependency and tool directories that are never scanned
+pub const DEFAULT_EXCLUDED_DIRS: &[&str] = &[
+    // Version control
+    ".git",
+    // Rust and JVM build output
+    "target",
+    "build",
+    // JavaScript dependencies and bundles
+    "node_modules",
+    "dist",
+    ".next",
+    ".nuxt",
+    // Python virtual environments and bytecode
+    ".venv",
+    "__pycache__",
+    // Terraform providers and modules
+    ".terraform",
+];
+
+/// Directories skipped wherever they appear below the scan root, matched by
+/// whole path component so `/` and `\` separated paths behave the same
+#[derive(Debug, Clone)]
+pub struct ExcludedDirs {
+    names: HashSet<OsString>,
+}
+
+impl ExcludedDirs {
+    /// Excludes [`DEFAULT_EXCLUDED_DIRS`] plus the directory names in `extra`
+    pub fn new<S: AsRef<str>>(extra: &[S]) -> Self {
+        Self {
+            names: DEFAULT_EXCLUDED_DIRS
+                .iter()
+                .copied()
+                .chain(extra.iter().map(|name| name.as_ref()))
+                .map(OsString::from)
+                .collect(),
+        }
+    }
+
+    /// The excluded directory names, sorted
+    pub fn names(&self) -> Vec<&OsStr> {
+        let mut names: Vec<&OsStr> = self.names.iter().map(OsString::as_os_str).collect();
+        names.sort();
+        names
+    }
+
+    /// True if `name` is an excluded directory name
+    pub fn is_excluded_name(&self, name: &OsStr) -> bool {
+        self.names.contains(name)
+    }
+
+    /// True if any directory of `path` is excluded. Pass paths relative to
+    /// the scan root, so a root inside e.g. a `build` directory still scans.
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        path.parent().is_some_and(|dirs| {
+            dirs.components().any(|component| match component {
+                Component::Normal(name) => self.is_excluded_name(name),
+                _ => false,
+            })
+        })
+    }
+}
+
+impl Default for ExcludedDirs {
+    fn default() -> Self {
+        Self::new::<&str>(&[])
+    }
+}
+
+/// The reason an `excluded_dirs` entry is invalid, if it is: entries are
+/// single directory names, not paths or globs
+pub fn invalid_excluded_dir(name: &str) -> Option<&'static str> {
+    if name.is_empty() {
+        Some("is empty")
+    } else if name.contains(['/', '\\']) {
+        Some("is a path; use a directory name such as 'out'")
+    } else if name.contains(['*', '?', '[']) {
+        Some("is a glob; use generated_globs for glob patterns")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_excluded_dirs_match_whole_components() {
+        let dirs = ExcludedDirs::new(&["out"]);
+        assert!(dirs.is_excluded(Path::new("target/debug/main.rs")));
+        assert!(dirs.is_excluded(Path::new("web/node_modules/react/index.js")));
+        assert!(dirs.is_excluded(Path::new("api/.venv/lib/site.py")));
+        assert!(dirs.is_excluded(Path::new("pkg/__pycache__/mod.py")));
+        assert!(dirs.is_excluded(Path::new("infra/.terraform/modules/main.tf")));
+        assert!(dirs.is_excluded(Path::new("app/out/bundle.js")));
+        // A file named like a directory, and names containing one
+        assert!(!dirs.is_excluded(Path::new("src/build")));
+        assert!(!dirs.is_excluded(Path::new("src/targets/mod.rs")));
+        assert!(!dirs.is_excluded(Path::new("rebuild/main.rs")));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_excluded_dirs_windows_paths() {
+        let dirs = ExcludedDirs::default();
+        assert!(dirs.is_excluded(Path::new(r"C:\repo\target\debug\main.rs")));
+        assert!(dirs.is_excluded(Path::new(r"web\node_modules\react\index.js")));
+        assert!(!dirs.is_excluded(Path::new(r"C:\repo\src\main.rs")));
+    }
+
+    #[test]
+    fn test_invalid_excluded_dir() {
+        assert_eq!(invalid_excluded_dir("out"), None);
+        assert!(invalid_excluded_dir("build/out").is_some());
+        assert!(invalid_excluded_dir(r"build\out").is_some());
+        assert!(invalid_excluded_dir("*.egg-info").is_some());
+        assert!(invalid_excluded_dir("").is_some());
+    }
+}
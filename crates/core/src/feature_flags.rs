@@ -0,0 +1,291 @@
+//! Leftovers of feature rollouts: references to flags missing from the flag
+//! registry, and experiments switched off with a constant condition such as
+//! `if (false)` or `#[cfg(any())]` and never cleaned up
+
+use crate::comment_spans::CommentIndex;
+use crate::detectors::{classify, detect_code_pattern, has_extension, CODE_EXTENSIONS};
+use crate::errors::{ScanError, ScanResult};
+use crate::line_index::LineIndex;
+use crate::quality_detectors::CODE_QUALITY_CATEGORY;
+use crate::regex_cache::cached_regex;
+use crate::{Match, PatternDetector, Severity, SharedStr};
+use lazy_static::lazy_static;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Flag lookups of common feature flag SDKs, such as `isEnabled("new-checkout")`
+/// or `client.variation("beta-ui", user, false)`
+pub const DEFAULT_FLAG_PATTERN: &str = r#"\b(?:is_?[Ee]nabled|isFeatureEnabled|is_feature_enabled|feature_enabled|variation|boolVariation|bool_variation|getBooleanValue|get_boolean_value|getFeatureValue|useFlag|useFeatureFlag)\(\s*["']([\w.:-]+)["']"#;
+
+lazy_static! {
+    /// Conditions that are always false: `if (false)`, `if false {`, `#if 0`,
+    /// `if False:` and `cfg(any())`
+    static ref DISABLED_CODE_REGEX: Regex = Regex::new(
+        r"(?m)\bif\s*\(\s*(?:false|0)\s*\)|\bif\s+false\s*\{|^[ \t]*#[ \t]*if[ \t]+0\b|^[ \t]*(?:el)?if[ \t]+(?:False|0)[ \t]*:|\bcfg!?\(\s*any\(\s*\)\s*\)"
+    ).unwrap();
+}
+
+/// Settings of the `STALE_FEATURE_FLAG` check, the `feature_flags` config section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FeatureFlagConfig {
+    /// File listing the active flags, relative to the working directory: a
+    /// JSON array of names, a JSON object keyed by name, or one name per line
+    /// with `#` comments. The check runs only when this is set.
+    pub registry: Option<PathBuf>,
+    /// Regexes whose first capture group is a flag name; the default covers
+    /// the lookup calls of common flag SDKs
+    pub patterns: Vec<String>,
+}
+
+impl Default for FeatureFlagConfig {
+    fn default() -> Self {
+        Self {
+            registry: None,
+            patterns: vec![DEFAULT_FLAG_PATTERN.to_string()],
+        }
+    }
+}
+
+/// Reads the active flags from a registry file, see [`FeatureFlagConfig::registry`]
+pub fn load_flag_registry(path: &Path) -> ScanResult<BTreeSet<String>> {
+    let content = std::fs::read_to_string(path).map_err(|e| ScanError::io(path, e))?;
+    if !has_extension(path, &["json"]) {
+        return Ok(content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect());
+    }
+    let invalid = |reason: &str| {
+        ScanError::config(format!(
+            "Invalid flag registry {}: {}",
+            path.display(),
+            reason
+        ))
+    };
+    match serde_json::from_str(&content).map_err(|e| invalid(&e.to_string()))? {
+        serde_json::Value::Object(flags) => Ok(flags.keys().cloned().collect()),
+        serde_json::Value::Array(flags) => flags
+            .iter()
+            .map(|flag| {
+                flag.as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| invalid("flag names must be strings"))
+            })
+            .collect(),
+        _ => Err(invalid("expected an array of flag names or an object")),
+    }
+}
+
+/// Detector for flag lookups naming a flag that is not in the registry,
+/// usually one removed after its rollout whose code path was left behind
+pub struct StaleFeatureFlagDetector {
+    active: BTreeSet<String>,
+    patterns: Vec<Regex>,
+}
+
+impl StaleFeatureFlagDetector {
+    pub fn new(active: BTreeSet<String>, patterns: &[String]) -> ScanResult<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                let re = cached_regex(pattern)
+                    .map_err(|e| ScanError::detector("STALE_FEATURE_FLAG", e))?;
+                if re.captures_len() < 2 {
+                    return Err(ScanError::detector(
+                        "STALE_FEATURE_FLAG",
+                        format!(
+                            "pattern '{}' has no capture group for the flag name",
+                            pattern
+                        ),
+                    ));
+                }
+                Ok(re)
+            })
+            .collect::<ScanResult<_>>()?;
+        Ok(Self { active, patterns })
+    }
+
+    /// The detector for `config`, or `None` when no registry is configured
+    pub fn from_config(config: &FeatureFlagConfig) -> ScanResult<Option<Self>> {
+        let Some(registry) = &config.registry else {
+            return Ok(None);
+        };
+        Self::new(load_flag_registry(registry)?, &config.patterns).map(Some)
+    }
+}
+
+impl PatternDetector for StaleFeatureFlagDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let mut comments = None;
+        let mut lines = None;
+        let mut matches = Vec::new();
+        let mut shared_path = None;
+        for re in &self.patterns {
+            for caps in re.captures_iter(content) {
+                let Some(flag) = caps.get(1) else {
+                    continue;
+                };
+                if self.active.contains(flag.as_str())
+                    || comments
+                        .get_or_insert_with(|| CommentIndex::new(content, file_path))
+                        .is_comment(flag.start())
+                {
+                    continue;
+                }
+                let (line_number, column) = lines
+                    .get_or_insert_with(|| LineIndex::new(content))
+                    .position(content, flag.start());
+                matches.push(Match {
+                    file_path: shared_path
+                        .get_or_insert_with(|| SharedStr::from_path(file_path))
+                        .clone(),
+                    line_number,
+                    column,
+                    pattern: SharedStr::interned("STALE_FEATURE_FLAG"),
+                    message: format!(
+                        "STALE_FEATURE_FLAG: '{}' is not in the flag registry",
+                        flag.as_str()
+                    ),
+                    severity: Some(Severity::Low),
+                    category: Some(CODE_QUALITY_CATEGORY.to_string()),
+                    end: None,
+                    author: None,
+                });
+            }
+        }
+        matches.sort_by_key(|m| (m.line_number, m.column));
+        matches
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, CODE_EXTENSIONS)
+    }
+
+    fn config_fingerprint(&self) -> String {
+        let mut fingerprint: Vec<&str> = self.active.iter().map(String::as_str).collect();
+        fingerprint.extend(self.patterns.iter().map(Regex::as_str));
+        fingerprint.join("\0")
+    }
+}
+
+/// Detector for code switched off with a condition that is always false,
+/// typically an experiment disabled instead of removed
+pub struct DisabledCodeDetector;
+
+impl PatternDetector for DisabledCodeDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        classify(
+            detect_code_pattern(content, file_path, "DISABLED_CODE", &DISABLED_CODE_REGEX),
+            Severity::Low,
+            CODE_QUALITY_CATEGORY,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, CODE_EXTENSIONS)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&DISABLED_CODE_REGEX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn lines(detector: &dyn PatternDetector, content: &str, path: &str) -> Vec<usize> {
+        detector
+            .detect(content, Path::new(path))
+            .iter()
+            .map(|m| m.line_number)
+            .collect()
+    }
+
+    #[test]
+    fn test_load_flag_registry() {
+        let temp_dir = TempDir::new().unwrap();
+        let text = temp_dir.path().join("flags.txt");
+        std::fs::write(&text, "# active flags\nnew-checkout\n\n  beta_ui\n").unwrap();
+        assert_eq!(
+            load_flag_registry(&text).unwrap(),
+            BTreeSet::from(["beta_ui".to_string(), "new-checkout".to_string()])
+        );
+
+        let json = temp_dir.path().join("flags.json");
+        std::fs::write(&json, r#"{"new-checkout": {"on": true}}"#).unwrap();
+        assert_eq!(load_flag_registry(&json).unwrap().len(), 1);
+        std::fs::write(&json, r#"["a", 1]"#).unwrap();
+        assert!(load_flag_registry(&json).is_err());
+        assert!(load_flag_registry(&temp_dir.path().join("missing.txt")).is_err());
+    }
+
+    #[test]
+    fn test_stale_feature_flag_detector() {
+        let active = BTreeSet::from(["new-checkout".to_string()]);
+        let detector =
+            StaleFeatureFlagDetector::new(active, &FeatureFlagConfig::default().patterns).unwrap();
+        let content = "if (flags.isEnabled('new-checkout')) {}\nif (client.variation(\"old-banner\", user, false)) {}\n// isEnabled('removed')\nuseFlag(\"old-banner\");\n";
+        let matches = detector.detect(content, Path::new("app.ts"));
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.line_number, m.column))
+                .collect::<Vec<_>>(),
+            vec![(2, 23), (4, 10)]
+        );
+        assert_eq!(
+            matches[0].message,
+            "STALE_FEATURE_FLAG: 'old-banner' is not in the flag registry"
+        );
+        assert_eq!(matches[0].category.as_deref(), Some("CodeQuality"));
+
+        assert!(StaleFeatureFlagDetector::new(BTreeSet::new(), &["isOn".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_disabled_code_detector() {
+        let detector = DisabledCodeDetector;
+        assert_eq!(
+            lines(
+                &detector,
+                "if (false) {\n  legacy();\n}\nif (flag) {}\n// if (false) {}\n",
+                "app.js"
+            ),
+            vec![1]
+        );
+        assert_eq!(
+            lines(
+                &detector,
+                "#[cfg(any())]\nfn old() {}\nfn run() {\n    if false {\n        old();\n    }\n}\n",
+                "lib.rs"
+            ),
+            vec![1, 4]
+        );
+        assert_eq!(
+            lines(&detector, "#if 0\nold();\n#endif\n", "main.c"),
+            vec![1]
+        );
+        assert_eq!(
+            lines(
+                &detector,
+                "if False:\n    old()\nif flag:\n    new()\n",
+                "app.py"
+            ),
+            vec![1]
+        );
+    }
+}
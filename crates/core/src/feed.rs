@@ -0,0 +1,161 @@
+//! Atom feed of recent scans and the Critical findings each scan added,
+//! served at `/feed.atom` so chat tools and feed readers can subscribe.
+
+use crate::enhanced_config::EnhancedScanConfig;
+use crate::errors::ScanResult;
+use crate::finding_state::finding_fingerprints;
+use crate::{Match, Severity};
+use chrono::{DateTime, Utc};
+
+/// New Critical findings listed per scan; the rest are only counted
+const MAX_FINDING_ENTRIES: usize = 20;
+
+/// A scan as the feed shows it
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedScan {
+    pub id: i64,
+    /// Unix timestamp of the scan
+    pub timestamp: i64,
+    pub root_path: String,
+    /// Number of findings of the scan
+    pub findings: usize,
+    /// Critical findings the previous scan of the same root did not have
+    pub new_critical: Vec<Match>,
+}
+
+/// Source of the scans in the feed, newest first
+pub trait ScanFeed: Send + Sync {
+    fn recent_scans(&self) -> ScanResult<Vec<FeedScan>>;
+}
+
+/// Critical findings of `matches`, by their own severity, else the built-in
+/// default of their pattern
+pub fn critical_findings(matches: &[Match]) -> Vec<Match> {
+    let defaults = EnhancedScanConfig::default();
+    matches
+        .iter()
+        .filter(|m| {
+            matches!(
+                m.severity
+                    .as_ref()
+                    .or_else(|| defaults.severity_levels.get(m.pattern.as_str())),
+                Some(Severity::Critical)
+            )
+        })
+        .cloned()
+        .collect()
+}
+
+/// Atom document with an entry per scan, followed by one per new Critical
+/// finding of the scan. `feed_url` is the URL the feed is served at.
+pub fn atom_feed(scans: &[FeedScan], feed_url: &str) -> String {
+    let updated = scans.iter().map(|scan| scan.timestamp).max().unwrap_or(0);
+    let mut feed = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n  <id>{}</id>\n  <title>code-guardian scans</title>\n  <updated>{}</updated>\n  <link rel=\"self\" href=\"{}\"/>\n  <author><name>code-guardian</name></author>\n",
+        xml_escape(feed_url),
+        rfc3339(updated),
+        xml_escape(feed_url)
+    );
+    for scan in scans {
+        let summary = format!(
+            "{} finding{}, {} new Critical",
+            scan.findings,
+            if scan.findings == 1 { "" } else { "s" },
+            scan.new_critical.len()
+        );
+        push_entry(
+            &mut feed,
+            &format!("urn:code-guardian:scan:{}", scan.id),
+            &format!("Scan {} of {}: {}", scan.id, scan.root_path, summary),
+            scan.timestamp,
+            &summary,
+        );
+        let fingerprints = finding_fingerprints(&scan.new_critical, &scan.root_path);
+        for (m, fingerprint) in scan
+            .new_critical
+            .iter()
+            .zip(fingerprints)
+            .take(MAX_FINDING_ENTRIES)
+        {
+            push_entry(
+                &mut feed,
+                &format!("urn:code-guardian:finding:{}:{}", scan.id, fingerprint),
+                &format!(
+                    "New Critical {} in {}:{}",
+                    m.pattern, m.file_path, m.line_number
+                ),
+                scan.timestamp,
+                m.message.trim(),
+            );
+        }
+    }
+    feed.push_str("</feed>\n");
+    feed
+}
+
+fn push_entry(feed: &mut String, id: &str, title: &str, timestamp: i64, summary: &str) {
+    feed.push_str(&format!(
+        "  <entry>\n    <id>{}</id>\n    <title>{}</title>\n    <updated>{}</updated>\n    <summary>{}</summary>\n  </entry>\n",
+        xml_escape(id),
+        xml_escape(title),
+        rfc3339(timestamp),
+        xml_escape(summary)
+    ));
+}
+
+fn rfc3339(timestamp: i64) -> String {
+    DateTime::<Utc>::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .to_rfc3339()
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(pattern: &str, line_number: usize) -> Match {
+        Match::new(
+            "src/app.js",
+            line_number,
+            pattern,
+            format!("{}: eval(a < b)", pattern),
+        )
+    }
+
+    #[test]
+    fn test_critical_findings() {
+        let mut high = finding("TODO", 3);
+        high.severity = Some(Severity::Critical);
+        let critical = critical_findings(&[finding("TODO", 1), finding("UNSAFE_EVAL", 2), high]);
+        assert_eq!(critical.len(), 2);
+        assert_eq!(critical[0].pattern, "UNSAFE_EVAL");
+        assert_eq!(critical[1].line_number, 3);
+    }
+
+    #[test]
+    fn test_atom_feed() {
+        let scans = vec![FeedScan {
+            id: 7,
+            timestamp: 1_700_000_000,
+            root_path: "/repo".to_string(),
+            findings: 3,
+            new_critical: vec![finding("UNSAFE_EVAL", 2)],
+        }];
+        let feed = atom_feed(&scans, "http://localhost:8080/feed.atom");
+        assert!(feed.contains("<feed xmlns=\"http://www.w3.org/2005/Atom\">"));
+        assert!(feed.contains("<updated>2023-11-14T22:13:20+00:00</updated>"));
+        assert!(feed.contains("<id>urn:code-guardian:scan:7</id>"));
+        assert!(feed.contains("<title>Scan 7 of /repo: 3 findings, 1 new Critical</title>"));
+        assert!(feed.contains("<title>New Critical UNSAFE_EVAL in src/app.js:2</title>"));
+        assert!(feed.contains("<summary>UNSAFE_EVAL: eval(a &lt; b)</summary>"));
+        assert_eq!(feed.matches("<entry>").count(), 2);
+        assert!(feed.ends_with("</feed>\n"));
+    }
+}
@@ -0,0 +1,82 @@
+//! Scan order for fast feedback: files likely to have findings are scanned,
+//! and their findings shown, before the rest.
+
+use crate::windows_paths;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Findings per file of earlier scans, keyed by canonical path so paths
+/// recorded relative to the working directory match absolute ones
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FindingDensity {
+    counts: HashMap<PathBuf, usize>,
+}
+
+impl FindingDensity {
+    /// Counts the findings of each of `file_paths`, one entry per finding.
+    /// Files that no longer exist are left out.
+    pub fn from_paths<'a>(file_paths: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut counts = HashMap::new();
+        for path in file_paths {
+            if let Ok(path) = windows_paths::canonicalize(Path::new(path)) {
+                *counts.entry(path).or_insert(0) += 1;
+            }
+        }
+        Self { counts }
+    }
+
+    /// Findings earlier scans had in the file at `path`
+    pub fn findings_in(&self, path: &Path) -> usize {
+        windows_paths::canonicalize(path)
+            .ok()
+            .and_then(|path| self.counts.get(&path).copied())
+            .unwrap_or(0)
+    }
+}
+
+/// Orders `files` by the findings earlier scans had in them, most first, then
+/// by modification time, most recent first. Files keep their order on ties.
+pub fn prioritize_files(files: &mut [PathBuf], density: &FindingDensity) {
+    files.sort_by_cached_key(|path| {
+        let modified = path
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        (
+            std::cmp::Reverse(density.findings_in(path)),
+            std::cmp::Reverse(modified),
+        )
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_prioritize_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths: Vec<PathBuf> = ["clean.rs", "some.rs", "risky.rs"]
+            .iter()
+            .map(|name| dir.path().join(name))
+            .collect();
+        for path in &paths {
+            fs::write(path, "").unwrap();
+        }
+        let some = paths[1].to_string_lossy().to_string();
+        let risky = paths[2].to_string_lossy().to_string();
+        let density =
+            FindingDensity::from_paths([risky.as_str(), some.as_str(), risky.as_str(), "gone.rs"]);
+        assert_eq!(density.findings_in(&paths[2]), 2);
+        assert_eq!(density.findings_in(&paths[0]), 0);
+
+        let mut files = paths.clone();
+        prioritize_files(&mut files, &density);
+        assert_eq!(
+            files,
+            vec![paths[2].clone(), paths[1].clone(), paths[0].clone()]
+        );
+    }
+}
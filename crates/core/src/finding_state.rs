@@ -0,0 +1,161 @@
+//! Lifecycle of findings tracked across scans of the same root. A finding
+//! is identified by a fingerprint that survives line shifts, so it keeps its
+//! state while unrelated code above it changes.
+
+use crate::scan_cache::content_hash;
+use crate::Match;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Where a tracked finding is in its lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingState {
+    /// Seen in the latest scan and not triaged yet
+    Open,
+    /// Known and accepted for now; still reported
+    Acknowledged,
+    /// Gone from the latest scan of its root
+    Fixed,
+    /// Deliberately left in place
+    WontFix,
+}
+
+impl FindingState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Acknowledged => "acknowledged",
+            Self::Fixed => "fixed",
+            Self::WontFix => "wontfix",
+        }
+    }
+}
+
+impl fmt::Display for FindingState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FindingState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(Self::Open),
+            "acknowledged" => Ok(Self::Acknowledged),
+            "fixed" => Ok(Self::Fixed),
+            "wontfix" => Ok(Self::WontFix),
+            _ => Err(format!(
+                "Unknown finding state '{}'. Supported: open, acknowledged, fixed, wontfix",
+                s
+            )),
+        }
+    }
+}
+
+/// Fingerprints of `matches`, found under `root`, in the same order. A
+/// fingerprint hashes the path relative to `root`, the pattern and the
+/// trimmed message, but not the line, plus how many identical findings came
+/// before it in the file so repeated lines stay distinct.
+pub fn finding_fingerprints(matches: &[Match], root: &str) -> Vec<String> {
+    let mut occurrences: HashMap<(&str, &str, &str), usize> = HashMap::new();
+    matches
+        .iter()
+        .map(|m| {
+            let path = Path::new(m.file_path.as_str());
+            let relative = path
+                .strip_prefix(root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let key = (m.file_path.as_str(), m.pattern.as_str(), m.message.trim());
+            let occurrence = occurrences.entry(key).or_insert(0);
+            let input = format!("{}\0{}\0{}\0{}", relative, key.1, key.2, occurrence);
+            *occurrence += 1;
+            format!("{:016x}", content_hash(input.as_bytes()))
+        })
+        .collect()
+}
+
+/// The findings of `head`, scanned under `head_root`, whose fingerprint is
+/// not among those of `base`, scanned under `base_root`: what a branch added
+/// over the scan of its target branch
+pub fn new_findings(
+    head: &[Match],
+    head_root: &str,
+    base: &[Match],
+    base_root: &str,
+) -> Vec<Match> {
+    let known: HashSet<String> = finding_fingerprints(base, base_root).into_iter().collect();
+    head.iter()
+        .zip(finding_fingerprints(head, head_root))
+        .filter(|(_, fingerprint)| !known.contains(fingerprint))
+        .map(|(m, _)| m.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(file_path: &str, line_number: usize, message: &str) -> Match {
+        Match::new(file_path, line_number, "TODO", message)
+    }
+
+    #[test]
+    fn test_fingerprints_survive_line_shifts() {
+        let before = finding_fingerprints(
+            &[
+                finding("/repo/src/a.rs", 3, "TODO: x"),
+                finding("/repo/src/a.rs", 9, "TODO: x"),
+            ],
+            "/repo",
+        );
+        assert_ne!(before[0], before[1]);
+
+        let after = finding_fingerprints(
+            &[
+                finding("/checkout/src/a.rs", 5, "  TODO: x"),
+                finding("/checkout/src/a.rs", 11, "TODO: x"),
+            ],
+            "/checkout",
+        );
+        assert_eq!(before, after);
+        assert_ne!(
+            before[0],
+            finding_fingerprints(&[finding("/repo/src/b.rs", 3, "TODO: x")], "/repo")[0]
+        );
+    }
+
+    #[test]
+    fn test_new_findings() {
+        let base = [finding("/main/src/a.rs", 3, "TODO: x")];
+        let head = [
+            finding("/mr/src/a.rs", 7, "TODO: x"),
+            finding("/mr/src/a.rs", 9, "TODO: y"),
+        ];
+        let added = new_findings(&head, "/mr", &base, "/main");
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].message, "TODO: y");
+        assert_eq!(new_findings(&head, "/mr", &[], "/main").len(), 2);
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        for state in [
+            FindingState::Open,
+            FindingState::Acknowledged,
+            FindingState::Fixed,
+            FindingState::WontFix,
+        ] {
+            assert_eq!(state.to_string().parse::<FindingState>(), Ok(state));
+        }
+        assert!("closed".parse::<FindingState>().is_err());
+    }
+}
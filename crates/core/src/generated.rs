@@ -0,0 +1,158 @@
+use crate::detectors::build_glob_set;
+use crate::errors::ScanResult;
+use globset::GlobSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Files produced by minifiers, code generators and vendored dependencies
+pub const DEFAULT_GENERATED_GLOBS: &[&str] = &[
+    // Minified bundles
+    "**/*.min.js",
+    "**/*.min.mjs",
+    "**/*.min.css",
+    // Protocol buffers and gRPC
+    "**/*.pb.go",
+    "**/*.pb.cc",
+    "**/*.pb.h",
+    "**/*_pb2.py",
+    "**/*_pb2_grpc.py",
+    "**/*_pb.js",
+    "**/*_pb.d.ts",
+    // Other code generators
+    "**/*.g.dart",
+    "**/*.freezed.dart",
+    "**/*.Designer.cs",
+    "**/*.designer.cs",
+    "**/*.generated.*",
+    // Vendored code
+    "**/vendor/**",
+    "**/third_party/**",
+    "**/third-party/**",
+];
+
+/// Markers that code generators put in a file header
+const GENERATED_HEADER_MARKERS: &[&str] = &[
+    "@generated",
+    "DO NOT EDIT",
+    "<auto-generated",
+    "Generated by the protocol buffer compiler",
+];
+
+/// How much of a file is searched for a generated-code header marker
+const HEADER_BYTES: usize = 2048;
+
+/// Recognizes generated and vendored files by path and by header markers
+/// such as `@generated` or `Code generated ... DO NOT EDIT.`, and counts
+/// how many files it has filtered out.
+pub struct GeneratedFileFilter {
+    globs: GlobSet,
+    skipped: AtomicUsize,
+}
+
+impl GeneratedFileFilter {
+    /// Creates a filter for [`DEFAULT_GENERATED_GLOBS`] plus `extra_globs`
+    pub fn new<S: AsRef<str>>(extra_globs: &[S]) -> ScanResult<Self> {
+        let globs: Vec<&str> = DEFAULT_GENERATED_GLOBS
+            .iter()
+            .copied()
+            .chain(extra_globs.iter().map(|glob| glob.as_ref()))
+            .collect();
+        Ok(Self {
+            globs: build_glob_set(&globs)?,
+            skipped: AtomicUsize::new(0),
+        })
+    }
+
+    /// True if the path matches a generated or vendored glob
+    pub fn is_generated_path(&self, path: &Path) -> bool {
+        self.globs.is_match(path)
+    }
+
+    /// True if the path or the file header marks the file as generated
+    pub fn is_generated(&self, path: &Path) -> bool {
+        self.is_generated_path(path) || has_generated_header(path)
+    }
+
+    /// Checks a file and counts it as skipped if it is generated
+    pub fn skip(&self, path: &Path) -> bool {
+        let generated = self.is_generated(path);
+        if generated {
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+        }
+        generated
+    }
+
+    /// Number of files skipped so far
+    pub fn skipped(&self) -> usize {
+        self.skipped.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for GeneratedFileFilter {
+    fn default() -> Self {
+        Self::new::<&str>(&[]).expect("default generated globs are valid")
+    }
+}
+
+fn has_generated_header(path: &Path) -> bool {
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    let mut header = Vec::with_capacity(HEADER_BYTES);
+    if file
+        .take(HEADER_BYTES as u64)
+        .read_to_end(&mut header)
+        .is_err()
+    {
+        return false;
+    }
+    let header = String::from_utf8_lossy(&header);
+    GENERATED_HEADER_MARKERS
+        .iter()
+        .any(|marker| header.contains(marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_generated_paths() {
+        let filter = GeneratedFileFilter::default();
+        assert!(filter.is_generated_path(Path::new("web/dist/app.min.js")));
+        assert!(filter.is_generated_path(Path::new("api/v1/user.pb.go")));
+        assert!(filter.is_generated_path(Path::new("proto/user_pb2.py")));
+        assert!(filter.is_generated_path(Path::new("vendor/github.com/x/y.go")));
+        assert!(filter.is_generated_path(Path::new("/repo/third_party/zlib/inflate.c")));
+        assert!(!filter.is_generated_path(Path::new("src/main.rs")));
+        assert!(!filter.is_generated_path(Path::new("src/vendors.rs")));
+
+        let filter = GeneratedFileFilter::new(&["**/src/gen/**"]).unwrap();
+        assert!(filter.is_generated_path(Path::new("app/src/gen/schema.rs")));
+        assert!(GeneratedFileFilter::new(&["[invalid"]).is_err());
+    }
+
+    #[test]
+    fn test_generated_headers() {
+        let temp_dir = TempDir::new().unwrap();
+        let go = temp_dir.path().join("types.go");
+        std::fs::write(
+            &go,
+            "// Code generated by stringer; DO NOT EDIT.\n\npackage types\n",
+        )
+        .unwrap();
+        let rust = temp_dir.path().join("bindings.rs");
+        std::fs::write(&rust, "// @generated by build.rs\n// TODO: x\n").unwrap();
+        let handwritten = temp_dir.path().join("lib.rs");
+        std::fs::write(&handwritten, "// TODO: handwritten\n").unwrap();
+
+        let filter = GeneratedFileFilter::default();
+        assert!(filter.skip(&go));
+        assert!(filter.skip(&rust));
+        assert!(!filter.skip(&handwritten));
+        assert_eq!(filter.skipped(), 2);
+    }
+}
@@ -0,0 +1,153 @@
+//! Merge request decoration on GitLab: a discussion on the merge request
+//! listing the findings its scan added, posted through the GitLab REST API.
+
+use crate::errors::{ScanError, ScanResult};
+use crate::webhook::post_json;
+use crate::Match;
+use std::time::Duration;
+
+/// Environment variable holding the access token, which needs the `api` scope
+pub const GITLAB_TOKEN_ENV: &str = "GITLAB_TOKEN";
+
+/// API of gitlab.com, used outside GitLab CI when no URL is given
+pub const DEFAULT_GITLAB_API_URL: &str = "https://gitlab.com/api/v4";
+
+/// Findings listed in a discussion; the rest are only counted
+const MAX_LISTED_FINDINGS: usize = 50;
+
+/// Client of the merge request API of one GitLab project
+#[derive(Debug, Clone)]
+pub struct GitLabClient {
+    api_url: String,
+    project: String,
+    token: String,
+    timeout: Duration,
+}
+
+impl GitLabClient {
+    /// `project` is the numeric ID or the path, e.g. `group/project`
+    pub fn new(
+        api_url: impl Into<String>,
+        project: impl Into<String>,
+        token: impl Into<String>,
+    ) -> Self {
+        Self {
+            api_url: api_url.into().trim_end_matches('/').to_string(),
+            project: project.into(),
+            token: token.into(),
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    fn discussions_url(&self, merge_request: u64) -> String {
+        format!(
+            "{}/projects/{}/merge_requests/{}/discussions",
+            self.api_url,
+            encode_path_segment(&self.project),
+            merge_request
+        )
+    }
+
+    /// Starts a discussion on merge request `merge_request` and returns its ID
+    pub fn post_discussion(&self, merge_request: u64, body: &str) -> ScanResult<String> {
+        let url = self.discussions_url(merge_request);
+        let request = serde_json::json!({ "body": body });
+        let token = [format!("PRIVATE-TOKEN: {}", self.token)];
+        let response = post_json(
+            &url,
+            &[],
+            &token,
+            request.to_string().as_bytes(),
+            self.timeout,
+        )
+        .map_err(|e| ScanError::integration("GitLab", e))?;
+        let discussion: serde_json::Value = serde_json::from_slice(&response)
+            .map_err(|e| ScanError::integration("GitLab", format!("unexpected response: {}", e)))?;
+        discussion["id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| ScanError::integration("GitLab", "response has no discussion ID"))
+    }
+}
+
+/// `segment` with everything but unreserved characters percent-encoded, so
+/// project paths like `group/project` fit in one path segment
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Markdown body of the discussion listing the findings `added` by scan
+/// `scan_id` over scan `base_id`
+pub fn merge_request_note(added: &[Match], scan_id: i64, base_id: Option<i64>) -> String {
+    let compared = match base_id {
+        Some(base_id) => format!("scan {} against scan {}", scan_id, base_id),
+        None => format!("scan {}", scan_id),
+    };
+    let mut note = format!(
+        "### code-guardian: {} new finding{}\n\nFrom {}.\n\n| Rule | Location | Message |\n|------|----------|---------|\n",
+        added.len(),
+        if added.len() == 1 { "" } else { "s" },
+        compared
+    );
+    for m in added.iter().take(MAX_LISTED_FINDINGS) {
+        note.push_str(&format!(
+            "| `{}` | `{}:{}` | {} |\n",
+            m.pattern,
+            m.file_path,
+            m.line_number,
+            table_cell(&m.message)
+        ));
+    }
+    if added.len() > MAX_LISTED_FINDINGS {
+        note.push_str(&format!(
+            "\n…and {} more.\n",
+            added.len() - MAX_LISTED_FINDINGS
+        ));
+    }
+    note
+}
+
+/// `text` on one line, with the pipes that would end a table cell escaped
+fn table_cell(text: &str) -> String {
+    text.trim().replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(line_number: usize, message: &str) -> Match {
+        Match::new("src/lib.rs", line_number, "TODO", message)
+    }
+
+    #[test]
+    fn test_discussions_url_encodes_project_path() {
+        let client = GitLabClient::new("https://gitlab.example.com/api/v4/", "group/sub/app", "t");
+        assert_eq!(
+            client.discussions_url(42),
+            "https://gitlab.example.com/api/v4/projects/group%2Fsub%2Fapp/merge_requests/42/discussions"
+        );
+    }
+
+    #[test]
+    fn test_merge_request_note() {
+        let note = merge_request_note(&[finding(3, "TODO: a | b")], 8, Some(7));
+        assert!(note.starts_with("### code-guardian: 1 new finding\n\nFrom scan 8 against scan 7."));
+        assert!(note.contains("| `TODO` | `src/lib.rs:3` | TODO: a \\| b |\n"));
+
+        let many: Vec<Match> = (1..=52).map(|line| finding(line, "TODO")).collect();
+        let note = merge_request_note(&many, 8, None);
+        assert!(note.contains("52 new findings"));
+        assert!(note.contains("`src/lib.rs:50`"));
+        assert!(!note.contains("`src/lib.rs:51`"));
+        assert!(note.ends_with("…and 2 more.\n"));
+    }
+}
@@ -0,0 +1,299 @@
+//! Go-specific detectors: debug printing in library packages, errors that are
+//! dropped or never checked, panics outside tests and sleeps in HTTP handlers
+
+use crate::comment_spans::CommentIndex;
+use crate::detectors::{classify, detect_code_pattern, has_extension};
+use crate::line_index::LineIndex;
+use crate::quality_detectors::{enclosing_function, function_spans, CODE_QUALITY_CATEGORY};
+use crate::{Match, PatternDetector, Severity, SharedStr};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::Path;
+
+lazy_static! {
+    static ref PACKAGE_REGEX: Regex = Regex::new(r"(?m)^package\s+(\w+)").unwrap();
+
+    static ref FMT_PRINT_REGEX: Regex = Regex::new(r"\bfmt\.Print(?:ln|f)?\(").unwrap();
+
+    /// `_ = err`, and `v, _ := f()` dropping the error of a call
+    static ref DISCARDED_ERROR_REGEX: Regex = Regex::new(
+        r"\b_\s*=\s*err\b|\b\w+\s*,\s*_\s*:?=\s*[\w.]*\w\("
+    ).unwrap();
+
+    /// A statement assigning `err`, alone or with other results
+    static ref ERR_ASSIGNMENT_REGEX: Regex = Regex::new(
+        r"^[ \t]*(?:[\w.]+\s*,\s*)*err\s*:?=[^=]"
+    ).unwrap();
+
+    static ref ERR_WORD_REGEX: Regex = Regex::new(r"\berr\b").unwrap();
+
+    static ref PANIC_CALL_REGEX: Regex = Regex::new(r"\bpanic\(").unwrap();
+
+    static ref SLEEP_CALL_REGEX: Regex = Regex::new(r"\btime\.Sleep\(").unwrap();
+
+    /// Parameters of `net/http`, gin, echo and fiber handlers
+    static ref HANDLER_PARAMETER_REGEX: Regex = Regex::new(
+        r"\bhttp\.ResponseWriter\b|\*gin\.Context\b|\becho\.Context\b|\*fiber\.Ctx\b"
+    ).unwrap();
+}
+
+fn is_go_test(file_path: &Path) -> bool {
+    file_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with("_test.go"))
+}
+
+/// Detector for `fmt.Println` and friends outside `package main`, where
+/// output belongs to the caller and should go through a logger instead
+pub struct GoPrintDetector;
+
+impl PatternDetector for GoPrintDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) || is_go_test(file_path) {
+            return Vec::new();
+        }
+        let package = PACKAGE_REGEX
+            .captures(content)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str());
+        if package == Some("main") {
+            return Vec::new();
+        }
+        classify(
+            detect_code_pattern(content, file_path, "GO_PRINT", &FMT_PRINT_REGEX),
+            Severity::Medium,
+            CODE_QUALITY_CATEGORY,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["go"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&FMT_PRINT_REGEX)
+    }
+}
+
+/// Detector for errors that are thrown away with `_` or assigned to `err`
+/// and never looked at before `err` is assigned again or the function ends
+pub struct GoIgnoredErrorDetector;
+
+impl PatternDetector for GoIgnoredErrorDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let mut matches = detect_code_pattern(
+            content,
+            file_path,
+            "GO_IGNORED_ERROR",
+            &DISCARDED_ERROR_REGEX,
+        );
+
+        let comments = CommentIndex::new(content, file_path);
+        let index = LineIndex::new(content);
+        let lines: Vec<&str> = content.lines().collect();
+        let code_line = |i: usize| {
+            let start = index.line_start(i + 1);
+            !comments.is_comment(start + lines[i].len() - lines[i].trim_start().len())
+        };
+        let mut shared_path = None;
+        for (i, line) in lines.iter().enumerate() {
+            if !ERR_ASSIGNMENT_REGEX.is_match(line) || !code_line(i) {
+                continue;
+            }
+            let mut checked = false;
+            for (j, next) in lines.iter().enumerate().skip(i + 1) {
+                // The function ends, or `err` is overwritten unread
+                if next.starts_with('}') || (ERR_ASSIGNMENT_REGEX.is_match(next) && code_line(j)) {
+                    break;
+                }
+                if ERR_WORD_REGEX.is_match(next) && code_line(j) {
+                    checked = true;
+                    break;
+                }
+            }
+            if checked {
+                continue;
+            }
+            let column = line.len() - line.trim_start().len() + 1;
+            matches.push(Match {
+                file_path: shared_path
+                    .get_or_insert_with(|| SharedStr::from_path(file_path))
+                    .clone(),
+                line_number: i + 1,
+                column,
+                pattern: SharedStr::interned("GO_IGNORED_ERROR"),
+                message: format!("GO_IGNORED_ERROR: err is never checked: {}", line.trim()),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
+            });
+        }
+        matches.sort_by_key(|m| (m.line_number, m.column));
+        classify(matches, Severity::Medium, CODE_QUALITY_CATEGORY)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["go"])
+    }
+}
+
+/// Detector for `panic(` outside tests. `Must*` functions, which panic by
+/// convention, are left alone.
+pub struct GoPanicDetector;
+
+impl PatternDetector for GoPanicDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) || is_go_test(file_path) {
+            return Vec::new();
+        }
+        let mut matches = detect_code_pattern(content, file_path, "GO_PANIC", &PANIC_CALL_REGEX);
+        if matches.is_empty() {
+            return matches;
+        }
+        let functions = function_spans(content, file_path);
+        let index = LineIndex::new(content);
+        matches.retain(|m| {
+            let offset = index.offset(content, m.line_number, m.column);
+            !enclosing_function(&functions, offset).is_some_and(|f| f.name.starts_with("Must"))
+        });
+        classify(matches, Severity::Medium, CODE_QUALITY_CATEGORY)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["go"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&PANIC_CALL_REGEX)
+    }
+}
+
+/// Detector for `time.Sleep` in HTTP handlers of `net/http`, gin, echo and
+/// fiber, which holds the request and its goroutine for the whole sleep
+pub struct GoSleepInHandlerDetector;
+
+impl PatternDetector for GoSleepInHandlerDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) || is_go_test(file_path) {
+            return Vec::new();
+        }
+        let mut matches =
+            detect_code_pattern(content, file_path, "GO_SLEEP_IN_HANDLER", &SLEEP_CALL_REGEX);
+        if matches.is_empty() {
+            return matches;
+        }
+        let functions = function_spans(content, file_path);
+        let index = LineIndex::new(content);
+        matches.retain(|m| {
+            let offset = index.offset(content, m.line_number, m.column);
+            enclosing_function(&functions, offset).is_some_and(|f| {
+                let signature = &content[f.start..f.end];
+                let signature = &signature[..signature.find('{').unwrap_or(signature.len())];
+                HANDLER_PARAMETER_REGEX.is_match(signature)
+            })
+        });
+        classify(matches, Severity::Medium, CODE_QUALITY_CATEGORY)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["go"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&SLEEP_CALL_REGEX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(detector: &dyn PatternDetector, content: &str, path: &str) -> Vec<usize> {
+        detector
+            .detect(content, Path::new(path))
+            .iter()
+            .map(|m| m.line_number)
+            .collect()
+    }
+
+    const SERVER: &str = r#"package server
+
+import (
+	"fmt"
+	"net/http"
+	"time"
+)
+
+func MustLoad(path string) *Config {
+	cfg, err := load(path)
+	if err != nil {
+		panic(err)
+	}
+	return cfg
+}
+
+func handle(w http.ResponseWriter, r *http.Request) {
+	time.Sleep(2 * time.Second)
+	fmt.Println("handling", r.URL)
+	n, _ := strconv.Atoi(r.URL.Query().Get("n"))
+	err := save(n)
+	err = notify(n)
+	if err != nil {
+		panic(err)
+	}
+}
+
+func retry() {
+	// fmt.Println("retrying")
+	time.Sleep(time.Second)
+	_ = err
+	for _, v := range items {
+		use(v)
+	}
+}
+"#;
+
+    #[test]
+    fn test_go_print_detector() {
+        assert_eq!(
+            lines(&GoPrintDetector, SERVER, "server/server.go"),
+            vec![19]
+        );
+        let main = SERVER.replacen("package server", "package main", 1);
+        assert!(lines(&GoPrintDetector, &main, "cmd/main.go").is_empty());
+        assert!(lines(&GoPrintDetector, SERVER, "server/server_test.go").is_empty());
+    }
+
+    #[test]
+    fn test_go_ignored_error_detector() {
+        let matches = GoIgnoredErrorDetector.detect(SERVER, Path::new("server.go"));
+        assert_eq!(
+            matches.iter().map(|m| m.line_number).collect::<Vec<_>>(),
+            vec![20, 21, 31]
+        );
+        assert_eq!(
+            matches[1].message,
+            "GO_IGNORED_ERROR: err is never checked: err := save(n)"
+        );
+        assert_eq!(matches[1].category.as_deref(), Some("CodeQuality"));
+    }
+
+    #[test]
+    fn test_go_panic_detector() {
+        assert_eq!(lines(&GoPanicDetector, SERVER, "server.go"), vec![24]);
+        assert!(lines(&GoPanicDetector, SERVER, "server_test.go").is_empty());
+    }
+
+    #[test]
+    fn test_go_sleep_in_handler_detector() {
+        assert_eq!(
+            lines(&GoSleepInHandlerDetector, SERVER, "server.go"),
+            vec![18]
+        );
+    }
+}
@@ -2,13 +2,26 @@
 //!
 //! Provides Kubernetes-compatible health and readiness endpoints
 //! along with Prometheus metrics for comprehensive monitoring.
-
-use axum::{extract::State, http::StatusCode, response::Json, routing::get, Router};
+//!
+//! Given a [`TokenVerifier`], `/metrics` and `/feed.atom` need a bearer
+//! token once any token exists, and `POST /scans` always needs a token of
+//! the scan scope. The probes stay open for Kubernetes.
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
+    Router,
+};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::net::TcpListener;
 use tracing::{error, info};
 // Prometheus imports handled in metrics module
+use crate::api_token::{bearer_token, TokenScope};
+use crate::errors::ScanResult;
+use crate::feed::{atom_feed, ScanFeed};
 use crate::metrics::{get_metrics, init_metrics};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -28,10 +41,54 @@ pub struct HealthChecks {
     pub disk: String,
 }
 
+/// Integrity of the findings database, checked by `/health`
+pub trait DatabaseIntegrity: Send + Sync {
+    /// Problems found in the database, empty when it is intact
+    fn integrity_problems(&self) -> ScanResult<Vec<String>>;
+}
+
+/// Bearer tokens the server accepts
+pub trait TokenVerifier: Send + Sync {
+    /// Whether any token exists; until one does, reading is open
+    fn has_tokens(&self) -> ScanResult<bool>;
+    /// Scope of `token`, None if it is not a valid token
+    fn verify(&self, token: &str) -> ScanResult<Option<TokenScope>>;
+}
+
+/// Body of `POST /scans`; every field is optional
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanRequest {
+    /// Path to scan, relative to the served root
+    pub path: Option<String>,
+    /// Detector profile
+    pub profile: Option<String>,
+    /// Project to save the scan under
+    pub project: Option<String>,
+}
+
+/// Runs the scans requested with `POST /scans`
+pub trait ScanTrigger: Send + Sync {
+    /// Scans as requested and returns a summary with the ID of the saved
+    /// scan, or a message telling the client what was wrong
+    fn trigger_scan(&self, request: &ScanRequest) -> Result<serde_json::Value, String>;
+}
+
 #[derive(Clone)]
 pub struct HealthState {
     pub version: String,
     pub start_time: std::time::Instant,
+    /// Scans served at `/feed.atom`; without it the feed is not found
+    pub feed: Option<Arc<dyn ScanFeed>>,
+    /// Database whose integrity `/health` reports; without it the database
+    /// check only looks for `DATABASE_URL`
+    pub database: Option<Arc<dyn DatabaseIntegrity>>,
+    /// Tokens guarding the feed, metrics and scans; without it they are open
+    /// and scans can't be triggered
+    pub tokens: Option<Arc<dyn TokenVerifier>>,
+    /// Runs the scans of `POST /scans`; without it they are not found
+    pub scans: Option<Arc<dyn ScanTrigger>>,
+    /// Serves https with this config instead of plain http
+    pub tls: Option<Arc<rustls::ServerConfig>>,
 }
 
 impl Default for HealthState {
@@ -39,6 +96,22 @@ impl Default for HealthState {
         Self {
             version: env!("CARGO_PKG_VERSION").to_string(),
             start_time: std::time::Instant::now(),
+            feed: None,
+            database: None,
+            tokens: None,
+            scans: None,
+            tls: None,
+        }
+    }
+}
+
+impl HealthState {
+    /// Scheme the server is reached over, given its TLS config
+    pub fn scheme(&self) -> &'static str {
+        if self.tls.is_some() {
+            "https"
+        } else {
+            "http"
         }
     }
 }
@@ -50,7 +123,10 @@ pub async fn health_handler(
     let uptime = state.start_time.elapsed().as_secs();
 
     // Perform health checks
-    let database_status = check_database_health().await;
+    let database_status = match state.database.clone() {
+        Some(database) => check_database_integrity(database).await,
+        None => check_database_health().await,
+    };
     let scanner_status = check_scanner_health().await;
     let memory_status = check_memory_health().await;
     let disk_status = check_disk_health().await;
@@ -120,7 +196,58 @@ pub async fn liveness_handler(State(state): State<Arc<HealthState>>) -> Json<ser
     }))
 }
 
-pub async fn metrics_handler() -> Result<String, StatusCode> {
+/// Checks the bearer token of a request against the `needed` scope:
+/// 401 without a valid token, 403 if its scope is too narrow
+pub async fn authorize(
+    state: &HealthState,
+    headers: &HeaderMap,
+    needed: TokenScope,
+) -> Result<(), Response> {
+    let unauthorized = || {
+        (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+        )
+            .into_response()
+    };
+    let Some(tokens) = state.tokens.clone() else {
+        return match needed {
+            TokenScope::Read => Ok(()),
+            TokenScope::Scan => Err(unauthorized()),
+        };
+    };
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(bearer_token)
+        .map(str::to_string);
+    // Tokens are read from the scan database, which blocks. None when no
+    // token was given and none exist, else the scope of the given token.
+    let scope = tokio::task::spawn_blocking(move || match token {
+        Some(token) => tokens.verify(&token).map(Some),
+        None => tokens
+            .has_tokens()
+            .map(|has_tokens| has_tokens.then_some(None)),
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?
+    .map_err(|e| {
+        error!("Failed to check the API token: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    })?;
+    match scope {
+        None if needed == TokenScope::Read => Ok(()),
+        Some(Some(scope)) if scope.allows(needed) => Ok(()),
+        Some(Some(_)) => Err(StatusCode::FORBIDDEN.into_response()),
+        _ => Err(unauthorized()),
+    }
+}
+
+pub async fn metrics_handler(
+    State(state): State<Arc<HealthState>>,
+    headers: HeaderMap,
+) -> Result<String, Response> {
+    authorize(&state, &headers, TokenScope::Read).await?;
     match get_metrics() {
         Ok(metrics) => {
             info!("Metrics endpoint accessed");
@@ -128,11 +255,77 @@ pub async fn metrics_handler() -> Result<String, StatusCode> {
         }
         Err(e) => {
             error!("Failed to get metrics: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
         }
     }
 }
 
+pub async fn feed_handler(
+    State(state): State<Arc<HealthState>>,
+    headers: HeaderMap,
+) -> Result<Response, Response> {
+    let feed = state
+        .feed
+        .clone()
+        .ok_or(StatusCode::NOT_FOUND.into_response())?;
+    authorize(&state, &headers, TokenScope::Read).await?;
+    let host = headers
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .unwrap_or("localhost");
+    let feed_url = format!("{}://{}/feed.atom", state.scheme(), host);
+    // Feeds read the scan database, which blocks
+    let scans = tokio::task::spawn_blocking(move || feed.recent_scans())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?
+        .map_err(|e| {
+            error!("Failed to read scans for the feed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+    Ok((
+        [(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")],
+        atom_feed(&scans, &feed_url),
+    )
+        .into_response())
+}
+
+/// `POST /scans`: runs a scan with a token of the scan scope and answers
+/// with its summary
+pub async fn scan_handler(
+    State(state): State<Arc<HealthState>>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Result<Response, Response> {
+    let scans = state
+        .scans
+        .clone()
+        .ok_or(StatusCode::NOT_FOUND.into_response())?;
+    authorize(&state, &headers, TokenScope::Scan).await?;
+    let request: ScanRequest = if body.is_empty() {
+        ScanRequest::default()
+    } else {
+        serde_json::from_slice(&body).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": e.to_string() })),
+            )
+                .into_response()
+        })?
+    };
+    // Scans read files, which blocks
+    let outcome = tokio::task::spawn_blocking(move || scans.trigger_scan(&request))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+    Ok(match outcome {
+        Ok(summary) => (StatusCode::CREATED, Json(summary)).into_response(),
+        Err(message) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": message })),
+        )
+            .into_response(),
+    })
+}
+
 async fn check_database_health() -> String {
     // Check database connectivity
     // For now, assume healthy - in a real implementation, this would ping the database
@@ -142,6 +335,24 @@ async fn check_database_health() -> String {
     }
 }
 
+async fn check_database_integrity(database: Arc<dyn DatabaseIntegrity>) -> String {
+    match tokio::task::spawn_blocking(move || database.integrity_problems()).await {
+        Ok(Ok(problems)) if problems.is_empty() => "healthy".to_string(),
+        Ok(Ok(problems)) => {
+            error!("Database integrity check failed: {}", problems.join("; "));
+            "unhealthy".to_string()
+        }
+        Ok(Err(e)) => {
+            error!("Database integrity check could not run: {}", e);
+            "unhealthy".to_string()
+        }
+        Err(e) => {
+            error!("Database integrity check panicked: {}", e);
+            "unhealthy".to_string()
+        }
+    }
+}
+
 async fn check_scanner_health() -> String {
     // Check if scanner components are functional
     use crate::detector_factory::DetectorFactory;
@@ -211,7 +422,21 @@ async fn check_readiness() -> Result<(), Box<dyn std::error::Error + Send + Sync
 pub async fn start_health_server(
     port: u16,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let state = Arc::new(HealthState::default());
+    start_server(port, HealthState::default()).await
+}
+
+/// Serves the health endpoints and, given a feed in `state`, the Atom feed
+/// of its scans. Given a database, `/health` reports its integrity; given a
+/// scan trigger, `POST /scans` runs scans.
+pub async fn start_server(
+    port: u16,
+    state: HealthState,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let has_feed = state.feed.is_some();
+    let has_scans = state.scans.is_some();
+    let scheme = state.scheme();
+    let tls = state.tls.clone();
+    let state = Arc::new(state);
 
     // Initialize metrics
     init_metrics().map_err(|e| format!("Failed to initialize metrics: {}", e))?;
@@ -221,19 +446,36 @@ pub async fn start_health_server(
         .route("/ready", get(readiness_handler))
         .route("/live", get(liveness_handler))
         .route("/metrics", get(metrics_handler))
+        .route("/feed.atom", get(feed_handler))
+        .route("/scans", post(scan_handler))
         .with_state(state);
 
     let addr = format!("0.0.0.0:{}", port);
-    let listener = TcpListener::bind(&addr).await?;
-
-    info!("Health check server starting on {}", addr);
+    info!("Health check server starting on {}://{}", scheme, addr);
     info!("Endpoints available:");
     info!("  GET /health  - Comprehensive health check");
     info!("  GET /ready   - Readiness probe (Kubernetes)");
     info!("  GET /live    - Liveness probe (Kubernetes)");
     info!("  GET /metrics - Prometheus metrics");
+    if has_feed {
+        info!("  GET /feed.atom - Atom feed of recent scans");
+    }
+    if has_scans {
+        info!("  POST /scans - Run a scan");
+    }
 
-    axum::serve(listener, app).await?;
+    match tls {
+        Some(config) => {
+            let config = axum_server::tls_rustls::RustlsConfig::from_config(config);
+            axum_server::bind_rustls(addr.parse()?, config)
+                .serve(app.into_make_service())
+                .await?;
+        }
+        None => {
+            let listener = TcpListener::bind(&addr).await?;
+            axum::serve(listener, app).await?;
+        }
+    }
 
     Ok(())
 }
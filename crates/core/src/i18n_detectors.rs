@@ -0,0 +1,297 @@
+//! Localization audits: user-facing string literals in UI code that are not
+//! passed through a translation function. Off by default, since most
+//! codebases are not localized; enabled in the `i18n` config section.
+
+use crate::comment_spans::CommentIndex;
+use crate::detectors::{build_glob_set, has_extension, DEFAULT_TEST_PATH_GLOBS};
+use crate::line_index::LineIndex;
+use crate::{Match, PatternDetector, Severity, SharedStr};
+use globset::GlobSet;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Category reported by [`HardcodedStringDetector`]
+pub const LOCALIZATION_CATEGORY: &str = "Localization";
+
+/// Files holding UI code
+const UI_EXTENSIONS: &[&str] = &[
+    "js", "jsx", "mjs", "cjs", "ts", "tsx", "vue", "svelte", "html", "htm",
+];
+
+/// Files whose markup has text nodes: JSX and templates
+const MARKUP_EXTENSIONS: &[&str] = &["jsx", "tsx", "vue", "svelte", "html", "htm"];
+
+/// Translation functions and components of common i18n libraries
+const DEFAULT_TRANSLATION_FUNCTIONS: &[&str] = &[
+    "t",
+    "$t",
+    "i18n.t",
+    "i18next.t",
+    "intl.formatMessage",
+    "formatMessage",
+    "gettext",
+    "_",
+    "__",
+    "Trans",
+    "FormattedMessage",
+];
+
+lazy_static! {
+    /// Opening, closing and self-closing tags, with arrow functions allowed
+    /// in attributes
+    static ref TAG_REGEX: Regex = Regex::new(
+        r"<(?P<close>/)?(?P<tag>[A-Za-z][\w.:-]*)(?:=>|[^<>])*>"
+    ).unwrap();
+
+    /// Messages shown by `alert`, `confirm` and `prompt`
+    static ref DIALOG_REGEX: Regex = Regex::new(
+        r#"\b(?:alert|confirm|prompt)\(\s*(?:(?P<call>[\w.$]+)\(\s*)?(?P<literal>"(?:[^"\\\n]|\\.)*"|'(?:[^'\\\n]|\\.)*'|`(?:[^`\\]|\\.)*`)"#
+    ).unwrap();
+
+    /// User-facing attributes, as plain strings or JSX expressions
+    static ref ATTRIBUTE_REGEX: Regex = Regex::new(
+        r#"\b(?:placeholder|title|alt|aria-label|label)=(?:\{\s*(?:(?P<call>[\w.$]+)\(\s*)?)?(?P<literal>"(?:[^"\\\n]|\\.)*"|'(?:[^'\\\n]|\\.)*'|`(?:[^`\\]|\\.)*`)"#
+    ).unwrap();
+
+    static ref INTERPOLATION_REGEX: Regex = Regex::new(r"\$\{[^}]*\}|\{\{[^}]*\}\}").unwrap();
+
+    /// Two letters in a row, in any script
+    static ref WORD_REGEX: Regex = Regex::new(r"\p{L}{2}").unwrap();
+
+    /// Text that is really code, caught between a `>` and a `<` that are not tags
+    static ref CODE_LIKE_REGEX: Regex = Regex::new(r"[;=]|&&|\|\||//").unwrap();
+}
+
+/// Settings of the `HARDCODED_STRING` check, the `i18n` config section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct I18nConfig {
+    /// Run the check on every scan
+    pub enabled: bool,
+    /// Functions and components whose strings count as translated, such as
+    /// `t` for `t("Save")` or `Trans` for `<Trans>Save</Trans>`
+    pub translation_functions: Vec<String>,
+}
+
+impl Default for I18nConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            translation_functions: DEFAULT_TRANSLATION_FUNCTIONS
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Detector for user-facing strings that skip translation: JSX and template
+/// text nodes, messages of `alert`, `confirm` and `prompt`, and attributes
+/// such as `placeholder` and `aria-label`. Strings passed to a configured
+/// translation function, or inside one of its components, are left alone,
+/// as are test files.
+pub struct HardcodedStringDetector {
+    translation_functions: Vec<String>,
+    test_paths: GlobSet,
+}
+
+impl HardcodedStringDetector {
+    pub fn new(config: &I18nConfig) -> Self {
+        Self {
+            translation_functions: config.translation_functions.clone(),
+            test_paths: build_glob_set(DEFAULT_TEST_PATH_GLOBS)
+                .expect("default test globs are valid"),
+        }
+    }
+
+    fn is_translation(&self, name: &str) -> bool {
+        self.translation_functions.iter().any(|known| known == name)
+    }
+
+    /// The user-facing text of a string literal match, unless it is
+    /// translated or has no words outside its interpolations
+    fn literal_text<'a>(&self, caps: &Captures<'a>) -> Option<regex::Match<'a>> {
+        if caps
+            .name("call")
+            .is_some_and(|call| self.is_translation(call.as_str()))
+        {
+            return None;
+        }
+        let literal = caps.name("literal")?;
+        has_words(&literal.as_str()[1..literal.len() - 1]).then_some(literal)
+    }
+}
+
+impl Default for HardcodedStringDetector {
+    fn default() -> Self {
+        Self::new(&I18nConfig::default())
+    }
+}
+
+impl PatternDetector for HardcodedStringDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) || self.test_paths.is_match(file_path) {
+            return Vec::new();
+        }
+        // Byte offset and text of each string found
+        let mut found: Vec<(usize, &str)> = Vec::new();
+        if has_extension(file_path, MARKUP_EXTENSIONS) {
+            // Text after a closing tag belongs to the enclosing element, which
+            // the regex cannot see, so only text opening an element is checked
+            // against translation components
+            for caps in TAG_REGEX.captures_iter(content) {
+                let tag = caps.get(0).expect("group 0 always matches");
+                // `Props<T>` is a generic type, not a tag
+                if content[..tag.start()]
+                    .chars()
+                    .next_back()
+                    .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$')
+                {
+                    continue;
+                }
+                // A text node runs to the next tag or `{expression}`
+                let rest = &content[tag.end()..];
+                let len = rest.find(['<', '>', '{', '}']).unwrap_or(rest.len());
+                let text = &rest[..len];
+                let opening = caps.name("close").is_none();
+                if !matches!(rest[len..].chars().next(), Some('<' | '{'))
+                    || (opening && self.is_translation(&caps["tag"]))
+                    || CODE_LIKE_REGEX.is_match(text)
+                    || !has_words(text)
+                {
+                    continue;
+                }
+                let leading = text.len() - text.trim_start().len();
+                found.push((tag.end() + leading, text.trim()));
+            }
+        }
+        for re in [&*DIALOG_REGEX, &*ATTRIBUTE_REGEX] {
+            for caps in re.captures_iter(content) {
+                if let Some(literal) = self.literal_text(&caps) {
+                    found.push((literal.start(), literal.as_str()));
+                }
+            }
+        }
+        if found.is_empty() {
+            return Vec::new();
+        }
+        found.sort_by_key(|(start, _)| *start);
+
+        let comments = CommentIndex::new(content, file_path);
+        let lines = LineIndex::new(content);
+        let shared_path = SharedStr::from_path(file_path);
+        found
+            .into_iter()
+            .filter(|(start, _)| !comments.is_comment(*start))
+            .map(|(start, text)| {
+                let (line_number, column) = lines.position(content, start);
+                Match {
+                    file_path: shared_path.clone(),
+                    line_number,
+                    column,
+                    pattern: SharedStr::interned("HARDCODED_STRING"),
+                    message: format!(
+                        "HARDCODED_STRING: {} is not passed through a translation function",
+                        text
+                    ),
+                    severity: Some(Severity::Low),
+                    category: Some(LOCALIZATION_CATEGORY.to_string()),
+                    end: None,
+                    author: None,
+                }
+            })
+            .collect()
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, UI_EXTENSIONS)
+    }
+
+    fn config_fingerprint(&self) -> String {
+        self.translation_functions.join("\0")
+    }
+}
+
+/// Whether `text` has a word outside its `${...}` and `{{...}}` interpolations
+fn has_words(text: &str) -> bool {
+    WORD_REGEX.is_match(&INTERPOLATION_REGEX.replace_all(text, ""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(detector: &HardcodedStringDetector, content: &str, path: &str) -> Vec<String> {
+        detector
+            .detect(content, Path::new(path))
+            .into_iter()
+            .map(|m| {
+                format!(
+                    "{}:{}",
+                    m.line_number,
+                    m.message
+                        .trim_start_matches("HARDCODED_STRING: ")
+                        .trim_end_matches(" is not passed through a translation function")
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_hardcoded_string_detector() {
+        let detector = HardcodedStringDetector::default();
+        let tsx = r#"export function Form<T>(props: Props<T>) {
+  return (
+    <form onSubmit={() => save()}>
+      <h1>Edit profile</h1>
+      <p>{t("profile.hint")}</p>
+      <Trans>Welcome back</Trans>
+      <input placeholder="Your name" title={t("name")} />
+      <img alt={`Avatar of ${name}`} src={url} />
+      <span>{count}</span>
+      {/* <p>Old text</p> */}
+      <button onClick={() => alert('Saved!')}>
+        Save
+      </button>
+    </form>
+  );
+}"#;
+        assert_eq!(
+            messages(&detector, tsx, "src/Form.tsx"),
+            vec![
+                "4:Edit profile",
+                "7:\"Your name\"",
+                "8:`Avatar of ${name}`",
+                "11:'Saved!'",
+                "12:Save",
+            ]
+        );
+        let matches = detector.detect(tsx, Path::new("src/Form.tsx"));
+        assert_eq!(matches[0].column, 11);
+        assert_eq!(matches[0].category.as_deref(), Some("Localization"));
+
+        assert!(messages(&detector, tsx, "src/__tests__/Form.tsx").is_empty());
+        // Text nodes are only read from markup files
+        assert_eq!(
+            messages(
+                &detector,
+                "confirm(\"Delete?\");\nlet x = a < b && c > d;\n",
+                "app.ts"
+            ),
+            vec!["1:\"Delete?\""]
+        );
+    }
+
+    #[test]
+    fn test_custom_translation_functions() {
+        let detector = HardcodedStringDetector::new(&I18nConfig {
+            enabled: true,
+            translation_functions: vec!["translate".to_string()],
+        });
+        let js = "alert(translate('Saved'));\nalert(t('Saved'));\n";
+        assert_eq!(messages(&detector, js, "app.js"), vec!["2:'Saved'"]);
+    }
+}
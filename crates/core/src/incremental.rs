@@ -1,5 +1,7 @@
-use crate::{Match, PatternDetector};
-use anyhow::Result;
+use crate::{
+    read_stable, DirectoryCache, DirectoryWalkStats, Icon, Match, PatternDetector, ScanError,
+    ScanResult, SkipReason, SkippedFile,
+};
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -25,6 +27,10 @@ pub struct IncrementalState {
     pub last_full_scan: u64,
     pub file_metadata: HashMap<PathBuf, FileMetadata>,
     pub scan_history: Vec<IncrementalScanResult>,
+    /// Listings of the directories walked with pruning, see
+    /// [`IncrementalScanner::with_directory_pruning`]
+    #[serde(default)]
+    pub directories: DirectoryCache,
 }
 
 /// Result of an incremental scan
@@ -32,12 +38,23 @@ pub struct IncrementalState {
 pub struct IncrementalScanResult {
     pub timestamp: u64,
     pub files_scanned: usize,
+    /// Unchanged files whose previous results were reused
     pub files_skipped: usize,
+    /// Changed files that could not be read; binary files are not listed.
+    /// Not kept in the scan history.
+    #[serde(skip)]
+    pub skipped_files: Vec<SkippedFile>,
     pub files_modified: usize,
     pub files_added: usize,
     pub files_removed: usize,
     pub total_matches: usize,
     pub scan_duration_ms: u64,
+    /// Directories whose recorded listing was used instead of reading them,
+    /// and those read, when pruning
+    #[serde(default)]
+    pub directories_reused: usize,
+    #[serde(default)]
+    pub directories_listed: usize,
 }
 
 /// Incremental scanner that only scans changed files
@@ -46,13 +63,16 @@ pub struct IncrementalScanner {
     state: IncrementalState,
     state_file: PathBuf,
     force_rescan_threshold: u64, // Days after which to force full rescan
+    walk_options: crate::WalkOptions,
+    prune_directories: bool,
 }
 
 impl IncrementalScanner {
     /// Create a new incremental scanner
-    pub fn new(detectors: Vec<Box<dyn PatternDetector>>, state_file: PathBuf) -> Result<Self> {
+    pub fn new(detectors: Vec<Box<dyn PatternDetector>>, state_file: PathBuf) -> ScanResult<Self> {
         let state = if state_file.exists() {
-            let content = std::fs::read_to_string(&state_file)?;
+            let content =
+                std::fs::read_to_string(&state_file).map_err(|e| ScanError::io(&state_file, e))?;
             serde_json::from_str(&content).unwrap_or_default()
         } else {
             IncrementalState::default()
@@ -63,17 +83,40 @@ impl IncrementalScanner {
             state,
             state_file,
             force_rescan_threshold: 7, // 7 days
+            walk_options: crate::WalkOptions::default(),
+            prune_directories: false,
         })
     }
 
+    /// Configure how the directory tree is walked, see [`crate::WalkOptions`].
+    pub fn with_walk_options(mut self, options: crate::WalkOptions) -> Self {
+        self.walk_options = options;
+        self
+    }
+
+    /// Read only the directories changed since the last scan, reusing the
+    /// recorded listings of the others, see [`DirectoryCache`]. Ignored when
+    /// the walk options limit the depth or follow symlinks.
+    pub fn with_directory_pruning(mut self, prune: bool) -> Self {
+        self.prune_directories = prune;
+        self
+    }
+
     /// Perform incremental scan
-    pub fn scan_incremental(&mut self, root: &Path) -> Result<(Vec<Match>, IncrementalScanResult)> {
+    pub fn scan_incremental(
+        &mut self,
+        root: &Path,
+    ) -> ScanResult<(Vec<Match>, IncrementalScanResult)> {
         let start_time = std::time::Instant::now();
-        let scan_timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let scan_timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
 
         let mut all_matches = Vec::new();
         let mut files_scanned = 0;
         let mut files_skipped = 0;
+        let mut skipped_files = Vec::new();
         let mut files_modified = 0;
         let mut files_added = 0;
         let mut files_removed = 0;
@@ -84,15 +127,17 @@ impl IncrementalScanner {
 
         if force_full_scan {
             println!(
-                "🔄 Performing full rescan (last full scan: {} days ago)",
+                "{} Performing full rescan (last full scan: {} days ago)",
+                Icon::Refresh,
                 days_since_full_scan
             );
             self.state.last_full_scan = scan_timestamp;
             self.state.file_metadata.clear();
+            self.state.directories.clear();
         }
 
         // Collect current files
-        let current_files = self.collect_files(root)?;
+        let (current_files, directories) = self.collect_files(root)?;
         let mut current_file_set = std::collections::HashSet::new();
 
         for file_path in current_files {
@@ -120,10 +165,18 @@ impl IncrementalScanner {
                         files_modified += 1;
                     }
 
-                    // Scan the file - skip if not valid UTF-8 (like binary files)
-                    let content = match std::fs::read_to_string(&file_path) {
+                    // Scan the file - skip if not valid UTF-8 (like binary files).
+                    // Files left unread keep no metadata, so they are retried next scan.
+                    let content = match read_stable(&file_path) {
                         Ok(content) => content,
-                        Err(_) => continue, // Skip files that can't be read as UTF-8
+                        Err(ScanError::Encoding { .. }) => continue,
+                        Err(e) => {
+                            skipped_files.push(SkippedFile {
+                                path: file_path.to_string_lossy().to_string(),
+                                reason: SkipReason::from_error(&e),
+                            });
+                            continue;
+                        }
                     };
                     let file_matches: Vec<Match> = self
                         .detectors
@@ -169,11 +222,14 @@ impl IncrementalScanner {
             timestamp: scan_timestamp,
             files_scanned,
             files_skipped,
+            skipped_files,
             files_modified,
             files_added,
             files_removed,
             total_matches: all_matches.len(),
             scan_duration_ms: scan_duration.as_millis() as u64,
+            directories_reused: directories.reused,
+            directories_listed: directories.listed,
         };
 
         // Save state
@@ -185,11 +241,18 @@ impl IncrementalScanner {
             self.state.scan_history.remove(0); // Keep last 100 scans
         }
 
-        println!("📊 Incremental scan completed:");
+        println!("{} Incremental scan completed:", Icon::Stats);
         println!(
             "   Files scanned: {} | Skipped: {} | Modified: {} | Added: {} | Removed: {}",
             files_scanned, files_skipped, files_modified, files_added, files_removed
         );
+        if directories.reused + directories.listed > 0 {
+            println!(
+                "   Directories reused unread: {} of {}",
+                directories.reused,
+                directories.reused + directories.listed
+            );
+        }
         println!(
             "   Speed improvement: {:.1}x faster than full scan",
             self.calculate_speedup(files_scanned, files_skipped)
@@ -202,6 +265,7 @@ impl IncrementalScanner {
     pub fn force_full_rescan(&mut self) {
         self.state.last_full_scan = 0;
         self.state.file_metadata.clear();
+        self.state.directories.clear();
     }
 
     /// Get incremental scan statistics
@@ -246,22 +310,32 @@ impl IncrementalScanner {
         }
     }
 
-    fn collect_files(&self, root: &Path) -> Result<Vec<PathBuf>> {
-        use ignore::WalkBuilder;
-
+    /// Files to check, with the directories reused and read when pruning
+    fn collect_files(&mut self, root: &Path) -> ScanResult<(Vec<PathBuf>, DirectoryWalkStats)> {
+        if self.prune_directories && DirectoryCache::supports(&self.walk_options) {
+            return self
+                .state
+                .directories
+                .collect_files(root, &self.walk_options);
+        }
         let mut files = Vec::new();
-        for entry in WalkBuilder::new(root).build() {
-            let entry = entry?;
+        for entry in crate::scan_walker_with(root, &self.walk_options).build() {
+            let entry = entry.map_err(|e| ScanError::from_walk(root, e))?;
             if entry.file_type().is_some_and(|ft| ft.is_file()) {
                 files.push(entry.path().to_path_buf());
             }
         }
-        Ok(files)
+        Ok((files, DirectoryWalkStats::default()))
     }
 
-    fn get_file_metadata(&self, path: &Path) -> Result<Option<FileMetadata>> {
+    fn get_file_metadata(&self, path: &Path) -> ScanResult<Option<FileMetadata>> {
         if let Ok(metadata) = std::fs::metadata(path) {
-            let modified_time = metadata.modified()?.duration_since(UNIX_EPOCH)?.as_secs();
+            let modified_time = metadata
+                .modified()
+                .map_err(|e| ScanError::io(path, e))?
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
 
             // Calculate content hash for accurate change detection
             let (hash, content_hash) = if metadata.len() < 2 * 1024 * 1024 {
@@ -293,21 +367,21 @@ impl IncrementalScanner {
         }
     }
 
-    fn calculate_file_hash(&self, path: &Path) -> Result<String> {
+    fn calculate_file_hash(&self, path: &Path) -> ScanResult<String> {
         use std::collections::hash_map::DefaultHasher;
         use std::hash::{Hash, Hasher};
 
-        let content = std::fs::read(path)?;
+        let content = std::fs::read(path).map_err(|e| ScanError::io(path, e))?;
         let mut hasher = DefaultHasher::new();
         content.hash(&mut hasher);
         Ok(format!("{:x}", hasher.finish()))
     }
 
-    fn calculate_content_hash(&self, path: &Path) -> Result<String> {
+    fn calculate_content_hash(&self, path: &Path) -> ScanResult<String> {
         use std::collections::hash_map::DefaultHasher;
         use std::hash::{Hash, Hasher};
 
-        let content = std::fs::read_to_string(path)?;
+        let content = std::fs::read_to_string(path).map_err(|e| ScanError::from_read(path, e))?;
         let mut hasher = DefaultHasher::new();
         content.hash(&mut hasher);
         Ok(format!("{:x}", hasher.finish()))
@@ -335,9 +409,10 @@ impl IncrementalScanner {
         }
     }
 
-    fn save_state(&self) -> Result<()> {
-        let content = serde_json::to_string_pretty(&self.state)?;
-        std::fs::write(&self.state_file, content)?;
+    fn save_state(&self) -> ScanResult<()> {
+        let content = serde_json::to_string_pretty(&self.state).map_err(ScanError::storage)?;
+        std::fs::write(&self.state_file, content)
+            .map_err(|e| ScanError::io(&self.state_file, e))?;
         Ok(())
     }
 }
@@ -389,4 +464,31 @@ mod tests {
         assert_eq!(result2.files_skipped, 1);
         assert_eq!(result2.files_scanned, 0);
     }
+
+    #[test]
+    fn test_directory_pruning() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("src")).unwrap();
+        std::fs::write(temp_dir.path().join("src/lib.rs"), "// TODO: one").unwrap();
+
+        let temp_state = NamedTempFile::new().unwrap();
+        let detectors: Vec<Box<dyn PatternDetector>> = vec![Box::new(TodoDetector)];
+        let mut scanner = IncrementalScanner::new(detectors, temp_state.path().to_path_buf())
+            .unwrap()
+            .with_directory_pruning(true);
+
+        let (_, result1) = scanner.scan_incremental(temp_dir.path()).unwrap();
+        assert_eq!(result1.directories_listed, 2);
+        assert_eq!(result1.files_added, 1);
+        assert_eq!(scanner.state.directories.len(), 2);
+
+        std::fs::write(temp_dir.path().join("src/main.rs"), "// TODO: two").unwrap();
+        let (matches2, result2) = scanner.scan_incremental(temp_dir.path()).unwrap();
+        assert_eq!(result2.files_added, 1);
+        assert_eq!(result2.files_skipped, 1);
+        assert_eq!(matches2.len(), 1);
+
+        scanner.force_full_rescan();
+        assert!(scanner.state.directories.is_empty());
+    }
 }
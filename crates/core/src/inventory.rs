@@ -0,0 +1,289 @@
+//! An SBOM-style inventory of a scanned codebase: which languages it holds,
+//! how many files and lines of each, and which detectors cover them. Useful
+//! for scoping audits and for spotting file types no rule looks at.
+
+use crate::{scan_walker_with, Match, PatternDetector, WalkOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+/// Language reported for text files of no known language
+pub const OTHER_LANGUAGE: &str = "Other";
+
+/// Languages by lowercase file extension
+const LANGUAGES_BY_EXTENSION: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("cjs", "JavaScript"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("vue", "Vue"),
+    ("svelte", "Svelte"),
+    ("py", "Python"),
+    ("go", "Go"),
+    ("java", "Java"),
+    ("kt", "Kotlin"),
+    ("kts", "Kotlin"),
+    ("scala", "Scala"),
+    ("cs", "C#"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cpp", "C++"),
+    ("cc", "C++"),
+    ("cxx", "C++"),
+    ("hpp", "C++"),
+    ("php", "PHP"),
+    ("rb", "Ruby"),
+    ("swift", "Swift"),
+    ("dart", "Dart"),
+    ("lua", "Lua"),
+    ("pl", "Perl"),
+    ("pm", "Perl"),
+    ("ex", "Elixir"),
+    ("exs", "Elixir"),
+    ("hs", "Haskell"),
+    ("clj", "Clojure"),
+    ("zig", "Zig"),
+    ("sh", "Shell"),
+    ("bash", "Shell"),
+    ("zsh", "Shell"),
+    ("fish", "Shell"),
+    ("ps1", "PowerShell"),
+    ("bat", "Batch"),
+    ("cmd", "Batch"),
+    ("sql", "SQL"),
+    ("html", "HTML"),
+    ("htm", "HTML"),
+    ("css", "CSS"),
+    ("scss", "CSS"),
+    ("sass", "CSS"),
+    ("less", "CSS"),
+    ("json", "JSON"),
+    ("yaml", "YAML"),
+    ("yml", "YAML"),
+    ("toml", "TOML"),
+    ("xml", "XML"),
+    ("md", "Markdown"),
+    ("tf", "Terraform"),
+    ("tfvars", "Terraform"),
+];
+
+/// The language of `path`, from its extension or a well-known file name
+pub fn language_of(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_string_lossy();
+    if name == "Dockerfile" || name.starts_with("Dockerfile.") {
+        return Some("Dockerfile");
+    }
+    if name == "Makefile" {
+        return Some("Makefile");
+    }
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    LANGUAGES_BY_EXTENSION
+        .iter()
+        .find(|(known, _)| *known == ext)
+        .map(|(_, language)| *language)
+}
+
+/// Every language [`language_of`] recognizes, with the file name globs it
+/// recognizes as that language
+pub fn known_languages() -> BTreeMap<&'static str, Vec<String>> {
+    let mut languages: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
+    for (ext, language) in LANGUAGES_BY_EXTENSION {
+        let globs = languages.entry(language).or_default();
+        let glob = format!("*.{}", ext);
+        if !globs.contains(&glob) {
+            globs.push(glob);
+        }
+    }
+    languages.insert(
+        "Dockerfile",
+        vec!["Dockerfile".to_string(), "Dockerfile.*".to_string()],
+    );
+    languages.insert("Makefile", vec!["Makefile".to_string()]);
+    languages
+}
+
+/// Files, lines and detector coverage of one language
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguageInventory {
+    pub language: String,
+    pub files: usize,
+    pub lines: usize,
+    pub bytes: u64,
+    /// Matches the scan found in files of this language
+    pub matches: usize,
+    /// Detectors that look at files of this language, see
+    /// [`PatternDetector::applies_to`]
+    pub detectors: Vec<String>,
+}
+
+/// What a scan root contains, see the module docs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Inventory {
+    pub files: usize,
+    pub lines: usize,
+    /// Detectors the scan ran
+    pub total_detectors: usize,
+    /// By lines of code, largest first
+    pub languages: Vec<LanguageInventory>,
+    /// Files per extension of text files of no known language, counted
+    /// under [`OTHER_LANGUAGE`]; `""` stands for files without an extension
+    pub unrecognized: BTreeMap<String, usize>,
+    /// Files per extension of binary files, which are never scanned
+    pub unscanned: BTreeMap<String, usize>,
+}
+
+impl Inventory {
+    /// Walks `root` as the scanners do and takes stock of its files, the
+    /// `detectors` that apply to each language and the scan's `matches`
+    pub fn collect(
+        root: &Path,
+        options: &WalkOptions,
+        detectors: &[Box<dyn PatternDetector>],
+        matches: &[Match],
+    ) -> Self {
+        let mut inventory = Self {
+            total_detectors: detectors.len(),
+            ..Self::default()
+        };
+        let mut languages: HashMap<&str, (LanguageInventory, BTreeSet<usize>)> = HashMap::new();
+        for entry in scan_walker_with(root, options).build().flatten() {
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
+                continue;
+            }
+            let path = entry.path();
+            let extension = path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default();
+            let Ok(bytes) = std::fs::read(path) else {
+                continue;
+            };
+            let Ok(content) = std::str::from_utf8(&bytes) else {
+                *inventory.unscanned.entry(extension).or_insert(0) += 1;
+                continue;
+            };
+            let language = language_of(path).unwrap_or_else(|| {
+                *inventory.unrecognized.entry(extension).or_insert(0) += 1;
+                OTHER_LANGUAGE
+            });
+            let lines = content.lines().count();
+            inventory.files += 1;
+            inventory.lines += lines;
+
+            let (stats, applicable) = languages.entry(language).or_insert_with(|| {
+                (
+                    LanguageInventory {
+                        language: language.to_string(),
+                        ..LanguageInventory::default()
+                    },
+                    BTreeSet::new(),
+                )
+            });
+            stats.files += 1;
+            stats.lines += lines;
+            stats.bytes += bytes.len() as u64;
+            applicable.extend(
+                detectors
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, detector)| detector.applies_to(path))
+                    .map(|(index, _)| index),
+            );
+        }
+
+        for m in matches {
+            let language = language_of(Path::new(&*m.file_path)).unwrap_or(OTHER_LANGUAGE);
+            if let Some((stats, _)) = languages.get_mut(language) {
+                stats.matches += 1;
+            }
+        }
+
+        inventory.languages = languages
+            .into_values()
+            .map(|(mut stats, applicable)| {
+                stats.detectors = applicable
+                    .into_iter()
+                    .map(|index| detectors[index].name().to_string())
+                    .collect();
+                stats
+            })
+            .collect();
+        inventory.languages.sort_by(|a, b| {
+            b.lines
+                .cmp(&a.lines)
+                .then_with(|| a.language.cmp(&b.language))
+        });
+        inventory
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detectors::{TodoDetector, UnwrapDetector};
+    use std::fs;
+
+    #[test]
+    fn test_inventory_counts_languages_and_coverage() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("main.rs"),
+            "fn main() {\n    x.unwrap();\n}\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("lib.rs"), "// TODO: docs\n").unwrap();
+        fs::write(dir.path().join("app.py"), "print(1)\n").unwrap();
+        fs::write(dir.path().join("notes.adoc"), "= Notes\n").unwrap();
+        fs::write(dir.path().join("logo.bin"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let detectors: Vec<Box<dyn PatternDetector>> =
+            vec![Box::new(TodoDetector), Box::new(UnwrapDetector)];
+        let matches: Vec<Match> = detectors
+            .iter()
+            .flat_map(|d| {
+                let path = dir.path().join("lib.rs");
+                d.detect("// TODO: docs\n", &path)
+            })
+            .collect();
+        let inventory =
+            Inventory::collect(dir.path(), &WalkOptions::default(), &detectors, &matches);
+
+        assert_eq!(inventory.files, 4);
+        assert_eq!(inventory.lines, 6);
+        assert_eq!(inventory.total_detectors, 2);
+        let rust = &inventory.languages[0];
+        assert_eq!(rust.language, "Rust");
+        assert_eq!((rust.files, rust.lines, rust.matches), (2, 4, 1));
+        assert_eq!(rust.detectors, vec!["TodoDetector", "UnwrapDetector"]);
+        let python = inventory
+            .languages
+            .iter()
+            .find(|l| l.language == "Python")
+            .unwrap();
+        assert_eq!(python.detectors, vec!["TodoDetector"]);
+        assert_eq!(inventory.unrecognized.get("adoc"), Some(&1));
+        assert_eq!(inventory.unscanned.get("bin"), Some(&1));
+    }
+
+    #[test]
+    fn test_language_of() {
+        assert_eq!(language_of(Path::new("src/App.TSX")), Some("TypeScript"));
+        assert_eq!(
+            language_of(Path::new("docker/Dockerfile")),
+            Some("Dockerfile")
+        );
+        assert_eq!(language_of(Path::new("README")), None);
+
+        let languages = known_languages();
+        assert_eq!(languages["Rust"], vec!["*.rs"]);
+        assert_eq!(languages["Dart"], vec!["*.dart"]);
+        assert!(languages["Dockerfile"].contains(&"Dockerfile.*".to_string()));
+        for (language, globs) in &languages {
+            let file = globs[0].replace('*', "file");
+            assert_eq!(language_of(Path::new(&file)), Some(*language));
+        }
+    }
+}
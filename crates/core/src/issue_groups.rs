@@ -0,0 +1,181 @@
+//! Findings of a scan grouped into the issues they report, for
+//! `report --group`. A rule often fires many times for one problem, such as
+//! a CLONE finding on every line of a generated file; its findings in a file
+//! form one issue when they are on consecutive lines or have the same
+//! message.
+
+use crate::Match;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Findings of one rule in one file reporting the same issue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssueGroup {
+    /// The earliest finding of the issue
+    #[serde(flatten)]
+    pub first: Match,
+    /// Findings grouped into the issue, at least 1
+    pub occurrences: usize,
+    /// Line of the last finding of the issue
+    pub last_line: usize,
+}
+
+impl IssueGroup {
+    /// The earliest finding, its message followed by the number of
+    /// occurrences and their lines when there are several, for formats that
+    /// only list findings
+    pub fn to_match(&self) -> Match {
+        let mut m = self.first.clone();
+        if self.occurrences > 1 {
+            m.message = format!(
+                "{} ({} occurrences, lines {}-{})",
+                m.message, self.occurrences, m.line_number, self.last_line
+            );
+        }
+        m
+    }
+}
+
+/// Groups `matches` into issues: findings of the same rule in the same file
+/// are one issue when they are on the same or consecutive lines or have the
+/// same message, directly or through other findings of the issue. Issues are
+/// in the order of their first finding in `matches`.
+pub fn group_issues(matches: &[Match]) -> Vec<IssueGroup> {
+    let mut buckets: HashMap<(&str, &str), Vec<usize>> = HashMap::new();
+    for (i, m) in matches.iter().enumerate() {
+        buckets
+            .entry((m.file_path.as_str(), m.pattern.as_str()))
+            .or_default()
+            .push(i);
+    }
+
+    let mut parents: Vec<usize> = (0..matches.len()).collect();
+    for mut bucket in buckets.into_values() {
+        bucket.sort_by_key(|&i| (matches[i].line_number, matches[i].column, i));
+        let mut by_message: HashMap<&str, usize> = HashMap::new();
+        for (n, &i) in bucket.iter().enumerate() {
+            if n > 0 {
+                let previous = bucket[n - 1];
+                if matches[i].line_number <= matches[previous].line_number + 1 {
+                    union(&mut parents, previous, i);
+                }
+            }
+            let same_message = *by_message.entry(matches[i].message.as_str()).or_insert(i);
+            union(&mut parents, same_message, i);
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of_root: HashMap<usize, usize> = HashMap::new();
+    for i in 0..matches.len() {
+        let root = find(&mut parents, i);
+        let group = *group_of_root.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[group].push(i);
+    }
+    groups
+        .into_iter()
+        .map(|members| {
+            let first = members
+                .iter()
+                .copied()
+                .min_by_key(|&i| (matches[i].line_number, matches[i].column))
+                .unwrap_or(0);
+            let last_line = members
+                .iter()
+                .map(|&i| matches[i].line_number)
+                .max()
+                .unwrap_or(0);
+            IssueGroup {
+                first: matches[first].clone(),
+                occurrences: members.len(),
+                last_line,
+            }
+        })
+        .collect()
+}
+
+fn find(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find(parents, a), find(parents, b));
+    if a != b {
+        // The earlier finding stays the root, so issues keep scan order
+        parents[a.max(b)] = a.min(b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(file: &str, line: usize, pattern: &str, message: &str) -> Match {
+        Match::new(file, line, pattern, message)
+    }
+
+    #[test]
+    fn test_group_issues() {
+        let matches = vec![
+            finding("gen.rs", 10, "CLONE", "clone() call"),
+            finding("gen.rs", 11, "CLONE", "clone() call on self"),
+            finding("gen.rs", 12, "CLONE", "clone() call on self"),
+            finding("gen.rs", 90, "CLONE", "clone() call"),
+            finding("gen.rs", 11, "TODO", "TODO: one"),
+            finding("gen.rs", 40, "TODO", "TODO: two"),
+            finding("main.rs", 10, "CLONE", "clone() call"),
+        ];
+
+        let groups = group_issues(&matches);
+        let summary: Vec<(&str, &str, usize, usize, usize)> = groups
+            .iter()
+            .map(|g| {
+                (
+                    g.first.file_path.as_str(),
+                    g.first.pattern.as_str(),
+                    g.first.line_number,
+                    g.last_line,
+                    g.occurrences,
+                )
+            })
+            .collect();
+        // Consecutive lines and the identical message at line 90 join the
+        // CLONE issue; other rules and files stay apart
+        assert_eq!(
+            summary,
+            vec![
+                ("gen.rs", "CLONE", 10, 90, 4),
+                ("gen.rs", "TODO", 11, 11, 1),
+                ("gen.rs", "TODO", 40, 40, 1),
+                ("main.rs", "CLONE", 10, 10, 1),
+            ]
+        );
+
+        assert_eq!(
+            groups[0].to_match().message,
+            "clone() call (4 occurrences, lines 10-90)"
+        );
+        assert_eq!(groups[1].to_match(), matches[4]);
+        assert!(group_issues(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_issue_group_json() {
+        let groups = group_issues(&[
+            finding("gen.rs", 2, "CLONE", "clone() call"),
+            finding("gen.rs", 1, "CLONE", "clone() call"),
+        ]);
+        let json = serde_json::to_value(&groups).unwrap();
+        assert_eq!(json[0]["line_number"], 1);
+        assert_eq!(json[0]["pattern"], "CLONE");
+        assert_eq!(json[0]["occurrences"], 2);
+        assert_eq!(json[0]["last_line"], 2);
+    }
+}
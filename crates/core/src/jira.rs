@@ -0,0 +1,188 @@
+//! Jira issues for findings: one issue per fingerprint, created on the first
+//! run and updated on later ones, through the Jira REST API v2.
+
+use crate::compliance::compliance_of;
+use crate::errors::{ScanError, ScanResult};
+use crate::webhook::send_json;
+use crate::{Match, Severity};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Environment variable holding the API token, or a personal access token
+/// on Jira Data Center
+pub const JIRA_TOKEN_ENV: &str = "JIRA_API_TOKEN";
+
+/// Environment variable holding the account email the Jira Cloud API token
+/// belongs to; without it the token is sent as a bearer token
+pub const JIRA_EMAIL_ENV: &str = "JIRA_EMAIL";
+
+/// Where issues for findings are created, the `jira` config section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct JiraConfig {
+    /// Base URL of the Jira site, e.g. `https://example.atlassian.net`
+    pub url: Option<String>,
+    /// Key of the project issues are created in, e.g. `SEC`
+    pub project: Option<String>,
+    /// Type of the created issues
+    pub issue_type: String,
+    /// Labels of the created issues
+    pub labels: Vec<String>,
+}
+
+impl Default for JiraConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            project: None,
+            issue_type: "Bug".to_string(),
+            labels: vec!["code-guardian".to_string()],
+        }
+    }
+}
+
+/// Client of the issue API of one Jira site
+#[derive(Debug, Clone)]
+pub struct JiraClient {
+    url: String,
+    authorization: String,
+    timeout: Duration,
+}
+
+impl JiraClient {
+    /// Authenticates with `email` and API token `token` on Jira Cloud, or
+    /// with `token` alone as a personal access token
+    pub fn new(url: impl Into<String>, email: Option<&str>, token: &str) -> Self {
+        let authorization = match email {
+            Some(email) => format!(
+                "Basic {}",
+                base64_encode(format!("{}:{}", email, token).as_bytes())
+            ),
+            None => format!("Bearer {}", token),
+        };
+        Self {
+            url: url.into().trim_end_matches('/').to_string(),
+            authorization,
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Creates an issue with `fields` and returns its key, e.g. `SEC-12`
+    pub fn create_issue(&self, fields: &Value) -> ScanResult<String> {
+        let url = format!("{}/rest/api/2/issue", self.url);
+        let response = self.send("POST", &url, fields)?;
+        let issue: Value = serde_json::from_slice(&response)
+            .map_err(|e| ScanError::integration("Jira", format!("unexpected response: {}", e)))?;
+        issue["key"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| ScanError::integration("Jira", "response has no issue key"))
+    }
+
+    /// Sets `fields` of the issue `key`
+    pub fn update_issue(&self, key: &str, fields: &Value) -> ScanResult<()> {
+        let url = format!("{}/rest/api/2/issue/{}", self.url, key);
+        self.send("PUT", &url, fields).map(|_| ())
+    }
+
+    fn send(&self, method: &str, url: &str, fields: &Value) -> ScanResult<Vec<u8>> {
+        let authorization = [format!("Authorization: {}", self.authorization)];
+        let body = json!({ "fields": fields }).to_string();
+        send_json(
+            method,
+            url,
+            &[],
+            &authorization,
+            body.as_bytes(),
+            self.timeout,
+        )
+        .map_err(|e| ScanError::integration("Jira", e))
+    }
+}
+
+/// Summary, description and labels of the issue of finding `m`. Updates
+/// send the same fields, without project and issue type.
+pub fn issue_fields(m: &Match, severity: &Severity, fingerprint: &str, labels: &[String]) -> Value {
+    let mut description = format!(
+        "code-guardian found a {:?} {} finding at {}:{}.\n\n{{noformat}}{}{{noformat}}\n\n",
+        severity,
+        m.pattern,
+        m.file_path,
+        m.line_number,
+        m.message.trim()
+    );
+    if let Some(mapping) = compliance_of(&m.pattern) {
+        description.push_str(&format!("Controls: {}\n", mapping.controls().join(", ")));
+    }
+    description.push_str(&format!("Fingerprint: {}", fingerprint));
+    json!({
+        "summary": format!("{} in {}", m.pattern, m.file_path),
+        "description": description,
+        "labels": labels,
+    })
+}
+
+/// Standard base64 with padding, for HTTP basic authentication
+fn base64_encode(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity((input.len() + 2) / 3 * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let triple = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(triple >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(
+            base64_encode(b"me@example.com:token"),
+            "bWVAZXhhbXBsZS5jb206dG9rZW4="
+        );
+    }
+
+    #[test]
+    fn test_issue_fields() {
+        let m = Match {
+            file_path: "src/db.rs".into(),
+            line_number: 12,
+            column: 1,
+            pattern: "LLM_SQL_INJECTION".into(),
+            message: "LLM_SQL_INJECTION: query(\"SELECT \" + id)".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
+        };
+        let fields = issue_fields(&m, &Severity::Critical, "0123456789abcdef", &["sec".into()]);
+        assert_eq!(fields["summary"], "LLM_SQL_INJECTION in src/db.rs");
+        let description = fields["description"].as_str().unwrap();
+        assert!(description.starts_with(
+            "code-guardian found a Critical LLM_SQL_INJECTION finding at src/db.rs:12."
+        ));
+        assert!(description.contains("Controls: CWE-89"));
+        assert!(description.ends_with("Fingerprint: 0123456789abcdef"));
+        assert_eq!(fields["labels"], json!(["sec"]));
+    }
+}
@@ -0,0 +1,331 @@
+//! Java and Kotlin detectors: console output and stack traces instead of a
+//! logger, sleeps in request handlers, blanket warning suppression and
+//! Kotlin's `!!` non-null assertions
+
+use crate::detectors::{
+    build_glob_set, classify, detect_code_pattern, has_extension, DEFAULT_TEST_PATH_GLOBS,
+};
+use crate::line_index::LineIndex;
+use crate::quality_detectors::{enclosing_function, function_spans, CODE_QUALITY_CATEGORY};
+use crate::{Match, PatternDetector, Severity};
+use globset::GlobSet;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::Path;
+
+/// Java and Kotlin sources
+const JVM_EXTENSIONS: &[&str] = &["java", "kt", "kts"];
+
+lazy_static! {
+    static ref TEST_PATHS: GlobSet =
+        build_glob_set(DEFAULT_TEST_PATH_GLOBS).expect("default test globs are valid");
+
+    static ref SYSTEM_OUT_REGEX: Regex =
+        Regex::new(r"\bSystem\.(?:out|err)\.print(?:ln|f)?\s*\(").unwrap();
+
+    /// A `main` method or function marks a command-line entry point
+    static ref MAIN_REGEX: Regex = Regex::new(
+        r"\bstatic\s+void\s+main\s*\(|(?m)^\s*fun\s+main\s*\("
+    ).unwrap();
+
+    static ref PRINT_STACK_TRACE_REGEX: Regex = Regex::new(r"\.printStackTrace\(\s*\)").unwrap();
+
+    static ref THREAD_SLEEP_REGEX: Regex =
+        Regex::new(r"\b(?:Thread\.sleep|TimeUnit\.\w+\.sleep)\s*\(").unwrap();
+
+    /// Spring and JAX-RS handler annotations, and servlet parameters
+    static ref REQUEST_HANDLER_REGEX: Regex = Regex::new(
+        r"@(?:Get|Post|Put|Delete|Patch|Request)Mapping\b|@(?:GET|POST|PUT|DELETE|PATCH)\b|\bHttpServlet(?:Request|Response)\b|\bServerRequest\b"
+    ).unwrap();
+
+    static ref SUPPRESS_ALL_REGEX: Regex = Regex::new(
+        r#"@Suppress(?:Warnings)?\(\s*(?:value\s*=\s*)?[{\[]?\s*"all""#
+    ).unwrap();
+
+    /// `!!` after a value, not the `!!` of a doubled negation
+    static ref NOT_NULL_ASSERTION_REGEX: Regex = Regex::new(r"[\w)\]]!!").unwrap();
+}
+
+fn is_jvm_test(file_path: &Path) -> bool {
+    TEST_PATHS.is_match(file_path)
+        || file_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| stem.ends_with("Test") || stem.ends_with("Tests"))
+}
+
+/// Whether `offset` is inside a string literal on its line, going by the
+/// number of unescaped double quotes before it
+fn in_string(content: &str, lines: &LineIndex, offset: usize) -> bool {
+    let line_start = lines.line_start(lines.line_of(offset));
+    let mut escaped = false;
+    let mut quotes = 0;
+    for b in content[line_start..offset].bytes() {
+        match b {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b'"' => quotes += 1,
+            _ => {}
+        }
+    }
+    quotes % 2 == 1
+}
+
+/// Detector for `System.out.println` and `System.err.print` outside tests
+/// and `main`-bearing entry points, where a logger belongs instead
+pub struct SystemOutDetector;
+
+impl PatternDetector for SystemOutDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) || is_jvm_test(file_path) || MAIN_REGEX.is_match(content) {
+            return Vec::new();
+        }
+        classify(
+            detect_code_pattern(content, file_path, "JVM_SYSTEM_OUT", &SYSTEM_OUT_REGEX),
+            Severity::Medium,
+            CODE_QUALITY_CATEGORY,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, JVM_EXTENSIONS)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&SYSTEM_OUT_REGEX)
+    }
+}
+
+/// Detector for `e.printStackTrace()`, which writes to stderr past the
+/// application's logging and usually stands in for real error handling
+pub struct PrintStackTraceDetector;
+
+impl PatternDetector for PrintStackTraceDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) || is_jvm_test(file_path) {
+            return Vec::new();
+        }
+        classify(
+            detect_code_pattern(
+                content,
+                file_path,
+                "JVM_PRINT_STACK_TRACE",
+                &PRINT_STACK_TRACE_REGEX,
+            ),
+            Severity::Medium,
+            CODE_QUALITY_CATEGORY,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, JVM_EXTENSIONS)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&PRINT_STACK_TRACE_REGEX)
+    }
+}
+
+/// Detector for `Thread.sleep` in request handlers: Spring `@*Mapping` and
+/// JAX-RS methods, and functions taking servlet or WebFlux requests. The
+/// sleep holds a request thread from a bounded pool.
+pub struct SleepInRequestDetector;
+
+impl PatternDetector for SleepInRequestDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) || is_jvm_test(file_path) {
+            return Vec::new();
+        }
+        let mut matches = detect_code_pattern(
+            content,
+            file_path,
+            "JVM_SLEEP_IN_REQUEST",
+            &THREAD_SLEEP_REGEX,
+        );
+        if matches.is_empty() {
+            return matches;
+        }
+        let functions = function_spans(content, file_path);
+        let index = LineIndex::new(content);
+        matches.retain(|m| {
+            let offset = index.offset(content, m.line_number, m.column);
+            enclosing_function(&functions, offset).is_some_and(|f| {
+                // The annotation lines above the function, and its signature
+                let mut line = index.line_of(f.start);
+                while line > 1 && index.line(content, line - 1).trim_start().starts_with('@') {
+                    line -= 1;
+                }
+                let signature_end = content[f.start..f.end]
+                    .find('{')
+                    .map_or(f.end, |i| f.start + i);
+                REQUEST_HANDLER_REGEX.is_match(&content[index.line_start(line)..signature_end])
+            })
+        });
+        classify(matches, Severity::Medium, CODE_QUALITY_CATEGORY)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, JVM_EXTENSIONS)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&THREAD_SLEEP_REGEX)
+    }
+}
+
+/// Detector for `@SuppressWarnings("all")` and Kotlin's `@Suppress("all")`,
+/// which silence every compiler and linter warning, not just the one meant
+pub struct SuppressAllWarningsDetector;
+
+impl PatternDetector for SuppressAllWarningsDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        classify(
+            detect_code_pattern(content, file_path, "JVM_SUPPRESS_ALL", &SUPPRESS_ALL_REGEX),
+            Severity::Low,
+            CODE_QUALITY_CATEGORY,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, JVM_EXTENSIONS)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&SUPPRESS_ALL_REGEX)
+    }
+}
+
+/// Detector for Kotlin's `!!`, which turns a null into a
+/// `NullPointerException` instead of handling it. Tests are skipped.
+pub struct NotNullAssertionDetector;
+
+impl PatternDetector for NotNullAssertionDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) || is_jvm_test(file_path) {
+            return Vec::new();
+        }
+        let mut matches = detect_code_pattern(
+            content,
+            file_path,
+            "KOTLIN_NOT_NULL_ASSERTION",
+            &NOT_NULL_ASSERTION_REGEX,
+        );
+        let index = LineIndex::new(content);
+        matches.retain(|m| {
+            let offset = index.offset(content, m.line_number, m.column);
+            !in_string(content, &index, offset)
+        });
+        // Point at the `!!` rather than the value before it
+        for m in &mut matches {
+            m.column += 1;
+        }
+        classify(matches, Severity::Low, CODE_QUALITY_CATEGORY)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["kt", "kts"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&NOT_NULL_ASSERTION_REGEX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(detector: &dyn PatternDetector, content: &str, path: &str) -> Vec<usize> {
+        detector
+            .detect(content, Path::new(path))
+            .iter()
+            .map(|m| m.line_number)
+            .collect()
+    }
+
+    const CONTROLLER: &str = r#"@RestController
+@SuppressWarnings("all")
+public class OrderController {
+    @GetMapping("/orders/{id}")
+    public Order get(@PathVariable long id) {
+        try {
+            Thread.sleep(100);
+            return repository.find(id);
+        } catch (Exception e) {
+            e.printStackTrace();
+            System.out.println("lookup failed: " + id);
+            return null;
+        }
+    }
+
+    @Scheduled(fixedRate = 1000)
+    public void poll() throws InterruptedException {
+        // System.out.println("polling");
+        Thread.sleep(1000);
+    }
+}
+"#;
+
+    #[test]
+    fn test_system_out_detector() {
+        assert_eq!(
+            lines(&SystemOutDetector, CONTROLLER, "OrderController.java"),
+            vec![11]
+        );
+        assert!(lines(&SystemOutDetector, CONTROLLER, "OrderControllerTest.java").is_empty());
+        let cli = "class Cli {\n  public static void main(String[] args) {\n    System.out.println(\"hi\");\n  }\n}\n";
+        assert!(lines(&SystemOutDetector, cli, "Cli.java").is_empty());
+    }
+
+    #[test]
+    fn test_print_stack_trace_detector() {
+        assert_eq!(
+            lines(&PrintStackTraceDetector, CONTROLLER, "OrderController.java"),
+            vec![10]
+        );
+    }
+
+    #[test]
+    fn test_sleep_in_request_detector() {
+        assert_eq!(
+            lines(&SleepInRequestDetector, CONTROLLER, "OrderController.java"),
+            vec![7]
+        );
+        let kotlin = "@GetMapping(\"/ping\")\nfun ping(): String {\n    Thread.sleep(50)\n    return \"pong\"\n}\n";
+        assert_eq!(lines(&SleepInRequestDetector, kotlin, "Ping.kt"), vec![3]);
+    }
+
+    #[test]
+    fn test_suppress_all_warnings_detector() {
+        assert_eq!(
+            lines(
+                &SuppressAllWarningsDetector,
+                CONTROLLER,
+                "OrderController.java"
+            ),
+            vec![2]
+        );
+        let kotlin = "@Suppress(\"unused\")\nval a = 1\n@Suppress(\"all\")\nval b = 2\n";
+        assert_eq!(
+            lines(&SuppressAllWarningsDetector, kotlin, "Values.kt"),
+            vec![3]
+        );
+    }
+
+    #[test]
+    fn test_not_null_assertion_detector() {
+        let kotlin = "val name = user!!.name\nval ok = !!flag\nprintln(\"Done!!\")\nval first = items[0]!!.id // really!!\n";
+        let matches = NotNullAssertionDetector.detect(kotlin, Path::new("User.kt"));
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.line_number, m.column))
+                .collect::<Vec<_>>(),
+            vec![(1, 16), (4, 21)]
+        );
+        assert!(lines(&NotNullAssertionDetector, kotlin, "User.java").is_empty());
+    }
+}
@@ -1,47 +1,315 @@
-use anyhow::Result;
+//! Pattern detection for code quality and security scanning.
+//!
+//! The [`prelude`] is the stable API. Build a [`Scanner`] with
+//! [`Scanner::builder`], choose its detectors with a [`DetectorProfile`] or a
+//! [`DetectorRegistry`], and read the [`ScanReport`] it returns. The
+//! [`config`], [`detectors`] and [`errors`] modules are stable too. Other
+//! modules are shared with the code-guardian CLI, storage and output crates.
+//! They are hidden from these docs and may change in any release.
+//!
+//! ```no_run
+//! use code_guardian_core::prelude::*;
+//! use code_guardian_core::detectors::HackDetector;
+//! use std::path::Path;
+//!
+//! let scanner = Scanner::builder()
+//!     .profile(DetectorProfile::Basic)
+//!     .detector(Box::new(HackDetector))
+//!     .max_depth(8)
+//!     .build();
+//! let report = scanner.scan_report(Path::new("src"))?;
+//! for m in &report.matches {
+//!     println!("{}:{} {} {}", m.file_path, m.line_number, m.pattern, m.message);
+//! }
+//! println!("{} findings in {} files", report.summary.total_matches, report.summary.files_scanned);
+//! # Ok::<(), ScanError>(())
+//! ```
+
 use dashmap::DashMap;
 use ignore::WalkBuilder;
-use memmap2::Mmap;
 use rayon::prelude::*;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
-use std::path::Path;
-use std::time::SystemTime;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 
+#[doc(hidden)]
+pub mod a11y_detectors;
+#[doc(hidden)]
+pub mod api_token;
+#[doc(hidden)]
+pub mod artifact_upload;
+#[doc(hidden)]
+pub mod audit_log;
+#[doc(hidden)]
+pub mod batch_sizing;
+#[doc(hidden)]
+pub mod bitbucket;
+#[doc(hidden)]
+pub mod c_detectors;
+#[doc(hidden)]
 pub mod cache;
+#[doc(hidden)]
+pub mod ci_detectors;
+#[doc(hidden)]
+pub mod ci_environment;
+#[doc(hidden)]
+pub mod comment_spans;
+#[doc(hidden)]
+pub mod compliance;
 pub mod config;
+#[doc(hidden)]
+pub mod config_validation;
+#[doc(hidden)]
+pub mod coverage;
+#[doc(hidden)]
 pub mod custom_detectors;
+#[doc(hidden)]
+pub mod deprecation;
+#[doc(hidden)]
+pub mod detector_conditions;
+#[doc(hidden)]
 pub mod detector_factory;
+#[doc(hidden)]
+pub mod detector_yield;
 pub mod detectors;
+#[doc(hidden)]
+pub mod directory_cache;
+#[doc(hidden)]
 pub mod distributed;
+#[doc(hidden)]
 pub mod enhanced_config;
+pub mod errors;
+#[doc(hidden)]
+pub mod excluded_dirs;
+#[doc(hidden)]
+pub mod feature_flags;
+#[doc(hidden)]
+pub mod feed;
+#[doc(hidden)]
+pub mod file_priority;
+#[doc(hidden)]
+pub mod finding_state;
+#[doc(hidden)]
+pub mod generated;
+#[doc(hidden)]
+pub mod gitlab;
+#[doc(hidden)]
+pub mod go_detectors;
+#[doc(hidden)]
 pub mod health_server;
+#[doc(hidden)]
+pub mod i18n_detectors;
+#[doc(hidden)]
+pub mod iac_detectors;
+#[doc(hidden)]
 pub mod incremental;
+#[doc(hidden)]
+pub mod inventory;
+#[doc(hidden)]
+pub mod issue_groups;
+#[doc(hidden)]
+pub mod jira;
+#[doc(hidden)]
+pub mod jvm_detectors;
+#[doc(hidden)]
+pub mod line_index;
+#[doc(hidden)]
 pub mod llm_detectors;
+#[doc(hidden)]
+pub mod match_limits;
+#[doc(hidden)]
 pub mod metrics;
+#[doc(hidden)]
+pub mod migration_detectors;
+#[doc(hidden)]
 pub mod monitoring;
+#[doc(hidden)]
+pub mod multi_pattern;
+#[doc(hidden)]
 pub mod observability;
+#[doc(hidden)]
 pub mod optimized_scanner;
+#[doc(hidden)]
+pub mod output_style;
+#[doc(hidden)]
 pub mod performance;
+#[doc(hidden)]
+pub mod performance_dashboard;
+#[doc(hidden)]
 pub mod performance_optimized_scanner;
+#[doc(hidden)]
+pub mod pii_detectors;
+pub mod prelude;
+#[doc(hidden)]
+pub mod python_detectors;
+#[doc(hidden)]
+pub mod quality_detectors;
+#[doc(hidden)]
+pub mod redaction;
+#[doc(hidden)]
+pub mod regex_cache;
+#[doc(hidden)]
+pub mod regex_safety;
+mod registry;
+mod report;
+#[doc(hidden)]
+pub mod rule_scopes;
+#[doc(hidden)]
+pub mod rust_panic_audit;
+#[doc(hidden)]
+pub mod scan_budget;
+#[doc(hidden)]
+pub mod scan_cache;
+#[doc(hidden)]
+pub mod scan_targets;
+#[doc(hidden)]
+pub mod schema;
+#[doc(hidden)]
+pub mod security_detectors;
+#[doc(hidden)]
+pub mod shared_str;
+#[doc(hidden)]
+pub mod skipped;
+#[doc(hidden)]
+pub mod stable_read;
+#[doc(hidden)]
+pub mod test_quality_detectors;
+#[doc(hidden)]
+pub mod tls;
+#[doc(hidden)]
+pub mod triggers;
+#[doc(hidden)]
+pub mod tuning;
+#[doc(hidden)]
+pub mod webhook;
+#[doc(hidden)]
+pub mod windows_paths;
+#[doc(hidden)]
+pub mod worker_discovery;
 
 /// Represents a detected pattern match in a file.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+)]
 pub struct Match {
     /// The path to the file where the match was found.
-    pub file_path: String,
+    pub file_path: SharedStr,
     /// The line number (1-based) where the match starts.
     pub line_number: usize,
     /// The column number (1-based) where the match starts.
     pub column: usize,
     /// The type of pattern detected (e.g., "TODO", "FIXME").
-    pub pattern: String,
+    pub pattern: SharedStr,
     /// The matched text or a descriptive message.
     pub message: String,
+    /// Severity set by the detector, e.g. a custom detector's configured
+    /// severity. When absent, reports use the built-in severity of the pattern.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    /// Category set by the detector, e.g. "Security" for a custom detector.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Where the match ends, for matches spanning several lines.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<LineColumn>,
+    /// Who last changed the line, from `git blame` when scanning with `--blame`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<Author>,
+}
+
+impl Match {
+    /// A match of `pattern` at column 1 of `line_number`, with no severity,
+    /// category or end of its own; see the `with_` methods for those.
+    pub fn new(
+        file_path: impl Into<SharedStr>,
+        line_number: usize,
+        pattern: impl Into<SharedStr>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            file_path: file_path.into(),
+            line_number,
+            column: 1,
+            pattern: pattern.into(),
+            message: message.into(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
+        }
+    }
+
+    pub fn with_column(mut self, column: usize) -> Self {
+        self.column = column;
+        self
+    }
+
+    pub fn with_severity(mut self, severity: impl Into<Option<Severity>>) -> Self {
+        self.severity = severity.into();
+        self
+    }
+
+    pub fn with_category(mut self, category: impl Into<Option<String>>) -> Self {
+        self.category = category.into();
+        self
+    }
+
+    pub fn with_author(mut self, author: impl Into<Option<Author>>) -> Self {
+        self.author = author.into();
+        self
+    }
+}
+
+/// The author of a line, as recorded by git.
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+)]
+pub struct Author {
+    pub name: String,
+    pub email: String,
 }
 
-/// Severity levels for detected patterns.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+impl std::fmt::Display for Author {
+    /// Formats the author like git does, e.g. `Jane Doe <jane@example.com>`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+/// A 1-based line and column in a file.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Severity levels for detected patterns, ordered from least to most severe.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
 pub enum Severity {
     Info,
     Low,
@@ -50,50 +318,309 @@ pub enum Severity {
     Critical,
 }
 
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    /// Parses a severity name in any case, e.g. `critical`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "info" => Ok(Self::Info),
+            "low" => Ok(Self::Low),
+            "medium" => Ok(Self::Medium),
+            "high" => Ok(Self::High),
+            "critical" => Ok(Self::Critical),
+            _ => Err(format!(
+                "Unknown severity '{}'. Supported: info, low, medium, high, critical",
+                s
+            )),
+        }
+    }
+}
+
 /// Trait for detecting patterns in code content.
 /// Implementors should define how to find specific patterns like TODO or FIXME.
 pub trait PatternDetector: Send + Sync {
     /// Detects patterns in the given content and returns a list of matches.
     /// The file_path is provided for context, such as filtering by file type.
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match>;
+
+    /// Like [`PatternDetector::detect`], reusing the file's [`LineIndex`] that
+    /// scanners build once per file. Detectors that resolve match offsets to
+    /// lines should override it; the default ignores the index.
+    fn detect_with_lines(&self, content: &str, file_path: &Path, lines: &LineIndex) -> Vec<Match> {
+        let _ = lines;
+        self.detect(content, file_path)
+    }
+
+    /// Name used to identify the detector in metrics and reports.
+    /// Defaults to the detector's type name.
+    fn name(&self) -> &str {
+        let type_name = std::any::type_name::<Self>();
+        type_name.rsplit("::").next().unwrap_or(type_name)
+    }
+
+    /// A regex that matches somewhere in every file this detector reports on.
+    /// Scanners combine these into one [`PatternPrefilter`] pass per file and
+    /// skip detectors that cannot match. `None` always runs the detector.
+    fn prefilter(&self) -> Option<&regex::Regex> {
+        None
+    }
+
+    /// Whether the detector looks at `file_path` at all, e.g. `false` for
+    /// non-Rust files in a Rust-only detector. Used to report detector
+    /// coverage; detectors that scan every file keep the default.
+    fn applies_to(&self, file_path: &Path) -> bool {
+        let _ = file_path;
+        true
+    }
+
+    /// Settings that change what the detector reports, such as its pattern
+    /// or limits, so persisted results of another configuration aren't
+    /// reused. Detectors without settings keep the default.
+    fn config_fingerprint(&self) -> String {
+        String::new()
+    }
+}
+
+/// Hidden entries that are still walked because they hold scannable configuration
+pub const SCANNED_HIDDEN_ENTRIES: &[&str] = &[".github", ".gitlab", ".gitlab-ci.yml"];
+
+/// Options for the directory walker shared by all scanners
+#[derive(Clone)]
+pub struct WalkOptions {
+    /// Skips generated and vendored files; `None` scans them too
+    pub generated_filter: Option<Arc<GeneratedFileFilter>>,
+    /// Build output and dependency directories that are not walked
+    pub excluded_dirs: Arc<ExcludedDirs>,
+    /// Descend into symlinked directories and scan symlinked files. Directories
+    /// reached twice, through a cycle or a second link, are walked only once.
+    pub follow_symlinks: bool,
+    /// Maximum directory depth below the scan root; `None` walks the whole tree
+    pub max_depth: Option<usize>,
+    /// Skip files matched by `.gitignore`, `.git/info/exclude` and `.ignore`
+    /// files of the repository
+    pub respect_gitignore: bool,
+    /// Skip files matched by the user's global git excludes file
+    pub respect_global_ignore: bool,
+    /// Walk hidden files and directories too, not just the CI configuration
+    /// of [`SCANNED_HIDDEN_ENTRIES`]
+    pub hidden: bool,
+}
+
+impl WalkOptions {
+    /// Total generated or vendored files skipped by walkers using these options
+    pub fn generated_files_skipped(&self) -> usize {
+        self.generated_filter
+            .as_ref()
+            .map_or(0, |filter| filter.skipped())
+    }
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            generated_filter: Some(Arc::new(GeneratedFileFilter::default())),
+            excluded_dirs: Arc::new(ExcludedDirs::default()),
+            follow_symlinks: false,
+            max_depth: None,
+            respect_gitignore: true,
+            respect_global_ignore: true,
+            hidden: false,
+        }
+    }
+}
+
+/// Creates the directory walker shared by all scanners with default [`WalkOptions`].
+/// Honors ignore files, including the global git excludes file, and skips
+/// hidden entries, except for CI configuration
+/// listed in [`SCANNED_HIDDEN_ENTRIES`], build and dependency directories
+/// such as `target` and `node_modules`, and generated or vendored files.
+pub fn scan_walker(root: &Path) -> WalkBuilder {
+    scan_walker_with(root, &WalkOptions::default())
+}
+
+/// Creates the directory walker shared by all scanners, see [`scan_walker`].
+/// Every scanner walks through it, so ignore files and hidden entries are
+/// treated the same whichever scanner runs.
+pub fn scan_walker_with(root: &Path, options: &WalkOptions) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(root);
+    let generated_filter = options.generated_filter.clone();
+    let excluded_dirs = options.excluded_dirs.clone();
+    let follow_symlinks = options.follow_symlinks;
+    let hidden = options.hidden;
+    // Canonical directory -> path it was first walked through
+    let visited_dirs: Mutex<HashMap<PathBuf, PathBuf>> = Mutex::new(
+        windows_paths::canonicalize(root)
+            .map(|canonical| HashMap::from([(canonical, root.to_path_buf())]))
+            .unwrap_or_default(),
+    );
+    // Hidden entries are filtered below, to keep CI configuration
+    builder
+        .hidden(false)
+        .git_ignore(options.respect_gitignore)
+        .git_exclude(options.respect_gitignore)
+        .ignore(options.respect_gitignore)
+        .parents(options.respect_gitignore)
+        .git_global(options.respect_global_ignore)
+        .follow_links(follow_symlinks)
+        .max_depth(options.max_depth);
+    builder.filter_entry(move |entry| {
+        let name = entry.file_name().to_string_lossy();
+        let visible = hidden
+            || entry.depth() == 0
+            || !name.starts_with('.')
+            || SCANNED_HIDDEN_ENTRIES.contains(&name.as_ref());
+        if !visible {
+            return false;
+        }
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        if is_dir && entry.depth() > 0 && excluded_dirs.is_excluded_name(entry.file_name()) {
+            return false;
+        }
+        if follow_symlinks && is_dir {
+            return first_visit(&visited_dirs, entry.path());
+        }
+        match &generated_filter {
+            Some(filter) if entry.file_type().is_some_and(|t| t.is_file()) => {
+                !filter.skip(entry.path())
+            }
+            _ => true,
+        }
+    });
+    builder
+}
+
+/// Records a directory reached while following symlinks. Returns false if it
+/// was already walked, which breaks symlink cycles and avoids scanning a tree
+/// twice when several links point into it.
+fn first_visit(visited_dirs: &Mutex<HashMap<PathBuf, PathBuf>>, path: &Path) -> bool {
+    let Ok(canonical) = windows_paths::canonicalize(path) else {
+        return true;
+    };
+    let mut visited = visited_dirs.lock().unwrap_or_else(|e| e.into_inner());
+    match visited.get(&canonical) {
+        Some(first) => {
+            eprintln!(
+                "{} Skipping '{}': already scanned as '{}' (symlink cycle or duplicate link)",
+                Icon::Warning,
+                path.display(),
+                first.display()
+            );
+            false
+        }
+        None => {
+            visited.insert(canonical, path.to_path_buf());
+            true
+        }
+    }
+}
+
+/// Counters for [`Scanner::scan_with_metrics`], shared across the parallel file workers
+#[derive(Default)]
+struct ScanCounters {
+    files_scanned: AtomicUsize,
+    lines_processed: AtomicUsize,
+    cache_hits: AtomicUsize,
+    cache_misses: AtomicUsize,
+    /// Files found by the walk, and those reached before a budget ran out
+    files_total: AtomicUsize,
+    files_reached: AtomicUsize,
+    batching: BatchController,
+    file_types: FileTypeCounters,
+}
+
+impl ScanCounters {
+    fn reset(&self) {
+        self.files_total.store(0, Ordering::Relaxed);
+        self.files_reached.store(0, Ordering::Relaxed);
+        self.files_scanned.store(0, Ordering::Relaxed);
+        self.lines_processed.store(0, Ordering::Relaxed);
+        self.cache_hits.store(0, Ordering::Relaxed);
+        self.cache_misses.store(0, Ordering::Relaxed);
+        self.batching.reset();
+        self.file_types.clear();
+    }
 }
 
 /// A scanner that uses parallel processing to scan codebases for patterns.
 pub struct Scanner {
     detectors: Vec<Box<dyn PatternDetector>>,
+    prefilter: PatternPrefilter,
     cache: DashMap<String, (SystemTime, Vec<Match>)>,
+    match_limits: MatchLimits,
+    rule_scopes: RuleScopes,
+    walk_options: WalkOptions,
+    skipped: SkipLog,
+    counters: ScanCounters,
+    schedule: Option<Arc<DetectorSchedule>>,
+    budget: Option<ScanBudget>,
 }
 
 impl Scanner {
     /// Creates a new scanner with the given pattern detectors.
     pub fn new(detectors: Vec<Box<dyn PatternDetector>>) -> Self {
         Self {
+            prefilter: PatternPrefilter::new(&detectors),
             detectors,
             cache: DashMap::new(),
+            match_limits: MatchLimits::default(),
+            rule_scopes: RuleScopes::default(),
+            walk_options: WalkOptions::default(),
+            skipped: SkipLog::default(),
+            counters: ScanCounters::default(),
+            schedule: None,
+            budget: None,
         }
     }
 
-    /// Check if a file should be scanned based on size and type
-    fn should_scan_file(&self, path: &Path, metadata: &std::fs::Metadata) -> bool {
-        // Skip files in common build/dependency directories
-        if let Some(path_str) = path.to_str() {
-            if path_str.contains("/target/")
-                || path_str.contains("/node_modules/")
-                || path_str.contains("/.git/")
-                || path_str.contains("/build/")
-                || path_str.contains("/dist/")
-                || path_str.contains("/.next/")
-                || path_str.contains("/.nuxt/")
-            {
-                return false;
-            }
-        }
+    /// Starts a [`ScannerBuilder`]
+    pub fn builder() -> ScannerBuilder {
+        ScannerBuilder::default()
+    }
 
-        // Check file size (skip files larger than 5MB)
-        if metadata.len() > 5 * 1024 * 1024 {
-            return false;
-        }
+    /// Configure how the directory tree is walked, see [`WalkOptions`].
+    pub fn with_walk_options(mut self, options: WalkOptions) -> Self {
+        self.walk_options = options;
+        self
+    }
 
+    /// Cap the findings kept per file and per pattern, see [`MatchLimits`].
+    pub fn with_match_limits(mut self, limits: MatchLimits) -> Self {
+        self.match_limits = limits;
+        self
+    }
+
+    /// Limit rules to parts of the tree, see [`RuleScopes`].
+    pub fn with_rule_scopes(mut self, scopes: RuleScopes) -> Self {
+        self.rule_scopes = scopes;
+        self
+    }
+
+    /// Stop scanning files once `budget` is used up, scanning the files
+    /// likeliest to have findings first, see [`ScanBudget`].
+    pub fn with_budget(mut self, budget: ScanBudget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// How many files the last [`Scanner::scan`] reached within its budget;
+    /// None without one.
+    pub fn budget_coverage(&self) -> Option<BudgetCoverage> {
+        self.budget.as_ref().map(|_| BudgetCoverage {
+            covered: self.counters.files_reached.load(Ordering::Relaxed),
+            total: self.counters.files_total.load(Ordering::Relaxed),
+        })
+    }
+
+    /// Skip detectors per file extension and record the yield of the rest,
+    /// see [`DetectorSchedule`].
+    pub fn with_detector_schedule(mut self, schedule: Arc<DetectorSchedule>) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    /// Check if a file should be scanned based on size and type
+    fn should_scan_file(&self, path: &Path, metadata: &std::fs::Metadata) -> bool {
         // Check file extension for known binary types (fallback)
         if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
             let ext_lower = ext.to_lowercase();
@@ -113,7 +640,7 @@ impl Scanner {
                 | "r" | "m" | "sh" | "bash" | "zsh" | "fish" | "ps1" | "bat" | "cmd" | "sql"
                 | "xml" | "json" | "yaml" | "yml" | "toml" | "ini" | "cfg" | "conf" | "md"
                 | "txt" | "html" | "htm" | "css" | "scss" | "sass" | "less" | "styl" => {
-                    return true
+                    return self.skipped.check_size(path, metadata.len())
                 }
                 _ => {}
             }
@@ -129,32 +656,183 @@ impl Scanner {
             }
         }
 
-        true
+        // Check file size last so large binaries aren't reported (skip files larger than 5MB)
+        self.skipped.check_size(path, metadata.len())
     }
 
-    /// Reads file content with memory mapping for large files
-    fn read_file_content(&self, path: &Path) -> Result<String> {
-        let metadata = std::fs::metadata(path)?;
+    /// Reads a file that missed the cache, recording it as skipped if that fails
+    fn read_uncached(&self, path: &Path) -> Option<String> {
+        self.counters.cache_misses.fetch_add(1, Ordering::Relaxed);
+        let content = read_stable(path)
+            .map_err(|e| self.skipped.record_error(path, &e))
+            .ok()?;
+        Some(content)
+    }
 
-        if metadata.len() > 1024 * 1024 {
-            // Use memory mapping for large files
-            let file = File::open(path)?;
-            let mmap = unsafe { Mmap::map(&file)? };
-            let content = std::str::from_utf8(&mmap)?;
-            Ok(content.to_string())
-        } else {
-            // Regular reading for smaller files
-            Ok(std::fs::read_to_string(path)?)
+    fn metadata_or_skip(&self, path: &Path) -> Option<std::fs::Metadata> {
+        std::fs::metadata(path)
+            .map_err(|e| self.skipped.record_error(path, &ScanError::io(path, e)))
+            .ok()
+    }
+
+    /// Files found by the last [`Scanner::scan`] that could not be read, sorted by path.
+    /// Binary files are skipped silently and not listed.
+    pub fn skipped_files(&self) -> Vec<SkippedFile> {
+        self.skipped.files()
+    }
+
+    /// Takes stock of the files under `root` as this scanner walks them, see
+    /// [`Inventory`], counting `matches` found by scanning it
+    pub fn inventory(&self, root: &Path, matches: &[Match]) -> Inventory {
+        Inventory::collect(root, &self.walk_options, &self.detectors, matches)
+    }
+
+    /// Runs the detectors over in-memory content as if it were the file at `path`,
+    /// e.g. an unsaved editor buffer. Match limits and rule scopes, resolved
+    /// against `path` as given, apply; the cache does not.
+    pub fn scan_content(&self, content: &str, path: &Path) -> Vec<Match> {
+        let lines = LineIndex::new(content);
+        let matches = self.run_detectors(content, &lines, path, Path::new(""), false);
+        self.match_limits.apply(matches)
+    }
+
+    /// Runs the detectors that can match `content`, see [`PatternPrefilter`],
+    /// sharing the [`LineIndex`] `lines` of it, and drops findings of rules
+    /// scoped away from `path` relative to `root`. With `parallel`, the
+    /// detectors run on the rayon pool when
+    /// [`BatchController::parallel_detectors`] finds them costly enough.
+    fn run_detectors(
+        &self,
+        content: &str,
+        lines: &LineIndex,
+        path: &Path,
+        root: &Path,
+        parallel: bool,
+    ) -> Vec<Match> {
+        if let Some(schedule) = &self.schedule {
+            return self.run_scheduled_detectors(schedule, content, lines, path, root, parallel);
         }
+        let candidates = self.prefilter.candidates(content);
+        self.counters
+            .lines_processed
+            .fetch_add(lines.line_count(), Ordering::Relaxed);
+        let detectors: Vec<&dyn PatternDetector> = self
+            .detectors
+            .iter()
+            .zip(candidates)
+            .filter(|(_, candidate)| *candidate)
+            .map(|(detector, _)| detector.as_ref())
+            .collect();
+        let batching = &self.counters.batching;
+        let detect =
+            |detector: &&dyn PatternDetector| batching.detect(*detector, content, path, lines);
+        let matches = if parallel && batching.parallel_detectors(detectors.len(), content.len()) {
+            detectors.par_iter().flat_map(detect).collect()
+        } else {
+            // For cheap detector runs, sequential is faster (less overhead)
+            detectors.iter().flat_map(detect).collect()
+        };
+        self.rule_scopes.retain(matches, root)
+    }
+
+    /// Like [`Scanner::run_detectors`], without the detectors `schedule`
+    /// skips for the extension of `path`, recording the findings of the
+    /// others. Detectors the prefilter rules out count as run.
+    fn run_scheduled_detectors(
+        &self,
+        schedule: &DetectorSchedule,
+        content: &str,
+        lines: &LineIndex,
+        path: &Path,
+        root: &Path,
+        parallel: bool,
+    ) -> Vec<Match> {
+        let extension = yield_extension(path);
+        let candidates = self.prefilter.candidates(content);
+        self.counters
+            .lines_processed
+            .fetch_add(lines.line_count(), Ordering::Relaxed);
+        let detectors: Vec<(&dyn PatternDetector, bool)> = self
+            .detectors
+            .iter()
+            .zip(candidates)
+            .filter(|(detector, _)| !schedule.skips(detector.name(), &extension))
+            .map(|(detector, candidate)| (detector.as_ref(), candidate))
+            .collect();
+        let batching = &self.counters.batching;
+        let detect = |(detector, candidate): &(&dyn PatternDetector, bool)| {
+            if *candidate {
+                batching.detect(*detector, content, path, lines)
+            } else {
+                Vec::new()
+            }
+        };
+        let found: Vec<Vec<Match>> =
+            if parallel && batching.parallel_detectors(detectors.len(), content.len()) {
+                detectors.par_iter().map(detect).collect()
+            } else {
+                detectors.iter().map(detect).collect()
+            };
+        schedule.record(
+            &extension,
+            detectors
+                .iter()
+                .zip(&found)
+                .map(|((detector, _), matches)| (detector.name(), matches.len())),
+        );
+        self.rule_scopes
+            .retain(found.into_iter().flatten().collect(), root)
+    }
+
+    /// Like [`Scanner::scan`], also returning files scanned, timings and cache statistics.
+    pub fn scan_with_metrics(&self, root: &Path) -> ScanResult<(Vec<Match>, ScanMetrics)> {
+        let start_time = Instant::now();
+        let generated_skipped_before = self.walk_options.generated_files_skipped();
+        let matches = self.scan(root)?;
+
+        let metrics = ScanMetrics {
+            total_files_scanned: self.counters.files_scanned.load(Ordering::Relaxed),
+            total_lines_processed: self.counters.lines_processed.load(Ordering::Relaxed),
+            total_matches_found: matches.len(),
+            scan_duration_ms: start_time.elapsed().as_millis() as u64,
+            cache_hits: self.counters.cache_hits.load(Ordering::Relaxed),
+            cache_misses: self.counters.cache_misses.load(Ordering::Relaxed),
+            cache_invalidated: 0,
+            generated_files_skipped: self.walk_options.generated_files_skipped()
+                - generated_skipped_before,
+            skipped_files: self.skipped.files(),
+            detector_timings: Vec::new(),
+            worker_metrics: Vec::new(),
+            batch_sizing: Some(self.counters.batching.stats()),
+            file_types: self.counters.file_types.metrics(),
+        };
+        Ok((matches, metrics))
+    }
+
+    /// Like [`Scanner::scan_with_metrics`], bundling the results into a [`ScanReport`].
+    pub fn scan_report(&self, root: &Path) -> ScanResult<ScanReport> {
+        let (matches, metrics) = self.scan_with_metrics(root)?;
+        Ok(ScanReport::new(matches, metrics))
     }
 
     /// Scans the directory tree starting from the given root path.
     /// Returns all matches found by the detectors.
     /// Uses conditional parallelism for small scans to reduce overhead.
-    pub fn scan(&self, root: &Path) -> Result<Vec<Match>> {
+    pub fn scan(&self, root: &Path) -> ScanResult<Vec<Match>> {
+        let start = Instant::now();
+        self.skipped.clear();
+        self.counters.reset();
+
         // Collect all file paths first to determine if we should use parallelism
         let mut file_paths = Vec::new();
-        for entry in WalkBuilder::new(root).build().flatten() {
+        for entry in scan_walker_with(root, &self.walk_options).build() {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(error) => {
+                    self.skipped.record_walk_error(root, error);
+                    continue;
+                }
+            };
             if let Some(file_type) = entry.file_type() {
                 if file_type.is_file() {
                     file_paths.push(entry.path().to_path_buf());
@@ -162,99 +840,82 @@ impl Scanner {
             }
         }
 
+        // With a budget, the likeliest files go first and the rest wait for the deadline
+        let deadline = self.budget.as_ref().map(|budget| {
+            prioritize_files(&mut file_paths, &budget.density);
+            start + budget.limit
+        });
+        self.counters
+            .files_total
+            .store(file_paths.len(), Ordering::Relaxed);
+
         // Decide on parallelism based on file count
         let use_parallel = file_paths.len() > 10;
 
+        let scan_file = |path: PathBuf, parallel: bool| -> Option<Vec<Match>> {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return None;
+            }
+            self.counters.files_reached.fetch_add(1, Ordering::Relaxed);
+            let metadata = self.metadata_or_skip(&path)?;
+            if !self.should_scan_file(&path, &metadata) {
+                return None;
+            }
+            self.counters.files_scanned.fetch_add(1, Ordering::Relaxed);
+            let path_str = path.to_string_lossy().to_string();
+            let mtime = metadata.modified().ok()?;
+            if let Some(cached) = self.cache.get(&path_str) {
+                let (cached_mtime, cached_matches) = &*cached;
+                if cached_mtime == &mtime {
+                    self.counters.cache_hits.fetch_add(1, Ordering::Relaxed);
+                    self.counters
+                        .file_types
+                        .record(&path, 0, cached_matches.len(), Duration::ZERO);
+                    return Some(cached_matches.clone());
+                }
+            }
+            let file_start = Instant::now();
+            let content = self.read_uncached(&path)?;
+            let lines = LineIndex::new(&content);
+            let file_matches = self.run_detectors(&content, &lines, &path, root, parallel);
+            let file_matches = self.match_limits.apply(file_matches);
+            let elapsed = file_start.elapsed();
+            self.counters.batching.record_file(&path, elapsed);
+            self.counters
+                .file_types
+                .record(&path, lines.line_count(), file_matches.len(), elapsed);
+            self.cache.insert(path_str, (mtime, file_matches.clone()));
+            Some(file_matches)
+        };
+
         let matches: Vec<Match> = if use_parallel {
-            // Parallel processing for many files
-            file_paths
-                .into_par_iter()
-                .filter_map(|path| {
-                    let metadata = std::fs::metadata(&path).ok()?;
-                    if !self.should_scan_file(&path, &metadata) {
-                        return None;
-                    }
-                    let path_str = path.to_string_lossy().to_string();
-                    let mtime = metadata.modified().ok()?;
-                    if let Some(cached) = self.cache.get(&path_str) {
-                        let (cached_mtime, cached_matches) = &*cached;
-                        if cached_mtime == &mtime {
-                            Some(cached_matches.clone())
-                        } else {
-                            let content = self.read_file_content(&path).ok()?;
-                            let file_matches: Vec<Match> = if self.detectors.len() <= 3 {
-                                // For few detectors, sequential is faster (less overhead)
-                                self.detectors
-                                    .iter()
-                                    .flat_map(|detector| detector.detect(&content, path.as_path()))
-                                    .collect()
-                            } else {
-                                // For many detectors, use parallel processing
-                                self.detectors
-                                    .par_iter()
-                                    .flat_map(|detector| detector.detect(&content, path.as_path()))
-                                    .collect()
-                            };
-                            self.cache.insert(path_str, (mtime, file_matches.clone()));
-                            Some(file_matches)
-                        }
-                    } else {
-                        let content = self.read_file_content(&path).ok()?;
-                        let file_matches: Vec<Match> = if self.detectors.len() <= 3 {
-                            // For few detectors, sequential is faster (less overhead)
-                            self.detectors
-                                .iter()
-                                .flat_map(|detector| detector.detect(&content, path.as_path()))
-                                .collect()
-                        } else {
-                            // For many detectors, use parallel processing
-                            self.detectors
-                                .par_iter()
-                                .flat_map(|detector| detector.detect(&content, path.as_path()))
-                                .collect()
-                        };
-                        self.cache.insert(path_str, (mtime, file_matches.clone()));
-                        Some(file_matches)
-                    }
+            // Chunks are taken in order, so a budget still reaches the likeliest
+            // files first, and sized from the files measured by then
+            let mut scanned: Vec<(usize, Vec<Match>)> = self
+                .counters
+                .batching
+                .chunks(file_paths)
+                .enumerate()
+                .par_bridge()
+                .map(|(i, chunk)| {
+                    let matches = chunk
+                        .into_iter()
+                        .filter_map(|path| scan_file(path, true))
+                        .flatten()
+                        .collect();
+                    (i, matches)
                 })
-                .flatten()
+                .collect();
+            scanned.sort_by_key(|(i, _)| *i);
+            scanned
+                .into_iter()
+                .flat_map(|(_, matches)| matches)
                 .collect()
         } else {
             // Sequential processing for few files
             file_paths
                 .into_iter()
-                .filter_map(|path| {
-                    let metadata = std::fs::metadata(&path).ok()?;
-                    if !self.should_scan_file(&path, &metadata) {
-                        return None;
-                    }
-                    let path_str = path.to_string_lossy().to_string();
-                    let mtime = metadata.modified().ok()?;
-                    if let Some(cached) = self.cache.get(&path_str) {
-                        let (cached_mtime, cached_matches) = &*cached;
-                        if cached_mtime == &mtime {
-                            Some(cached_matches.clone())
-                        } else {
-                            let content = self.read_file_content(&path).ok()?;
-                            let file_matches: Vec<Match> = self
-                                .detectors
-                                .iter()
-                                .flat_map(|detector| detector.detect(&content, path.as_path()))
-                                .collect();
-                            self.cache.insert(path_str, (mtime, file_matches.clone()));
-                            Some(file_matches)
-                        }
-                    } else {
-                        let content = self.read_file_content(&path).ok()?;
-                        let file_matches: Vec<Match> = self
-                            .detectors
-                            .iter()
-                            .flat_map(|detector| detector.detect(&content, path.as_path()))
-                            .collect();
-                        self.cache.insert(path_str, (mtime, file_matches.clone()));
-                        Some(file_matches)
-                    }
-                })
+                .filter_map(|path| scan_file(path, false))
                 .flatten()
                 .collect()
         };
@@ -263,18 +924,105 @@ impl Scanner {
     }
 }
 
+/// Configures a [`Scanner`]. Without any detectors, the scanner uses
+/// [`DetectorProfile::Basic`].
+#[derive(Default)]
+pub struct ScannerBuilder {
+    registry: DetectorRegistry,
+    walk_options: WalkOptions,
+    match_limits: MatchLimits,
+    rule_scopes: RuleScopes,
+}
+
+impl ScannerBuilder {
+    /// Adds the detectors of `profile`
+    pub fn profile(mut self, profile: DetectorProfile) -> Self {
+        self.registry.extend(profile.get_detectors());
+        self
+    }
+
+    /// Adds `detector`, replacing an earlier one with the same name
+    pub fn detector(mut self, detector: Box<dyn PatternDetector>) -> Self {
+        self.registry.register(detector);
+        self
+    }
+
+    /// Adds every detector of `registry`
+    pub fn registry(mut self, registry: DetectorRegistry) -> Self {
+        self.registry.extend(registry.into_detectors());
+        self
+    }
+
+    /// Descend into symlinked directories, walking each directory once
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.walk_options.follow_symlinks = follow;
+        self
+    }
+
+    /// Maximum directory depth below the scan root
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.walk_options.max_depth = Some(depth);
+        self
+    }
+
+    /// Scan generated and vendored files too, which are skipped by default
+    pub fn include_generated(mut self, include: bool) -> Self {
+        self.walk_options.generated_filter = if include {
+            None
+        } else {
+            Some(Arc::new(GeneratedFileFilter::default()))
+        };
+        self
+    }
+
+    /// Cap the findings kept per file and per pattern
+    pub fn match_limits(mut self, limits: MatchLimits) -> Self {
+        self.match_limits = limits;
+        self
+    }
+
+    /// Limit rules to parts of the tree, e.g. from [`crate::config::Config::rule_paths`]
+    pub fn rule_scopes(mut self, scopes: RuleScopes) -> Self {
+        self.rule_scopes = scopes;
+        self
+    }
+
+    pub fn build(self) -> Scanner {
+        let detectors = if self.registry.is_empty() {
+            DetectorProfile::Basic.get_detectors()
+        } else {
+            self.registry.into_detectors()
+        };
+        Scanner::new(detectors)
+            .with_walk_options(self.walk_options)
+            .with_match_limits(self.match_limits)
+            .with_rule_scopes(self.rule_scopes)
+    }
+}
+
 // Re-export detectors and factory for convenience
-pub use cache::*;
-pub use custom_detectors::*;
-pub use detector_factory::*;
 pub use detectors::*;
-pub use distributed::*;
-pub use enhanced_config::*;
-pub use incremental::*;
-pub use llm_detectors::*;
-pub use monitoring::*;
-pub use optimized_scanner::*;
-pub use performance::*;
+pub use errors::*;
+pub use registry::DetectorRegistry;
+pub use report::{ScanReport, ScanStatistics};
+
+// Workspace internals, re-exported for the other code-guardian crates. They are
+// hidden from the docs and not covered by semver; use the prelude instead.
+#[doc(hidden)]
+pub use {
+    a11y_detectors::*, api_token::*, artifact_upload::*, audit_log::*, batch_sizing::*,
+    bitbucket::*, c_detectors::*, cache::*, ci_detectors::*, ci_environment::*, comment_spans::*,
+    compliance::*, coverage::*, custom_detectors::*, deprecation::*, detector_conditions::*,
+    detector_factory::*, detector_yield::*, directory_cache::*, distributed::*, enhanced_config::*,
+    excluded_dirs::*, feature_flags::*, feed::*, file_priority::*, finding_state::*, generated::*,
+    gitlab::*, go_detectors::*, i18n_detectors::*, iac_detectors::*, incremental::*, inventory::*,
+    issue_groups::*, jira::*, jvm_detectors::*, line_index::*, llm_detectors::*, match_limits::*,
+    migration_detectors::*, monitoring::*, multi_pattern::*, optimized_scanner::*, output_style::*,
+    performance::*, pii_detectors::*, python_detectors::*, quality_detectors::*, redaction::*,
+    regex_cache::*, rule_scopes::*, rust_panic_audit::*, scan_budget::*, scan_cache::*,
+    scan_targets::*, security_detectors::*, shared_str::*, skipped::*, stable_read::*,
+    test_quality_detectors::*, tls::*, triggers::*, tuning::*, webhook::*, worker_discovery::*,
+};
 
 #[cfg(test)]
 mod tests {
@@ -348,6 +1096,328 @@ mod tests {
         assert_eq!(sorted[1].pattern, "TODO");
     }
 
+    #[test]
+    fn test_scanner_with_match_limits() {
+        use tempfile::TempDir;
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("generated.rs"), "// TODO\n".repeat(50)).unwrap();
+
+        let scanner = Scanner::new(vec![Box::new(TodoDetector)])
+            .with_match_limits(MatchLimits::new(None, Some(10)));
+        let matches = scanner.scan(temp_dir.path()).unwrap();
+        assert_eq!(matches.len(), 11);
+        assert_eq!(matches[10].pattern, TRUNCATED_PATTERN);
+        assert_eq!(matches[10].line_number, 11);
+        assert_eq!(matches[10].message, "+40 more TODO findings in this file");
+    }
+
+    #[test]
+    fn test_scanner_reports_skipped_files() {
+        use tempfile::TempDir;
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("ok.rs"), "// TODO\n").unwrap();
+        std::fs::write(temp_dir.path().join("latin1.rs"), b"// TODO caf\xe9\n").unwrap();
+        std::fs::write(
+            temp_dir.path().join("logo.png"),
+            [0x89, b'P', b'N', b'G', 0xff],
+        )
+        .unwrap();
+        let huge = vec![b'a'; MAX_SCANNED_FILE_SIZE as usize + 1];
+        std::fs::write(temp_dir.path().join("huge.rs"), huge).unwrap();
+
+        let scanner = Scanner::new(vec![Box::new(TodoDetector)]);
+        let matches = scanner.scan(temp_dir.path()).unwrap();
+        assert_eq!(matches.len(), 1);
+
+        // Binary files are skipped silently
+        let reasons: Vec<(String, SkipReason)> = scanner
+            .skipped_files()
+            .into_iter()
+            .map(|f| {
+                (
+                    Path::new(&f.path)
+                        .file_name()
+                        .unwrap()
+                        .to_string_lossy()
+                        .to_string(),
+                    f.reason,
+                )
+            })
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (
+                    "huge.rs".to_string(),
+                    SkipReason::TooLarge {
+                        size: MAX_SCANNED_FILE_SIZE + 1,
+                        limit: MAX_SCANNED_FILE_SIZE
+                    }
+                ),
+                ("latin1.rs".to_string(), SkipReason::InvalidUtf8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scanner_with_budget() {
+        use std::time::Duration;
+        use tempfile::TempDir;
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..20 {
+            std::fs::write(temp_dir.path().join(format!("{}.rs", i)), "// TODO\n").unwrap();
+        }
+
+        let scanner = Scanner::new(vec![Box::new(TodoDetector)]);
+        let matches = scanner.scan(temp_dir.path()).unwrap();
+        assert_eq!(matches.len(), 20);
+        assert_eq!(scanner.budget_coverage(), None);
+
+        let scanner = scanner.with_budget(ScanBudget::new(Duration::from_secs(60)));
+        assert_eq!(scanner.scan(temp_dir.path()).unwrap().len(), 20);
+        let coverage = scanner.budget_coverage().unwrap();
+        assert_eq!((coverage.covered, coverage.total), (20, 20));
+        assert!(!coverage.is_partial());
+
+        // Used up before the first file
+        let scanner = Scanner::new(vec![Box::new(TodoDetector)])
+            .with_budget(ScanBudget::new(Duration::from_nanos(1)));
+        assert!(scanner.scan(temp_dir.path()).unwrap().is_empty());
+        assert_eq!(
+            scanner.budget_coverage().unwrap().to_string(),
+            "0/20 files covered (0%)"
+        );
+    }
+
+    #[test]
+    fn test_scanner_scan_with_metrics() {
+        use tempfile::TempDir;
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("a.rs"), "// TODO\nfn a() {}\n").unwrap();
+        std::fs::write(temp_dir.path().join("b.rs"), "fn b() {}\n").unwrap();
+        std::fs::write(temp_dir.path().join("notes.txt"), "notes\n").unwrap();
+
+        let scanner = Scanner::new(vec![Box::new(TodoDetector)]);
+        let (matches, metrics) = scanner.scan_with_metrics(temp_dir.path()).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(metrics.total_files_scanned, 3);
+        assert_eq!(metrics.total_lines_processed, 4);
+        assert_eq!(metrics.total_matches_found, 1);
+        assert_eq!((metrics.cache_hits, metrics.cache_misses), (0, 3));
+        let batch_sizing = metrics.batch_sizing.unwrap();
+        assert_eq!(batch_sizing.directories.len(), 1);
+        assert_eq!(batch_sizing.directories[0].files, 3);
+        let file_types: Vec<_> = metrics
+            .file_types
+            .iter()
+            .map(|t| {
+                (
+                    t.extension.as_str(),
+                    t.language.as_deref(),
+                    t.files,
+                    t.lines,
+                    t.matches,
+                )
+            })
+            .collect();
+        assert_eq!(file_types.len(), 2);
+        assert!(file_types.contains(&("rs", Some("Rust"), 2, 3, 1)));
+        assert!(file_types.contains(&("txt", None, 1, 1, 0)));
+
+        let (_, metrics) = scanner.scan_with_metrics(temp_dir.path()).unwrap();
+        assert_eq!((metrics.cache_hits, metrics.cache_misses), (3, 0));
+        assert_eq!(metrics.total_lines_processed, 0);
+        let rust = metrics
+            .file_types
+            .iter()
+            .find(|t| t.extension == "rs")
+            .unwrap();
+        assert_eq!((rust.files, rust.lines, rust.matches), (2, 0, 1));
+    }
+
+    #[test]
+    fn test_scanner_builder_report() {
+        use crate::prelude::*;
+        use tempfile::TempDir;
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("a.rs"), "// TODO\n// HACK\n").unwrap();
+        std::fs::create_dir(temp_dir.path().join("nested")).unwrap();
+        std::fs::write(temp_dir.path().join("nested/b.rs"), "// FIXME\n").unwrap();
+
+        let report = Scanner::builder()
+            .build()
+            .scan_report(temp_dir.path())
+            .unwrap();
+        assert_eq!(report.summary.total_matches, 2);
+        assert_eq!(report.summary.files_with_matches, 2);
+
+        let report = Scanner::builder()
+            .detector(Box::new(HackDetector))
+            .max_depth(1)
+            .build()
+            .scan_report(temp_dir.path())
+            .unwrap();
+        let patterns: Vec<&str> = report.matches.iter().map(|m| m.pattern.as_str()).collect();
+        assert_eq!(patterns, vec!["HACK"]);
+        assert_eq!(report.metrics.total_files_scanned, 1);
+        assert!(report.skipped_files().is_empty());
+    }
+
+    #[test]
+    fn test_scanner_scan_content() {
+        let scanner = Scanner::new(vec![Box::new(TodoDetector), Box::new(FixmeDetector)])
+            .with_match_limits(MatchLimits::new(None, Some(1)));
+        let matches = scanner.scan_content(
+            "// TODO: one\n// TODO: two\n// FIXME: three\n",
+            Path::new("src/unsaved.rs"),
+        );
+        let summary: Vec<(&str, usize, &str)> = matches
+            .iter()
+            .map(|m| (m.file_path.as_str(), m.line_number, m.pattern.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("src/unsaved.rs", 1, "TODO"),
+                ("src/unsaved.rs", 3, "FIXME"),
+                ("src/unsaved.rs", 2, TRUNCATED_PATTERN),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_walker_skips_excluded_dirs() {
+        use tempfile::TempDir;
+        let temp_dir = TempDir::new().unwrap();
+        // A scan root inside a directory named like an excluded one
+        let root = temp_dir.path().join("build").join("repo");
+        for dir in [
+            "src",
+            "target/debug",
+            "web/node_modules/pkg",
+            "api/__pycache__",
+            "out",
+        ] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+            std::fs::write(root.join(dir).join("file.py"), "# TODO here\n").unwrap();
+        }
+
+        let scan = |options: WalkOptions| {
+            let mut files: Vec<String> = Scanner::new(vec![Box::new(TodoDetector)])
+                .with_walk_options(options)
+                .scan(&root)
+                .unwrap()
+                .into_iter()
+                .map(|m| {
+                    Path::new(m.file_path.as_str())
+                        .strip_prefix(&root)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect();
+            files.sort();
+            files
+        };
+        assert_eq!(
+            scan(WalkOptions::default()),
+            vec!["out/file.py", "src/file.py"]
+        );
+        let options = WalkOptions {
+            excluded_dirs: Arc::new(ExcludedDirs::new(&["out"])),
+            ..WalkOptions::default()
+        };
+        assert_eq!(scan(options), vec!["src/file.py"]);
+    }
+
+    #[test]
+    fn test_scan_walker_ignore_and_hidden_options() {
+        use tempfile::TempDir;
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        // Ignore files of a repository apply once it has a `.git` directory
+        std::fs::create_dir_all(root.join(".git/info")).unwrap();
+        std::fs::write(root.join(".git/info/exclude"), "local.rs\n").unwrap();
+        std::fs::write(root.join(".gitignore"), "ignored.rs\n").unwrap();
+        std::fs::create_dir(root.join(".tools")).unwrap();
+        for file in ["kept.rs", "ignored.rs", "local.rs", ".tools/hidden.rs"] {
+            std::fs::write(root.join(file), "// TODO here\n").unwrap();
+        }
+
+        let scan = |options: WalkOptions| {
+            let mut files: Vec<String> = Scanner::new(vec![Box::new(TodoDetector)])
+                .with_walk_options(options)
+                .scan(root)
+                .unwrap()
+                .into_iter()
+                .map(|m| {
+                    Path::new(m.file_path.as_str())
+                        .strip_prefix(root)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect();
+            files.sort();
+            files
+        };
+        assert_eq!(scan(WalkOptions::default()), vec!["kept.rs"]);
+        let everything = WalkOptions {
+            respect_gitignore: false,
+            hidden: true,
+            ..WalkOptions::default()
+        };
+        assert_eq!(
+            scan(everything),
+            vec![".tools/hidden.rs", "ignored.rs", "kept.rs", "local.rs"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_walker_symlinks_and_depth() {
+        use tempfile::TempDir;
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("repo");
+        let shared = temp_dir.path().join("shared");
+        std::fs::create_dir_all(root.join("app/nested")).unwrap();
+        std::fs::create_dir_all(&shared).unwrap();
+        std::fs::write(root.join("app/nested/deep.rs"), "// TODO deep\n").unwrap();
+        std::fs::write(shared.join("lib.rs"), "// TODO shared\n").unwrap();
+        std::os::unix::fs::symlink(&shared, root.join("shared")).unwrap();
+        std::os::unix::fs::symlink(&shared, root.join("app/shared_again")).unwrap();
+        std::os::unix::fs::symlink(&root, root.join("app/loop")).unwrap();
+
+        let scan = |options: WalkOptions| {
+            let mut messages: Vec<String> = Scanner::new(vec![Box::new(TodoDetector)])
+                .with_walk_options(options)
+                .scan(&root)
+                .unwrap()
+                .into_iter()
+                .map(|m| m.message)
+                .collect();
+            messages.sort();
+            messages
+        };
+
+        assert_eq!(scan(WalkOptions::default()), vec!["TODO: // TODO deep"]);
+
+        let follow = WalkOptions {
+            follow_symlinks: true,
+            ..WalkOptions::default()
+        };
+        assert_eq!(
+            scan(follow.clone()),
+            vec!["TODO: // TODO deep", "TODO: // TODO shared"]
+        );
+
+        let shallow = WalkOptions {
+            max_depth: Some(2),
+            ..follow
+        };
+        assert_eq!(scan(shallow), vec!["TODO: // TODO shared"]);
+    }
+
     #[test]
     fn test_production_readiness_multi_language_scan() {
         use tempfile::TempDir;
@@ -427,7 +1497,8 @@ mod tests {
         );
 
         println!(
-            "✅ Production readiness scan found {} issues across multiple languages",
+            "{} Production readiness scan found {} issues across multiple languages",
+            Icon::Success,
             matches.len()
         );
         for m in &matches {
@@ -0,0 +1,116 @@
+//! Line offsets of a file, built once and shared by every detector
+
+use crate::LineColumn;
+use memchr::memchr_iter;
+
+/// Byte offsets of the start of each line, so a match offset resolves to a
+/// line and column with a binary search instead of rescanning the content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// Offset of each line start; always begins with 0
+    starts: Vec<usize>,
+    len: usize,
+}
+
+impl LineIndex {
+    pub fn new(content: &str) -> Self {
+        let mut starts = Vec::with_capacity(content.len() / 40 + 1);
+        starts.push(0);
+        starts.extend(memchr_iter(b'\n', content.as_bytes()).map(|pos| pos + 1));
+        Self {
+            starts,
+            len: content.len(),
+        }
+    }
+
+    /// Number of lines, counted like [`str::lines`]
+    pub fn line_count(&self) -> usize {
+        match self.starts.last() {
+            Some(&last) if last == self.len => self.starts.len() - 1,
+            _ => self.starts.len(),
+        }
+    }
+
+    /// 1-based line containing the byte `offset`
+    pub fn line_of(&self, offset: usize) -> usize {
+        self.starts.partition_point(|&start| start <= offset)
+    }
+
+    /// Byte offset where the 1-based `line` starts
+    pub fn line_start(&self, line: usize) -> usize {
+        self.starts[line.saturating_sub(1).min(self.starts.len() - 1)]
+    }
+
+    /// 1-based line and character column of the byte `offset` in `content`,
+    /// which must be the content the index was built from
+    pub fn position(&self, content: &str, offset: usize) -> (usize, usize) {
+        let line = self.line_of(offset);
+        let column = content[self.line_start(line)..offset].chars().count() + 1;
+        (line, column)
+    }
+
+    /// Byte offset of the 1-based `line` and character `column`, the inverse
+    /// of [`LineIndex::position`]. Columns past the end of the line give the
+    /// end of the line.
+    pub fn offset(&self, content: &str, line: usize, column: usize) -> usize {
+        let text = self.line(content, line);
+        let start = self.line_start(line);
+        start
+            + text
+                .char_indices()
+                .nth(column.saturating_sub(1))
+                .map_or(text.len(), |(i, _)| i)
+    }
+
+    /// Where the text from byte `start` to `end` ends, without trailing
+    /// whitespace, if that is past the line it starts on. The column is just
+    /// past the last character, like the end of a SARIF region.
+    pub fn span_end(&self, content: &str, start: usize, end: usize) -> Option<LineColumn> {
+        let end = start + content[start..end].trim_end().len();
+        if end == start || self.line_of(end - 1) == self.line_of(start) {
+            return None;
+        }
+        let (line, column) = self.position(content, end);
+        Some(LineColumn { line, column })
+    }
+
+    /// Text of the 1-based `line` without its line ending
+    pub fn line<'a>(&self, content: &'a str, line: usize) -> &'a str {
+        let start = self.line_start(line);
+        let end = self
+            .starts
+            .get(line)
+            .map_or(content.len(), |&next| next - 1);
+        let text = &content[start..end.max(start)];
+        text.strip_suffix('\r').unwrap_or(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_index() {
+        let content = "ab\r\ncé d\n\nlast";
+        let index = LineIndex::new(content);
+
+        assert_eq!(index.line_count(), content.lines().count());
+        assert_eq!(LineIndex::new("a\n").line_count(), 1);
+        assert_eq!(LineIndex::new("").line_count(), 0);
+
+        assert_eq!(index.position(content, 0), (1, 1));
+        assert_eq!(index.position(content, 1), (1, 2));
+        // Columns count characters, not bytes
+        let d = content.find('d').unwrap();
+        assert_eq!(index.position(content, d), (2, 4));
+        assert_eq!(index.position(content, content.len()), (4, 5));
+        assert_eq!(index.offset(content, 2, 4), d);
+        assert_eq!(index.offset(content, 1, 9), 2);
+
+        assert_eq!(index.line(content, 1), "ab");
+        assert_eq!(index.line(content, 2), "cé d");
+        assert_eq!(index.line(content, 3), "");
+        assert_eq!(index.line(content, 4), "last");
+    }
+}
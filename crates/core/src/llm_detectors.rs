@@ -1,7 +1,14 @@
-use crate::{Match, PatternDetector};
+use crate::detectors::has_extension;
+use crate::errors::{ScanError, ScanResult};
+use crate::redaction::finding_message;
+use crate::webhook::{post_json, validate_webhook_url};
+use crate::{Match, PatternDetector, SharedStr};
 use lazy_static::lazy_static;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
+use std::time::Duration;
 
 lazy_static! {
     // Hallucinated API patterns - APIs that LLMs commonly generate but don't exist
@@ -141,6 +148,8 @@ fn detect_pattern_with_context(
     re: &Regex,
 ) -> Vec<Match> {
     let mut matches = Vec::new();
+    let mut shared_path = None;
+    let pattern = SharedStr::interned(pattern_name);
     for (line_idx, line) in content.lines().enumerate() {
         for mat in re.find_iter(line) {
             let context_start = mat.start().saturating_sub(15);
@@ -148,11 +157,20 @@ fn detect_pattern_with_context(
             let match_context = &line[context_start..context_end];
 
             matches.push(Match {
-                file_path: file_path.to_string_lossy().to_string(),
+                file_path: shared_path
+                    .get_or_insert_with(|| SharedStr::from_path(file_path))
+                    .clone(),
                 line_number: line_idx + 1,
                 column: mat.start() + 1,
-                pattern: pattern_name.to_string(),
-                message: format!("{}: {}", pattern_name, match_context.trim()),
+                pattern: pattern.clone(),
+                message: finding_message(
+                    pattern_name,
+                    format!("{}: {}", pattern_name, match_context.trim()),
+                ),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             });
         }
     }
@@ -194,6 +212,10 @@ impl PatternDetector for LLMSQLInjectionDetector {
             &SQL_INJECTION_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&SQL_INJECTION_REGEX)
+    }
 }
 
 /// Detector for insecure random number generation in security contexts
@@ -208,6 +230,10 @@ impl PatternDetector for InsecureRandomDetector {
             &INSECURE_RANDOM_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&INSECURE_RANDOM_REGEX)
+    }
 }
 
 /// Detector for hardcoded credentials in LLM-generated code
@@ -222,6 +248,10 @@ impl PatternDetector for HardcodedCredentialsDetector {
             &HARDCODED_CREDENTIALS_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&HARDCODED_CREDENTIALS_REGEX)
+    }
 }
 
 /// Detector for memory safety issues in Rust code generated by LLMs
@@ -229,17 +259,23 @@ pub struct RustMemorySafetyDetector;
 
 impl PatternDetector for RustMemorySafetyDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        if let Some(ext) = file_path.extension() {
-            if ext == "rs" {
-                return detect_pattern_with_context(
-                    content,
-                    file_path,
-                    "LLM_RUST_MEMORY_SAFETY",
-                    &RUST_MEMORY_SAFETY_REGEX,
-                );
-            }
+        if !self.applies_to(file_path) {
+            return Vec::new();
         }
-        Vec::new()
+        detect_pattern_with_context(
+            content,
+            file_path,
+            "LLM_RUST_MEMORY_SAFETY",
+            &RUST_MEMORY_SAFETY_REGEX,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["rs"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&RUST_MEMORY_SAFETY_REGEX)
     }
 }
 
@@ -248,18 +284,23 @@ pub struct AsyncAntipatternDetector;
 
 impl PatternDetector for AsyncAntipatternDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        if let Some(ext) = file_path.extension() {
-            let ext_str = ext.to_string_lossy();
-            if matches!(ext_str.as_ref(), "js" | "ts" | "jsx" | "tsx" | "rs") {
-                return detect_pattern_with_context(
-                    content,
-                    file_path,
-                    "LLM_ASYNC_ANTIPATTERN",
-                    &ASYNC_ANTIPATTERN_REGEX,
-                );
-            }
+        if !self.applies_to(file_path) {
+            return Vec::new();
         }
-        Vec::new()
+        detect_pattern_with_context(
+            content,
+            file_path,
+            "LLM_ASYNC_ANTIPATTERN",
+            &ASYNC_ANTIPATTERN_REGEX,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["js", "ts", "jsx", "tsx", "rs"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&ASYNC_ANTIPATTERN_REGEX)
     }
 }
 
@@ -275,6 +316,10 @@ impl PatternDetector for PerformanceAntipatternDetector {
             &PERFORMANCE_ANTIPATTERN_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&PERFORMANCE_ANTIPATTERN_REGEX)
+    }
 }
 
 /// Detector for poor error handling patterns
@@ -289,6 +334,10 @@ impl PatternDetector for ErrorHandlingDetector {
             &ERROR_HANDLING_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&ERROR_HANDLING_REGEX)
+    }
 }
 
 /// Detector for cryptographic anti-patterns
@@ -303,6 +352,10 @@ impl PatternDetector for CryptoAntipatternDetector {
             &CRYPTO_ANTIPATTERN_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&CRYPTO_ANTIPATTERN_REGEX)
+    }
 }
 
 /// Detector for over-engineering patterns
@@ -317,6 +370,10 @@ impl PatternDetector for OverengineeringDetector {
             &OVERENGINEERING_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&OVERENGINEERING_REGEX)
+    }
 }
 
 /// Detector for XSS and code injection vulnerabilities
@@ -331,6 +388,10 @@ impl PatternDetector for XSSInjectionDetector {
             &XSS_INJECTION_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&XSS_INJECTION_REGEX)
+    }
 }
 
 /// Detector for file system security issues
@@ -345,6 +406,10 @@ impl PatternDetector for FilesystemSecurityDetector {
             &FILESYSTEM_SECURITY_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&FILESYSTEM_SECURITY_REGEX)
+    }
 }
 
 /// Detector for configuration anti-patterns
@@ -359,6 +424,10 @@ impl PatternDetector for ConfigAntipatternDetector {
             &CONFIG_ANTIPATTERN_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&CONFIG_ANTIPATTERN_REGEX)
+    }
 }
 
 /// Detector for JavaScript-specific LLM issues
@@ -366,21 +435,18 @@ pub struct JSLLMIssuesDetector;
 
 impl PatternDetector for JSLLMIssuesDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        if let Some(ext) = file_path.extension() {
-            let ext_str = ext.to_string_lossy();
-            if matches!(
-                ext_str.as_ref(),
-                "js" | "ts" | "jsx" | "tsx" | "vue" | "svelte"
-            ) {
-                return detect_pattern_with_context(
-                    content,
-                    file_path,
-                    "LLM_JS_ISSUES",
-                    &JS_LLM_ISSUES_REGEX,
-                );
-            }
+        if !self.applies_to(file_path) {
+            return Vec::new();
         }
-        Vec::new()
+        detect_pattern_with_context(content, file_path, "LLM_JS_ISSUES", &JS_LLM_ISSUES_REGEX)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["js", "ts", "jsx", "tsx", "vue", "svelte"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&JS_LLM_ISSUES_REGEX)
     }
 }
 
@@ -389,17 +455,23 @@ pub struct PythonLLMIssuesDetector;
 
 impl PatternDetector for PythonLLMIssuesDetector {
     fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
-        if let Some(ext) = file_path.extension() {
-            if ext == "py" {
-                return detect_pattern_with_context(
-                    content,
-                    file_path,
-                    "LLM_PYTHON_ISSUES",
-                    &PYTHON_LLM_ISSUES_REGEX,
-                );
-            }
+        if !self.applies_to(file_path) {
+            return Vec::new();
         }
-        Vec::new()
+        detect_pattern_with_context(
+            content,
+            file_path,
+            "LLM_PYTHON_ISSUES",
+            &PYTHON_LLM_ISSUES_REGEX,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["py"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&PYTHON_LLM_ISSUES_REGEX)
     }
 }
 
@@ -415,6 +487,10 @@ impl PatternDetector for ContextConfusionDetector {
             &CONTEXT_CONFUSION_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&CONTEXT_CONFUSION_REGEX)
+    }
 }
 
 /// Detector for database anti-patterns
@@ -429,6 +505,10 @@ impl PatternDetector for DatabaseAntipatternDetector {
             &DATABASE_ANTIPATTERN_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&DATABASE_ANTIPATTERN_REGEX)
+    }
 }
 
 /// Detector for comments indicating LLM-generated code
@@ -443,6 +523,10 @@ impl PatternDetector for LLMGeneratedCommentsDetector {
             &LLM_GENERATED_COMMENTS_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&LLM_GENERATED_COMMENTS_REGEX)
+    }
 }
 
 /// Detector for AI model hallucinated patterns
@@ -457,6 +541,10 @@ impl PatternDetector for AIModelHallucinationDetector {
             &AI_MODEL_HALLUCINATION_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&AI_MODEL_HALLUCINATION_REGEX)
+    }
 }
 
 /// Detector for incorrect async patterns
@@ -471,6 +559,10 @@ impl PatternDetector for IncorrectAsyncDetector {
             &INCORRECT_ASYNC_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&INCORRECT_ASYNC_REGEX)
+    }
 }
 
 /// Detector for LLM-generated security anti-patterns
@@ -485,6 +577,10 @@ impl PatternDetector for LLMSecurityAntipatternDetector {
             &LLM_SECURITY_ANTIPATTERN_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&LLM_SECURITY_ANTIPATTERN_REGEX)
+    }
 }
 
 /// Detector for LLM-generated database anti-patterns
@@ -499,6 +595,10 @@ impl PatternDetector for LLMDBAntipatternDetector {
             &LLM_DB_ANTIPATTERN_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&LLM_DB_ANTIPATTERN_REGEX)
+    }
 }
 
 /// Detector for LLM-generated error handling mistakes
@@ -513,6 +613,10 @@ impl PatternDetector for LLMErrorHandlingMistakesDetector {
             &LLM_ERROR_HANDLING_MISTAKES_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&LLM_ERROR_HANDLING_MISTAKES_REGEX)
+    }
 }
 
 /// Detector for LLM-generated performance mistakes
@@ -527,6 +631,10 @@ impl PatternDetector for LLMPerformanceMistakesDetector {
             &LLM_PERFORMANCE_MISTAKES_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&LLM_PERFORMANCE_MISTAKES_REGEX)
+    }
 }
 
 /// Detector for LLM-generated type handling mistakes
@@ -541,6 +649,10 @@ impl PatternDetector for LLMTypeMistakesDetector {
             &LLM_TYPE_MISTAKES_REGEX,
         )
     }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&LLM_TYPE_MISTAKES_REGEX)
+    }
 }
 
 /// Comprehensive LLM vulnerability detector that combines multiple patterns
@@ -600,6 +712,223 @@ impl PatternDetector for ComprehensiveLLMDetector {
     }
 }
 
+/// How likely a finding of `pattern` is a real issue before triage.
+/// Broad heuristics score low; patterns outside this module score 1.0.
+pub fn baseline_confidence(pattern: &str) -> f64 {
+    match pattern {
+        "LLM_HARDCODED_CREDENTIALS"
+        | "LLM_SQL_INJECTION"
+        | "LLM_DB_ANTIPATTERN"
+        | "LLM_SECURITY_ANTIPATTERN"
+        | "LLM_PYTHON_ISSUES" => 0.8,
+        "LLM_XSS_INJECTION"
+        | "LLM_INSECURE_RANDOM"
+        | "LLM_CRYPTO_ANTIPATTERN"
+        | "LLM_RUST_MEMORY_SAFETY"
+        | "LLM_CONTEXT_CONFUSION"
+        | "LLM_FILESYSTEM_SECURITY" => 0.6,
+        "LLM_HALLUCINATED_API"
+        | "LLM_INCOMPLETE_API"
+        | "LLM_AI_MODEL_HALLUCINATION"
+        | "LLM_ERROR_HANDLING"
+        | "LLM_ERROR_HANDLING_MISTAKE"
+        | "LLM_INCORRECT_ASYNC" => 0.5,
+        p if p.starts_with("LLM_") => 0.3,
+        _ => 1.0,
+    }
+}
+
+/// Classification of a finding by the triage endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TriageVerdict {
+    TruePositive,
+    FalsePositive,
+    /// Not sent for triage, or the endpoint gave no answer
+    Unknown,
+}
+
+/// A finding annotated with how likely it is a real issue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriagedMatch {
+    #[serde(flatten)]
+    pub finding: Match,
+    /// Between 0.0 and 1.0
+    pub confidence: f64,
+    pub verdict: TriageVerdict,
+}
+
+impl TriagedMatch {
+    /// The finding with the verdict appended to its message, for outputs
+    /// that only carry plain matches
+    pub fn into_annotated_match(self) -> Match {
+        let mut finding = self.finding;
+        let note = match self.verdict {
+            TriageVerdict::TruePositive => "likely real",
+            TriageVerdict::FalsePositive => "likely false positive",
+            TriageVerdict::Unknown => return finding,
+        };
+        finding.message = format!(
+            "{} [triage: {}, confidence {:.2}]",
+            finding.message, note, self.confidence
+        );
+        finding
+    }
+}
+
+/// Result of [`LlmTriage::triage`]
+#[derive(Debug)]
+pub struct TriageOutcome {
+    pub matches: Vec<TriagedMatch>,
+    /// Why triage stopped early; the remaining findings keep their baseline confidence
+    pub error: Option<ScanError>,
+}
+
+/// Body posted to the triage endpoint for each finding
+#[derive(Debug, Serialize)]
+struct TriageRequest<'a> {
+    pattern: &'a str,
+    message: &'a str,
+    file_path: &'a str,
+    line_number: usize,
+    /// Lines around the finding, numbered
+    context: String,
+}
+
+/// Expected response from the triage endpoint
+#[derive(Debug, Deserialize)]
+struct TriageResponse {
+    verdict: TriageVerdict,
+    confidence: f64,
+}
+
+/// Optional post-processing pass that asks an LLM endpoint whether
+/// low-confidence findings are real.
+///
+/// Each finding below the threshold is POSTed as JSON with its surrounding
+/// lines; the endpoint answers `{"verdict": "false_positive", "confidence": 0.1}`.
+/// Triage never fails a scan: once the endpoint errors, the remaining
+/// findings keep their baseline confidence.
+#[derive(Debug, Clone)]
+pub struct LlmTriage {
+    endpoint: String,
+    api_key: Option<String>,
+    threshold: f64,
+    context_lines: usize,
+    timeout: Duration,
+}
+
+impl LlmTriage {
+    pub fn new(endpoint: impl Into<String>) -> ScanResult<Self> {
+        let endpoint = endpoint.into();
+        validate_webhook_url(&endpoint)
+            .map_err(|_| ScanError::triage(&endpoint, "URL must start with http:// or https://"))?;
+        Ok(Self {
+            endpoint,
+            api_key: None,
+            threshold: 0.5,
+            context_lines: 3,
+            timeout: Duration::from_secs(10),
+        })
+    }
+
+    /// Sent as `Authorization: Bearer <key>`
+    pub fn with_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key.filter(|k| !k.is_empty());
+        self
+    }
+
+    /// Findings with a baseline confidence at or below this are triaged
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn triage(&self, matches: Vec<Match>) -> TriageOutcome {
+        let mut files: HashMap<SharedStr, Option<Vec<String>>> = HashMap::new();
+        let mut error = None;
+        let matches = matches
+            .into_iter()
+            .map(|finding| {
+                let confidence = baseline_confidence(&finding.pattern);
+                if error.is_some() || confidence > self.threshold {
+                    return TriagedMatch {
+                        finding,
+                        confidence,
+                        verdict: TriageVerdict::Unknown,
+                    };
+                }
+                let lines = files.entry(finding.file_path.clone()).or_insert_with(|| {
+                    std::fs::read_to_string(&finding.file_path)
+                        .ok()
+                        .map(|content| content.lines().map(str::to_string).collect())
+                });
+                let context = lines
+                    .as_deref()
+                    .map(|lines| context_around(lines, finding.line_number, self.context_lines))
+                    .unwrap_or_default();
+                match self.classify(&finding, context) {
+                    Ok(response) => TriagedMatch {
+                        finding,
+                        confidence: response.confidence.clamp(0.0, 1.0),
+                        verdict: response.verdict,
+                    },
+                    Err(e) => {
+                        error = Some(e);
+                        TriagedMatch {
+                            finding,
+                            confidence,
+                            verdict: TriageVerdict::Unknown,
+                        }
+                    }
+                }
+            })
+            .collect();
+        TriageOutcome { matches, error }
+    }
+
+    fn classify(&self, finding: &Match, context: String) -> ScanResult<TriageResponse> {
+        let request = TriageRequest {
+            pattern: &finding.pattern,
+            message: &finding.message,
+            file_path: &finding.file_path,
+            line_number: finding.line_number,
+            context,
+        };
+        let body =
+            serde_json::to_vec(&request).map_err(|e| ScanError::triage(&self.endpoint, e))?;
+        let api_key: Vec<String> = self
+            .api_key
+            .iter()
+            .map(|key| format!("Authorization: Bearer {}", key))
+            .collect();
+        let response = post_json(&self.endpoint, &[], &api_key, &body, self.timeout)
+            .map_err(|e| ScanError::triage(&self.endpoint, e))?;
+        serde_json::from_slice(&response)
+            .map_err(|e| ScanError::triage(&self.endpoint, format!("unexpected response: {}", e)))
+    }
+}
+
+/// `line_number` (1-based) and up to `radius` lines either side, each
+/// prefixed with its number
+fn context_around(lines: &[String], line_number: usize, radius: usize) -> String {
+    let start = line_number.saturating_sub(radius + 1);
+    let end = (line_number + radius).min(lines.len());
+    lines
+        .iter()
+        .enumerate()
+        .take(end)
+        .skip(start)
+        .map(|(i, line)| format!("{:>4} | {}", i + 1, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -756,4 +1085,68 @@ mod tests {
         assert!(!python_detector.detect(content, &py_path).is_empty());
         assert_eq!(python_detector.detect(content, &js_path).len(), 0);
     }
+
+    #[test]
+    fn test_triage_never_blocks_when_endpoint_is_down() {
+        let finding = |pattern: &str| Match {
+            file_path: "missing.js".into(),
+            line_number: 1,
+            column: 1,
+            pattern: pattern.into(),
+            message: "x".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
+        };
+        let triage = LlmTriage::new("http://127.0.0.1:1/triage")
+            .unwrap()
+            .with_timeout(Duration::from_secs(1));
+        let outcome = triage.triage(vec![
+            finding("TODO"),
+            finding("LLM_JS_ISSUES"),
+            finding("LLM_CONFIG_ANTIPATTERN"),
+        ]);
+
+        assert!(matches!(outcome.error, Some(ScanError::Triage { .. })));
+        assert_eq!(outcome.matches.len(), 3);
+        assert!(outcome
+            .matches
+            .iter()
+            .all(|m| m.verdict == TriageVerdict::Unknown));
+        assert_eq!(outcome.matches[0].confidence, 1.0);
+        assert_eq!(outcome.matches[1].confidence, 0.3);
+
+        assert!(LlmTriage::new("localhost:8080").is_err());
+    }
+
+    #[test]
+    fn test_triaged_match_annotation_and_context() {
+        let triaged = TriagedMatch {
+            finding: Match {
+                file_path: "a.js".into(),
+                line_number: 2,
+                column: 1,
+                pattern: "LLM_JS_ISSUES".into(),
+                message: "parseInt without radix".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
+            },
+            confidence: 0.1,
+            verdict: TriageVerdict::FalsePositive,
+        };
+        let json = serde_json::to_value(&triaged).unwrap();
+        assert_eq!(json["pattern"], "LLM_JS_ISSUES");
+        assert_eq!(json["verdict"], "false_positive");
+        assert_eq!(
+            triaged.into_annotated_match().message,
+            "parseInt without radix [triage: likely false positive, confidence 0.10]"
+        );
+
+        let lines: Vec<String> = ["a", "b", "c", "d"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(context_around(&lines, 2, 1), "   1 | a\n   2 | b\n   3 | c");
+        assert_eq!(context_around(&lines, 4, 1), "   3 | c\n   4 | d");
+    }
 }
@@ -0,0 +1,298 @@
+use crate::{Match, Severity, SharedStr};
+use std::collections::{BTreeMap, HashMap};
+
+/// Pattern name of the marker that stands in for findings dropped by [`MatchLimits`]
+pub const TRUNCATED_PATTERN: &str = "TRUNCATED";
+
+/// Pattern name of the marker that stands in for findings dropped by [`SeveritySampling`]
+pub const SAMPLED_PATTERN: &str = "SAMPLED";
+
+/// Caps on the number of findings kept for a single file, protecting scans
+/// against generated or minified files with huge numbers of hits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatchLimits {
+    /// Maximum findings kept per file, across all patterns
+    pub max_per_file: Option<usize>,
+    /// Maximum findings kept per pattern within a file
+    pub max_per_pattern: Option<usize>,
+}
+
+impl MatchLimits {
+    pub fn new(max_per_file: Option<usize>, max_per_pattern: Option<usize>) -> Self {
+        Self {
+            max_per_file,
+            max_per_pattern,
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_per_file.is_none() && self.max_per_pattern.is_none()
+    }
+
+    /// Apply the limits to the matches of a single file. Dropped findings are
+    /// replaced by one `TRUNCATED` marker per pattern, e.g.
+    /// "+137 more CLONE findings in this file", placed at the first dropped line.
+    pub fn apply(&self, matches: Vec<Match>) -> Vec<Match> {
+        if self.is_unlimited() {
+            return matches;
+        }
+
+        let mut kept =
+            Vec::with_capacity(matches.len().min(self.max_per_file.unwrap_or(usize::MAX)));
+        let mut per_pattern: HashMap<SharedStr, usize> = HashMap::new();
+        // pattern -> (dropped count, first dropped match)
+        let mut dropped: BTreeMap<SharedStr, (usize, Match)> = BTreeMap::new();
+
+        for m in matches {
+            let pattern_count = per_pattern.entry(m.pattern.clone()).or_insert(0);
+            let over_pattern = self
+                .max_per_pattern
+                .is_some_and(|max| *pattern_count >= max);
+            let over_file = self.max_per_file.is_some_and(|max| kept.len() >= max);
+            if over_pattern || over_file {
+                dropped
+                    .entry(m.pattern.clone())
+                    .and_modify(|(count, _)| *count += 1)
+                    .or_insert((1, m));
+            } else {
+                *pattern_count += 1;
+                kept.push(m);
+            }
+        }
+
+        kept.extend(dropped.into_iter().map(|(pattern, (count, first))| Match {
+            file_path: first.file_path,
+            line_number: first.line_number,
+            column: first.column,
+            pattern: SharedStr::interned(TRUNCATED_PATTERN),
+            message: format!("+{} more {} findings in this file", count, pattern),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
+        }));
+        kept
+    }
+
+    /// Apply the limits to matches from many files, grouping by file path
+    /// and keeping files in the order they first appear
+    pub fn apply_per_file(&self, matches: Vec<Match>) -> Vec<Match> {
+        if self.is_unlimited() {
+            return matches;
+        }
+
+        let mut order: Vec<SharedStr> = Vec::new();
+        let mut by_file: HashMap<SharedStr, Vec<Match>> = HashMap::new();
+        for m in matches {
+            if !by_file.contains_key(&m.file_path) {
+                order.push(m.file_path.clone());
+            }
+            by_file.entry(m.file_path.clone()).or_default().push(m);
+        }
+
+        order
+            .into_iter()
+            .filter_map(|file| by_file.remove(&file))
+            .flat_map(|file_matches| self.apply(file_matches))
+            .collect()
+    }
+}
+
+/// Thins out huge result sets, e.g. on the first scan of a large monorepo.
+/// Once a scan has more than `threshold` findings, Critical and High findings
+/// are all kept while Medium, Low and Info findings are sampled down to
+/// `per_file` per file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeveritySampling {
+    pub threshold: usize,
+    pub per_file: usize,
+}
+
+impl SeveritySampling {
+    pub fn new(threshold: usize, per_file: usize) -> Self {
+        Self {
+            threshold,
+            per_file,
+        }
+    }
+
+    /// Sample `matches`, looking up the severity of findings that don't
+    /// carry their own with `severity`.
+    /// Kept findings are spread evenly over each file; dropped ones are
+    /// counted in one `SAMPLED` marker per file and pattern, e.g.
+    /// "+40 more TODO findings sampled out of this file".
+    pub fn apply(&self, matches: Vec<Match>, severity: impl Fn(&str) -> Severity) -> Vec<Match> {
+        if matches.len() <= self.threshold {
+            return matches;
+        }
+
+        let mut order: Vec<SharedStr> = Vec::new();
+        let mut sampleable: HashMap<SharedStr, Vec<Match>> = HashMap::new();
+        let mut kept = Vec::new();
+        for m in matches {
+            let finding_severity = m.severity.clone().unwrap_or_else(|| severity(&m.pattern));
+            if matches!(finding_severity, Severity::Critical | Severity::High) {
+                kept.push(m);
+                continue;
+            }
+            if !sampleable.contains_key(&m.file_path) {
+                order.push(m.file_path.clone());
+            }
+            sampleable.entry(m.file_path.clone()).or_default().push(m);
+        }
+
+        for file in order {
+            let file_matches = sampleable.remove(&file).unwrap_or_default();
+            let total = file_matches.len();
+            let keep = self.per_file.min(total);
+            // Indices i * total / keep spread the sample over the whole file
+            let mut next_kept = (0..keep).map(|i| i * total / keep).peekable();
+            let mut dropped: BTreeMap<SharedStr, (usize, Match)> = BTreeMap::new();
+            for (index, m) in file_matches.into_iter().enumerate() {
+                if next_kept.peek() == Some(&index) {
+                    next_kept.next();
+                    kept.push(m);
+                } else {
+                    dropped
+                        .entry(m.pattern.clone())
+                        .and_modify(|(count, _)| *count += 1)
+                        .or_insert((1, m));
+                }
+            }
+            kept.extend(dropped.into_iter().map(|(pattern, (count, first))| Match {
+                file_path: first.file_path,
+                line_number: first.line_number,
+                column: first.column,
+                pattern: SharedStr::interned(SAMPLED_PATTERN),
+                message: format!(
+                    "+{} more {} findings sampled out of this file",
+                    count, pattern
+                ),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
+            }));
+        }
+        kept
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn m(file: &str, line: usize, pattern: &str) -> Match {
+        Match::new(file, line, pattern, format!("{}: x", pattern))
+    }
+
+    fn summary(matches: &[Match]) -> Vec<(usize, &str, &str)> {
+        matches
+            .iter()
+            .map(|m| (m.line_number, m.pattern.as_str(), m.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_unlimited_keeps_everything() {
+        let matches: Vec<Match> = (1..=5).map(|line| m("a.rs", line, "CLONE")).collect();
+        assert_eq!(MatchLimits::default().apply(matches.clone()), matches);
+    }
+
+    #[test]
+    fn test_max_per_pattern() {
+        let mut matches: Vec<Match> = (1..=5).map(|line| m("a.rs", line, "CLONE")).collect();
+        matches.push(m("a.rs", 9, "TODO"));
+
+        let limited = MatchLimits::new(None, Some(2)).apply(matches);
+        assert_eq!(
+            summary(&limited),
+            vec![
+                (1, "CLONE", "CLONE: x"),
+                (2, "CLONE", "CLONE: x"),
+                (9, "TODO", "TODO: x"),
+                (3, TRUNCATED_PATTERN, "+3 more CLONE findings in this file"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_max_per_file() {
+        let matches = vec![
+            m("a.rs", 1, "TODO"),
+            m("a.rs", 2, "CLONE"),
+            m("a.rs", 3, "CLONE"),
+            m("a.rs", 4, "FIXME"),
+        ];
+
+        let limited = MatchLimits::new(Some(2), None).apply(matches);
+        assert_eq!(
+            summary(&limited),
+            vec![
+                (1, "TODO", "TODO: x"),
+                (2, "CLONE", "CLONE: x"),
+                (3, TRUNCATED_PATTERN, "+1 more CLONE findings in this file"),
+                (4, TRUNCATED_PATTERN, "+1 more FIXME findings in this file"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_per_file() {
+        let matches = vec![
+            m("a.rs", 1, "TODO"),
+            m("b.rs", 1, "TODO"),
+            m("a.rs", 2, "TODO"),
+            m("b.rs", 2, "TODO"),
+        ];
+
+        let limited = MatchLimits::new(Some(1), None).apply_per_file(matches);
+        let files: Vec<(&str, &str)> = limited
+            .iter()
+            .map(|m| (m.file_path.as_str(), m.pattern.as_str()))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                ("a.rs", "TODO"),
+                ("a.rs", TRUNCATED_PATTERN),
+                ("b.rs", "TODO"),
+                ("b.rs", TRUNCATED_PATTERN),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_severity_sampling() {
+        let severity = |pattern: &str| match pattern {
+            "SECRET" => Severity::Critical,
+            _ => Severity::Low,
+        };
+        let mut matches: Vec<Match> = (1..=6).map(|line| m("a.rs", line, "TODO")).collect();
+        matches.push(m("a.rs", 7, "SECRET"));
+        matches.push(m("b.rs", 1, "TODO"));
+
+        // At or below the threshold nothing is sampled
+        let sampling = SeveritySampling::new(8, 2);
+        assert_eq!(sampling.apply(matches.clone(), severity), matches);
+
+        let sampled = SeveritySampling::new(5, 2).apply(matches, severity);
+        assert_eq!(
+            sampled
+                .iter()
+                .map(|m| (m.file_path.as_str(), m.line_number, m.pattern.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("a.rs", 7, "SECRET"),
+                ("a.rs", 1, "TODO"),
+                ("a.rs", 4, "TODO"),
+                ("a.rs", 2, SAMPLED_PATTERN),
+                ("b.rs", 1, "TODO"),
+            ]
+        );
+        assert_eq!(
+            sampled[3].message,
+            "+4 more TODO findings sampled out of this file"
+        );
+    }
+}
@@ -0,0 +1,315 @@
+//! Review checks for SQL migrations: statements that drop data without a
+//! guard, index builds that lock a populated table and updates of every row.
+//! Migrations are found by path glob, set in the `migrations` config section.
+
+use crate::detectors::build_glob_set;
+use crate::errors::ScanResult;
+use crate::line_index::LineIndex;
+use crate::{Match, PatternDetector, Severity, SharedStr};
+use globset::GlobSet;
+use lazy_static::lazy_static;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Category reported by [`MigrationRiskDetector`]
+pub const MIGRATION_CATEGORY: &str = "Migration";
+
+/// Where the common migration tools keep their SQL files
+pub const DEFAULT_MIGRATION_GLOBS: &[&str] = &[
+    "**/migrations/**/*.sql",
+    "**/migrate/**/*.sql",
+    "**/migration/**/*.sql",
+    "**/flyway/**/*.sql",
+    "**/liquibase/**/*.sql",
+    "**/*.migration.sql",
+    "**/*.up.sql",
+];
+
+lazy_static! {
+    static ref DROP_TABLE_REGEX: Regex =
+        Regex::new(r"(?i)^drop\s+table\s+(?P<guard>if\s+exists\b)?").unwrap();
+
+    static ref DROP_COLUMN_REGEX: Regex = Regex::new(
+        r"(?is)^alter\s+table\b.*?\bdrop\s+column\s+(?P<guard>if\s+exists\b)?"
+    ).unwrap();
+
+    static ref CREATE_INDEX_REGEX: Regex = Regex::new(
+        r#"(?is)^create\s+(?:unique\s+)?index\s+(?P<concurrently>concurrently\b)?.*?\bon\s+(?:only\s+)?(?P<table>[\w."]+)"#
+    ).unwrap();
+
+    static ref CREATE_TABLE_REGEX: Regex = Regex::new(
+        r#"(?i)^create\s+(?:(?:global\s+|local\s+)?temp(?:orary)?\s+|unlogged\s+)?table\s+(?:if\s+not\s+exists\s+)?(?P<table>[\w."]+)"#
+    ).unwrap();
+
+    static ref UPDATE_REGEX: Regex = Regex::new(r"(?i)^update\s").unwrap();
+
+    static ref WHERE_REGEX: Regex = Regex::new(r"(?i)\bwhere\b").unwrap();
+}
+
+/// Settings of the `MIGRATION_*` checks, the `migrations` config section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct MigrationConfig {
+    /// Globs of the SQL files checked as migrations; setting this replaces
+    /// the defaults
+    pub paths: Vec<String>,
+    /// Tables big enough that a plain `CREATE INDEX` blocks writes for too
+    /// long. When empty, every table the migration does not create counts.
+    pub large_tables: Vec<String>,
+}
+
+impl Default for MigrationConfig {
+    fn default() -> Self {
+        Self {
+            paths: DEFAULT_MIGRATION_GLOBS
+                .iter()
+                .map(|glob| glob.to_string())
+                .collect(),
+            large_tables: Vec::new(),
+        }
+    }
+}
+
+/// Detector for risky statements in SQL migrations, all rated High:
+/// - `MIGRATION_DROP`: `DROP TABLE` or `DROP COLUMN` without `IF EXISTS`
+/// - `MIGRATION_INDEX_NOT_CONCURRENT`: `CREATE INDEX` without `CONCURRENTLY`
+///   on a table the migration did not create, which locks it for writes
+/// - `MIGRATION_UPDATE_WITHOUT_WHERE`: an `UPDATE` of every row
+pub struct MigrationRiskDetector {
+    paths: GlobSet,
+    large_tables: HashSet<String>,
+    fingerprint: String,
+}
+
+impl MigrationRiskDetector {
+    pub fn new(config: &MigrationConfig) -> ScanResult<Self> {
+        Ok(Self {
+            paths: build_glob_set(&config.paths)?,
+            fingerprint: serde_json::to_string(config).unwrap_or_default(),
+            large_tables: config
+                .large_tables
+                .iter()
+                .map(|table| table_name(table))
+                .collect(),
+        })
+    }
+
+    /// Whether an index on `table` should be built concurrently
+    fn is_large(&self, table: &str) -> bool {
+        self.large_tables.is_empty() || self.large_tables.contains(table)
+    }
+}
+
+impl Default for MigrationRiskDetector {
+    fn default() -> Self {
+        Self::new(&MigrationConfig::default()).expect("default migration globs are valid")
+    }
+}
+
+impl PatternDetector for MigrationRiskDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let code = code_only(content);
+        let lines = LineIndex::new(content);
+        let shared_path = SharedStr::from_path(file_path);
+        let mut created = HashSet::new();
+        let mut matches = Vec::new();
+        let mut offset = 0;
+        for statement in code.split(';') {
+            let start = offset + statement.len() - statement.trim_start().len();
+            offset += statement.len() + 1;
+            let statement = statement.trim();
+            if statement.is_empty() {
+                continue;
+            }
+
+            let finding = if let Some(caps) = CREATE_TABLE_REGEX.captures(statement) {
+                created.insert(table_name(&caps["table"]));
+                None
+            } else if let Some(caps) = DROP_TABLE_REGEX
+                .captures(statement)
+                .or_else(|| DROP_COLUMN_REGEX.captures(statement))
+            {
+                caps.name("guard")
+                    .is_none()
+                    .then_some(("MIGRATION_DROP", "drops data without IF EXISTS"))
+            } else if let Some(caps) = CREATE_INDEX_REGEX.captures(statement) {
+                let table = table_name(&caps["table"]);
+                (caps.name("concurrently").is_none()
+                    && !created.contains(&table)
+                    && self.is_large(&table))
+                .then_some((
+                    "MIGRATION_INDEX_NOT_CONCURRENT",
+                    "locks the table for writes; use CREATE INDEX CONCURRENTLY",
+                ))
+            } else if UPDATE_REGEX.is_match(statement) && !WHERE_REGEX.is_match(statement) {
+                Some(("MIGRATION_UPDATE_WITHOUT_WHERE", "updates every row"))
+            } else {
+                None
+            };
+
+            if let Some((pattern, problem)) = finding {
+                let (line_number, column) = lines.position(content, start);
+                matches.push(Match {
+                    file_path: shared_path.clone(),
+                    line_number,
+                    column,
+                    pattern: SharedStr::interned(pattern),
+                    message: format!(
+                        "{}: {} {}",
+                        pattern,
+                        lines
+                            .line(content, line_number)
+                            .trim()
+                            .trim_end_matches(';'),
+                        problem
+                    ),
+                    severity: Some(Severity::High),
+                    category: Some(MIGRATION_CATEGORY.to_string()),
+                    end: None,
+                    author: None,
+                });
+            }
+        }
+        matches
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        self.paths.is_match(file_path)
+    }
+
+    fn config_fingerprint(&self) -> String {
+        self.fingerprint.clone()
+    }
+}
+
+/// `name` without quotes or schema, lowercased, as tables are compared
+fn table_name(name: &str) -> String {
+    let name = name.rsplit('.').next().unwrap_or(name);
+    name.trim_matches('"').to_lowercase()
+}
+
+/// `sql` with comments, string literals and dollar-quoted bodies replaced by
+/// spaces, keeping byte offsets and newlines, so `;` and keywords in them
+/// are not mistaken for code
+fn code_only(sql: &str) -> String {
+    let mut code = sql.as_bytes().to_vec();
+    let mut i = 0;
+    while i < sql.len() {
+        let rest = &sql[i..];
+        let end = if rest.starts_with("--") {
+            rest.find('\n').map_or(sql.len(), |n| i + n)
+        } else if let Some(body) = rest.strip_prefix("/*") {
+            body.find("*/").map_or(sql.len(), |n| i + n + 4)
+        } else if let Some(body) = rest.strip_prefix('\'') {
+            // `''` escapes a quote, which reads as two adjacent strings
+            body.find('\'').map_or(sql.len(), |n| i + n + 2)
+        } else if let Some(tag) = dollar_tag(rest) {
+            rest[tag.len()..]
+                .find(tag)
+                .map_or(sql.len(), |n| i + n + 2 * tag.len())
+        } else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+        for b in &mut code[i..end] {
+            if *b != b'\n' {
+                *b = b' ';
+            }
+        }
+        i = end;
+    }
+    String::from_utf8(code).expect("only whole characters are blanked")
+}
+
+/// The `$tag$` opening a dollar-quoted string at the start of `text`
+fn dollar_tag(text: &str) -> Option<&str> {
+    let body = text.strip_prefix('$')?;
+    let len = body.find('$')?;
+    body[..len]
+        .bytes()
+        .all(|b| b == b'_' || b.is_ascii_alphanumeric())
+        .then(|| &text[..len + 2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(detector: &MigrationRiskDetector, content: &str, path: &str) -> Vec<(usize, String)> {
+        detector
+            .detect(content, Path::new(path))
+            .into_iter()
+            .map(|m| (m.line_number, m.pattern.to_string()))
+            .collect()
+    }
+
+    const MIGRATION: &str = r#"-- DROP TABLE legacy; is done below
+CREATE TABLE invoices (id bigint PRIMARY KEY, customer_id bigint);
+CREATE INDEX invoices_customer ON invoices (customer_id);
+CREATE INDEX orders_created ON public."Orders" (created_at);
+CREATE INDEX CONCURRENTLY orders_status ON orders (status);
+DROP TABLE IF EXISTS legacy_orders;
+DROP TABLE legacy;
+ALTER TABLE orders
+    DROP COLUMN notes;
+ALTER TABLE orders ALTER COLUMN total DROP DEFAULT;
+UPDATE orders SET note = 'no WHERE; here' WHERE id = 1;
+UPDATE orders SET archived = false;
+CREATE FUNCTION touch() RETURNS trigger AS $$
+BEGIN
+    UPDATE audit SET seen = now();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+"#;
+
+    #[test]
+    fn test_migration_risk_detector() {
+        let detector = MigrationRiskDetector::default();
+        assert_eq!(
+            found(&detector, MIGRATION, "db/migrations/0042_orders.sql"),
+            vec![
+                (4, "MIGRATION_INDEX_NOT_CONCURRENT".to_string()),
+                (7, "MIGRATION_DROP".to_string()),
+                (8, "MIGRATION_DROP".to_string()),
+                (12, "MIGRATION_UPDATE_WITHOUT_WHERE".to_string()),
+            ]
+        );
+        let matches = detector.detect(MIGRATION, Path::new("db/migrations/0042_orders.sql"));
+        assert_eq!(
+            matches[1].message,
+            "MIGRATION_DROP: DROP TABLE legacy drops data without IF EXISTS"
+        );
+        assert_eq!(matches[1].severity, Some(Severity::High));
+        assert_eq!(matches[1].category.as_deref(), Some("Migration"));
+
+        // Only migrations are checked
+        assert!(found(&detector, MIGRATION, "db/seed.sql").is_empty());
+    }
+
+    #[test]
+    fn test_migration_config() {
+        let detector = MigrationRiskDetector::new(&MigrationConfig {
+            paths: vec!["schema/*.sql".to_string()],
+            large_tables: vec!["Events".to_string()],
+        })
+        .unwrap();
+        let sql = "CREATE INDEX a ON users (email);\nCREATE INDEX b ON analytics.events (at);\n";
+        assert_eq!(
+            found(&detector, sql, "schema/V2__indexes.sql"),
+            vec![(2, "MIGRATION_INDEX_NOT_CONCURRENT".to_string())]
+        );
+        assert!(found(&detector, sql, "db/migrations/V2.sql").is_empty());
+        assert!(MigrationRiskDetector::new(&MigrationConfig {
+            paths: vec!["[invalid".to_string()],
+            large_tables: Vec::new(),
+        })
+        .is_err());
+    }
+}
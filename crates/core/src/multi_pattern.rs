@@ -0,0 +1,105 @@
+//! Single-pass prefilter that finds which regex detectors can match a file
+
+use crate::regex_cache::cached_regex_set;
+use crate::PatternDetector;
+use regex::RegexSet;
+
+/// Combines the [`PatternDetector::prefilter`] regexes of a detector list
+/// into one [`RegexSet`], so a file is searched once to find the detectors
+/// that can match it instead of once per detector.
+pub struct PatternPrefilter {
+    set: Option<RegexSet>,
+    /// Detector index of each pattern in `set`
+    detector_indices: Vec<usize>,
+    detector_count: usize,
+}
+
+impl PatternPrefilter {
+    pub fn new(detectors: &[Box<dyn PatternDetector>]) -> Self {
+        let (detector_indices, patterns): (Vec<usize>, Vec<String>) = detectors
+            .iter()
+            .enumerate()
+            .filter_map(|(index, detector)| {
+                // Detectors match line by line; multi-line CRLF mode makes `^`
+                // and `$` behave the same over the whole file
+                let regex = detector.prefilter()?;
+                Some((index, format!("(?mR:{})", regex.as_str())))
+            })
+            .unzip();
+
+        // Without a set every detector runs, as before
+        let set = if patterns.is_empty() {
+            None
+        } else {
+            cached_regex_set(&patterns).ok()
+        };
+        Self {
+            set,
+            detector_indices,
+            detector_count: detectors.len(),
+        }
+    }
+
+    /// Whether each detector can match `content`. Detectors without a
+    /// prefilter always can.
+    pub fn candidates(&self, content: &str) -> Vec<bool> {
+        let Some(set) = &self.set else {
+            return vec![true; self.detector_count];
+        };
+        let mut candidates = vec![true; self.detector_count];
+        for &index in &self.detector_indices {
+            candidates[index] = false;
+        }
+        for pattern in set.matches(content).iter() {
+            candidates[self.detector_indices[pattern]] = true;
+        }
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detectors::*;
+    use crate::llm_detectors::HallucinatedApiDetector;
+    use std::path::Path;
+
+    #[test]
+    fn test_candidates() {
+        let detectors: Vec<Box<dyn PatternDetector>> = vec![
+            Box::new(TodoDetector),
+            Box::new(FixmeDetector),
+            Box::new(HallucinatedApiDetector),
+            Box::new(UnwrapDetector),
+        ];
+        let prefilter = PatternPrefilter::new(&detectors);
+
+        // Detectors without a prefilter always run
+        assert_eq!(
+            prefilter.candidates("// TODO: x\nlet y = z.unwrap();\n"),
+            vec![true, false, true, true]
+        );
+        assert_eq!(
+            prefilter.candidates("fn main() {}\n"),
+            vec![false, false, true, false]
+        );
+    }
+
+    #[test]
+    fn test_prefilter_never_skips_a_detector_that_matches() {
+        let detectors = crate::DetectorFactory::create_comprehensive_detectors();
+        let prefilter = PatternPrefilter::new(&detectors);
+        let content = "// todo later\r\nlet a = b.clone(); // FIXME\r\nconsole.log(x);\r\n\
+                       unsafe { y }\r\nif DEBUG { print(\"staging\") }\r\nphase 2 // dead code\n";
+
+        for path in ["lib.rs", "app.js"] {
+            let path = Path::new(path);
+            let candidates = prefilter.candidates(content);
+            for (detector, candidate) in detectors.iter().zip(candidates) {
+                if !detector.detect(content, path).is_empty() {
+                    assert!(candidate, "{} was skipped", detector.name());
+                }
+            }
+        }
+    }
+}
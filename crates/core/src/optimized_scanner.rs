@@ -1,17 +1,20 @@
-use crate::{Match, PatternDetector};
-use anyhow::Result;
+use crate::{
+    content_hash, read_stable, BatchController, BatchSizing, CacheSaveStats, CoverageReport,
+    LineIndex, Match, MatchLimits, PatternDetector, PatternPrefilter, ScanCache, ScanError,
+    ScanResult, SkipLog, SkippedFile, WalkOptions, WorkerMetrics,
+};
 use dashmap::DashMap;
-use ignore::WalkBuilder;
-use memmap2::Mmap;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Read;
-use std::path::Path;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::time::Instant;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 /// Performance metrics for scanning operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanMetrics {
     pub total_files_scanned: usize,
     pub total_lines_processed: usize,
@@ -19,24 +22,170 @@ pub struct ScanMetrics {
     pub scan_duration_ms: u64,
     pub cache_hits: usize,
     pub cache_misses: usize,
+    /// Persisted cache results dropped because the detector configuration
+    /// changed, see [`ScanCache::invalidated`]
+    #[serde(default)]
+    pub cache_invalidated: usize,
+    /// Generated or vendored files skipped by the walker
+    pub generated_files_skipped: usize,
+    /// Files that were found but could not be scanned, sorted by path
+    pub skipped_files: Vec<SkippedFile>,
+    /// Per-detector timings, slowest first. Only populated when detector
+    /// profiling is enabled.
+    pub detector_timings: Vec<DetectorTiming>,
+    /// Per-worker totals of a distributed scan, sorted by worker ID
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub worker_metrics: Vec<WorkerMetrics>,
+    /// Measured costs and the parallelism they chose, for scanners that
+    /// size their work with a [`BatchController`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_sizing: Option<BatchSizing>,
+    /// Files, lines, matches and time per file extension, slowest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub file_types: Vec<FileTypeMetrics>,
+}
+
+/// Time spent and results produced by a single detector during a scan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetectorTiming {
+    pub detector: String,
+    pub duration_us: u64,
+    pub matches_found: usize,
+    pub files_scanned: usize,
+    pub files_with_matches: usize,
+}
+
+/// What the files of one extension cost a scan
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileTypeMetrics {
+    /// Lowercase extension without the dot, empty for files without one
+    pub extension: String,
+    /// Language of the extension, see [`crate::inventory::language_of`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    pub files: usize,
+    /// Lines of the files read; files whose results came from a cache count
+    /// no lines
+    pub lines: usize,
+    pub matches: usize,
+    /// Time spent reading and scanning the files
+    pub duration_us: u64,
+}
+
+impl FileTypeMetrics {
+    /// Share of the time of all `file_types` spent on this one
+    pub fn time_share(&self, file_types: &[FileTypeMetrics]) -> f64 {
+        let total: u64 = file_types.iter().map(|t| t.duration_us).sum();
+        if total == 0 {
+            0.0
+        } else {
+            self.duration_us as f64 / total as f64
+        }
+    }
+}
+
+/// Per-extension counters shared across the parallel file workers
+#[derive(Default)]
+pub(crate) struct FileTypeCounters {
+    by_extension: DashMap<String, FileTypeMetrics>,
+}
+
+impl FileTypeCounters {
+    /// Records a scanned file of `path`
+    pub(crate) fn record(&self, path: &Path, lines: usize, matches: usize, elapsed: Duration) {
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        let mut metrics = self
+            .by_extension
+            .entry(extension.clone())
+            .or_insert_with(|| FileTypeMetrics {
+                extension,
+                language: crate::inventory::language_of(path).map(str::to_string),
+                ..FileTypeMetrics::default()
+            });
+        metrics.files += 1;
+        metrics.lines += lines;
+        metrics.matches += matches;
+        metrics.duration_us += elapsed.as_micros() as u64;
+    }
+
+    pub(crate) fn clear(&self) {
+        self.by_extension.clear();
+    }
+
+    /// The recorded extensions, slowest first
+    pub(crate) fn metrics(&self) -> Vec<FileTypeMetrics> {
+        let mut metrics: Vec<FileTypeMetrics> = self
+            .by_extension
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        metrics.sort_by(|a, b| {
+            b.duration_us
+                .cmp(&a.duration_us)
+                .then(b.files.cmp(&a.files))
+                .then_with(|| a.extension.cmp(&b.extension))
+        });
+        metrics
+    }
+}
+
+/// Per-detector counters shared across the parallel file workers
+#[derive(Default)]
+struct DetectorCounters {
+    duration_ns: AtomicU64,
+    matches_found: AtomicUsize,
+    files_scanned: AtomicUsize,
+    files_with_matches: AtomicUsize,
+}
+
+impl DetectorCounters {
+    fn record(&self, elapsed: std::time::Duration, matches: usize) {
+        self.duration_ns
+            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+        self.matches_found.fetch_add(matches, Ordering::Relaxed);
+        self.files_scanned.fetch_add(1, Ordering::Relaxed);
+        if matches > 0 {
+            self.files_with_matches.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Whole milliseconds, rounding up so a short scan never reports 0ms
+fn duration_ms(duration: Duration) -> u64 {
+    (duration.as_micros() as u64 + 999) / 1000
 }
 
 /// Optimized scanner with performance enhancements
 pub struct OptimizedScanner {
     detectors: Vec<Box<dyn PatternDetector>>,
+    prefilter: PatternPrefilter,
     cache: DashMap<String, Vec<Match>>,
     file_cache: DashMap<String, (u64, Vec<Match>)>, // (modified_time, matches)
     max_cache_size: usize,
+    scan_cache: Option<ScanCache>,
+    profile_detectors: bool,
+    match_limits: MatchLimits,
+    walk_options: WalkOptions,
+    batching: BatchController,
 }
 
 impl OptimizedScanner {
     /// Creates a new optimized scanner with the given pattern detectors
     pub fn new(detectors: Vec<Box<dyn PatternDetector>>) -> Self {
         Self {
+            prefilter: PatternPrefilter::new(&detectors),
             detectors,
             cache: DashMap::new(),
             file_cache: DashMap::new(),
             max_cache_size: 1000, // Maximum number of cached file results
+            scan_cache: None,
+            profile_detectors: false,
+            match_limits: MatchLimits::default(),
+            walk_options: WalkOptions::default(),
+            batching: BatchController::default(),
         }
     }
 
@@ -46,90 +195,76 @@ impl OptimizedScanner {
         self
     }
 
-    /// Check if a file should be scanned based on size and type
-    /// This optimizes performance by only running detectors that are likely to match
-    fn get_relevant_detectors(&self, path: &Path) -> Vec<&dyn PatternDetector> {
-        let ext = path.extension().and_then(|e| e.to_str());
-
-        match ext {
-            Some("rs") => {
-                // For Rust files, prioritize Rust-specific detectors but include general ones
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-            Some("js" | "ts" | "jsx" | "tsx" | "vue" | "svelte") => {
-                // For JS/TS files, include all detectors
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-            Some("py" | "pyw" | "pyx") => {
-                // For Python files, include all detectors
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-            Some("java") => {
-                // For Java files, include all detectors
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-            Some("c" | "cpp" | "cc" | "cxx" | "h" | "hpp") => {
-                // For C/C++ files, include all detectors
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-            Some("go") => {
-                // For Go files, include all detectors
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-            Some("php") => {
-                // For PHP files, include all detectors
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-            Some("rb") => {
-                // For Ruby files, include all detectors
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-            Some("sh" | "bash" | "zsh") => {
-                // For shell scripts, include all detectors
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-            Some("json" | "yaml" | "yml" | "toml" | "xml" | "ini" | "cfg") => {
-                // For config files, include general detectors (TODO, FIXME, etc.)
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-            _ => {
-                // For unknown extensions, include all detectors
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-        }
+    /// Key cached file results on a hash of their content instead of their
+    /// modification time, using `cache` loaded from a previous run. Call
+    /// [`Self::save_scan_cache`] after scanning to persist it.
+    pub fn with_scan_cache(mut self, cache: ScanCache) -> Self {
+        self.scan_cache = Some(cache);
+        self
     }
 
-    /// Reads file content with memory mapping for large files
-    fn read_file_content(&self, path: &Path) -> Result<String> {
-        let metadata = std::fs::metadata(path)?;
+    /// Persist the content-keyed cache set with [`Self::with_scan_cache`], if any
+    pub fn save_scan_cache(&self) -> Option<ScanResult<CacheSaveStats>> {
+        self.scan_cache.as_ref().map(ScanCache::save)
+    }
 
-        if metadata.len() > 1024 * 1024 {
-            // Use memory mapping for large files
-            let file = File::open(path)?;
-            let mmap = unsafe { Mmap::map(&file)? };
-            let content = std::str::from_utf8(&mmap)?;
-            Ok(content.to_string())
-        } else {
-            // Regular reading for smaller files
-            Ok(std::fs::read_to_string(path)?)
-        }
+    /// Record time spent, matches and files per detector in
+    /// [`ScanMetrics::detector_timings`]. Detectors run sequentially within
+    /// each file while profiling so their timings don't overlap.
+    pub fn with_detector_profiling(mut self, enabled: bool) -> Self {
+        self.profile_detectors = enabled;
+        self
+    }
+
+    /// Cap the findings kept per file and per pattern, see [`MatchLimits`].
+    pub fn with_match_limits(mut self, limits: MatchLimits) -> Self {
+        self.match_limits = limits;
+        self
+    }
+
+    /// Configure how the directory tree is walked, see [`WalkOptions`].
+    pub fn with_walk_options(mut self, options: WalkOptions) -> Self {
+        self.walk_options = options;
+        self
+    }
+
+    /// Detectors that can match `content`, found with one [`PatternPrefilter`] pass
+    fn get_relevant_detectors(&self, content: &str) -> Vec<&dyn PatternDetector> {
+        let candidates = self.prefilter.candidates(content);
+        self.detectors
+            .iter()
+            .zip(candidates)
+            .filter(|(_, candidate)| *candidate)
+            .map(|(detector, _)| detector.as_ref())
+            .collect()
     }
 
     /// Optimized scan with performance improvements
-    pub fn scan_optimized(&self, root: &Path) -> Result<(Vec<Match>, ScanMetrics)> {
+    pub fn scan_optimized(&self, root: &Path) -> ScanResult<(Vec<Match>, ScanMetrics)> {
         let start_time = Instant::now();
         let files_processed = AtomicUsize::new(0);
         let lines_processed = AtomicUsize::new(0);
         let cache_hits = AtomicUsize::new(0);
         let cache_misses = AtomicUsize::new(0);
+        let generated_skipped_before = self.walk_options.generated_files_skipped();
+        let skipped = SkipLog::default();
+        let file_types = FileTypeCounters::default();
+        self.batching.reset();
+        let counters: Vec<DetectorCounters> = if self.profile_detectors {
+            self.detectors
+                .iter()
+                .map(|_| DetectorCounters::default())
+                .collect()
+        } else {
+            Vec::new()
+        };
 
         // Pre-compile regex patterns and optimize file filtering
-        let matches: Vec<Match> = WalkBuilder::new(root)
-            .standard_filters(true) // Use gitignore, etc.
+        let matches: Vec<Match> = crate::scan_walker_with(root, &self.walk_options)
             .build()
             .par_bridge()
             .filter_map(|entry| {
-                let entry = entry.ok()?;
+                let entry = entry.map_err(|e| skipped.record_walk_error(root, e)).ok()?;
                 let file_type = entry.file_type()?;
 
                 if !file_type.is_file() {
@@ -139,7 +274,7 @@ impl OptimizedScanner {
                 let path = entry.path();
 
                 // Skip binary files and large files early
-                if !self.should_scan_file(path) {
+                if !self.should_scan_file(path, &skipped) {
                     return None;
                 }
 
@@ -148,35 +283,87 @@ impl OptimizedScanner {
                 let path_str = path.to_string_lossy().to_string();
 
                 // Check file-based cache with modification time
-                if let Some(cached_result) = self.get_cached_result(path, &path_str) {
-                    cache_hits.fetch_add(1, Ordering::Relaxed);
-                    return Some(cached_result);
+                if self.scan_cache.is_none() {
+                    if let Some(cached_result) = self.get_cached_result(path, &path_str) {
+                        cache_hits.fetch_add(1, Ordering::Relaxed);
+                        file_types.record(path, 0, cached_result.len(), Duration::ZERO);
+                        return Some(cached_result);
+                    }
+                    cache_misses.fetch_add(1, Ordering::Relaxed);
                 }
 
-                cache_misses.fetch_add(1, Ordering::Relaxed);
-
                 // Read and process file
-                let content = self.read_file_content(path).ok()?;
-                lines_processed.fetch_add(content.lines().count(), Ordering::Relaxed);
+                let file_start = Instant::now();
+                let content = read_stable(path)
+                    .map_err(|e| skipped.record_error(path, &e))
+                    .ok()?;
+
+                // The content-keyed cache still hits when timestamps change
+                let hash = self
+                    .scan_cache
+                    .as_ref()
+                    .map(|_| content_hash(content.as_bytes()));
+                if let (Some(cache), Some(hash)) = (&self.scan_cache, hash) {
+                    if let Some(cached_result) = cache.get(&path_str, hash) {
+                        cache_hits.fetch_add(1, Ordering::Relaxed);
+                        file_types.record(path, 0, cached_result.len(), file_start.elapsed());
+                        return Some(cached_result);
+                    }
+                    cache_misses.fetch_add(1, Ordering::Relaxed);
+                }
+                // Shared by every detector that resolves offsets to lines
+                let lines = LineIndex::new(&content);
+                lines_processed.fetch_add(lines.line_count(), Ordering::Relaxed);
 
                 // Use optimized parallel processing for detectors
-                let relevant_detectors = self.get_relevant_detectors(path);
-                let file_matches: Vec<Match> = if relevant_detectors.len() > 3 {
-                    // For many detectors, use parallel processing
-                    relevant_detectors
-                        .par_iter()
-                        .flat_map(|detector| detector.detect(&content, path))
-                        .collect()
-                } else {
-                    // For few detectors, sequential is faster (less overhead)
-                    relevant_detectors
+                let file_matches: Vec<Match> = if self.profile_detectors {
+                    let candidates = self.prefilter.candidates(&content);
+                    self.detectors
                         .iter()
-                        .flat_map(|detector| detector.detect(&content, path))
+                        .zip(&counters)
+                        .zip(candidates)
+                        .flat_map(|((detector, counters), candidate)| {
+                            // Detectors ruled out by the prefilter count as scanned
+                            // in no time
+                            if !candidate {
+                                counters.record(Duration::ZERO, 0);
+                                return Vec::new();
+                            }
+                            let detector_start = Instant::now();
+                            let detector_matches =
+                                detector.detect_with_lines(&content, path, &lines);
+                            counters.record(detector_start.elapsed(), detector_matches.len());
+                            detector_matches
+                        })
                         .collect()
+                } else {
+                    let relevant_detectors = self.get_relevant_detectors(&content);
+                    let detect = |detector: &&dyn PatternDetector| {
+                        self.batching.detect(*detector, &content, path, &lines)
+                    };
+                    if self
+                        .batching
+                        .parallel_detectors(relevant_detectors.len(), content.len())
+                    {
+                        // Costly enough for threads by the measured detector cost per byte,
+                        // or more than three detectors before any run is measured
+                        relevant_detectors.par_iter().flat_map(detect).collect()
+                    } else {
+                        // Too cheap to be worth spreading over threads, or a single detector
+                        relevant_detectors.iter().flat_map(detect).collect()
+                    }
                 };
 
-                // Cache the result with file modification time
-                self.cache_result(path, &path_str, &file_matches);
+                let file_matches = self.match_limits.apply(file_matches);
+                let elapsed = file_start.elapsed();
+                self.batching.record_file(path, elapsed);
+                file_types.record(path, lines.line_count(), file_matches.len(), elapsed);
+
+                // Cache the result with its content hash or file modification time
+                match (&self.scan_cache, hash) {
+                    (Some(cache), Some(hash)) => cache.insert(hash, &file_matches),
+                    _ => self.cache_result(path, &path_str, &file_matches),
+                }
 
                 Some(file_matches)
             })
@@ -189,37 +376,67 @@ impl OptimizedScanner {
             total_files_scanned: files_processed.load(Ordering::Relaxed),
             total_lines_processed: lines_processed.load(Ordering::Relaxed),
             total_matches_found: matches.len(),
-            scan_duration_ms: duration.as_millis() as u64,
+            scan_duration_ms: duration_ms(duration),
             cache_hits: cache_hits.load(Ordering::Relaxed),
             cache_misses: cache_misses.load(Ordering::Relaxed),
+            cache_invalidated: self.scan_cache.as_ref().map_or(0, ScanCache::invalidated),
+            generated_files_skipped: self.walk_options.generated_files_skipped()
+                - generated_skipped_before,
+            skipped_files: skipped.files(),
+            detector_timings: self.collect_detector_timings(counters),
+            worker_metrics: Vec::new(),
+            batch_sizing: Some(self.batching.stats()),
+            file_types: file_types.metrics(),
         };
 
         Ok((matches, metrics))
     }
 
-    /// Check if a file should be scanned based on size and type
-    fn should_scan_file(&self, path: &Path) -> bool {
-        // Skip files in common build/dependency directories
-        if let Some(path_str) = path.to_str() {
-            if path_str.contains("/target/")
-                || path_str.contains("/node_modules/")
-                || path_str.contains("/.git/")
-                || path_str.contains("/build/")
-                || path_str.contains("/dist/")
-                || path_str.contains("/.next/")
-                || path_str.contains("/.nuxt/")
+    /// Reports how well the detectors cover `root`, from the metrics of a
+    /// scan with [`Self::with_detector_profiling`] enabled. Walks `root`
+    /// again to find the scanned files that no detector applies to.
+    pub fn coverage_report(&self, root: &Path, metrics: &ScanMetrics) -> CoverageReport {
+        let probe = SkipLog::default();
+        let mut uncovered = BTreeMap::new();
+        for entry in crate::scan_walker_with(root, &self.walk_options)
+            .build()
+            .flatten()
+        {
+            let path = entry.path();
+            if !entry.file_type().is_some_and(|t| t.is_file())
+                || !self.should_scan_file(path, &probe)
+                || self.detectors.iter().any(|d| d.applies_to(path))
             {
-                return false;
+                continue;
             }
+            let extension = path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default();
+            *uncovered.entry(extension).or_insert(0) += 1;
         }
+        CoverageReport::new(metrics, uncovered)
+    }
 
-        // Check file size (skip files larger than 5MB)
-        if let Ok(metadata) = std::fs::metadata(path) {
-            if metadata.len() > 5 * 1024 * 1024 {
-                return false;
-            }
-        }
+    fn collect_detector_timings(&self, counters: Vec<DetectorCounters>) -> Vec<DetectorTiming> {
+        let mut timings: Vec<DetectorTiming> = self
+            .detectors
+            .iter()
+            .zip(counters)
+            .map(|(detector, counters)| DetectorTiming {
+                detector: detector.name().to_string(),
+                duration_us: counters.duration_ns.into_inner() / 1000,
+                matches_found: counters.matches_found.into_inner(),
+                files_scanned: counters.files_scanned.into_inner(),
+                files_with_matches: counters.files_with_matches.into_inner(),
+            })
+            .collect();
+        timings.sort_by_key(|timing| std::cmp::Reverse(timing.duration_us));
+        timings
+    }
 
+    /// Check if a file should be scanned based on size and type
+    fn should_scan_file(&self, path: &Path, skipped: &SkipLog) -> bool {
         // Check if file is binary by trying to read first 1024 bytes as UTF-8
         if let Ok(mut file) = File::open(path) {
             let mut buffer = [0; 1024];
@@ -245,6 +462,11 @@ impl OptimizedScanner {
             }
         }
 
+        // Check file size last so large binaries aren't reported (skip files larger than 5MB)
+        if let Ok(metadata) = std::fs::metadata(path) {
+            return skipped.check_size(path, metadata.len());
+        }
+
         true
     }
 
@@ -313,61 +535,42 @@ impl OptimizedScanner {
 /// Memory-efficient streaming scanner for very large codebases
 pub struct StreamingScanner {
     detectors: Vec<Box<dyn PatternDetector>>,
+    prefilter: PatternPrefilter,
     batch_size: usize,
+    walk_options: WalkOptions,
+    batching: BatchController,
 }
 
 impl StreamingScanner {
     pub fn new(detectors: Vec<Box<dyn PatternDetector>>) -> Self {
         Self {
+            prefilter: PatternPrefilter::new(&detectors),
             detectors,
-            batch_size: 100, // Process files in batches
+            batch_size: 100, // Files in memory at once, scanned in chunks
+            walk_options: WalkOptions::default(),
+            batching: BatchController::default(),
         }
     }
 
-    /// Gets the relevant detectors for a specific file extension
-    fn get_relevant_detectors(&self, path: &Path) -> Vec<&dyn PatternDetector> {
-        let ext = path.extension().and_then(|e| e.to_str());
+    /// Configure how the directory tree is walked, see [`WalkOptions`].
+    pub fn with_walk_options(mut self, options: WalkOptions) -> Self {
+        self.walk_options = options;
+        self
+    }
 
-        match ext {
-            Some("rs") => self.detectors.iter().map(|d| d.as_ref()).collect(),
-            Some("js" | "ts" | "jsx" | "tsx" | "vue" | "svelte") => {
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-            Some("py" | "pyw" | "pyx") => self.detectors.iter().map(|d| d.as_ref()).collect(),
-            Some("c" | "cpp" | "cc" | "cxx" | "h" | "hpp") => {
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-            Some("sh" | "bash" | "zsh") => self.detectors.iter().map(|d| d.as_ref()).collect(),
-            Some("json" | "yaml" | "yml" | "toml" | "xml" | "ini" | "cfg") => {
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-            _ => self.detectors.iter().map(|d| d.as_ref()).collect(),
-        }
+    /// Detectors that can match `content`, found with one [`PatternPrefilter`] pass
+    fn get_relevant_detectors(&self, content: &str) -> Vec<&dyn PatternDetector> {
+        let candidates = self.prefilter.candidates(content);
+        self.detectors
+            .iter()
+            .zip(candidates)
+            .filter(|(_, candidate)| *candidate)
+            .map(|(detector, _)| detector.as_ref())
+            .collect()
     }
 
     /// Check if a file should be scanned based on size and type
-    fn should_scan_file_streaming(&self, path: &Path) -> bool {
-        // Skip files in common build/dependency directories
-        if let Some(path_str) = path.to_str() {
-            if path_str.contains("/target/")
-                || path_str.contains("/node_modules/")
-                || path_str.contains("/.git/")
-                || path_str.contains("/build/")
-                || path_str.contains("/dist/")
-                || path_str.contains("/.next/")
-                || path_str.contains("/.nuxt/")
-            {
-                return false;
-            }
-        }
-
-        // Check file size (skip files larger than 5MB)
-        if let Ok(metadata) = std::fs::metadata(path) {
-            if metadata.len() > 5 * 1024 * 1024 {
-                return false;
-            }
-        }
-
+    fn should_scan_file_streaming(&self, path: &Path, skipped: &SkipLog) -> bool {
         // Check if file is binary by trying to read first 1024 bytes as UTF-8
         if let Ok(mut file) = File::open(path) {
             let mut buffer = [0; 1024];
@@ -393,32 +596,42 @@ impl StreamingScanner {
             }
         }
 
+        // Check file size last so large binaries aren't reported (skip files larger than 5MB)
+        if let Ok(metadata) = std::fs::metadata(path) {
+            return skipped.check_size(path, metadata.len());
+        }
+
         true
     }
 
     /// Scan with memory-efficient streaming
-    pub fn scan_streaming<F>(&self, root: &Path, mut callback: F) -> Result<ScanMetrics>
+    pub fn scan_streaming<F>(&self, root: &Path, mut callback: F) -> ScanResult<ScanMetrics>
     where
-        F: FnMut(Vec<Match>) -> Result<()>,
+        F: FnMut(Vec<Match>) -> ScanResult<()>,
     {
         let start_time = Instant::now();
         let mut total_files = 0;
         let mut total_lines = 0;
         let mut total_matches = 0;
+        let generated_skipped_before = self.walk_options.generated_files_skipped();
+        let skipped = SkipLog::default();
+        let file_types = FileTypeCounters::default();
+        self.batching.reset();
 
-        let walker = WalkBuilder::new(root).standard_filters(true).build();
+        let walker = crate::scan_walker_with(root, &self.walk_options).build();
 
         let mut file_batch = Vec::new();
 
         for entry in walker {
-            let entry = entry?;
+            let entry = entry.map_err(|e| ScanError::from_walk(root, e))?;
             if entry.file_type().is_some_and(|ft| ft.is_file())
-                && self.should_scan_file_streaming(entry.path())
+                && self.should_scan_file_streaming(entry.path(), &skipped)
             {
                 file_batch.push(entry.path().to_path_buf());
 
                 if file_batch.len() >= self.batch_size {
-                    let (batch_matches, batch_lines) = self.process_batch(&file_batch)?;
+                    let (batch_matches, batch_lines) =
+                        self.process_batch(&file_batch, &skipped, &file_types)?;
                     total_files += file_batch.len();
                     total_lines += batch_lines;
                     total_matches += batch_matches.len();
@@ -431,7 +644,8 @@ impl StreamingScanner {
 
         // Process remaining files
         if !file_batch.is_empty() {
-            let (batch_matches, batch_lines) = self.process_batch(&file_batch)?;
+            let (batch_matches, batch_lines) =
+                self.process_batch(&file_batch, &skipped, &file_types)?;
             total_files += file_batch.len();
             total_lines += batch_lines;
             total_matches += batch_matches.len();
@@ -445,61 +659,67 @@ impl StreamingScanner {
             total_files_scanned: total_files,
             total_lines_processed: total_lines,
             total_matches_found: total_matches,
-            scan_duration_ms: duration.as_millis() as u64,
+            scan_duration_ms: duration_ms(duration),
             cache_hits: 0,
             cache_misses: 0,
+            cache_invalidated: 0,
+            generated_files_skipped: self.walk_options.generated_files_skipped()
+                - generated_skipped_before,
+            skipped_files: skipped.files(),
+            detector_timings: Vec::new(),
+            worker_metrics: Vec::new(),
+            batch_sizing: Some(self.batching.stats()),
+            file_types: file_types.metrics(),
         })
     }
 
-    fn process_batch(&self, files: &[std::path::PathBuf]) -> Result<(Vec<Match>, usize)> {
-        let results: Vec<(Vec<Match>, usize)> = files
-            .par_iter()
-            .filter_map(|path| {
-                // Use memory-mapped files for large files (>1MB) for better performance
-                let (content, line_count) = if let Ok(metadata) = std::fs::metadata(path) {
-                    if metadata.len() > 1024 * 1024 {
-                        // Use memory mapping for large files
-                        if let Ok(file) = File::open(path) {
-                            if let Ok(mmap) = unsafe { Mmap::map(&file) } {
-                                let content = std::str::from_utf8(&mmap).ok()?;
-                                let line_count = content.lines().count();
-                                (content.to_string(), line_count)
-                            } else {
-                                // Fallback to regular reading
-                                let content = std::fs::read_to_string(path).ok()?;
-                                let line_count = content.lines().count();
-                                (content, line_count)
-                            }
-                        } else {
-                            return None;
-                        }
-                    } else {
-                        // Use regular reading for smaller files
-                        let content = std::fs::read_to_string(path).ok()?;
-                        let line_count = content.lines().count();
-                        (content, line_count)
-                    }
-                } else {
-                    return None;
-                };
-
-                let relevant_detectors = self.get_relevant_detectors(path);
-                let matches: Vec<Match> = if relevant_detectors.len() <= 3 {
-                    // For few detectors, sequential is faster (less overhead)
-                    relevant_detectors
-                        .iter()
-                        .flat_map(|detector| detector.detect(&content, path))
-                        .collect()
-                } else {
-                    // For many detectors, use parallel processing
-                    relevant_detectors
-                        .par_iter()
-                        .flat_map(|detector| detector.detect(&content, path))
-                        .collect()
-                };
-
-                Some((matches, line_count))
-            })
+    fn process_batch(
+        &self,
+        files: &[std::path::PathBuf],
+        skipped: &SkipLog,
+        file_types: &FileTypeCounters,
+    ) -> ScanResult<(Vec<Match>, usize)> {
+        let scan_file = |path: PathBuf| {
+            let file_start = Instant::now();
+            let content = read_stable(&path)
+                .map_err(|e| skipped.record_error(&path, &e))
+                .ok()?;
+            let lines = LineIndex::new(&content);
+            let line_count = lines.line_count();
+
+            let relevant_detectors = self.get_relevant_detectors(&content);
+            let detect = |detector: &&dyn PatternDetector| {
+                self.batching.detect(*detector, &content, &path, &lines)
+            };
+            let matches: Vec<Match> = if self
+                .batching
+                .parallel_detectors(relevant_detectors.len(), content.len())
+            {
+                // Costly enough for threads by the measured detector cost per byte,
+                // or more than three detectors before any run is measured
+                relevant_detectors.par_iter().flat_map(detect).collect()
+            } else {
+                // Too cheap to be worth spreading over threads, or a single detector
+                relevant_detectors.iter().flat_map(detect).collect()
+            };
+            let elapsed = file_start.elapsed();
+            self.batching.record_file(&path, elapsed);
+            file_types.record(&path, line_count, matches.len(), elapsed);
+
+            Some((matches, line_count))
+        };
+        // Chunk sizes follow the costs measured in earlier chunks and batches
+        let mut chunks: Vec<(usize, Vec<(Vec<Match>, usize)>)> = self
+            .batching
+            .chunks(files.to_vec())
+            .enumerate()
+            .par_bridge()
+            .map(|(i, chunk)| (i, chunk.into_iter().filter_map(scan_file).collect()))
+            .collect();
+        chunks.sort_by_key(|(i, _)| *i);
+        let results: Vec<(Vec<Match>, usize)> = chunks
+            .into_iter()
+            .flat_map(|(_, results)| results)
             .collect();
 
         let all_matches: Vec<Match> = results.iter().flat_map(|(m, _)| m.clone()).collect();
@@ -512,10 +732,11 @@ impl StreamingScanner {
 /// Advanced scanner combining multiple optimization techniques
 pub struct AdvancedScanner {
     detectors: Vec<Box<dyn PatternDetector>>,
+    prefilter: PatternPrefilter,
     high_perf_detector: crate::detectors::HighPerformanceDetector,
     cache: DashMap<String, (u64, Vec<Match>)>,
     max_cache_size: usize,
-    use_memory_mapping: bool,
+    batching: BatchController,
 }
 
 impl AdvancedScanner {
@@ -524,52 +745,42 @@ impl AdvancedScanner {
         let high_perf_detector = crate::detectors::HighPerformanceDetector::for_common_patterns();
 
         Self {
+            prefilter: PatternPrefilter::new(&detectors),
             detectors,
             high_perf_detector,
             cache: DashMap::new(),
             max_cache_size: 20000,
-            use_memory_mapping: true,
+            batching: BatchController::default(),
         }
     }
 
-    /// Gets the relevant detectors for a specific file extension
-    fn get_relevant_detectors(&self, path: &Path) -> Vec<&dyn PatternDetector> {
-        let ext = path.extension().and_then(|e| e.to_str());
-
-        match ext {
-            Some("rs") => self.detectors.iter().map(|d| d.as_ref()).collect(),
-            Some("js" | "ts" | "jsx" | "tsx" | "vue" | "svelte") => {
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-            Some("py" | "pyw" | "pyx") => self.detectors.iter().map(|d| d.as_ref()).collect(),
-            Some("java") => self.detectors.iter().map(|d| d.as_ref()).collect(),
-            Some("c" | "cpp" | "cc" | "cxx" | "h" | "hpp") => {
-                self.detectors.iter().map(|d| d.as_ref()).collect()
-            }
-            Some("go") => self.detectors.iter().map(|d| d.as_ref()).collect(),
-            Some("php") => self.detectors.iter().map(|d| d.as_ref()).collect(),
-            Some("rb") => self.detectors.iter().map(|d| d.as_ref()).collect(),
-            Some("sh" | "bash" | "zsh") => self.detectors.iter().map(|d| d.as_ref()).collect(),
-            Some("json") | Some("yaml") | Some("yml") | Some("toml") | Some("xml")
-            | Some("ini") | Some("cfg") => self.detectors.iter().map(|d| d.as_ref()).collect(),
-            _ => self.detectors.iter().map(|d| d.as_ref()).collect(),
-        }
+    /// Detectors that can match `content`, found with one [`PatternPrefilter`] pass
+    fn get_relevant_detectors(&self, content: &str) -> Vec<&dyn PatternDetector> {
+        let candidates = self.prefilter.candidates(content);
+        self.detectors
+            .iter()
+            .zip(candidates)
+            .filter(|(_, candidate)| *candidate)
+            .map(|(detector, _)| detector.as_ref())
+            .collect()
     }
 
     /// Advanced scan with multiple optimization layers
-    pub fn scan_advanced(&self, root: &Path) -> Result<(Vec<Match>, ScanMetrics)> {
+    pub fn scan_advanced(&self, root: &Path) -> ScanResult<(Vec<Match>, ScanMetrics)> {
         let start_time = Instant::now();
         let files_processed = AtomicUsize::new(0);
         let lines_processed = AtomicUsize::new(0);
         let cache_hits = AtomicUsize::new(0);
         let cache_misses = AtomicUsize::new(0);
+        let skipped = SkipLog::default();
+        let file_types = FileTypeCounters::default();
+        self.batching.reset();
 
-        let matches: Vec<Match> = WalkBuilder::new(root)
-            .standard_filters(true)
+        let matches: Vec<Match> = crate::scan_walker(root)
             .build()
             .par_bridge()
             .filter_map(|entry| {
-                let entry = entry.ok()?;
+                let entry = entry.map_err(|e| skipped.record_walk_error(root, e)).ok()?;
                 let file_type = entry.file_type()?;
 
                 if !file_type.is_file() {
@@ -579,7 +790,7 @@ impl AdvancedScanner {
                 let path = entry.path();
 
                 // Skip inappropriate files early
-                if !self.should_scan_file_advanced(path) {
+                if !self.should_scan_file_advanced(path, &skipped) {
                     return None;
                 }
 
@@ -589,32 +800,43 @@ impl AdvancedScanner {
                 // Check cache
                 if let Some(cached_result) = self.get_cached_result_advanced(path, &path_str) {
                     cache_hits.fetch_add(1, Ordering::Relaxed);
+                    file_types.record(path, 0, cached_result.len(), Duration::ZERO);
                     return Some(cached_result);
                 }
 
                 cache_misses.fetch_add(1, Ordering::Relaxed);
 
                 // Read content with optimizations
-                let content = self.read_file_content_advanced(path).ok()?;
-                lines_processed.fetch_add(content.lines().count(), Ordering::Relaxed);
+                let file_start = Instant::now();
+                let content = read_stable(path)
+                    .map_err(|e| skipped.record_error(path, &e))
+                    .ok()?;
+                // Shared by every detector that resolves offsets to lines
+                let lines = LineIndex::new(&content);
+                lines_processed.fetch_add(lines.line_count(), Ordering::Relaxed);
 
                 // Use high-performance detector for common patterns
-                let mut file_matches = self.high_perf_detector.detect(&content, path);
+                let mut file_matches = self
+                    .high_perf_detector
+                    .detect_with_lines(&content, path, &lines);
 
                 // Use specialized detectors for remaining patterns
-                let relevant_detectors = self.get_relevant_detectors(path);
-                if relevant_detectors.len() <= 3 {
-                    // For few detectors, sequential is faster (less overhead)
-                    for detector in relevant_detectors {
-                        file_matches.extend(detector.detect(&content, path));
-                    }
-                } else {
-                    // For many detectors, use parallel processing
-                    let additional_matches: Vec<Match> = relevant_detectors
-                        .par_iter()
-                        .flat_map(|detector| detector.detect(&content, path))
-                        .collect();
+                let relevant_detectors = self.get_relevant_detectors(&content);
+                let detect = |detector: &&dyn PatternDetector| {
+                    self.batching.detect(*detector, &content, path, &lines)
+                };
+                if self
+                    .batching
+                    .parallel_detectors(relevant_detectors.len(), content.len())
+                {
+                    // Costly enough for threads by the measured detector cost per byte,
+                    // or more than three detectors before any run is measured
+                    let additional_matches: Vec<Match> =
+                        relevant_detectors.par_iter().flat_map(detect).collect();
                     file_matches.extend(additional_matches);
+                } else {
+                    // Too cheap to be worth spreading over threads, or a single detector
+                    file_matches.extend(relevant_detectors.iter().flat_map(detect));
                 }
 
                 // Remove duplicates (patterns might overlap)
@@ -631,6 +853,9 @@ impl AdvancedScanner {
 
                 // Cache result
                 self.cache_result_advanced(path, &path_str, &file_matches);
+                let elapsed = file_start.elapsed();
+                self.batching.record_file(path, elapsed);
+                file_types.record(path, lines.line_count(), file_matches.len(), elapsed);
 
                 Some(file_matches)
             })
@@ -643,37 +868,23 @@ impl AdvancedScanner {
             total_files_scanned: files_processed.load(Ordering::Relaxed),
             total_lines_processed: lines_processed.load(Ordering::Relaxed),
             total_matches_found: matches.len(),
-            scan_duration_ms: duration.as_millis() as u64,
+            scan_duration_ms: duration_ms(duration),
             cache_hits: cache_hits.load(Ordering::Relaxed),
             cache_misses: cache_misses.load(Ordering::Relaxed),
+            cache_invalidated: 0,
+            generated_files_skipped: 0,
+            skipped_files: skipped.files(),
+            detector_timings: Vec::new(),
+            worker_metrics: Vec::new(),
+            batch_sizing: Some(self.batching.stats()),
+            file_types: file_types.metrics(),
         };
 
         Ok((matches, metrics))
     }
 
     /// Advanced file filtering with better heuristics
-    fn should_scan_file_advanced(&self, path: &Path) -> bool {
-        // Skip files in common build/dependency directories
-        if let Some(path_str) = path.to_str() {
-            if path_str.contains("/target/")
-                || path_str.contains("/node_modules/")
-                || path_str.contains("/.git/")
-                || path_str.contains("/build/")
-                || path_str.contains("/dist/")
-                || path_str.contains("/.next/")
-                || path_str.contains("/.nuxt/")
-            {
-                return false;
-            }
-        }
-
-        // Check file size (skip files larger than 5MB)
-        if let Ok(metadata) = std::fs::metadata(path) {
-            if metadata.len() > 5 * 1024 * 1024 {
-                return false;
-            }
-        }
-
+    fn should_scan_file_advanced(&self, path: &Path, skipped: &SkipLog) -> bool {
         // Check if file is binary by trying to read first 1024 bytes as UTF-8
         if let Ok(mut file) = File::open(path) {
             let mut buffer = [0; 1024];
@@ -698,28 +909,12 @@ impl AdvancedScanner {
             }
         }
 
-        true
-    }
-
-    /// Advanced file reading with memory mapping for large files
-    fn read_file_content_advanced(&self, path: &Path) -> Result<String> {
-        if !self.use_memory_mapping {
-            return Ok(std::fs::read_to_string(path)?);
+        // Check file size last so large binaries aren't reported (skip files larger than 5MB)
+        if let Ok(metadata) = std::fs::metadata(path) {
+            return skipped.check_size(path, metadata.len());
         }
 
-        let metadata = std::fs::metadata(path)?;
-
-        if metadata.len() > 1024 * 1024 {
-            // 1MB threshold
-            // Use memory mapping for large files
-            let file = File::open(path)?;
-            let mmap = unsafe { Mmap::map(&file)? };
-            let content = std::str::from_utf8(&mmap)?;
-            Ok(content.to_string())
-        } else {
-            // Regular reading for smaller files
-            Ok(std::fs::read_to_string(path)?)
-        }
+        true
     }
 
     /// Advanced caching with better invalidation
@@ -771,12 +966,6 @@ impl AdvancedScanner {
         }
     }
 
-    /// Configure memory mapping usage
-    pub fn with_memory_mapping(mut self, enabled: bool) -> Self {
-        self.use_memory_mapping = enabled;
-        self
-    }
-
     /// Set cache size
     pub fn with_cache_size(mut self, size: usize) -> Self {
         self.max_cache_size = size;
@@ -805,6 +994,34 @@ mod tests {
         assert_eq!(matches.len(), 2);
         assert_eq!(metrics.total_files_scanned, 1);
         assert!(metrics.scan_duration_ms > 0);
+        assert_eq!(metrics.file_types.len(), 1);
+        assert_eq!(metrics.file_types[0].extension, "rs");
+        assert_eq!(metrics.file_types[0].lines, 2);
+        assert_eq!(metrics.file_types[0].matches, 2);
+    }
+
+    #[test]
+    fn test_file_type_counters() {
+        let counters = FileTypeCounters::default();
+        counters.record(Path::new("a/app.min.JS"), 1, 0, Duration::from_millis(80));
+        counters.record(Path::new("a/lib.rs"), 40, 2, Duration::from_millis(15));
+        counters.record(Path::new("b/main.rs"), 10, 1, Duration::from_millis(5));
+        counters.record(Path::new("Makefile"), 3, 0, Duration::ZERO);
+
+        let metrics = counters.metrics();
+        let order: Vec<&str> = metrics.iter().map(|t| t.extension.as_str()).collect();
+        assert_eq!(order, ["js", "rs", ""]);
+        assert_eq!(metrics[0].language.as_deref(), Some("JavaScript"));
+        assert_eq!(
+            (metrics[1].files, metrics[1].lines, metrics[1].matches),
+            (2, 50, 3)
+        );
+        assert_eq!(metrics[1].duration_us, 20_000);
+        assert_eq!(metrics[0].time_share(&metrics), 0.8);
+        assert_eq!(metrics[2].time_share(&metrics), 0.0);
+
+        counters.clear();
+        assert!(counters.metrics().is_empty());
     }
 
     #[test]
@@ -825,4 +1042,93 @@ mod tests {
         assert_eq!(matches1.len(), matches2.len());
         assert!(metrics2.cache_hits > 0);
     }
+
+    #[test]
+    fn test_content_cache_persists_across_runs() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.rs");
+        std::fs::write(&file_path, "// TODO: test").unwrap();
+        let cache_dir = temp_dir.path().join(crate::SCAN_CACHE_DIR);
+        let scan = || {
+            let detectors: Vec<Box<dyn PatternDetector>> = vec![Box::new(TodoDetector)];
+            let cache = ScanCache::open(&cache_dir, &detectors, 1 << 20);
+            let scanner = OptimizedScanner::new(detectors).with_scan_cache(cache);
+            let (matches, metrics) = scanner.scan_optimized(temp_dir.path()).unwrap();
+            scanner.save_scan_cache().unwrap().unwrap();
+            (matches, metrics)
+        };
+
+        let (_, metrics) = scan();
+        assert_eq!((metrics.cache_hits, metrics.cache_misses), (0, 1));
+
+        // A fresh timestamp on the same content still hits
+        std::fs::write(&file_path, "// TODO: test").unwrap();
+        let (matches, metrics) = scan();
+        assert_eq!((metrics.cache_hits, metrics.cache_misses), (1, 0));
+        assert_eq!(matches.len(), 1);
+
+        std::fs::write(&file_path, "// TODO: changed\n// TODO: more").unwrap();
+        let (matches, metrics) = scan();
+        assert_eq!((metrics.cache_hits, metrics.cache_misses), (0, 1));
+        assert_eq!(matches.len(), 2);
+    }
+
+    #[test]
+    fn test_detector_profiling() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("a.rs"), "// TODO: one\n// TODO: two").unwrap();
+        std::fs::write(temp_dir.path().join("b.rs"), "// FIXME: three").unwrap();
+
+        let detectors: Vec<Box<dyn PatternDetector>> = vec![
+            Box::new(TodoDetector),
+            Box::new(CustomPatternDetector::new("NEVER", r"never-matches").unwrap()),
+        ];
+
+        let unprofiled = OptimizedScanner::new(vec![Box::new(TodoDetector)]);
+        let (_, metrics) = unprofiled.scan_optimized(temp_dir.path()).unwrap();
+        assert!(metrics.detector_timings.is_empty());
+
+        let scanner = OptimizedScanner::new(detectors).with_detector_profiling(true);
+        let (matches, metrics) = scanner.scan_optimized(temp_dir.path()).unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(metrics.detector_timings.len(), 2);
+
+        let todo = metrics
+            .detector_timings
+            .iter()
+            .find(|t| t.detector == "TodoDetector")
+            .unwrap();
+        assert_eq!(todo.matches_found, 2);
+        assert_eq!(todo.files_scanned, 2);
+        assert_eq!(todo.files_with_matches, 1);
+
+        let never = metrics
+            .detector_timings
+            .iter()
+            .find(|t| t.detector == "NEVER")
+            .unwrap();
+        assert_eq!(never.matches_found, 0);
+        assert_eq!(never.files_with_matches, 0);
+    }
+
+    #[test]
+    fn test_coverage_report() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("a.rs"), "x.unwrap();\n").unwrap();
+        std::fs::write(temp_dir.path().join("b.py"), "print(1)\n").unwrap();
+        std::fs::write(temp_dir.path().join("c.py"), "print(2)\n").unwrap();
+
+        let detectors: Vec<Box<dyn PatternDetector>> =
+            vec![Box::new(UnwrapDetector), Box::new(PanicDetector)];
+        let scanner = OptimizedScanner::new(detectors).with_detector_profiling(true);
+        let (_, metrics) = scanner.scan_optimized(temp_dir.path()).unwrap();
+        let report = scanner.coverage_report(temp_dir.path(), &metrics);
+
+        assert_eq!(report.files_scanned, 3);
+        assert_eq!(report.detectors, 2);
+        assert_eq!(report.silent_detectors, vec!["PanicDetector"]);
+        assert_eq!(report.uncovered_extensions.get("py"), Some(&2));
+        assert!(!report.uncovered_extensions.contains_key("rs"));
+        assert!(!report.is_complete());
+    }
 }
@@ -0,0 +1,352 @@
+//! Styling of terminal output: the icons before status lines, printed as
+//! emoji or as ASCII for CI log viewers and terminals that cannot show
+//! emoji, and the colors of severity levels. The `theme` config section and
+//! `--no-emoji` set the style once per process; all output goes through it.
+
+use crate::Severity;
+use colored::{Color, ColoredString, Colorize};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::RwLock;
+
+/// Terminal colors a theme can use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+}
+
+impl From<ThemeColor> for Color {
+    fn from(color: ThemeColor) -> Self {
+        match color {
+            ThemeColor::Black => Color::Black,
+            ThemeColor::Red => Color::Red,
+            ThemeColor::Green => Color::Green,
+            ThemeColor::Yellow => Color::Yellow,
+            ThemeColor::Blue => Color::Blue,
+            ThemeColor::Magenta => Color::Magenta,
+            ThemeColor::Cyan => Color::Cyan,
+            ThemeColor::White => Color::White,
+            ThemeColor::BrightBlack => Color::BrightBlack,
+            ThemeColor::BrightRed => Color::BrightRed,
+            ThemeColor::BrightGreen => Color::BrightGreen,
+            ThemeColor::BrightYellow => Color::BrightYellow,
+            ThemeColor::BrightBlue => Color::BrightBlue,
+            ThemeColor::BrightMagenta => Color::BrightMagenta,
+            ThemeColor::BrightCyan => Color::BrightCyan,
+            ThemeColor::BrightWhite => Color::BrightWhite,
+        }
+    }
+}
+
+/// Color of each severity level
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SeverityColors {
+    pub critical: ThemeColor,
+    pub high: ThemeColor,
+    pub medium: ThemeColor,
+    pub low: ThemeColor,
+    pub info: ThemeColor,
+}
+
+impl Default for SeverityColors {
+    fn default() -> Self {
+        Self {
+            critical: ThemeColor::Red,
+            high: ThemeColor::Yellow,
+            medium: ThemeColor::Cyan,
+            low: ThemeColor::Green,
+            info: ThemeColor::Blue,
+        }
+    }
+}
+
+impl SeverityColors {
+    pub fn get(&self, severity: &Severity) -> ThemeColor {
+        match severity {
+            Severity::Critical => self.critical,
+            Severity::High => self.high,
+            Severity::Medium => self.medium,
+            Severity::Low => self.low,
+            Severity::Info => self.info,
+        }
+    }
+}
+
+/// Settings of the terminal output, the `theme` config section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// Print emoji icons; `false` prints ASCII such as `[ok]` instead, like `--no-emoji`
+    pub emoji: bool,
+    /// Color the output; `NO_COLOR` in the environment turns colors off too
+    pub color: bool,
+    /// Colors of the severity levels in text reports
+    pub severity_colors: SeverityColors,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self {
+            emoji: true,
+            color: true,
+            severity_colors: SeverityColors::default(),
+        }
+    }
+}
+
+/// The theme in effect; `None` until [`set_output_theme`] is called
+static THEME: RwLock<Option<ThemeConfig>> = RwLock::new(None);
+
+/// Style all output printed from now on with `theme`
+pub fn set_output_theme(theme: ThemeConfig) {
+    if !theme.color {
+        colored::control::set_override(false);
+    }
+    *THEME.write().unwrap_or_else(|e| e.into_inner()) = Some(theme);
+}
+
+fn emoji_enabled() -> bool {
+    THEME
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map_or(true, |theme| theme.emoji)
+}
+
+/// `text` in the theme's color for `severity`
+pub fn paint_severity(text: &str, severity: &Severity) -> ColoredString {
+    let color = THEME
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map_or_else(
+            || SeverityColors::default().get(severity),
+            |theme| theme.severity_colors.get(severity),
+        );
+    text.color(Color::from(color))
+}
+
+/// Icons of the terminal output. They display as emoji, or as the ASCII of
+/// [`Icon::ascii`] when the theme turns emoji off.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Icon {
+    Success,
+    Failure,
+    Warning,
+    Info,
+    Tip,
+    Skipped,
+    Hourglass,
+    Stats,
+    Trend,
+    Search,
+    Inspect,
+    Folder,
+    File,
+    List,
+    Docs,
+    Target,
+    Tool,
+    Launch,
+    Fast,
+    Slow,
+    Timer,
+    Finish,
+    Test,
+    Network,
+    Globe,
+    Delete,
+    Sampled,
+    Gate,
+    Watch,
+    Save,
+    Add,
+    Remove,
+    Refresh,
+    Worker,
+    Package,
+    Shield,
+    /// A numbered step, 1 to 9
+    Step(u8),
+    /// The marker of a severity level
+    Severity(Severity),
+    /// A list bullet
+    Bullet,
+    /// An entry of a tree listing
+    Branch,
+    /// The last entry of a tree listing
+    LastBranch,
+}
+
+impl Icon {
+    /// The icon as emoji, without the padding [`Icon`]'s `Display` adds
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Icon::Success => "✅",
+            Icon::Failure => "❌",
+            Icon::Warning => "⚠️",
+            Icon::Info => "ℹ️",
+            Icon::Tip => "💡",
+            Icon::Skipped => "⏭️",
+            Icon::Hourglass => "⏳",
+            Icon::Stats => "📊",
+            Icon::Trend => "📈",
+            Icon::Search => "🔍",
+            Icon::Inspect => "🔎",
+            Icon::Folder => "📁",
+            Icon::File => "📄",
+            Icon::List => "📋",
+            Icon::Docs => "📖",
+            Icon::Target => "🎯",
+            Icon::Tool => "🔧",
+            Icon::Launch => "🚀",
+            Icon::Fast => "⚡",
+            Icon::Slow => "🐢",
+            Icon::Timer => "⏱️",
+            Icon::Finish => "🏁",
+            Icon::Test => "🧪",
+            Icon::Network => "🌐",
+            Icon::Globe => "🌍",
+            Icon::Delete => "🗑️",
+            Icon::Sampled => "🎲",
+            Icon::Gate => "🚦",
+            Icon::Watch => "👁️",
+            Icon::Save => "💾",
+            Icon::Add => "➕",
+            Icon::Remove => "➖",
+            Icon::Refresh => "🔄",
+            Icon::Worker => "🤖",
+            Icon::Package => "📦",
+            Icon::Shield => "🛡️",
+            Icon::Step(n) => match n {
+                1 => "1️⃣",
+                2 => "2️⃣",
+                3 => "3️⃣",
+                4 => "4️⃣",
+                5 => "5️⃣",
+                6 => "6️⃣",
+                7 => "7️⃣",
+                8 => "8️⃣",
+                _ => "9️⃣",
+            },
+            Icon::Severity(severity) => match severity {
+                Severity::Critical => "🔴",
+                Severity::High => "🟠",
+                Severity::Medium => "🟡",
+                Severity::Low => "🟢",
+                Severity::Info => "⚪",
+            },
+            Icon::Bullet => "•",
+            Icon::Branch => "├──",
+            Icon::LastBranch => "└──",
+        }
+    }
+
+    /// The icon without emoji or box drawing. Status icons become labels;
+    /// the decorative ones become `*`.
+    pub fn ascii(&self) -> &'static str {
+        match self {
+            Icon::Success => "[ok]",
+            Icon::Failure => "[error]",
+            Icon::Warning => "[warn]",
+            Icon::Info => "[info]",
+            Icon::Tip => "[tip]",
+            Icon::Skipped => "[skip]",
+            Icon::Step(n) => match n {
+                1 => "1.",
+                2 => "2.",
+                3 => "3.",
+                4 => "4.",
+                5 => "5.",
+                6 => "6.",
+                7 => "7.",
+                8 => "8.",
+                _ => "9.",
+            },
+            Icon::Severity(severity) => match severity {
+                Severity::Critical => "[critical]",
+                Severity::High => "[high]",
+                Severity::Medium => "[medium]",
+                Severity::Low => "[low]",
+                Severity::Info => "[info]",
+            },
+            Icon::Bullet => "-",
+            Icon::Branch => "|--",
+            Icon::LastBranch => "`--",
+            _ => "*",
+        }
+    }
+}
+
+impl fmt::Display for Icon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if emoji_enabled() {
+            let emoji = self.emoji();
+            f.write_str(emoji)?;
+            // Emoji drawn from text symbols are often rendered one column
+            // wide; pad them so the text after them lines up
+            if emoji.ends_with('\u{fe0f}') {
+                f.write_str(" ")?;
+            }
+            Ok(())
+        } else {
+            f.write_str(self.ascii())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icons_are_ascii_without_emoji() {
+        for icon in [
+            Icon::Success,
+            Icon::Stats,
+            Icon::Step(3),
+            Icon::Severity(Severity::High),
+            Icon::Branch,
+        ] {
+            assert!(icon.ascii().is_ascii(), "{:?}", icon);
+            assert!(!icon.emoji().is_ascii(), "{:?}", icon);
+        }
+        assert_eq!(Icon::Warning.ascii(), "[warn]");
+    }
+
+    #[test]
+    fn test_theme_config_from_toml() {
+        let theme: ThemeConfig =
+            toml::from_str("emoji = false\n[severity_colors]\nhigh = \"bright_magenta\"\n")
+                .unwrap();
+        assert!(!theme.emoji);
+        assert!(theme.color);
+        assert_eq!(
+            theme.severity_colors.get(&Severity::High),
+            ThemeColor::BrightMagenta
+        );
+        assert_eq!(
+            theme.severity_colors.get(&Severity::Critical),
+            ThemeColor::Red
+        );
+        assert!(toml::from_str::<ThemeConfig>("[severity_colors]\nhigh = \"orange\"\n").is_err());
+    }
+}
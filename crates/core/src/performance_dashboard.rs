@@ -1,3 +1,4 @@
+use crate::Icon;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -259,7 +260,7 @@ impl PerformanceDashboard {
 <body>
     <div class="dashboard">
         <div class="header">
-            <h1>🛡️ Code Guardian Performance Dashboard</h1>
+            <h1>{} Code Guardian Performance Dashboard</h1>
             <p class="timestamp">Last updated: {}</p>
             <p>Uptime: {} minutes</p>
         </div>
@@ -291,13 +292,14 @@ impl PerformanceDashboard {
         </div>
 
         <div class="history-chart">
-            <h3>📊 Performance History</h3>
+            <h3>{} Performance History</h3>
             <p>Tracking {} recent scans</p>
             <p>Configure monitoring with: <code>cargo run -- scan . --metrics</code></p>
         </div>
     </div>
 </body>
 </html>"#,
+            Icon::Shield,
             chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
             report.uptime_seconds / 60,
             self.generate_alerts_html(&report.active_alerts),
@@ -307,6 +309,7 @@ impl PerformanceDashboard {
             report.average_duration_ms.unwrap_or(0.0),
             report.average_throughput_files_per_second.unwrap_or(0.0),
             self.generate_current_metrics_html(&report.current_metrics),
+            Icon::Stats,
             report.history_size,
         )
     }
@@ -316,7 +319,10 @@ impl PerformanceDashboard {
             return String::new();
         }
 
-        let mut html = String::from(r#"<div class="alerts"><h3>⚠️ Active Alerts</h3>"#);
+        let mut html = format!(
+            r#"<div class="alerts"><h3>{} Active Alerts</h3>"#,
+            Icon::Warning
+        );
 
         for alert in alerts {
             let class = match alert.severity {
@@ -446,7 +452,7 @@ impl PerformanceDashboard {
 
         // Get current process memory usage
         if let Some(process) = sys.process(sysinfo::get_current_pid().unwrap()) {
-            (process.memory() / 1024 / 1024) as u64 // Convert to MB
+            process.memory() / 1024 / 1024 // Convert to MB
         } else {
             64 // Fallback
         }
@@ -592,6 +598,29 @@ mod tests {
         assert!(html.contains("<!DOCTYPE html>"));
     }
 
+    #[test]
+    fn test_html_dashboard_without_emoji() {
+        let mut config = DashboardConfig::default();
+        config
+            .alert_thresholds
+            .insert("max_scan_duration_ms".to_string(), 500.0);
+        let mut dashboard = PerformanceDashboard::new(config);
+        dashboard
+            .record_scan_metrics(Duration::from_millis(1000), 100, 5000, 25)
+            .unwrap();
+
+        crate::set_output_theme(crate::ThemeConfig {
+            emoji: false,
+            ..crate::ThemeConfig::default()
+        });
+        let html = dashboard.generate_html_dashboard();
+        crate::set_output_theme(crate::ThemeConfig::default());
+
+        assert!(html.contains("<h3>[warn] Active Alerts</h3>"));
+        assert!(html.contains("<h3>* Performance History</h3>"));
+        assert!(html.is_ascii());
+    }
+
     #[test]
     fn test_json_export() {
         let dashboard = PerformanceDashboard::new_with_defaults();
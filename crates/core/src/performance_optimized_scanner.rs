@@ -1,4 +1,4 @@
-use crate::{Match, PatternDetector};
+use crate::{read_stable, Match, PatternDetector, ScanResult, SharedStr};
 
 /// Metrics for tracking scanning performance
 #[derive(Clone)]
@@ -13,15 +13,11 @@ struct ScanMetrics {
     pattern_search_time: Arc<AtomicUsize>,
     result_processing_time: Arc<AtomicUsize>,
 }
-use anyhow::Result;
 use dashmap::DashMap;
-use ignore::WalkBuilder;
-use memchr::{memchr, memmem};
+use memchr::memchr;
 use once_cell::sync::Lazy;
 use rayon::prelude::*;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::Read;
 use std::path::Path;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -215,7 +211,7 @@ impl PerformanceOptimizedScanner {
     }
 
     /// Ultra-fast scan with all optimizations enabled
-    pub fn scan_ultra_fast(&self, root: &Path) -> Result<(Vec<Match>, AdvancedScanMetrics)> {
+    pub fn scan_ultra_fast(&self, root: &Path) -> ScanResult<(Vec<Match>, AdvancedScanMetrics)> {
         let start_time = Instant::now();
         let files_processed = AtomicUsize::new(0);
         let lines_processed = AtomicUsize::new(0);
@@ -232,8 +228,7 @@ impl PerformanceOptimizedScanner {
         let optimal_batch_size = (num_cpus * 8).clamp(32, 512);
 
         // Collect files first, then process in optimized batches
-        let files: Vec<_> = WalkBuilder::new(root)
-            .standard_filters(true)
+        let files: Vec<_> = crate::scan_walker(root)
             .build()
             .filter_map(|entry| entry.ok())
             .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
@@ -311,7 +306,7 @@ impl PerformanceOptimizedScanner {
 
         // Optimized file reading
         let read_start = Instant::now();
-        let content = self.read_file_optimized(path).ok()?;
+        let content = read_stable(path).ok()?;
         metrics
             .file_read_time
             .fetch_add(read_start.elapsed().as_nanos() as usize, Ordering::Relaxed);
@@ -391,43 +386,8 @@ impl PerformanceOptimizedScanner {
         Some(matches)
     }
 
-    /// Fast file content reading with size-based strategy
-    fn read_file_optimized(&self, path: &Path) -> Result<String> {
-        let metadata = std::fs::metadata(path)?;
-        let file_size = metadata.len();
-
-        if file_size > 4 * 1024 * 1024 {
-            // For large files (>4MB), use memory mapping
-            let file = File::open(path)?;
-            let mmap = unsafe { memmap2::Mmap::map(&file)? };
-            Ok(std::str::from_utf8(&mmap)?.to_string())
-        } else if file_size > 64 * 1024 {
-            // For medium files (>64KB), read with pre-allocated buffer
-            let mut file = File::open(path)?;
-            let mut buffer = String::with_capacity(file_size as usize);
-            file.read_to_string(&mut buffer)?;
-            Ok(buffer)
-        } else {
-            // For small files, use standard reading
-            Ok(std::fs::read_to_string(path)?)
-        }
-    }
-
     /// Optimized file filtering with early rejection
     fn should_scan_file_optimized(&self, path: &Path) -> bool {
-        // Quick string-based filtering first (fastest)
-        if let Some(path_str) = path.to_str() {
-            // Check for common exclusion patterns using byte search
-            if memmem::find(path_str.as_bytes(), b"/target/").is_some()
-                || memmem::find(path_str.as_bytes(), b"/node_modules/").is_some()
-                || memmem::find(path_str.as_bytes(), b"/.git/").is_some()
-                || memmem::find(path_str.as_bytes(), b"/build/").is_some()
-                || memmem::find(path_str.as_bytes(), b"/dist/").is_some()
-            {
-                return false;
-            }
-        }
-
         // File size check (avoid syscall for known small files)
         if let Ok(metadata) = path.metadata() {
             let size = metadata.len();
@@ -504,11 +464,15 @@ impl PerformanceOptimizedScanner {
         let context = &content[start..end];
 
         Some(Match {
-            file_path: path.to_string_lossy().to_string(),
+            file_path: SharedStr::from_path(path),
             line_number,
             column,
-            pattern: pattern.to_string(),
+            pattern: SharedStr::interned(pattern),
             message: format!("{}: {}", pattern, context.trim()),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         })
     }
 
@@ -0,0 +1,346 @@
+//! Detection of likely personal data in code and fixtures: email addresses,
+//! phone numbers, payment card numbers and national ID numbers. Noisy by
+//! nature, so it only runs when enabled in the `pii` config section, and ID
+//! and phone formats are chosen per jurisdiction.
+
+use crate::detectors::has_extension;
+use crate::{Match, PatternDetector, Severity, SharedStr};
+use lazy_static::lazy_static;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Category reported by [`PiiDetector`]
+pub const PRIVACY_CATEGORY: &str = "Privacy";
+
+/// A kind of personal data [`PiiDetector`] looks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiKind {
+    Email,
+    Phone,
+    CreditCard,
+    NationalId,
+}
+
+impl PiiKind {
+    pub fn pattern(&self) -> &'static str {
+        match self {
+            Self::Email => "PII_EMAIL",
+            Self::Phone => "PII_PHONE",
+            Self::CreditCard => "PII_CREDIT_CARD",
+            Self::NationalId => "PII_NATIONAL_ID",
+        }
+    }
+}
+
+/// Country whose national ID and phone number formats are checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Jurisdiction {
+    /// Social Security numbers and NANP phone numbers
+    Us,
+    /// National Insurance numbers and UK phone numbers
+    Uk,
+    /// Social Insurance numbers (Luhn-checked) and NANP phone numbers
+    Ca,
+    /// Aadhaar numbers and Indian mobile numbers
+    In,
+}
+
+/// Settings of the PII check, the `pii` config section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PiiConfig {
+    /// Run the PII check on every scan
+    pub enabled: bool,
+    /// Kinds of personal data to report
+    pub kinds: Vec<PiiKind>,
+    /// Countries whose national ID and phone formats are checked; phone
+    /// numbers in international `+` format are checked regardless
+    pub jurisdictions: Vec<Jurisdiction>,
+}
+
+impl Default for PiiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            kinds: vec![
+                PiiKind::Email,
+                PiiKind::Phone,
+                PiiKind::CreditCard,
+                PiiKind::NationalId,
+            ],
+            jurisdictions: vec![Jurisdiction::Us],
+        }
+    }
+}
+
+lazy_static! {
+    static ref EMAIL_REGEX: Regex = Regex::new(
+        r"\b[A-Za-z0-9._%+-]+@([A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,})\b"
+    ).unwrap();
+
+    /// Domains reserved for documentation and tests, RFC 2606
+    static ref RESERVED_DOMAIN_REGEX: Regex = Regex::new(
+        r"(?i)(?:^|\.)(?:example\.(?:com|org|net)|example|test|invalid|localhost)$"
+    ).unwrap();
+
+    static ref CARD_NUMBER_REGEX: Regex = Regex::new(r"\b\d(?:[ -]?\d){12,18}\b").unwrap();
+
+    static ref INTERNATIONAL_PHONE_REGEX: Regex = Regex::new(
+        r"\+\d{1,3}[ .-]?\(?\d{1,4}\)?(?:[ .-]?\d{2,4}){2,4}\b"
+    ).unwrap();
+
+    static ref NANP_PHONE_REGEX: Regex = Regex::new(
+        r"(?:\(\d{3}\)\s?|\b\d{3}[-.])\d{3}[-.]\d{4}\b"
+    ).unwrap();
+
+    static ref UK_PHONE_REGEX: Regex = Regex::new(r"\b0(?:7\d{3}|\d{4})\s?\d{6}\b").unwrap();
+
+    static ref IN_PHONE_REGEX: Regex = Regex::new(r"\b[6-9]\d{4}[ -]?\d{5}\b").unwrap();
+
+    static ref US_SSN_REGEX: Regex = Regex::new(r"\b(\d{3})-(\d{2})-(\d{4})\b").unwrap();
+
+    static ref UK_NINO_REGEX: Regex = Regex::new(
+        r"\b[A-CEGHJ-PR-TW-Z][A-CEGHJ-NPR-TW-Z] ?\d{2} ?\d{2} ?\d{2} ?[A-D]\b"
+    ).unwrap();
+
+    static ref CA_SIN_REGEX: Regex = Regex::new(r"\b\d{3}[- ]\d{3}[- ]\d{3}\b").unwrap();
+
+    static ref IN_AADHAAR_REGEX: Regex = Regex::new(r"\b[2-9]\d{3} \d{4} \d{4}\b").unwrap();
+}
+
+/// Detector for likely personal data, see the module docs. Values are
+/// masked in messages so reports don't copy the data they warn about.
+pub struct PiiDetector {
+    config: PiiConfig,
+}
+
+impl PiiDetector {
+    pub fn new(config: &PiiConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+
+    fn checks(&self) -> Vec<(PiiKind, &'static Regex, fn(&str) -> bool)> {
+        let mut checks: Vec<(PiiKind, &'static Regex, fn(&str) -> bool)> = Vec::new();
+        for kind in &self.config.kinds {
+            match kind {
+                PiiKind::Email => checks.push((*kind, &EMAIL_REGEX, is_personal_email)),
+                PiiKind::CreditCard => checks.push((*kind, &CARD_NUMBER_REGEX, is_card_number)),
+                PiiKind::Phone => {
+                    checks.push((*kind, &INTERNATIONAL_PHONE_REGEX, any_value));
+                    for jurisdiction in &self.config.jurisdictions {
+                        let re: &'static Regex = match jurisdiction {
+                            Jurisdiction::Us | Jurisdiction::Ca => &NANP_PHONE_REGEX,
+                            Jurisdiction::Uk => &UK_PHONE_REGEX,
+                            Jurisdiction::In => &IN_PHONE_REGEX,
+                        };
+                        if !checks.iter().any(|(_, known, _)| std::ptr::eq(*known, re)) {
+                            checks.push((*kind, re, any_value));
+                        }
+                    }
+                }
+                PiiKind::NationalId => {
+                    for jurisdiction in &self.config.jurisdictions {
+                        checks.push(match jurisdiction {
+                            Jurisdiction::Us => (*kind, &US_SSN_REGEX, is_ssn),
+                            Jurisdiction::Uk => (*kind, &UK_NINO_REGEX, any_value),
+                            Jurisdiction::Ca => (*kind, &CA_SIN_REGEX, passes_luhn),
+                            Jurisdiction::In => (*kind, &IN_AADHAAR_REGEX, any_value),
+                        });
+                    }
+                }
+            }
+        }
+        checks
+    }
+}
+
+impl Default for PiiDetector {
+    fn default() -> Self {
+        Self::new(&PiiConfig::default())
+    }
+}
+
+impl PatternDetector for PiiDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let checks = self.checks();
+        let mut matches = Vec::new();
+        let mut shared_path = None;
+        for (line_idx, line) in content.lines().enumerate() {
+            // Each part of a line is reported once, by the first check matching it
+            let mut claimed: Vec<std::ops::Range<usize>> = Vec::new();
+            for (kind, re, accept) in &checks {
+                for mat in re.find_iter(line) {
+                    if claimed
+                        .iter()
+                        .any(|r| r.start < mat.end() && mat.start() < r.end)
+                        || !accept(mat.as_str())
+                    {
+                        continue;
+                    }
+                    claimed.push(mat.range());
+                    matches.push(Match {
+                        file_path: shared_path
+                            .get_or_insert_with(|| SharedStr::from_path(file_path))
+                            .clone(),
+                        line_number: line_idx + 1,
+                        column: line[..mat.start()].chars().count() + 1,
+                        pattern: SharedStr::interned(kind.pattern()),
+                        message: format!("{}: {}", kind.pattern(), mask(mat.as_str())),
+                        severity: Some(Severity::Medium),
+                        category: Some(PRIVACY_CATEGORY.to_string()),
+                        end: None,
+                        author: None,
+                    });
+                }
+            }
+        }
+        matches.sort_by_key(|m| (m.line_number, m.column));
+        matches
+    }
+
+    /// Everything but lock files, whose hashes and versions look like numbers
+    fn applies_to(&self, file_path: &Path) -> bool {
+        !has_extension(file_path, &["lock", "sum"])
+    }
+
+    fn config_fingerprint(&self) -> String {
+        serde_json::to_string(&self.config).unwrap_or_default()
+    }
+}
+
+fn any_value(_: &str) -> bool {
+    true
+}
+
+fn is_personal_email(email: &str) -> bool {
+    EMAIL_REGEX
+        .captures(email)
+        .is_some_and(|caps| !RESERVED_DOMAIN_REGEX.is_match(&caps[1]))
+}
+
+fn digits(value: &str) -> Vec<u32> {
+    value.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+/// Whether the digits of `value` pass the Luhn checksum used by payment
+/// cards and Canadian SINs
+fn passes_luhn(value: &str) -> bool {
+    let digits = digits(value);
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match i % 2 {
+            0 => d,
+            _ if d * 2 > 9 => d * 2 - 9,
+            _ => d * 2,
+        })
+        .sum();
+    !digits.is_empty() && sum % 10 == 0
+}
+
+/// Luhn-valid numbers of 13 to 19 digits with a known card network prefix
+fn is_card_number(value: &str) -> bool {
+    let digits = digits(value);
+    (13..=19).contains(&digits.len())
+        && matches!(digits[0], 2..=6)
+        && digits.iter().any(|&d| d != digits[0])
+        && passes_luhn(value)
+}
+
+/// SSNs never start with 000, 666 or 9 and have no all-zero group
+fn is_ssn(value: &str) -> bool {
+    let Some(caps) = US_SSN_REGEX.captures(value) else {
+        return false;
+    };
+    let (area, group, serial) = (&caps[1], &caps[2], &caps[3]);
+    area != "000" && area != "666" && !area.starts_with('9') && group != "00" && serial != "0000"
+}
+
+/// `value` with all but its first two and last two letters and digits
+/// replaced by `*`, keeping separators such as `@`, `.` and `-`
+fn mask(value: &str) -> String {
+    let alphanumerics = value.chars().filter(|c| c.is_alphanumeric()).count();
+    let mut seen = 0;
+    value
+        .chars()
+        .map(|c| {
+            if !c.is_alphanumeric() {
+                return c;
+            }
+            seen += 1;
+            if seen <= 2 || seen > alphanumerics.saturating_sub(2) {
+                c
+            } else {
+                '*'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(detector: &PiiDetector, content: &str) -> Vec<(usize, String)> {
+        detector
+            .detect(content, Path::new("fixtures/users.json"))
+            .into_iter()
+            .map(|m| (m.line_number, m.pattern.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_pii_detector() {
+        let content = "{\"email\": \"jane.doe@gmail.com\", \"demo\": \"user@example.com\"}\n{\"card\": \"4111 1111 1111 1111\", \"order\": \"4111 1111 1111 1112\"}\n{\"ssn\": \"123-45-6789\", \"bad\": \"666-45-6789\"}\n{\"phone\": \"+1 415 555 2671\", \"local\": \"(415) 555-2671\"}\n{\"nino\": \"AB 12 34 56 C\"}\n";
+        let detector = PiiDetector::default();
+        assert_eq!(
+            found(&detector, content),
+            vec![
+                (1, "PII_EMAIL".to_string()),
+                (2, "PII_CREDIT_CARD".to_string()),
+                (3, "PII_NATIONAL_ID".to_string()),
+                (4, "PII_PHONE".to_string()),
+                (4, "PII_PHONE".to_string()),
+            ]
+        );
+
+        let matches = detector.detect(content, Path::new("users.json"));
+        assert_eq!(matches[0].message, "PII_EMAIL: ja**.***@*****.*om");
+        assert_eq!(matches[1].message, "PII_CREDIT_CARD: 41** **** **** **11");
+        assert_eq!(matches[0].category.as_deref(), Some("Privacy"));
+
+        let uk = PiiDetector::new(&PiiConfig {
+            enabled: true,
+            kinds: vec![PiiKind::NationalId],
+            jurisdictions: vec![Jurisdiction::Uk],
+        });
+        assert_eq!(
+            found(&uk, content),
+            vec![(5, "PII_NATIONAL_ID".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_luhn() {
+        assert!(passes_luhn("4111 1111 1111 1111"));
+        assert!(passes_luhn("046 454 286"));
+        assert!(!passes_luhn("4111 1111 1111 1112"));
+        assert!(!is_card_number("0000 0000 0000 0000"));
+    }
+
+    #[test]
+    fn test_pii_config_rejects_unknown_jurisdictions() {
+        assert!(serde_json::from_str::<PiiConfig>(r#"{"jurisdictions": ["us", "uk"]}"#).is_ok());
+        assert!(serde_json::from_str::<PiiConfig>(r#"{"jurisdictions": ["mars"]}"#).is_err());
+    }
+}
@@ -0,0 +1,24 @@
+//! The stable library API.
+//!
+//! `use code_guardian_core::prelude::*;` brings in everything needed to
+//! configure a [`Scanner`], choose its detectors, run it and read the
+//! [`ScanReport`]. Items outside the prelude and the documented modules may
+//! change in any release.
+
+pub use crate::config::{load_config, Config};
+pub use crate::detector_factory::DetectorProfile;
+pub use crate::errors::{ScanError, ScanResult};
+pub use crate::feature_flags::FeatureFlagConfig;
+pub use crate::i18n_detectors::I18nConfig;
+pub use crate::line_index::LineIndex;
+pub use crate::match_limits::MatchLimits;
+pub use crate::optimized_scanner::ScanMetrics;
+pub use crate::pii_detectors::{Jurisdiction, PiiConfig, PiiKind};
+pub use crate::quality_detectors::{LengthLimits, LengthLimitsConfig, MagicNumberConfig};
+pub use crate::registry::DetectorRegistry;
+pub use crate::report::ScanReport;
+pub use crate::rule_scopes::{RuleScope, RuleScopes};
+pub use crate::shared_str::SharedStr;
+pub use crate::skipped::{SkipReason, SkippedFile};
+pub use crate::webhook::ScanSummary;
+pub use crate::{Match, PatternDetector, Scanner, ScannerBuilder, Severity};
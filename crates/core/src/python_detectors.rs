@@ -0,0 +1,351 @@
+//! Python-specific detectors: printing outside command-line entry points,
+//! leftover debugger imports, broad `except` clauses, mutable default
+//! arguments and `assert` used to validate input at runtime
+
+use crate::comment_spans::CommentIndex;
+use crate::detectors::{
+    build_glob_set, classify, detect_code_pattern, has_extension, DEFAULT_TEST_PATH_GLOBS,
+};
+use crate::line_index::LineIndex;
+use crate::quality_detectors::CODE_QUALITY_CATEGORY;
+use crate::{Match, PatternDetector, Severity, SharedStr};
+use globset::GlobSet;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::Path;
+
+lazy_static! {
+    static ref TEST_PATHS: GlobSet =
+        build_glob_set(DEFAULT_TEST_PATH_GLOBS).expect("default test globs are valid");
+
+    static ref PRINT_CALL_REGEX: Regex = Regex::new(r"\bprint\s*\(").unwrap();
+
+    /// A `__main__` guard or an argument parsing library marks a script
+    static ref ENTRY_POINT_REGEX: Regex = Regex::new(
+        r#"(?m)^if\s+__name__\s*==\s*["']__main__["']|^(?:import|from)\s+(?:argparse|click|typer)\b"#
+    ).unwrap();
+
+    /// Debugger imports, and `set_trace()` of the debuggers `DEBUGGER`
+    /// does not know about
+    static ref DEBUGGER_IMPORT_REGEX: Regex = Regex::new(
+        r"(?m)^[ \t]*(?:import[ \t]+(?:i|pu)?pdb\b|from[ \t]+(?:i|pu)?pdb[ \t]+import\b)|\b(?:ipdb|pudb)\.set_trace\(|(?:^|[^\w.])set_trace\(\)"
+    ).unwrap();
+
+    /// `except:`, and handlers for `Exception` or `BaseException`
+    static ref BROAD_EXCEPT_REGEX: Regex = Regex::new(
+        r"(?m)^[ \t]*except(?:[ \t]*:|[ \t]+\(?[ \t]*(?:Exception|BaseException)\b)"
+    ).unwrap();
+
+    static ref RAISE_REGEX: Regex = Regex::new(r"^raise\b").unwrap();
+
+    static ref DEF_REGEX: Regex = Regex::new(r"(?m)^[ \t]*(?:async[ \t]+)?def[ \t]+\w+[ \t]*\(").unwrap();
+
+    /// A list, dict or set literal, or an empty one built by a call
+    static ref MUTABLE_DEFAULT_REGEX: Regex = Regex::new(
+        r"=[ \t]*(?:\[|\{|(?:list|dict|set|defaultdict|deque)\(\))"
+    ).unwrap();
+
+    static ref ASSERT_REGEX: Regex = Regex::new(r"(?m)^[ \t]*assert\b").unwrap();
+}
+
+fn is_python_test(file_path: &Path) -> bool {
+    TEST_PATHS.is_match(file_path)
+}
+
+/// Width of the indentation of `line`
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Detector for `print(` in modules that are not command-line entry points,
+/// whose output belongs to the caller and should go through `logging`.
+/// `__main__.py`, modules with a `__main__` guard and modules importing
+/// `argparse`, `click` or `typer` are entry points; tests are skipped.
+pub struct PythonPrintDetector;
+
+impl PatternDetector for PythonPrintDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path)
+            || is_python_test(file_path)
+            || file_path
+                .file_name()
+                .is_some_and(|name| name == "__main__.py")
+            || ENTRY_POINT_REGEX.is_match(content)
+        {
+            return Vec::new();
+        }
+        let index = LineIndex::new(content);
+        let mut matches = detect_code_pattern(content, file_path, "PY_PRINT", &PRINT_CALL_REGEX);
+        // `logger.print(` and `self.print(` are methods, not the builtin
+        matches.retain(|m| {
+            let offset = index.offset(content, m.line_number, m.column);
+            !content[..offset].ends_with('.')
+        });
+        classify(matches, Severity::Medium, CODE_QUALITY_CATEGORY)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["py"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&PRINT_CALL_REGEX)
+    }
+}
+
+/// Detector for `import pdb` and the like, and for `ipdb`, `pudb` and
+/// imported `set_trace()` breakpoints. `pdb.set_trace()` and `breakpoint()`
+/// are reported by the language-neutral `DEBUGGER` check.
+pub struct PythonDebuggerDetector;
+
+impl PatternDetector for PythonDebuggerDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        classify(
+            detect_code_pattern(content, file_path, "PY_DEBUGGER", &DEBUGGER_IMPORT_REGEX),
+            Severity::High,
+            CODE_QUALITY_CATEGORY,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["py"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&DEBUGGER_IMPORT_REGEX)
+    }
+}
+
+/// Detector for bare `except:` and handlers catching `Exception` or
+/// `BaseException`, which also catch bugs such as a `NameError`. Handlers
+/// that re-raise are left alone.
+pub struct BroadExceptDetector;
+
+impl PatternDetector for BroadExceptDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let mut matches =
+            detect_code_pattern(content, file_path, "PY_BROAD_EXCEPT", &BROAD_EXCEPT_REGEX);
+        if matches.is_empty() {
+            return matches;
+        }
+        let lines: Vec<&str> = content.lines().collect();
+        matches.retain(|m| {
+            let handler = lines[m.line_number - 1];
+            // A one-line handler, `except Exception: raise`
+            if let Some((_, body)) = handler.split_once(':') {
+                if RAISE_REGEX.is_match(body.trim()) {
+                    return false;
+                }
+            }
+            let mut body = lines[m.line_number..]
+                .iter()
+                .map(|line| line.trim_end())
+                .filter(|line| !line.trim().is_empty() && !line.trim().starts_with('#'))
+                .take_while(|line| indent(line) > indent(handler));
+            !body.any(|line| RAISE_REGEX.is_match(line.trim()))
+        });
+        classify(matches, Severity::Medium, CODE_QUALITY_CATEGORY)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["py"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&BROAD_EXCEPT_REGEX)
+    }
+}
+
+/// Detector for default arguments that are lists, dicts or sets. Defaults
+/// are evaluated once, so every call shares and mutates the same object.
+pub struct MutableDefaultArgumentDetector;
+
+impl PatternDetector for MutableDefaultArgumentDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let comments = CommentIndex::new(content, file_path);
+        let index = LineIndex::new(content);
+        let shared_path = SharedStr::from_path(file_path);
+        let mut matches = Vec::new();
+        for def in DEF_REGEX.find_iter(content) {
+            if comments.is_comment(def.end() - 1) {
+                continue;
+            }
+            // The parameters run to the parenthesis closing the one opened
+            // by `def`; defaults may hold brackets of their own
+            let mut depth = 1usize;
+            let mut params_end = content.len();
+            for (i, b) in content.bytes().enumerate().skip(def.end()) {
+                match b {
+                    b'(' | b'[' | b'{' => depth += 1,
+                    b')' | b']' | b'}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            params_end = i;
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            let params = &content[def.end()..params_end];
+            for default in MUTABLE_DEFAULT_REGEX.find_iter(params) {
+                let start = def.end() + default.start() + 1;
+                let start = start + content[start..].len() - content[start..].trim_start().len();
+                if comments.is_comment(start) {
+                    continue;
+                }
+                let (line_number, column) = index.position(content, start);
+                matches.push(Match {
+                    file_path: shared_path.clone(),
+                    line_number,
+                    column,
+                    pattern: SharedStr::interned("PY_MUTABLE_DEFAULT"),
+                    message: format!(
+                        "PY_MUTABLE_DEFAULT: {} is shared by every call",
+                        index.line(content, line_number).trim()
+                    ),
+                    severity: None,
+                    category: None,
+                    end: None,
+                    author: None,
+                });
+            }
+        }
+        classify(matches, Severity::Medium, CODE_QUALITY_CATEGORY)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["py"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&MUTABLE_DEFAULT_REGEX)
+    }
+}
+
+/// Detector for `assert` outside tests. `python -O` strips assertions, so
+/// checks of input or state made with them silently stop running.
+pub struct PythonAssertDetector;
+
+impl PatternDetector for PythonAssertDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) || is_python_test(file_path) {
+            return Vec::new();
+        }
+        classify(
+            detect_code_pattern(content, file_path, "PY_ASSERT", &ASSERT_REGEX),
+            Severity::Medium,
+            CODE_QUALITY_CATEGORY,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["py"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&ASSERT_REGEX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(detector: &dyn PatternDetector, content: &str, path: &str) -> Vec<usize> {
+        detector
+            .detect(content, Path::new(path))
+            .iter()
+            .map(|m| m.line_number)
+            .collect()
+    }
+
+    const SERVICE: &str = r#"import logging
+import pdb
+from ipdb import set_trace
+
+def load(path, cache={}, *, tags=[]):
+    # print("loading", path)
+    assert path, "path is required"
+    print("loading", path)
+    logger.print(path)
+    try:
+        return read(path, opts=dict())
+    except Exception as e:
+        log(e)
+    except:
+        set_trace()
+        raise
+
+def merge(a, b=None, c=(1, 2)):
+    try:
+        return a + b
+    except (Exception, KeyError):
+        raise ValueError("cannot merge")
+"#;
+
+    #[test]
+    fn test_python_print_detector() {
+        assert_eq!(
+            lines(&PythonPrintDetector, SERVICE, "app/service.py"),
+            vec![8]
+        );
+        let script = format!(
+            "{}\nif __name__ == \"__main__\":\n    load(\"x\")\n",
+            SERVICE
+        );
+        assert!(lines(&PythonPrintDetector, &script, "app/service.py").is_empty());
+        assert!(lines(&PythonPrintDetector, SERVICE, "app/__main__.py").is_empty());
+        assert!(lines(&PythonPrintDetector, SERVICE, "tests/test_service.py").is_empty());
+        assert_eq!(
+            lines(&PythonPrintDetector, "café = 1; print(café)\n", "menu.py"),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn test_python_debugger_detector() {
+        assert_eq!(
+            lines(&PythonDebuggerDetector, SERVICE, "service.py"),
+            vec![2, 3, 15]
+        );
+    }
+
+    #[test]
+    fn test_broad_except_detector() {
+        assert_eq!(lines(&BroadExceptDetector, SERVICE, "service.py"), vec![12]);
+        let one_line = "try:\n    run()\nexcept BaseException: raise\n";
+        assert!(lines(&BroadExceptDetector, one_line, "run.py").is_empty());
+    }
+
+    #[test]
+    fn test_mutable_default_argument_detector() {
+        let matches = MutableDefaultArgumentDetector.detect(SERVICE, Path::new("service.py"));
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.line_number, m.column))
+                .collect::<Vec<_>>(),
+            vec![(5, 22), (5, 34)]
+        );
+        assert_eq!(
+            matches[0].message,
+            "PY_MUTABLE_DEFAULT: def load(path, cache={}, *, tags=[]): is shared by every call"
+        );
+    }
+
+    #[test]
+    fn test_python_assert_detector() {
+        assert_eq!(lines(&PythonAssertDetector, SERVICE, "service.py"), vec![7]);
+        assert!(lines(&PythonAssertDetector, SERVICE, "tests/test_service.py").is_empty());
+    }
+}
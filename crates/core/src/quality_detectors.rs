@@ -0,0 +1,907 @@
+//! Code quality detectors: maintainability and reliability issues rather
+//! than outright bugs, reported under the `CodeQuality` category
+
+use crate::comment_spans::CommentIndex;
+use crate::detectors::{
+    build_glob_set, classify, detect_code_pattern, has_extension, CODE_EXTENSIONS,
+    DEFAULT_TEST_PATH_GLOBS,
+};
+use crate::line_index::LineIndex;
+use crate::{Match, PatternDetector, Severity, SharedStr};
+use globset::GlobSet;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Category reported by the detectors of this module
+pub const CODE_QUALITY_CATEGORY: &str = "CodeQuality";
+
+/// Type suffixes a numeric literal may carry, as in `7u32` or `1.5f`
+const TYPE_SUFFIXES: &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", "f32",
+    "f64", "f", "d", "l", "L", "u", "U", "n",
+];
+
+/// Values common enough not to need a name, on top of those below `min_value`
+const COMMON_NUMBERS: &[f64] = &[2.0, 10.0, 24.0, 60.0, 100.0, 1000.0, 1024.0];
+
+/// Built-in length limits of languages whose idioms differ from the general
+/// defaults: extensions, file lines and function lines
+const LANGUAGE_LENGTH_LIMITS: &[(&[&str], usize, usize)] = &[
+    (&["py", "rb"], 600, 50),
+    (&["js", "jsx", "mjs", "cjs", "ts", "tsx"], 600, 60),
+    (&["java", "kt", "kts", "scala", "cs"], 1500, 60),
+    (&["c", "h", "cpp", "cc", "cxx", "hpp"], 2000, 100),
+];
+
+/// Words that may precede `name(` in C-like code without declaring a function
+const NON_FUNCTION_WORDS: &[&str] = &[
+    "if",
+    "for",
+    "while",
+    "switch",
+    "catch",
+    "return",
+    "new",
+    "else",
+    "throw",
+    "case",
+    "await",
+    "delete",
+    "sizeof",
+    "synchronized",
+    "using",
+    "lock",
+    "foreach",
+    "yield",
+    "goto",
+];
+
+lazy_static! {
+    static ref NUMBER_REGEX: Regex = Regex::new(
+        r"0[xX][0-9a-fA-F_]+|\d[\d_]*(?:\.\d[\d_]*)?(?:[eE][+-]?\d+)?"
+    ).unwrap();
+
+    /// Lines declaring a named constant, which is where literals belong
+    static ref CONSTANT_DECLARATION_REGEX: Regex = Regex::new(
+        r"^\s*(?:#define\b|#\[|@)|\b(?:const|static|final|readonly|enum)\b|^\s*(?:export\s+)?[A-Z][A-Z0-9_]*\s*(?::[^=]*)?="
+    ).unwrap();
+
+    static ref STRING_LITERAL_REGEX: Regex = Regex::new(
+        r#""(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*'|`(?:[^`\\]|\\.)*`"#
+    ).unwrap();
+
+    /// Rust strings only; `'` also starts lifetimes there
+    static ref RUST_STRING_LITERAL_REGEX: Regex = Regex::new(
+        r#""(?:[^"\\]|\\.)*""#
+    ).unwrap();
+
+    /// Empty `catch` blocks, with or without a parameter, and `.catch(() => {})`
+    static ref EMPTY_CATCH_REGEX: Regex = Regex::new(
+        r"\bcatch\s*(?:\([^)]*\))?\s*\{\s*\}|\.catch\(\s*(?:\(\s*\w*\s*\)|\w+)\s*=>\s*\{\s*\}\s*\)"
+    ).unwrap();
+
+    /// Bare `except:` and handlers whose whole body is `pass`
+    static ref PYTHON_SWALLOWED_REGEX: Regex = Regex::new(
+        r"(?m)^[ \t]*except[ \t]*:|^[ \t]*except\b[^:\n]*:[ \t]*(?:\n[ \t]*)?pass\b"
+    ).unwrap();
+
+    /// Results discarded with `let _ = ...;` or `.ok();`
+    static ref RUST_DISCARDED_RESULT_REGEX: Regex = Regex::new(
+        r"\blet\s+_\s*=\s*[^;]+;|\.ok\(\)\s*;"
+    ).unwrap();
+
+    static ref RUST_FN_REGEX: Regex = Regex::new(r"\bfn\s+(?P<name>\w+)").unwrap();
+
+    static ref GO_FUNC_REGEX: Regex = Regex::new(
+        r"(?m)^func\s+(?:\([^)]*\)\s*)?(?P<name>\w+)"
+    ).unwrap();
+
+    /// `function` declarations and expressions, and arrow functions with a
+    /// block body assigned to a name
+    static ref JS_FUNCTION_REGEX: Regex = Regex::new(
+        r"\bfunction\b\s*\*?\s*(?P<name>[\w$]*)\s*\(|(?P<arrow>[\w$]+)\s*[:=]\s*(?:async\s*)?(?:\([^()]*\)|[\w$]+)\s*=>\s*\{"
+    ).unwrap();
+
+    static ref KOTLIN_FUN_REGEX: Regex = Regex::new(
+        r"\bfun\s+(?:<[^>]*>\s*)?(?:[\w.]+\.)?(?P<name>\w+)"
+    ).unwrap();
+
+    static ref SWIFT_FUNC_REGEX: Regex = Regex::new(r"\bfunc\s+(?P<name>\w+)").unwrap();
+
+    static ref SCALA_DEF_REGEX: Regex = Regex::new(r"\bdef\s+(?P<name>\w+)").unwrap();
+
+    static ref PHP_FUNCTION_REGEX: Regex = Regex::new(r"\bfunction\s+&?(?P<name>\w+)").unwrap();
+
+    /// Declarations such as `public static int parse(` in Java, C#, C and C++:
+    /// at least one type or modifier word, then the name
+    static ref C_LIKE_FUNCTION_REGEX: Regex = Regex::new(
+        r"(?m)^[ \t]*(?P<lead>(?:[\w<>\[\],.*&:~?]+[ \t]+)+)[*&]*(?P<name>~?\w+)[ \t]*\("
+    ).unwrap();
+
+    static ref PYTHON_DEF_REGEX: Regex = Regex::new(
+        r"(?m)^(?P<indent>[ \t]*)(?:async[ \t]+)?def[ \t]+(?P<name>\w+)"
+    ).unwrap();
+
+    static ref RUBY_DEF_REGEX: Regex = Regex::new(
+        r"(?m)^(?P<indent>[ \t]*)def[ \t]+(?P<name>[\w.]+[?!=]?)"
+    ).unwrap();
+}
+
+/// Settings of the `MAGIC_NUMBER` check, the `magic_numbers` config section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct MagicNumberConfig {
+    /// Literals whose absolute value is below this are never reported
+    pub min_value: f64,
+    /// Further values that are fine as literals, e.g. `3600` or `0.75`
+    pub allowlist: Vec<f64>,
+}
+
+impl Default for MagicNumberConfig {
+    fn default() -> Self {
+        Self {
+            min_value: 2.0,
+            allowlist: Vec::new(),
+        }
+    }
+}
+
+/// Line limits of one language. An unset limit falls back to the built-in
+/// default of the language, then to the general default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LengthLimits {
+    /// Lines a file may have
+    pub file_lines: Option<usize>,
+    /// Lines a function may span, from its signature to its last line
+    pub function_lines: Option<usize>,
+}
+
+/// Settings of the `FILE_TOO_LONG` and `FUNCTION_TOO_LONG` checks, the
+/// `length_limits` config section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LengthLimitsConfig {
+    /// Lines a file may have, for languages without a limit of their own
+    pub file_lines: usize,
+    /// Lines a function may span, for languages without a limit of their own
+    pub function_lines: usize,
+    /// Limits by file extension, such as `py`, overriding the built-in
+    /// defaults of Python and Ruby (600 and 50), JavaScript and TypeScript
+    /// (600 and 60), Java, Kotlin, Scala and C# (1500 and 60), and C and C++
+    /// (2000 and 100)
+    pub languages: BTreeMap<String, LengthLimits>,
+}
+
+impl Default for LengthLimitsConfig {
+    fn default() -> Self {
+        Self {
+            file_lines: 1000,
+            function_lines: 80,
+            languages: BTreeMap::new(),
+        }
+    }
+}
+
+impl LengthLimitsConfig {
+    /// File and function line limits of files with the extension `ext`
+    pub fn limits_for(&self, ext: &str) -> (usize, usize) {
+        let ext = ext.to_ascii_lowercase();
+        let configured = self.languages.get(&ext).copied().unwrap_or_default();
+        let builtin = LANGUAGE_LENGTH_LIMITS
+            .iter()
+            .find(|(extensions, _, _)| extensions.contains(&ext.as_str()));
+        (
+            configured
+                .file_lines
+                .or(builtin.map(|limits| limits.1))
+                .unwrap_or(self.file_lines),
+            configured
+                .function_lines
+                .or(builtin.map(|limits| limits.2))
+                .unwrap_or(self.function_lines),
+        )
+    }
+
+    fn limits_for_path(&self, file_path: &Path) -> (usize, usize) {
+        self.limits_for(
+            file_path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or(""),
+        )
+    }
+}
+
+/// Detector for unexplained numeric literals in code, such as
+/// `sleep(86400)`. Declarations of named constants, comments, strings, test
+/// files and Rust `#[cfg(test)]` modules are left alone, as are `0`, `1` and
+/// common values like `100`. Reports the first such literal of each line.
+pub struct MagicNumberDetector {
+    config: MagicNumberConfig,
+    test_paths: GlobSet,
+}
+
+impl MagicNumberDetector {
+    pub fn new(config: &MagicNumberConfig) -> Self {
+        Self {
+            config: config.clone(),
+            test_paths: build_glob_set(DEFAULT_TEST_PATH_GLOBS)
+                .expect("default test globs are valid"),
+        }
+    }
+
+    /// Whether `literal` is a value worth naming
+    fn is_magic(&self, literal: &str) -> bool {
+        let Some(value) = parse_number(literal) else {
+            return false;
+        };
+        let value = value.abs();
+        value >= self.config.min_value
+            && !COMMON_NUMBERS.contains(&value)
+            && !self
+                .config
+                .allowlist
+                .iter()
+                .any(|allowed| allowed.abs() == value)
+    }
+
+    /// Byte offset in `line` and text of the first magic literal, skipping
+    /// string literals
+    fn find_in_line<'a>(&self, line: &'a str, rust: bool) -> Option<(usize, &'a str)> {
+        let strings = if rust {
+            &*RUST_STRING_LITERAL_REGEX
+        } else {
+            &*STRING_LITERAL_REGEX
+        };
+        let strings: Vec<_> = strings.find_iter(line).map(|m| m.range()).collect();
+        let bytes = line.as_bytes();
+        NUMBER_REGEX.find_iter(line).find_map(|mat| {
+            let before = mat.start().checked_sub(1).map(|i| bytes[i]);
+            if before.is_some_and(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'.') {
+                return None;
+            }
+            // A type suffix such as `u32` may follow, but not a version's `.2`
+            let rest = &line[mat.end()..];
+            let suffix = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            if suffix > 0 && !TYPE_SUFFIXES.contains(&&rest[..suffix]) {
+                return None;
+            }
+            let mut after = rest[suffix..].chars();
+            if after.next() == Some('.') && after.next().is_some_and(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            if strings.iter().any(|s| s.contains(&mat.start())) {
+                return None;
+            }
+            self.is_magic(mat.as_str())
+                .then(|| (mat.start(), mat.as_str()))
+        })
+    }
+}
+
+impl Default for MagicNumberDetector {
+    fn default() -> Self {
+        Self::new(&MagicNumberConfig::default())
+    }
+}
+
+impl PatternDetector for MagicNumberDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) || self.test_paths.is_match(file_path) {
+            return Vec::new();
+        }
+        let rust = has_extension(file_path, &["rs"]);
+        let mut comments = None;
+        let mut matches = Vec::new();
+        let mut shared_path = None;
+        let pattern = SharedStr::interned("MAGIC_NUMBER");
+        for (line_idx, line) in content.lines().enumerate() {
+            if rust && line.trim_start().starts_with("#[cfg(test)]") {
+                break;
+            }
+            if CONSTANT_DECLARATION_REGEX.is_match(line) {
+                continue;
+            }
+            let Some((start, literal)) = self.find_in_line(line, rust) else {
+                continue;
+            };
+            if comments
+                .get_or_insert_with(|| CommentIndex::new(content, file_path))
+                .is_comment(line.as_ptr() as usize - content.as_ptr() as usize + start)
+            {
+                continue;
+            }
+            matches.push(Match {
+                file_path: shared_path
+                    .get_or_insert_with(|| SharedStr::from_path(file_path))
+                    .clone(),
+                line_number: line_idx + 1,
+                column: line[..start].chars().count() + 1,
+                pattern: pattern.clone(),
+                message: format!("MAGIC_NUMBER: {} in {}", literal, line.trim()),
+                severity: Some(Severity::Low),
+                category: Some(CODE_QUALITY_CATEGORY.to_string()),
+                end: None,
+                author: None,
+            });
+        }
+        matches
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, CODE_EXTENSIONS)
+    }
+
+    fn config_fingerprint(&self) -> String {
+        serde_json::to_string(&self.config).unwrap_or_default()
+    }
+}
+
+/// Detector for errors that are caught or discarded and then ignored:
+/// empty `catch` blocks (JavaScript, TypeScript, Java, C# and the like), bare
+/// `except:` and `except ...: pass` in Python, and `let _ = ...;` or
+/// `.ok();` in Rust. Each language gets its own pattern.
+pub struct ErrorSwallowedDetector;
+
+impl ErrorSwallowedDetector {
+    fn regex_for(file_path: &Path) -> Option<&'static Regex> {
+        if has_extension(file_path, &["py"]) {
+            Some(&PYTHON_SWALLOWED_REGEX)
+        } else if has_extension(file_path, &["rs"]) {
+            Some(&RUST_DISCARDED_RESULT_REGEX)
+        } else if has_extension(
+            file_path,
+            &[
+                "js", "jsx", "mjs", "cjs", "ts", "tsx", "vue", "svelte", "java", "kt", "kts",
+                "scala", "cs", "php", "swift", "dart",
+            ],
+        ) {
+            Some(&EMPTY_CATCH_REGEX)
+        } else {
+            None
+        }
+    }
+}
+
+impl PatternDetector for ErrorSwallowedDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        let Some(re) = Self::regex_for(file_path) else {
+            return Vec::new();
+        };
+        let mut matches = detect_code_pattern(content, file_path, "ERROR_SWALLOWED", re);
+        if has_extension(file_path, &["rs"]) {
+            // `let value = parse(s).ok();` keeps the value, so only
+            // statements that drop the `Option` count
+            let lines: Vec<&str> = content.lines().collect();
+            matches.retain(|m| {
+                !m.message.contains(".ok()")
+                    || !lines[m.line_number - 1]
+                        .chars()
+                        .take(m.column - 1)
+                        .any(|c| c == '=')
+            });
+        }
+        classify(matches, Severity::Medium, CODE_QUALITY_CATEGORY)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        Self::regex_for(file_path).is_some()
+    }
+}
+
+/// Detector for source files longer than their language's limit, see
+/// [`LengthLimitsConfig`]. Reports once per file, on its first line.
+#[derive(Default)]
+pub struct FileLengthDetector {
+    config: LengthLimitsConfig,
+}
+
+impl FileLengthDetector {
+    pub fn new(config: &LengthLimitsConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+}
+
+impl PatternDetector for FileLengthDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let (limit, _) = self.config.limits_for_path(file_path);
+        let lines = LineIndex::new(content).line_count();
+        if lines <= limit {
+            return Vec::new();
+        }
+        vec![Match {
+            file_path: SharedStr::from_path(file_path),
+            line_number: 1,
+            column: 1,
+            pattern: SharedStr::interned("FILE_TOO_LONG"),
+            message: format!("FILE_TOO_LONG: {} lines, limit {}", lines, limit),
+            severity: Some(Severity::Low),
+            category: Some(CODE_QUALITY_CATEGORY.to_string()),
+            end: None,
+            author: None,
+        }]
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, CODE_EXTENSIONS)
+    }
+
+    fn config_fingerprint(&self) -> String {
+        serde_json::to_string(&self.config).unwrap_or_default()
+    }
+}
+
+/// Detector for functions spanning more lines than their language's limit,
+/// see [`LengthLimitsConfig`]. Functions are found with per-language
+/// patterns: braces delimit bodies in most languages, indentation in Python
+/// and `end` in Ruby. Nested functions are measured on their own as well.
+#[derive(Default)]
+pub struct FunctionLengthDetector {
+    config: LengthLimitsConfig,
+}
+
+impl FunctionLengthDetector {
+    pub fn new(config: &LengthLimitsConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+}
+
+impl PatternDetector for FunctionLengthDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let (_, limit) = self.config.limits_for_path(file_path);
+        let index = LineIndex::new(content);
+        let mut matches = Vec::new();
+        let mut shared_path = None;
+        for function in function_spans(content, file_path) {
+            let (line_number, column) = index.position(content, function.start);
+            let lines = index.line_of(function.end.saturating_sub(1)) - line_number + 1;
+            if lines <= limit {
+                continue;
+            }
+            let name = if function.name.is_empty() {
+                "anonymous function"
+            } else {
+                function.name
+            };
+            matches.push(Match {
+                file_path: shared_path
+                    .get_or_insert_with(|| SharedStr::from_path(file_path))
+                    .clone(),
+                line_number,
+                column,
+                pattern: SharedStr::interned("FUNCTION_TOO_LONG"),
+                message: format!(
+                    "FUNCTION_TOO_LONG: {} spans {} lines, limit {}",
+                    name, lines, limit
+                ),
+                severity: Some(Severity::Medium),
+                category: Some(CODE_QUALITY_CATEGORY.to_string()),
+                end: index.span_end(content, function.start, function.end),
+                author: None,
+            });
+        }
+        matches
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, CODE_EXTENSIONS)
+    }
+
+    fn config_fingerprint(&self) -> String {
+        serde_json::to_string(&self.config).unwrap_or_default()
+    }
+}
+
+/// A function found by [`function_spans`]
+pub(crate) struct FunctionSpan<'a> {
+    pub(crate) name: &'a str,
+    /// Byte offset of the name, or of the `function` keyword if anonymous
+    pub(crate) start: usize,
+    /// Byte offset just past the last line of the body
+    pub(crate) end: usize,
+}
+
+/// Functions declared in `content`, read as the language of `file_path`
+pub(crate) fn function_spans<'a>(content: &'a str, file_path: &Path) -> Vec<FunctionSpan<'a>> {
+    let comments = CommentIndex::new(content, file_path);
+    if has_extension(file_path, &["py"]) {
+        return PYTHON_DEF_REGEX
+            .captures_iter(content)
+            .filter(|caps| {
+                !comments.is_comment(caps["name"].as_ptr() as usize - content.as_ptr() as usize)
+            })
+            .filter_map(|caps| python_function(content, &caps))
+            .collect();
+    }
+    if has_extension(file_path, &["rb"]) {
+        return RUBY_DEF_REGEX
+            .captures_iter(content)
+            .filter(|caps| {
+                !comments.is_comment(caps["name"].as_ptr() as usize - content.as_ptr() as usize)
+            })
+            .filter_map(|caps| ruby_function(content, &caps))
+            .collect();
+    }
+    let Some(re) = function_header_regex(file_path) else {
+        return Vec::new();
+    };
+    let headers: Vec<Captures> = re
+        .captures_iter(content)
+        .filter(|caps| {
+            let whole = caps.get(0).expect("group 0 always matches");
+            !comments.is_comment(whole.start())
+                && caps.name("lead").map_or(true, |lead| {
+                    !lead
+                        .as_str()
+                        .split_whitespace()
+                        .any(|word| NON_FUNCTION_WORDS.contains(&word))
+                })
+                && caps
+                    .name("name")
+                    .map_or(true, |name| !NON_FUNCTION_WORDS.contains(&name.as_str()))
+        })
+        .collect();
+    let mut functions = Vec::new();
+    for (i, caps) in headers.iter().enumerate() {
+        let whole = caps.get(0).expect("group 0 always matches");
+        // The body must open before the next function does
+        let limit = headers.get(i + 1).map_or(content.len(), |next| {
+            next.get(0).expect("group 0 always matches").start()
+        });
+        let from = if whole.as_str().ends_with('{') {
+            whole.end() - 1
+        } else {
+            whole.end()
+        };
+        let Some(open) = body_start(content, from, limit, &comments) else {
+            continue;
+        };
+        let name = caps
+            .name("name")
+            .filter(|name| !name.as_str().is_empty())
+            .or_else(|| caps.name("arrow"));
+        functions.push(FunctionSpan {
+            name: name.map_or("", |name| name.as_str()),
+            start: name.map_or(whole.start(), |name| name.start()),
+            end: block_end(content, open, &comments),
+        });
+    }
+    functions
+}
+
+/// The innermost of `functions` around `offset`, for closures and nested
+/// functions
+pub(crate) fn enclosing_function<'a, 'b>(
+    functions: &'b [FunctionSpan<'a>],
+    offset: usize,
+) -> Option<&'b FunctionSpan<'a>> {
+    functions
+        .iter()
+        .filter(|f| f.start <= offset && offset < f.end)
+        .min_by_key(|f| f.end - f.start)
+}
+
+fn function_header_regex(file_path: &Path) -> Option<&'static Regex> {
+    let ext = file_path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match ext.as_str() {
+        "rs" => &RUST_FN_REGEX,
+        "go" => &GO_FUNC_REGEX,
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => &JS_FUNCTION_REGEX,
+        "kt" | "kts" => &KOTLIN_FUN_REGEX,
+        "swift" => &SWIFT_FUNC_REGEX,
+        "scala" => &SCALA_DEF_REGEX,
+        "php" => &PHP_FUNCTION_REGEX,
+        "java" | "cs" | "c" | "h" | "cpp" | "cc" | "cxx" | "hpp" | "dart" => &C_LIKE_FUNCTION_REGEX,
+        _ => return None,
+    })
+}
+
+/// Offset of the `{` opening the body of a function whose signature goes on
+/// from `from`, or `None` for a declaration without a body: one ending with
+/// `;`, or with `=` for expression bodies, before any `{` outside brackets
+fn body_start(content: &str, from: usize, limit: usize, comments: &CommentIndex) -> Option<usize> {
+    let bytes = content.as_bytes();
+    let mut depth = 0usize;
+    for i in from..limit {
+        if comments.is_comment(i) {
+            continue;
+        }
+        match bytes[i] {
+            b'(' | b'[' => depth += 1,
+            b')' | b']' => depth = depth.saturating_sub(1),
+            b'{' if depth == 0 => return Some(i),
+            b';' | b'}' if depth == 0 => return None,
+            b'=' if depth == 0 && bytes.get(i + 1) != Some(&b'>') => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Offset just past the `}` matching the `{` at `open`, skipping comments,
+/// string literals and char literals, or the end of `content`
+pub(crate) fn block_end(content: &str, open: usize, comments: &CommentIndex) -> usize {
+    let bytes = content.as_bytes();
+    let mut spans = comments
+        .spans()
+        .iter()
+        .skip_while(|span| span.end <= open)
+        .peekable();
+    let mut depth = 0usize;
+    let mut i = open;
+    while i < bytes.len() {
+        if let Some(span) = spans.next_if(|span| span.start <= i) {
+            i = i.max(span.end);
+            continue;
+        }
+        match bytes[i] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' && bytes[i] != b'\n' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'\'' if bytes.get(i + 1) == Some(&b'\\') || bytes.get(i + 2) == Some(&b'\'') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'\'' && bytes[i] != b'\n' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// A Python `def`: the signature runs until its parentheses close, and the
+/// body is the lines after it indented deeper than the `def`
+fn python_function<'a>(content: &'a str, caps: &Captures<'a>) -> Option<FunctionSpan<'a>> {
+    let name = caps.name("name")?;
+    let indent = caps["indent"].len();
+    let mut depth = 0usize;
+    let mut signature_end = None;
+    for (i, b) in content.bytes().enumerate().skip(name.end()) {
+        match b {
+            b'(' | b'[' => depth += 1,
+            b')' | b']' => depth = depth.saturating_sub(1),
+            b'\n' if depth == 0 => {
+                signature_end = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let mut end = signature_end.unwrap_or(content.len());
+    let mut offset = end + 1;
+    for line in content.get(offset..).unwrap_or("").split_inclusive('\n') {
+        let code = line.trim();
+        if !code.is_empty() && !code.starts_with('#') {
+            if line.len() - line.trim_start().len() <= indent {
+                break;
+            }
+            end = offset + line.trim_end().len();
+        }
+        offset += line.len();
+    }
+    Some(FunctionSpan {
+        name: name.as_str(),
+        start: name.start(),
+        end,
+    })
+}
+
+/// A Ruby `def`, ending at the first `end` indented like it
+fn ruby_function<'a>(content: &'a str, caps: &Captures<'a>) -> Option<FunctionSpan<'a>> {
+    let name = caps.name("name")?;
+    let indent = &caps["indent"];
+    let mut offset = content[name.end()..].find('\n')? + name.end() + 1;
+    for line in content[offset..].split_inclusive('\n') {
+        if let Some(rest) = line.strip_prefix(indent) {
+            let rest = rest.trim_end();
+            if rest == "end" || rest.starts_with("end ") {
+                return Some(FunctionSpan {
+                    name: name.as_str(),
+                    start: name.start(),
+                    end: offset + indent.len() + 3,
+                });
+            }
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn parse_number(literal: &str) -> Option<f64> {
+    let literal = literal.replace('_', "");
+    match literal
+        .strip_prefix("0x")
+        .or_else(|| literal.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok().map(|v| v as f64),
+        None => literal.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(detector: &dyn PatternDetector, content: &str, path: &str) -> Vec<usize> {
+        detector
+            .detect(content, Path::new(path))
+            .iter()
+            .map(|m| m.line_number)
+            .collect()
+    }
+
+    #[test]
+    fn test_magic_numbers() {
+        let content = "const TIMEOUT: u64 = 86400;\nfn run() {\n    sleep(86400);\n    let x = items[0] + 1;\n    let pct = total * 100 / 7u32;\n    let v = \"version 4.2.1\";\n    // retry 5 times\n    let y = x.0 + 0x7f;\n}\n#[cfg(test)]\nmod tests { fn t() { assert_eq!(f(), 42); } }\n";
+        let detector = MagicNumberDetector::default();
+        assert_eq!(lines(&detector, content, "src/lib.rs"), vec![3, 5, 8]);
+
+        let matches = detector.detect(content, Path::new("src/lib.rs"));
+        assert_eq!(matches[0].column, 11);
+        assert_eq!(
+            matches[1].message,
+            "MAGIC_NUMBER: 7 in let pct = total * 100 / 7u32;"
+        );
+        assert_eq!(matches[0].severity, Some(Severity::Low));
+        assert_eq!(matches[0].category.as_deref(), Some("CodeQuality"));
+
+        assert!(lines(&detector, content, "src/lib_test.rs").is_empty());
+        assert!(lines(&detector, "port: 8080\n", "config.yaml").is_empty());
+    }
+
+    #[test]
+    fn test_magic_number_config() {
+        let content = "MAX_RETRIES = 5\ntimeout = 3600\nretries = 5\n";
+        let detector = MagicNumberDetector::default();
+        assert_eq!(lines(&detector, content, "app.py"), vec![2, 3]);
+
+        let detector = MagicNumberDetector::new(&MagicNumberConfig {
+            min_value: 6.0,
+            allowlist: vec![3600.0],
+        });
+        assert!(lines(&detector, content, "app.py").is_empty());
+    }
+
+    #[test]
+    fn test_error_swallowed_detector() {
+        let detector = ErrorSwallowedDetector;
+        let js = "try { run(); } catch (e) {}\ntry { run(); } catch {\n}\ntry { run(); } catch (e) { log(e); }\nfetch(url).catch(() => {});\n// catch (e) {}";
+        assert_eq!(lines(&detector, js, "app.ts"), vec![1, 2, 5]);
+        assert_eq!(lines(&detector, js, "App.java"), vec![1, 2, 5]);
+
+        let py = "try:\n    run()\nexcept:\n    log()\ntry:\n    run()\nexcept Exception as e:\n    pass\nexcept ValueError: pass\nexcept KeyError:\n    handle()\n";
+        assert_eq!(lines(&detector, py, "app.py"), vec![3, 7, 9]);
+
+        let rs = "let _ = tx.send(msg);\nfile.sync_all().ok();\nlet value = parse(s).ok();\nlet _guard = lock();\n";
+        let matches = detector.detect(rs, Path::new("lib.rs"));
+        assert_eq!(
+            matches.iter().map(|m| m.line_number).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(matches[0].severity, Some(Severity::Medium));
+        assert_eq!(matches[0].category.as_deref(), Some("CodeQuality"));
+
+        assert!(!detector.applies_to(Path::new("main.go")));
+    }
+
+    fn short_functions() -> LengthLimitsConfig {
+        LengthLimitsConfig {
+            function_lines: 3,
+            languages: ["py", "rb", "js", "java"]
+                .iter()
+                .map(|ext| {
+                    let limits = LengthLimits {
+                        file_lines: None,
+                        function_lines: Some(3),
+                    };
+                    (ext.to_string(), limits)
+                })
+                .collect(),
+            ..LengthLimitsConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_length_limits() {
+        let config = LengthLimitsConfig::default();
+        assert_eq!(config.limits_for("rs"), (1000, 80));
+        assert_eq!(config.limits_for("PY"), (600, 50));
+
+        let mut config = LengthLimitsConfig {
+            file_lines: 400,
+            ..LengthLimitsConfig::default()
+        };
+        config.languages.insert(
+            "py".to_string(),
+            LengthLimits {
+                file_lines: None,
+                function_lines: Some(30),
+            },
+        );
+        assert_eq!(config.limits_for("go"), (400, 80));
+        assert_eq!(config.limits_for("py"), (600, 30));
+    }
+
+    #[test]
+    fn test_file_length_detector() {
+        let detector = FileLengthDetector::new(&LengthLimitsConfig {
+            file_lines: 2,
+            ..LengthLimitsConfig::default()
+        });
+        assert!(detector.detect("a\nb\n", Path::new("lib.rs")).is_empty());
+        let matches = detector.detect("a\nb\nc\n", Path::new("lib.rs"));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].message, "FILE_TOO_LONG: 3 lines, limit 2");
+        assert_eq!(matches[0].category.as_deref(), Some("CodeQuality"));
+        assert!(detector
+            .detect("a\nb\nc\n", Path::new("notes.txt"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_function_length_detector() {
+        let detector = FunctionLengthDetector::new(&short_functions());
+
+        let rs = "trait T {\n    fn declared(&self);\n}\nfn short() -> [u8; 2] {\n    [1, 2]\n}\nfn long(x: u8) {\n    let s = \"}\";\n    // }\n    if x > 0 {\n        run();\n    }\n}\n";
+        let matches = detector.detect(rs, Path::new("lib.rs"));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(
+            matches[0].message,
+            "FUNCTION_TOO_LONG: long spans 7 lines, limit 3"
+        );
+        assert_eq!((matches[0].line_number, matches[0].column), (7, 4));
+        assert_eq!(matches[0].end.map(|end| end.line), Some(13));
+        assert_eq!(matches[0].severity, Some(Severity::Medium));
+
+        let py = "def short(a,\n          b):\n    return a\n\nclass C:\n    def long(self):\n        x = 1\n\n        # note\n        y = 2\n        return x + y\n    def other(self): pass\n";
+        assert_eq!(lines(&detector, py, "app.py"), vec![6]);
+
+        let rb = "def long\n  a\n  b\n  c\nend\ndef short\n  a\nend\n";
+        assert_eq!(lines(&detector, rb, "app.rb"), vec![1]);
+
+        let js = "const handler = async (req) => {\n  a();\n  b();\n  c();\n};\nfunction f() { return 1; }\nexport default function () {\n  a();\n  b();\n}\n";
+        let matches = detector.detect(js, Path::new("app.js"));
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| m.message.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "FUNCTION_TOO_LONG: handler spans 5 lines, limit 3",
+                "FUNCTION_TOO_LONG: anonymous function spans 4 lines, limit 3",
+            ]
+        );
+
+        let java = "public class A {\n    public static int parse(String s)\n        throws IOException {\n        if (s.isEmpty()) {\n            return 0;\n        }\n        return 1;\n    }\n    abstract void hook();\n}\n";
+        assert_eq!(lines(&detector, java, "A.java"), vec![2]);
+    }
+}
@@ -0,0 +1,132 @@
+//! Process-wide cache of compiled regexes. Custom detectors, conditions and
+//! the prefilter set are rebuilt from configuration for every scan; in watch
+//! mode and the server that would recompile the same patterns over and over.
+//! Compiled regexes share their program, so handing out clones is cheap.
+
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use regex::{Regex, RegexBuilder, RegexSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Compiled patterns kept before the cache is cleared, so patterns of
+/// configurations no longer in use don't pile up in a long-running process
+pub const MAX_CACHED_PATTERNS: usize = 4096;
+
+/// Flags a pattern is compiled with, part of its cache key
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RegexFlags {
+    pub case_insensitive: bool,
+    pub multi_line: bool,
+}
+
+/// Lookups of the cache since the process started
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegexCacheStats {
+    pub patterns: usize,
+    pub sets: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+lazy_static! {
+    static ref PATTERNS: DashMap<(String, RegexFlags), Regex> = DashMap::new();
+    static ref SETS: DashMap<Vec<String>, RegexSet> = DashMap::new();
+}
+
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+
+/// `pattern` compiled with `flags`, compiled once per process. Patterns that
+/// fail to compile are not cached.
+pub fn cached_regex_with(pattern: &str, flags: RegexFlags) -> Result<Regex, regex::Error> {
+    let key = (pattern.to_string(), flags);
+    if let Some(regex) = PATTERNS.get(&key) {
+        HITS.fetch_add(1, Ordering::Relaxed);
+        return Ok(regex.clone());
+    }
+    MISSES.fetch_add(1, Ordering::Relaxed);
+    // Compiled outside the map's locks; threads racing on a new pattern
+    // each compile it and the last one is kept
+    let regex = RegexBuilder::new(pattern)
+        .case_insensitive(flags.case_insensitive)
+        .multi_line(flags.multi_line)
+        .build()?;
+    if PATTERNS.len() >= MAX_CACHED_PATTERNS {
+        PATTERNS.clear();
+    }
+    PATTERNS.insert(key, regex.clone());
+    Ok(regex)
+}
+
+/// `pattern` compiled without flags, see [`cached_regex_with`]
+pub fn cached_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    cached_regex_with(pattern, RegexFlags::default())
+}
+
+/// A [`RegexSet`] of `patterns`, compiled once per process
+pub fn cached_regex_set(patterns: &[String]) -> Result<RegexSet, regex::Error> {
+    if let Some(set) = SETS.get(patterns) {
+        HITS.fetch_add(1, Ordering::Relaxed);
+        return Ok(set.clone());
+    }
+    MISSES.fetch_add(1, Ordering::Relaxed);
+    let set = RegexSet::new(patterns)?;
+    if SETS.len() >= MAX_CACHED_PATTERNS {
+        SETS.clear();
+    }
+    SETS.insert(patterns.to_vec(), set.clone());
+    Ok(set)
+}
+
+/// Size and lookups of the cache
+pub fn regex_cache_stats() -> RegexCacheStats {
+    RegexCacheStats {
+        patterns: PATTERNS.len(),
+        sets: SETS.len(),
+        hits: HITS.load(Ordering::Relaxed),
+        misses: MISSES.load(Ordering::Relaxed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns_compile_once() {
+        let pattern = r"regex_cache_test_\d+";
+        cached_regex(pattern).unwrap();
+        let before = regex_cache_stats();
+        let regex = cached_regex(pattern).unwrap();
+        assert!(regex.is_match("regex_cache_test_42"));
+        assert!(regex_cache_stats().hits > before.hits);
+
+        // Flags are part of the key
+        let insensitive = RegexFlags {
+            case_insensitive: true,
+            ..RegexFlags::default()
+        };
+        assert!(cached_regex_with(pattern, insensitive)
+            .unwrap()
+            .is_match("REGEX_CACHE_TEST_1"));
+        assert!(!cached_regex(pattern)
+            .unwrap()
+            .is_match("REGEX_CACHE_TEST_1"));
+
+        assert!(cached_regex("regex_cache_test_(").is_err());
+        assert!(cached_regex("regex_cache_test_(").is_err());
+
+        let set = [r"regex_cache_set_a".to_string(), r"\bb\b".to_string()];
+        cached_regex_set(&set).unwrap();
+        let before = regex_cache_stats();
+        assert_eq!(
+            cached_regex_set(&set)
+                .unwrap()
+                .matches("regex_cache_set_a")
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![0]
+        );
+        assert!(regex_cache_stats().hits > before.hits);
+    }
+}
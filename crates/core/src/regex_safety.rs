@@ -0,0 +1,173 @@
+use crate::errors::{ScanError, ScanResult};
+use regex_syntax::hir::{Class, Hir, HirKind};
+use std::fmt;
+
+/// Bounded repetitions above this count inflate the compiled program size
+const LARGE_REPETITION_LIMIT: u32 = 1000;
+
+/// A construct in a custom detector pattern that makes it slow or overly broad.
+///
+/// The regex engine guarantees linear-time matching, so these don't cause
+/// catastrophic backtracking, but they can still explode the compiled automaton
+/// or produce a match at nearly every position of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegexRisk {
+    /// An unbounded quantifier applied to an expression that already contains one, e.g. `(a+)*`
+    NestedQuantifier,
+    /// The pattern starts with `.*` or `.+`, which only widens every match to the line start
+    LeadingWildcard,
+    /// The pattern can match the empty string, so it matches at every position
+    MatchesEmpty,
+    /// A counted repetition such as `{5000}` that inflates the compiled regex
+    LargeRepetition,
+}
+
+impl RegexRisk {
+    pub fn description(&self) -> &'static str {
+        match self {
+            RegexRisk::NestedQuantifier => {
+                "nested quantifiers, e.g. (a+)*, are expensive to compile and match"
+            }
+            RegexRisk::LeadingWildcard => {
+                "leading .* or .+ matches from the start of the line and slows every search"
+            }
+            RegexRisk::MatchesEmpty => "pattern matches the empty string, so it matches everywhere",
+            RegexRisk::LargeRepetition => "large counted repetition inflates the compiled regex",
+        }
+    }
+}
+
+impl fmt::Display for RegexRisk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// Analyze a pattern for constructs that are slow or overly broad.
+/// Fails only if the pattern doesn't parse.
+pub fn analyze_pattern(pattern: &str) -> ScanResult<Vec<RegexRisk>> {
+    let hir = regex_syntax::parse(pattern)
+        .map_err(|e| ScanError::config(format!("invalid pattern '{}': {}", pattern, e)))?;
+
+    let mut risks = Vec::new();
+    if has_nested_quantifier(&hir, false) {
+        risks.push(RegexRisk::NestedQuantifier);
+    }
+    if starts_with_wildcard(&hir) {
+        risks.push(RegexRisk::LeadingWildcard);
+    }
+    if hir.properties().minimum_len() == Some(0) {
+        risks.push(RegexRisk::MatchesEmpty);
+    }
+    if has_large_repetition(&hir) {
+        risks.push(RegexRisk::LargeRepetition);
+    }
+    Ok(risks)
+}
+
+fn has_nested_quantifier(hir: &Hir, inside_unbounded: bool) -> bool {
+    match hir.kind() {
+        HirKind::Repetition(rep) => {
+            let unbounded = rep.max.is_none();
+            if unbounded && inside_unbounded {
+                return true;
+            }
+            has_nested_quantifier(&rep.sub, inside_unbounded || unbounded)
+        }
+        HirKind::Capture(cap) => has_nested_quantifier(&cap.sub, inside_unbounded),
+        HirKind::Concat(subs) | HirKind::Alternation(subs) => subs
+            .iter()
+            .any(|sub| has_nested_quantifier(sub, inside_unbounded)),
+        _ => false,
+    }
+}
+
+fn starts_with_wildcard(hir: &Hir) -> bool {
+    match hir.kind() {
+        HirKind::Repetition(rep) => rep.max.is_none() && rep.min <= 1 && is_wildcard(&rep.sub),
+        HirKind::Capture(cap) => starts_with_wildcard(&cap.sub),
+        HirKind::Concat(subs) => subs.first().is_some_and(starts_with_wildcard),
+        _ => false,
+    }
+}
+
+/// True for `.` and `(?s).`: classes that exclude at most a couple of characters
+fn is_wildcard(hir: &Hir) -> bool {
+    match hir.kind() {
+        HirKind::Class(Class::Unicode(class)) => {
+            let covered: u32 = class
+                .ranges()
+                .iter()
+                .map(|r| r.end() as u32 - r.start() as u32 + 1)
+                .sum();
+            // All scalar values (0x10FFFF + 1 minus 2048 surrogates), less `\n` or `\r\n`
+            covered >= 0x110000 - 0x800 - 2
+        }
+        HirKind::Class(Class::Bytes(class)) => {
+            let covered: u32 = class
+                .ranges()
+                .iter()
+                .map(|r| r.end() as u32 - r.start() as u32 + 1)
+                .sum();
+            covered >= 254
+        }
+        _ => false,
+    }
+}
+
+fn has_large_repetition(hir: &Hir) -> bool {
+    match hir.kind() {
+        HirKind::Repetition(rep) => {
+            rep.min > LARGE_REPETITION_LIMIT
+                || rep.max.is_some_and(|max| max > LARGE_REPETITION_LIMIT)
+                || has_large_repetition(&rep.sub)
+        }
+        HirKind::Capture(cap) => has_large_repetition(&cap.sub),
+        HirKind::Concat(subs) | HirKind::Alternation(subs) => subs.iter().any(has_large_repetition),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_patterns() {
+        assert!(analyze_pattern(r"\bTODO\b").unwrap().is_empty());
+        assert!(analyze_pattern(r#"(?i)(password|secret)\s*=\s*"[^"]{8,}""#)
+            .unwrap()
+            .is_empty());
+        // A trailing wildcard is fine
+        assert!(analyze_pattern(r"TODO:.*").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_risky_patterns() {
+        assert_eq!(
+            analyze_pattern(r"(a+)+b").unwrap(),
+            vec![RegexRisk::NestedQuantifier]
+        );
+        assert_eq!(
+            analyze_pattern(r"(?:\w+\s*)*;").unwrap(),
+            vec![RegexRisk::NestedQuantifier]
+        );
+        assert_eq!(
+            analyze_pattern(r".*password").unwrap(),
+            vec![RegexRisk::LeadingWildcard]
+        );
+        assert_eq!(
+            analyze_pattern(r"(?s)(.+)secret").unwrap(),
+            vec![RegexRisk::LeadingWildcard]
+        );
+        assert_eq!(
+            analyze_pattern(r"\d*").unwrap(),
+            vec![RegexRisk::MatchesEmpty]
+        );
+        assert_eq!(
+            analyze_pattern(r"x{2000}").unwrap(),
+            vec![RegexRisk::LargeRepetition]
+        );
+        assert!(analyze_pattern(r"[invalid").is_err());
+    }
+}
@@ -0,0 +1,108 @@
+//! Named sets of detectors for building scanners
+
+use crate::detector_factory::DetectorProfile;
+use crate::PatternDetector;
+
+/// Detectors keyed by [`PatternDetector::name`], in registration order.
+/// Registering a detector under a name that is already taken replaces the
+/// earlier one, so a profile can be extended or overridden piece by piece.
+#[derive(Default)]
+pub struct DetectorRegistry {
+    detectors: Vec<Box<dyn PatternDetector>>,
+}
+
+impl DetectorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry holding the detectors of `profile`
+    pub fn from_profile(profile: &DetectorProfile) -> Self {
+        profile.get_detectors().into_iter().collect()
+    }
+
+    /// Adds `detector`, returning the detector it replaced, if any
+    pub fn register(
+        &mut self,
+        detector: Box<dyn PatternDetector>,
+    ) -> Option<Box<dyn PatternDetector>> {
+        match self.position(detector.name()) {
+            Some(index) => Some(std::mem::replace(&mut self.detectors[index], detector)),
+            None => {
+                self.detectors.push(detector);
+                None
+            }
+        }
+    }
+
+    /// Removes and returns the detector named `name`
+    pub fn unregister(&mut self, name: &str) -> Option<Box<dyn PatternDetector>> {
+        let index = self.position(name)?;
+        Some(self.detectors.remove(index))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.position(name).is_some()
+    }
+
+    /// Names of the registered detectors, in registration order
+    pub fn names(&self) -> Vec<&str> {
+        self.detectors
+            .iter()
+            .map(|detector| detector.name())
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.detectors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.detectors.is_empty()
+    }
+
+    pub fn into_detectors(self) -> Vec<Box<dyn PatternDetector>> {
+        self.detectors
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.detectors
+            .iter()
+            .position(|detector| detector.name() == name)
+    }
+}
+
+impl Extend<Box<dyn PatternDetector>> for DetectorRegistry {
+    fn extend<I: IntoIterator<Item = Box<dyn PatternDetector>>>(&mut self, iter: I) {
+        for detector in iter {
+            self.register(detector);
+        }
+    }
+}
+
+impl FromIterator<Box<dyn PatternDetector>> for DetectorRegistry {
+    fn from_iter<I: IntoIterator<Item = Box<dyn PatternDetector>>>(iter: I) -> Self {
+        let mut registry = Self::new();
+        registry.extend(iter);
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detectors::{FixmeDetector, TodoDetector};
+
+    #[test]
+    fn test_registry_replaces_by_name() {
+        let mut registry = DetectorRegistry::from_profile(&DetectorProfile::Basic);
+        assert_eq!(registry.names(), vec!["TodoDetector", "FixmeDetector"]);
+
+        assert!(registry.register(Box::new(TodoDetector)).is_some());
+        assert_eq!(registry.len(), 2);
+        assert!(registry.unregister("FixmeDetector").is_some());
+        assert!(!registry.contains("FixmeDetector"));
+        assert!(registry.register(Box::new(FixmeDetector)).is_none());
+        assert_eq!(registry.names(), vec!["TodoDetector", "FixmeDetector"]);
+    }
+}
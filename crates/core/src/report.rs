@@ -0,0 +1,171 @@
+//! The result of a whole scan, as returned by [`crate::Scanner::scan_report`]
+
+use crate::optimized_scanner::{FileTypeMetrics, ScanMetrics};
+use crate::skipped::SkippedFile;
+use crate::webhook::ScanSummary;
+use crate::{Match, Severity};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Findings of a scan together with their counts and the scan's metrics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanReport {
+    pub matches: Vec<Match>,
+    pub summary: ScanSummary,
+    pub metrics: ScanMetrics,
+}
+
+impl ScanReport {
+    pub fn new(matches: Vec<Match>, metrics: ScanMetrics) -> Self {
+        Self {
+            summary: ScanSummary::from_matches(&matches, metrics.total_files_scanned),
+            matches,
+            metrics,
+        }
+    }
+
+    /// Files that were found but could not be scanned, sorted by path
+    pub fn skipped_files(&self) -> &[SkippedFile] {
+        &self.metrics.skipped_files
+    }
+}
+
+/// The totals of a scan that every scanning command can print as one line,
+/// `files=1234 skipped=12 matches=345 critical=2 duration_ms=4521`, and
+/// that is stored with the scan
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanStatistics {
+    /// Files scanned
+    pub files: usize,
+    /// Files found but not scanned: generated or vendored, or unreadable
+    pub skipped: usize,
+    pub matches: usize,
+    /// Matches rated Critical
+    pub critical: usize,
+    pub duration_ms: u64,
+    /// Files whose results came from the scan cache, and those scanned
+    /// because it had none
+    #[serde(default)]
+    pub cache_hits: usize,
+    #[serde(default)]
+    pub cache_misses: usize,
+    /// Files, lines, matches and time per file extension, slowest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub file_types: Vec<FileTypeMetrics>,
+}
+
+impl ScanStatistics {
+    pub fn new(matches: &[Match], metrics: &ScanMetrics) -> Self {
+        Self {
+            files: metrics.total_files_scanned,
+            skipped: metrics.generated_files_skipped + metrics.skipped_files.len(),
+            matches: matches.len(),
+            critical: matches
+                .iter()
+                .filter(|m| m.severity == Some(Severity::Critical))
+                .count(),
+            duration_ms: metrics.scan_duration_ms,
+            cache_hits: metrics.cache_hits,
+            cache_misses: metrics.cache_misses,
+            file_types: metrics.file_types.clone(),
+        }
+    }
+
+    /// Share of cache lookups that hit, if the scan used a cache
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        let lookups = self.cache_hits + self.cache_misses;
+        (lookups > 0).then(|| self.cache_hits as f64 / lookups as f64)
+    }
+
+    /// Milliseconds scanning `files` files should take at the throughput of
+    /// `history`, earlier scans of the same root; None without scans that
+    /// scanned any files
+    pub fn estimate_duration_ms(history: &[ScanStatistics], files: usize) -> Option<u64> {
+        let (scanned, duration_ms) = history
+            .iter()
+            .filter(|scan| scan.files > 0)
+            .fold((0u128, 0u128), |(files, ms), scan| {
+                (files + scan.files as u128, ms + scan.duration_ms as u128)
+            });
+        (scanned > 0).then(|| (duration_ms * files as u128 / scanned) as u64)
+    }
+}
+
+impl fmt::Display for ScanStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "files={} skipped={} matches={} critical={} duration_ms={}",
+            self.files, self.skipped, self.matches, self.critical, self.duration_ms
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_statistics_line() {
+        let finding = |severity| Match {
+            file_path: "src/main.rs".into(),
+            line_number: 1,
+            column: 1,
+            pattern: "TODO".into(),
+            message: "TODO".to_string(),
+            severity,
+            category: None,
+            end: None,
+            author: None,
+        };
+        let metrics = ScanMetrics {
+            total_files_scanned: 1234,
+            scan_duration_ms: 4521,
+            generated_files_skipped: 10,
+            skipped_files: ["a.txt", "b.txt"]
+                .into_iter()
+                .map(|path| SkippedFile {
+                    path: path.to_string(),
+                    reason: crate::SkipReason::InvalidUtf8,
+                })
+                .collect(),
+            total_lines_processed: 0,
+            total_matches_found: 3,
+            cache_hits: 0,
+            cache_misses: 0,
+            cache_invalidated: 0,
+            detector_timings: Vec::new(),
+            worker_metrics: Vec::new(),
+            batch_sizing: None,
+            file_types: Vec::new(),
+        };
+        let matches = vec![
+            finding(Some(Severity::Critical)),
+            finding(Some(Severity::Low)),
+            finding(None),
+        ];
+        assert_eq!(
+            ScanStatistics::new(&matches, &metrics).to_string(),
+            "files=1234 skipped=12 matches=3 critical=1 duration_ms=4521"
+        );
+    }
+
+    #[test]
+    fn test_estimate_duration() {
+        let scan = |files, duration_ms| ScanStatistics {
+            files,
+            duration_ms,
+            ..ScanStatistics::default()
+        };
+        assert_eq!(ScanStatistics::estimate_duration_ms(&[], 100), None);
+        assert_eq!(
+            ScanStatistics::estimate_duration_ms(&[scan(0, 50)], 100),
+            None
+        );
+        // 300 files in 900ms over both scans: 3ms per file
+        assert_eq!(
+            ScanStatistics::estimate_duration_ms(&[scan(100, 200), scan(200, 700), scan(0, 5)], 50),
+            Some(150)
+        );
+    }
+}
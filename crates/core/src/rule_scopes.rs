@@ -0,0 +1,196 @@
+//! Path globs that switch rules on or off for parts of a project, e.g.
+//! allowing `CONSOLE_LOG` under `examples/**` while forbidding it under `src/**`,
+//! and per-rule severity overrides
+
+use crate::detectors::build_glob_set;
+use crate::errors::{ScanError, ScanResult};
+use crate::{Match, Severity};
+use globset::GlobSet;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Where a rule applies, as globs relative to the scan root
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct RuleScope {
+    /// The rule only applies to files matching one of these; empty means every file
+    pub include: Vec<String>,
+    /// The rule never applies to files matching one of these
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct CompiledScope {
+    rule: String,
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+/// Compiled [`RuleScope`]s keyed by rule (pattern) name, compared case-insensitively.
+/// Rules without a scope apply everywhere.
+#[derive(Debug, Clone, Default)]
+pub struct RuleScopes {
+    scopes: Vec<CompiledScope>,
+}
+
+impl RuleScopes {
+    pub fn new(scopes: &BTreeMap<String, RuleScope>) -> ScanResult<Self> {
+        let scopes = scopes
+            .iter()
+            .map(|(rule, scope)| {
+                let invalid = |e: ScanError| match e {
+                    ScanError::Config { message } => {
+                        ScanError::config(format!("rule_paths.{}: {}", rule, message))
+                    }
+                    other => other,
+                };
+                Ok(CompiledScope {
+                    rule: rule.clone(),
+                    include: if scope.include.is_empty() {
+                        None
+                    } else {
+                        Some(build_glob_set(&scope.include).map_err(invalid)?)
+                    },
+                    exclude: build_glob_set(&scope.exclude).map_err(invalid)?,
+                })
+            })
+            .collect::<ScanResult<_>>()?;
+        Ok(Self { scopes })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scopes.is_empty()
+    }
+
+    /// Whether the rule `pattern` applies to `path`, relative to the scan root
+    pub fn allows(&self, pattern: &str, path: &Path) -> bool {
+        let Some(scope) = self
+            .scopes
+            .iter()
+            .find(|scope| scope.rule.eq_ignore_ascii_case(pattern))
+        else {
+            return true;
+        };
+        scope
+            .include
+            .as_ref()
+            .map_or(true, |include| include.is_match(path))
+            && !scope.exclude.is_match(path)
+    }
+
+    /// Drops the matches of rules that don't apply to their file, resolving
+    /// each match's path relative to `root`
+    pub fn retain(&self, mut matches: Vec<Match>, root: &Path) -> Vec<Match> {
+        if self.is_empty() {
+            return matches;
+        }
+        matches.retain(|m| {
+            let path = Path::new(&*m.file_path);
+            self.allows(&m.pattern, path.strip_prefix(root).unwrap_or(path))
+        });
+        matches
+    }
+}
+
+/// Rates the matches of each rule in `overrides`, keyed by pattern name
+/// compared case-insensitively, at its severity, e.g. from
+/// [`crate::config::Config::severity_overrides`]
+pub fn override_severities(
+    mut matches: Vec<Match>,
+    overrides: &BTreeMap<String, Severity>,
+) -> Vec<Match> {
+    if overrides.is_empty() {
+        return matches;
+    }
+    for m in &mut matches {
+        if let Some((_, severity)) = overrides
+            .iter()
+            .find(|(rule, _)| rule.eq_ignore_ascii_case(&m.pattern))
+        {
+            m.severity = Some(severity.clone());
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn console_log_scopes() -> RuleScopes {
+        let mut scopes = BTreeMap::new();
+        scopes.insert(
+            "CONSOLE_LOG".to_string(),
+            RuleScope {
+                include: vec!["src/**".to_string()],
+                exclude: vec!["src/**/debug.js".to_string()],
+            },
+        );
+        scopes.insert(
+            "todo".to_string(),
+            RuleScope {
+                include: vec![],
+                exclude: vec!["examples/**".to_string()],
+            },
+        );
+        RuleScopes::new(&scopes).unwrap()
+    }
+
+    #[test]
+    fn test_rule_scopes() {
+        let scopes = console_log_scopes();
+        assert!(scopes.allows("CONSOLE_LOG", Path::new("src/app.js")));
+        assert!(!scopes.allows("CONSOLE_LOG", Path::new("examples/demo.js")));
+        assert!(!scopes.allows("CONSOLE_LOG", Path::new("src/util/debug.js")));
+        assert!(scopes.allows("TODO", Path::new("src/app.js")));
+        assert!(!scopes.allows("TODO", Path::new("examples/demo.js")));
+        assert!(scopes.allows("FIXME", Path::new("examples/demo.js")));
+
+        let mut invalid = BTreeMap::new();
+        invalid.insert(
+            "TODO".to_string(),
+            RuleScope {
+                include: vec!["src/[".to_string()],
+                exclude: vec![],
+            },
+        );
+        let err = RuleScopes::new(&invalid).unwrap_err();
+        assert!(err.to_string().contains("rule_paths.TODO"));
+    }
+
+    #[test]
+    fn test_override_severities() {
+        let finding = |pattern: &str, severity| Match {
+            file_path: "src/app.js".into(),
+            line_number: 1,
+            column: 1,
+            pattern: pattern.into(),
+            message: pattern.to_string(),
+            severity,
+            category: None,
+            end: None,
+            author: None,
+        };
+        let mut overrides = BTreeMap::new();
+        overrides.insert("console_log".to_string(), Severity::Low);
+        let matches = override_severities(
+            vec![
+                finding("CONSOLE_LOG", Some(Severity::High)),
+                finding("CONSOLE_LOG", None),
+                finding("DEBUGGER", Some(Severity::Critical)),
+            ],
+            &overrides,
+        );
+        let severities: Vec<_> = matches.iter().map(|m| m.severity.clone()).collect();
+        assert_eq!(
+            severities,
+            [
+                Some(Severity::Low),
+                Some(Severity::Low),
+                Some(Severity::Critical)
+            ]
+        );
+    }
+}
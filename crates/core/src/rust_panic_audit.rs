@@ -0,0 +1,368 @@
+//! Panic-path audit for Rust services: every way a function can panic, from
+//! `.unwrap()` to slice indexing and integer division, with a hint of where
+//! it can be reached from. Panics in request handlers and `main` are reported
+//! as High, those in other code as Medium and those in tests as Low, so
+//! filtering on High severity lists the panics written in request paths.
+
+use crate::comment_spans::CommentIndex;
+use crate::detectors::{build_glob_set, has_extension, DEFAULT_TEST_PATH_GLOBS};
+use crate::line_index::LineIndex;
+use crate::quality_detectors::{enclosing_function, function_spans, FunctionSpan};
+use crate::{Match, PatternDetector, Severity, SharedStr};
+use globset::GlobSet;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt;
+use std::path::Path;
+
+/// Category reported by [`RustPanicPathDetector`]
+pub const PANIC_PATH_CATEGORY: &str = "PanicPath";
+
+lazy_static! {
+    /// String, byte string and raw string literals, and char literals
+    static ref LITERAL_REGEX: Regex = Regex::new(
+        r###"b?r##"(?s:.*?)"##|b?r#"(?s:.*?)"#|b?r"[^"]*"|b?"(?:[^"\\]|\\.)*"|b?'(?:[^'\\\n]|\\.)'"###
+    ).unwrap();
+
+    /// Lines doing float arithmetic, whose divisions cannot panic
+    static ref FLOAT_LINE_REGEX: Regex = Regex::new(r"\bas\s+f(?:32|64)\b|\bf(?:32|64)::|\b\d+\.\d").unwrap();
+
+    static ref PANIC_SOURCE_REGEX: Regex = Regex::new(concat!(
+        r"(?P<unwrap>\.unwrap\(\))",
+        r"|(?P<expect>\.expect\()",
+        r"|\b(?P<macro>panic|todo|unimplemented|unreachable)!",
+        // `v[i]` and `v[1..]`, but not `#[attr]`, `vec![...]` or `&[u8]`
+        r"|[\w)\]](?P<index>\[[^\]\n]*\])",
+        // Division by anything but a literal may divide by zero
+        r"|[\w)\]]\s*(?P<division>[/%]=?)\s*[A-Za-z_]",
+    )).unwrap();
+
+    static ref TEST_ATTRIBUTE_REGEX: Regex = Regex::new(r"#\[(?:\w+::)*test\b").unwrap();
+
+    static ref RUST_TEST_MODULE_REGEX: Regex = Regex::new(r"(?m)^[ \t]*#\[cfg\(test\)\]").unwrap();
+
+    /// Route attributes of actix-web, rocket, poem and axum's debug macro
+    static ref ROUTE_ATTRIBUTE_REGEX: Regex = Regex::new(
+        r"#\[(?:\w+::)*(?:get|post|put|patch|delete|head|options|route|handler|debug_handler)\b"
+    ).unwrap();
+
+    /// Request extractors of common web frameworks in a signature
+    static ref EXTRACTOR_REGEX: Regex = Regex::new(
+        r"\b(?:Json|Path|Query|Form|State|Extension|Data|Request|HttpRequest|web::\w+)\s*<|\bHttpRequest\b"
+    ).unwrap();
+}
+
+/// A way for Rust code to panic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicSource {
+    Unwrap,
+    Expect,
+    /// `panic!`
+    Explicit,
+    /// `todo!` and `unimplemented!`
+    Todo,
+    Unreachable,
+    /// Indexing or slicing out of bounds
+    Index,
+    /// Integer division or remainder by zero
+    Division,
+}
+
+impl PanicSource {
+    /// The pattern name reported for this source
+    pub fn pattern(&self) -> &'static str {
+        match self {
+            PanicSource::Unwrap => "PANIC_UNWRAP",
+            PanicSource::Expect => "PANIC_EXPECT",
+            PanicSource::Explicit => "PANIC_EXPLICIT",
+            PanicSource::Todo => "PANIC_TODO",
+            PanicSource::Unreachable => "PANIC_UNREACHABLE",
+            PanicSource::Index => "PANIC_INDEX",
+            PanicSource::Division => "PANIC_DIVISION",
+        }
+    }
+}
+
+/// Where a panic can be reached from, judged by its enclosing function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reachability {
+    /// A request handler: a function with a route attribute or taking a
+    /// request extractor such as `Json<T>` or `State<T>`
+    Handler(String),
+    /// The program's `main`
+    Main,
+    /// Any other function, or code outside a function such as a `static`
+    Other(Option<String>),
+    /// Tests, `#[cfg(test)]` modules and files on test paths
+    Test,
+}
+
+impl Reachability {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Reachability::Handler(_) | Reachability::Main => Severity::High,
+            Reachability::Other(_) => Severity::Medium,
+            Reachability::Test => Severity::Low,
+        }
+    }
+}
+
+impl fmt::Display for Reachability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reachability::Handler(name) => write!(f, "in request handler `{}`", name),
+            Reachability::Main => write!(f, "in `main`"),
+            Reachability::Other(Some(name)) => write!(f, "in `{}`", name),
+            Reachability::Other(None) => write!(f, "outside any function"),
+            Reachability::Test => write!(f, "in test code"),
+        }
+    }
+}
+
+/// Detector for the panic sources of Rust code, each reported with the
+/// [`Reachability`] of its enclosing function. Index and division findings
+/// are hints: `v[i]` panics only out of bounds and `a / b` only for an
+/// integer `b` of zero.
+pub struct RustPanicPathDetector {
+    test_paths: GlobSet,
+}
+
+impl Default for RustPanicPathDetector {
+    fn default() -> Self {
+        Self {
+            test_paths: build_glob_set(DEFAULT_TEST_PATH_GLOBS)
+                .expect("default test globs are valid"),
+        }
+    }
+}
+
+impl RustPanicPathDetector {
+    fn reachability(
+        &self,
+        code: &str,
+        function: Option<&FunctionSpan>,
+        test_module: usize,
+        offset: usize,
+    ) -> Reachability {
+        if offset >= test_module {
+            return Reachability::Test;
+        }
+        let Some(function) = function else {
+            return Reachability::Other(None);
+        };
+        // Attributes and qualifiers since the previous item, and the
+        // signature up to the body
+        let preamble_start = code[..function.start]
+            .rfind(['{', '}', ';'])
+            .map_or(0, |i| i + 1);
+        let preamble = &code[preamble_start..function.start];
+        let signature_end = code[function.start..function.end]
+            .find('{')
+            .map_or(function.end, |i| function.start + i);
+        let signature = &code[function.start..signature_end];
+        if TEST_ATTRIBUTE_REGEX.is_match(preamble) {
+            Reachability::Test
+        } else if function.name == "main" {
+            Reachability::Main
+        } else if ROUTE_ATTRIBUTE_REGEX.is_match(preamble) || EXTRACTOR_REGEX.is_match(signature) {
+            Reachability::Handler(function.name.to_string())
+        } else {
+            Reachability::Other(Some(function.name.to_string()))
+        }
+    }
+}
+
+impl PatternDetector for RustPanicPathDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        // Comments and literals are blanked out so their text is neither a
+        // panic source nor a brace, keeping every offset in place
+        let mut code = content.to_string();
+        for span in CommentIndex::new(content, file_path).spans() {
+            code.replace_range(span.clone(), &blank(&content[span.clone()]));
+        }
+        let code = LITERAL_REGEX
+            .replace_all(&code, |caps: &regex::Captures| blank(&caps[0]))
+            .into_owned();
+        let test_module = if self.test_paths.is_match(file_path) {
+            0
+        } else {
+            RUST_TEST_MODULE_REGEX
+                .find(&code)
+                .map_or(usize::MAX, |m| m.start())
+        };
+        let functions = function_spans(&code, file_path);
+        let lines = LineIndex::new(content);
+        let shared_path = SharedStr::from_path(file_path);
+        let mut matches = Vec::new();
+        for caps in PANIC_SOURCE_REGEX.captures_iter(&code) {
+            let (source, found, what) = if let Some(m) = caps.name("unwrap") {
+                (PanicSource::Unwrap, m, ".unwrap()".to_string())
+            } else if let Some(m) = caps.name("expect") {
+                (PanicSource::Expect, m, ".expect()".to_string())
+            } else if let Some(m) = caps.name("macro") {
+                let source = match m.as_str() {
+                    "panic" => PanicSource::Explicit,
+                    "unreachable" => PanicSource::Unreachable,
+                    _ => PanicSource::Todo,
+                };
+                (source, m, format!("{}!", m.as_str()))
+            } else if let Some(m) = caps.name("index") {
+                if m.as_str() == "[..]" || m.as_str() == "[]" {
+                    continue;
+                }
+                (
+                    PanicSource::Index,
+                    m,
+                    format!("indexing {}", &content[m.range()]),
+                )
+            } else if let Some(m) = caps.name("division") {
+                let line_start = code[..m.start()].rfind('\n').map_or(0, |i| i + 1);
+                let line_end = code[m.end()..]
+                    .find('\n')
+                    .map_or(code.len(), |i| m.end() + i);
+                if FLOAT_LINE_REGEX.is_match(&code[line_start..line_end]) {
+                    continue;
+                }
+                let op = if m.as_str().starts_with('/') {
+                    "division"
+                } else {
+                    "remainder"
+                };
+                (PanicSource::Division, m, format!("{} by a variable", op))
+            } else {
+                continue;
+            };
+            let function = enclosing_function(&functions, found.start());
+            let reachability = self.reachability(&code, function, test_module, found.start());
+            let (line_number, column) = lines.position(content, found.start());
+            matches.push(Match {
+                file_path: shared_path.clone(),
+                line_number,
+                column,
+                pattern: SharedStr::interned(source.pattern()),
+                message: format!("{}: {} {}", source.pattern(), what, reachability),
+                severity: Some(reachability.severity()),
+                category: Some(PANIC_PATH_CATEGORY.to_string()),
+                end: None,
+                author: None,
+            });
+        }
+        matches
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["rs"])
+    }
+}
+
+/// `text` with every character but newlines replaced by spaces of the same
+/// byte length
+fn blank(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c == '\n' {
+                "\n".to_string()
+            } else {
+                " ".repeat(c.len_utf8())
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(content: &str, path: &str) -> Vec<(usize, String, Severity)> {
+        RustPanicPathDetector::default()
+            .detect(content, Path::new(path))
+            .into_iter()
+            .map(|m| (m.line_number, m.message, m.severity.unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_panic_path_detector() {
+        let content = r#"#[get("/users/{id}")]
+async fn get_user(id: u32) -> String {
+    let users = load().unwrap();
+    users[id as usize].name.clone()
+}
+
+async fn create(Json(body): Json<NewUser>) -> StatusCode {
+    todo!()
+}
+
+fn average(total: u64, count: u64) -> u64 {
+    // total / count panics when count is 0, see .unwrap() docs
+    let label = "a[0] / b.unwrap()";
+    total / count + total / 2
+}
+
+fn main() {
+    let config = std::env::args().nth(1).expect("config path");
+    let bytes: [u8; 4] = [0; 4];
+    let all = &bytes[..];
+    match config.len() {
+        0 => unreachable!("checked above"),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn reads() {
+        panic!("boom");
+    }
+}
+"#;
+        assert_eq!(
+            found(content, "src/main.rs"),
+            vec![
+                (
+                    3,
+                    "PANIC_UNWRAP: .unwrap() in request handler `get_user`".to_string(),
+                    Severity::High
+                ),
+                (
+                    4,
+                    "PANIC_INDEX: indexing [id as usize] in request handler `get_user`".to_string(),
+                    Severity::High
+                ),
+                (
+                    8,
+                    "PANIC_TODO: todo! in request handler `create`".to_string(),
+                    Severity::High
+                ),
+                (
+                    14,
+                    "PANIC_DIVISION: division by a variable in `average`".to_string(),
+                    Severity::Medium
+                ),
+                (
+                    18,
+                    "PANIC_EXPECT: .expect() in `main`".to_string(),
+                    Severity::High
+                ),
+                (
+                    22,
+                    "PANIC_UNREACHABLE: unreachable! in `main`".to_string(),
+                    Severity::High
+                ),
+                (
+                    31,
+                    "PANIC_EXPLICIT: panic! in test code".to_string(),
+                    Severity::Low
+                ),
+            ]
+        );
+        assert!(found("fn f() { x.unwrap(); }", "tests/api.rs")
+            .iter()
+            .all(|(_, message, _)| message.ends_with("in test code")));
+        assert!(found("x.unwrap()", "app.py").is_empty());
+    }
+}
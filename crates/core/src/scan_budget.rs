@@ -0,0 +1,130 @@
+//! Time budget of a scan: files likely to have findings are scanned first,
+//! and files not reached when the budget runs out are left unscanned and
+//! reported as not covered.
+
+use crate::file_priority::FindingDensity;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Longest time a scan may spend scanning files, e.g. `30s`, `500ms` or `2m`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanBudget {
+    pub limit: Duration,
+    /// Findings of earlier scans, whose files are scanned first
+    pub density: FindingDensity,
+}
+
+impl ScanBudget {
+    pub fn new(limit: Duration) -> Self {
+        Self {
+            limit,
+            density: FindingDensity::default(),
+        }
+    }
+
+    /// Scans the files with the most `density` findings first
+    pub fn with_density(mut self, density: FindingDensity) -> Self {
+        self.density = density;
+        self
+    }
+}
+
+impl FromStr for ScanBudget {
+    type Err = String;
+
+    /// A number with the unit `ms`, `s` or `m`; plain numbers are seconds
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (amount, unit) = s.split_at(split);
+        let amount: u64 = amount
+            .parse()
+            .map_err(|_| format!("Invalid budget '{}'. Use e.g. 30s, 500ms or 2m", s))?;
+        let limit = match unit {
+            "ms" => Duration::from_millis(amount),
+            "" | "s" => Duration::from_secs(amount),
+            "m" => Duration::from_secs(amount * 60),
+            _ => {
+                return Err(format!(
+                    "Invalid budget unit '{}'. Supported: ms, s, m",
+                    unit
+                ))
+            }
+        };
+        if limit.is_zero() {
+            return Err("The budget must be greater than 0".to_string());
+        }
+        Ok(Self::new(limit))
+    }
+}
+
+/// How much of a budgeted scan was covered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetCoverage {
+    /// Files reached before the budget ran out
+    pub covered: usize,
+    /// Files the scan found
+    pub total: usize,
+}
+
+impl BudgetCoverage {
+    /// Whether the budget ran out before every file was reached
+    pub fn is_partial(&self) -> bool {
+        self.covered < self.total
+    }
+}
+
+impl fmt::Display for BudgetCoverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent = (self.covered * 100).checked_div(self.total).unwrap_or(100);
+        write!(
+            f,
+            "{}/{} files covered ({}%)",
+            self.covered, self.total, percent
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_budget() {
+        assert_eq!(
+            "30s".parse::<ScanBudget>().unwrap().limit,
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            "45".parse::<ScanBudget>().unwrap().limit,
+            Duration::from_secs(45)
+        );
+        assert_eq!(
+            "500ms".parse::<ScanBudget>().unwrap().limit,
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            "2m".parse::<ScanBudget>().unwrap().limit,
+            Duration::from_secs(120)
+        );
+        assert!("0s".parse::<ScanBudget>().is_err());
+        assert!("1h".parse::<ScanBudget>().unwrap_err().contains("unit 'h'"));
+        assert!("fast".parse::<ScanBudget>().is_err());
+    }
+
+    #[test]
+    fn test_coverage() {
+        let coverage = BudgetCoverage {
+            covered: 812,
+            total: 10_000,
+        };
+        assert!(coverage.is_partial());
+        assert_eq!(coverage.to_string(), "812/10000 files covered (8%)");
+        assert!(!BudgetCoverage {
+            covered: 0,
+            total: 0
+        }
+        .is_partial());
+    }
+}
@@ -0,0 +1,420 @@
+//! Scan cache keyed by file content and detector set, persisted between runs.
+//! Entries don't depend on the path of a file, so checkouts of different
+//! branches, and worktrees sharing a cache directory, reuse each other's
+//! results for identical files.
+
+use crate::errors::{ScanError, ScanResult};
+use crate::redaction::redaction_enabled;
+use crate::{Match, PatternDetector, SharedStr};
+use dashmap::DashMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Directory, relative to the scan root, holding the persisted scan cache
+pub const SCAN_CACHE_DIR: &str = ".code-guardian/cache";
+
+const CACHE_FILE: &str = "scan-cache.json";
+const CACHE_VERSION: u32 = 2;
+
+/// What decides whether a cached file result is still valid
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheKey {
+    /// The file's modification time; cheap, but misses after a checkout or
+    /// cache restore gives every file a fresh timestamp
+    #[default]
+    Mtime,
+    /// A hash of the file's content, persisted under [`SCAN_CACHE_DIR`] so
+    /// later runs start warm
+    Content,
+}
+
+impl FromStr for CacheKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mtime" => Ok(Self::Mtime),
+            "content" => Ok(Self::Content),
+            _ => Err(format!(
+                "Unknown cache key '{}'. Supported: mtime, content",
+                s
+            )),
+        }
+    }
+}
+
+/// XXH64 (seed 0) of `data`. Fast, and stable across platforms and runs, so
+/// it can key a cache that outlives the process.
+pub fn content_hash(data: &[u8]) -> u64 {
+    const P1: u64 = 0x9E37_79B1_85EB_CA87;
+    const P2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+    const P3: u64 = 0x1656_67B1_9E37_79F9;
+    const P4: u64 = 0x85EB_CA77_C2B2_AE63;
+    const P5: u64 = 0x27D4_EB2F_1656_67C5;
+
+    fn read_u64(bytes: &[u8]) -> u64 {
+        u64::from_le_bytes(bytes[..8].try_into().unwrap())
+    }
+    fn round(acc: u64, input: u64) -> u64 {
+        acc.wrapping_add(input.wrapping_mul(P2))
+            .rotate_left(31)
+            .wrapping_mul(P1)
+    }
+    fn merge_round(acc: u64, value: u64) -> u64 {
+        (acc ^ round(0, value)).wrapping_mul(P1).wrapping_add(P4)
+    }
+
+    let mut rest = data;
+    let mut hash = if data.len() >= 32 {
+        let mut lanes = [P1.wrapping_add(P2), P2, 0, 0u64.wrapping_sub(P1)];
+        while rest.len() >= 32 {
+            for (i, lane) in lanes.iter_mut().enumerate() {
+                *lane = round(*lane, read_u64(&rest[i * 8..]));
+            }
+            rest = &rest[32..];
+        }
+        let mut hash = lanes[0]
+            .rotate_left(1)
+            .wrapping_add(lanes[1].rotate_left(7))
+            .wrapping_add(lanes[2].rotate_left(12))
+            .wrapping_add(lanes[3].rotate_left(18));
+        for lane in lanes {
+            hash = merge_round(hash, lane);
+        }
+        hash
+    } else {
+        P5
+    };
+    hash = hash.wrapping_add(data.len() as u64);
+
+    while rest.len() >= 8 {
+        hash ^= round(0, read_u64(rest));
+        hash = hash.rotate_left(27).wrapping_mul(P1).wrapping_add(P4);
+        rest = &rest[8..];
+    }
+    if rest.len() >= 4 {
+        let word = u32::from_le_bytes(rest[..4].try_into().unwrap()) as u64;
+        hash ^= word.wrapping_mul(P1);
+        hash = hash.rotate_left(23).wrapping_mul(P2).wrapping_add(P3);
+        rest = &rest[4..];
+    }
+    for &byte in rest {
+        hash ^= (byte as u64).wrapping_mul(P5);
+        hash = hash.rotate_left(11).wrapping_mul(P1);
+    }
+
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(P2);
+    hash ^= hash >> 29;
+    hash = hash.wrapping_mul(P3);
+    hash ^ (hash >> 32)
+}
+
+/// Fingerprint of the effective detector configuration: the version of
+/// code-guardian, and the name, prefilter and
+/// [`PatternDetector::config_fingerprint`] of each detector. Cached results
+/// from a different configuration, or from a run with redaction switched the
+/// other way, don't apply.
+pub fn detector_fingerprint(detectors: &[Box<dyn PatternDetector>]) -> u64 {
+    let mut key = format!("{}\0", env!("CARGO_PKG_VERSION"));
+    if !redaction_enabled() {
+        key.push_str("no-redact\0");
+    }
+    for detector in detectors {
+        key.push_str(detector.name());
+        key.push('\0');
+        if let Some(regex) = detector.prefilter() {
+            key.push_str(regex.as_str());
+        }
+        key.push('\0');
+        key.push_str(&detector.config_fingerprint());
+        key.push('\0');
+    }
+    content_hash(key.as_bytes())
+}
+
+/// Content hash and detector set fingerprint of a cached file result
+type EntryKey = (u64, u64);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Run in which the entry was last read or written, for eviction
+    last_used: u64,
+    /// Findings, with the path of the file they were found in
+    matches: Vec<Match>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    run: u64,
+    /// Entries keyed by `<detectors>-<content hash>` in hex
+    entries: HashMap<String, CacheEntry>,
+}
+
+fn entry_name((hash, detectors): EntryKey) -> String {
+    format!("{:016x}-{:016x}", detectors, hash)
+}
+
+fn parse_entry_name(name: &str) -> Option<EntryKey> {
+    let (detectors, hash) = name.split_once('-')?;
+    Some((
+        u64::from_str_radix(hash, 16).ok()?,
+        u64::from_str_radix(detectors, 16).ok()?,
+    ))
+}
+
+/// What [`ScanCache::save`] wrote
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheSaveStats {
+    pub entries: usize,
+    pub evicted: usize,
+    pub bytes: usize,
+}
+
+/// File results keyed by [`content_hash`] and [`detector_fingerprint`],
+/// loaded from and saved to a cache directory. Results of other detector
+/// sets are kept, so switching between branches with different detectors
+/// doesn't start cold. The saved file is kept under a size limit by evicting
+/// the entries that went unused for the most runs.
+pub struct ScanCache {
+    file: PathBuf,
+    detectors: u64,
+    run: u64,
+    max_bytes: usize,
+    entries: DashMap<EntryKey, CacheEntry>,
+    invalidated: usize,
+}
+
+impl ScanCache {
+    /// Load the cache in `dir` for `detectors`. A missing, unreadable or
+    /// outdated cache starts empty.
+    pub fn open(dir: &Path, detectors: &[Box<dyn PatternDetector>], max_bytes: usize) -> Self {
+        let file = dir.join(CACHE_FILE);
+        let detectors = detector_fingerprint(detectors);
+        let loaded = std::fs::read(&file)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<CacheFile>(&bytes).ok())
+            .filter(|cache| cache.version == CACHE_VERSION);
+        let (run, entries) = match loaded {
+            Some(cache) => (
+                cache.run + 1,
+                cache
+                    .entries
+                    .into_iter()
+                    .filter_map(|(name, entry)| Some((parse_entry_name(&name)?, entry)))
+                    .collect(),
+            ),
+            None => (1, DashMap::new()),
+        };
+        let invalidated = if entries.iter().any(|entry| entry.key().1 == detectors) {
+            0
+        } else {
+            entries.len()
+        };
+        Self {
+            file,
+            detectors,
+            run,
+            max_bytes,
+            entries,
+            invalidated,
+        }
+    }
+
+    /// Cached file results that no longer apply because the detector
+    /// configuration changed, when none of the loaded ones still do
+    pub fn invalidated(&self) -> usize {
+        self.invalidated
+    }
+
+    /// Number of cached file results, of every detector set
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Cached matches for a file at `path` whose content hashes to `hash`,
+    /// wherever the identical content was scanned before
+    pub fn get(&self, path: &str, hash: u64) -> Option<Vec<Match>> {
+        let mut entry = self.entries.get_mut(&(hash, self.detectors))?;
+        entry.last_used = self.run;
+        let mut matches = entry.matches.clone();
+        if matches.iter().any(|m| m.file_path != path) {
+            let path = SharedStr::from(path);
+            for m in &mut matches {
+                m.file_path = path.clone();
+            }
+        }
+        Some(matches)
+    }
+
+    pub fn insert(&self, hash: u64, matches: &[Match]) {
+        self.entries.insert(
+            (hash, self.detectors),
+            CacheEntry {
+                last_used: self.run,
+                matches: matches.to_vec(),
+            },
+        );
+    }
+
+    /// Write the cache, evicting least recently used entries until it fits
+    /// in the size limit
+    pub fn save(&self) -> ScanResult<CacheSaveStats> {
+        let mut sized: Vec<(String, CacheEntry, usize)> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let name = entry_name(*entry.key());
+                let size =
+                    name.len() + serde_json::to_vec(entry.value()).map_or(0, |json| json.len()) + 4;
+                (name, entry.value().clone(), size)
+            })
+            .collect();
+        // Most recently used first, then by key so ties evict the same way every run
+        sized.sort_by(|a, b| b.1.last_used.cmp(&a.1.last_used).then(a.0.cmp(&b.0)));
+
+        let mut total = 0;
+        let mut entries = HashMap::new();
+        let mut evicted = 0;
+        for (name, entry, size) in sized {
+            if total + size > self.max_bytes {
+                evicted += 1;
+                continue;
+            }
+            total += size;
+            entries.insert(name, entry);
+        }
+
+        let cache = CacheFile {
+            version: CACHE_VERSION,
+            run: self.run,
+            entries,
+        };
+        let json = serde_json::to_vec(&cache).map_err(|e| ScanError::io(&self.file, e.into()))?;
+        if let Some(dir) = self.file.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| ScanError::io(dir, e))?;
+        }
+        // Write then rename, so an interrupted save never leaves a torn cache;
+        // scans sharing the directory each write their own temporary file
+        let tmp = self
+            .file
+            .with_extension(format!("json.{}.tmp", std::process::id()));
+        std::fs::write(&tmp, &json).map_err(|e| ScanError::io(&tmp, e))?;
+        std::fs::rename(&tmp, &self.file).map_err(|e| ScanError::io(&self.file, e))?;
+
+        Ok(CacheSaveStats {
+            entries: cache.entries.len(),
+            evicted,
+            bytes: json.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detectors::{FixmeDetector, TodoDetector};
+
+    #[test]
+    fn test_content_hash_matches_xxh64() {
+        assert_eq!(content_hash(b""), 0xEF46_DB37_51D8_E999);
+        assert_eq!(content_hash(b"a"), 0xD24E_C4F1_A98C_6E5B);
+        assert_eq!(content_hash(b"abc"), 0x44BC_2CF5_AD77_0999);
+        assert_eq!(
+            content_hash(b"Nobody inspects the spammish repetition"),
+            0xFBCE_A83C_8A37_8BF1
+        );
+    }
+
+    #[test]
+    fn test_scan_cache_round_trip_and_eviction() {
+        let dir = tempfile::tempdir().unwrap();
+        let detectors: Vec<Box<dyn PatternDetector>> = vec![Box::new(TodoDetector)];
+        let finding = |file: &str| Match {
+            file_path: file.into(),
+            line_number: 1,
+            column: 1,
+            pattern: "TODO".into(),
+            message: "TODO: x".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
+        };
+
+        let cache = ScanCache::open(dir.path(), &detectors, 1 << 20);
+        assert!(cache.is_empty());
+        cache.insert(1, &[finding("a.rs")]);
+        cache.insert(2, &[]);
+        assert_eq!(cache.save().unwrap().entries, 2);
+
+        // Hits need the same content hash, at any path
+        let cache = ScanCache::open(dir.path(), &detectors, 1 << 20);
+        assert_eq!(cache.get("a.rs", 1), Some(vec![finding("a.rs")]));
+        assert_eq!(cache.get("a.rs", 9), None);
+        assert_eq!(
+            cache.get("../worktree/a.rs", 1),
+            Some(vec![finding("../worktree/a.rs")])
+        );
+
+        // b.rs went unused this run, so it is evicted first
+        let stats = ScanCache {
+            max_bytes: 150,
+            ..cache
+        }
+        .save()
+        .unwrap();
+        assert_eq!((stats.entries, stats.evicted), (1, 1));
+        let cache = ScanCache::open(dir.path(), &detectors, 1 << 20);
+        assert!(cache.get("a.rs", 1).is_some());
+        assert!(cache.get("b.rs", 2).is_none());
+
+        // Another detector set misses, and keeps the results of the first
+        let other: Vec<Box<dyn PatternDetector>> = vec![Box::new(FixmeDetector)];
+        let cache = ScanCache::open(dir.path(), &other, 1 << 20);
+        assert_eq!(cache.get("a.rs", 1), None);
+        cache.insert(1, &[]);
+        cache.save().unwrap();
+        let cache = ScanCache::open(dir.path(), &detectors, 1 << 20);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("a.rs", 1), Some(vec![finding("a.rs")]));
+    }
+
+    #[test]
+    fn test_rule_change_invalidates_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let rule = |pattern: &str| -> Vec<Box<dyn PatternDetector>> {
+            vec![Box::new(
+                crate::detectors::CustomPatternDetector::new("NO_UNWRAP", pattern).unwrap(),
+            )]
+        };
+        assert_ne!(
+            detector_fingerprint(&rule(r"\.unwrap\(")),
+            detector_fingerprint(&rule(r"\.unwrap\(\)"))
+        );
+
+        let cache = ScanCache::open(dir.path(), &rule(r"\.unwrap\("), 1 << 20);
+        assert_eq!(cache.invalidated(), 0);
+        cache.insert(1, &[]);
+        cache.insert(2, &[]);
+        cache.save().unwrap();
+        assert_eq!(
+            ScanCache::open(dir.path(), &rule(r"\.unwrap\("), 1 << 20).invalidated(),
+            0
+        );
+
+        // The same detector name with another pattern reuses nothing
+        let cache = ScanCache::open(dir.path(), &rule(r"\.unwrap\(\)"), 1 << 20);
+        assert_eq!(cache.invalidated(), 2);
+        assert_eq!(cache.get("a.rs", 1), None);
+    }
+}
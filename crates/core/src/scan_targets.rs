@@ -0,0 +1,102 @@
+//! Named parts of a repository that are scanned on their own, e.g. one per
+//! service of a monorepo, set in the `targets` config section
+
+use crate::errors::{ScanError, ScanResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A directory scanned as its own scan, a `[targets.<name>]` config section
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ScanTarget {
+    /// Directory to scan, relative to the directory of the config file
+    pub path: PathBuf,
+    /// Detector profile of the target; defaults to `--profile`
+    pub profile: Option<String>,
+}
+
+impl ScanTarget {
+    /// The directory to scan, resolving a relative `path` against `config_dir`
+    pub fn root(&self, config_dir: &Path) -> PathBuf {
+        config_dir.join(&self.path)
+    }
+}
+
+/// The targets called `names`, in the order given, or all of them in name
+/// order when `names` is empty
+pub fn select_targets<'a>(
+    targets: &'a BTreeMap<String, ScanTarget>,
+    names: &[String],
+) -> ScanResult<Vec<(&'a str, &'a ScanTarget)>> {
+    if targets.is_empty() {
+        return Err(ScanError::config(
+            "No scan targets defined; add [targets.<name>] sections to the config",
+        ));
+    }
+    if names.is_empty() {
+        return Ok(targets
+            .iter()
+            .map(|(name, target)| (name.as_str(), target))
+            .collect());
+    }
+    names
+        .iter()
+        .map(|name| {
+            targets
+                .get_key_value(name)
+                .map(|(name, target)| (name.as_str(), target))
+                .ok_or_else(|| {
+                    ScanError::config(format!(
+                        "Unknown target '{}', expected one of: {}",
+                        name,
+                        targets.keys().cloned().collect::<Vec<_>>().join(", ")
+                    ))
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets() -> BTreeMap<String, ScanTarget> {
+        toml::from_str(
+            "[web]\npath = \"apps/web\"\n\n[api]\npath = \"services/api\"\nprofile = \"security\"\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_select_targets() {
+        let targets = targets();
+        let names = |selected: Vec<(&str, &ScanTarget)>| {
+            selected
+                .into_iter()
+                .map(|(name, _)| name.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(select_targets(&targets, &[]).unwrap()),
+            ["api", "web"]
+        );
+        let selected = select_targets(&targets, &["web".to_string(), "api".to_string()]).unwrap();
+        assert_eq!(selected[1].1.profile.as_deref(), Some("security"));
+        assert_eq!(names(selected), ["web", "api"]);
+
+        let err = select_targets(&targets, &["docs".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("expected one of: api, web"));
+        assert!(select_targets(&BTreeMap::new(), &[]).is_err());
+    }
+
+    #[test]
+    fn test_target_root_is_relative_to_config() {
+        let targets = targets();
+        assert_eq!(
+            targets["api"].root(Path::new("/repo")),
+            Path::new("/repo/services/api")
+        );
+    }
+}
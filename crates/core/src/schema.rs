@@ -0,0 +1,126 @@
+use crate::config::Config;
+use crate::custom_detectors::CustomDetectorConfig;
+use crate::errors::{ScanError, ScanResult};
+use crate::Match;
+use schemars::schema::{RootSchema, Schema};
+use schemars::schema_for;
+
+/// Documents that have a published JSON Schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaKind {
+    /// JSON report output (`report --format json`)
+    Report,
+    /// Custom detector definitions (`--custom-detectors`)
+    CustomDetectors,
+    /// Scanner configuration file (`code-guardian.toml`)
+    Config,
+}
+
+impl SchemaKind {
+    /// All schema kinds, in the order they are listed to users
+    pub const ALL: [SchemaKind; 3] = [
+        SchemaKind::Report,
+        SchemaKind::CustomDetectors,
+        SchemaKind::Config,
+    ];
+
+    /// Name used on the command line and in published file names
+    pub fn name(self) -> &'static str {
+        match self {
+            SchemaKind::Report => "report",
+            SchemaKind::CustomDetectors => "custom-detectors",
+            SchemaKind::Config => "config",
+        }
+    }
+
+    /// Parse a schema kind from its command-line name
+    pub fn from_name(name: &str) -> ScanResult<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|kind| kind.name()).collect();
+                ScanError::config(format!(
+                    "Unknown schema '{}'. Available: {}",
+                    name,
+                    names.join(", ")
+                ))
+            })
+    }
+
+    /// Generate the JSON Schema for this document
+    pub fn schema(self) -> RootSchema {
+        match self {
+            SchemaKind::Report => schema_for!(Vec<Match>),
+            SchemaKind::CustomDetectors => schema_for!(Vec<CustomDetectorConfig>),
+            SchemaKind::Config => {
+                let mut schema = schema_for!(Config);
+                // The max_threads default is the CPU count of the machine generating the schema
+                let max_threads = schema
+                    .schema
+                    .object
+                    .as_mut()
+                    .and_then(|object| object.properties.get_mut("max_threads"));
+                if let Some(Schema::Object(max_threads)) = max_threads {
+                    let metadata = max_threads.metadata();
+                    metadata.default = None;
+                    metadata.description =
+                        Some("Worker threads (default: number of CPU cores)".to_string());
+                }
+                schema
+            }
+        }
+    }
+
+    /// Generate the pretty-printed JSON Schema for this document
+    pub fn schema_json(self) -> String {
+        serde_json::to_string_pretty(&self.schema()).expect("schemas serialize to JSON")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn published_schema_path(kind: SchemaKind) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../schemas")
+            .join(format!("{}.schema.json", kind.name()))
+    }
+
+    #[test]
+    fn test_schema_kind_names_round_trip() {
+        for kind in SchemaKind::ALL {
+            assert_eq!(SchemaKind::from_name(kind.name()).unwrap(), kind);
+        }
+        assert!(SchemaKind::from_name("sarif").is_err());
+    }
+
+    #[test]
+    fn test_report_schema_matches_json_output() {
+        let schema: serde_json::Value =
+            serde_json::from_str(&SchemaKind::Report.schema_json()).unwrap();
+        assert_eq!(schema["type"], "array");
+        let required = &schema["definitions"]["Match"]["required"];
+        for field in ["file_path", "line_number", "column", "pattern", "message"] {
+            assert!(required.as_array().unwrap().iter().any(|f| f == field));
+        }
+    }
+
+    #[test]
+    fn test_published_schemas_are_up_to_date() {
+        for kind in SchemaKind::ALL {
+            let path = published_schema_path(kind);
+            let published = std::fs::read_to_string(&path).unwrap_or_default();
+            assert_eq!(
+                published.trim_end(),
+                kind.schema_json(),
+                "{} is stale; regenerate with `code-guardian schema {} > {}`",
+                path.display(),
+                kind.name(),
+                path.display()
+            );
+        }
+    }
+}
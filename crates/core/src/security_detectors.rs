@@ -0,0 +1,355 @@
+//! Security detectors, reported under the `Security` category: insecure
+//! cryptography (weak hashes, hardcoded IVs and salts, non-cryptographic
+//! randomness for secrets, comparisons of secrets that leak timing) with High
+//! severity, and code execution through `eval` or deserialization of
+//! untrusted data with Critical severity
+
+use crate::detectors::{
+    classify, detect_call_pattern, detect_code_pattern, has_extension, CODE_EXTENSIONS,
+};
+use crate::{Match, PatternDetector, Severity};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::Path;
+
+/// Category reported by the detectors of this module
+pub const SECURITY_CATEGORY: &str = "Security";
+
+lazy_static! {
+    /// MD5 and SHA-1 through the common hashing APIs of each language
+    pub static ref WEAK_HASH_REGEX: Regex = Regex::new(
+        r#"(?i)\bhashlib\.(?:md5|sha1)\s*\(|\b(?:md5|sha1)::|\b(?:Md5|Sha1)::new\b|\b(?:MD5|SHA1)(?:CryptoServiceProvider)?\.Create\s*\(|\bDigestUtils\.(?:md5|sha1)\w*\s*\(|\b(?:createHash|getInstance|HashAlgorithm\.Create|new)\s*\(\s*["'](?:md5|sha-?1)["']|\bmd5\s*\("#
+    ).unwrap();
+
+    /// IVs, nonces and salts assigned a literal string, byte array or zeroes
+    pub static ref HARDCODED_IV_REGEX: Regex = Regex::new(
+        r#"(?i)\b(?:[a-z0-9]+_)?(?:iv|nonce|salt)\s*(?::[^=\n]*)?=\s*(?:b?["'][^"'\n]{4,}["']|\[\s*(?:0x)?[0-9a-f]+(?:u8)?\s*[,;]|Buffer\.from\(\s*["']|bytes\.fromhex\(|new\s+byte\[\]\s*\{\s*(?:0x)?[0-9a-f])"#
+    ).unwrap();
+
+    /// `Math.random()` or Python's `random` module producing a secret value
+    pub static ref INSECURE_RANDOM_TOKEN_REGEX: Regex = Regex::new(
+        r"(?i)\b\w*(?:token|secret|password|passwd|nonce|salt|session_?id|otp|api_?key)\w*\s*(?::[^=\n]*)?=[^;\n]*(?:Math\.random\(\)|\brandom\.(?:random|randint|choice|choices|getrandbits)\s*\()"
+    ).unwrap();
+
+    /// `==`/`!=` comparisons between two operands, checked for secrets below
+    static ref COMPARISON_REGEX: Regex = Regex::new(
+        r"([\w.]+(?:\(\))?)\s*(?:===?|!==?)\s*([\w.]+(?:\(\))?)"
+    ).unwrap();
+
+    static ref SECRET_OPERAND_REGEX: Regex = Regex::new(
+        r"(?i)(?:token|secret|signature|hmac|digest|password|passwd|api_?key)"
+    ).unwrap();
+
+    static ref LITERAL_OPERAND_REGEX: Regex = Regex::new(
+        r"(?i)^(?:null|none|nil|undefined|true|false|\d[\w.]*)$|\.(?:len|length|size|count|is_empty|isEmpty)\b"
+    ).unwrap();
+
+    static ref JS_EVAL_REGEX: Regex = Regex::new(r"\beval\s*\(|\bnew\s+Function\s*\(").unwrap();
+
+    static ref EVAL_REGEX: Regex = Regex::new(r"\beval\s*\(").unwrap();
+
+    /// `pickle`, `marshal` and friends, and PyYAML's `load` (checked for a
+    /// safe loader below)
+    static ref PYTHON_DESERIALIZATION_REGEX: Regex = Regex::new(
+        r"\b(?:c?[pP]ickle|dill|marshal|shelve)\.(?:loads?|open)\s*\(|\byaml\.(?:load|load_all|unsafe_load)\s*\("
+    ).unwrap();
+
+    static ref RUBY_DESERIALIZATION_REGEX: Regex = Regex::new(
+        r"\bMarshal\.(?:load|restore)\b"
+    ).unwrap();
+}
+
+const JS_EXTENSIONS: &[&str] = &["js", "jsx", "mjs", "cjs", "ts", "tsx", "vue", "svelte"];
+
+/// Detector for MD5 and SHA-1, which are broken for signatures and password
+/// hashing, e.g. `hashlib.md5(data)` or `createHash('sha1')`
+pub struct WeakHashDetector;
+
+impl PatternDetector for WeakHashDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        classify(
+            detect_code_pattern(content, file_path, "WEAK_HASH", &WEAK_HASH_REGEX),
+            Severity::High,
+            SECURITY_CATEGORY,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, CODE_EXTENSIONS)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&WEAK_HASH_REGEX)
+    }
+}
+
+/// Detector for initialization vectors, nonces and salts set to a constant,
+/// which defeats their purpose, e.g. `iv = b"0123456789abcdef"`
+pub struct HardcodedIvDetector;
+
+impl PatternDetector for HardcodedIvDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        classify(
+            detect_code_pattern(content, file_path, "HARDCODED_IV", &HARDCODED_IV_REGEX),
+            Severity::High,
+            SECURITY_CATEGORY,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, CODE_EXTENSIONS)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&HARDCODED_IV_REGEX)
+    }
+}
+
+/// Detector for tokens, passwords and salts generated with `Math.random()`
+/// or Python's `random` module instead of a cryptographic generator
+pub struct InsecureRandomTokenDetector;
+
+impl PatternDetector for InsecureRandomTokenDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        classify(
+            detect_code_pattern(
+                content,
+                file_path,
+                "INSECURE_RANDOM",
+                &INSECURE_RANDOM_TOKEN_REGEX,
+            ),
+            Severity::High,
+            SECURITY_CATEGORY,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, JS_EXTENSIONS) || has_extension(file_path, &["py"])
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&INSECURE_RANDOM_TOKEN_REGEX)
+    }
+}
+
+/// Detector for secrets such as tokens, signatures and HMACs compared with
+/// `==` or `!=`, which return as soon as a byte differs and so leak how much
+/// of a guess was right. Comparisons against `null`, numbers or lengths are
+/// not reported.
+pub struct TimingUnsafeComparisonDetector;
+
+impl PatternDetector for TimingUnsafeComparisonDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let mut matches = detect_code_pattern(
+            content,
+            file_path,
+            "TIMING_UNSAFE_COMPARISON",
+            &COMPARISON_REGEX,
+        );
+        let lines: Vec<&str> = content.lines().collect();
+        matches.retain(|m| {
+            let (line, start) = match_line(&lines, m);
+            COMPARISON_REGEX
+                .captures(&line[start..])
+                .is_some_and(|caps| compares_secret(&caps[1], &caps[2]))
+        });
+        classify(matches, Severity::High, SECURITY_CATEGORY)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, CODE_EXTENSIONS)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&SECRET_OPERAND_REGEX)
+    }
+}
+
+/// Detector for code built from strings at runtime: `eval(` and, in
+/// JavaScript and TypeScript, `new Function(`. Runs on JavaScript, TypeScript,
+/// Python and Ruby; method calls such as PyTorch's `model.eval()` and text in
+/// string literals, such as log messages, are not reported.
+pub struct EvalDetector;
+
+impl EvalDetector {
+    fn regex_for(file_path: &Path) -> Option<&'static Regex> {
+        if has_extension(file_path, JS_EXTENSIONS) {
+            Some(&JS_EVAL_REGEX)
+        } else if has_extension(file_path, &["py", "rb"]) {
+            Some(&EVAL_REGEX)
+        } else {
+            None
+        }
+    }
+}
+
+impl PatternDetector for EvalDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        let Some(re) = Self::regex_for(file_path) else {
+            return Vec::new();
+        };
+        let mut matches = detect_call_pattern(content, file_path, "UNSAFE_EVAL", re);
+        let lines: Vec<&str> = content.lines().collect();
+        matches.retain(|m| {
+            let (line, start) = match_line(&lines, m);
+            !line[..start].ends_with('.')
+        });
+        classify(matches, Severity::Critical, SECURITY_CATEGORY)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        Self::regex_for(file_path).is_some()
+    }
+}
+
+/// Detector for deserialization that can run code from its input: Python's
+/// `pickle`, `marshal`, `shelve` and `dill`, PyYAML's `yaml.load` without a
+/// `SafeLoader`, and Ruby's `Marshal.load`
+pub struct InsecureDeserializationDetector;
+
+impl InsecureDeserializationDetector {
+    fn regex_for(file_path: &Path) -> Option<&'static Regex> {
+        if has_extension(file_path, &["py"]) {
+            Some(&PYTHON_DESERIALIZATION_REGEX)
+        } else if has_extension(file_path, &["rb"]) {
+            Some(&RUBY_DESERIALIZATION_REGEX)
+        } else {
+            None
+        }
+    }
+}
+
+impl PatternDetector for InsecureDeserializationDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        let Some(re) = Self::regex_for(file_path) else {
+            return Vec::new();
+        };
+        let mut matches = detect_code_pattern(content, file_path, "INSECURE_DESERIALIZATION", re);
+        let lines: Vec<&str> = content.lines().collect();
+        matches.retain(|m| {
+            let (line, start) = match_line(&lines, m);
+            let call = &line[start..];
+            !(call.starts_with("yaml.load") && call.contains("SafeLoader"))
+        });
+        classify(matches, Severity::Critical, SECURITY_CATEGORY)
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        Self::regex_for(file_path).is_some()
+    }
+}
+
+/// The line a match starts on and the byte offset of its start in that line
+fn match_line<'a>(lines: &[&'a str], m: &Match) -> (&'a str, usize) {
+    let line = lines.get(m.line_number - 1).copied().unwrap_or_default();
+    let start = line
+        .char_indices()
+        .nth(m.column - 1)
+        .map_or(line.len(), |(i, _)| i);
+    (line, start)
+}
+
+/// Whether comparing `lhs` with `rhs` compares a secret with another value
+fn compares_secret(lhs: &str, rhs: &str) -> bool {
+    (SECRET_OPERAND_REGEX.is_match(lhs) || SECRET_OPERAND_REGEX.is_match(rhs))
+        && !LITERAL_OPERAND_REGEX.is_match(lhs)
+        && !LITERAL_OPERAND_REGEX.is_match(rhs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(detector: &dyn PatternDetector, content: &str, path: &str) -> Vec<usize> {
+        detector
+            .detect(content, Path::new(path))
+            .iter()
+            .map(|m| m.line_number)
+            .collect()
+    }
+
+    #[test]
+    fn test_weak_hash_detector() {
+        let py =
+            "import hashlib\nh = hashlib.md5(data)\nh = hashlib.sha256(data)\n# hashlib.sha1(x)\n";
+        assert_eq!(lines(&WeakHashDetector, py, "app.py"), vec![2]);
+        let js = "const a = crypto.createHash('sha1');\nconst b = crypto.createHash('sha256');\n";
+        assert_eq!(lines(&WeakHashDetector, js, "app.js"), vec![1]);
+        let java = "MessageDigest md = MessageDigest.getInstance(\"MD5\");\n";
+        let matches = WeakHashDetector.detect(java, Path::new("Hash.java"));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].severity, Some(Severity::High));
+        assert_eq!(matches[0].category.as_deref(), Some("Security"));
+        assert!(lines(&WeakHashDetector, "md5(x)\n", "notes.md").is_empty());
+    }
+
+    #[test]
+    fn test_hardcoded_iv_detector() {
+        let content = "iv = b\"0123456789abcdef\"\nlet nonce: [u8; 12] = [0; 12];\nconst salt = Buffer.from('c2FsdA==', 'base64');\niv = os.urandom(16)\nlet salt = generate_salt();\n";
+        assert_eq!(
+            lines(&HardcodedIvDetector, content, "crypto.py"),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_insecure_random_token_detector() {
+        let js = "const token = Math.random().toString(36);\nconst x = Math.random();\n";
+        assert_eq!(lines(&InsecureRandomTokenDetector, js, "auth.js"), vec![1]);
+        let py = "reset_token = ''.join(random.choice(chars) for _ in range(8))\ntoken = secrets.token_hex(16)\n";
+        assert_eq!(lines(&InsecureRandomTokenDetector, py, "auth.py"), vec![1]);
+        assert!(lines(&InsecureRandomTokenDetector, js, "auth.rs").is_empty());
+    }
+
+    #[test]
+    fn test_timing_unsafe_comparison_detector() {
+        let content = "if (req.token === expected) {}\nif (token == null) {}\nif signature != computed_signature:\nif token.length == 0 {}\nif count == total {}\n";
+        assert_eq!(
+            lines(&TimingUnsafeComparisonDetector, content, "auth.ts"),
+            vec![1, 3]
+        );
+    }
+
+    #[test]
+    fn test_eval_detector() {
+        let js = "eval(userInput);\nconst f = new Function('a', body);\nconst evaluate = () => 1;\n// eval(x)\n";
+        assert_eq!(lines(&EvalDetector, js, "app.js"), vec![1, 2]);
+        let py = "result = eval(expr)\nmodel.eval()\n";
+        let matches = EvalDetector.detect(py, Path::new("train.py"));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].pattern, "UNSAFE_EVAL");
+        assert_eq!(matches[0].severity, Some(Severity::Critical));
+        assert!(lines(&EvalDetector, "eval(x)\n", "main.rs").is_empty());
+        let text =
+            "s = \"eval(x)\"\nlog.warn('never call eval(input)')\nrun(eval(code), \"eval(\")\n";
+        assert_eq!(lines(&EvalDetector, text, "app.py"), vec![3]);
+        assert_eq!(lines(&EvalDetector, text, "app.js"), vec![3]);
+    }
+
+    #[test]
+    fn test_insecure_deserialization_detector() {
+        let py = "data = pickle.loads(blob)\nconf = yaml.load(f)\nconf = yaml.load(f, Loader=yaml.SafeLoader)\nconf = yaml.safe_load(f)\nraw = yaml.unsafe_load(f)\n";
+        assert_eq!(
+            lines(&InsecureDeserializationDetector, py, "app.py"),
+            vec![1, 2, 5]
+        );
+        let rb = "obj = Marshal.load(data)\nobj = JSON.parse(data)\n";
+        assert_eq!(
+            lines(&InsecureDeserializationDetector, rb, "app.rb"),
+            vec![1]
+        );
+        assert!(lines(&InsecureDeserializationDetector, rb, "app.js").is_empty());
+    }
+}
@@ -0,0 +1,220 @@
+//! Cheaply cloned strings for the file paths and pattern names of matches
+
+use dashmap::DashSet;
+use lazy_static::lazy_static;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::{Borrow, Cow};
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+lazy_static! {
+    static ref INTERNED: DashSet<Arc<str>> = DashSet::new();
+}
+
+/// An immutable, reference-counted string. Every match of a file shares one
+/// allocation for its path, and every match of a pattern one for its name,
+/// so cloning a [`crate::Match`] only copies its message.
+///
+/// Derefs to `str` and compares equal to `&str` and `String`.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SharedStr(Arc<str>);
+
+impl SharedStr {
+    /// The shared copy of `s` from a process-wide table, for the small set of
+    /// strings such as pattern names that repeat across the whole scan
+    pub fn interned(s: &str) -> Self {
+        if let Some(existing) = INTERNED.get(s) {
+            return Self(existing.clone());
+        }
+        let shared: Arc<str> = Arc::from(s);
+        INTERNED.insert(shared.clone());
+        Self(shared)
+    }
+
+    /// The lossy UTF-8 form of `path`, as stored in [`crate::Match::file_path`]
+    pub fn from_path(path: &std::path::Path) -> Self {
+        Self::from(path.to_string_lossy())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for SharedStr {
+    fn default() -> Self {
+        Self::from("")
+    }
+}
+
+impl Deref for SharedStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for SharedStr {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<std::path::Path> for SharedStr {
+    fn as_ref(&self) -> &std::path::Path {
+        std::path::Path::new(&*self.0)
+    }
+}
+
+impl AsRef<std::ffi::OsStr> for SharedStr {
+    fn as_ref(&self) -> &std::ffi::OsStr {
+        std::ffi::OsStr::new(&*self.0)
+    }
+}
+
+impl Borrow<str> for SharedStr {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Debug for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl From<&str> for SharedStr {
+    fn from(s: &str) -> Self {
+        Self(Arc::from(s))
+    }
+}
+
+impl From<String> for SharedStr {
+    fn from(s: String) -> Self {
+        Self(Arc::from(s))
+    }
+}
+
+impl From<&String> for SharedStr {
+    fn from(s: &String) -> Self {
+        Self::from(s.as_str())
+    }
+}
+
+impl From<Cow<'_, str>> for SharedStr {
+    fn from(s: Cow<'_, str>) -> Self {
+        match s {
+            Cow::Borrowed(s) => Self::from(s),
+            Cow::Owned(s) => Self::from(s),
+        }
+    }
+}
+
+impl From<SharedStr> for String {
+    fn from(s: SharedStr) -> Self {
+        s.0.to_string()
+    }
+}
+
+impl PartialEq<str> for SharedStr {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for SharedStr {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for SharedStr {
+    fn eq(&self, other: &String) -> bool {
+        &*self.0 == other.as_str()
+    }
+}
+
+impl PartialEq<Cow<'_, str>> for SharedStr {
+    fn eq(&self, other: &Cow<'_, str>) -> bool {
+        &*self.0 == other.as_ref()
+    }
+}
+
+impl PartialEq<SharedStr> for str {
+    fn eq(&self, other: &SharedStr) -> bool {
+        self == &*other.0
+    }
+}
+
+impl PartialEq<SharedStr> for &str {
+    fn eq(&self, other: &SharedStr) -> bool {
+        *self == &*other.0
+    }
+}
+
+impl PartialEq<SharedStr> for String {
+    fn eq(&self, other: &SharedStr) -> bool {
+        self.as_str() == &*other.0
+    }
+}
+
+impl Serialize for SharedStr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for SharedStr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from)
+    }
+}
+
+impl JsonSchema for SharedStr {
+    fn schema_name() -> String {
+        String::schema_name()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        String::json_schema(gen)
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_str() {
+        let a = SharedStr::interned("TODO");
+        let b = SharedStr::interned("TODO");
+        assert!(Arc::ptr_eq(&a.0, &b.0));
+
+        assert_eq!(a, "TODO");
+        assert_eq!("TODO", a);
+        assert_eq!(a, "TODO".to_string());
+        assert_eq!(a.len(), 4);
+        assert_eq!(format!("{} {:?}", a, a), "TODO \"TODO\"");
+
+        let json = serde_json::to_string(&a).unwrap();
+        assert_eq!(json, "\"TODO\"");
+        let back: SharedStr = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, a);
+    }
+}
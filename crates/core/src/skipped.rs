@@ -0,0 +1,214 @@
+use crate::errors::ScanError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Files larger than this are skipped by the scanners
+pub const MAX_SCANNED_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Why a file was left out of a scan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum SkipReason {
+    PermissionDenied,
+    /// Text file that is not valid UTF-8; files detected as binary are never reported
+    InvalidUtf8,
+    /// Changed while it was read, every time it was read
+    Unstable,
+    TooLarge {
+        size: u64,
+        limit: u64,
+    },
+    Unreadable {
+        error: String,
+    },
+}
+
+impl SkipReason {
+    /// Classifies an error from reading a file
+    pub fn from_error(error: &ScanError) -> Self {
+        match error {
+            ScanError::Io { source, .. }
+                if source.kind() == std::io::ErrorKind::PermissionDenied =>
+            {
+                SkipReason::PermissionDenied
+            }
+            ScanError::Io { source, .. } => SkipReason::Unreadable {
+                error: source.to_string(),
+            },
+            ScanError::Encoding { .. } => SkipReason::InvalidUtf8,
+            ScanError::Unstable { .. } => SkipReason::Unstable,
+            other => SkipReason::Unreadable {
+                error: other.to_string(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::PermissionDenied => f.write_str("permission denied"),
+            SkipReason::InvalidUtf8 => f.write_str("invalid UTF-8"),
+            SkipReason::Unstable => f.write_str("unstable, changed while scanned"),
+            SkipReason::TooLarge { size, limit } => {
+                write!(f, "too large ({} bytes, limit {} bytes)", size, limit)
+            }
+            SkipReason::Unreadable { error } => write!(f, "unreadable: {}", error),
+        }
+    }
+}
+
+/// A file that was found by the walker but could not be scanned
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedFile {
+    pub path: String,
+    #[serde(flatten)]
+    pub reason: SkipReason,
+}
+
+/// Collects the files skipped during a scan from parallel workers
+#[derive(Debug, Default)]
+pub struct SkipLog {
+    files: Mutex<Vec<SkippedFile>>,
+}
+
+impl SkipLog {
+    pub fn record(&self, path: &Path, reason: SkipReason) {
+        let skipped = SkippedFile {
+            path: path.to_string_lossy().to_string(),
+            reason,
+        };
+        self.lock().push(skipped);
+    }
+
+    pub fn record_error(&self, path: &Path, error: &ScanError) {
+        self.record(path, SkipReason::from_error(error));
+    }
+
+    /// Records the entry the walker below `root` failed to read, such as a
+    /// directory it couldn't list, so it isn't left out of the scan silently.
+    /// Errors that aren't about reading an entry, like a bad pattern in an
+    /// ignore file, are not recorded.
+    pub fn record_walk_error(&self, root: &Path, error: ignore::Error) {
+        if error.io_error().is_none() {
+            return;
+        }
+        let path = walk_error_path(&error).unwrap_or_else(|| root.to_path_buf());
+        self.record_error(&path, &ScanError::from_walk(&path, error));
+    }
+
+    /// Records the file and returns false if it exceeds [`MAX_SCANNED_FILE_SIZE`]
+    pub fn check_size(&self, path: &Path, size: u64) -> bool {
+        if size > MAX_SCANNED_FILE_SIZE {
+            self.record(
+                path,
+                SkipReason::TooLarge {
+                    size,
+                    limit: MAX_SCANNED_FILE_SIZE,
+                },
+            );
+            return false;
+        }
+        true
+    }
+
+    /// The recorded files, sorted by path
+    pub fn files(&self) -> Vec<SkippedFile> {
+        let mut files = self.lock().clone();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<SkippedFile>> {
+        self.files.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The entry a walk error is about
+fn walk_error_path(error: &ignore::Error) -> Option<PathBuf> {
+    match error {
+        ignore::Error::WithPath { path, .. } => Some(path.clone()),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            walk_error_path(err)
+        }
+        ignore::Error::Partial(errors) => errors.iter().find_map(walk_error_path),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_reasons() {
+        let denied = ScanError::io(
+            "a.rs",
+            std::io::Error::from(std::io::ErrorKind::PermissionDenied),
+        );
+        assert_eq!(
+            SkipReason::from_error(&denied),
+            SkipReason::PermissionDenied
+        );
+        assert_eq!(
+            SkipReason::from_error(&ScanError::encoding("a.rs", "bad byte")),
+            SkipReason::InvalidUtf8
+        );
+        assert_eq!(
+            SkipReason::from_error(&ScanError::unstable("a.log")),
+            SkipReason::Unstable
+        );
+
+        let log = SkipLog::default();
+        assert!(log.check_size(Path::new("small.rs"), 10));
+        assert!(!log.check_size(Path::new("huge.rs"), MAX_SCANNED_FILE_SIZE + 1));
+        log.record_error(Path::new("denied.rs"), &denied);
+
+        let skipped = log.files();
+        assert_eq!(skipped.len(), 2);
+        assert_eq!(skipped[0].path, "denied.rs");
+        assert_eq!(
+            skipped[1].reason.to_string(),
+            "too large (5242881 bytes, limit 5242880 bytes)"
+        );
+        log.clear();
+        assert!(log.files().is_empty());
+
+        let json = serde_json::to_string(&skipped[0]).unwrap();
+        assert_eq!(json, r#"{"path":"denied.rs","reason":"permission_denied"}"#);
+
+        let unlistable = ignore::Error::WithDepth {
+            depth: 2,
+            err: Box::new(ignore::Error::WithPath {
+                path: PathBuf::from("root/deep"),
+                err: Box::new(ignore::Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "path too long",
+                ))),
+            }),
+        };
+        log.record_walk_error(Path::new("root"), unlistable);
+        log.record_walk_error(
+            Path::new("root"),
+            ignore::Error::Glob {
+                glob: Some("[".to_string()),
+                err: "unclosed class".to_string(),
+            },
+        );
+        let skipped = log.files();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].path, "root/deep");
+        assert_eq!(
+            skipped[0].reason,
+            SkipReason::Unreadable {
+                error: "path too long".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! Reading files that may be written while they are scanned, e.g. by an
+//! editor, a formatter or a build. A file read half-written yields findings
+//! on lines that no longer exist, so a read only counts if the file has the
+//! same size and modification time afterwards as before. Files are read
+//! into memory rather than mapped: the mapped text was copied out anyway,
+//! and a mapping of a file truncated meanwhile faults the whole process.
+
+use crate::errors::{ScanError, ScanResult};
+use std::fs::{File, Metadata};
+use std::io::Read;
+use std::path::Path;
+
+/// Reads of a file that changed before it is reported as unstable
+const READ_ATTEMPTS: usize = 2;
+
+/// Reads `path` as UTF-8 text, reading it again if it changed meanwhile.
+/// Fails with [`ScanError::Unstable`] if it changed during every attempt.
+pub fn read_stable(path: &Path) -> ScanResult<String> {
+    read_stable_with(path, |file, bytes| file.read_to_end(bytes))
+}
+
+/// [`read_stable`], reading the opened file with `read`
+fn read_stable_with(
+    path: &Path,
+    mut read: impl FnMut(&mut File, &mut Vec<u8>) -> std::io::Result<usize>,
+) -> ScanResult<String> {
+    for _ in 0..READ_ATTEMPTS {
+        if let Some(bytes) = read_unchanged(path, &mut read)? {
+            return String::from_utf8(bytes).map_err(|e| ScanError::encoding(path, e.utf8_error()));
+        }
+    }
+    Err(ScanError::unstable(path))
+}
+
+/// The content of `path`, or `None` if it changed while being read
+fn read_unchanged(
+    path: &Path,
+    read: &mut impl FnMut(&mut File, &mut Vec<u8>) -> std::io::Result<usize>,
+) -> ScanResult<Option<Vec<u8>>> {
+    let mut file = File::open(path).map_err(|e| ScanError::io(path, e))?;
+    let before = file.metadata().map_err(|e| ScanError::io(path, e))?;
+    let mut bytes = Vec::with_capacity(before.len() as usize);
+    read(&mut file, &mut bytes).map_err(|e| ScanError::io(path, e))?;
+    // Through the path, so a file replaced by a rename counts as changed
+    let after = std::fs::metadata(path).map_err(|e| ScanError::io(path, e))?;
+    let unchanged = bytes.len() as u64 == before.len() && same_version(&before, &after);
+    Ok(unchanged.then_some(bytes))
+}
+
+fn same_version(before: &Metadata, after: &Metadata) -> bool {
+    before.len() == after.len() && before.modified().ok() == after.modified().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_read_stable() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("a.rs");
+        std::fs::write(&path, "// TODO: fix\n").unwrap();
+        assert_eq!(read_stable(&path).unwrap(), "// TODO: fix\n");
+
+        std::fs::write(&path, [0xff, 0xfe]).unwrap();
+        assert!(matches!(
+            read_stable(&path),
+            Err(ScanError::Encoding { .. })
+        ));
+        assert!(matches!(
+            read_stable(&temp_dir.path().join("missing.rs")),
+            Err(ScanError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn test_file_changed_while_read() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("out.log");
+        std::fs::write(&path, "first\n").unwrap();
+        // Appends a line while the file is being read, `changes` times
+        let appending = |changes: usize| {
+            let path = path.clone();
+            let mut reads = 0;
+            move |file: &mut File, bytes: &mut Vec<u8>| {
+                if reads < changes {
+                    let mut log = std::fs::OpenOptions::new().append(true).open(&path)?;
+                    log.write_all(b"more\n")?;
+                }
+                reads += 1;
+                file.read_to_end(bytes)
+            }
+        };
+
+        // Read again once it changed
+        let content = read_stable_with(&path, appending(1)).unwrap();
+        assert_eq!(content, "first\nmore\n");
+
+        let err = read_stable_with(&path, appending(READ_ATTEMPTS)).unwrap_err();
+        assert!(matches!(err, ScanError::Unstable { .. }));
+        assert!(err
+            .to_string()
+            .ends_with("out.log changed while it was read"));
+    }
+}
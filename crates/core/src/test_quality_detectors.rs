@@ -0,0 +1,355 @@
+//! Test hygiene: disabled tests, tests that assert nothing and tests that
+//! sleep. These detectors only look at test code, which is all of a file on
+//! a test path and the `#[cfg(test)]` module of a Rust source file, so they
+//! stay silent on production code.
+
+use crate::comment_spans::CommentIndex;
+use crate::detectors::{
+    build_glob_set, classify, detect_code_pattern, has_extension, CODE_EXTENSIONS,
+    DEFAULT_TEST_PATH_GLOBS,
+};
+use crate::line_index::LineIndex;
+use crate::quality_detectors::{block_end, function_spans};
+use crate::{Match, PatternDetector, Severity, SharedStr};
+use globset::GlobSet;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::Path;
+
+/// Category reported by the detectors of this module
+pub const TEST_QUALITY_CATEGORY: &str = "TestQuality";
+
+const JS_EXTENSIONS: &[&str] = &["js", "jsx", "mjs", "cjs", "ts", "tsx"];
+
+lazy_static! {
+    static ref RUST_TEST_MODULE_REGEX: Regex = Regex::new(r"(?m)^[ \t]*#\[cfg\(test\)\]").unwrap();
+
+    /// `#[ignore]`, `it.skip`, `xit(`, `@Disabled`, `@Ignore`,
+    /// `@pytest.mark.skip`, `t.Skip()` and xUnit's `Skip = "..."`
+    static ref DISABLED_TEST_REGEX: Regex = Regex::new(
+        r#"#\[ignore\b|\b(?:it|test|describe|context|suite)\.skip\b|\bx(?:it|test|describe)\s*\(|@(?:Disabled|Ignore)\b|@(?:pytest\.mark|unittest)\.skip\b|\bt\.Skip(?:Now|f)?\(|\[Ignore\b|\bSkip\s*=\s*""#
+    ).unwrap();
+
+    /// `sleep(...)` in any spelling: `thread::sleep`, `time.sleep`,
+    /// `Thread.sleep`, `time.Sleep`, `Task.Delay` and Ruby's `sleep 1`
+    static ref SLEEP_REGEX: Regex = Regex::new(
+        r"\b(?:[Ss]leep|usleep|Task\.Delay)\s*\(|\bsleep\s+\d"
+    ).unwrap();
+
+    /// Assertions of common test frameworks
+    static ref ASSERTION_REGEX: Regex = Regex::new(
+        r"(?i)\bassert|\bexpect\s*[(.]|\.should\b|\bt\.(?:Error|Fatal|Fail)|\bverify\s*\(|\braises\b|\bpanic!|\bassertThrows\b"
+    ).unwrap();
+
+    /// Attributes and annotations marking a test function
+    static ref TEST_ATTRIBUTE_REGEX: Regex = Regex::new(
+        r"#\[(?:\w+::)*test\b|@Test\b|\[(?:Test|TestMethod|Fact|Theory)\b"
+    ).unwrap();
+
+    /// `it("...", () => {` and `test("...", async function () {`
+    static ref JS_TEST_REGEX: Regex = Regex::new(
+        r#"\b(?:it|test)(?:\.only)?\(\s*(?:"(?P<double>[^"\n]*)"|'(?P<single>[^'\n]*)'|`(?P<backtick>[^`]*)`)\s*,\s*(?:async\s+)?(?:function\s*\w*\s*\([^)]*\)|\([^)]*\)|\w+)\s*(?:=>\s*)?\{"#
+    ).unwrap();
+}
+
+/// Test path globs shared by the detectors of this module
+fn default_test_paths() -> GlobSet {
+    build_glob_set(DEFAULT_TEST_PATH_GLOBS).expect("default test globs are valid")
+}
+
+/// Byte offset where the test code of a file starts, or `None` if it has none
+fn test_code_start(content: &str, file_path: &Path, test_paths: &GlobSet) -> Option<usize> {
+    if test_paths.is_match(file_path) {
+        return Some(0);
+    }
+    if has_extension(file_path, &["rs"]) {
+        return RUST_TEST_MODULE_REGEX.find(content).map(|m| m.start());
+    }
+    None
+}
+
+/// Matches of `re` in the test code of a file
+fn detect_in_tests(
+    content: &str,
+    file_path: &Path,
+    test_paths: &GlobSet,
+    pattern_name: &str,
+    re: &Regex,
+) -> Vec<Match> {
+    let Some(start) = test_code_start(content, file_path, test_paths) else {
+        return Vec::new();
+    };
+    let first_line = content[..start].matches('\n').count() + 1;
+    let mut matches = detect_code_pattern(content, file_path, pattern_name, re);
+    matches.retain(|m| m.line_number >= first_line);
+    classify(matches, Severity::Low, TEST_QUALITY_CATEGORY)
+}
+
+/// Detector for tests switched off with `#[ignore]`, `it.skip`, `@Disabled`
+/// and the like, which stop running without anyone noticing
+pub struct DisabledTestDetector {
+    test_paths: GlobSet,
+}
+
+impl Default for DisabledTestDetector {
+    fn default() -> Self {
+        Self {
+            test_paths: default_test_paths(),
+        }
+    }
+}
+
+impl PatternDetector for DisabledTestDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        detect_in_tests(
+            content,
+            file_path,
+            &self.test_paths,
+            "DISABLED_TEST",
+            &DISABLED_TEST_REGEX,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, CODE_EXTENSIONS)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&DISABLED_TEST_REGEX)
+    }
+}
+
+/// Detector for sleeps in tests, which make them slow and flaky; waiting on
+/// the condition itself or using a fake clock is more reliable
+pub struct SleepInTestDetector {
+    test_paths: GlobSet,
+}
+
+impl Default for SleepInTestDetector {
+    fn default() -> Self {
+        Self {
+            test_paths: default_test_paths(),
+        }
+    }
+}
+
+impl PatternDetector for SleepInTestDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        detect_in_tests(
+            content,
+            file_path,
+            &self.test_paths,
+            "SLEEP_IN_TEST",
+            &SLEEP_REGEX,
+        )
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, CODE_EXTENSIONS)
+    }
+
+    fn prefilter(&self) -> Option<&Regex> {
+        Some(&SLEEP_REGEX)
+    }
+}
+
+/// Detector for test functions without any assertion, which pass as long as
+/// nothing throws. Tests are `#[test]` functions in Rust, `it(...)` and
+/// `test(...)` callbacks in JavaScript and TypeScript, `test_*` functions in
+/// Python, `Test*` functions in Go, and methods annotated `@Test`, `[Test]`
+/// or `[Fact]` in Java, Kotlin and C#. Tests expecting a panic or exception
+/// through their attribute are left alone.
+pub struct AssertlessTestDetector {
+    test_paths: GlobSet,
+}
+
+impl Default for AssertlessTestDetector {
+    fn default() -> Self {
+        Self {
+            test_paths: default_test_paths(),
+        }
+    }
+}
+
+impl AssertlessTestDetector {
+    /// Name offset, name and end of each test function from `start` on
+    fn tests<'a>(
+        &self,
+        content: &'a str,
+        file_path: &Path,
+        start: usize,
+    ) -> Vec<(usize, &'a str, usize)> {
+        let comments = CommentIndex::new(content, file_path);
+        if has_extension(file_path, JS_EXTENSIONS) {
+            return JS_TEST_REGEX
+                .captures_iter(content)
+                .filter_map(|caps| {
+                    let whole = caps.get(0).expect("group 0 always matches");
+                    if whole.start() < start || comments.is_comment(whole.start()) {
+                        return None;
+                    }
+                    let name = caps
+                        .name("double")
+                        .or_else(|| caps.name("single"))
+                        .or_else(|| caps.name("backtick"))?;
+                    Some((
+                        name.start(),
+                        name.as_str(),
+                        block_end(content, whole.end() - 1, &comments),
+                    ))
+                })
+                .collect();
+        }
+        let python = has_extension(file_path, &["py"]);
+        let go = has_extension(file_path, &["go"]);
+        function_spans(content, file_path)
+            .into_iter()
+            .filter(|function| {
+                if function.start < start {
+                    return false;
+                }
+                if python {
+                    return function.name.starts_with("test");
+                }
+                if go {
+                    return function.name.starts_with("Test") && function.name != "TestMain";
+                }
+                // Attributes and annotations since the previous item
+                let preamble_start = content[..function.start]
+                    .rfind(['{', '}', ';'])
+                    .map_or(0, |i| i + 1);
+                let preamble = &content[preamble_start..function.start];
+                TEST_ATTRIBUTE_REGEX.is_match(preamble)
+                    && !preamble.contains("should_panic")
+                    && !preamble.contains("expected")
+            })
+            .map(|function| (function.start, function.name, function.end))
+            .collect()
+    }
+}
+
+impl PatternDetector for AssertlessTestDetector {
+    fn detect(&self, content: &str, file_path: &Path) -> Vec<Match> {
+        if !self.applies_to(file_path) {
+            return Vec::new();
+        }
+        let Some(start) = test_code_start(content, file_path, &self.test_paths) else {
+            return Vec::new();
+        };
+        let mut lines = None;
+        let mut shared_path = None;
+        self.tests(content, file_path, start)
+            .into_iter()
+            .filter(|(name_start, _, end)| !ASSERTION_REGEX.is_match(&content[*name_start..*end]))
+            .map(|(name_start, name, _)| {
+                let (line_number, column) = lines
+                    .get_or_insert_with(|| LineIndex::new(content))
+                    .position(content, name_start);
+                Match {
+                    file_path: shared_path
+                        .get_or_insert_with(|| SharedStr::from_path(file_path))
+                        .clone(),
+                    line_number,
+                    column,
+                    pattern: SharedStr::interned("ASSERTLESS_TEST"),
+                    message: format!("ASSERTLESS_TEST: test '{}' asserts nothing", name),
+                    severity: Some(Severity::Low),
+                    category: Some(TEST_QUALITY_CATEGORY.to_string()),
+                    end: None,
+                    author: None,
+                }
+            })
+            .collect()
+    }
+
+    fn applies_to(&self, file_path: &Path) -> bool {
+        has_extension(file_path, CODE_EXTENSIONS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(detector: &dyn PatternDetector, content: &str, path: &str) -> Vec<usize> {
+        detector
+            .detect(content, Path::new(path))
+            .iter()
+            .map(|m| m.line_number)
+            .collect()
+    }
+
+    const RUST_SOURCE: &str = "fn run() {\n    std::thread::sleep(DELAY);\n}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    #[ignore]\n    fn slow() {\n        std::thread::sleep(DELAY);\n        assert!(run());\n    }\n\n    #[test]\n    fn smoke() {\n        run();\n    }\n\n    #[test]\n    #[should_panic]\n    fn fails() {\n        run();\n    }\n}\n";
+
+    #[test]
+    fn test_disabled_test_detector() {
+        let detector = DisabledTestDetector::default();
+        assert_eq!(lines(&detector, RUST_SOURCE, "src/lib.rs"), vec![8]);
+        assert_eq!(
+            lines(
+                &detector,
+                "it.skip('loads', () => {});\nxit('saves', () => {});\n// it.skip('old')\nit('runs', () => {});\n",
+                "src/app.test.ts"
+            ),
+            vec![1, 2]
+        );
+        assert_eq!(
+            lines(
+                &detector,
+                "@Disabled(\"flaky\")\n@Test\nvoid loads() {}\n",
+                "src/test/java/AppTest.java"
+            ),
+            vec![1]
+        );
+        // Production code is left alone
+        assert!(lines(&detector, "it.skip('x', f);\n", "src/app.ts").is_empty());
+    }
+
+    #[test]
+    fn test_sleep_in_test_detector() {
+        let detector = SleepInTestDetector::default();
+        let matches = detector.detect(RUST_SOURCE, Path::new("src/lib.rs"));
+        assert_eq!(
+            matches.iter().map(|m| m.line_number).collect::<Vec<_>>(),
+            vec![10]
+        );
+        assert_eq!(matches[0].category.as_deref(), Some("TestQuality"));
+        assert_eq!(
+            lines(
+                &detector,
+                "import time\n\ndef test_poll():\n    time.sleep(2)\n",
+                "tests/test_poll.py"
+            ),
+            vec![4]
+        );
+    }
+
+    #[test]
+    fn test_assertless_test_detector() {
+        let detector = AssertlessTestDetector::default();
+        let matches = detector.detect(RUST_SOURCE, Path::new("src/lib.rs"));
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.line_number, m.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(15, "ASSERTLESS_TEST: test 'smoke' asserts nothing")]
+        );
+
+        let js = "describe('cart', () => {\n  it('adds items', () => {\n    cart.add(item);\n  });\n  it('totals', async () => {\n    expect(cart.total()).toBe(3);\n  });\n});\n";
+        assert_eq!(lines(&detector, js, "src/cart.spec.js"), vec![2]);
+
+        let python = "def helper():\n    pass\n\ndef test_load():\n    load()\n\ndef test_save(self):\n    self.assertTrue(save())\n";
+        assert_eq!(lines(&detector, python, "tests/test_io.py"), vec![4]);
+
+        let go = "func TestLoad(t *testing.T) {\n\tLoad()\n}\n\nfunc TestSave(t *testing.T) {\n\tif err := Save(); err != nil {\n\t\tt.Fatal(err)\n\t}\n}\n";
+        assert_eq!(lines(&detector, go, "store_test.go"), vec![1]);
+        assert!(lines(&detector, go, "store.go").is_empty());
+    }
+}
@@ -0,0 +1,422 @@
+//! TLS for `code-guardian serve` and for the coordinator's handshakes with
+//! distributed workers, both made with rustls.
+
+use crate::errors::{ScanError, ScanResult};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use std::io::{BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Reads the PEM certificate chain at `cert`, leaf first, and the PEM
+/// private key at `key` into a server config offering TLS 1.2 and 1.3
+pub fn server_tls_config(cert: &Path, key: &Path) -> ScanResult<rustls::ServerConfig> {
+    let certs = read_certificates(cert)?;
+    let private_key = read_private_key(key)?;
+    let mut config = rustls::ServerConfig::builder_with_provider(crypto_provider())
+        .with_safe_default_protocol_versions()
+        .map_err(ScanError::config)?
+        .with_no_client_auth()
+        .with_single_cert(certs, private_key)
+        .map_err(|e| {
+            ScanError::config(format!(
+                "{} and {} are not a usable certificate and key: {}",
+                cert.display(),
+                key.display(),
+                e
+            ))
+        })?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+fn crypto_provider() -> Arc<rustls::crypto::CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+fn read_certificates(path: &Path) -> ScanResult<Vec<CertificateDer<'static>>> {
+    let file = std::fs::File::open(path).map_err(|e| ScanError::io(path, e))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ScanError::io(path, e))?;
+    if certs.is_empty() {
+        return Err(ScanError::config(format!(
+            "{} has no PEM certificate",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
+
+fn read_private_key(path: &Path) -> ScanResult<PrivateKeyDer<'static>> {
+    let file = std::fs::File::open(path).map_err(|e| ScanError::io(path, e))?;
+    rustls_pemfile::private_key(&mut BufReader::new(file))
+        .map_err(|e| ScanError::io(path, e))?
+        .ok_or_else(|| ScanError::config(format!("{} has no PEM private key", path.display())))
+}
+
+/// TLS options of the coordinator's connections to workers. With any of
+/// them set, workers must be reached over https.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkerTls {
+    /// Client certificate presented to workers that require mutual TLS
+    pub cert: Option<PathBuf>,
+    /// Private key of `cert`
+    pub key: Option<PathBuf>,
+    /// CA certificates that sign the workers' certificates, instead of the
+    /// system's
+    pub ca: Option<PathBuf>,
+}
+
+impl WorkerTls {
+    pub fn is_enabled(&self) -> bool {
+        self.cert.is_some() || self.key.is_some() || self.ca.is_some()
+    }
+
+    /// Scheme of worker URLs built from SRV records
+    pub fn scheme(&self) -> &'static str {
+        if self.is_enabled() {
+            "https"
+        } else {
+            "http"
+        }
+    }
+
+    /// Fails unless a certificate comes with its key and every file exists
+    pub fn validate(&self) -> ScanResult<()> {
+        if self.cert.is_some() != self.key.is_some() {
+            return Err(ScanError::config(
+                "a worker TLS client certificate needs its key, and the key its certificate",
+            ));
+        }
+        for path in [&self.cert, &self.key, &self.ca].into_iter().flatten() {
+            if !path.is_file() {
+                return Err(ScanError::config(format!(
+                    "{}: no such TLS file",
+                    path.display()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Client config trusting `ca`, else the system's CA certificates, and
+    /// presenting `cert` to workers that ask for a client certificate
+    pub fn client_config(&self) -> ScanResult<rustls::ClientConfig> {
+        let mut roots = rustls::RootCertStore::empty();
+        match &self.ca {
+            Some(ca) => {
+                for cert in read_certificates(ca)? {
+                    roots.add(cert).map_err(|e| {
+                        ScanError::config(format!("{} is not a usable CA: {}", ca.display(), e))
+                    })?;
+                }
+            }
+            None => {
+                roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+            }
+        }
+        let builder = rustls::ClientConfig::builder_with_provider(crypto_provider())
+            .with_safe_default_protocol_versions()
+            .map_err(ScanError::config)?
+            .with_root_certificates(roots);
+        match (&self.cert, &self.key) {
+            (Some(cert), Some(key)) => builder
+                .with_client_auth_cert(read_certificates(cert)?, read_private_key(key)?)
+                .map_err(|e| {
+                    ScanError::config(format!(
+                        "{} and {} are not a usable certificate and key: {}",
+                        cert.display(),
+                        key.display(),
+                        e
+                    ))
+                }),
+            _ => Ok(builder.with_no_client_auth()),
+        }
+    }
+
+    /// POSTs a JSON body to the https `url` and returns the response body,
+    /// failing on statuses other than 2xx
+    pub(crate) fn post_json(
+        &self,
+        url: &str,
+        body: &[u8],
+        timeout: Duration,
+    ) -> Result<Vec<u8>, String> {
+        let rest = url
+            .strip_prefix("https://")
+            .ok_or("URL must start with https://")?;
+        let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        let path = if path.is_empty() { "/" } else { path };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) if port.parse::<u16>().is_ok() => (host, port),
+            _ => (authority, "443"),
+        };
+        let server_name = ServerName::try_from(
+            host.trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string(),
+        )
+        .map_err(|e| format!("invalid host {}: {}", host, e))?;
+        let config = self.client_config().map_err(|e| e.to_string())?;
+        let connection = rustls::ClientConnection::new(Arc::new(config), server_name)
+            .map_err(|e| e.to_string())?;
+
+        let tcp = connect(&format!("{}:{}", host, port), timeout)?;
+        let mut stream = rustls::StreamOwned::new(connection, tcp);
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n",
+            path,
+            authority,
+            body.len()
+        );
+        stream
+            .write_all(request.as_bytes())
+            .and_then(|_| stream.write_all(body))
+            .and_then(|_| stream.flush())
+            .map_err(|e| e.to_string())?;
+        let mut response = Vec::new();
+        match stream.read_to_end(&mut response) {
+            // Workers may close the connection without a TLS close_notify;
+            // the body length is still checked against its header
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {}
+            Err(e) => return Err(e.to_string()),
+        }
+        response_body(&response)
+    }
+}
+
+/// TCP connection to the first address of `address` that answers
+fn connect(address: &str, timeout: Duration) -> Result<TcpStream, String> {
+    let mut last_error = format!("{} did not resolve", address);
+    for addr in address.to_socket_addrs().map_err(|e| e.to_string())? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => {
+                stream
+                    .set_read_timeout(Some(timeout))
+                    .and_then(|_| stream.set_write_timeout(Some(timeout)))
+                    .map_err(|e| e.to_string())?;
+                return Ok(stream);
+            }
+            Err(e) => last_error = format!("{}: {}", addr, e),
+        }
+    }
+    Err(last_error)
+}
+
+/// Body of the HTTP/1.1 `response`, which must have a 2xx status
+fn response_body(response: &[u8]) -> Result<Vec<u8>, String> {
+    let header_end = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or("incomplete HTTP response")?;
+    let head = String::from_utf8_lossy(&response[..header_end]);
+    let body = &response[header_end + 4..];
+    let status = head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or("invalid HTTP status line")?;
+    if !(200..300).contains(&status) {
+        return Err(format!("The requested URL returned error: {}", status));
+    }
+    let header = |name: &str| {
+        head.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case(name)
+                .then(|| value.trim().to_string())
+        })
+    };
+    if header("Transfer-Encoding").is_some_and(|value| value.eq_ignore_ascii_case("chunked")) {
+        return dechunk(body);
+    }
+    match header("Content-Length") {
+        Some(length) => {
+            let length: usize = length.parse().map_err(|_| "invalid Content-Length")?;
+            body.get(..length)
+                .map(<[u8]>::to_vec)
+                .ok_or_else(|| "truncated HTTP response".to_string())
+        }
+        None => Ok(body.to_vec()),
+    }
+}
+
+/// Joins the chunks of a chunked transfer-encoded `body`
+fn dechunk(mut body: &[u8]) -> Result<Vec<u8>, String> {
+    let mut joined = Vec::new();
+    loop {
+        let line_end = body
+            .windows(2)
+            .position(|window| window == b"\r\n")
+            .ok_or("truncated HTTP response")?;
+        let size = String::from_utf8_lossy(&body[..line_end]);
+        let size = size.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16).map_err(|_| "invalid chunk size")?;
+        if size == 0 {
+            return Ok(joined);
+        }
+        let start = line_end + 2;
+        let end = start.checked_add(size).ok_or("invalid chunk size")?;
+        let chunk = body.get(start..end).ok_or("truncated HTTP response")?;
+        if body.get(end..end + 2) != Some(b"\r\n") {
+            return Err("invalid chunk terminator".to_string());
+        }
+        joined.extend_from_slice(chunk);
+        body = &body[end + 2..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_tls_options() {
+        let none = WorkerTls::default();
+        assert!(!none.is_enabled());
+        assert_eq!(none.scheme(), "http");
+        assert!(none.validate().is_ok());
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let ca = temp_dir.path().join("ca.pem");
+        std::fs::write(&ca, "").unwrap();
+        let tls = WorkerTls {
+            ca: Some(ca.clone()),
+            ..WorkerTls::default()
+        };
+        assert_eq!(tls.scheme(), "https");
+        assert!(tls.validate().is_ok());
+        let err = tls.client_config().unwrap_err().to_string();
+        assert!(err.contains("has no PEM certificate"), "{}", err);
+
+        let cert_only = WorkerTls {
+            cert: Some(ca.clone()),
+            ..WorkerTls::default()
+        };
+        assert!(cert_only.validate().is_err());
+        let missing = WorkerTls {
+            ca: Some(temp_dir.path().join("missing.pem")),
+            ..WorkerTls::default()
+        };
+        assert!(missing.validate().is_err());
+    }
+
+    #[test]
+    fn test_server_tls_config_errors() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let empty = temp_dir.path().join("empty.pem");
+        std::fs::write(&empty, "").unwrap();
+        let err = server_tls_config(&empty, &empty).unwrap_err().to_string();
+        assert!(err.contains("has no PEM certificate"), "{}", err);
+        assert!(server_tls_config(&temp_dir.path().join("missing.pem"), &empty).is_err());
+    }
+
+    #[test]
+    fn test_post_json_over_tls() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert = temp_dir.path().join("worker.pem");
+        let key = temp_dir.path().join("worker-key.pem");
+        std::fs::write(&cert, certified.cert.pem()).unwrap();
+        std::fs::write(&key, certified.key_pair.serialize_pem()).unwrap();
+        let server_config = Arc::new(server_tls_config(&cert, &key).unwrap());
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let refusing = (listener.try_clone().unwrap(), server_config.clone());
+        let worker = std::thread::spawn(move || {
+            let (tcp, _) = listener.accept().unwrap();
+            let connection = rustls::ServerConnection::new(server_config).unwrap();
+            let mut stream = rustls::StreamOwned::new(connection, tcp);
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"{\"ping\":1}") {
+                let read = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+            stream
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+                      5\r\n{\"pon\r\n5\r\ng\":1}\r\n0\r\n\r\n",
+                )
+                .unwrap();
+            stream.conn.send_close_notify();
+            stream.flush().unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let tls = WorkerTls {
+            ca: Some(cert),
+            ..WorkerTls::default()
+        };
+        let url = format!("https://localhost:{}/handshake", port);
+        let response = tls
+            .post_json(&url, b"{\"ping\":1}", Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(response, b"{\"pong\":1}");
+        let request = worker.join().unwrap();
+        assert!(
+            request.starts_with("POST /handshake HTTP/1.1\r\n"),
+            "{}",
+            request
+        );
+        assert!(request.contains(&format!("Host: localhost:{}\r\n", port)));
+
+        // With another CA the worker's certificate is refused
+        let worker = std::thread::spawn(move || {
+            let (mut tcp, _) = refusing.0.accept().unwrap();
+            let mut connection = rustls::ServerConnection::new(refusing.1).unwrap();
+            let _ = connection.complete_io(&mut tcp);
+        });
+        let other = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let other_ca = temp_dir.path().join("other-ca.pem");
+        std::fs::write(&other_ca, other.cert.pem()).unwrap();
+        let untrusted = WorkerTls {
+            ca: Some(other_ca),
+            ..WorkerTls::default()
+        };
+        let err = untrusted
+            .post_json(&url, b"{}", Duration::from_secs(10))
+            .unwrap_err();
+        assert!(err.contains("certificate"), "{}", err);
+        worker.join().unwrap();
+    }
+
+    #[test]
+    fn test_response_body() {
+        assert_eq!(
+            response_body(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}").unwrap(),
+            b"{}"
+        );
+        assert_eq!(
+            response_body(b"HTTP/1.1 404 Not Found\r\n\r\n").unwrap_err(),
+            "The requested URL returned error: 404"
+        );
+        assert!(response_body(b"HTTP/1.1 200 OK\r\nContent-Length: 9\r\n\r\n{}").is_err());
+        assert!(response_body(b"HTTP/1.1 200 OK\r\n").is_err());
+
+        let chunked = |chunks: &[u8]| {
+            let mut response = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n".to_vec();
+            response.extend_from_slice(chunks);
+            response_body(&response)
+        };
+        assert_eq!(chunked(b"2\r\n{}\r\n0\r\n\r\n").unwrap(), b"{}");
+        assert_eq!(
+            chunked(b"ffffffffffffffff\r\n{}\r\n0\r\n\r\n").unwrap_err(),
+            "invalid chunk size"
+        );
+        assert_eq!(
+            chunked(b"zz\r\n{}\r\n0\r\n\r\n").unwrap_err(),
+            "invalid chunk size"
+        );
+        assert_eq!(
+            chunked(b"1\r\n{}\r\n0\r\n\r\n").unwrap_err(),
+            "invalid chunk terminator"
+        );
+    }
+}
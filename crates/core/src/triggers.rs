@@ -0,0 +1,353 @@
+//! Follow-up actions of a scan, set in the `triggers` config section: when
+//! a scan finds what a trigger looks for, e.g. any SECRET finding, its
+//! actions run in order, such as scanning the affected files again with a
+//! deeper profile, notifying a webhook or quarantining the scan.
+
+use crate::enhanced_config::EnhancedScanConfig;
+use crate::errors::{ScanError, ScanResult};
+use crate::webhook::validate_webhook_url;
+use crate::{Match, Severity};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Event name of [`TriggerFiredPayload`]
+pub const TRIGGER_FIRED_EVENT: &str = "trigger.fired";
+
+/// Findings a scan is checked for and what to do when it has them, a
+/// `[[triggers]]` config section
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FindingTrigger {
+    /// Shown when the trigger fires and sent to its webhooks
+    pub name: String,
+    /// Pattern names whose findings fire the trigger, such as `SECRET`;
+    /// any pattern when empty
+    pub patterns: Vec<String>,
+    /// Only findings at least this severe fire the trigger, by their own
+    /// severity, else the built-in default of their pattern
+    pub min_severity: Option<Severity>,
+    /// Run in order when the trigger fires
+    pub actions: Vec<TriggerAction>,
+}
+
+/// What a trigger does when it fires
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum TriggerAction {
+    /// Scan the files with findings again with a detector profile, adding
+    /// what it finds to the scan
+    DeepScan { profile: String },
+    /// POST a [`TriggerFiredPayload`] to a URL, signed like scan webhooks
+    Webhook { url: String },
+    /// Mark the saved scan as quarantined, shown by `report` and `history`
+    Quarantine,
+}
+
+impl FindingTrigger {
+    /// Whether `m` is a finding the trigger looks for. Findings without a
+    /// severity of their own are rated by `defaults`, the built-in severities
+    /// of patterns; those of patterns without one never pass `min_severity`.
+    pub fn matches(&self, m: &Match, defaults: &HashMap<String, Severity>) -> bool {
+        let pattern_matches = self.patterns.is_empty()
+            || self
+                .patterns
+                .iter()
+                .any(|pattern| pattern.eq_ignore_ascii_case(&m.pattern));
+        let severe_enough = match &self.min_severity {
+            Some(min) => m
+                .severity
+                .as_ref()
+                .or_else(|| defaults.get(m.pattern.as_str()))
+                .is_some_and(|severity| severity >= min),
+            None => true,
+        };
+        pattern_matches && severe_enough
+    }
+
+    /// Fails if the trigger has no name, would fire on every finding or
+    /// does nothing, or a webhook URL is not http(s)
+    pub fn validate(&self) -> ScanResult<()> {
+        if self.name.trim().is_empty() {
+            return Err(ScanError::config("every trigger needs a 'name'"));
+        }
+        if self.patterns.is_empty() && self.min_severity.is_none() {
+            return Err(ScanError::config(format!(
+                "trigger '{}' needs 'patterns' or 'min_severity'",
+                self.name
+            )));
+        }
+        if self.actions.is_empty() {
+            return Err(ScanError::config(format!(
+                "trigger '{}' has no 'actions'",
+                self.name
+            )));
+        }
+        for action in &self.actions {
+            if let TriggerAction::Webhook { url } = action {
+                validate_webhook_url(url).map_err(|_| {
+                    ScanError::config(format!(
+                        "trigger '{}' webhook '{}' must start with http:// or https://",
+                        self.name, url
+                    ))
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks every trigger, and that no two share a name
+pub fn validate_triggers(triggers: &[FindingTrigger]) -> ScanResult<()> {
+    let mut names = HashSet::new();
+    for trigger in triggers {
+        trigger.validate()?;
+        if !names.insert(trigger.name.as_str()) {
+            return Err(ScanError::config(format!(
+                "more than one trigger is named '{}'",
+                trigger.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// A trigger that fired, with the findings that fired it
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiredTrigger<'a> {
+    pub trigger: &'a FindingTrigger,
+    pub matches: Vec<Match>,
+}
+
+impl FiredTrigger<'_> {
+    /// The files of the findings, sorted
+    pub fn files(&self) -> BTreeSet<&str> {
+        self.matches.iter().map(|m| m.file_path.as_str()).collect()
+    }
+
+    /// Why a quarantined scan is quarantined
+    pub fn quarantine_reason(&self) -> String {
+        format!(
+            "trigger '{}' fired on {} finding(s) in {} file(s)",
+            self.trigger.name,
+            self.matches.len(),
+            self.files().len()
+        )
+    }
+}
+
+/// The triggers that fire on `matches`, in config order
+pub fn fire_triggers<'a>(
+    triggers: &'a [FindingTrigger],
+    matches: &[Match],
+) -> Vec<FiredTrigger<'a>> {
+    let defaults = EnhancedScanConfig::default().severity_levels;
+    triggers
+        .iter()
+        .filter_map(|trigger| {
+            let fired: Vec<Match> = matches
+                .iter()
+                .filter(|m| trigger.matches(m, &defaults))
+                .cloned()
+                .collect();
+            (!fired.is_empty()).then_some(FiredTrigger {
+                trigger,
+                matches: fired,
+            })
+        })
+        .collect()
+}
+
+/// `matches` followed by those of `extra` not already among them, e.g. the
+/// findings of a deep scan of files the scan already covered
+pub fn merge_matches(mut matches: Vec<Match>, extra: Vec<Match>) -> Vec<Match> {
+    let mut seen: HashSet<(String, usize, usize, String)> = matches.iter().map(match_key).collect();
+    matches.extend(extra.into_iter().filter(|m| seen.insert(match_key(m))));
+    matches
+}
+
+fn match_key(m: &Match) -> (String, usize, usize, String) {
+    (
+        m.file_path.to_string(),
+        m.line_number,
+        m.column,
+        m.pattern.to_string(),
+    )
+}
+
+/// JSON body posted to a trigger's webhooks when it fires
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggerFiredPayload {
+    pub event: String,
+    pub trigger: String,
+    /// ID of the saved scan
+    pub scan_id: i64,
+    pub root_path: String,
+    pub timestamp: i64,
+    /// Whether a quarantine action of the trigger marked the scan
+    pub quarantined: bool,
+    /// The findings that fired the trigger
+    pub matches: Vec<Match>,
+}
+
+impl TriggerFiredPayload {
+    pub fn new(
+        fired: &FiredTrigger<'_>,
+        scan_id: i64,
+        root_path: impl Into<String>,
+        timestamp: i64,
+    ) -> Self {
+        Self {
+            event: TRIGGER_FIRED_EVENT.to_string(),
+            trigger: fired.trigger.name.clone(),
+            scan_id,
+            root_path: root_path.into(),
+            timestamp,
+            quarantined: fired.trigger.actions.contains(&TriggerAction::Quarantine),
+            matches: fired.matches.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(file: &str, line: usize, pattern: &str, severity: Severity) -> Match {
+        Match::new(file, line, pattern, format!("{} found", pattern)).with_severity(severity)
+    }
+
+    fn trigger(patterns: &[&str], min_severity: Option<Severity>) -> FindingTrigger {
+        FindingTrigger {
+            name: "secrets".to_string(),
+            patterns: patterns.iter().map(|p| p.to_string()).collect(),
+            min_severity,
+            actions: vec![TriggerAction::Quarantine],
+        }
+    }
+
+    #[test]
+    fn test_fire_triggers() {
+        let matches = vec![
+            finding("a.rs", 1, "SECRET", Severity::Critical),
+            finding("a.rs", 5, "TODO", Severity::Low),
+            finding("b.rs", 2, "SECRET", Severity::Critical),
+            finding("c.rs", 3, "SQL_INJECTION", Severity::High),
+        ];
+        let triggers = vec![
+            trigger(&["secret"], None),
+            FindingTrigger {
+                name: "severe".to_string(),
+                ..trigger(&[], Some(Severity::High))
+            },
+            FindingTrigger {
+                name: "unused".to_string(),
+                ..trigger(&["DEBUGGER"], None)
+            },
+        ];
+
+        let fired = fire_triggers(&triggers, &matches);
+        assert_eq!(fired.len(), 2);
+        assert_eq!(fired[0].trigger.name, "secrets");
+        assert_eq!(fired[0].matches.len(), 2);
+        assert_eq!(
+            fired[0].files().into_iter().collect::<Vec<_>>(),
+            ["a.rs", "b.rs"]
+        );
+        assert_eq!(
+            fired[0].quarantine_reason(),
+            "trigger 'secrets' fired on 2 finding(s) in 2 file(s)"
+        );
+        assert_eq!(fired[1].matches.len(), 3);
+
+        let payload = TriggerFiredPayload::new(&fired[0], 7, "/repo", 1_700_000_000);
+        assert_eq!(payload.event, TRIGGER_FIRED_EVENT);
+        assert!(payload.quarantined);
+        assert_eq!(payload.matches.len(), 2);
+    }
+
+    #[test]
+    fn test_min_severity_uses_pattern_defaults() {
+        // Built-in detectors leave the severity to the pattern's default
+        let debugger = Match::new("app.js", 3, "DEBUGGER", "debugger;");
+        let todo = Match::new("app.js", 4, "TODO", "TODO: one");
+        let unknown = Match::new("app.js", 5, "NO_DEFAULT", "found");
+        let triggers = [trigger(&[], Some(Severity::High))];
+        let fired = fire_triggers(&triggers, &[debugger.clone(), todo, unknown]);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].matches, vec![debugger]);
+    }
+
+    #[test]
+    fn test_validate_triggers() {
+        assert!(validate_triggers(&[trigger(&["SECRET"], None)]).is_ok());
+
+        let everything = trigger(&[], None);
+        assert!(everything.validate().is_err());
+        let idle = FindingTrigger {
+            actions: Vec::new(),
+            ..trigger(&["SECRET"], None)
+        };
+        assert!(idle.validate().is_err());
+        let bad_url = FindingTrigger {
+            actions: vec![TriggerAction::Webhook {
+                url: "ftp://hooks.example.com".to_string(),
+            }],
+            ..trigger(&["SECRET"], None)
+        };
+        let err = bad_url.validate().unwrap_err().to_string();
+        assert!(err.contains("must start with http"), "{}", err);
+        let twice = [trigger(&["SECRET"], None), trigger(&["TODO"], None)];
+        let err = validate_triggers(&twice).unwrap_err().to_string();
+        assert!(
+            err.contains("more than one trigger is named 'secrets'"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_merge_matches() {
+        let scanned = vec![finding("a.rs", 1, "SECRET", Severity::Critical)];
+        let deep = vec![
+            finding("a.rs", 1, "SECRET", Severity::Critical),
+            finding("a.rs", 4, "HARDCODED_PASSWORD", Severity::High),
+        ];
+        let merged = merge_matches(scanned, deep);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[1].pattern, "HARDCODED_PASSWORD");
+    }
+
+    #[test]
+    fn test_trigger_config_parses() {
+        let triggers: Vec<FindingTrigger> = toml::from_str::<toml::Table>(
+            r#"
+            [[triggers]]
+            name = "leaked-secrets"
+            patterns = ["SECRET"]
+            actions = [
+                { action = "deep_scan", profile = "security" },
+                { action = "webhook", url = "https://hooks.example.com/secrets" },
+                { action = "quarantine" },
+            ]
+            "#,
+        )
+        .unwrap()["triggers"]
+            .clone()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            triggers[0].actions,
+            vec![
+                TriggerAction::DeepScan {
+                    profile: "security".to_string()
+                },
+                TriggerAction::Webhook {
+                    url: "https://hooks.example.com/secrets".to_string()
+                },
+                TriggerAction::Quarantine,
+            ]
+        );
+        assert_eq!(triggers[0].min_severity, None);
+    }
+}
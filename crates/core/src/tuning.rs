@@ -0,0 +1,426 @@
+//! The rules of a scan with the most findings, and config that would quiet
+//! them, for `report --tune`: rule path exclusions for test, example and
+//! vendored directories and for single files holding most of a rule's
+//! findings, and severity downgrades for rules that flood a scan.
+
+use crate::enhanced_config::EnhancedScanConfig;
+use crate::{Match, Severity};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path};
+
+/// Directory names of code that doesn't ship, whose findings are usually noise
+const NON_PRODUCTION_DIRS: &[&str] = &[
+    "test",
+    "tests",
+    "__tests__",
+    "spec",
+    "specs",
+    "testdata",
+    "fixtures",
+    "mocks",
+    "__mocks__",
+    "examples",
+    "example",
+    "samples",
+    "demo",
+    "benches",
+    "vendor",
+    "third_party",
+    "generated",
+];
+
+/// Share of a rule's findings a directory or file must hold to be suggested
+/// for exclusion
+const EXCLUDE_MIN_SHARE: f64 = 0.2;
+/// Findings a single file must hold, and share of its rule's, to be
+/// suggested for exclusion
+const FILE_MIN_MATCHES: usize = 10;
+const FILE_MIN_SHARE: f64 = 0.5;
+/// Findings and share of the scan's a Medium or higher rule must have to be
+/// suggested for a downgrade
+const DOWNGRADE_MIN_MATCHES: usize = 20;
+const DOWNGRADE_MIN_SHARE: f64 = 0.25;
+/// Directories listed per noisy rule
+const TOP_DIRECTORIES: usize = 3;
+
+/// Findings of a rule under one directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathCount {
+    /// Relative to the scan root, with `/` separators; empty for the root
+    pub path: String,
+    pub matches: usize,
+}
+
+/// A rule among the noisiest of a scan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoisyRule {
+    pub rule: String,
+    pub matches: usize,
+    /// Files with findings of the rule
+    pub files: usize,
+    /// Highest severity of the rule's findings, built-in where they have none
+    pub severity: Severity,
+    /// Directories with the most of the rule's findings
+    pub directories: Vec<PathCount>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TuningKind {
+    /// Turn the rule off for a path with `rule_paths`
+    ExcludePath,
+    /// Lower the rule's severity with `severity_overrides`
+    DowngradeSeverity,
+}
+
+/// A config change quieting a noisy rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TuningSuggestion {
+    pub rule: String,
+    pub kind: TuningKind,
+    /// The glob excluded, for [`TuningKind::ExcludePath`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// The lower severity, for [`TuningKind::DowngradeSeverity`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    /// What was observed, e.g. `42 of 60 matches are under web/tests`
+    pub reason: String,
+}
+
+/// The noisiest rules of a scan and the suggestions for them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TuningReport {
+    pub total_matches: usize,
+    /// Most findings first
+    pub rules: Vec<NoisyRule>,
+    pub suggestions: Vec<TuningSuggestion>,
+    /// The suggestions as one `code-guardian.toml` snippet
+    pub config: String,
+}
+
+impl TuningReport {
+    /// Ranks the rules of `matches`, found by a scan of `root`, keeping the
+    /// `top` ones with the most findings and suggesting config for them
+    pub fn new(matches: &[Match], root: &Path, top: usize) -> Self {
+        let defaults = EnhancedScanConfig::default().severity_levels;
+        let mut by_rule: BTreeMap<&str, Vec<&Match>> = BTreeMap::new();
+        for m in matches {
+            by_rule.entry(&m.pattern).or_default().push(m);
+        }
+        let mut ranked: Vec<(&str, Vec<&Match>)> = by_rule.into_iter().collect();
+        ranked.sort_by(|(a, a_matches), (b, b_matches)| {
+            b_matches.len().cmp(&a_matches.len()).then(a.cmp(b))
+        });
+        ranked.truncate(top);
+
+        let mut rules = Vec::new();
+        let mut suggestions = Vec::new();
+        for (rule, rule_matches) in ranked {
+            let severity = rule_matches
+                .iter()
+                .map(|m| {
+                    m.severity
+                        .clone()
+                        .or_else(|| defaults.get(rule).cloned())
+                        .unwrap_or(Severity::Medium)
+                })
+                .max()
+                .unwrap_or(Severity::Medium);
+            let noisy = analyze_rule(rule, &rule_matches, root, severity, matches.len());
+            suggestions.extend(noisy.1);
+            rules.push(noisy.0);
+        }
+        let config = config_snippet(&suggestions);
+        Self {
+            total_matches: matches.len(),
+            rules,
+            suggestions,
+            config,
+        }
+    }
+}
+
+/// The [`NoisyRule`] of the `rule_matches` of `rule` and the suggestions
+/// for it, out of `total` findings of the scan
+fn analyze_rule(
+    rule: &str,
+    rule_matches: &[&Match],
+    root: &Path,
+    severity: Severity,
+    total: usize,
+) -> (NoisyRule, Vec<TuningSuggestion>) {
+    let count = rule_matches.len();
+    let mut files: HashMap<String, usize> = HashMap::new();
+    let mut directories: HashMap<String, usize> = HashMap::new();
+    let mut non_production: HashMap<String, usize> = HashMap::new();
+    for m in rule_matches {
+        let path = relative_path(&m.file_path, root);
+        *files.entry(path.join("/")).or_default() += 1;
+        let dirs = &path[..path.len().saturating_sub(1)];
+        *directories.entry(dirs.join("/")).or_default() += 1;
+        // The outermost non-production directory, so nested ones count once
+        if let Some(i) = dirs.iter().position(|dir| is_non_production(dir)) {
+            *non_production.entry(dirs[..=i].join("/")).or_default() += 1;
+        }
+    }
+
+    let mut suggestions = Vec::new();
+    let share = |n: usize| n as f64 / count as f64;
+    for (dir, n) in sorted_counts(non_production) {
+        if share(n) >= EXCLUDE_MIN_SHARE {
+            suggestions.push(TuningSuggestion {
+                rule: rule.to_string(),
+                kind: TuningKind::ExcludePath,
+                path: Some(format!("{}/**", dir)),
+                severity: None,
+                reason: format!(
+                    "{} of {} matches are under {}, which holds no production code",
+                    n, count, dir
+                ),
+            });
+        }
+    }
+    let excluded: Vec<String> = suggestions
+        .iter()
+        .filter_map(|s| s.path.as_deref())
+        .map(|glob| glob.trim_end_matches('*').to_string())
+        .collect();
+    for (file, n) in sorted_counts(files.clone()) {
+        if n >= FILE_MIN_MATCHES
+            && share(n) >= FILE_MIN_SHARE
+            && !excluded.iter().any(|dir| file.starts_with(dir.as_str()))
+        {
+            suggestions.push(TuningSuggestion {
+                rule: rule.to_string(),
+                kind: TuningKind::ExcludePath,
+                path: Some(file.clone()),
+                severity: None,
+                reason: format!("{} of {} matches are in {}", n, count, file),
+            });
+        }
+    }
+    if let Some(lower) = lower_severity(&severity) {
+        if count >= DOWNGRADE_MIN_MATCHES && count as f64 / total as f64 >= DOWNGRADE_MIN_SHARE {
+            suggestions.push(TuningSuggestion {
+                rule: rule.to_string(),
+                kind: TuningKind::DowngradeSeverity,
+                path: None,
+                severity: Some(lower),
+                reason: format!(
+                    "{} {:?} matches are {:.0}% of the scan's findings",
+                    count,
+                    severity,
+                    100.0 * count as f64 / total as f64
+                ),
+            });
+        }
+    }
+
+    let mut top_directories = sorted_counts(directories);
+    top_directories.truncate(TOP_DIRECTORIES);
+    let rule = NoisyRule {
+        rule: rule.to_string(),
+        matches: count,
+        files: files.len(),
+        severity,
+        directories: top_directories
+            .into_iter()
+            .map(|(path, matches)| PathCount { path, matches })
+            .collect(),
+    };
+    (rule, suggestions)
+}
+
+/// The components of `file_path` below `root`
+fn relative_path(file_path: &str, root: &Path) -> Vec<String> {
+    let path = Path::new(file_path);
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn is_non_production(dir: &str) -> bool {
+    NON_PRODUCTION_DIRS
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(dir))
+}
+
+/// `counts` with the most first, then by path
+fn sorted_counts(counts: HashMap<String, usize>) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+    counts
+}
+
+/// The next lower severity, for Medium and higher; Low and Info findings are
+/// cheap enough to leave as they are
+fn lower_severity(severity: &Severity) -> Option<Severity> {
+    match severity {
+        Severity::Critical => Some(Severity::High),
+        Severity::High => Some(Severity::Medium),
+        Severity::Medium => Some(Severity::Low),
+        Severity::Low | Severity::Info => None,
+    }
+}
+
+/// `suggestions` as `rule_paths` and `severity_overrides` tables, each
+/// entry commented with its reason
+fn config_snippet(suggestions: &[TuningSuggestion]) -> String {
+    let mut excludes: BTreeMap<&str, Vec<&TuningSuggestion>> = BTreeMap::new();
+    let mut downgrades = Vec::new();
+    for suggestion in suggestions {
+        match suggestion.kind {
+            TuningKind::ExcludePath => excludes
+                .entry(&suggestion.rule)
+                .or_default()
+                .push(suggestion),
+            TuningKind::DowngradeSeverity => downgrades.push(suggestion),
+        }
+    }
+
+    let mut sections = Vec::new();
+    for (rule, suggestions) in excludes {
+        let mut section = format!("[rule_paths.{}]\nexclude = [\n", toml_key(rule));
+        for suggestion in suggestions {
+            let path = suggestion.path.as_deref().unwrap_or_default();
+            section.push_str(&format!(
+                "    # {}\n    {},\n",
+                suggestion.reason,
+                toml::Value::String(path.to_string())
+            ));
+        }
+        section.push_str("]\n");
+        sections.push(section);
+    }
+    if !downgrades.is_empty() {
+        let mut section = "[severity_overrides]\n".to_string();
+        for suggestion in downgrades {
+            let severity = suggestion.severity.clone().unwrap_or(Severity::Low);
+            section.push_str(&format!(
+                "# {}\n{} = \"{:?}\"\n",
+                suggestion.reason,
+                toml_key(&suggestion.rule),
+                severity
+            ));
+        }
+        sections.push(section);
+    }
+    sections.join("\n")
+}
+
+/// `key` bare when TOML allows it, else quoted
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        key.to_string()
+    } else {
+        toml::Value::String(key.to_string()).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn finding(path: &str, pattern: &str) -> Match {
+        Match::new(path, 1, pattern, pattern)
+    }
+
+    fn scan() -> Vec<Match> {
+        let mut matches = Vec::new();
+        for i in 0..30 {
+            matches.push(finding(
+                &format!("/repo/web/tests/t{}.js", i % 3),
+                "CONSOLE_LOG",
+            ));
+        }
+        for _ in 0..12 {
+            matches.push(finding("/repo/web/src/legacy.js", "CONSOLE_LOG"));
+        }
+        for i in 0..8 {
+            matches.push(finding(&format!("/repo/src/m{}.rs", i), "CONSOLE_LOG"));
+        }
+        for i in 0..6 {
+            matches.push(finding(&format!("/repo/src/m{}.rs", i), "TODO"));
+        }
+        matches.push(finding("/repo/src/lib.rs", "FIXME"));
+        matches
+    }
+
+    #[test]
+    fn test_tuning_report_ranks_rules() {
+        let report = TuningReport::new(&scan(), Path::new("/repo"), 2);
+        assert_eq!(report.total_matches, 57);
+        let rules: Vec<(&str, usize, usize)> = report
+            .rules
+            .iter()
+            .map(|r| (r.rule.as_str(), r.matches, r.files))
+            .collect();
+        assert_eq!(rules, [("CONSOLE_LOG", 50, 12), ("TODO", 6, 6)]);
+        assert_eq!(report.rules[0].severity, Severity::High);
+        assert_eq!(
+            report.rules[0].directories[0],
+            PathCount {
+                path: "web/tests".to_string(),
+                matches: 30
+            }
+        );
+    }
+
+    #[test]
+    fn test_tuning_suggestions() {
+        let report = TuningReport::new(&scan(), Path::new("/repo"), 10);
+        let suggestions: Vec<(&str, TuningKind, Option<&str>)> = report
+            .suggestions
+            .iter()
+            .map(|s| (s.rule.as_str(), s.kind, s.path.as_deref()))
+            .collect();
+        assert_eq!(
+            suggestions,
+            [
+                ("CONSOLE_LOG", TuningKind::ExcludePath, Some("web/tests/**")),
+                ("CONSOLE_LOG", TuningKind::DowngradeSeverity, None),
+            ]
+        );
+        assert_eq!(report.suggestions[1].severity, Some(Severity::Medium));
+        assert_eq!(
+            report.suggestions[0].reason,
+            "30 of 50 matches are under web/tests, which holds no production code"
+        );
+
+        // The config parses and sets what was suggested
+        let config: Config = toml::from_str(&report.config).unwrap();
+        assert_eq!(
+            config.rule_paths["CONSOLE_LOG"].exclude,
+            ["web/tests/**".to_string()]
+        );
+        assert_eq!(config.severity_overrides["CONSOLE_LOG"], Severity::Medium);
+    }
+
+    #[test]
+    fn test_single_file_exclusion() {
+        let mut matches: Vec<Match> = (0..15).map(|_| finding("gen/schema.rs", "TODO")).collect();
+        matches.push(finding("src/main.rs", "TODO"));
+        let report = TuningReport::new(&matches, Path::new("."), 10);
+        assert_eq!(report.suggestions.len(), 1);
+        assert_eq!(report.suggestions[0].path.as_deref(), Some("gen/schema.rs"));
+        assert!(report.config.contains("\"gen/schema.rs\","));
+        // TODOs are Low, too cheap to downgrade
+        assert!(!report.config.contains("severity_overrides"));
+
+        assert_eq!(toml_key("CONSOLE_LOG"), "CONSOLE_LOG");
+        assert_eq!(toml_key("my rule"), "\"my rule\"");
+        assert!(TuningReport::new(&[], Path::new("."), 10).config.is_empty());
+    }
+}
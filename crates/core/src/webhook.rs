@@ -0,0 +1,393 @@
+//! Signed webhook notifications sent when a scan completes
+
+use crate::ci_environment::CiRun;
+use crate::errors::{ScanError, ScanResult};
+use crate::Match;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tempfile::NamedTempFile;
+
+/// Header carrying the `sha256=<hex>` HMAC of the request body
+pub const SIGNATURE_HEADER: &str = "X-Code-Guardian-Signature";
+
+/// Event name of [`ScanCompletedPayload`]
+pub const SCAN_COMPLETED_EVENT: &str = "scan.completed";
+
+/// JSON body posted to each webhook when a scan finishes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanCompletedPayload {
+    pub event: String,
+    /// ID of the saved scan, if it was stored
+    pub scan_id: Option<i64>,
+    pub root_path: String,
+    pub timestamp: i64,
+    pub summary: ScanSummary,
+    pub threshold: ThresholdStatus,
+    /// The CI pipeline run the scan ran in, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ci: Option<CiRun>,
+    /// URLs of the reports uploaded with `--upload`, keyed by file name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub artifacts: BTreeMap<String, String>,
+}
+
+impl ScanCompletedPayload {
+    pub fn new(
+        scan_id: Option<i64>,
+        root_path: impl Into<String>,
+        timestamp: i64,
+        summary: ScanSummary,
+        threshold: ThresholdStatus,
+    ) -> Self {
+        Self {
+            event: SCAN_COMPLETED_EVENT.to_string(),
+            scan_id,
+            root_path: root_path.into(),
+            timestamp,
+            summary,
+            threshold,
+            ci: None,
+            artifacts: BTreeMap::new(),
+        }
+    }
+
+    /// Links the payload to the CI pipeline run of the scan
+    pub fn with_ci(mut self, ci: Option<CiRun>) -> Self {
+        self.ci = ci;
+        self
+    }
+
+    /// Links the payload to the reports uploaded for the scan
+    pub fn with_artifacts(mut self, artifacts: BTreeMap<String, String>) -> Self {
+        self.artifacts = artifacts;
+        self
+    }
+}
+
+/// Counts of what a scan found
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScanSummary {
+    pub total_matches: usize,
+    pub files_scanned: usize,
+    pub files_with_matches: usize,
+    pub matches_by_pattern: BTreeMap<String, usize>,
+}
+
+impl ScanSummary {
+    pub fn from_matches(matches: &[Match], files_scanned: usize) -> Self {
+        let mut matches_by_pattern = BTreeMap::new();
+        for m in matches {
+            *matches_by_pattern.entry(m.pattern.to_string()).or_insert(0) += 1;
+        }
+        let files_with_matches = matches
+            .iter()
+            .map(|m| m.file_path.as_str())
+            .collect::<std::collections::BTreeSet<_>>()
+            .len();
+        Self {
+            total_matches: matches.len(),
+            files_scanned,
+            files_with_matches,
+            matches_by_pattern,
+        }
+    }
+}
+
+/// Outcome of the scan's failure thresholds, e.g. `--fail-on-stale-todos`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ThresholdStatus {
+    NotConfigured,
+    Passed,
+    Failed { message: String },
+}
+
+/// Posts [`ScanCompletedPayload`]s to a list of webhook URLs with `curl`,
+/// signing the body with HMAC-SHA256 when a secret is set
+#[derive(Debug, Clone)]
+pub struct WebhookNotifier {
+    urls: Vec<String>,
+    secret: Option<String>,
+    timeout: Duration,
+}
+
+impl WebhookNotifier {
+    pub fn new(urls: Vec<String>) -> Self {
+        Self {
+            urls,
+            secret: None,
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    pub fn with_secret(mut self, secret: Option<String>) -> Self {
+        self.secret = secret.filter(|s| !s.is_empty());
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.urls.is_empty()
+    }
+
+    /// Sends the payload to every URL, returning the failed deliveries.
+    /// Failures never affect the other URLs.
+    pub fn notify(&self, payload: &ScanCompletedPayload) -> Vec<ScanError> {
+        self.notify_event(SCAN_COMPLETED_EVENT, payload)
+    }
+
+    /// [`WebhookNotifier::notify`] with the payload of another event, e.g.
+    /// [`TRIGGER_FIRED_EVENT`](crate::triggers::TRIGGER_FIRED_EVENT)
+    pub fn notify_event(&self, event: &str, payload: &impl Serialize) -> Vec<ScanError> {
+        let body = match serde_json::to_vec(payload) {
+            Ok(body) => body,
+            Err(e) => return vec![ScanError::webhook("<all>", e)],
+        };
+        let signature = self
+            .secret
+            .as_ref()
+            .map(|secret| sign_payload(secret.as_bytes(), &body));
+
+        self.urls
+            .iter()
+            .filter_map(|url| self.post(url, event, &body, signature.as_deref()).err())
+            .collect()
+    }
+
+    fn post(&self, url: &str, event: &str, body: &[u8], signature: Option<&str>) -> ScanResult<()> {
+        validate_webhook_url(url)?;
+
+        let mut headers = vec![format!("X-Code-Guardian-Event: {}", event)];
+        if let Some(signature) = signature {
+            headers.push(format!("{}: {}", SIGNATURE_HEADER, signature));
+        }
+        post_json(url, &headers, &[], body, self.timeout)
+            .map(|_| ())
+            .map_err(|e| ScanError::webhook(url, e))
+    }
+}
+
+/// POSTs a JSON body with `curl` and returns the response body. See
+/// [`send_json`] for `secret_headers`.
+pub(crate) fn post_json(
+    url: &str,
+    headers: &[String],
+    secret_headers: &[String],
+    body: &[u8],
+    timeout: Duration,
+) -> Result<Vec<u8>, String> {
+    send_json("POST", url, headers, secret_headers, body, timeout)
+}
+
+/// Sends a JSON body with `curl` using `method`, e.g. PUT, and returns the
+/// response body. `secret_headers`, such as API tokens, are read by curl
+/// from a file only the current user can read, as its command line is
+/// visible to every local user through `ps`.
+pub(crate) fn send_json(
+    method: &str,
+    url: &str,
+    headers: &[String],
+    secret_headers: &[String],
+    body: &[u8],
+    timeout: Duration,
+) -> Result<Vec<u8>, String> {
+    // Kept until curl exits, then removed
+    let header_file = secret_header_file(secret_headers)
+        .map_err(|e| format!("could not write request headers: {}", e))?;
+    let mut command = curl_command(method, url, headers, header_file.as_ref(), timeout);
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not run curl: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(body).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// The curl command sending the body read from stdin, see [`send_json`]
+fn curl_command(
+    method: &str,
+    url: &str,
+    headers: &[String],
+    header_file: Option<&NamedTempFile>,
+    timeout: Duration,
+) -> Command {
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--fail", "--max-time"])
+        .arg(timeout.as_secs().max(1).to_string())
+        .args(["-X", method, "-H", "Content-Type: application/json"]);
+    for header in headers {
+        command.args(["-H", header]);
+    }
+    if let Some(file) = header_file {
+        let mut arg = OsString::from("@");
+        arg.push(file.path());
+        command.arg("-H").arg(arg);
+    }
+    command.args(["--data-binary", "@-", url]);
+    command
+}
+
+/// A file holding `headers`, one per line, readable only by the current
+/// user; `None` without headers
+fn secret_header_file(headers: &[String]) -> std::io::Result<Option<NamedTempFile>> {
+    if headers.is_empty() {
+        return Ok(None);
+    }
+    // Created with mode 0600 on Unix
+    let mut file = NamedTempFile::new()?;
+    for header in headers {
+        writeln!(file, "{}", header)?;
+    }
+    file.flush()?;
+    Ok(Some(file))
+}
+
+/// Checks that a webhook URL uses http or https
+pub fn validate_webhook_url(url: &str) -> ScanResult<()> {
+    if url.starts_with("https://") || url.starts_with("http://") {
+        Ok(())
+    } else {
+        Err(ScanError::webhook(
+            url,
+            "URL must start with http:// or https://",
+        ))
+    }
+}
+
+/// HMAC-SHA256 of `body` keyed by `secret`, formatted as `sha256=<hex>`
+pub fn sign_payload(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    let hex: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("sha256={}", hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_headers_stay_off_the_command_line() {
+        let secret = ["PRIVATE-TOKEN: glpat-s3cret".to_string()];
+        let file = secret_header_file(&secret).unwrap().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(file.path()).unwrap(),
+            "PRIVATE-TOKEN: glpat-s3cret\n"
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = file.as_file().metadata().unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let headers = ["X-Code-Guardian-Event: scan.completed".to_string()];
+        let command = curl_command(
+            "POST",
+            "https://gitlab.example.com/api",
+            &headers,
+            Some(&file),
+            Duration::from_secs(10),
+        );
+        let args: Vec<String> = command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        assert!(args.contains(&headers[0]));
+        assert!(args.contains(&format!("@{}", file.path().display())));
+        assert!(!args.iter().any(|arg| arg.contains("s3cret")));
+        assert!(secret_header_file(&[]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_sign_payload_matches_rfc4231() {
+        assert_eq!(
+            sign_payload(b"Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Keys longer than the block size are hashed first
+        assert_eq!(
+            sign_payload(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ),
+            "sha256=60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn test_payload_json() {
+        let matches = vec![
+            Match {
+                file_path: "src/a.rs".into(),
+                line_number: 1,
+                column: 4,
+                pattern: "TODO".into(),
+                message: "TODO: x".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
+            },
+            Match {
+                file_path: "src/a.rs".into(),
+                line_number: 2,
+                column: 4,
+                pattern: "FIXME".into(),
+                message: "FIXME: y".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
+            },
+        ];
+        let payload = ScanCompletedPayload::new(
+            Some(7),
+            "/repo",
+            1_700_000_000,
+            ScanSummary::from_matches(&matches, 3),
+            ThresholdStatus::Failed {
+                message: "1 stale TODO".to_string(),
+            },
+        );
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["event"], "scan.completed");
+        assert_eq!(json["scan_id"], 7);
+        assert_eq!(json["summary"]["total_matches"], 2);
+        assert_eq!(json["summary"]["files_with_matches"], 1);
+        assert_eq!(json["summary"]["matches_by_pattern"]["TODO"], 1);
+        assert_eq!(json["threshold"]["status"], "failed");
+        assert_eq!(json["threshold"]["message"], "1 stale TODO");
+        assert!(json.get("ci").is_none());
+        assert!(json.get("artifacts").is_none());
+
+        assert!(validate_webhook_url("https://ci.example.com/hook").is_ok());
+        assert!(validate_webhook_url("ftp://example.com").is_err());
+    }
+}
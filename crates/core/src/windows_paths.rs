@@ -0,0 +1,141 @@
+//! Windows verbatim paths, the `\\?\C:\repo` and `\\?\UNC\server\share\repo`
+//! forms that `std::fs::canonicalize` returns and that enterprise checkouts
+//! deeper than 260 characters are often given as. They never match the same
+//! path written without the prefix, so roots, reported files and cache keys
+//! are kept in the ordinary form whenever it names the same file. Reading
+//! long paths needs no prefix, as the standard library adds it itself. On
+//! other platforms paths are left as they are.
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+/// Longest path the ordinary form can name without the verbatim prefix
+#[cfg_attr(not(windows), allow(dead_code))]
+const MAX_PATH: usize = 260;
+
+/// Device names Windows reserves in every directory, with any extension
+#[cfg_attr(not(windows), allow(dead_code))]
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// `path` without its verbatim prefix when the ordinary form names the same
+/// file, e.g. `\\?\UNC\server\share\repo` as `\\server\share\repo`. Paths
+/// that need the prefix, such as ones longer than 260 characters or with a
+/// component Windows would reinterpret, are returned unchanged.
+pub fn simplified(path: &Path) -> Cow<'_, Path> {
+    #[cfg(windows)]
+    if let Some(simple) = path.to_str().and_then(simplify_verbatim) {
+        return Cow::Owned(PathBuf::from(simple));
+    }
+    Cow::Borrowed(path)
+}
+
+/// [`std::fs::canonicalize`], in the ordinary form where possible, see
+/// [`simplified`]
+pub fn canonicalize(path: &Path) -> std::io::Result<PathBuf> {
+    let canonical = std::fs::canonicalize(path)?;
+    Ok(match simplified(&canonical) {
+        Cow::Owned(simple) => simple,
+        Cow::Borrowed(_) => canonical,
+    })
+}
+
+/// The ordinary form of the verbatim path `path`, or `None` if it isn't
+/// verbatim or has no ordinary form
+#[cfg_attr(not(windows), allow(dead_code))]
+fn simplify_verbatim(path: &str) -> Option<String> {
+    let rest = path.strip_prefix(r"\\?\")?;
+    let (simple, components) = if let Some(unc) = rest.strip_prefix(r"UNC\") {
+        let mut parts = unc.splitn(3, '\\');
+        let (server, share) = (parts.next()?, parts.next()?);
+        if server.is_empty() || share.is_empty() {
+            return None;
+        }
+        (format!(r"\\{}", unc), parts.next().unwrap_or(""))
+    } else {
+        let drive = rest.as_bytes();
+        if drive.len() < 3 || !drive[0].is_ascii_alphabetic() || &drive[1..3] != b":\\" {
+            return None;
+        }
+        (rest.to_string(), &rest[3..])
+    };
+    if simple.len() >= MAX_PATH {
+        return None;
+    }
+    let components: Vec<&str> = components.split('\\').collect();
+    let last = components.len() - 1;
+    components
+        .iter()
+        .enumerate()
+        .all(|(i, component)| (component.is_empty() && i == last) || is_ordinary_name(component))
+        .then_some(simple)
+}
+
+/// Whether Windows reads `name` as written outside a verbatim path. Trailing
+/// dots and spaces are stripped there, `.` and `..` resolved, `/` taken as a
+/// separator and reserved names taken as devices.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn is_ordinary_name(name: &str) -> bool {
+    if name.is_empty() || name.ends_with(['.', ' ']) {
+        return false;
+    }
+    if name
+        .chars()
+        .any(|c| c < ' ' || matches!(c, '/' | '<' | '>' | ':' | '"' | '|' | '?' | '*'))
+    {
+        return false;
+    }
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    !RESERVED_NAMES
+        .iter()
+        .any(|reserved| stem.eq_ignore_ascii_case(reserved))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplify_verbatim() {
+        assert_eq!(
+            simplify_verbatim(r"\\?\C:\repo\src\lib.rs").as_deref(),
+            Some(r"C:\repo\src\lib.rs")
+        );
+        assert_eq!(simplify_verbatim(r"\\?\C:\").as_deref(), Some(r"C:\"));
+        assert_eq!(
+            simplify_verbatim(r"\\?\UNC\server\share\repo").as_deref(),
+            Some(r"\\server\share\repo")
+        );
+        assert_eq!(
+            simplify_verbatim(r"\\?\UNC\server\share").as_deref(),
+            Some(r"\\server\share")
+        );
+
+        // Not verbatim, or no ordinary form
+        assert_eq!(simplify_verbatim(r"C:\repo"), None);
+        assert_eq!(simplify_verbatim(r"\\?\Volume{1234}\repo"), None);
+        assert_eq!(simplify_verbatim(r"\\?\UNC\server"), None);
+        assert_eq!(simplify_verbatim(r"\\?\C:\repo\nul.txt"), None);
+        assert_eq!(simplify_verbatim(r"\\?\C:\repo\Com1"), None);
+        assert_eq!(simplify_verbatim(r"\\?\C:\repo\trailing."), None);
+        assert_eq!(simplify_verbatim(r"\\?\C:\repo\..\other"), None);
+        assert_eq!(simplify_verbatim(r"\\?\C:\repo/src"), None);
+        assert_eq!(simplify_verbatim(r"\\?\C:\repo\\src"), None);
+        let long = format!(r"\\?\C:\{}", "a".repeat(MAX_PATH));
+        assert_eq!(simplify_verbatim(&long), None);
+        let unc_long = format!(r"\\?\UNC\server\share\{}", "a".repeat(MAX_PATH));
+        assert_eq!(simplify_verbatim(&unc_long), None);
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let canonical = canonicalize(temp_dir.path()).unwrap();
+        assert!(canonical.is_absolute());
+        assert!(!canonical.to_string_lossy().starts_with(r"\\?\"));
+        assert_eq!(simplified(&canonical), canonical.as_path());
+        assert!(canonicalize(&temp_dir.path().join("missing")).is_err());
+    }
+}
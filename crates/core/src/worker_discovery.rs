@@ -0,0 +1,297 @@
+//! Finding distributed scan workers and checking they can take part in a scan
+
+use crate::errors::{ScanError, ScanResult};
+use crate::tls::WorkerTls;
+use crate::webhook::{post_json, validate_webhook_url};
+use crate::WorkerConfig;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::time::Duration;
+
+/// Version of the coordinator-worker protocol. Workers speaking another
+/// version are rejected during the handshake.
+pub const WORKER_PROTOCOL_VERSION: u32 = 1;
+
+/// Path appended to a worker endpoint for the capability handshake
+pub const HANDSHAKE_PATH: &str = "/handshake";
+
+/// Where the coordinator finds its workers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkerDiscovery {
+    /// A fixed list of worker base URLs
+    Static(Vec<String>),
+    /// A DNS SRV record such as `_code-guardian._tcp.example.com`, resolved
+    /// with `dig`; each target becomes `http://<target>:<port>`, or
+    /// `https://` with TLS
+    DnsSrv(String),
+}
+
+impl WorkerDiscovery {
+    /// Worker endpoints, best first for SRV records (lowest priority, then
+    /// highest weight). With `tls` enabled every endpoint must be https.
+    pub fn discover(&self, tls: &WorkerTls) -> ScanResult<Vec<String>> {
+        let endpoints = match self {
+            Self::Static(endpoints) => endpoints.clone(),
+            Self::DnsSrv(name) => {
+                let output = Command::new("dig")
+                    .args(["+short", "SRV", name])
+                    .output()
+                    .map_err(|e| ScanError::worker(name, format!("could not run dig: {}", e)))?;
+                if !output.status.success() {
+                    return Err(ScanError::worker(
+                        name,
+                        String::from_utf8_lossy(&output.stderr).trim(),
+                    ));
+                }
+                let endpoints =
+                    parse_srv_records(&String::from_utf8_lossy(&output.stdout), tls.scheme());
+                if endpoints.is_empty() {
+                    return Err(ScanError::worker(name, "no SRV records found"));
+                }
+                endpoints
+            }
+        };
+        for endpoint in &endpoints {
+            validate_webhook_url(endpoint).map_err(|_| {
+                ScanError::worker(endpoint, "endpoint must start with http:// or https://")
+            })?;
+            if tls.is_enabled() && !endpoint.starts_with("https://") {
+                return Err(ScanError::worker(
+                    endpoint,
+                    "endpoint must start with https:// when worker TLS is configured",
+                ));
+            }
+        }
+        Ok(endpoints)
+    }
+}
+
+/// Endpoints from `dig +short SRV` output lines (`priority weight port target`)
+fn parse_srv_records(output: &str, scheme: &str) -> Vec<String> {
+    let mut records: Vec<(u16, u16, String)> = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let priority = fields.next()?.parse().ok()?;
+            let weight = fields.next()?.parse().ok()?;
+            let port: u16 = fields.next()?.parse().ok()?;
+            let target = fields.next()?.trim_end_matches('.');
+            Some((
+                priority,
+                weight,
+                format!("{}://{}:{}", scheme, target, port),
+            ))
+        })
+        .collect();
+    records.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2)));
+    records
+        .into_iter()
+        .map(|(_, _, endpoint)| endpoint)
+        .collect()
+}
+
+/// What the coordinator sends a worker to open the handshake
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandshakeRequest {
+    pub version: String,
+    pub protocol_version: u32,
+    /// Detectors the scan needs
+    pub detectors: Vec<String>,
+}
+
+/// What a worker answers the handshake with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkerCapabilities {
+    pub worker_id: String,
+    /// Code Guardian version of the worker
+    pub version: String,
+    pub protocol_version: u32,
+    pub supported_detectors: Vec<String>,
+    #[serde(default = "default_cpu_cores")]
+    pub cpu_cores: usize,
+    #[serde(default)]
+    pub memory_limit_mb: usize,
+}
+
+fn default_cpu_cores() -> usize {
+    1
+}
+
+/// Whether two versions can work together: the same major version, or for
+/// 0.x releases the same minor version
+pub fn versions_compatible(a: &str, b: &str) -> bool {
+    fn major_minor(version: &str) -> Option<(u64, u64)> {
+        let mut parts = version.trim_start_matches('v').split('.');
+        Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+    }
+    match (major_minor(a), major_minor(b)) {
+        (Some((0, a_minor)), Some((0, b_minor))) => a_minor == b_minor,
+        (Some((a_major, _)), Some((b_major, _))) => a_major == b_major,
+        _ => false,
+    }
+}
+
+impl WorkerCapabilities {
+    /// Why this worker can't take part in a scan needing `detectors`, if it can't
+    pub fn incompatibility(&self, detectors: &[String]) -> Option<String> {
+        let version = env!("CARGO_PKG_VERSION");
+        if self.protocol_version != WORKER_PROTOCOL_VERSION {
+            return Some(format!(
+                "speaks protocol version {}, the coordinator speaks {}",
+                self.protocol_version, WORKER_PROTOCOL_VERSION
+            ));
+        }
+        if !versions_compatible(&self.version, version) {
+            return Some(format!(
+                "runs version {}, which is incompatible with the coordinator's {}",
+                self.version, version
+            ));
+        }
+        let missing: Vec<&str> = detectors
+            .iter()
+            .filter(|detector| !self.supported_detectors.contains(detector))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Some(format!(
+                "version {} does not support detectors: {}",
+                self.version,
+                missing.join(", ")
+            ));
+        }
+        None
+    }
+}
+
+/// Exchange capabilities with the worker at `endpoint`, returning its config
+/// if it supports every detector in `detectors` at a compatible version
+pub fn handshake(
+    endpoint: &str,
+    detectors: &[String],
+    timeout: Duration,
+    tls: &WorkerTls,
+) -> ScanResult<WorkerConfig> {
+    let request = HandshakeRequest {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        protocol_version: WORKER_PROTOCOL_VERSION,
+        detectors: detectors.to_vec(),
+    };
+    let body = serde_json::to_vec(&request).map_err(|e| ScanError::worker(endpoint, e))?;
+    let url = format!("{}{}", endpoint.trim_end_matches('/'), HANDSHAKE_PATH);
+    // https goes through rustls, so TLS doesn't depend on how curl was built
+    let response = if url.starts_with("https://") {
+        tls.post_json(&url, &body, timeout)
+    } else {
+        post_json(&url, &[], &[], &body, timeout)
+    }
+    .map_err(|e| ScanError::worker(endpoint, format!("handshake failed: {}", e)))?;
+    let capabilities: WorkerCapabilities = serde_json::from_slice(&response)
+        .map_err(|e| ScanError::worker(endpoint, format!("invalid handshake response: {}", e)))?;
+    if let Some(reason) = capabilities.incompatibility(detectors) {
+        return Err(ScanError::worker(
+            format!("{} ({})", capabilities.worker_id, endpoint),
+            reason,
+        ));
+    }
+    Ok(WorkerConfig {
+        worker_id: capabilities.worker_id,
+        max_concurrent_units: capabilities.cpu_cores.max(1),
+        supported_detectors: capabilities.supported_detectors,
+        cpu_cores: capabilities.cpu_cores,
+        memory_limit_mb: capabilities.memory_limit_mb,
+        endpoint: Some(endpoint.to_string()),
+    })
+}
+
+/// Discover workers and handshake with each. Any unreachable or mismatched
+/// worker fails the whole negotiation, so a scan never runs on a subset of
+/// workers that would skew its results.
+pub fn negotiate_workers(
+    discovery: &WorkerDiscovery,
+    detectors: &[String],
+    timeout: Duration,
+    tls: &WorkerTls,
+) -> ScanResult<Vec<WorkerConfig>> {
+    tls.validate()?;
+    discovery
+        .discover(tls)?
+        .iter()
+        .map(|endpoint| handshake(endpoint, detectors, timeout, tls))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_srv_records() {
+        let output =
+            "20 5 8080 backup.example.com.\n10 1 9000 b.example.com.\n10 5 9000 a.example.com.\n";
+        assert_eq!(
+            parse_srv_records(output, "http"),
+            vec![
+                "http://a.example.com:9000",
+                "http://b.example.com:9000",
+                "http://backup.example.com:8080"
+            ]
+        );
+        assert_eq!(
+            parse_srv_records("10 5 9000 a.example.com.\n", "https"),
+            vec!["https://a.example.com:9000"]
+        );
+        assert!(parse_srv_records(";; connection timed out\n", "http").is_empty());
+    }
+
+    #[test]
+    fn test_tls_requires_https_endpoints() {
+        let discovery = WorkerDiscovery::Static(vec![
+            "https://a.example.com:9000".to_string(),
+            "http://b.example.com:9000".to_string(),
+        ]);
+        assert_eq!(discovery.discover(&WorkerTls::default()).unwrap().len(), 2);
+        let tls = WorkerTls {
+            ca: Some("ca.pem".into()),
+            ..WorkerTls::default()
+        };
+        let err = discovery.discover(&tls).unwrap_err().to_string();
+        assert!(err.contains("http://b.example.com:9000"), "{}", err);
+        assert!(err.contains("https://"), "{}", err);
+    }
+
+    #[test]
+    fn test_capability_negotiation() {
+        assert!(versions_compatible("0.2.1", "0.2.9"));
+        assert!(!versions_compatible("0.1.0", "0.2.0"));
+        assert!(versions_compatible("1.4.0", "1.0.2"));
+        assert!(!versions_compatible("garbage", "1.0.0"));
+
+        let worker = WorkerCapabilities {
+            worker_id: "w1".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            protocol_version: WORKER_PROTOCOL_VERSION,
+            supported_detectors: vec!["TODO".to_string()],
+            cpu_cores: 2,
+            memory_limit_mb: 512,
+        };
+        assert_eq!(worker.incompatibility(&["TODO".to_string()]), None);
+        assert!(worker
+            .incompatibility(&["TODO".to_string(), "FIXME".to_string()])
+            .unwrap()
+            .contains("does not support detectors: FIXME"));
+
+        let old = WorkerCapabilities {
+            version: "0.0.1".to_string(),
+            ..worker.clone()
+        };
+        assert!(old.incompatibility(&[]).unwrap().contains("0.0.1"));
+        let other_protocol = WorkerCapabilities {
+            protocol_version: WORKER_PROTOCOL_VERSION + 1,
+            ..worker
+        };
+        assert!(other_protocol
+            .incompatibility(&[])
+            .unwrap()
+            .contains("protocol version"));
+    }
+}
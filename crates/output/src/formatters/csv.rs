@@ -8,16 +8,32 @@ pub struct CsvFormatter;
 impl Formatter for CsvFormatter {
     fn format(&self, matches: &[Match]) -> String {
         let mut wtr = csv::Writer::from_writer(vec![]);
-        wtr.write_record(["file_path", "line_number", "column", "pattern", "message"])
-            .unwrap();
+        wtr.write_record([
+            "file_path",
+            "line_number",
+            "column",
+            "pattern",
+            "message",
+            "severity",
+            "category",
+            "author",
+            "author_email",
+        ])
+        .unwrap();
 
         for m in matches {
             wtr.write_record([
-                &m.file_path,
+                m.file_path.as_str(),
                 &m.line_number.to_string(),
                 &m.column.to_string(),
-                &m.pattern,
+                m.pattern.as_str(),
                 &m.message,
+                &m.severity
+                    .as_ref()
+                    .map_or(String::new(), |severity| format!("{:?}", severity)),
+                m.category.as_deref().unwrap_or(""),
+                m.author.as_ref().map_or("", |author| author.name.as_str()),
+                m.author.as_ref().map_or("", |author| author.email.as_str()),
             ])
             .unwrap();
         }
@@ -45,11 +61,15 @@ mod tests {
     fn test_single_match() {
         let formatter = CsvFormatter;
         let matches = vec![Match {
-            file_path: "test.rs".to_string(),
+            file_path: "test.rs".into(),
             line_number: 1,
             column: 1,
-            pattern: "TODO".to_string(),
+            pattern: "TODO".into(),
             message: "TODO: fix this".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         }];
         let output = formatter.format(&matches);
         let lines: Vec<&str> = output.lines().collect();
@@ -62,18 +82,26 @@ mod tests {
         let formatter = CsvFormatter;
         let matches = vec![
             Match {
-                file_path: "test.rs".to_string(),
+                file_path: "test.rs".into(),
                 line_number: 1,
                 column: 1,
-                pattern: "TODO".to_string(),
+                pattern: "TODO".into(),
                 message: "TODO".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
             Match {
-                file_path: "test.js".to_string(),
+                file_path: "test.js".into(),
                 line_number: 2,
                 column: 3,
-                pattern: "FIXME".to_string(),
+                pattern: "FIXME".into(),
                 message: "FIXME".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
         ];
         let output = formatter.format(&matches);
@@ -87,11 +115,15 @@ mod tests {
     fn test_csv_escaping() {
         let formatter = CsvFormatter;
         let matches = vec![Match {
-            file_path: "test,file.rs".to_string(),
+            file_path: "test,file.rs".into(),
             line_number: 1,
             column: 1,
-            pattern: "TODO".to_string(),
+            pattern: "TODO".into(),
             message: "TODO, with comma".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         }];
         let output = formatter.format(&matches);
         let lines: Vec<&str> = output.lines().collect();
@@ -114,11 +146,15 @@ mod proptest_tests {
             ".*",
         )
             .prop_map(|(fp, ln, col, pat, msg)| Match {
-                file_path: fp.to_string(),
+                file_path: fp.into(),
                 line_number: ln,
                 column: col,
-                pattern: pat.to_string(),
+                pattern: pat.into(),
                 message: msg.to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             })
     }
 
@@ -133,7 +169,7 @@ mod proptest_tests {
             prop_assert_eq!(records.len(), matches.len());
             for (i, record) in records.into_iter().enumerate() {
                 let record = record.unwrap();
-                prop_assert_eq!(record.len(), 5);
+                prop_assert_eq!(record.len(), 9);
                 prop_assert_eq!(record[0].to_string(), matches[i].file_path.clone());
                 prop_assert_eq!(record[1].to_string(), matches[i].line_number.to_string());
                 prop_assert_eq!(record[2].to_string(), matches[i].column.to_string());
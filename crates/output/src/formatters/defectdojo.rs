@@ -0,0 +1,101 @@
+use super::Formatter;
+use code_guardian_core::{
+    compliance_of, finding_fingerprints, EnhancedScanConfig, Match, Severity,
+};
+use serde_json::{json, Value};
+
+/// Formatter that outputs matches in DefectDojo's Generic Findings Import
+/// format, for the "Generic Findings Import" scan type. Findings keep their
+/// fingerprint as `unique_id_from_tool`, so reimports deduplicate them.
+pub struct DefectDojoFormatter;
+
+/// DefectDojo severity of a finding: its own, else the built-in default of
+/// its pattern; unknown patterns count as Medium
+fn severity(m: &Match, defaults: &EnhancedScanConfig) -> &'static str {
+    let severity = m
+        .severity
+        .as_ref()
+        .or_else(|| defaults.severity_levels.get(m.pattern.as_str()))
+        .unwrap_or(&Severity::Medium);
+    match severity {
+        Severity::Critical => "Critical",
+        Severity::High => "High",
+        Severity::Medium => "Medium",
+        Severity::Low => "Low",
+        Severity::Info => "Info",
+    }
+}
+
+impl Formatter for DefectDojoFormatter {
+    fn format(&self, matches: &[Match]) -> String {
+        let defaults = EnhancedScanConfig::default();
+        let findings: Vec<Value> = matches
+            .iter()
+            .zip(finding_fingerprints(matches, ""))
+            .map(|(m, fingerprint)| {
+                let file_path = m.file_path.as_str().replace('\\', "/");
+                let mut finding = json!({
+                    "title": format!("{} in {}:{}", m.pattern, file_path, m.line_number),
+                    "description": m.message,
+                    "severity": severity(m, &defaults),
+                    "file_path": file_path,
+                    "line": m.line_number,
+                    "vuln_id_from_tool": m.pattern.as_str(),
+                    "unique_id_from_tool": fingerprint,
+                    "static_finding": true,
+                    "dynamic_finding": false,
+                });
+                if let Some(mapping) = compliance_of(&m.pattern) {
+                    if let Some(cwe) = mapping.cwe.first() {
+                        finding["cwe"] = json!(cwe);
+                    }
+                    finding["tags"] = json!(mapping.controls());
+                }
+                finding
+            })
+            .collect();
+        serde_json::to_string_pretty(&json!({ "findings": findings })).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn m(pattern: &str, line_number: usize, severity: Option<Severity>) -> Match {
+        Match::new(
+            "src/main.rs",
+            line_number,
+            pattern,
+            format!("{}: found", pattern),
+        )
+        .with_severity(severity)
+    }
+
+    #[test]
+    fn test_defectdojo_findings() {
+        let matches = vec![
+            m("LLM_HARDCODED_CREDENTIALS", 3, None),
+            m("TODO", 7, None),
+            m("MY_RULE", 9, Some(Severity::High)),
+            m("UNKNOWN_RULE", 11, None),
+        ];
+        let output: Value = serde_json::from_str(&DefectDojoFormatter.format(&matches)).unwrap();
+        let findings = output["findings"].as_array().unwrap();
+        assert_eq!(findings.len(), 4);
+        assert_eq!(
+            findings[0]["title"],
+            "LLM_HARDCODED_CREDENTIALS in src/main.rs:3"
+        );
+        assert_eq!(findings[0]["severity"], "Critical");
+        assert_eq!(findings[0]["cwe"], 798);
+        assert_eq!(
+            findings[0]["unique_id_from_tool"].as_str().unwrap().len(),
+            16
+        );
+        assert_eq!(findings[1]["severity"], "Low");
+        assert!(findings[1].get("cwe").is_none());
+        assert_eq!(findings[2]["severity"], "High");
+        assert_eq!(findings[3]["severity"], "Medium");
+    }
+}
@@ -1,8 +1,9 @@
-use super::Formatter;
+use super::{pattern_label, Formatter};
 use code_guardian_core::Match;
 
 /// Formatter that outputs matches in HTML table format.
-/// Includes basic HTML structure for standalone display.
+/// Includes basic HTML structure for standalone display. An Author column is
+/// added when any match has an author.
 pub struct HtmlFormatter;
 
 impl Formatter for HtmlFormatter {
@@ -29,24 +30,34 @@ impl Formatter for HtmlFormatter {
                 <th>Column</th>
                 <th>Pattern</th>
                 <th>Message</th>
-            </tr>
-        </thead>
-        <tbody>
 "#,
         );
+        let with_authors = matches.iter().any(|m| m.author.is_some());
+        if with_authors {
+            output.push_str("                <th>Author</th>\n");
+        }
+        output.push_str("            </tr>\n        </thead>\n        <tbody>\n");
 
         if matches.is_empty() {
             output.push_str("        <tr><td colspan=\"5\">No matches found.</td></tr>\n");
         } else {
             for m in matches {
                 output.push_str(&format!(
-                    "        <tr>\n            <td>{}</td>\n            <td>{}</td>\n            <td>{}</td>\n            <td>{}</td>\n            <td>{}</td>\n        </tr>\n",
+                    "        <tr>\n            <td>{}</td>\n            <td>{}</td>\n            <td>{}</td>\n            <td>{}</td>\n            <td>{}</td>\n",
                     html_escape(&m.file_path),
                     m.line_number,
                     m.column,
-                    html_escape(&m.pattern),
+                    html_escape(&pattern_label(m)),
                     html_escape(&m.message)
                 ));
+                if with_authors {
+                    let author = m.author.as_ref().map(ToString::to_string);
+                    output.push_str(&format!(
+                        "            <td>{}</td>\n",
+                        html_escape(&author.unwrap_or_default())
+                    ));
+                }
+                output.push_str("        </tr>\n");
             }
         }
 
@@ -89,11 +100,15 @@ mod tests {
     fn test_single_match() {
         let formatter = HtmlFormatter;
         let matches = vec![Match {
-            file_path: "test.rs".to_string(),
+            file_path: "test.rs".into(),
             line_number: 1,
             column: 1,
-            pattern: "TODO".to_string(),
+            pattern: "TODO".into(),
             message: "TODO: fix this".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         }];
         let output = formatter.format(&matches);
         assert!(output.contains("<table>"));
@@ -102,17 +117,38 @@ mod tests {
         assert!(output.contains("<td>TODO</td>"));
         assert!(output.contains("<td>TODO: fix this</td>"));
         assert!(output.contains("</html>"));
+        assert!(!output.contains("<th>Author</th>"));
+    }
+
+    #[test]
+    fn test_author_column() {
+        let author = code_guardian_core::Author {
+            name: "Jane Doe".to_string(),
+            email: "jane@example.com".to_string(),
+        };
+        let matches = vec![
+            Match::new("a.rs", 1, "TODO", "TODO: one").with_author(author),
+            Match::new("b.rs", 2, "TODO", "TODO: two"),
+        ];
+        let output = HtmlFormatter.format(&matches);
+        assert!(output.contains("<th>Author</th>"));
+        assert!(output.contains("<td>Jane Doe &lt;jane@example.com&gt;</td>"));
+        assert!(output.contains("<td>TODO: two</td>\n            <td></td>\n        </tr>"));
     }
 
     #[test]
     fn test_html_escape() {
         let formatter = HtmlFormatter;
         let matches = vec![Match {
-            file_path: "test&<>\"'.rs".to_string(),
+            file_path: "test&<>\"'.rs".into(),
             line_number: 1,
             column: 1,
-            pattern: "TODO".to_string(),
+            pattern: "TODO".into(),
             message: "TODO&<>\"'".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         }];
         let output = formatter.format(&matches);
         assert!(output.contains("test&amp;&lt;&gt;&quot;&#x27;.rs"));
@@ -124,18 +160,26 @@ mod tests {
         let formatter = HtmlFormatter;
         let matches = vec![
             Match {
-                file_path: "test.rs".to_string(),
+                file_path: "test.rs".into(),
                 line_number: 1,
                 column: 1,
-                pattern: "TODO".to_string(),
+                pattern: "TODO".into(),
                 message: "TODO".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
             Match {
-                file_path: "test.js".to_string(),
+                file_path: "test.js".into(),
                 line_number: 2,
                 column: 3,
-                pattern: "FIXME".to_string(),
+                pattern: "FIXME".into(),
                 message: "FIXME".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
         ];
         let output = formatter.format(&matches);
@@ -160,11 +204,15 @@ mod proptest_tests {
             ".*",
         )
             .prop_map(|(fp, ln, col, pat, msg)| Match {
-                file_path: fp.to_string(),
+                file_path: fp.into(),
                 line_number: ln,
                 column: col,
-                pattern: pat.to_string(),
+                pattern: pat.into(),
                 message: msg.to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             })
     }
 
@@ -1,13 +1,30 @@
 use super::Formatter;
-use code_guardian_core::Match;
+use code_guardian_core::{compliance_of, ComplianceMapping, Match};
+use serde::Serialize;
 
 /// Formatter that outputs matches in JSON format.
 /// Uses pretty-printed JSON for readability.
 pub struct JsonFormatter;
 
+/// A match with the compliance controls of its rule, if it has any
+#[derive(Serialize)]
+struct JsonMatch<'a> {
+    #[serde(flatten)]
+    m: &'a Match,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compliance: Option<ComplianceMapping>,
+}
+
 impl Formatter for JsonFormatter {
     fn format(&self, matches: &[Match]) -> String {
-        serde_json::to_string_pretty(matches).unwrap_or_else(|_| "[]".to_string())
+        let matches: Vec<JsonMatch> = matches
+            .iter()
+            .map(|m| JsonMatch {
+                m,
+                compliance: compliance_of(&m.pattern),
+            })
+            .collect();
+        serde_json::to_string_pretty(&matches).unwrap_or_else(|_| "[]".to_string())
     }
 }
 
@@ -28,11 +45,15 @@ mod tests {
     fn test_single_match() {
         let formatter = JsonFormatter;
         let matches = vec![Match {
-            file_path: "test.rs".to_string(),
+            file_path: "test.rs".into(),
             line_number: 1,
             column: 1,
-            pattern: "TODO".to_string(),
+            pattern: "TODO".into(),
             message: "TODO: fix this".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         }];
         let output = formatter.format(&matches);
         let expected = r#"[
@@ -52,18 +73,26 @@ mod tests {
         let formatter = JsonFormatter;
         let matches = vec![
             Match {
-                file_path: "test.rs".to_string(),
+                file_path: "test.rs".into(),
                 line_number: 1,
                 column: 1,
-                pattern: "TODO".to_string(),
+                pattern: "TODO".into(),
                 message: "TODO".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
             Match {
-                file_path: "test.js".to_string(),
+                file_path: "test.js".into(),
                 line_number: 2,
                 column: 3,
-                pattern: "FIXME".to_string(),
+                pattern: "FIXME".into(),
                 message: "FIXME".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
         ];
         let output = formatter.format(&matches);
@@ -71,6 +100,27 @@ mod tests {
         let parsed: Vec<Match> = serde_json::from_str(&output).unwrap();
         assert_eq!(parsed, matches);
     }
+
+    #[test]
+    fn test_compliance_controls() {
+        let matches = vec![Match {
+            file_path: "main.tf".into(),
+            line_number: 3,
+            column: 3,
+            pattern: "TF_HARDCODED_CREDENTIALS".into(),
+            message: "TF_HARDCODED_CREDENTIALS: password = \"********\"".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
+        }];
+        let output: serde_json::Value =
+            serde_json::from_str(&JsonFormatter.format(&matches)).unwrap();
+        assert_eq!(
+            output[0]["compliance"],
+            serde_json::json!({"cwe": [798], "owasp": ["A07:2021"], "soc2": ["CC6.1"]})
+        );
+    }
 }
 
 #[cfg(test)]
@@ -87,11 +137,15 @@ mod proptest_tests {
             ".*",
         )
             .prop_map(|(fp, ln, col, pat, msg)| Match {
-                file_path: fp.to_string(),
+                file_path: fp.into(),
                 line_number: ln,
                 column: col,
-                pattern: pat.to_string(),
+                pattern: pat.into(),
                 message: msg.to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             })
     }
 
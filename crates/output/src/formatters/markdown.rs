@@ -1,8 +1,9 @@
-use super::Formatter;
+use super::{pattern_label, Formatter};
 use code_guardian_core::Match;
 
 /// Formatter that outputs matches in Markdown table format.
-/// Suitable for documentation or GitHub issues.
+/// Suitable for documentation or GitHub issues. An Author column is added
+/// when any match has an author.
 pub struct MarkdownFormatter;
 
 impl Formatter for MarkdownFormatter {
@@ -11,18 +12,26 @@ impl Formatter for MarkdownFormatter {
             return "No matches found.".to_string();
         }
 
-        let mut output = String::from("| File | Line | Column | Pattern | Message |\n");
-        output.push_str("|------|------|--------|---------|---------|\n");
+        let with_authors = matches.iter().any(|m| m.author.is_some());
+        let mut output = String::from("| File | Line | Column | Pattern | Message |");
+        output.push_str(if with_authors { " Author |\n" } else { "\n" });
+        output.push_str("|------|------|--------|---------|---------|");
+        output.push_str(if with_authors { "--------|\n" } else { "\n" });
 
         for m in matches {
             output.push_str(&format!(
-                "| {} | {} | {} | {} | {} |\n",
+                "| {} | {} | {} | {} | {} |",
                 escape_md(&m.file_path),
                 m.line_number,
                 m.column,
-                escape_md(&m.pattern),
+                escape_md(&pattern_label(m)),
                 escape_md(&m.message)
             ));
+            if with_authors {
+                let author = m.author.as_ref().map(ToString::to_string);
+                output.push_str(&format!(" {} |", escape_md(&author.unwrap_or_default())));
+            }
+            output.push('\n');
         }
 
         output
@@ -50,11 +59,15 @@ mod tests {
     fn test_single_match() {
         let formatter = MarkdownFormatter;
         let matches = vec![Match {
-            file_path: "test.rs".to_string(),
+            file_path: "test.rs".into(),
             line_number: 1,
             column: 1,
-            pattern: "TODO".to_string(),
+            pattern: "TODO".into(),
             message: "TODO: fix this".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         }];
         let output = formatter.format(&matches);
         assert!(output.contains("| test.rs | 1 | 1 | TODO | TODO: fix this |"));
@@ -65,11 +78,15 @@ mod tests {
     fn test_escape_pipes() {
         let formatter = MarkdownFormatter;
         let matches = vec![Match {
-            file_path: "test|file.rs".to_string(),
+            file_path: "test|file.rs".into(),
             line_number: 1,
             column: 1,
-            pattern: "TODO".to_string(),
+            pattern: "TODO".into(),
             message: "TODO|fix".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         }];
         let output = formatter.format(&matches);
         assert!(output.contains("test\\|file.rs"));
@@ -81,18 +98,26 @@ mod tests {
         let formatter = MarkdownFormatter;
         let matches = vec![
             Match {
-                file_path: "test.rs".to_string(),
+                file_path: "test.rs".into(),
                 line_number: 1,
                 column: 1,
-                pattern: "TODO".to_string(),
+                pattern: "TODO".into(),
                 message: "TODO".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
             Match {
-                file_path: "test.js".to_string(),
+                file_path: "test.js".into(),
                 line_number: 2,
                 column: 3,
-                pattern: "FIXME".to_string(),
+                pattern: "FIXME".into(),
                 message: "FIXME".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
         ];
         let output = formatter.format(&matches);
@@ -117,14 +142,41 @@ mod proptest_tests {
             ".*",
         )
             .prop_map(|(fp, ln, col, pat, msg)| Match {
-                file_path: fp.to_string(),
+                file_path: fp.into(),
                 line_number: ln,
                 column: col,
-                pattern: pat.to_string(),
+                pattern: pat.into(),
                 message: msg.to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             })
     }
 
+    #[test]
+    fn test_author_column() {
+        let author = code_guardian_core::Author {
+            name: "Jane Doe".to_string(),
+            email: "jane@example.com".to_string(),
+        };
+        let matches = vec![
+            Match::new("a.rs", 1, "TODO", "TODO: one").with_author(author),
+            Match::new("b.rs", 2, "TODO", "TODO: two"),
+        ];
+        let output = MarkdownFormatter.format(&matches);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[0],
+            "| File | Line | Column | Pattern | Message | Author |"
+        );
+        assert_eq!(
+            lines[2],
+            "| a.rs | 1 | 1 | TODO | TODO: one | Jane Doe <jane@example.com> |"
+        );
+        assert_eq!(lines[3], "| b.rs | 2 | 1 | TODO | TODO: two |  |");
+    }
+
     proptest! {
         #[test]
         fn test_markdown_formatter_arbitrary_matches(matches in proptest::collection::vec(arb_match(), 0..10)) {
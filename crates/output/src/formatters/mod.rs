@@ -1,4 +1,5 @@
 use code_guardian_core::Match;
+use std::borrow::Cow;
 
 /// Trait for formatting a list of matches into a string representation.
 /// Implementors should define how to convert matches into various output formats.
@@ -8,14 +9,39 @@ pub trait Formatter {
     fn format(&self, matches: &[Match]) -> String;
 }
 
+/// The pattern of `m`, followed by the severity and category its detector
+/// set, e.g. "NO_PRINT [High, Security]"
+pub(crate) fn pattern_label(m: &Match) -> Cow<'_, str> {
+    let severity = m
+        .severity
+        .as_ref()
+        .map(|severity| format!("{:?}", severity));
+    let details: Vec<&str> = severity
+        .as_deref()
+        .into_iter()
+        .chain(m.category.as_deref())
+        .collect();
+    if details.is_empty() {
+        Cow::Borrowed(&m.pattern)
+    } else {
+        Cow::Owned(format!("{} [{}]", m.pattern, details.join(", ")))
+    }
+}
+
 pub mod csv;
+pub mod defectdojo;
 pub mod html;
 pub mod json;
 pub mod markdown;
+pub mod sarif;
+pub mod sonarqube;
 pub mod text;
 
 pub use csv::CsvFormatter;
+pub use defectdojo::DefectDojoFormatter;
 pub use html::HtmlFormatter;
 pub use json::JsonFormatter;
 pub use markdown::MarkdownFormatter;
+pub use sarif::SarifFormatter;
+pub use sonarqube::SonarQubeFormatter;
 pub use text::TextFormatter;
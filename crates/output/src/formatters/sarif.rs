@@ -0,0 +1,323 @@
+use super::Formatter;
+use code_guardian_core::{compliance_of, cwe_name, owasp_name, ComplianceMapping, Match, Severity};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Formatter that outputs matches as a SARIF 2.1.0 log, the format of GitHub
+/// code scanning and most vulnerability management tools. Rules carry the
+/// compliance controls they map to as `tags`, e.g. `external/cwe/cwe-798`,
+/// and as `relationships` to the CWE and OWASP Top 10 `taxonomies` of the
+/// run, which DefectDojo and other importers categorize findings by.
+pub struct SarifFormatter;
+
+/// Taxonomy entries referenced by the rules of a log
+#[derive(Default)]
+struct Taxa {
+    cwe: BTreeSet<u32>,
+    owasp: BTreeSet<String>,
+}
+
+impl Taxa {
+    fn new<'a>(mappings: impl Iterator<Item = &'a ComplianceMapping>) -> Self {
+        let mut taxa = Self::default();
+        for mapping in mappings {
+            taxa.cwe.extend(&mapping.cwe);
+            taxa.owasp.extend(mapping.owasp.iter().cloned());
+        }
+        taxa
+    }
+
+    /// Index of the CWE and OWASP taxonomies in the run's `taxonomies`
+    fn component_indices(&self) -> (usize, usize) {
+        (0, usize::from(!self.cwe.is_empty()))
+    }
+
+    fn taxonomies(&self) -> Vec<Value> {
+        let mut taxonomies = Vec::new();
+        if !self.cwe.is_empty() {
+            let taxa: Vec<Value> = self
+                .cwe
+                .iter()
+                .map(|id| {
+                    let mut taxon = json!({
+                        "id": id.to_string(),
+                        "helpUri": format!("https://cwe.mitre.org/data/definitions/{}.html", id),
+                    });
+                    if let Some(name) = cwe_name(*id) {
+                        taxon["name"] = json!(name);
+                        taxon["shortDescription"] = json!({ "text": name });
+                    }
+                    taxon
+                })
+                .collect();
+            taxonomies.push(json!({
+                "name": "CWE",
+                "organization": "MITRE",
+                "informationUri": "https://cwe.mitre.org/",
+                "shortDescription": { "text": "The MITRE Common Weakness Enumeration" },
+                "taxa": taxa,
+            }));
+        }
+        if !self.owasp.is_empty() {
+            let taxa: Vec<Value> = self
+                .owasp
+                .iter()
+                .map(|id| {
+                    let mut taxon = json!({ "id": id });
+                    if let Some(name) = owasp_name(id) {
+                        taxon["name"] = json!(name);
+                        taxon["shortDescription"] = json!({ "text": name });
+                    }
+                    taxon
+                })
+                .collect();
+            taxonomies.push(json!({
+                "name": "OWASP Top 10",
+                "organization": "OWASP",
+                "informationUri": "https://owasp.org/Top10/",
+                "shortDescription": { "text": "The OWASP Top 10 web application security risks" },
+                "taxa": taxa,
+            }));
+        }
+        taxonomies
+    }
+
+    /// `relationships` of a rule mapped to `mapping`
+    fn relationships(&self, mapping: &ComplianceMapping) -> Vec<Value> {
+        let (cwe_component, owasp_component) = self.component_indices();
+        let relationship = |id: String, index: usize, name: &str, component: usize| {
+            json!({
+                "target": {
+                    "id": id,
+                    "index": index,
+                    "toolComponent": { "name": name, "index": component },
+                },
+                "kinds": ["superset"],
+            })
+        };
+        let cwe = mapping.cwe.iter().filter_map(|id| {
+            let index = self.cwe.iter().position(|taxon| taxon == id)?;
+            Some(relationship(id.to_string(), index, "CWE", cwe_component))
+        });
+        let owasp = mapping.owasp.iter().filter_map(|id| {
+            let index = self.owasp.iter().position(|taxon| taxon == id)?;
+            Some(relationship(
+                id.clone(),
+                index,
+                "OWASP Top 10",
+                owasp_component,
+            ))
+        });
+        cwe.chain(owasp).collect()
+    }
+}
+
+/// SARIF `level` of a finding
+fn level(m: &Match) -> &'static str {
+    match m.severity {
+        Some(Severity::Critical | Severity::High) => "error",
+        Some(Severity::Low | Severity::Info) => "note",
+        _ => "warning",
+    }
+}
+
+/// The rule entry of `pattern`, tagged with and related to its compliance controls
+fn rule(pattern: &str, mapping: Option<&ComplianceMapping>, taxa: &Taxa) -> Value {
+    let mut rule = json!({
+        "id": pattern,
+        "shortDescription": { "text": pattern },
+    });
+    if let Some(mapping) = mapping {
+        let tags: Vec<String> = std::iter::once("security".to_string())
+            .chain(
+                mapping
+                    .cwe
+                    .iter()
+                    .map(|id| format!("external/cwe/cwe-{}", id)),
+            )
+            .chain(
+                mapping
+                    .owasp
+                    .iter()
+                    .map(|id| format!("external/owasp/{}", id.to_lowercase())),
+            )
+            .chain(mapping.soc2.iter().map(|id| format!("soc2/{}", id)))
+            .collect();
+        rule["properties"] = json!({ "tags": tags, "compliance": mapping });
+        let relationships = taxa.relationships(mapping);
+        if !relationships.is_empty() {
+            rule["relationships"] = json!(relationships);
+        }
+    }
+    rule
+}
+
+impl Formatter for SarifFormatter {
+    fn format(&self, matches: &[Match]) -> String {
+        let mut patterns: Vec<&str> = Vec::new();
+        let results: Vec<Value> = matches
+            .iter()
+            .map(|m| {
+                let rule_index = patterns
+                    .iter()
+                    .position(|pattern| *pattern == m.pattern.as_str())
+                    .unwrap_or_else(|| {
+                        patterns.push(&m.pattern);
+                        patterns.len() - 1
+                    });
+                let mut region = json!({
+                    "startLine": m.line_number,
+                    "startColumn": m.column,
+                });
+                if let Some(end) = &m.end {
+                    region["endLine"] = json!(end.line);
+                    region["endColumn"] = json!(end.column);
+                }
+                let mut result = json!({
+                    "ruleId": m.pattern.as_str(),
+                    "ruleIndex": rule_index,
+                    "level": level(m),
+                    "message": { "text": m.message },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": m.file_path.as_str().replace('\\', "/") },
+                            "region": region,
+                        }
+                    }],
+                });
+                if let Some(author) = &m.author {
+                    result["properties"] =
+                        json!({ "author": author.name, "authorEmail": author.email });
+                }
+                result
+            })
+            .collect();
+        let mappings: Vec<Option<ComplianceMapping>> = patterns
+            .iter()
+            .map(|pattern| compliance_of(pattern))
+            .collect();
+        let taxa = Taxa::new(mappings.iter().flatten());
+        let rules: Vec<Value> = patterns
+            .iter()
+            .zip(&mappings)
+            .map(|(pattern, mapping)| rule(pattern, mapping.as_ref(), &taxa))
+            .collect();
+        let mut run = json!({
+            "tool": {
+                "driver": {
+                    "name": "code-guardian",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/d-oit/code-guardian",
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        });
+        let taxonomies = taxa.taxonomies();
+        if !taxonomies.is_empty() {
+            run["taxonomies"] = json!(taxonomies);
+        }
+        let log = json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [run],
+        });
+        serde_json::to_string_pretty(&log).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn m(pattern: &str, severity: Option<Severity>) -> Match {
+        Match::new("infra\\main.tf", 3, pattern, format!("{}: found", pattern))
+            .with_column(5)
+            .with_severity(severity)
+    }
+
+    #[test]
+    fn test_sarif_rules_and_results() {
+        let matches = vec![
+            m("TF_HARDCODED_CREDENTIALS", Some(Severity::Critical)),
+            m("TODO", None),
+            m("TF_HARDCODED_CREDENTIALS", Some(Severity::Critical)),
+        ];
+        let log: Value = serde_json::from_str(&SarifFormatter.format(&matches)).unwrap();
+        let run = &log["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(
+            rules[0]["properties"]["tags"],
+            json!([
+                "security",
+                "external/cwe/cwe-798",
+                "external/owasp/a07:2021",
+                "soc2/CC6.1"
+            ])
+        );
+        assert!(rules[1].get("properties").is_none());
+        assert!(rules[1].get("relationships").is_none());
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1]["ruleIndex"], 1);
+        assert_eq!(results[1]["level"], "warning");
+        assert_eq!(results[2]["ruleIndex"], 0);
+        assert_eq!(results[2]["level"], "error");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "infra/main.tf"
+        );
+    }
+
+    #[test]
+    fn test_sarif_taxonomies() {
+        let matches = vec![m("UNSAFE_EVAL", None), m("TF_HARDCODED_CREDENTIALS", None)];
+        let log: Value = serde_json::from_str(&SarifFormatter.format(&matches)).unwrap();
+        let run = &log["runs"][0];
+        let taxonomies = run["taxonomies"].as_array().unwrap();
+        assert_eq!(taxonomies[0]["name"], "CWE");
+        let cwe: Vec<&str> = taxonomies[0]["taxa"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|taxon| taxon["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(cwe, ["95", "798"]);
+        assert_eq!(
+            taxonomies[0]["taxa"][1]["name"],
+            "Use of Hard-coded Credentials"
+        );
+        assert_eq!(taxonomies[1]["name"], "OWASP Top 10");
+
+        let relationships = &run["tool"]["driver"]["rules"][1]["relationships"];
+        assert_eq!(
+            relationships[0]["target"],
+            json!({"id": "798", "index": 1, "toolComponent": {"name": "CWE", "index": 0}})
+        );
+        assert_eq!(relationships[1]["target"]["id"], "A07:2021");
+        assert_eq!(relationships[1]["target"]["toolComponent"]["index"], 1);
+
+        let log: Value = serde_json::from_str(&SarifFormatter.format(&[m("TODO", None)])).unwrap();
+        assert!(log["runs"][0].get("taxonomies").is_none());
+    }
+
+    #[test]
+    fn test_sarif_result_author() {
+        let author = code_guardian_core::Author {
+            name: "Jane Doe".to_string(),
+            email: "jane@example.com".to_string(),
+        };
+        let matches = [m("TODO", None).with_author(author), m("TODO", None)];
+        let log: Value = serde_json::from_str(&SarifFormatter.format(&matches)).unwrap();
+        let results = &log["runs"][0]["results"];
+        assert_eq!(
+            results[0]["properties"],
+            json!({"author": "Jane Doe", "authorEmail": "jane@example.com"})
+        );
+        assert!(results[1].get("properties").is_none());
+    }
+}
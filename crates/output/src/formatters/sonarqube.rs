@@ -0,0 +1,136 @@
+use super::Formatter;
+use code_guardian_core::{compliance_of, EnhancedScanConfig, Match, Severity};
+use serde_json::{json, Value};
+
+const ENGINE_ID: &str = "code-guardian";
+
+/// Formatter that outputs matches as a SonarQube generic external issues
+/// report (SonarQube 10.3 and later), for `sonar.externalIssuesReportPaths`.
+/// Compliance-mapped and Security-category rules count as security issues,
+/// all others as maintainability issues.
+pub struct SonarQubeFormatter;
+
+/// Impact severity of a finding: its own severity, else the built-in default
+/// of its pattern; unknown patterns count as Medium
+fn impact_severity(m: &Match, defaults: &EnhancedScanConfig) -> &'static str {
+    let severity = m
+        .severity
+        .as_ref()
+        .or_else(|| defaults.severity_levels.get(m.pattern.as_str()))
+        .unwrap_or(&Severity::Medium);
+    match severity {
+        Severity::Critical | Severity::High => "HIGH",
+        Severity::Medium => "MEDIUM",
+        Severity::Low | Severity::Info => "LOW",
+    }
+}
+
+fn is_security(m: &Match) -> bool {
+    compliance_of(&m.pattern).is_some()
+        || m.category
+            .as_deref()
+            .is_some_and(|category| category.eq_ignore_ascii_case("security"))
+}
+
+/// The rule entry of the first finding `m` of a pattern
+fn rule(m: &Match, defaults: &EnhancedScanConfig) -> Value {
+    let (attribute, quality) = if is_security(m) {
+        ("TRUSTWORTHY", "SECURITY")
+    } else {
+        ("COMPLETE", "MAINTAINABILITY")
+    };
+    let description = match compliance_of(&m.pattern) {
+        Some(mapping) => format!("{} ({})", m.pattern, mapping.controls().join(", ")),
+        None => m.pattern.to_string(),
+    };
+    json!({
+        "id": m.pattern.as_str(),
+        "name": m.pattern.as_str(),
+        "description": description,
+        "engineId": ENGINE_ID,
+        "cleanCodeAttribute": attribute,
+        "impacts": [{
+            "softwareQuality": quality,
+            "severity": impact_severity(m, defaults),
+        }],
+    })
+}
+
+impl Formatter for SonarQubeFormatter {
+    fn format(&self, matches: &[Match]) -> String {
+        let defaults = EnhancedScanConfig::default();
+        let mut rules: Vec<Value> = Vec::new();
+        let issues: Vec<Value> = matches
+            .iter()
+            .map(|m| {
+                if !rules.iter().any(|rule| rule["id"] == m.pattern.as_str()) {
+                    rules.push(rule(m, &defaults));
+                }
+                let mut text_range = json!({ "startLine": m.line_number });
+                if let Some(end) = &m.end {
+                    text_range["endLine"] = json!(end.line);
+                }
+                json!({
+                    "ruleId": m.pattern.as_str(),
+                    "primaryLocation": {
+                        "message": m.message,
+                        "filePath": m.file_path.as_str().replace('\\', "/"),
+                        "textRange": text_range,
+                    },
+                })
+            })
+            .collect();
+        serde_json::to_string_pretty(&json!({ "rules": rules, "issues": issues }))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn m(pattern: &str, category: Option<&str>) -> Match {
+        Match::new("src\\lib.rs", 4, pattern, format!("{}: found", pattern))
+            .with_column(9)
+            .with_category(category.map(str::to_string))
+    }
+
+    #[test]
+    fn test_sonarqube_rules_and_issues() {
+        let matches = vec![
+            m("UNSAFE_EVAL", None),
+            m("TODO", None),
+            m("UNSAFE_EVAL", None),
+            m("NO_HTTP", Some("Security")),
+        ];
+        let output: Value = serde_json::from_str(&SonarQubeFormatter.format(&matches)).unwrap();
+        let rules = output["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 3);
+        assert_eq!(rules[0]["engineId"], "code-guardian");
+        assert_eq!(
+            rules[0]["impacts"],
+            json!([{"softwareQuality": "SECURITY", "severity": "HIGH"}])
+        );
+        assert_eq!(
+            rules[0]["description"],
+            "UNSAFE_EVAL (CWE-95, OWASP A03:2021, SOC2 CC7.1)"
+        );
+        assert_eq!(
+            rules[1]["impacts"],
+            json!([{"softwareQuality": "MAINTAINABILITY", "severity": "LOW"}])
+        );
+        assert_eq!(rules[2]["impacts"][0]["softwareQuality"], "SECURITY");
+
+        let issues = output["issues"].as_array().unwrap();
+        assert_eq!(issues.len(), 4);
+        assert_eq!(issues[2]["ruleId"], "UNSAFE_EVAL");
+        assert_eq!(
+            issues[0]["primaryLocation"],
+            json!({
+                "message": "UNSAFE_EVAL: found",
+                "filePath": "src/lib.rs",
+                "textRange": {"startLine": 4},
+            })
+        );
+    }
+}
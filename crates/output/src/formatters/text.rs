@@ -1,8 +1,9 @@
-use super::Formatter;
+use super::{pattern_label, Formatter};
 use code_guardian_core::Match;
 
 /// Formatter that outputs matches in a simple text format.
-/// Each match is displayed as "file:line:column: pattern - message".
+/// Each match is displayed as "file:line:column: pattern - message",
+/// followed by " (author <email>)" when the match has an author.
 pub struct TextFormatter;
 
 impl Formatter for TextFormatter {
@@ -14,9 +15,17 @@ impl Formatter for TextFormatter {
         let mut output = String::new();
         for m in matches {
             output.push_str(&format!(
-                "{}:{}:{}: {} - {}\n",
-                m.file_path, m.line_number, m.column, m.pattern, m.message
+                "{}:{}:{}: {} - {}",
+                m.file_path,
+                m.line_number,
+                m.column,
+                pattern_label(m),
+                m.message
             ));
+            if let Some(author) = &m.author {
+                output.push_str(&format!(" ({})", author));
+            }
+            output.push('\n');
         }
         output.trim_end().to_string()
     }
@@ -38,11 +47,15 @@ mod tests {
     fn test_single_match() {
         let formatter = TextFormatter;
         let matches = vec![Match {
-            file_path: "test.rs".to_string(),
+            file_path: "test.rs".into(),
             line_number: 1,
             column: 1,
-            pattern: "TODO".to_string(),
+            pattern: "TODO".into(),
             message: "TODO comment".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         }];
         let output = formatter.format(&matches);
         let expected = "test.rs:1:1: TODO - TODO comment";
@@ -54,18 +67,26 @@ mod tests {
         let formatter = TextFormatter;
         let matches = vec![
             Match {
-                file_path: "src/main.rs".to_string(),
+                file_path: "src/main.rs".into(),
                 line_number: 10,
                 column: 5,
-                pattern: "TODO".to_string(),
+                pattern: "TODO".into(),
                 message: "Found a TODO".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
             Match {
-                file_path: "src/lib.rs".to_string(),
+                file_path: "src/lib.rs".into(),
                 line_number: 10,
                 column: 1,
-                pattern: "FIXME".to_string(),
+                pattern: "FIXME".into(),
                 message: "FIXME: temporary workaround".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
         ];
         let output = formatter.format(&matches);
@@ -78,24 +99,52 @@ mod tests {
         let formatter = TextFormatter;
         let matches = vec![
             Match {
-                file_path: "test.rs".to_string(),
+                file_path: "test.rs".into(),
                 line_number: 1,
                 column: 1,
-                pattern: "TODO".to_string(),
+                pattern: "TODO".into(),
                 message: "TODO".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
             Match {
-                file_path: "test.js".to_string(),
+                file_path: "test.js".into(),
                 line_number: 2,
                 column: 3,
-                pattern: "FIXME".to_string(),
+                pattern: "FIXME".into(),
                 message: "FIXME".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
         ];
         let output = formatter.format(&matches);
         let expected = "test.rs:1:1: TODO - TODO\ntest.js:2:3: FIXME - FIXME";
         assert_eq!(output, expected);
     }
+
+    #[test]
+    fn test_detector_severity_and_category() {
+        let formatter = TextFormatter;
+        let matches = vec![Match {
+            file_path: "app.py".into(),
+            line_number: 3,
+            column: 1,
+            pattern: "NO_PRINT".into(),
+            message: "NO_PRINT: print(x)".to_string(),
+            severity: Some(code_guardian_core::Severity::High),
+            category: Some("CodeQuality".to_string()),
+            end: None,
+            author: None,
+        }];
+        assert_eq!(
+            formatter.format(&matches),
+            "app.py:3:1: NO_PRINT [High, CodeQuality] - NO_PRINT: print(x)"
+        );
+    }
 }
 
 #[cfg(test)]
@@ -112,14 +161,34 @@ mod proptest_tests {
             ".*",
         )
             .prop_map(|(fp, ln, col, pat, msg)| Match {
-                file_path: fp.to_string(),
+                file_path: fp.into(),
                 line_number: ln,
                 column: col,
-                pattern: pat.to_string(),
+                pattern: pat.into(),
                 message: msg.to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             })
     }
 
+    #[test]
+    fn test_author_suffix() {
+        let author = code_guardian_core::Author {
+            name: "Jane Doe".to_string(),
+            email: "jane@example.com".to_string(),
+        };
+        let matches = vec![
+            Match::new("a.rs", 1, "TODO", "TODO: one").with_author(author),
+            Match::new("b.rs", 2, "TODO", "TODO: two"),
+        ];
+        assert_eq!(
+            TextFormatter.format(&matches),
+            "a.rs:1:1: TODO - TODO: one (Jane Doe <jane@example.com>)\nb.rs:2:1: TODO - TODO: two"
+        );
+    }
+
     proptest! {
         #[test]
         fn test_text_formatter_arbitrary_matches(matches in proptest::collection::vec(arb_match(), 0..10)) {
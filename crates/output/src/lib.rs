@@ -10,18 +10,26 @@ mod tests {
     fn create_test_matches() -> Vec<Match> {
         vec![
             Match {
-                file_path: "src/main.rs".to_string(),
+                file_path: "src/main.rs".into(),
                 line_number: 10,
                 column: 5,
-                pattern: "TODO".to_string(),
+                pattern: "TODO".into(),
                 message: "Fix this implementation".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
             Match {
-                file_path: "src/lib.rs".to_string(),
+                file_path: "src/lib.rs".into(),
                 line_number: 25,
                 column: 1,
-                pattern: "FIXME".to_string(),
+                pattern: "FIXME".into(),
                 message: "Handle error case".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             },
         ]
     }
@@ -78,11 +86,15 @@ mod tests {
     #[test]
     fn test_formatters_with_special_characters() {
         let matches = vec![Match {
-            file_path: "test/file with spaces.rs".to_string(),
+            file_path: "test/file with spaces.rs".into(),
             line_number: 1,
             column: 1,
-            pattern: "TODO".to_string(),
+            pattern: "TODO".into(),
             message: "Message with \"quotes\" and <html> & symbols".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         }];
 
         // Test that formatters properly escape or handle special characters
@@ -4,11 +4,15 @@ use code_guardian_output::*;
 #[test]
 fn test_formatter_trait_object() {
     let matches = vec![Match {
-        file_path: "test.rs".to_string(),
+        file_path: "test.rs".into(),
         line_number: 1,
         column: 1,
-        pattern: "TODO".to_string(),
+        pattern: "TODO".into(),
         message: "Test message".to_string(),
+        severity: None,
+        category: None,
+        end: None,
+        author: None,
     }];
 
     let formatters: Vec<Box<dyn Formatter>> = vec![
@@ -29,11 +33,15 @@ fn test_formatter_trait_object() {
 #[test]
 fn test_formatters_with_unicode_content() {
     let matches = vec![Match {
-        file_path: "测试.rs".to_string(),
+        file_path: "测试.rs".into(),
         line_number: 1,
         column: 1,
-        pattern: "TODO".to_string(),
+        pattern: "TODO".into(),
         message: "Message with émojis 🚀 and unicode: αβγ".to_string(),
+        severity: None,
+        category: None,
+        end: None,
+        author: None,
     }];
 
     // Test all formatters handle unicode correctly
@@ -64,11 +72,15 @@ fn test_formatters_with_very_long_content() {
     let long_path = format!("very/long/path/{}/file.rs", "dir/".repeat(100));
 
     let matches = vec![Match {
-        file_path: long_path.clone(),
+        file_path: long_path.clone().into(),
         line_number: 999999,
         column: 999999,
-        pattern: "TODO".to_string(),
+        pattern: "TODO".into(),
         message: long_message.clone(),
+        severity: None,
+        category: None,
+        end: None,
+        author: None,
     }];
 
     // Test all formatters handle very long content
@@ -97,18 +109,26 @@ fn test_formatters_with_very_long_content() {
 fn test_formatters_with_edge_case_numbers() {
     let matches = vec![
         Match {
-            file_path: "test.rs".to_string(),
+            file_path: "test.rs".into(),
             line_number: 0,
             column: 0,
-            pattern: "TODO".to_string(),
+            pattern: "TODO".into(),
             message: "Zero values".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         },
         Match {
-            file_path: "test2.rs".to_string(),
+            file_path: "test2.rs".into(),
             line_number: usize::MAX,
             column: usize::MAX,
-            pattern: "FIXME".to_string(),
+            pattern: "FIXME".into(),
             message: "Max values".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         },
     ];
 
@@ -137,11 +157,12 @@ fn test_formatters_with_edge_case_numbers() {
 #[test]
 fn test_formatters_comprehensive_special_chars() {
     let matches = vec![Match {
-        file_path: "test\n\r\t\"'\\&<>/file.rs".to_string(),
+        file_path: "test\n\r\t\"'\\&<>/file.rs".into(),
         line_number: 1,
         column: 1,
-        pattern: "TODO\n\r\t".to_string(),
-        message: "Message\nwith\rnewlines\tand\ttabs\"quotes'apostrophes\\backslashes&ampersands<less>greater/slashes".to_string(),
+        pattern: "TODO\n\r\t".into(),
+        message: "Message\nwith\rnewlines\tand\ttabs\"quotes'apostrophes\\backslashes&ampersands<less>greater/slashes".to_string(), severity: None, category: None, end: None,
+        author: None,
     }];
 
     // Test JSON handles all special characters
@@ -6,14 +6,18 @@ use std::time::Instant;
 fn test_formatter_performance_with_large_datasets() {
     let large_matches: Vec<Match> = (0..1000)
         .map(|i| Match {
-            file_path: format!("file_{}.rs", i),
+            file_path: format!("file_{}.rs", i).into(),
             line_number: i,
             column: i % 100,
-            pattern: format!("PATTERN_{}", i % 10),
+            pattern: format!("PATTERN_{}", i % 10).into(),
             message: format!(
                 "Message number {} with some additional text to make it longer",
                 i
             ),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         })
         .collect();
 
@@ -64,11 +68,15 @@ fn test_memory_efficiency() {
     // Test that formatters don't use excessive memory with many matches
     let matches: Vec<Match> = (0..10000)
         .map(|i| Match {
-            file_path: format!("memory_test_{}.rs", i),
+            file_path: format!("memory_test_{}.rs", i).into(),
             line_number: i,
             column: i,
-            pattern: "TODO".to_string(),
+            pattern: "TODO".into(),
             message: "x".repeat(100), // 100 character message
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
         })
         .collect();
 
@@ -86,11 +94,15 @@ fn test_concurrent_usage() {
     use std::thread;
 
     let matches = Arc::new(vec![Match {
-        file_path: "concurrent_test.rs".to_string(),
+        file_path: "concurrent_test.rs".into(),
         line_number: 1,
         column: 1,
-        pattern: "TODO".to_string(),
+        pattern: "TODO".into(),
         message: "Concurrent access test".to_string(),
+        severity: None,
+        category: None,
+        end: None,
+        author: None,
     }]);
 
     let handles: Vec<_> = (0..10)
@@ -124,11 +136,15 @@ fn test_concurrent_usage() {
 #[test]
 fn test_formatter_consistency_across_runs() {
     let matches = vec![Match {
-        file_path: "consistency_test.rs".to_string(),
+        file_path: "consistency_test.rs".into(),
         line_number: 42,
         column: 10,
-        pattern: "TODO".to_string(),
+        pattern: "TODO".into(),
         message: "Consistency test message".to_string(),
+        severity: None,
+        category: None,
+        end: None,
+        author: None,
     }];
 
     let formatters: Vec<Box<dyn Formatter>> = vec![
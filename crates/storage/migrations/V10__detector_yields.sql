@@ -0,0 +1,8 @@
+CREATE TABLE detector_yields (
+    detector TEXT NOT NULL,
+    extension TEXT NOT NULL,
+    files INTEGER NOT NULL,
+    matches INTEGER NOT NULL,
+    skipped_scans INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (detector, extension)
+);
@@ -0,0 +1,9 @@
+ALTER TABLE matches ADD COLUMN message_zstd BLOB;
+
+CREATE TABLE scan_compression (
+    scan_id INTEGER PRIMARY KEY,
+    dictionary BLOB NOT NULL,
+    raw_bytes INTEGER NOT NULL,
+    stored_bytes INTEGER NOT NULL,
+    FOREIGN KEY(scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
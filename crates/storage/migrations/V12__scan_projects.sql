@@ -0,0 +1,33 @@
+ALTER TABLE scans ADD COLUMN project TEXT NOT NULL DEFAULT '';
+
+CREATE INDEX idx_scans_project ON scans (project, timestamp);
+
+-- Findings of projects that share a root path are tracked apart
+CREATE TABLE findings_by_project (
+    project TEXT NOT NULL DEFAULT '',
+    root_path TEXT NOT NULL,
+    fingerprint TEXT NOT NULL,
+    state TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    line_number INTEGER NOT NULL,
+    pattern TEXT NOT NULL,
+    message TEXT NOT NULL,
+    first_seen_scan INTEGER NOT NULL,
+    last_seen_scan INTEGER NOT NULL,
+    note TEXT,
+    issue_key TEXT,
+    PRIMARY KEY (project, root_path, fingerprint)
+);
+
+INSERT INTO findings_by_project (root_path, fingerprint, state, file_path, line_number, pattern, message, first_seen_scan, last_seen_scan, note, issue_key)
+SELECT root_path, fingerprint, state, file_path, line_number, pattern, message, first_seen_scan, last_seen_scan, note, issue_key FROM findings;
+
+DROP TABLE findings;
+
+ALTER TABLE findings_by_project RENAME TO findings;
+
+ALTER TABLE finding_transitions ADD COLUMN project TEXT NOT NULL DEFAULT '';
+
+DROP INDEX idx_finding_transitions_root;
+
+CREATE INDEX idx_finding_transitions_root ON finding_transitions (project, root_path, scan_id);
@@ -0,0 +1,6 @@
+CREATE TABLE api_tokens (
+    name TEXT PRIMARY KEY,
+    scope TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at INTEGER NOT NULL
+);
@@ -0,0 +1,5 @@
+CREATE TABLE scan_quarantines (
+    scan_id INTEGER PRIMARY KEY,
+    reason TEXT NOT NULL,
+    FOREIGN KEY(scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
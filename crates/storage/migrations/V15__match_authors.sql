@@ -0,0 +1,2 @@
+ALTER TABLE matches ADD COLUMN author_name TEXT;
+ALTER TABLE matches ADD COLUMN author_email TEXT;
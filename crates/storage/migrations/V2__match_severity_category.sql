@@ -0,0 +1,2 @@
+ALTER TABLE matches ADD COLUMN severity TEXT;
+ALTER TABLE matches ADD COLUMN category TEXT;
@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS idx_matches_scan_id ON matches (scan_id, id);
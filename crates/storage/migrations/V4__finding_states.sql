@@ -0,0 +1,26 @@
+CREATE TABLE findings (
+    root_path TEXT NOT NULL,
+    fingerprint TEXT NOT NULL,
+    state TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    line_number INTEGER NOT NULL,
+    pattern TEXT NOT NULL,
+    message TEXT NOT NULL,
+    first_seen_scan INTEGER NOT NULL,
+    last_seen_scan INTEGER NOT NULL,
+    note TEXT,
+    PRIMARY KEY (root_path, fingerprint)
+);
+
+CREATE TABLE finding_transitions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    root_path TEXT NOT NULL,
+    fingerprint TEXT NOT NULL,
+    scan_id INTEGER,
+    from_state TEXT,
+    to_state TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    note TEXT
+);
+
+CREATE INDEX idx_finding_transitions_root ON finding_transitions (root_path, scan_id);
@@ -0,0 +1,5 @@
+CREATE TABLE scan_inventories (
+    scan_id INTEGER PRIMARY KEY,
+    inventory TEXT NOT NULL,
+    FOREIGN KEY(scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
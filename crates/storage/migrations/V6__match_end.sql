@@ -0,0 +1,2 @@
+ALTER TABLE matches ADD COLUMN end_line INTEGER;
+ALTER TABLE matches ADD COLUMN end_column INTEGER;
@@ -0,0 +1,5 @@
+CREATE TABLE scan_statistics (
+    scan_id INTEGER PRIMARY KEY,
+    statistics TEXT NOT NULL,
+    FOREIGN KEY(scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
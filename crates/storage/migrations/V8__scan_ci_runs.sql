@@ -0,0 +1,5 @@
+CREATE TABLE scan_ci_runs (
+    scan_id INTEGER PRIMARY KEY,
+    ci_run TEXT NOT NULL,
+    FOREIGN KEY(scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
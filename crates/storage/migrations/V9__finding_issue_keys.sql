@@ -0,0 +1 @@
+ALTER TABLE findings ADD COLUMN issue_key TEXT;
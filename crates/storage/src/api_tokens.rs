@@ -0,0 +1,100 @@
+//! Bearer tokens of `code-guardian serve`, stored as SHA-256 hashes. The
+//! token itself is shown once, when it is created.
+
+use code_guardian_core::{constant_time_eq, generate_token, hash_token, TokenScope};
+use code_guardian_core::{ScanError, ScanResult};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// A stored token, without the token itself
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub name: String,
+    pub scope: TokenScope,
+    /// Unix timestamp
+    pub created_at: i64,
+}
+
+/// Stores a new token called `name` and returns it
+pub(crate) fn create(conn: &Connection, name: &str, scope: TokenScope) -> ScanResult<String> {
+    if name.trim().is_empty() {
+        return Err(ScanError::storage("a token needs a name"));
+    }
+    let token = generate_token();
+    let inserted = conn
+        .execute(
+            "INSERT INTO api_tokens (name, scope, token_hash, created_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (name) DO NOTHING",
+            (
+                name,
+                scope.as_str(),
+                hash_token(&token),
+                chrono::Utc::now().timestamp(),
+            ),
+        )
+        .map_err(ScanError::storage)?;
+    if inserted == 0 {
+        return Err(ScanError::storage(format!(
+            "a token called '{}' already exists; revoke it first",
+            name
+        )));
+    }
+    Ok(token)
+}
+
+pub(crate) fn list(conn: &Connection) -> ScanResult<Vec<ApiToken>> {
+    let mut stmt = conn
+        .prepare("SELECT name, scope, created_at FROM api_tokens ORDER BY name")
+        .map_err(ScanError::storage)?;
+    let tokens = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get(2)?,
+            ))
+        })
+        .map_err(ScanError::storage)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(ScanError::storage)?;
+    Ok(tokens
+        .into_iter()
+        .filter_map(|(name, scope, created_at)| {
+            Some(ApiToken {
+                name,
+                scope: scope.parse().ok()?,
+                created_at,
+            })
+        })
+        .collect())
+}
+
+/// Deletes the token called `name`; false if there is none
+pub(crate) fn revoke(conn: &Connection, name: &str) -> ScanResult<bool> {
+    conn.execute("DELETE FROM api_tokens WHERE name = ?1", [name])
+        .map(|deleted| deleted > 0)
+        .map_err(ScanError::storage)
+}
+
+/// Scope of `token`, None if it is not a stored token. Every stored hash is
+/// compared, each in constant time.
+pub(crate) fn verify(conn: &Connection, token: &str) -> ScanResult<Option<TokenScope>> {
+    let hash = hash_token(token);
+    let mut stmt = conn
+        .prepare("SELECT scope, token_hash FROM api_tokens")
+        .map_err(ScanError::storage)?;
+    let stored = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(ScanError::storage)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(ScanError::storage)?;
+    let mut scope = None;
+    for (stored_scope, stored_hash) in &stored {
+        if constant_time_eq(stored_hash.as_bytes(), hash.as_bytes()) {
+            scope = stored_scope.parse().ok();
+        }
+    }
+    Ok(scope)
+}
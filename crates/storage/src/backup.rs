@@ -0,0 +1,99 @@
+//! Backups of the findings database with SQLite's online backup API, which
+//! copies a consistent snapshot page by page while other connections keep
+//! reading and writing, WAL mode included.
+
+use crate::encryption;
+use code_guardian_core::{ScanError, ScanResult};
+use rusqlite::backup::Backup;
+use rusqlite::Connection;
+use std::path::Path;
+use std::time::Duration;
+
+/// Pages copied per backup step; other connections may write between steps
+const PAGES_PER_STEP: std::os::raw::c_int = 256;
+
+const PAUSE_BETWEEN_STEPS: Duration = Duration::from_millis(5);
+
+/// Opens the database at `path`, unlocked with `key` unless it is `None`
+fn open(path: &Path, key: Option<&str>) -> ScanResult<Connection> {
+    let conn = Connection::open(path)
+        .map_err(|e| ScanError::storage(format!("{}: {}", path.display(), e)))?;
+    if let Some(key) = key {
+        encryption::unlock(&conn, path, key)?;
+    }
+    Ok(conn)
+}
+
+fn copy(from: &Connection, to: &mut Connection) -> ScanResult<()> {
+    Backup::new(from, to)
+        .and_then(|backup| backup.run_to_completion(PAGES_PER_STEP, PAUSE_BETWEEN_STEPS, None))
+        .map_err(ScanError::storage)
+}
+
+/// Problems `PRAGMA integrity_check` finds in `conn`, empty when it is intact
+pub(crate) fn integrity_problems(conn: &Connection) -> ScanResult<Vec<String>> {
+    let mut stmt = conn
+        .prepare("PRAGMA integrity_check")
+        .map_err(ScanError::storage)?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(ScanError::storage)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(ScanError::storage)?;
+    Ok(rows.into_iter().filter(|row| row != "ok").collect())
+}
+
+/// Copies the database at `db` to a new file at `dest`, encrypted with the
+/// same `key`, and checks the integrity of the copy
+pub fn backup_database(db: &Path, dest: &Path, key: Option<&str>) -> ScanResult<()> {
+    if !db.exists() {
+        return Err(ScanError::storage(format!(
+            "{}: no database to back up",
+            db.display()
+        )));
+    }
+    if dest.exists() {
+        return Err(ScanError::storage(format!(
+            "{}: already exists; choose a new backup path",
+            dest.display()
+        )));
+    }
+    let source = open(db, key)?;
+    let mut backup = open(dest, key)?;
+    copy(&source, &mut backup)?;
+    let problems = integrity_problems(&backup)?;
+    if !problems.is_empty() {
+        return Err(ScanError::storage(format!(
+            "{}: the backup failed its integrity check: {}",
+            dest.display(),
+            problems.join("; ")
+        )));
+    }
+    Ok(())
+}
+
+/// Replaces the contents of the database at `db` with the backup at
+/// `backup`, after checking the integrity of the backup. Connections open on
+/// `db` see the restored contents on their next transaction.
+pub fn restore_database(backup: &Path, db: &Path, key: Option<&str>) -> ScanResult<()> {
+    if !backup.exists() {
+        return Err(ScanError::storage(format!(
+            "{}: no such backup",
+            backup.display()
+        )));
+    }
+    let source = open(backup, key)?;
+    let problems = integrity_problems(&source)?;
+    if !problems.is_empty() {
+        return Err(ScanError::storage(format!(
+            "{}: the backup failed its integrity check: {}",
+            backup.display(),
+            problems.join("; ")
+        )));
+    }
+    if let Some(parent) = db.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(ScanError::storage)?;
+    }
+    let mut target = open(db, key)?;
+    copy(&source, &mut target)
+}
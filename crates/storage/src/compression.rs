@@ -0,0 +1,107 @@
+//! Compression of the match messages of large scans. Messages of one scan
+//! repeat the same detector texts, so a zstd dictionary is trained on them
+//! and stored with the scan, and each message is compressed with it. Small
+//! scans are stored as plain text.
+
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use zstd::dict::DecoderDictionary;
+
+/// Matches a scan needs before its messages are compressed
+pub const COMPRESS_MIN_MATCHES: usize = 1000;
+
+/// Smallest and largest dictionary trained for a scan; in between it is a
+/// sixteenth of the size of the messages
+const MIN_DICTIONARY_BYTES: usize = 1024;
+const MAX_DICTIONARY_BYTES: usize = 16 * 1024;
+
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Compresses the messages of one scan with a dictionary trained on them
+pub(crate) struct MessageCompressor {
+    dictionary: Vec<u8>,
+    compressor: zstd::bulk::Compressor<'static>,
+}
+
+impl MessageCompressor {
+    /// Trains a dictionary on `messages`; None when there is too little to
+    /// train on
+    pub(crate) fn train(messages: &[&str]) -> Option<Self> {
+        let bytes: usize = messages.iter().map(|message| message.len()).sum();
+        let dictionary_bytes = (bytes / 16).clamp(MIN_DICTIONARY_BYTES, MAX_DICTIONARY_BYTES);
+        let dictionary = zstd::dict::from_samples(messages, dictionary_bytes).ok()?;
+        let mut compressor =
+            zstd::bulk::Compressor::with_dictionary(COMPRESSION_LEVEL, &dictionary).ok()?;
+        // Messages are a few dozen bytes; the frame fields the dictionary
+        // and stream decoder don't need would eat much of the saving
+        compressor.include_dictid(false).ok()?;
+        compressor.include_contentsize(false).ok()?;
+        Some(Self {
+            dictionary,
+            compressor,
+        })
+    }
+
+    pub(crate) fn dictionary(&self) -> &[u8] {
+        &self.dictionary
+    }
+
+    /// `message` compressed, unless that doesn't make it smaller
+    pub(crate) fn compress(&mut self, message: &str) -> Option<Vec<u8>> {
+        self.compressor
+            .compress(message.as_bytes())
+            .ok()
+            .filter(|compressed| compressed.len() < message.len())
+    }
+}
+
+/// Decompresses the messages of one scan
+pub(crate) struct MessageDecompressor {
+    dictionary: DecoderDictionary<'static>,
+}
+
+impl MessageDecompressor {
+    pub(crate) fn new(dictionary: &[u8]) -> Self {
+        Self {
+            dictionary: DecoderDictionary::copy(dictionary),
+        }
+    }
+
+    pub(crate) fn decompress(&self, compressed: &[u8]) -> std::io::Result<String> {
+        let mut message = String::new();
+        zstd::stream::read::Decoder::with_prepared_dictionary(compressed, &self.dictionary)?
+            .read_to_string(&mut message)?;
+        Ok(message)
+    }
+}
+
+/// Space taken by the stored matches, see
+/// [`crate::SqliteScanRepository::storage_stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageStats {
+    pub scans: usize,
+    pub matches: usize,
+    /// Scans whose messages are compressed
+    pub compressed_scans: usize,
+    /// Size of all match messages as text
+    pub message_bytes: u64,
+    /// Size the messages take as stored, dictionaries included
+    pub stored_message_bytes: u64,
+    /// Size of the database file
+    pub database_bytes: u64,
+}
+
+impl StorageStats {
+    /// Bytes compression saves
+    pub fn saved_bytes(&self) -> u64 {
+        self.message_bytes.saturating_sub(self.stored_message_bytes)
+    }
+
+    /// How many times smaller the stored messages are than as text
+    pub fn compression_ratio(&self) -> f64 {
+        if self.stored_message_bytes == 0 {
+            return 1.0;
+        }
+        self.message_bytes as f64 / self.stored_message_bytes as f64
+    }
+}
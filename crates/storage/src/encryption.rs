@@ -0,0 +1,62 @@
+//! At-rest encryption of the findings database with SQLCipher. The key comes
+//! from [`DB_KEY_ENV`] or, in builds with the `keychain` feature, from the OS
+//! keychain; without a key the database is plain SQLite.
+
+use code_guardian_core::{ScanError, ScanResult};
+use rusqlite::{Connection, OptionalExtension};
+use std::path::Path;
+
+/// Environment variable holding the database key
+pub const DB_KEY_ENV: &str = "CODE_GUARDIAN_DB_KEY";
+
+/// Keychain service and account the `keychain` feature reads the key from
+pub const KEYCHAIN_SERVICE: &str = "code-guardian";
+pub const KEYCHAIN_ACCOUNT: &str = "database";
+
+/// The key to open databases with, if one is configured
+pub fn database_key() -> ScanResult<Option<String>> {
+    if let Some(key) = std::env::var(DB_KEY_ENV).ok().filter(|key| !key.is_empty()) {
+        return Ok(Some(key));
+    }
+    keychain_key()
+}
+
+#[cfg(feature = "keychain")]
+fn keychain_key() -> ScanResult<Option<String>> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)
+        .map_err(|e| ScanError::storage(format!("keychain: {}", e)))?;
+    match entry.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(ScanError::storage(format!("keychain: {}", e))),
+    }
+}
+
+#[cfg(not(feature = "keychain"))]
+fn keychain_key() -> ScanResult<Option<String>> {
+    Ok(None)
+}
+
+/// Unlocks the freshly opened `conn` with `key`. Fails if the build has no
+/// SQLCipher, which would otherwise ignore the key and write plaintext, or if
+/// the key does not decrypt the database at `path`.
+pub(crate) fn unlock(conn: &Connection, path: &Path, key: &str) -> ScanResult<()> {
+    conn.pragma_update(None, "key", key)
+        .map_err(ScanError::storage)?;
+    let cipher_version: Option<String> = conn
+        .query_row("PRAGMA cipher_version", [], |row| row.get(0))
+        .optional()
+        .map_err(ScanError::storage)?;
+    if cipher_version.is_none() {
+        return Err(ScanError::storage(
+            "a database key is set but this build cannot encrypt databases; rebuild with --features encryption",
+        ));
+    }
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+        .map_err(|_| {
+            ScanError::storage(format!(
+                "{}: cannot decrypt the database; the key is wrong or the file is not encrypted",
+                path.display()
+            ))
+        })
+}
@@ -1,11 +1,67 @@
-use anyhow::Result;
-use code_guardian_core::Match;
-use rusqlite::{Connection, OptionalExtension};
+use code_guardian_core::{
+    finding_fingerprints, Author, CiRun, DetectorYield, FindingState, Inventory, LineColumn, Match,
+    ScanError, ScanResult, ScanStatistics, SharedStr, TokenScope, SAMPLED_PATTERN,
+    TRUNCATED_PATTERN,
+};
+use rusqlite::{Connection, OptionalExtension, Row};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
+pub mod api_tokens;
+pub mod backup;
+pub mod compression;
+pub mod encryption;
+pub mod schema_version;
+
+pub use api_tokens::ApiToken;
+pub use backup::{backup_database, restore_database};
+use compression::{MessageCompressor, MessageDecompressor};
+pub use compression::{StorageStats, COMPRESS_MIN_MATCHES};
+pub use encryption::{database_key, DB_KEY_ENV};
+pub use schema_version::{expected_schema_version, MigrationStatus, SchemaStatus};
+
 refinery::embed_migrations!("migrations");
 
+/// Version of the SQLite library databases are stored with.
+pub fn sqlite_version() -> &'static str {
+    rusqlite::version()
+}
+
+/// Longest project name [`validate_project`] accepts.
+pub const MAX_PROJECT_LEN: usize = 64;
+
+/// Checks that `project` is a slug: lowercase letters, digits, `-`, `_` and
+/// `.`, starting with a letter or digit, at most [`MAX_PROJECT_LEN`] long.
+pub fn validate_project(project: &str) -> Result<(), String> {
+    let valid = project.len() <= MAX_PROJECT_LEN
+        && project
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && project
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid project name '{}': use up to {} lowercase letters, digits, '-', '_' and '.', starting with a letter or digit",
+            project, MAX_PROJECT_LEN
+        ))
+    }
+}
+
+/// Project column value of a scan, empty for scans without a project
+fn project_key(project: Option<&str>) -> &str {
+    project.unwrap_or("")
+}
+
+/// A project column read back, None for the empty value
+fn project_column(row: &Row, index: usize) -> rusqlite::Result<Option<String>> {
+    Ok(Some(row.get::<_, String>(index)?).filter(|project| !project.is_empty()))
+}
+
 /// Represents a scan session with its metadata and results.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Scan {
@@ -15,96 +71,540 @@ pub struct Scan {
     pub timestamp: i64,
     /// Root path of the scanned directory.
     pub root_path: String,
+    /// Project the scan belongs to, so one database can hold the scans of
+    /// many repositories. None for scans saved without a project.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
     /// List of matches found during the scan.
     pub matches: Vec<Match>,
 }
 
+/// Narrows the matches returned by [`ScanRepository::get_matches`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatchFilter {
+    /// Only matches of this pattern, compared case-insensitively.
+    pub pattern: Option<String>,
+    /// Only matches whose file path contains this text.
+    pub file: Option<String>,
+}
+
+impl MatchFilter {
+    pub fn is_empty(&self) -> bool {
+        self.pattern.is_none() && self.file.is_none()
+    }
+}
+
+/// One page of a scan's matches, in the order they were saved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchPage {
+    pub matches: Vec<Match>,
+    /// Number of matches meeting the filter across all pages.
+    pub total: usize,
+    /// Position of the first match of this page among them.
+    pub offset: usize,
+}
+
+impl MatchPage {
+    /// Whether more matches follow this page.
+    pub fn has_more(&self) -> bool {
+        self.offset + self.matches.len() < self.total
+    }
+}
+
+/// A finding tracked across the scans of one root and project, see [`FindingState`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackedFinding {
+    pub fingerprint: String,
+    pub root_path: String,
+    /// Project of the scans that found it, see [`Scan::project`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    pub state: FindingState,
+    /// Where the finding was last seen.
+    pub file_path: String,
+    pub line_number: usize,
+    pub pattern: String,
+    pub message: String,
+    pub first_seen_scan: i64,
+    pub last_seen_scan: i64,
+    /// Reason given when the state was last set by hand.
+    pub note: Option<String>,
+    /// Key of the issue tracking the finding, e.g. the Jira issue `SEC-12`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_key: Option<String>,
+}
+
+/// A change in the state of a tracked finding.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateTransition {
+    pub fingerprint: String,
+    pub pattern: String,
+    pub file_path: String,
+    /// Scan that caused the change; None when the state was set by hand.
+    pub scan_id: Option<i64>,
+    /// None when the finding first appeared.
+    pub from: Option<FindingState>,
+    pub to: FindingState,
+    pub timestamp: i64,
+    pub note: Option<String>,
+}
+
 /// Repository trait for scan data access.
 pub trait ScanRepository {
     /// Saves a new scan and returns its ID.
-    fn save_scan(&mut self, scan: &Scan) -> Result<i64>;
+    fn save_scan(&mut self, scan: &Scan) -> ScanResult<i64>;
     /// Retrieves a scan by ID, including its matches.
-    fn get_scan(&self, id: i64) -> Result<Option<Scan>>;
+    fn get_scan(&self, id: i64) -> ScanResult<Option<Scan>>;
+    /// Retrieves up to `limit` matches of a scan meeting `filter`, skipping
+    /// the first `offset`, without loading the rest. None if the scan doesn't exist.
+    fn get_matches(
+        &self,
+        scan_id: i64,
+        offset: usize,
+        limit: usize,
+        filter: &MatchFilter,
+    ) -> ScanResult<Option<MatchPage>>;
     /// Retrieves all scans, without matches for performance.
-    fn get_all_scans(&self) -> Result<Vec<Scan>>;
+    fn get_all_scans(&self) -> ScanResult<Vec<Scan>>;
+    /// Retrieves the scans of `project`, newest first, without matches.
+    fn get_project_scans(&self, project: &str) -> ScanResult<Vec<Scan>>;
     /// Deletes a scan and its matches.
-    fn delete_scan(&mut self, id: i64) -> Result<()>;
+    fn delete_scan(&mut self, id: i64) -> ScanResult<()>;
+    /// Retrieves the findings tracked for `root_path`, or for every root,
+    /// of every project, optionally only those in `state`.
+    fn get_findings(
+        &self,
+        root_path: Option<&str>,
+        state: Option<FindingState>,
+    ) -> ScanResult<Vec<TrackedFinding>>;
+    /// Sets the state of the findings with `fingerprint`, recording the
+    /// transition, and returns how many were found.
+    fn set_finding_state(
+        &mut self,
+        fingerprint: &str,
+        state: FindingState,
+        note: Option<&str>,
+    ) -> ScanResult<usize>;
+    /// Links the finding with `fingerprint` under `root_path` of `project`
+    /// to the issue `issue_key`, replacing any earlier link.
+    fn set_issue_key(
+        &mut self,
+        project: Option<&str>,
+        root_path: &str,
+        fingerprint: &str,
+        issue_key: &str,
+    ) -> ScanResult<()>;
+    /// Retrieves the state changes in the root and project of `later` since `earlier`,
+    /// up to and including `later`.
+    fn get_transitions(&self, earlier: &Scan, later: &Scan) -> ScanResult<Vec<StateTransition>>;
+    /// Stores the inventory of the files a scan covered, replacing any earlier one.
+    fn save_inventory(&mut self, scan_id: i64, inventory: &Inventory) -> ScanResult<()>;
+    /// Retrieves the inventory stored with a scan, if any.
+    fn get_inventory(&self, scan_id: i64) -> ScanResult<Option<Inventory>>;
+    /// Stores the summary statistics of a scan, replacing any earlier ones.
+    fn save_statistics(&mut self, scan_id: i64, statistics: &ScanStatistics) -> ScanResult<()>;
+    /// Retrieves the summary statistics stored with a scan, if any.
+    fn get_statistics(&self, scan_id: i64) -> ScanResult<Option<ScanStatistics>>;
+    /// Records the CI pipeline run that produced a scan, replacing any earlier one.
+    fn save_ci_run(&mut self, scan_id: i64, ci_run: &CiRun) -> ScanResult<()>;
+    /// Retrieves the CI pipeline run recorded for a scan, if any.
+    fn get_ci_run(&self, scan_id: i64) -> ScanResult<Option<CiRun>>;
+    /// Marks a scan as quarantined for `reason`, replacing any earlier reason.
+    fn quarantine_scan(&mut self, scan_id: i64, reason: &str) -> ScanResult<()>;
+    /// Retrieves why a scan is quarantined, if it is.
+    fn get_quarantine(&self, scan_id: i64) -> ScanResult<Option<String>>;
+    /// Retrieves the yield of every detector per file extension.
+    fn get_detector_yields(&self) -> ScanResult<Vec<DetectorYield>>;
+    /// Stores detector yields, replacing those of the same detector and extension.
+    fn save_detector_yields(&mut self, yields: &[DetectorYield]) -> ScanResult<()>;
 }
 
 /// SQLite implementation of the scan repository.
 pub struct SqliteScanRepository {
     conn: Connection,
+    compress_min_matches: usize,
 }
 
 impl SqliteScanRepository {
     /// Creates a new repository with an in-memory database for testing.
-    pub fn new_in_memory() -> Result<Self> {
-        let mut conn = Connection::open_in_memory()?;
-        Self::init_db(&mut conn)?;
-        Ok(Self { conn })
+    pub fn new_in_memory() -> ScanResult<Self> {
+        let mut conn = Connection::open_in_memory().map_err(ScanError::storage)?;
+        Self::init_db(&mut conn, Path::new(":memory:"))?;
+        Ok(Self {
+            conn,
+            compress_min_matches: COMPRESS_MIN_MATCHES,
+        })
+    }
+
+    /// Creates a new repository with a file-based database, encrypted with
+    /// the key of [`database_key`] if one is configured.
+    pub fn new<P: AsRef<Path>>(path: P) -> ScanResult<Self> {
+        Self::with_key(path, database_key()?.as_deref())
+    }
+
+    /// Creates a new repository with a file-based database, encrypted with
+    /// `key` unless it is `None`.
+    pub fn with_key<P: AsRef<Path>>(path: P, key: Option<&str>) -> ScanResult<Self> {
+        let path = path.as_ref();
+        let mut conn = Connection::open(path)
+            .map_err(|e| ScanError::storage(format!("{}: {}", path.display(), e)))?;
+        if let Some(key) = key {
+            encryption::unlock(&conn, path, key)?;
+        }
+        Self::init_db(&mut conn, path)?;
+        Ok(Self {
+            conn,
+            compress_min_matches: COMPRESS_MIN_MATCHES,
+        })
+    }
+
+    /// Reads the migrations of the database at `path`, encrypted with `key`
+    /// unless it is `None`, without applying pending ones.
+    pub fn schema_status<P: AsRef<Path>>(path: P, key: Option<&str>) -> ScanResult<SchemaStatus> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(ScanError::storage(format!(
+                "{}: no such database",
+                path.display()
+            )));
+        }
+        let mut conn = Connection::open(path)
+            .map_err(|e| ScanError::storage(format!("{}: {}", path.display(), e)))?;
+        if let Some(key) = key {
+            encryption::unlock(&conn, path, key)?;
+        }
+        schema_version::read_schema_status(&mut conn)
+    }
+
+    /// Compresses the messages of scans with at least `min_matches` matches
+    /// instead of [`COMPRESS_MIN_MATCHES`].
+    pub fn with_compression_threshold(mut self, min_matches: usize) -> Self {
+        self.compress_min_matches = min_matches;
+        self
+    }
+
+    /// Reports how much space the stored scans take and compression saves.
+    pub fn storage_stats(&self) -> ScanResult<StorageStats> {
+        let count = |sql: &str| -> ScanResult<u64> {
+            self.conn
+                .query_row(sql, [], |row| row.get::<_, i64>(0))
+                .map(|value| value as u64)
+                .map_err(ScanError::storage)
+        };
+        let plain_bytes = count(
+            "SELECT COALESCE(SUM(length(CAST(message AS BLOB))), 0) FROM matches
+             WHERE scan_id NOT IN (SELECT scan_id FROM scan_compression)",
+        )?;
+        Ok(StorageStats {
+            scans: count("SELECT COUNT(*) FROM scans")? as usize,
+            matches: count("SELECT COUNT(*) FROM matches")? as usize,
+            compressed_scans: count("SELECT COUNT(*) FROM scan_compression")? as usize,
+            message_bytes: plain_bytes
+                + count("SELECT COALESCE(SUM(raw_bytes), 0) FROM scan_compression")?,
+            stored_message_bytes: plain_bytes
+                + count(
+                    "SELECT COALESCE(SUM(stored_bytes + length(dictionary)), 0) FROM scan_compression",
+                )?,
+            database_bytes: count("PRAGMA page_count")? * count("PRAGMA page_size")?,
+        })
+    }
+
+    /// Problems `PRAGMA integrity_check` finds in the database, empty when
+    /// it is intact.
+    pub fn integrity_check(&self) -> ScanResult<Vec<String>> {
+        backup::integrity_problems(&self.conn)
+    }
+
+    /// Creates a bearer token called `name` for `code-guardian serve` and
+    /// returns it; only its hash is stored.
+    pub fn create_api_token(&self, name: &str, scope: TokenScope) -> ScanResult<String> {
+        api_tokens::create(&self.conn, name, scope)
     }
 
-    /// Creates a new repository with a file-based database.
-    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let mut conn = Connection::open(path)?;
-        Self::init_db(&mut conn)?;
-        Ok(Self { conn })
+    /// Lists the stored tokens by name.
+    pub fn api_tokens(&self) -> ScanResult<Vec<ApiToken>> {
+        api_tokens::list(&self.conn)
     }
 
-    /// Initializes the database schema using migrations.
-    fn init_db(conn: &mut Connection) -> Result<()> {
-        migrations::runner().run(conn)?;
+    /// Deletes the token called `name`, returning whether there was one.
+    pub fn revoke_api_token(&self, name: &str) -> ScanResult<bool> {
+        api_tokens::revoke(&self.conn, name)
+    }
+
+    /// Scope of `token`, None if it is not a stored token.
+    pub fn verify_api_token(&self, token: &str) -> ScanResult<Option<TokenScope>> {
+        api_tokens::verify(&self.conn, token)
+    }
+
+    /// The decompressor of a scan's messages, if they are compressed
+    fn message_decompressor(&self, scan_id: i64) -> ScanResult<Option<MessageDecompressor>> {
+        let dictionary: Option<Vec<u8>> = self
+            .conn
+            .query_row(
+                "SELECT dictionary FROM scan_compression WHERE scan_id = ?1",
+                [scan_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(ScanError::storage)?;
+        Ok(dictionary.map(|dictionary| MessageDecompressor::new(&dictionary)))
+    }
+
+    /// Initializes the database schema using migrations, refusing databases
+    /// migrated by a newer version.
+    fn init_db(conn: &mut Connection, path: &Path) -> ScanResult<()> {
+        schema_version::ensure_supported(conn, path)?;
+        migrations::runner().run(conn).map_err(ScanError::storage)?;
         Ok(())
     }
+
+    fn scan_exists(&self, id: i64) -> ScanResult<bool> {
+        self.conn
+            .query_row("SELECT 1 FROM scans WHERE id = ?1", [id], |_| Ok(()))
+            .optional()
+            .map(|row| row.is_some())
+            .map_err(ScanError::storage)
+    }
+}
+
+/// Columns read by [`match_from_row`]
+const MATCH_COLUMNS: &str =
+    "file_path, line_number, column, pattern, message, severity, category, end_line, end_column, message_zstd, author_name, author_email";
+
+/// Filter on `matches` for [`ScanRepository::get_matches`], taking the scan
+/// ID, pattern and file text as parameters 1 to 3
+const MATCH_FILTER: &str = "scan_id = ?1 AND (?2 IS NULL OR pattern = ?2 COLLATE NOCASE) AND (?3 IS NULL OR instr(file_path, ?3) > 0)";
+
+/// Opens findings that appear for the first time, or again after they were
+/// fixed, and marks the findings of the root and project that `scan` no
+/// longer has as fixed.
+fn track_findings(conn: &Connection, scan_id: i64, scan: &Scan) -> rusqlite::Result<()> {
+    let project = project_key(scan.project.as_deref());
+    let mut known: HashMap<String, FindingState> = HashMap::new();
+    {
+        let mut stmt = conn.prepare(
+            "SELECT fingerprint, state FROM findings WHERE project = ?1 AND root_path = ?2",
+        )?;
+        let rows = stmt.query_map([project, &scan.root_path], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (fingerprint, state) = row?;
+            if let Ok(state) = state.parse() {
+                known.insert(fingerprint, state);
+            }
+        }
+    }
+    let record = |fingerprint: &str, from: Option<FindingState>, to: FindingState| {
+        conn.execute(
+            "INSERT INTO finding_transitions (project, root_path, fingerprint, scan_id, from_state, to_state, timestamp) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            (
+                project,
+                &scan.root_path,
+                fingerprint,
+                scan_id,
+                from.map(|state| state.as_str()),
+                to.as_str(),
+                scan.timestamp,
+            ),
+        )
+    };
+
+    // Limit markers summarize other findings and change with their counts
+    let matches: Vec<Match> = scan
+        .matches
+        .iter()
+        .filter(|m| m.pattern != TRUNCATED_PATTERN && m.pattern != SAMPLED_PATTERN)
+        .cloned()
+        .collect();
+    let fingerprints = finding_fingerprints(&matches, &scan.root_path);
+    for (m, fingerprint) in matches.iter().zip(&fingerprints) {
+        let state = match known.remove(fingerprint) {
+            Some(FindingState::Fixed) => {
+                record(fingerprint, Some(FindingState::Fixed), FindingState::Open)?;
+                FindingState::Open
+            }
+            Some(state) => state,
+            None => {
+                record(fingerprint, None, FindingState::Open)?;
+                FindingState::Open
+            }
+        };
+        conn.execute(
+            "INSERT INTO findings (root_path, fingerprint, state, file_path, line_number, pattern, message, first_seen_scan, last_seen_scan, project) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8, ?9)
+             ON CONFLICT (project, root_path, fingerprint) DO UPDATE SET state = ?3, file_path = ?4, line_number = ?5, message = ?7, last_seen_scan = ?8",
+            (
+                &scan.root_path,
+                fingerprint,
+                state.as_str(),
+                m.file_path.as_str(),
+                m.line_number as i64,
+                m.pattern.as_str(),
+                &m.message,
+                scan_id,
+                project,
+            ),
+        )?;
+    }
+
+    let mut gone: Vec<(String, FindingState)> = known
+        .into_iter()
+        .filter(|(_, state)| *state != FindingState::Fixed)
+        .collect();
+    gone.sort_by(|a, b| a.0.cmp(&b.0));
+    for (fingerprint, state) in gone {
+        conn.execute(
+            "UPDATE findings SET state = ?3 WHERE root_path = ?1 AND fingerprint = ?2 AND project = ?4",
+            (
+                &scan.root_path,
+                &fingerprint,
+                FindingState::Fixed.as_str(),
+                project,
+            ),
+        )?;
+        record(&fingerprint, Some(state), FindingState::Fixed)?;
+    }
+    Ok(())
+}
+
+/// Reads the id, timestamp, root path and project of a scan, without its
+/// matches, which are not loaded for performance
+fn scan_from_row(row: &Row) -> rusqlite::Result<Scan> {
+    Ok(Scan {
+        id: Some(row.get(0)?),
+        timestamp: row.get(1)?,
+        root_path: row.get(2)?,
+        project: project_column(row, 3)?,
+        matches: Vec::new(),
+    })
+}
+
+/// Parses a state column, treating unknown values as open
+fn state_column(row: &Row, index: usize) -> rusqlite::Result<FindingState> {
+    Ok(row
+        .get::<_, String>(index)?
+        .parse()
+        .unwrap_or(FindingState::Open))
+}
+
+/// Reads a match, decompressing its message with `decompressor` if it was
+/// stored compressed
+fn match_from_row(
+    row: &Row,
+    decompressor: Option<&MessageDecompressor>,
+) -> rusqlite::Result<Match> {
+    let message = match (row.get::<_, Option<Vec<u8>>>(9)?, decompressor) {
+        (Some(compressed), Some(decompressor)) => {
+            decompressor.decompress(&compressed).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(9, rusqlite::types::Type::Blob, e.into())
+            })?
+        }
+        _ => row.get(4)?,
+    };
+    Ok(Match {
+        file_path: row.get::<_, String>(0)?.into(),
+        line_number: row.get(1)?,
+        column: row.get(2)?,
+        pattern: SharedStr::interned(&row.get::<_, String>(3)?),
+        message,
+        severity: row
+            .get::<_, Option<String>>(5)?
+            .and_then(|severity| serde_json::from_value(serde_json::Value::String(severity)).ok()),
+        category: row.get(6)?,
+        end: match (row.get(7)?, row.get(8)?) {
+            (Some(line), Some(column)) => Some(LineColumn { line, column }),
+            _ => None,
+        },
+        author: match (row.get(10)?, row.get(11)?) {
+            (Some(name), Some(email)) => Some(Author { name, email }),
+            _ => None,
+        },
+    })
 }
 
 impl ScanRepository for SqliteScanRepository {
-    fn save_scan(&mut self, scan: &Scan) -> Result<i64> {
-        let tx = self.conn.transaction()?;
+    fn save_scan(&mut self, scan: &Scan) -> ScanResult<i64> {
+        if let Some(project) = &scan.project {
+            validate_project(project).map_err(ScanError::storage)?;
+        }
+        let tx = self.conn.transaction().map_err(ScanError::storage)?;
         tx.execute(
-            "INSERT INTO scans (timestamp, root_path) VALUES (?1, ?2)",
-            (scan.timestamp, &scan.root_path),
-        )?;
+            "INSERT INTO scans (timestamp, root_path, project) VALUES (?1, ?2, ?3)",
+            (
+                scan.timestamp,
+                &scan.root_path,
+                project_key(scan.project.as_deref()),
+            ),
+        )
+        .map_err(ScanError::storage)?;
         let scan_id = tx.last_insert_rowid();
+        let mut compressor = if scan.matches.len() >= self.compress_min_matches {
+            let messages: Vec<&str> = scan.matches.iter().map(|m| m.message.as_str()).collect();
+            MessageCompressor::train(&messages)
+        } else {
+            None
+        };
+        let (mut raw_bytes, mut stored_bytes) = (0, 0);
         for m in &scan.matches {
+            let compressed = compressor
+                .as_mut()
+                .and_then(|compressor| compressor.compress(&m.message));
+            raw_bytes += m.message.len();
+            stored_bytes += compressed.as_ref().map_or(m.message.len(), Vec::len);
             tx.execute(
-                "INSERT INTO matches (scan_id, file_path, line_number, column, pattern, message) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-                (scan_id, &m.file_path, m.line_number as i64, m.column as i64, &m.pattern, &m.message),
-            )?;
+                "INSERT INTO matches (scan_id, file_path, line_number, column, pattern, message, severity, category, end_line, end_column, message_zstd, author_name, author_email) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                (
+                    scan_id,
+                    m.file_path.as_str(),
+                    m.line_number as i64,
+                    m.column as i64,
+                    m.pattern.as_str(),
+                    if compressed.is_some() { "" } else { &m.message },
+                    m.severity.as_ref().map(|severity| format!("{:?}", severity)),
+                    &m.category,
+                    m.end.map(|end| end.line as i64),
+                    m.end.map(|end| end.column as i64),
+                    compressed,
+                    m.author.as_ref().map(|author| author.name.as_str()),
+                    m.author.as_ref().map(|author| author.email.as_str()),
+                ),
+            ).map_err(ScanError::storage)?;
         }
-        tx.commit()?;
+        if let Some(compressor) = &compressor {
+            tx.execute(
+                "INSERT INTO scan_compression (scan_id, dictionary, raw_bytes, stored_bytes) VALUES (?1, ?2, ?3, ?4)",
+                (scan_id, compressor.dictionary(), raw_bytes as i64, stored_bytes as i64),
+            )
+            .map_err(ScanError::storage)?;
+        }
+        track_findings(&tx, scan_id, scan).map_err(ScanError::storage)?;
+        tx.commit().map_err(ScanError::storage)?;
         Ok(scan_id)
     }
 
-    fn get_scan(&self, id: i64) -> Result<Option<Scan>> {
+    fn get_scan(&self, id: i64) -> ScanResult<Option<Scan>> {
         let mut stmt = self
             .conn
-            .prepare("SELECT id, timestamp, root_path FROM scans WHERE id = ?1")?;
+            .prepare("SELECT id, timestamp, root_path, project FROM scans WHERE id = ?1")
+            .map_err(ScanError::storage)?;
         let scan_opt = stmt
-            .query_row([id], |row| {
-                Ok(Scan {
-                    id: Some(row.get(0)?),
-                    timestamp: row.get(1)?,
-                    root_path: row.get(2)?,
-                    matches: Vec::new(),
-                })
-            })
-            .optional()?;
+            .query_row([id], scan_from_row)
+            .optional()
+            .map_err(ScanError::storage)?;
         if let Some(mut scan) = scan_opt {
-            let mut stmt = self.conn.prepare(
-                "SELECT file_path, line_number, column, pattern, message FROM matches WHERE scan_id = ?1",
-            )?;
-            let matches_iter = stmt.query_map([id], |row| {
-                Ok(Match {
-                    file_path: row.get(0)?,
-                    line_number: row.get(1)?,
-                    column: row.get(2)?,
-                    pattern: row.get(3)?,
-                    message: row.get(4)?,
-                })
-            })?;
+            let decompressor = self.message_decompressor(id)?;
+            let mut stmt = self
+                .conn
+                .prepare(&format!(
+                    "SELECT {} FROM matches WHERE scan_id = ?1 ORDER BY id",
+                    MATCH_COLUMNS
+                ))
+                .map_err(ScanError::storage)?;
+            let matches_iter = stmt
+                .query_map([id], |row| match_from_row(row, decompressor.as_ref()))
+                .map_err(ScanError::storage)?;
             for m in matches_iter {
-                scan.matches.push(m?);
+                scan.matches.push(m.map_err(ScanError::storage)?);
             }
             Ok(Some(scan))
         } else {
@@ -112,30 +612,376 @@ impl ScanRepository for SqliteScanRepository {
         }
     }
 
-    fn get_all_scans(&self) -> Result<Vec<Scan>> {
+    fn get_matches(
+        &self,
+        scan_id: i64,
+        offset: usize,
+        limit: usize,
+        filter: &MatchFilter,
+    ) -> ScanResult<Option<MatchPage>> {
+        if !self.scan_exists(scan_id)? {
+            return Ok(None);
+        }
+        let decompressor = self.message_decompressor(scan_id)?;
+        let total: i64 = self
+            .conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM matches WHERE {}", MATCH_FILTER),
+                (scan_id, &filter.pattern, &filter.file),
+                |row| row.get(0),
+            )
+            .map_err(ScanError::storage)?;
         let mut stmt = self
             .conn
-            .prepare("SELECT id, timestamp, root_path FROM scans ORDER BY timestamp DESC")?;
-        let scans_iter = stmt.query_map([], |row| {
-            Ok(Scan {
-                id: Some(row.get(0)?),
-                timestamp: row.get(1)?,
-                root_path: row.get(2)?,
-                matches: Vec::new(), // Not loaded for performance
-            })
-        })?;
+            .prepare(&format!(
+                "SELECT {} FROM matches WHERE {} ORDER BY id LIMIT ?4 OFFSET ?5",
+                MATCH_COLUMNS, MATCH_FILTER
+            ))
+            .map_err(ScanError::storage)?;
+        let matches = stmt
+            .query_map(
+                (
+                    scan_id,
+                    &filter.pattern,
+                    &filter.file,
+                    limit.min(i64::MAX as usize) as i64,
+                    offset.min(i64::MAX as usize) as i64,
+                ),
+                |row| match_from_row(row, decompressor.as_ref()),
+            )
+            .map_err(ScanError::storage)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(ScanError::storage)?;
+        Ok(Some(MatchPage {
+            matches,
+            total: total as usize,
+            offset,
+        }))
+    }
+
+    fn get_all_scans(&self) -> ScanResult<Vec<Scan>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, timestamp, root_path, project FROM scans ORDER BY timestamp DESC")
+            .map_err(ScanError::storage)?;
+        let scans_iter = stmt
+            .query_map([], scan_from_row)
+            .map_err(ScanError::storage)?;
         let mut scans = Vec::new();
         for scan in scans_iter {
-            scans.push(scan?);
+            scans.push(scan.map_err(ScanError::storage)?);
         }
         Ok(scans)
     }
 
-    fn delete_scan(&mut self, id: i64) -> Result<()> {
-        let tx = self.conn.transaction()?;
-        tx.execute("DELETE FROM matches WHERE scan_id = ?1", [id])?;
-        tx.execute("DELETE FROM scans WHERE id = ?1", [id])?;
-        tx.commit()?;
+    fn get_project_scans(&self, project: &str) -> ScanResult<Vec<Scan>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT id, timestamp, root_path, project FROM scans WHERE project = ?1 ORDER BY timestamp DESC",
+            )
+            .map_err(ScanError::storage)?;
+        let scans = stmt
+            .query_map([project], scan_from_row)
+            .map_err(ScanError::storage)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(ScanError::storage)?;
+        Ok(scans)
+    }
+
+    fn delete_scan(&mut self, id: i64) -> ScanResult<()> {
+        let tx = self.conn.transaction().map_err(ScanError::storage)?;
+        tx.execute("DELETE FROM matches WHERE scan_id = ?1", [id])
+            .map_err(ScanError::storage)?;
+        tx.execute("DELETE FROM scan_inventories WHERE scan_id = ?1", [id])
+            .map_err(ScanError::storage)?;
+        tx.execute("DELETE FROM scan_statistics WHERE scan_id = ?1", [id])
+            .map_err(ScanError::storage)?;
+        tx.execute("DELETE FROM scan_ci_runs WHERE scan_id = ?1", [id])
+            .map_err(ScanError::storage)?;
+        tx.execute("DELETE FROM scan_quarantines WHERE scan_id = ?1", [id])
+            .map_err(ScanError::storage)?;
+        tx.execute("DELETE FROM scan_compression WHERE scan_id = ?1", [id])
+            .map_err(ScanError::storage)?;
+        tx.execute("DELETE FROM scans WHERE id = ?1", [id])
+            .map_err(ScanError::storage)?;
+        tx.commit().map_err(ScanError::storage)?;
+        Ok(())
+    }
+
+    fn get_findings(
+        &self,
+        root_path: Option<&str>,
+        state: Option<FindingState>,
+    ) -> ScanResult<Vec<TrackedFinding>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT fingerprint, root_path, state, file_path, line_number, pattern, message, first_seen_scan, last_seen_scan, note, issue_key, project FROM findings
+                 WHERE (?1 IS NULL OR root_path = ?1) AND (?2 IS NULL OR state = ?2)
+                 ORDER BY project, root_path, file_path, line_number",
+            )
+            .map_err(ScanError::storage)?;
+        let findings = stmt
+            .query_map((root_path, state.map(|state| state.as_str())), |row| {
+                Ok(TrackedFinding {
+                    fingerprint: row.get(0)?,
+                    root_path: row.get(1)?,
+                    project: project_column(row, 11)?,
+                    state: state_column(row, 2)?,
+                    file_path: row.get(3)?,
+                    line_number: row.get(4)?,
+                    pattern: row.get(5)?,
+                    message: row.get(6)?,
+                    first_seen_scan: row.get(7)?,
+                    last_seen_scan: row.get(8)?,
+                    note: row.get(9)?,
+                    issue_key: row.get(10)?,
+                })
+            })
+            .map_err(ScanError::storage)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(ScanError::storage)?;
+        Ok(findings)
+    }
+
+    fn set_finding_state(
+        &mut self,
+        fingerprint: &str,
+        state: FindingState,
+        note: Option<&str>,
+    ) -> ScanResult<usize> {
+        let tx = self.conn.transaction().map_err(ScanError::storage)?;
+        let current: Vec<(String, String, FindingState)> = {
+            let mut stmt = tx
+                .prepare("SELECT project, root_path, state FROM findings WHERE fingerprint = ?1")
+                .map_err(ScanError::storage)?;
+            let rows = stmt
+                .query_map([fingerprint], |row| {
+                    Ok((row.get(0)?, row.get(1)?, state_column(row, 2)?))
+                })
+                .map_err(ScanError::storage)?;
+            rows.collect::<rusqlite::Result<_>>()
+                .map_err(ScanError::storage)?
+        };
+        let now = chrono::Utc::now().timestamp();
+        for (project, root_path, from) in &current {
+            tx.execute(
+                "UPDATE findings SET state = ?3, note = ?4 WHERE root_path = ?1 AND fingerprint = ?2 AND project = ?5",
+                (root_path, fingerprint, state.as_str(), note, project),
+            )
+            .map_err(ScanError::storage)?;
+            if *from != state {
+                tx.execute(
+                    "INSERT INTO finding_transitions (root_path, fingerprint, scan_id, from_state, to_state, timestamp, note, project) VALUES (?1, ?2, NULL, ?3, ?4, ?5, ?6, ?7)",
+                    (root_path, fingerprint, from.as_str(), state.as_str(), now, note, project),
+                )
+                .map_err(ScanError::storage)?;
+            }
+        }
+        tx.commit().map_err(ScanError::storage)?;
+        Ok(current.len())
+    }
+
+    fn set_issue_key(
+        &mut self,
+        project: Option<&str>,
+        root_path: &str,
+        fingerprint: &str,
+        issue_key: &str,
+    ) -> ScanResult<()> {
+        self.conn
+            .execute(
+                "UPDATE findings SET issue_key = ?3 WHERE root_path = ?1 AND fingerprint = ?2 AND project = ?4",
+                (root_path, fingerprint, issue_key, project_key(project)),
+            )
+            .map_err(ScanError::storage)?;
+        Ok(())
+    }
+
+    fn get_transitions(&self, earlier: &Scan, later: &Scan) -> ScanResult<Vec<StateTransition>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT t.fingerprint, f.pattern, f.file_path, t.scan_id, t.from_state, t.to_state, t.timestamp, t.note
+                 FROM finding_transitions t
+                 JOIN findings f ON f.project = t.project AND f.root_path = t.root_path AND f.fingerprint = t.fingerprint
+                 WHERE t.root_path = ?1 AND t.project = ?6
+                   AND ((t.scan_id > ?2 AND t.scan_id <= ?3)
+                     OR (t.scan_id IS NULL AND t.timestamp >= ?4 AND t.timestamp <= ?5))
+                 ORDER BY t.id",
+            )
+            .map_err(ScanError::storage)?;
+        let transitions = stmt
+            .query_map(
+                (
+                    &later.root_path,
+                    earlier.id,
+                    later.id,
+                    earlier.timestamp,
+                    later.timestamp,
+                    project_key(later.project.as_deref()),
+                ),
+                |row| {
+                    Ok(StateTransition {
+                        fingerprint: row.get(0)?,
+                        pattern: row.get(1)?,
+                        file_path: row.get(2)?,
+                        scan_id: row.get(3)?,
+                        from: row
+                            .get::<_, Option<String>>(4)?
+                            .and_then(|state| state.parse().ok()),
+                        to: state_column(row, 5)?,
+                        timestamp: row.get(6)?,
+                        note: row.get(7)?,
+                    })
+                },
+            )
+            .map_err(ScanError::storage)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(ScanError::storage)?;
+        Ok(transitions)
+    }
+
+    fn save_inventory(&mut self, scan_id: i64, inventory: &Inventory) -> ScanResult<()> {
+        let json = serde_json::to_string(inventory).map_err(ScanError::storage)?;
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO scan_inventories (scan_id, inventory) VALUES (?1, ?2)",
+                (scan_id, json),
+            )
+            .map_err(ScanError::storage)?;
+        Ok(())
+    }
+
+    fn get_inventory(&self, scan_id: i64) -> ScanResult<Option<Inventory>> {
+        let json: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT inventory FROM scan_inventories WHERE scan_id = ?1",
+                [scan_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(ScanError::storage)?;
+        json.map(|json| serde_json::from_str(&json).map_err(ScanError::storage))
+            .transpose()
+    }
+
+    fn save_statistics(&mut self, scan_id: i64, statistics: &ScanStatistics) -> ScanResult<()> {
+        let json = serde_json::to_string(statistics).map_err(ScanError::storage)?;
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO scan_statistics (scan_id, statistics) VALUES (?1, ?2)",
+                (scan_id, json),
+            )
+            .map_err(ScanError::storage)?;
+        Ok(())
+    }
+
+    fn get_statistics(&self, scan_id: i64) -> ScanResult<Option<ScanStatistics>> {
+        let json: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT statistics FROM scan_statistics WHERE scan_id = ?1",
+                [scan_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(ScanError::storage)?;
+        json.map(|json| serde_json::from_str(&json).map_err(ScanError::storage))
+            .transpose()
+    }
+
+    fn save_ci_run(&mut self, scan_id: i64, ci_run: &CiRun) -> ScanResult<()> {
+        let json = serde_json::to_string(ci_run).map_err(ScanError::storage)?;
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO scan_ci_runs (scan_id, ci_run) VALUES (?1, ?2)",
+                (scan_id, json),
+            )
+            .map_err(ScanError::storage)?;
+        Ok(())
+    }
+
+    fn get_ci_run(&self, scan_id: i64) -> ScanResult<Option<CiRun>> {
+        let json: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT ci_run FROM scan_ci_runs WHERE scan_id = ?1",
+                [scan_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(ScanError::storage)?;
+        json.map(|json| serde_json::from_str(&json).map_err(ScanError::storage))
+            .transpose()
+    }
+
+    fn quarantine_scan(&mut self, scan_id: i64, reason: &str) -> ScanResult<()> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO scan_quarantines (scan_id, reason) VALUES (?1, ?2)",
+                (scan_id, reason),
+            )
+            .map_err(ScanError::storage)?;
+        Ok(())
+    }
+
+    fn get_quarantine(&self, scan_id: i64) -> ScanResult<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT reason FROM scan_quarantines WHERE scan_id = ?1",
+                [scan_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(ScanError::storage)
+    }
+
+    fn get_detector_yields(&self) -> ScanResult<Vec<DetectorYield>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT detector, extension, files, matches, skipped_scans FROM detector_yields
+                 ORDER BY detector, extension",
+            )
+            .map_err(ScanError::storage)?;
+        let yields = stmt
+            .query_map([], |row| {
+                Ok(DetectorYield {
+                    detector: row.get(0)?,
+                    extension: row.get(1)?,
+                    files: row.get::<_, i64>(2)? as u64,
+                    matches: row.get::<_, i64>(3)? as u64,
+                    skipped_scans: row.get(4)?,
+                })
+            })
+            .map_err(ScanError::storage)?;
+        yields.collect::<Result<_, _>>().map_err(ScanError::storage)
+    }
+
+    fn save_detector_yields(&mut self, yields: &[DetectorYield]) -> ScanResult<()> {
+        let tx = self.conn.transaction().map_err(ScanError::storage)?;
+        {
+            let mut stmt = tx
+                .prepare(
+                    "INSERT OR REPLACE INTO detector_yields (detector, extension, files, matches, skipped_scans)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                )
+                .map_err(ScanError::storage)?;
+            for y in yields {
+                stmt.execute((
+                    &y.detector,
+                    &y.extension,
+                    y.files as i64,
+                    y.matches as i64,
+                    y.skipped_scans,
+                ))
+                .map_err(ScanError::storage)?;
+            }
+        }
+        tx.commit().map_err(ScanError::storage)?;
         Ok(())
     }
 }
@@ -144,6 +990,7 @@ impl ScanRepository for SqliteScanRepository {
 mod tests {
     use super::*;
     use chrono::Utc;
+    use code_guardian_core::CiProvider;
     use tempfile::TempDir;
 
     #[test]
@@ -154,21 +1001,41 @@ mod tests {
             id: None,
             timestamp: now,
             root_path: "/test/path".to_string(),
-            matches: vec![Match {
-                file_path: "file.rs".to_string(),
-                line_number: 1,
-                column: 1,
-                pattern: "TODO".to_string(),
-                message: "TODO".to_string(),
-            }],
+            project: None,
+            matches: vec![
+                Match {
+                    file_path: "file.rs".into(),
+                    line_number: 1,
+                    column: 1,
+                    pattern: "TODO".into(),
+                    message: "TODO".to_string(),
+                    severity: None,
+                    category: None,
+                    end: None,
+                    author: None,
+                },
+                Match {
+                    file_path: "file.rs".into(),
+                    line_number: 2,
+                    column: 1,
+                    pattern: "NO_PRINT".into(),
+                    message: "NO_PRINT: print".to_string(),
+                    severity: Some(code_guardian_core::Severity::High),
+                    category: Some("CodeQuality".to_string()),
+                    end: Some(LineColumn { line: 4, column: 2 }),
+                    author: Some(Author {
+                        name: "Jane Doe".to_string(),
+                        email: "jane@example.com".to_string(),
+                    }),
+                },
+            ],
         };
         let id = repo.save_scan(&scan).unwrap();
         let retrieved = repo.get_scan(id).unwrap().unwrap();
         assert_eq!(retrieved.id, Some(id));
         assert_eq!(retrieved.timestamp, now);
         assert_eq!(retrieved.root_path, scan.root_path);
-        assert_eq!(retrieved.matches.len(), 1);
-        assert_eq!(retrieved.matches[0], scan.matches[0]);
+        assert_eq!(retrieved.matches, scan.matches);
     }
 
     #[test]
@@ -179,6 +1046,7 @@ mod tests {
             id: None,
             timestamp: now1,
             root_path: "/path1".to_string(),
+            project: None,
             matches: vec![],
         };
         let now2 = Utc::now().timestamp();
@@ -186,6 +1054,7 @@ mod tests {
             id: None,
             timestamp: now2,
             root_path: "/path2".to_string(),
+            project: None,
             matches: vec![],
         };
         repo.save_scan(&scan1).unwrap();
@@ -197,6 +1066,63 @@ mod tests {
         assert_eq!(all[1].timestamp, now1);
     }
 
+    #[test]
+    fn test_large_scans_store_compressed_messages() {
+        let mut repo = SqliteScanRepository::new_in_memory().unwrap();
+        let finding = |i: usize| Match {
+            file_path: format!("src/module_{}.rs", i % 40).into(),
+            line_number: i,
+            column: 5,
+            pattern: ["TODO", "UNWRAP", "CONSOLE_LOG"][i % 3].into(),
+            message: [
+                format!(
+                    "TODO: handle the error case of request {} before release",
+                    i
+                ),
+                format!(
+                    ".unwrap() on a Result in handler_{}; propagate the error",
+                    i % 17
+                ),
+                format!("console.log(\"debug state\", item_{}) left in code", i),
+            ][i % 3]
+                .clone(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
+        };
+        let scan = |matches: Vec<Match>| Scan {
+            id: None,
+            timestamp: Utc::now().timestamp(),
+            root_path: "/test".to_string(),
+            project: None,
+            matches,
+        };
+        let matches: Vec<Match> = (0..COMPRESS_MIN_MATCHES * 2).map(finding).collect();
+        let large = repo.save_scan(&scan(matches.clone())).unwrap();
+        let small = repo.save_scan(&scan(matches[..10].to_vec())).unwrap();
+
+        assert_eq!(repo.get_scan(large).unwrap().unwrap().matches, matches);
+        let page = repo
+            .get_matches(large, 100, 5, &MatchFilter::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(page.matches, matches[100..105]);
+        assert_eq!(
+            repo.get_scan(small).unwrap().unwrap().matches,
+            matches[..10]
+        );
+
+        let stats = repo.storage_stats().unwrap();
+        assert_eq!((stats.scans, stats.compressed_scans), (2, 1));
+        assert_eq!(stats.matches, COMPRESS_MIN_MATCHES * 2 + 10);
+        assert!(stats.compression_ratio() > 2.0, "{:?}", stats);
+        assert!(stats.database_bytes > 0);
+
+        repo.delete_scan(large).unwrap();
+        assert_eq!(repo.storage_stats().unwrap().compressed_scans, 0);
+    }
+
     #[test]
     fn test_delete_scan() {
         let mut repo = SqliteScanRepository::new_in_memory().unwrap();
@@ -204,12 +1130,17 @@ mod tests {
             id: None,
             timestamp: Utc::now().timestamp(),
             root_path: "/test".to_string(),
+            project: None,
             matches: vec![Match {
-                file_path: "f.rs".to_string(),
+                file_path: "f.rs".into(),
                 line_number: 1,
                 column: 1,
-                pattern: "FIXME".to_string(),
+                pattern: "FIXME".into(),
                 message: "FIXME".to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             }],
         };
         let id = repo.save_scan(&scan).unwrap();
@@ -217,6 +1148,329 @@ mod tests {
         assert!(repo.get_scan(id).unwrap().is_none());
     }
 
+    #[test]
+    fn test_get_matches_pages_and_filters() {
+        let mut repo = SqliteScanRepository::new_in_memory().unwrap();
+        let matches: Vec<Match> = (1..=25)
+            .map(|i| Match {
+                file_path: if i % 5 == 0 {
+                    "src/lib.rs"
+                } else {
+                    "src/main.rs"
+                }
+                .into(),
+                line_number: i,
+                column: 1,
+                pattern: if i % 2 == 0 { "TODO" } else { "FIXME" }.into(),
+                message: format!("item {}", i),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
+            })
+            .collect();
+        let id = repo
+            .save_scan(&Scan {
+                id: None,
+                timestamp: Utc::now().timestamp(),
+                root_path: "/test".to_string(),
+                project: None,
+                matches: matches.clone(),
+            })
+            .unwrap();
+
+        let all = MatchFilter::default();
+        let page = repo.get_matches(id, 10, 10, &all).unwrap().unwrap();
+        assert_eq!(page.total, 25);
+        assert_eq!(page.matches, matches[10..20]);
+        assert!(page.has_more());
+        let last = repo.get_matches(id, 20, 10, &all).unwrap().unwrap();
+        assert_eq!(last.matches.len(), 5);
+        assert!(!last.has_more());
+
+        let filter = MatchFilter {
+            pattern: Some("todo".to_string()),
+            file: Some("lib.rs".to_string()),
+        };
+        let page = repo.get_matches(id, 0, 10, &filter).unwrap().unwrap();
+        let lines: Vec<usize> = page.matches.iter().map(|m| m.line_number).collect();
+        assert_eq!(lines, vec![10, 20]);
+        assert_eq!(page.total, 2);
+
+        assert!(repo.get_matches(id + 1, 0, 10, &all).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_finding_state_lifecycle() {
+        let mut repo = SqliteScanRepository::new_in_memory().unwrap();
+        let todo = |line_number: usize, message: &str| Match {
+            file_path: "/repo/src/a.rs".into(),
+            line_number,
+            column: 1,
+            pattern: "TODO".into(),
+            message: message.to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
+        };
+        fn save(repo: &mut SqliteScanRepository, timestamp: i64, matches: Vec<Match>) -> Scan {
+            let scan = Scan {
+                id: None,
+                timestamp,
+                root_path: "/repo".to_string(),
+                project: None,
+                matches,
+            };
+            let id = repo.save_scan(&scan).unwrap();
+            Scan {
+                id: Some(id),
+                ..scan
+            }
+        }
+        let first = save(&mut repo, 100, vec![todo(1, "TODO: a"), todo(2, "TODO: b")]);
+        // `a` moved down a line, `b` was removed
+        let second = save(&mut repo, 200, vec![todo(2, "TODO: a")]);
+
+        let findings = repo.get_findings(Some("/repo"), None).unwrap();
+        let states: Vec<(&str, FindingState)> = findings
+            .iter()
+            .map(|f| (f.message.as_str(), f.state))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                ("TODO: a", FindingState::Open),
+                ("TODO: b", FindingState::Fixed)
+            ]
+        );
+        assert_eq!(findings[0].line_number, 2);
+        assert_eq!(findings[0].first_seen_scan, first.id.unwrap());
+
+        let a = findings[0].fingerprint.clone();
+        assert_eq!(
+            repo.set_finding_state(&a, FindingState::Acknowledged, Some("tracked in #12"))
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            repo.set_finding_state("unknown", FindingState::WontFix, None)
+                .unwrap(),
+            0
+        );
+        let third = save(
+            &mut repo,
+            i64::MAX,
+            vec![todo(2, "TODO: a"), todo(3, "TODO: b")],
+        );
+        let acknowledged = repo
+            .get_findings(None, Some(FindingState::Acknowledged))
+            .unwrap();
+        assert_eq!(acknowledged.len(), 1);
+        assert_eq!(acknowledged[0].note.as_deref(), Some("tracked in #12"));
+        assert_eq!(acknowledged[0].issue_key, None);
+        repo.set_issue_key(None, "/repo", &a, "SEC-12").unwrap();
+        assert_eq!(
+            repo.get_findings(None, Some(FindingState::Acknowledged))
+                .unwrap()[0]
+                .issue_key
+                .as_deref(),
+            Some("SEC-12")
+        );
+
+        let transitions =
+            |earlier: &Scan, later: &Scan| -> Vec<(String, Option<FindingState>, FindingState)> {
+                repo.get_transitions(earlier, later)
+                    .unwrap()
+                    .into_iter()
+                    .map(|t| (t.fingerprint, t.from, t.to))
+                    .collect()
+            };
+        let b = findings[1].fingerprint.clone();
+        assert_eq!(
+            transitions(&first, &second),
+            vec![(b.clone(), Some(FindingState::Open), FindingState::Fixed)]
+        );
+        assert_eq!(
+            transitions(&second, &third),
+            vec![
+                (a, Some(FindingState::Open), FindingState::Acknowledged),
+                (b, Some(FindingState::Fixed), FindingState::Open)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inventory_is_stored_with_scan() {
+        let mut repo = SqliteScanRepository::new_in_memory().unwrap();
+        let scan = Scan {
+            id: None,
+            timestamp: Utc::now().timestamp(),
+            root_path: "/repo".to_string(),
+            project: None,
+            matches: vec![],
+        };
+        let id = repo.save_scan(&scan).unwrap();
+        assert_eq!(repo.get_inventory(id).unwrap(), None);
+
+        let inventory = Inventory {
+            files: 3,
+            lines: 120,
+            total_detectors: 2,
+            ..Inventory::default()
+        };
+        repo.save_inventory(id, &inventory).unwrap();
+        assert_eq!(repo.get_inventory(id).unwrap(), Some(inventory));
+
+        repo.delete_scan(id).unwrap();
+        assert_eq!(repo.get_inventory(id).unwrap(), None);
+    }
+
+    #[test]
+    fn test_statistics_are_stored_with_scan() {
+        let mut repo = SqliteScanRepository::new_in_memory().unwrap();
+        let scan = Scan {
+            id: None,
+            timestamp: Utc::now().timestamp(),
+            root_path: "/repo".to_string(),
+            project: None,
+            matches: vec![],
+        };
+        let id = repo.save_scan(&scan).unwrap();
+        assert_eq!(repo.get_statistics(id).unwrap(), None);
+
+        let statistics = ScanStatistics {
+            files: 12,
+            skipped: 1,
+            matches: 4,
+            critical: 1,
+            duration_ms: 250,
+            cache_hits: 9,
+            cache_misses: 3,
+            file_types: vec![code_guardian_core::FileTypeMetrics {
+                extension: "rs".to_string(),
+                language: Some("Rust".to_string()),
+                files: 12,
+                lines: 900,
+                matches: 4,
+                duration_us: 240_000,
+            }],
+        };
+        repo.save_statistics(id, &statistics).unwrap();
+        assert_eq!(repo.get_statistics(id).unwrap(), Some(statistics));
+
+        repo.delete_scan(id).unwrap();
+        assert_eq!(repo.get_statistics(id).unwrap(), None);
+    }
+
+    #[test]
+    fn test_ci_run_is_stored_with_scan() {
+        let mut repo = SqliteScanRepository::new_in_memory().unwrap();
+        let scan = Scan {
+            id: None,
+            timestamp: Utc::now().timestamp(),
+            root_path: "/repo".to_string(),
+            project: None,
+            matches: vec![],
+        };
+        let id = repo.save_scan(&scan).unwrap();
+        assert_eq!(repo.get_ci_run(id).unwrap(), None);
+
+        let ci_run = CiRun {
+            provider: CiProvider::Gitlab,
+            run_id: Some("77".to_string()),
+            job_url: Some("https://gitlab.example.com/g/p/-/jobs/901".to_string()),
+            trigger: Some("schedule".to_string()),
+        };
+        repo.save_ci_run(id, &ci_run).unwrap();
+        assert_eq!(repo.get_ci_run(id).unwrap(), Some(ci_run));
+
+        repo.delete_scan(id).unwrap();
+        assert_eq!(repo.get_ci_run(id).unwrap(), None);
+    }
+
+    #[test]
+    fn test_quarantine_is_stored_with_scan() {
+        let mut repo = SqliteScanRepository::new_in_memory().unwrap();
+        let scan = Scan {
+            id: None,
+            timestamp: Utc::now().timestamp(),
+            root_path: "/repo".to_string(),
+            project: None,
+            matches: vec![],
+        };
+        let id = repo.save_scan(&scan).unwrap();
+        assert_eq!(repo.get_quarantine(id).unwrap(), None);
+
+        repo.quarantine_scan(id, "trigger 'secrets' fired").unwrap();
+        repo.quarantine_scan(id, "trigger 'secrets' fired again")
+            .unwrap();
+        assert_eq!(
+            repo.get_quarantine(id).unwrap().as_deref(),
+            Some("trigger 'secrets' fired again")
+        );
+
+        repo.delete_scan(id).unwrap();
+        assert_eq!(repo.get_quarantine(id).unwrap(), None);
+    }
+
+    #[test]
+    fn test_detector_yields() {
+        let mut repo = SqliteScanRepository::new_in_memory().unwrap();
+        assert!(repo.get_detector_yields().unwrap().is_empty());
+        let mut yields = vec![DetectorYield {
+            detector: "TodoDetector".to_string(),
+            extension: "rs".to_string(),
+            files: 120,
+            matches: 0,
+            skipped_scans: 2,
+        }];
+        repo.save_detector_yields(&yields).unwrap();
+        yields[0].matches = 1;
+        yields.push(DetectorYield {
+            extension: String::new(),
+            ..yields[0].clone()
+        });
+        repo.save_detector_yields(&yields).unwrap();
+        let mut stored = repo.get_detector_yields().unwrap();
+        stored.sort_by(|a, b| b.extension.cmp(&a.extension));
+        assert_eq!(stored, yields);
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    #[test]
+    fn test_key_needs_encryption_feature() {
+        let temp_dir = TempDir::new().unwrap();
+        let err = SqliteScanRepository::with_key(temp_dir.path().join("test.db"), Some("secret"))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("--features encryption"));
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encrypted_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        {
+            let mut repo = SqliteScanRepository::with_key(&db_path, Some("secret")).unwrap();
+            let scan = Scan {
+                id: None,
+                timestamp: Utc::now().timestamp(),
+                root_path: "/secret/repo".to_string(),
+                project: None,
+                matches: vec![],
+            };
+            repo.save_scan(&scan).unwrap();
+        }
+        let bytes = std::fs::read(&db_path).unwrap();
+        assert!(!bytes.windows(12).any(|w| w == b"/secret/repo"));
+        assert!(SqliteScanRepository::with_key(&db_path, None).is_err());
+        assert!(SqliteScanRepository::with_key(&db_path, Some("wrong")).is_err());
+        let repo = SqliteScanRepository::with_key(&db_path, Some("secret")).unwrap();
+        assert_eq!(repo.get_all_scans().unwrap().len(), 1);
+    }
+
     #[test]
     fn test_file_based_repo() {
         let temp_dir = TempDir::new().unwrap();
@@ -227,6 +1481,7 @@ mod tests {
                 id: None,
                 timestamp: Utc::now().timestamp(),
                 root_path: "/file/test".to_string(),
+                project: None,
                 matches: vec![],
             };
             repo.save_scan(&scan).unwrap();
@@ -237,6 +1492,207 @@ mod tests {
             assert_eq!(all.len(), 1);
         }
     }
+
+    #[test]
+    fn test_projects_keep_scans_and_findings_apart() {
+        let mut repo = SqliteScanRepository::new_in_memory().unwrap();
+        let todo = Match {
+            file_path: "/repo/src/a.rs".into(),
+            line_number: 1,
+            column: 1,
+            pattern: "TODO".into(),
+            message: "TODO: a".to_string(),
+            severity: None,
+            category: None,
+            end: None,
+            author: None,
+        };
+        let mut save = |timestamp: i64, project: Option<&str>, matches: Vec<Match>| {
+            let scan = Scan {
+                id: None,
+                timestamp,
+                root_path: "/repo".to_string(),
+                project: project.map(str::to_string),
+                matches,
+            };
+            repo.save_scan(&scan).unwrap()
+        };
+        // Two repositories checked out at the same path on different CI runners
+        let api = save(100, Some("api"), vec![todo.clone()]);
+        let web = save(200, Some("web"), vec![]);
+        let plain = save(300, None, vec![todo]);
+
+        assert_eq!(
+            repo.get_scan(api).unwrap().unwrap().project.as_deref(),
+            Some("api")
+        );
+        assert_eq!(repo.get_scan(plain).unwrap().unwrap().project, None);
+        let ids = |scans: Vec<Scan>| scans.iter().filter_map(|scan| scan.id).collect::<Vec<_>>();
+        assert_eq!(ids(repo.get_project_scans("api").unwrap()), vec![api]);
+        assert_eq!(ids(repo.get_project_scans("web").unwrap()), vec![web]);
+        assert!(repo.get_project_scans("docs").unwrap().is_empty());
+        assert_eq!(ids(repo.get_all_scans().unwrap()), vec![plain, web, api]);
+
+        // The empty scan of `web` doesn't fix the finding of `api`
+        let findings = repo.get_findings(Some("/repo"), None).unwrap();
+        let states: Vec<(Option<&str>, FindingState)> = findings
+            .iter()
+            .map(|f| (f.project.as_deref(), f.state))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                (None, FindingState::Open),
+                (Some("api"), FindingState::Open)
+            ]
+        );
+
+        let invalid = Scan {
+            id: None,
+            timestamp: 400,
+            root_path: "/repo".to_string(),
+            project: Some("Not A Slug".to_string()),
+            matches: Vec::new(),
+        };
+        assert!(repo.save_scan(&invalid).is_err());
+        assert!(validate_project("code-guardian_2.x").is_ok());
+        for invalid in [
+            "",
+            "-api",
+            "API",
+            "api/web",
+            &"a".repeat(MAX_PROJECT_LEN + 1),
+        ] {
+            assert!(validate_project(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_api_tokens() {
+        let repo = SqliteScanRepository::new_in_memory().unwrap();
+        let ci = repo.create_api_token("ci", TokenScope::Scan).unwrap();
+        let dashboard = repo
+            .create_api_token("dashboard", TokenScope::Read)
+            .unwrap();
+        assert!(repo.create_api_token("ci", TokenScope::Read).is_err());
+        assert!(repo.create_api_token(" ", TokenScope::Read).is_err());
+
+        let names: Vec<(String, TokenScope)> = repo
+            .api_tokens()
+            .unwrap()
+            .into_iter()
+            .map(|token| (token.name, token.scope))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("ci".to_string(), TokenScope::Scan),
+                ("dashboard".to_string(), TokenScope::Read)
+            ]
+        );
+        // Only the hash is stored
+        let stored: String = repo
+            .conn
+            .query_row(
+                "SELECT token_hash FROM api_tokens WHERE name = 'ci'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_ne!(stored, ci);
+
+        assert_eq!(repo.verify_api_token(&ci).unwrap(), Some(TokenScope::Scan));
+        assert_eq!(
+            repo.verify_api_token(&dashboard).unwrap(),
+            Some(TokenScope::Read)
+        );
+        assert_eq!(repo.verify_api_token("cgt_guess").unwrap(), None);
+
+        assert!(repo.revoke_api_token("ci").unwrap());
+        assert!(!repo.revoke_api_token("ci").unwrap());
+        assert_eq!(repo.verify_api_token(&ci).unwrap(), None);
+    }
+
+    #[test]
+    fn test_schema_status_and_newer_databases() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        assert!(SqliteScanRepository::schema_status(&db_path, None).is_err());
+        drop(SqliteScanRepository::new(&db_path).unwrap());
+
+        let status = SqliteScanRepository::schema_status(&db_path, None).unwrap();
+        assert_eq!(status.applied_version, Some(expected_schema_version()));
+        assert_eq!(status.pending(), 0);
+        assert!(!status.is_newer());
+        assert_eq!(status.migrations[0].name, "initial_schema");
+
+        // A newer code-guardian added a migration
+        let future = expected_schema_version() + 1;
+        Connection::open(&db_path)
+            .unwrap()
+            .execute(
+                "INSERT INTO refinery_schema_history (version, name, applied_on, checksum) VALUES (?1, 'future_table', '2030-01-01T00:00:00Z', '0')",
+                [future],
+            )
+            .unwrap();
+        let status = SqliteScanRepository::schema_status(&db_path, None).unwrap();
+        assert!(status.is_newer());
+        let last = status.migrations.last().unwrap();
+        assert_eq!((last.version, last.known), (future, false));
+        let err = SqliteScanRepository::new(&db_path)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(
+            err.contains(&format!("schema version {}", future)),
+            "{}",
+            err
+        );
+        assert!(err.contains("upgrade code-guardian"));
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let backup_path = temp_dir.path().join("backup.db");
+        let scan = |root_path: &str| Scan {
+            id: None,
+            timestamp: Utc::now().timestamp(),
+            root_path: root_path.to_string(),
+            project: None,
+            matches: vec![],
+        };
+        // The backup runs while a connection to the database is open
+        let mut repo = SqliteScanRepository::new(&db_path).unwrap();
+        repo.save_scan(&scan("/before")).unwrap();
+        backup_database(&db_path, &backup_path, None).unwrap();
+        assert!(backup_database(&db_path, &backup_path, None)
+            .unwrap_err()
+            .to_string()
+            .contains("already exists"));
+        assert!(SqliteScanRepository::new(&backup_path)
+            .unwrap()
+            .integrity_check()
+            .unwrap()
+            .is_empty());
+
+        repo.save_scan(&scan("/after")).unwrap();
+        restore_database(&backup_path, &db_path, None).unwrap();
+        let roots: Vec<String> = repo
+            .get_all_scans()
+            .unwrap()
+            .into_iter()
+            .map(|scan| scan.root_path)
+            .collect();
+        assert_eq!(roots, vec!["/before"]);
+
+        let garbage = temp_dir.path().join("garbage.db");
+        std::fs::write(&garbage, b"not a database").unwrap();
+        assert!(restore_database(&garbage, &db_path, None).is_err());
+        assert!(restore_database(&temp_dir.path().join("missing.db"), &db_path, None).is_err());
+        assert_eq!(repo.get_all_scans().unwrap().len(), 1);
+    }
 }
 
 #[cfg(test)]
@@ -254,11 +1710,15 @@ mod proptest_tests {
             ".*",
         )
             .prop_map(|(fp, ln, col, pat, msg)| Match {
-                file_path: fp.to_string(),
+                file_path: fp.into(),
                 line_number: ln,
                 column: col,
-                pattern: pat.to_string(),
+                pattern: pat.into(),
                 message: msg.to_string(),
+                severity: None,
+                category: None,
+                end: None,
+                author: None,
             })
     }
 
@@ -270,6 +1730,7 @@ mod proptest_tests {
                 id: None,
                 timestamp: Utc::now().timestamp(),
                 root_path: "test_path".to_string(),
+                project: None,
                 matches: matches.clone(),
             };
             let id = repo.save_scan(&scan).unwrap();
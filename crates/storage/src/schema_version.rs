@@ -0,0 +1,132 @@
+//! Schema version of a findings database against the migrations this build
+//! embeds. A database migrated by a newer code-guardian may have tables this
+//! build doesn't know, so it is refused instead of being used half-understood.
+
+use crate::migrations;
+use code_guardian_core::{ScanError, ScanResult};
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Table refinery records applied migrations in
+const HISTORY_TABLE: &str = "refinery_schema_history";
+
+/// A schema migration, embedded in this build, applied to the database, or both
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationStatus {
+    pub version: u32,
+    pub name: String,
+    /// When the migration was applied in RFC 3339, None if it is pending
+    pub applied_on: Option<String>,
+    /// Whether this build embeds the migration
+    pub known: bool,
+}
+
+/// Migrations of a database and the schema version this build expects
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaStatus {
+    /// Latest migration embedded in this build
+    pub expected_version: u32,
+    /// Latest migration applied to the database, None if it has none
+    pub applied_version: Option<u32>,
+    /// Every migration, by version
+    pub migrations: Vec<MigrationStatus>,
+}
+
+impl SchemaStatus {
+    /// Whether a newer code-guardian migrated the database
+    pub fn is_newer(&self) -> bool {
+        self.applied_version
+            .is_some_and(|version| version > self.expected_version)
+    }
+
+    /// Migrations the next open of the database applies
+    pub fn pending(&self) -> usize {
+        self.migrations
+            .iter()
+            .filter(|migration| migration.applied_on.is_none())
+            .count()
+    }
+}
+
+/// Latest schema version this build migrates databases to
+pub fn expected_schema_version() -> u32 {
+    migrations::runner()
+        .get_migrations()
+        .iter()
+        .map(|migration| migration.version())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Reads the migration status of `conn` without migrating it
+pub(crate) fn read_schema_status(conn: &mut Connection) -> ScanResult<SchemaStatus> {
+    let runner = migrations::runner();
+    let has_history = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            [HISTORY_TABLE],
+            |_| Ok(()),
+        )
+        .optional()
+        .map_err(ScanError::storage)?
+        .is_some();
+    let applied = if has_history {
+        runner
+            .get_applied_migrations(conn)
+            .map_err(ScanError::storage)?
+    } else {
+        Vec::new()
+    };
+
+    let mut migrations: Vec<MigrationStatus> = runner
+        .get_migrations()
+        .iter()
+        .map(|migration| MigrationStatus {
+            version: migration.version(),
+            name: migration.name().to_string(),
+            applied_on: None,
+            known: true,
+        })
+        .collect();
+    for migration in &applied {
+        let applied_on = migration
+            .applied_on()
+            .and_then(|time| chrono::DateTime::from_timestamp(time.unix_timestamp(), 0))
+            .map(|time| time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+        match migrations
+            .iter_mut()
+            .find(|known| known.version == migration.version())
+        {
+            Some(known) => known.applied_on = applied_on,
+            None => migrations.push(MigrationStatus {
+                version: migration.version(),
+                name: migration.name().to_string(),
+                applied_on,
+                known: false,
+            }),
+        }
+    }
+    migrations.sort_by_key(|migration| migration.version);
+    Ok(SchemaStatus {
+        expected_version: expected_schema_version(),
+        applied_version: applied.iter().map(|migration| migration.version()).max(),
+        migrations,
+    })
+}
+
+/// Fails with the versions involved if a newer code-guardian migrated the
+/// database at `path`
+pub(crate) fn ensure_supported(conn: &mut Connection, path: &Path) -> ScanResult<()> {
+    let status = read_schema_status(conn)?;
+    match status.applied_version {
+        Some(version) if status.is_newer() => Err(ScanError::storage(format!(
+            "{} has schema version {}, but code-guardian {} supports up to version {}; upgrade code-guardian, or restore a backup made by this version with `code-guardian db restore`",
+            path.display(),
+            version,
+            env!("CARGO_PKG_VERSION"),
+            status.expected_version
+        ))),
+        _ => Ok(()),
+    }
+}